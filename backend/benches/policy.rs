@@ -0,0 +1,145 @@
+//! Microbenchmarks for `domain::policy`, the per-item checks behind
+//! `GET /api/expenses/reports/:id/policy`.
+//!
+//! Run with `cargo bench --bench policy`. The fixtures mirror a busy report:
+//! a mix of meal, mileage, and uncapped items evaluated against several
+//! overlapping rules.
+
+use std::hint::black_box;
+
+use chrono::{Duration, NaiveDate, Utc};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use expense_portal::domain::{
+    models::{
+        Currency, ExpenseCategory, ExpenseItem, PolicyRule, RuleComparison, RuleCondition,
+        RuleScope, RuleSeverity,
+    },
+    policy::{check_receipt_capture_dates, evaluate_item},
+};
+use uuid::Uuid;
+
+fn start_date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 5, 1).expect("valid date")
+}
+
+fn items(count: usize) -> Vec<ExpenseItem> {
+    let categories = [
+        ExpenseCategory::Meal,
+        ExpenseCategory::Mileage,
+        ExpenseCategory::Lodging,
+        ExpenseCategory::Meal,
+    ];
+    let report_id = Uuid::new_v4();
+    (0..count)
+        .map(|idx| ExpenseItem {
+            id: Uuid::new_v4(),
+            report_id,
+            expense_date: start_date() + Duration::days((idx % 28) as i64),
+            category: categories[idx % categories.len()],
+            gl_account_id: None,
+            description: Some("Benchmark item".to_string()),
+            attendees: None,
+            location: None,
+            amount_cents: 1_500 + (idx as i64 * 137) % 9_000,
+            reimbursable: true,
+            payment_method: None,
+            is_policy_exception: false,
+            exception_justification: None,
+            class: None,
+            tax_amount_cents: None,
+            tax_jurisdiction: None,
+            miles: None,
+            merchant: None,
+            cost_center_id: None,
+        })
+        .collect()
+}
+
+fn rules() -> Vec<PolicyRule> {
+    let rule = |category, threshold_cents, active_from: NaiveDate, active_to| PolicyRule {
+        id: Uuid::new_v4(),
+        org_id: None,
+        name: format!("{category:?}").to_lowercase(),
+        category: Some(category),
+        comparison: RuleComparison::Gt,
+        threshold_cents,
+        scope: RuleScope::Item,
+        severity: RuleSeverity::Violation,
+        condition: RuleCondition::Always,
+        per_attendee: false,
+        advance_days: None,
+        message: "Exceeds limit of {limit}".to_string(),
+        active_from,
+        active_to,
+        enabled: true,
+        updated_by: None,
+        updated_at: Utc::now(),
+    };
+    vec![
+        rule(ExpenseCategory::Meal, 5_000, start_date(), None),
+        rule(
+            ExpenseCategory::Meal,
+            4_500,
+            start_date() - Duration::days(365),
+            Some(start_date() - Duration::days(1)),
+        ),
+        rule(ExpenseCategory::Mileage, 6_700, start_date(), None),
+        rule(ExpenseCategory::Lodging, 25_000, start_date(), None),
+    ]
+}
+
+fn bench_evaluate_item(c: &mut Criterion) {
+    let rules = rules();
+    let mut group = c.benchmark_group("policy/evaluate_item");
+    for count in [10_usize, 100, 1_000] {
+        let items = items(count);
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &items, |b, items| {
+            b.iter(|| {
+                for item in items {
+                    black_box(evaluate_item(black_box(item), Currency::USD, &rules, true));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_foreign_currency(c: &mut Criterion) {
+    let rules = rules();
+    let items = items(100);
+    let eur = Currency::parse("EUR").expect("valid currency");
+    c.bench_function("policy/evaluate_item_unconverted_100", |b| {
+        b.iter(|| {
+            for item in &items {
+                black_box(evaluate_item(black_box(item), eur, &rules, true));
+            }
+        })
+    });
+}
+
+fn bench_capture_dates(c: &mut Criterion) {
+    let items = items(100);
+    let captures: Vec<_> = (0..3)
+        .map(|offset| {
+            (start_date() + Duration::days(offset * 5))
+                .and_hms_opt(12, 0, 0)
+                .expect("valid time")
+        })
+        .collect();
+    c.bench_function("policy/check_receipt_capture_dates_100", |b| {
+        b.iter(|| {
+            for item in &items {
+                black_box(check_receipt_capture_dates(item, &captures, 3));
+            }
+        })
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_evaluate_item,
+    bench_foreign_currency,
+    bench_capture_dates
+);
+criterion_main!(benches);
@@ -0,0 +1,45 @@
+//! Generates the tonic server stubs for the gRPC services in `src/grpc`.
+//!
+//! The messages are hand-written `prost` structs, so the stubs are described
+//! here rather than compiled from `proto/` and the build needs no `protoc`.
+//! Keep both in step with `proto/expense_portal/v1/expense_portal.proto`.
+
+use tonic_build::manual::{Builder, Method, Service};
+
+const PACKAGE: &str = "expense_portal.v1";
+const CODEC: &str = "tonic::codec::ProstCodec";
+
+fn main() {
+    let employee_directory = Service::builder()
+        .name("EmployeeDirectory")
+        .package(PACKAGE)
+        .method(
+            Method::builder()
+                .name("get_employee")
+                .route_name("GetEmployee")
+                .input_type("crate::grpc::employees::GetEmployeeRequest")
+                .output_type("crate::grpc::employees::Employee")
+                .codec_path(CODEC)
+                .build(),
+        )
+        .build();
+
+    let report_ingestion = Service::builder()
+        .name("ReportIngestion")
+        .package(PACKAGE)
+        .method(
+            Method::builder()
+                .name("create_report")
+                .route_name("CreateReport")
+                .input_type("crate::grpc::reports::CreateReportRequest")
+                .output_type("crate::grpc::reports::Report")
+                .codec_path(CODEC)
+                .build(),
+        )
+        .build();
+
+    Builder::new()
+        .build_client(false)
+        .compile(&[employee_directory, report_ingestion]);
+    println!("cargo:rerun-if-changed=build.rs");
+}
@@ -0,0 +1,181 @@
+//! Goose load profiles for the portal's hot endpoints.
+//!
+//! Run against a seeded database (see `backend/migrations/*seed*`) with the
+//! API listening locally, for example:
+//!
+//! ```text
+//! cargo run --release -- --host http://localhost:8080 --users 20 \
+//!     --hatch-rate 5 --run-time 2m --report-file loadtest-report.html
+//! ```
+//!
+//! Credentials default to the seeded employee `EMP3101`, manager `MGMT1001`,
+//! and the developer credential from `.env.example`; override them with
+//! `LOADTEST_EMPLOYEE`, `LOADTEST_MANAGER`, and `LOADTEST_CREDENTIAL`.
+
+use std::env;
+
+use goose::prelude::*;
+use serde_json::{json, Value};
+
+/// Per-user session state captured during `on_start`.
+struct Session {
+    token: String,
+    report_id: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), GooseError> {
+    GooseAttack::initialize()?
+        .register_scenario(
+            scenario!("ReportCreation")
+                .set_weight(3)?
+                .register_transaction(transaction!(login_employee).set_on_start())
+                .register_transaction(transaction!(create_report)),
+        )
+        .register_scenario(
+            scenario!("ManagerQueue")
+                .set_weight(2)?
+                .register_transaction(transaction!(login_manager).set_on_start())
+                .register_transaction(transaction!(fetch_queue)),
+        )
+        .register_scenario(
+            scenario!("PolicyEvaluation")
+                .set_weight(2)?
+                .register_transaction(transaction!(login_employee).set_on_start())
+                .register_transaction(transaction!(seed_report).set_on_start())
+                .register_transaction(transaction!(evaluate_policy)),
+        )
+        .execute()
+        .await?;
+    Ok(())
+}
+
+fn setting(key: &str, default: &str) -> String {
+    env::var(key).unwrap_or_else(|_| default.to_string())
+}
+
+async fn login(user: &mut GooseUser, hr_identifier: String) -> TransactionResult {
+    let payload = json!({
+        "hr_identifier": hr_identifier,
+        "credential": setting("LOADTEST_CREDENTIAL", "dev-pass"),
+    });
+    let mut goose = user.post_json("/api/auth/login", &payload).await?;
+    let Ok(response) = goose.response else {
+        return user.set_failure("login request failed", &mut goose.request, None, None);
+    };
+    let body: Value = match response.json().await {
+        Ok(body) => body,
+        Err(_) => {
+            return user.set_failure(
+                "login returned invalid JSON",
+                &mut goose.request,
+                None,
+                None,
+            )
+        }
+    };
+    let Some(token) = body["token"].as_str() else {
+        return user.set_failure("login returned no token", &mut goose.request, None, None);
+    };
+    user.set_session_data(Session {
+        token: token.to_string(),
+        report_id: None,
+    });
+    Ok(())
+}
+
+async fn login_employee(user: &mut GooseUser) -> TransactionResult {
+    login(user, setting("LOADTEST_EMPLOYEE", "EMP3101")).await
+}
+
+async fn login_manager(user: &mut GooseUser) -> TransactionResult {
+    login(user, setting("LOADTEST_MANAGER", "MGMT1001")).await
+}
+
+/// Draft report with a capped meal and an uncapped supply purchase so policy
+/// evaluation exercises both the cap lookup and the pass-through path.
+fn report_payload() -> Value {
+    json!({
+        "reporting_period_start": "2024-05-01",
+        "reporting_period_end": "2024-05-31",
+        "currency": "USD",
+        "items": [
+            {
+                "expense_date": "2024-05-03",
+                "category": "meal",
+                "description": "Client lunch",
+                "attendees": "Load test",
+                "amount_cents": 4200,
+                "reimbursable": true
+            },
+            {
+                "expense_date": "2024-05-04",
+                "category": "supplies",
+                "description": "Printer paper",
+                "amount_cents": 1899,
+                "reimbursable": true
+            }
+        ]
+    })
+}
+
+async fn post_report(user: &mut GooseUser) -> Result<Option<String>, Box<TransactionError>> {
+    let token = user.get_session_data_unchecked::<Session>().token.clone();
+    let builder = user
+        .get_request_builder(&GooseMethod::Post, "/api/expenses/reports")?
+        .bearer_auth(token)
+        .json(&report_payload());
+    let request = GooseRequest::builder()
+        .method(GooseMethod::Post)
+        .path("/api/expenses/reports")
+        .name("POST /api/expenses/reports")
+        .set_request_builder(builder)
+        .build();
+    let goose = user.request(request).await?;
+    let Ok(response) = goose.response else {
+        return Ok(None);
+    };
+    let body: Value = response.json().await.unwrap_or(Value::Null);
+    Ok(body["report"]["id"].as_str().map(str::to_string))
+}
+
+async fn create_report(user: &mut GooseUser) -> TransactionResult {
+    post_report(user).await?;
+    Ok(())
+}
+
+async fn seed_report(user: &mut GooseUser) -> TransactionResult {
+    let report_id = post_report(user).await?;
+    user.get_session_data_unchecked_mut::<Session>().report_id = report_id;
+    Ok(())
+}
+
+async fn authorized_get(user: &mut GooseUser, path: &str, name: &str) -> TransactionResult {
+    let token = user.get_session_data_unchecked::<Session>().token.clone();
+    let builder = user
+        .get_request_builder(&GooseMethod::Get, path)?
+        .bearer_auth(token);
+    let request = GooseRequest::builder()
+        .path(path)
+        .name(name)
+        .set_request_builder(builder)
+        .build();
+    user.request(request).await?;
+    Ok(())
+}
+
+async fn fetch_queue(user: &mut GooseUser) -> TransactionResult {
+    authorized_get(user, "/api/manager/queue", "GET /api/manager/queue").await
+}
+
+async fn evaluate_policy(user: &mut GooseUser) -> TransactionResult {
+    let Some(report_id) = user
+        .get_session_data_unchecked::<Session>()
+        .report_id
+        .clone()
+    else {
+        return Ok(());
+    };
+    let path = format!("/api/expenses/reports/{report_id}/policy");
+    authorized_get(user, &path, "GET /api/expenses/reports/:id/policy").await
+}
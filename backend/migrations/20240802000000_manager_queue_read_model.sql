@@ -0,0 +1,138 @@
+-- Denormalized manager queue read model maintained by triggers on report, item,
+-- and employee changes so `GET /api/manager/queue` is a single indexed lookup.
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS manager_queue_entries (
+    report_id UUID PRIMARY KEY REFERENCES expense_reports(id) ON DELETE CASCADE,
+    manager_id UUID REFERENCES employees(id) ON DELETE SET NULL,
+    employee_id UUID NOT NULL REFERENCES employees(id) ON DELETE CASCADE,
+    employee_hr_identifier TEXT NOT NULL,
+    reporting_period_start DATE NOT NULL,
+    reporting_period_end DATE NOT NULL,
+    total_amount_cents BIGINT NOT NULL,
+    total_reimbursable_cents BIGINT NOT NULL,
+    currency TEXT NOT NULL,
+    submitted_at TIMESTAMPTZ NOT NULL,
+    line_items JSONB NOT NULL DEFAULT '[]'::jsonb,
+    policy_flag_count INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_manager_queue_entries_manager_submitted
+    ON manager_queue_entries (manager_id, submitted_at);
+
+-- Rebuilds (or removes) the queue entry for one report. Entries only exist
+-- while the report is `submitted`; any other status clears it.
+CREATE OR REPLACE FUNCTION refresh_manager_queue_entry(target_report UUID)
+RETURNS VOID AS $$
+BEGIN
+    DELETE FROM manager_queue_entries WHERE report_id = target_report;
+
+    INSERT INTO manager_queue_entries (
+        report_id,
+        manager_id,
+        employee_id,
+        employee_hr_identifier,
+        reporting_period_start,
+        reporting_period_end,
+        total_amount_cents,
+        total_reimbursable_cents,
+        currency,
+        submitted_at,
+        line_items,
+        policy_flag_count
+    )
+    SELECT
+        r.id,
+        e.manager_id,
+        r.employee_id,
+        e.hr_identifier,
+        r.reporting_period_start,
+        r.reporting_period_end,
+        r.total_amount_cents,
+        r.total_reimbursable_cents,
+        r.currency,
+        r.updated_at,
+        COALESCE(
+            (
+                SELECT jsonb_agg(
+                    jsonb_build_object(
+                        'id', i.id,
+                        'reportId', i.report_id,
+                        'expenseDate', i.expense_date,
+                        'category', i.category::text,
+                        'description', i.description,
+                        'amountCents', i.amount_cents,
+                        'reimbursable', i.reimbursable,
+                        'paymentMethod', i.payment_method,
+                        'isPolicyException', i.is_policy_exception
+                    )
+                    ORDER BY i.expense_date ASC, i.id ASC
+                )
+                FROM expense_items i
+                WHERE i.report_id = r.id
+            ),
+            '[]'::jsonb
+        ),
+        (
+            SELECT COUNT(*)
+            FROM expense_items i
+            WHERE i.report_id = r.id AND i.is_policy_exception
+        )
+    FROM expense_reports r
+    JOIN employees e ON e.id = r.employee_id
+    WHERE r.id = target_report AND r.status::text = 'submitted';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION manager_queue_report_changed()
+RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM refresh_manager_queue_entry(NEW.id);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION manager_queue_item_changed()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP IN ('UPDATE', 'DELETE') THEN
+        PERFORM refresh_manager_queue_entry(OLD.report_id);
+    END IF;
+    IF TG_OP = 'INSERT' OR (TG_OP = 'UPDATE' AND NEW.report_id <> OLD.report_id) THEN
+        PERFORM refresh_manager_queue_entry(NEW.report_id);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION manager_queue_employee_changed()
+RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM refresh_manager_queue_entry(r.id)
+    FROM expense_reports r
+    WHERE r.employee_id = NEW.id AND r.status::text = 'submitted';
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_manager_queue_report ON expense_reports;
+CREATE TRIGGER trg_manager_queue_report
+    AFTER INSERT OR UPDATE ON expense_reports
+    FOR EACH ROW EXECUTE FUNCTION manager_queue_report_changed();
+
+DROP TRIGGER IF EXISTS trg_manager_queue_item ON expense_items;
+CREATE TRIGGER trg_manager_queue_item
+    AFTER INSERT OR UPDATE OR DELETE ON expense_items
+    FOR EACH ROW EXECUTE FUNCTION manager_queue_item_changed();
+
+DROP TRIGGER IF EXISTS trg_manager_queue_employee ON employees;
+CREATE TRIGGER trg_manager_queue_employee
+    AFTER UPDATE OF manager_id, hr_identifier ON employees
+    FOR EACH ROW EXECUTE FUNCTION manager_queue_employee_changed();
+
+-- Backfill reports already waiting on a manager.
+SELECT refresh_manager_queue_entry(id)
+FROM expense_reports
+WHERE status::text = 'submitted';
+
+COMMIT;
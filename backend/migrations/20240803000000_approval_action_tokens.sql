@@ -0,0 +1,17 @@
+-- Single-use tokens behind the approve/request-changes links in approval emails
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS approval_action_tokens (
+    id UUID PRIMARY KEY,
+    report_id UUID NOT NULL REFERENCES expense_reports(id) ON DELETE CASCADE,
+    approver_id UUID NOT NULL REFERENCES employees(id) ON DELETE CASCADE,
+    action TEXT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_approval_action_tokens_report
+    ON approval_action_tokens (report_id, approver_id);
+
+COMMIT;
@@ -0,0 +1,17 @@
+-- Upload metadata for sanitized receipt images and the EXIF capture time kept
+-- after GPS and other metadata are stripped
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS receipt_uploads (
+    file_key TEXT PRIMARY KEY,
+    uploaded_by UUID NOT NULL REFERENCES employees(id) ON DELETE CASCADE,
+    file_name TEXT NOT NULL,
+    mime_type TEXT NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    captured_at TIMESTAMP,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE receipts ADD COLUMN IF NOT EXISTS captured_at TIMESTAMP;
+
+COMMIT;
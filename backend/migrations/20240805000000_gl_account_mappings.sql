@@ -0,0 +1,24 @@
+-- Category to GL account mapping used to build itemized journal lines, seeded
+-- from POLICY.md "General Ledger Mapping"
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS gl_account_mappings (
+    category TEXT PRIMARY KEY,
+    gl_account TEXT NOT NULL,
+    description TEXT,
+    updated_by UUID REFERENCES employees(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+INSERT INTO gl_account_mappings (category, gl_account, description)
+VALUES
+    ('airfare', '64190', 'Travel - GA'),
+    ('lodging', '64190', 'Travel - GA'),
+    ('ground_transport', '64190', 'Travel - GA'),
+    ('mileage', '64190', 'Travel - GA'),
+    ('meal', '64180', 'Meals & Entertainment - GA'),
+    ('supplies', '62090', 'Office Supplies'),
+    ('other', '66500', 'FSI Global G&A')
+ON CONFLICT (category) DO NOTHING;
+
+COMMIT;
@@ -0,0 +1,13 @@
+-- Tracks how many times a NetSuite batch export has been attempted so failed
+-- batches can be retried via `POST /api/finance/batches/:id/retry`.
+BEGIN;
+
+ALTER TABLE netsuite_batches
+    ADD COLUMN IF NOT EXISTS export_attempts INTEGER NOT NULL DEFAULT 1,
+    ADD COLUMN IF NOT EXISTS last_attempted_at TIMESTAMPTZ;
+
+UPDATE netsuite_batches
+SET last_attempted_at = COALESCE(exported_at, finalized_at)
+WHERE last_attempted_at IS NULL;
+
+COMMIT;
@@ -0,0 +1,12 @@
+-- Links reversal batches to the exported batch they offset so
+-- `POST /api/finance/batches/:id/reverse` can void a batch at most once.
+BEGIN;
+
+ALTER TABLE netsuite_batches
+    ADD COLUMN IF NOT EXISTS reverses_batch_id UUID REFERENCES netsuite_batches(id);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_netsuite_batches_reverses_batch
+    ON netsuite_batches (reverses_batch_id)
+    WHERE reverses_batch_id IS NOT NULL;
+
+COMMIT;
@@ -0,0 +1,34 @@
+-- Captures VAT/GST per expense item and maps categories (optionally narrowed
+-- to a tax jurisdiction) to the tax codes stamped on journal lines at
+-- finalization.
+BEGIN;
+
+ALTER TABLE expense_items
+    ADD COLUMN IF NOT EXISTS tax_amount_cents BIGINT,
+    ADD COLUMN IF NOT EXISTS tax_jurisdiction TEXT;
+
+ALTER TABLE expense_items
+    DROP CONSTRAINT IF EXISTS expense_items_tax_amount_check;
+ALTER TABLE expense_items
+    ADD CONSTRAINT expense_items_tax_amount_check
+    CHECK (tax_amount_cents IS NULL OR (tax_amount_cents >= 0 AND tax_amount_cents <= amount_cents));
+
+ALTER TABLE journal_lines
+    ADD COLUMN IF NOT EXISTS tax_amount_cents BIGINT;
+
+-- A NULL jurisdiction is the category's fallback code; jurisdictions are
+-- stored upper-cased (e.g. `GB`, `CA-ON`).
+CREATE TABLE IF NOT EXISTS tax_code_mappings (
+    id UUID PRIMARY KEY,
+    category TEXT NOT NULL,
+    jurisdiction TEXT,
+    tax_code TEXT NOT NULL,
+    description TEXT,
+    updated_by UUID REFERENCES employees(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_tax_code_mappings_category_jurisdiction
+    ON tax_code_mappings (category, (COALESCE(jurisdiction, '')));
+
+COMMIT;
@@ -0,0 +1,8 @@
+-- Lets employees tag an expense item with a project/class so finalization can
+-- post it to the matching NetSuite class alongside the employee's department.
+BEGIN;
+
+ALTER TABLE expense_items
+    ADD COLUMN IF NOT EXISTS class TEXT;
+
+COMMIT;
@@ -0,0 +1,28 @@
+-- Accounting periods that finance opens and closes. Finalization never posts
+-- into a closed period; batches carry the posting date they were assigned.
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS periods (
+    id UUID PRIMARY KEY,
+    period_start DATE NOT NULL,
+    period_end DATE NOT NULL,
+    status TEXT NOT NULL DEFAULT 'open' CHECK (status IN ('open', 'closed')),
+    closed_by UUID REFERENCES employees(id) ON DELETE SET NULL,
+    closed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (period_end >= period_start),
+    EXCLUDE USING gist (daterange(period_start, period_end, '[]') WITH &&)
+);
+
+ALTER TABLE netsuite_batches
+    ADD COLUMN IF NOT EXISTS posting_date DATE;
+
+UPDATE netsuite_batches
+SET posting_date = (finalized_at AT TIME ZONE 'UTC')::date
+WHERE posting_date IS NULL;
+
+ALTER TABLE netsuite_batches
+    ALTER COLUMN posting_date SET DEFAULT CURRENT_DATE,
+    ALTER COLUMN posting_date SET NOT NULL;
+
+COMMIT;
@@ -0,0 +1,9 @@
+-- Distinguishes the expense debits written at finalization from the balancing
+-- liability line added per report, so batches post as balanced journals.
+BEGIN;
+
+ALTER TABLE journal_lines
+    ADD COLUMN IF NOT EXISTS line_kind TEXT NOT NULL DEFAULT 'expense'
+        CHECK (line_kind IN ('expense', 'liability'));
+
+COMMIT;
@@ -0,0 +1,13 @@
+-- Records when a report was last submitted so finance can measure how long
+-- manager approval takes.
+BEGIN;
+
+ALTER TABLE expense_reports
+    ADD COLUMN IF NOT EXISTS submitted_at TIMESTAMPTZ;
+
+-- Reports still awaiting review were last touched by their submission.
+UPDATE expense_reports
+SET submitted_at = updated_at
+WHERE status::text = 'submitted' AND submitted_at IS NULL;
+
+COMMIT;
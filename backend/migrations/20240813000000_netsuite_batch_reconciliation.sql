@@ -0,0 +1,8 @@
+-- Tracks when the reconciliation worker last asked NetSuite about an exported
+-- batch, so each sweep checks the least recently reconciled batches first.
+BEGIN;
+
+ALTER TABLE netsuite_batches
+    ADD COLUMN IF NOT EXISTS last_reconciled_at TIMESTAMPTZ;
+
+COMMIT;
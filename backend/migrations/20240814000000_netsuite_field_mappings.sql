@@ -0,0 +1,27 @@
+-- Customer-specific translation of journal data into NetSuite record fields
+-- (subsidiary, location, custom segments, account internal IDs), applied by
+-- the NetSuite exporter.
+BEGIN;
+
+-- `target` is the part of the journal entry the field is written on. A NULL
+-- `source_field` sets the field on every header or line; otherwise the
+-- mapping applies to lines whose `source_field` equals `source_value`.
+CREATE TABLE IF NOT EXISTS netsuite_field_mappings (
+    id UUID PRIMARY KEY,
+    target TEXT NOT NULL CHECK (target IN ('header', 'line')),
+    source_field TEXT CHECK (source_field IN ('gl_account', 'department', 'class', 'tax_code')),
+    source_value TEXT,
+    netsuite_field TEXT NOT NULL,
+    netsuite_value TEXT NOT NULL,
+    description TEXT,
+    updated_by UUID REFERENCES employees(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK ((source_field IS NULL) = (source_value IS NULL)),
+    CHECK (target = 'line' OR source_field IS NULL)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_netsuite_field_mappings_rule
+    ON netsuite_field_mappings
+    (target, netsuite_field, (COALESCE(source_field, '')), (COALESCE(source_value, '')));
+
+COMMIT;
@@ -0,0 +1,22 @@
+-- Archive of the exact request bodies sent to NetSuite for each export
+-- attempt, so auditors can reproduce what was transmitted for any batch.
+BEGIN;
+
+-- `payload` is stored as TEXT rather than JSONB so the bytes are kept exactly
+-- as serialized; `payload_sha256` is the hex digest of those bytes. One row
+-- per record per attempt: vendor-bill exports write one row per bill.
+CREATE TABLE IF NOT EXISTS export_payloads (
+    id UUID PRIMARY KEY,
+    batch_id UUID NOT NULL REFERENCES netsuite_batches(id) ON DELETE CASCADE,
+    attempt INTEGER NOT NULL,
+    record_type TEXT NOT NULL,
+    external_id TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    payload_sha256 TEXT NOT NULL,
+    sent_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_export_payloads_batch
+    ON export_payloads (batch_id, attempt);
+
+COMMIT;
@@ -0,0 +1,55 @@
+-- Data-driven policy rules replacing the hard-coded meal and mileage checks,
+-- so new policies can be added without code changes.
+BEGIN;
+
+-- A rule fires when the spend in `scope` (one item, one day's items, or the
+-- whole report), restricted to `category` when set, compares to
+-- `threshold_cents` (USD) as `comparison` says.
+CREATE TABLE IF NOT EXISTS policy_rules (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    category TEXT CHECK (category IN (
+        'airfare', 'lodging', 'meal', 'ground_transport', 'mileage', 'supplies', 'other'
+    )),
+    comparison TEXT NOT NULL CHECK (comparison IN ('gt', 'gte', 'lt', 'lte')),
+    threshold_cents BIGINT NOT NULL CHECK (threshold_cents >= 0),
+    scope TEXT NOT NULL CHECK (scope IN ('item', 'day', 'report')),
+    severity TEXT NOT NULL CHECK (severity IN ('violation', 'warning')),
+    message TEXT NOT NULL,
+    active_from DATE NOT NULL DEFAULT CURRENT_DATE,
+    active_to DATE,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    updated_by UUID REFERENCES employees(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (active_to IS NULL OR active_to >= active_from)
+);
+
+CREATE INDEX IF NOT EXISTS idx_policy_rules_category ON policy_rules (category);
+
+-- Carry existing meal and mileage caps over with the messages the old checks
+-- produced. Re-running skips caps already converted (rule id = cap id).
+INSERT INTO policy_rules (
+    id, name, category, comparison, threshold_cents, scope, severity, message,
+    active_from, active_to
+)
+SELECT
+    c.id,
+    c.policy_key,
+    c.category::text,
+    'gt',
+    c.amount_cents,
+    'item',
+    'violation',
+    CASE
+        WHEN c.category::text = 'meal' THEN 'Meal exceeds per-diem limit of {limit}'
+        ELSE 'Mileage exceeds configured reimbursement rate'
+    END,
+    c.active_from,
+    c.active_to
+FROM policy_caps c
+WHERE c.category::text IN ('meal', 'mileage')
+  AND c.amount_cents >= 0
+  AND (c.active_to IS NULL OR c.active_to >= c.active_from)
+ON CONFLICT (id) DO NOTHING;
+
+COMMIT;
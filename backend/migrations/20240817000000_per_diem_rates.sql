@@ -0,0 +1,28 @@
+-- Location-based per-diem rates (e.g. the GSA CONUS tables) used to resolve
+-- meal limits from an expense item's location instead of one global cap.
+BEGIN;
+
+-- One row per location, fiscal year, and season. A NULL `city` is the
+-- state's standard rate; NULL `state` and `city` the country's. Seasons are
+-- `month * 100 + day`, inclusive, and may wrap past December.
+CREATE TABLE IF NOT EXISTS per_diem_rates (
+    id UUID PRIMARY KEY,
+    country TEXT NOT NULL DEFAULT 'US',
+    state TEXT,
+    city TEXT,
+    county TEXT,
+    fiscal_year INTEGER NOT NULL,
+    season_begin SMALLINT CHECK (season_begin BETWEEN 101 AND 1231),
+    season_end SMALLINT CHECK (season_end BETWEEN 101 AND 1231),
+    meals_cents BIGINT NOT NULL CHECK (meals_cents >= 0),
+    lodging_cents BIGINT CHECK (lodging_cents >= 0),
+    source TEXT NOT NULL CHECK (source IN ('gsa_conus')),
+    imported_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK ((season_begin IS NULL) = (season_end IS NULL)),
+    CHECK (city IS NULL OR state IS NOT NULL OR country <> 'US')
+);
+
+CREATE INDEX IF NOT EXISTS idx_per_diem_rates_lookup
+    ON per_diem_rates (fiscal_year, country, state);
+
+COMMIT;
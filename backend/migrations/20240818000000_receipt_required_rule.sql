@@ -0,0 +1,38 @@
+-- Receipt-required policy: rules limited to items without receipts, a
+-- `blocking` severity that prevents submission, and the justification an
+-- employee gives when marking an item a policy exception.
+BEGIN;
+
+-- `missing_receipt` rules only count items with no receipt attached
+-- (mileage items never count).
+ALTER TABLE policy_rules
+    ADD COLUMN IF NOT EXISTS condition TEXT NOT NULL DEFAULT 'always'
+        CHECK (condition IN ('always', 'missing_receipt'));
+
+ALTER TABLE policy_rules DROP CONSTRAINT IF EXISTS policy_rules_severity_check;
+ALTER TABLE policy_rules
+    ADD CONSTRAINT policy_rules_severity_check
+        CHECK (severity IN ('violation', 'warning', 'blocking'));
+
+ALTER TABLE expense_items ADD COLUMN IF NOT EXISTS exception_justification TEXT;
+
+-- Items over $25 need a receipt unless marked a justified exception.
+INSERT INTO policy_rules (
+    id, name, category, comparison, threshold_cents, scope, severity, condition, message,
+    active_from
+)
+VALUES (
+    '6f1c2a52-5d1e-4c8b-9a57-0c4e2b7d9f31',
+    'receipt_required',
+    NULL,
+    'gt',
+    2500,
+    'item',
+    'blocking',
+    'missing_receipt',
+    'A receipt is required for the {amount} expense on {date} (over {limit}); attach one or mark the item as a policy exception with a justification',
+    DATE '2024-01-01'
+)
+ON CONFLICT (id) DO NOTHING;
+
+COMMIT;
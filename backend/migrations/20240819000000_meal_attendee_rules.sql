@@ -0,0 +1,35 @@
+-- Business meal attendee rules: a `missing_attendees` rule condition and
+-- per-attendee caps that compare an item's cost per head.
+BEGIN;
+
+ALTER TABLE policy_rules DROP CONSTRAINT IF EXISTS policy_rules_condition_check;
+ALTER TABLE policy_rules
+    ADD CONSTRAINT policy_rules_condition_check
+        CHECK (condition IN ('always', 'missing_receipt', 'missing_attendees'));
+
+ALTER TABLE policy_rules
+    ADD COLUMN IF NOT EXISTS per_attendee BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE policy_rules DROP CONSTRAINT IF EXISTS policy_rules_per_attendee_check;
+ALTER TABLE policy_rules
+    ADD CONSTRAINT policy_rules_per_attendee_check CHECK (NOT per_attendee OR scope = 'item');
+
+-- Meals over $50 must list who attended.
+INSERT INTO policy_rules (
+    id, name, category, comparison, threshold_cents, scope, severity, condition, message,
+    active_from
+)
+VALUES (
+    'b3d0f6e4-8a27-4f3c-9e61-2d5a7c90e418',
+    'meal_attendees_required',
+    'meal',
+    'gt',
+    5000,
+    'item',
+    'blocking',
+    'missing_attendees',
+    'List the attendees for the {amount} meal on {date}; meals over {limit} require them',
+    DATE '2024-01-01'
+)
+ON CONFLICT (id) DO NOTHING;
+
+COMMIT;
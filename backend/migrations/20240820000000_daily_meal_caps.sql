@@ -0,0 +1,18 @@
+-- Meal per-diem caps apply to each day's meals rather than to each meal, so
+-- several smaller meals cannot add up past the daily limit unflagged.
+BEGIN;
+
+-- Only the rules copied from `policy_caps` that an administrator has not
+-- edited since (same scope and message) are converted.
+UPDATE policy_rules r
+SET scope = 'day',
+    message = 'Meals on {date} total {amount}, above the per-diem limit of {limit}',
+    updated_at = NOW()
+FROM policy_caps c
+WHERE r.id = c.id
+  AND c.category::text = 'meal'
+  AND r.category = 'meal'
+  AND r.scope = 'item'
+  AND r.message = 'Meal exceeds per-diem limit of {limit}';
+
+COMMIT;
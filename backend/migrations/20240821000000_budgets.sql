@@ -0,0 +1,26 @@
+-- Monthly and quarterly spending budgets by category and/or department,
+-- checked against cumulative spend when reports are evaluated.
+BEGIN;
+
+-- NULL `category` or `department` covers all of them; `amount_cents` is in
+-- USD like policy rule thresholds.
+CREATE TABLE IF NOT EXISTS budgets (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    category TEXT CHECK (category IN (
+        'airfare', 'lodging', 'meal', 'ground_transport', 'mileage', 'supplies', 'other'
+    )),
+    department TEXT,
+    period TEXT NOT NULL CHECK (period IN ('month', 'quarter')),
+    amount_cents BIGINT NOT NULL CHECK (amount_cents > 0),
+    active_from DATE NOT NULL DEFAULT CURRENT_DATE,
+    active_to DATE,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    updated_by UUID REFERENCES employees(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (active_to IS NULL OR active_to >= active_from)
+);
+
+CREATE INDEX IF NOT EXISTS idx_budgets_department ON budgets (department);
+
+COMMIT;
@@ -0,0 +1,59 @@
+-- Weekend and holiday expense flagging: company holidays, manager-approved
+-- trips that reports can be linked to, and a `non_working_day` rule
+-- condition that skips dates inside the linked trip.
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS holidays (
+    id UUID PRIMARY KEY,
+    holiday_date DATE NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    updated_by UUID REFERENCES employees(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- A trip an employee plans before travelling; only `approved` trips exempt
+-- the reports linked to them from the weekend and holiday rule.
+CREATE TABLE IF NOT EXISTS trips (
+    id UUID PRIMARY KEY,
+    employee_id UUID NOT NULL REFERENCES employees(id) ON DELETE CASCADE,
+    purpose TEXT NOT NULL,
+    destination TEXT,
+    departure_date DATE NOT NULL,
+    return_date DATE NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'approved', 'rejected')),
+    reviewed_by UUID REFERENCES employees(id) ON DELETE SET NULL,
+    reviewed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (return_date >= departure_date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_trips_employee ON trips (employee_id, departure_date);
+
+ALTER TABLE expense_reports
+    ADD COLUMN IF NOT EXISTS trip_id UUID REFERENCES trips(id) ON DELETE SET NULL;
+
+ALTER TABLE policy_rules DROP CONSTRAINT IF EXISTS policy_rules_condition_check;
+ALTER TABLE policy_rules
+    ADD CONSTRAINT policy_rules_condition_check
+        CHECK (condition IN ('always', 'missing_receipt', 'missing_attendees', 'non_working_day'));
+
+-- Any expense on a weekend or holiday outside an approved trip is a warning.
+INSERT INTO policy_rules (
+    id, name, category, comparison, threshold_cents, scope, severity, condition, message,
+    active_from
+)
+VALUES (
+    '0e7b93c1-4f62-4d8a-b5e9-71c2a8d4f053',
+    'weekend_holiday_expense',
+    NULL,
+    'gt',
+    0,
+    'item',
+    'warning',
+    'non_working_day',
+    'The {amount} expense on {date} falls on {day}; link the report to an approved trip or explain the business purpose',
+    DATE '2024-01-01'
+)
+ON CONFLICT (id) DO NOTHING;
+
+COMMIT;
@@ -0,0 +1,41 @@
+-- Airfare advance-purchase policy: a `late_booking` rule condition that
+-- compares an item's date with the linked trip's departure date.
+BEGIN;
+
+-- Minimum days between purchase and departure for `late_booking` rules.
+ALTER TABLE policy_rules ADD COLUMN IF NOT EXISTS advance_days INTEGER;
+
+ALTER TABLE policy_rules DROP CONSTRAINT IF EXISTS policy_rules_condition_check;
+ALTER TABLE policy_rules
+    ADD CONSTRAINT policy_rules_condition_check
+        CHECK (condition IN (
+            'always', 'missing_receipt', 'missing_attendees', 'non_working_day', 'late_booking'
+        ));
+
+ALTER TABLE policy_rules DROP CONSTRAINT IF EXISTS policy_rules_advance_days_check;
+ALTER TABLE policy_rules
+    ADD CONSTRAINT policy_rules_advance_days_check
+        CHECK (CASE WHEN condition = 'late_booking' THEN COALESCE(advance_days > 0, FALSE)
+                    ELSE advance_days IS NULL END);
+
+-- Airfare should be booked at least 14 days before departure.
+INSERT INTO policy_rules (
+    id, name, category, comparison, threshold_cents, scope, severity, condition, advance_days,
+    message, active_from
+)
+VALUES (
+    '5a9d2e70-3c18-4b6f-a4d2-8e1f07b6c935',
+    'airfare_advance_purchase',
+    'airfare',
+    'gt',
+    0,
+    'item',
+    'warning',
+    'late_booking',
+    14,
+    'The {amount} airfare on {date} was booked {days} days before the {travel_date} departure; book at least 14 days ahead',
+    DATE '2024-01-01'
+)
+ON CONFLICT (id) DO NOTHING;
+
+COMMIT;
@@ -0,0 +1,57 @@
+-- Policy versioning: a snapshot of every policy rule each time the rules
+-- change, and the evaluation stored with each report when it is submitted.
+BEGIN;
+
+-- Version `id` is in force from `effective_from` until the next version's.
+CREATE TABLE IF NOT EXISTS policy_versions (
+    id BIGSERIAL PRIMARY KEY,
+    effective_from TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    rules JSONB NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_policy_versions_effective_from
+    ON policy_versions (effective_from);
+
+-- Records a version after any statement that changes the rules.
+CREATE OR REPLACE FUNCTION record_policy_version()
+RETURNS TRIGGER AS $$
+DECLARE
+    snapshot JSONB;
+BEGIN
+    SELECT COALESCE(
+        jsonb_agg(to_jsonb(r) ORDER BY r.category NULLS FIRST, r.name, r.id),
+        '[]'::jsonb
+    )
+    INTO snapshot
+    FROM policy_rules r;
+    IF snapshot IS DISTINCT FROM (SELECT rules FROM policy_versions ORDER BY id DESC LIMIT 1) THEN
+        INSERT INTO policy_versions (effective_from, rules) VALUES (NOW(), snapshot);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_policy_rules_versioned ON policy_rules;
+CREATE TRIGGER trg_policy_rules_versioned
+    AFTER INSERT OR UPDATE OR DELETE ON policy_rules
+    FOR EACH STATEMENT EXECUTE FUNCTION record_policy_version();
+
+-- The rules in force when versioning started. Reports submitted earlier are
+-- judged by this version, the oldest known.
+INSERT INTO policy_versions (effective_from, rules)
+SELECT NOW(), COALESCE(
+    jsonb_agg(to_jsonb(r) ORDER BY r.category NULLS FIRST, r.name, r.id),
+    '[]'::jsonb
+)
+FROM policy_rules r
+WHERE NOT EXISTS (SELECT 1 FROM policy_versions);
+
+-- The evaluation a report was submitted with and the policy version it used.
+CREATE TABLE IF NOT EXISTS report_policy_evaluations (
+    report_id UUID PRIMARY KEY REFERENCES expense_reports(id) ON DELETE CASCADE,
+    policy_version BIGINT NOT NULL REFERENCES policy_versions(id),
+    evaluation JSONB NOT NULL,
+    evaluated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMIT;
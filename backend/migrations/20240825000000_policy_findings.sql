@@ -0,0 +1,32 @@
+-- Typed policy findings: converts evaluations stored at submission from the
+-- `violations` / `warnings` / `blocking` message lists to `findings` entries
+-- with a severity.
+BEGIN;
+
+UPDATE report_policy_evaluations
+SET evaluation = jsonb_build_object(
+    'is_valid', evaluation -> 'is_valid',
+    'policy_version', evaluation -> 'policy_version',
+    'findings', COALESCE((
+        SELECT jsonb_agg(jsonb_build_object(
+            'severity', CASE WHEN evaluation -> 'blocking' ? message THEN 'blocking' ELSE 'violation' END,
+            'code', 'unknown',
+            'message', message,
+            'item_id', NULL,
+            'cap', NULL
+        ))
+        FROM jsonb_array_elements_text(evaluation -> 'violations') AS message
+    ), '[]'::jsonb) || COALESCE((
+        SELECT jsonb_agg(jsonb_build_object(
+            'severity', 'warning',
+            'code', 'unknown',
+            'message', message,
+            'item_id', NULL,
+            'cap', NULL
+        ))
+        FROM jsonb_array_elements_text(evaluation -> 'warnings') AS message
+    ), '[]'::jsonb)
+)
+WHERE evaluation ? 'violations';
+
+COMMIT;
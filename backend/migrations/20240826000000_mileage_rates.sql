@@ -0,0 +1,29 @@
+-- Mileage rate administration: fractional cents per mile (IRS rates such as
+-- 65.5 cents), one rate per effective date, audit columns, and the miles
+-- driven on mileage items so claims can be checked against the rate in force.
+BEGIN;
+
+ALTER TABLE mileage_rates
+    ALTER COLUMN rate_cents_per_mile TYPE NUMERIC(6, 2);
+
+ALTER TABLE mileage_rates
+    ADD COLUMN IF NOT EXISTS updated_by UUID REFERENCES employees(id) ON DELETE SET NULL,
+    ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+-- Keep one rate per effective date (an arbitrary one if several were loaded)
+-- so the rate in force on a date is unambiguous.
+DELETE FROM mileage_rates a
+USING mileage_rates b
+WHERE a.effective_date = b.effective_date AND a.id < b.id;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_mileage_rates_effective_date
+    ON mileage_rates (effective_date);
+
+ALTER TABLE mileage_rates DROP CONSTRAINT IF EXISTS mileage_rates_rate_check;
+ALTER TABLE mileage_rates
+    ADD CONSTRAINT mileage_rates_rate_check CHECK (rate_cents_per_mile > 0);
+
+ALTER TABLE expense_items
+    ADD COLUMN IF NOT EXISTS miles NUMERIC(10, 1) CHECK (miles IS NULL OR miles > 0);
+
+COMMIT;
@@ -0,0 +1,59 @@
+-- Cross-report duplicate detection: merchants on expense items, content
+-- hashes on receipts, and `duplicate_expense` / `duplicate_receipt` rule
+-- conditions matching items against other submitted reports.
+BEGIN;
+
+ALTER TABLE expense_items ADD COLUMN IF NOT EXISTS merchant TEXT;
+
+-- Hex SHA-256 of the stored (sanitized) receipt file, copied from the upload
+-- when the receipt is attached.
+ALTER TABLE receipt_uploads ADD COLUMN IF NOT EXISTS content_sha256 TEXT;
+ALTER TABLE receipts ADD COLUMN IF NOT EXISTS content_sha256 TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_receipts_content_sha256
+    ON receipts (content_sha256) WHERE content_sha256 IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_expense_items_date_amount
+    ON expense_items (expense_date, amount_cents);
+
+ALTER TABLE policy_rules DROP CONSTRAINT IF EXISTS policy_rules_condition_check;
+ALTER TABLE policy_rules
+    ADD CONSTRAINT policy_rules_condition_check
+        CHECK (condition IN (
+            'always', 'missing_receipt', 'missing_attendees', 'non_working_day', 'late_booking',
+            'duplicate_expense', 'duplicate_receipt'
+        ));
+
+-- Both start as warnings; administrators can make `duplicate_receipt`
+-- blocking, since an identical receipt file is rarely a coincidence.
+INSERT INTO policy_rules (
+    id, name, category, comparison, threshold_cents, scope, severity, condition, message,
+    active_from
+)
+VALUES
+    (
+        '9c41f6b2-7a0e-4d35-8f12-b6e3d5a07c84',
+        'duplicate_expense',
+        NULL,
+        'gt',
+        0,
+        'item',
+        'warning',
+        'duplicate_expense',
+        'The {amount} expense on {date} matches an item with the same date, amount, and merchant on report {duplicate_report}',
+        DATE '2024-01-01'
+    ),
+    (
+        'e2b8d0a5-1f67-4c93-a4e0-5d7c92f1b316',
+        'duplicate_receipt',
+        NULL,
+        'gt',
+        0,
+        'item',
+        'warning',
+        'duplicate_receipt',
+        'The receipt for the {amount} expense on {date} is identical to one attached to report {duplicate_report}',
+        DATE '2024-01-01'
+    )
+ON CONFLICT (id) DO NOTHING;
+
+COMMIT;
@@ -0,0 +1,94 @@
+-- Elevated approval for policy exceptions: department heads, the approver a
+-- report with exception items is routed to after its manager approves, and
+-- the `exception_review` status the report waits in meanwhile.
+BEGIN;
+
+-- Databases that still use the `report_status` enum need the new value.
+DO $$
+BEGIN
+    IF EXISTS (SELECT 1 FROM pg_type WHERE typname = 'report_status') THEN
+        ALTER TYPE report_status ADD VALUE IF NOT EXISTS 'exception_review' AFTER 'submitted';
+    END IF;
+END;
+$$;
+
+-- One head per `employees.department`.
+CREATE TABLE IF NOT EXISTS department_heads (
+    id UUID PRIMARY KEY,
+    department TEXT NOT NULL UNIQUE,
+    employee_id UUID NOT NULL REFERENCES employees(id) ON DELETE CASCADE,
+    updated_by UUID REFERENCES employees(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Who must approve the report's policy exceptions once its manager has; NULL
+-- when no one but an administrator can.
+ALTER TABLE expense_reports
+    ADD COLUMN IF NOT EXISTS exception_approver_id UUID REFERENCES employees(id) ON DELETE SET NULL;
+
+-- Reports awaiting exception review are queued for their exception approver
+-- instead of the employee's manager.
+CREATE OR REPLACE FUNCTION refresh_manager_queue_entry(target_report UUID)
+RETURNS VOID AS $$
+BEGIN
+    DELETE FROM manager_queue_entries WHERE report_id = target_report;
+
+    INSERT INTO manager_queue_entries (
+        report_id,
+        manager_id,
+        employee_id,
+        employee_hr_identifier,
+        reporting_period_start,
+        reporting_period_end,
+        total_amount_cents,
+        total_reimbursable_cents,
+        currency,
+        submitted_at,
+        line_items,
+        policy_flag_count
+    )
+    SELECT
+        r.id,
+        CASE WHEN r.status::text = 'exception_review' THEN r.exception_approver_id
+             ELSE e.manager_id END,
+        r.employee_id,
+        e.hr_identifier,
+        r.reporting_period_start,
+        r.reporting_period_end,
+        r.total_amount_cents,
+        r.total_reimbursable_cents,
+        r.currency,
+        r.updated_at,
+        COALESCE(
+            (
+                SELECT jsonb_agg(
+                    jsonb_build_object(
+                        'id', i.id,
+                        'reportId', i.report_id,
+                        'expenseDate', i.expense_date,
+                        'category', i.category::text,
+                        'description', i.description,
+                        'amountCents', i.amount_cents,
+                        'reimbursable', i.reimbursable,
+                        'paymentMethod', i.payment_method,
+                        'isPolicyException', i.is_policy_exception
+                    )
+                    ORDER BY i.expense_date ASC, i.id ASC
+                )
+                FROM expense_items i
+                WHERE i.report_id = r.id
+            ),
+            '[]'::jsonb
+        ),
+        (
+            SELECT COUNT(*)
+            FROM expense_items i
+            WHERE i.report_id = r.id AND i.is_policy_exception
+        )
+    FROM expense_reports r
+    JOIN employees e ON e.id = r.employee_id
+    WHERE r.id = target_report AND r.status::text IN ('submitted', 'exception_review');
+END;
+$$ LANGUAGE plpgsql;
+
+COMMIT;
@@ -0,0 +1,32 @@
+-- Policy evaluation history: every draft evaluation that changed a report's
+-- findings and every submission, with the policy and backend versions used.
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS policy_evaluation_runs (
+    id BIGSERIAL PRIMARY KEY,
+    report_id UUID NOT NULL REFERENCES expense_reports(id) ON DELETE CASCADE,
+    trigger TEXT NOT NULL CHECK (trigger IN ('evaluation', 'submission')),
+    policy_version BIGINT REFERENCES policy_versions(id),
+    -- NULL for runs backfilled from earlier submissions.
+    engine_version TEXT,
+    is_valid BOOLEAN NOT NULL,
+    evaluation JSONB NOT NULL,
+    evaluated_by UUID REFERENCES employees(id) ON DELETE SET NULL,
+    evaluated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_policy_evaluation_runs_report
+    ON policy_evaluation_runs (report_id, id);
+
+-- Evaluations stored at submission start each report's history.
+INSERT INTO policy_evaluation_runs
+    (report_id, trigger, policy_version, is_valid, evaluation, evaluated_at)
+SELECT e.report_id, 'submission', e.policy_version,
+       COALESCE((e.evaluation->>'is_valid')::boolean, TRUE), e.evaluation, e.evaluated_at
+FROM report_policy_evaluations e
+WHERE NOT EXISTS (
+    SELECT 1 FROM policy_evaluation_runs r WHERE r.report_id = e.report_id
+)
+ORDER BY e.evaluated_at;
+
+COMMIT;
@@ -0,0 +1,12 @@
+-- Fiscal-year budgets, and budgets applied to each employee's own spend
+-- (e.g. an annual training allowance per employee).
+BEGIN;
+
+ALTER TABLE budgets ADD COLUMN IF NOT EXISTS per_employee BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE budgets DROP CONSTRAINT IF EXISTS budgets_period_check;
+ALTER TABLE budgets
+    ADD CONSTRAINT budgets_period_check
+        CHECK (period IN ('month', 'quarter', 'fiscal_year'));
+
+COMMIT;
@@ -0,0 +1,19 @@
+-- Escalations of approvals left waiting too long: first to the stalled
+-- approver's own manager, then to finance.
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS approval_escalations (
+    id UUID PRIMARY KEY,
+    report_id UUID NOT NULL REFERENCES expense_reports(id) ON DELETE CASCADE,
+    level TEXT NOT NULL CHECK (level IN ('skip_level', 'finance')),
+    approver_id UUID REFERENCES employees(id) ON DELETE SET NULL,
+    escalated_to UUID REFERENCES employees(id) ON DELETE SET NULL,
+    pending_since TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (report_id, level, pending_since)
+);
+
+CREATE INDEX IF NOT EXISTS idx_approval_escalations_level
+    ON approval_escalations (level, created_at);
+
+COMMIT;
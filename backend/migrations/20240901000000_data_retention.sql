@@ -0,0 +1,22 @@
+-- Data retention runs: each purge of expense data past the retention window is
+-- planned as a dry run first and only executed once an administrator confirms.
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS retention_runs (
+    id UUID PRIMARY KEY,
+    mode TEXT NOT NULL CHECK (mode IN ('anonymize', 'delete')),
+    cutoff TIMESTAMPTZ NOT NULL,
+    status TEXT NOT NULL DEFAULT 'planned' CHECK (status IN ('planned', 'executed')),
+    reports_anonymized BIGINT NOT NULL DEFAULT 0,
+    reports_deleted BIGINT NOT NULL DEFAULT 0,
+    receipts_removed BIGINT NOT NULL DEFAULT 0,
+    audit_logs_removed BIGINT NOT NULL DEFAULT 0,
+    requested_by UUID REFERENCES employees(id) ON DELETE SET NULL,
+    confirmed_by UUID REFERENCES employees(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    executed_at TIMESTAMPTZ
+);
+
+ALTER TABLE expense_reports ADD COLUMN IF NOT EXISTS anonymized_at TIMESTAMPTZ;
+
+COMMIT;
@@ -0,0 +1,23 @@
+-- Transactional outbox: side effects that leave the database (the NetSuite
+-- export of a finalized batch) are recorded in the same transaction as the
+-- state change and delivered afterwards, at least once, by the outbox worker.
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS outbox_events (
+    id UUID PRIMARY KEY,
+    topic TEXT NOT NULL,
+    dedup_key TEXT NOT NULL UNIQUE,
+    payload JSONB NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'delivered', 'dead')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    delivered_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_outbox_events_due
+    ON outbox_events (next_attempt_at)
+    WHERE status = 'pending';
+
+COMMIT;
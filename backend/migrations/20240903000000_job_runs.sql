@@ -0,0 +1,24 @@
+-- Background job history: one row per run of a scheduled or manually
+-- triggered job, with its outcome and error, for the admin jobs API.
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS job_runs (
+    id UUID PRIMARY KEY,
+    job_name TEXT NOT NULL,
+    trigger TEXT NOT NULL CHECK (trigger IN ('scheduled', 'manual')),
+    triggered_by UUID REFERENCES employees(id) ON DELETE SET NULL,
+    status TEXT NOT NULL DEFAULT 'running' CHECK (status IN ('running', 'succeeded', 'failed')),
+    error TEXT,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    finished_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_job_runs_job_started
+    ON job_runs (job_name, started_at DESC);
+
+-- A job never runs twice at once, whatever started it.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_job_runs_one_running
+    ON job_runs (job_name)
+    WHERE status = 'running';
+
+COMMIT;
@@ -0,0 +1,10 @@
+-- Employee email addresses and delivery tracking for emailed notifications
+BEGIN;
+
+ALTER TABLE employees ADD COLUMN IF NOT EXISTS email TEXT;
+
+ALTER TABLE notifications
+    ADD COLUMN IF NOT EXISTS last_error TEXT,
+    ADD COLUMN IF NOT EXISTS sent_at TIMESTAMPTZ;
+
+COMMIT;
@@ -0,0 +1,6 @@
+-- Personal Slack or Teams webhooks for chat notifications
+BEGIN;
+
+ALTER TABLE notification_preferences ADD COLUMN IF NOT EXISTS chat_webhook_url TEXT;
+
+COMMIT;
@@ -0,0 +1,8 @@
+-- Per-reviewer digest frequency: daily, weekly (the previous behaviour), or none
+BEGIN;
+
+ALTER TABLE notification_preferences
+    ADD COLUMN IF NOT EXISTS digest_frequency TEXT NOT NULL DEFAULT 'weekly'
+        CHECK (digest_frequency IN ('daily', 'weekly', 'none'));
+
+COMMIT;
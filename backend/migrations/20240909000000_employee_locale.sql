@@ -0,0 +1,6 @@
+-- Employee locale used to pick the language of notification templates
+BEGIN;
+
+ALTER TABLE employees ADD COLUMN IF NOT EXISTS locale TEXT;
+
+COMMIT;
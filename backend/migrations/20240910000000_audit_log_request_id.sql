@@ -0,0 +1,6 @@
+-- Request ID on audit log rows, to match an audited change to the request logs
+BEGIN;
+
+ALTER TABLE audit_logs ADD COLUMN IF NOT EXISTS request_id TEXT;
+
+COMMIT;
@@ -0,0 +1,20 @@
+-- Idempotency keys: the first response to each `Idempotency-Key` an employee
+-- sends with a mutating request, replayed when the request is retried.
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    employee_id UUID NOT NULL,
+    idempotency_key TEXT NOT NULL,
+    request_hash TEXT NOT NULL,
+    response_status INTEGER,
+    response_content_type TEXT,
+    response_body BYTEA,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ,
+    PRIMARY KEY (employee_id, idempotency_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_created
+    ON idempotency_keys (created_at);
+
+COMMIT;
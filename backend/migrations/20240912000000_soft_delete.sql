@@ -0,0 +1,75 @@
+-- Soft deletion: reports, items, and receipts are marked deleted instead of
+-- removed, so an administrator can restore them and their audit history keeps
+-- pointing at existing rows.
+BEGIN;
+
+ALTER TABLE expense_reports ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE expense_items ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE receipts ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+-- Deleted reports and items stay out of the manager queue.
+CREATE OR REPLACE FUNCTION refresh_manager_queue_entry(target_report UUID)
+RETURNS VOID AS $$
+BEGIN
+    DELETE FROM manager_queue_entries WHERE report_id = target_report;
+
+    INSERT INTO manager_queue_entries (
+        report_id,
+        manager_id,
+        employee_id,
+        employee_hr_identifier,
+        reporting_period_start,
+        reporting_period_end,
+        total_amount_cents,
+        total_reimbursable_cents,
+        currency,
+        submitted_at,
+        line_items,
+        policy_flag_count
+    )
+    SELECT
+        r.id,
+        CASE WHEN r.status::text = 'exception_review' THEN r.exception_approver_id
+             ELSE e.manager_id END,
+        r.employee_id,
+        e.hr_identifier,
+        r.reporting_period_start,
+        r.reporting_period_end,
+        r.total_amount_cents,
+        r.total_reimbursable_cents,
+        r.currency,
+        r.updated_at,
+        COALESCE(
+            (
+                SELECT jsonb_agg(
+                    jsonb_build_object(
+                        'id', i.id,
+                        'reportId', i.report_id,
+                        'expenseDate', i.expense_date,
+                        'category', i.category::text,
+                        'description', i.description,
+                        'amountCents', i.amount_cents,
+                        'reimbursable', i.reimbursable,
+                        'paymentMethod', i.payment_method,
+                        'isPolicyException', i.is_policy_exception
+                    )
+                    ORDER BY i.expense_date ASC, i.id ASC
+                )
+                FROM expense_items i
+                WHERE i.report_id = r.id AND i.deleted_at IS NULL
+            ),
+            '[]'::jsonb
+        ),
+        (
+            SELECT COUNT(*)
+            FROM expense_items i
+            WHERE i.report_id = r.id AND i.is_policy_exception AND i.deleted_at IS NULL
+        )
+    FROM expense_reports r
+    JOIN employees e ON e.id = r.employee_id
+    WHERE r.id = target_report AND r.status::text IN ('submitted', 'exception_review')
+      AND r.deleted_at IS NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+COMMIT;
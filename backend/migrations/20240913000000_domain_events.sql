@@ -0,0 +1,23 @@
+-- Domain events: an append-only record of workflow facts (a report was
+-- submitted, a decision was recorded, a batch was exported), written in the
+-- same transaction as the change. Consumers read them in `sequence` order.
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS domain_events (
+    id UUID PRIMARY KEY,
+    sequence BIGSERIAL NOT NULL UNIQUE,
+    event_type TEXT NOT NULL,
+    aggregate_type TEXT NOT NULL,
+    aggregate_id UUID NOT NULL,
+    actor_id UUID REFERENCES employees(id),
+    payload JSONB NOT NULL,
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_domain_events_aggregate
+    ON domain_events (aggregate_type, aggregate_id, sequence);
+
+CREATE INDEX IF NOT EXISTS idx_domain_events_type
+    ON domain_events (event_type, sequence);
+
+COMMIT;
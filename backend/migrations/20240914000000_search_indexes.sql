@@ -0,0 +1,27 @@
+-- Search over expense items: a weighted full-text vector of merchant and
+-- description with a GIN index for word matches, and trigram GIN indexes so
+-- substring (`ILIKE '%…%'`) and fuzzy matches on those columns and on batch
+-- references use an index instead of scanning.
+BEGIN;
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+ALTER TABLE expense_items
+    ADD COLUMN IF NOT EXISTS search_vector TSVECTOR GENERATED ALWAYS AS (
+        setweight(to_tsvector('english', COALESCE(merchant, '')), 'A')
+        || setweight(to_tsvector('english', COALESCE(description, '')), 'B')
+    ) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_expense_items_search_vector
+    ON expense_items USING GIN (search_vector);
+
+CREATE INDEX IF NOT EXISTS idx_expense_items_merchant_trgm
+    ON expense_items USING GIN (merchant gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS idx_expense_items_description_trgm
+    ON expense_items USING GIN (description gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS idx_netsuite_batches_reference_trgm
+    ON netsuite_batches USING GIN (batch_reference gin_trgm_ops);
+
+COMMIT;
@@ -0,0 +1,15 @@
+-- Cold storage for old finalized reports: the archive job moves a report's
+-- items, receipts, approvals, and policy evaluations into a compressed JSON
+-- object in the storage backend and keeps the report row as a stub pointing
+-- at it. Rehydrating restores the rows and clears the pointer.
+BEGIN;
+
+ALTER TABLE expense_reports ADD COLUMN IF NOT EXISTS archived_at TIMESTAMPTZ;
+ALTER TABLE expense_reports ADD COLUMN IF NOT EXISTS archive_key TEXT;
+ALTER TABLE expense_reports ADD COLUMN IF NOT EXISTS rehydrated_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_expense_reports_archivable
+    ON expense_reports (updated_at)
+    WHERE status = 'finance_finalized' AND archived_at IS NULL;
+
+COMMIT;
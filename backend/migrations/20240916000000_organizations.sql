@@ -0,0 +1,65 @@
+-- Organizations: several subsidiaries share one deployment, each seeing only
+-- its own employees, reports, caps, and batches. Existing data belongs to the
+-- default organization, which also operates the deployment. Reports and
+-- batches always belong to the organization of the employee who owns or
+-- finalized them; triggers set `org_id` on insert so no writer can get it
+-- wrong. Policy rules without an organization are shared by all of them.
+-- Department names are only unique within an organization, so each has its
+-- own department heads.
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS organizations (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+INSERT INTO organizations (id, name)
+VALUES ('00000000-0000-0000-0000-000000000001', 'Default')
+ON CONFLICT (id) DO NOTHING;
+
+ALTER TABLE employees ADD COLUMN IF NOT EXISTS org_id UUID NOT NULL
+    DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES organizations(id);
+ALTER TABLE expense_reports ADD COLUMN IF NOT EXISTS org_id UUID NOT NULL
+    DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES organizations(id);
+ALTER TABLE netsuite_batches ADD COLUMN IF NOT EXISTS org_id UUID NOT NULL
+    DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES organizations(id);
+ALTER TABLE policy_rules ADD COLUMN IF NOT EXISTS org_id UUID REFERENCES organizations(id);
+ALTER TABLE department_heads ADD COLUMN IF NOT EXISTS org_id UUID NOT NULL
+    DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES organizations(id);
+
+ALTER TABLE department_heads DROP CONSTRAINT IF EXISTS department_heads_department_key;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_department_heads_org_department
+    ON department_heads (org_id, department);
+
+CREATE INDEX IF NOT EXISTS idx_employees_org ON employees (org_id, hr_identifier);
+CREATE INDEX IF NOT EXISTS idx_expense_reports_org_status ON expense_reports (org_id, status);
+CREATE INDEX IF NOT EXISTS idx_netsuite_batches_org ON netsuite_batches (org_id, finalized_at DESC);
+
+CREATE OR REPLACE FUNCTION set_expense_report_org()
+RETURNS TRIGGER AS $$
+BEGIN
+    SELECT org_id INTO NEW.org_id FROM employees WHERE id = NEW.employee_id;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS expense_reports_set_org ON expense_reports;
+CREATE TRIGGER expense_reports_set_org
+    BEFORE INSERT ON expense_reports
+    FOR EACH ROW EXECUTE FUNCTION set_expense_report_org();
+
+CREATE OR REPLACE FUNCTION set_netsuite_batch_org()
+RETURNS TRIGGER AS $$
+BEGIN
+    SELECT org_id INTO NEW.org_id FROM employees WHERE id = NEW.finalized_by;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS netsuite_batches_set_org ON netsuite_batches;
+CREATE TRIGGER netsuite_batches_set_org
+    BEFORE INSERT ON netsuite_batches
+    FOR EACH ROW EXECUTE FUNCTION set_netsuite_batch_org();
+
+COMMIT;
@@ -0,0 +1,26 @@
+-- Hash chain over each entity's audit trail: entries are numbered per entity
+-- and each new entry's signature hash covers the previous entry's hash, so a
+-- removed, reordered, or edited entry breaks the chain. Existing entries are
+-- numbered in the order they were recorded and keep their hashes; they have
+-- no `previous_hash`, and the first chained entry links to the last of them.
+BEGIN;
+
+ALTER TABLE audit_logs ADD COLUMN IF NOT EXISTS chain_sequence BIGINT;
+ALTER TABLE audit_logs ADD COLUMN IF NOT EXISTS previous_hash TEXT;
+
+UPDATE audit_logs a
+SET chain_sequence = numbered.chain_sequence
+FROM (
+    SELECT id,
+           ROW_NUMBER() OVER (PARTITION BY entity_type, entity_id ORDER BY performed_at, id)
+               AS chain_sequence
+    FROM audit_logs
+) numbered
+WHERE a.id = numbered.id AND a.chain_sequence IS NULL;
+
+ALTER TABLE audit_logs ALTER COLUMN chain_sequence SET NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_audit_logs_chain
+    ON audit_logs (entity_type, entity_id, chain_sequence);
+
+COMMIT;
@@ -0,0 +1,56 @@
+-- Departments and cost centers as managed reference data. Each organization
+-- keeps its own; `code` is what journal lines export as the department (the
+-- NetSuite department, for instance). Employees point at their department
+-- and default cost center, and items can be allocated to a cost center.
+-- `employees.department` stays as the department's code, which department
+-- heads and budgets key on. Departments are created for the free-text values
+-- already on file, ignoring case.
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS departments (
+    id UUID PRIMARY KEY,
+    org_id UUID NOT NULL REFERENCES organizations(id),
+    code TEXT NOT NULL,
+    name TEXT NOT NULL,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_departments_org_code
+    ON departments (org_id, UPPER(code));
+
+CREATE TABLE IF NOT EXISTS cost_centers (
+    id UUID PRIMARY KEY,
+    org_id UUID NOT NULL REFERENCES organizations(id),
+    code TEXT NOT NULL,
+    name TEXT NOT NULL,
+    department_id UUID REFERENCES departments(id),
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_cost_centers_org_code
+    ON cost_centers (org_id, UPPER(code));
+
+ALTER TABLE employees ADD COLUMN IF NOT EXISTS department_id UUID REFERENCES departments(id);
+ALTER TABLE employees ADD COLUMN IF NOT EXISTS cost_center_id UUID REFERENCES cost_centers(id);
+ALTER TABLE expense_items ADD COLUMN IF NOT EXISTS cost_center_id UUID REFERENCES cost_centers(id);
+
+CREATE INDEX IF NOT EXISTS idx_employees_department ON employees (department_id);
+CREATE INDEX IF NOT EXISTS idx_expense_items_cost_center ON expense_items (cost_center_id);
+
+INSERT INTO departments (id, org_id, code, name)
+SELECT gen_random_uuid(), org_id, MIN(department), MIN(department)
+FROM employees
+WHERE department IS NOT NULL
+GROUP BY org_id, UPPER(department)
+ON CONFLICT DO NOTHING;
+
+UPDATE employees e
+SET department_id = d.id, department = d.code
+FROM departments d
+WHERE d.org_id = e.org_id AND UPPER(d.code) = UPPER(e.department) AND e.department_id IS NULL;
+
+COMMIT;
@@ -0,0 +1,15 @@
+-- Deactivation of employees who have left. A deactivated employee can no
+-- longer sign in or approve; their rows stay for the reports and audit
+-- history that point at them. Drafts still open when their owner was
+-- deactivated are flagged with `owner_deactivated_at` for finance to finish
+-- or discard.
+BEGIN;
+
+ALTER TABLE employees ADD COLUMN IF NOT EXISTS deactivated_at TIMESTAMPTZ;
+ALTER TABLE expense_reports ADD COLUMN IF NOT EXISTS owner_deactivated_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_expense_reports_owner_deactivated
+    ON expense_reports (org_id, owner_deactivated_at)
+    WHERE owner_deactivated_at IS NOT NULL;
+
+COMMIT;
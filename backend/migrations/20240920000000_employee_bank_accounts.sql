@@ -0,0 +1,19 @@
+-- Employees' reimbursement bank accounts. The routing and account numbers
+-- are sealed together with AES-256-GCM under the banking key named by
+-- `key_id` and never stored in the clear; only the holder's name and the
+-- account type are readable without the key.
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS employee_bank_accounts (
+    id UUID PRIMARY KEY,
+    employee_id UUID NOT NULL UNIQUE REFERENCES employees(id),
+    account_holder TEXT NOT NULL,
+    account_type TEXT NOT NULL CHECK (account_type IN ('checking', 'savings')),
+    key_id TEXT NOT NULL,
+    nonce BYTEA NOT NULL,
+    ciphertext BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMIT;
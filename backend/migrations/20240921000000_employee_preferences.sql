@@ -0,0 +1,10 @@
+-- Profile preferences employees set through `PATCH /api/auth/me/preferences`
+-- alongside `locale`: the IANA time zone their reporting periods end in and
+-- the currency new reports default to. Both start unset, which keeps the
+-- previous behaviour (UTC, currency required on every report).
+BEGIN;
+
+ALTER TABLE employees ADD COLUMN IF NOT EXISTS timezone TEXT;
+ALTER TABLE employees ADD COLUMN IF NOT EXISTS default_currency TEXT;
+
+COMMIT;
@@ -0,0 +1,26 @@
+-- Assistants acting for other employees. An employee links the assistants
+-- allowed to create and submit reports for them; reports record who created
+-- and submitted them, and audit entries made by an assistant name the
+-- employee they acted for in `on_behalf_of`.
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS assistant_links (
+    id UUID PRIMARY KEY,
+    principal_id UUID NOT NULL REFERENCES employees(id) ON DELETE CASCADE,
+    assistant_id UUID NOT NULL REFERENCES employees(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (principal_id, assistant_id),
+    CHECK (principal_id <> assistant_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_assistant_links_assistant
+    ON assistant_links (assistant_id);
+
+ALTER TABLE expense_reports
+    ADD COLUMN IF NOT EXISTS created_by UUID REFERENCES employees(id) ON DELETE SET NULL;
+ALTER TABLE expense_reports
+    ADD COLUMN IF NOT EXISTS submitted_by UUID REFERENCES employees(id) ON DELETE SET NULL;
+
+ALTER TABLE audit_logs ADD COLUMN IF NOT EXISTS on_behalf_of UUID;
+
+COMMIT;
@@ -0,0 +1,13 @@
+-- When each reviewer was last reminded about each report, so the reminder
+-- worker repeats a reminder only after `reminders.repeat_after_business_days`
+-- instead of on every sweep.
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS approval_reminder_log (
+    report_id UUID NOT NULL REFERENCES expense_reports(id) ON DELETE CASCADE,
+    reviewer_id UUID NOT NULL REFERENCES employees(id) ON DELETE CASCADE,
+    last_reminded_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (report_id, reviewer_id)
+);
+
+COMMIT;
@@ -0,0 +1,84 @@
+-- The manager queue took `submitted_at` from the report's `updated_at` on
+-- every refresh, so editing a waiting report moved it to the back of the
+-- queue. Entries now use the report's own `submitted_at`, which is written
+-- once on submission, and reports without one keep the time their entry was
+-- first created.
+BEGIN;
+
+CREATE OR REPLACE FUNCTION refresh_manager_queue_entry(target_report UUID)
+RETURNS VOID AS $$
+DECLARE
+    queued_at TIMESTAMPTZ;
+BEGIN
+    SELECT submitted_at INTO queued_at
+    FROM manager_queue_entries
+    WHERE report_id = target_report;
+
+    DELETE FROM manager_queue_entries WHERE report_id = target_report;
+
+    INSERT INTO manager_queue_entries (
+        report_id,
+        manager_id,
+        employee_id,
+        employee_hr_identifier,
+        reporting_period_start,
+        reporting_period_end,
+        total_amount_cents,
+        total_reimbursable_cents,
+        currency,
+        submitted_at,
+        line_items,
+        policy_flag_count
+    )
+    SELECT
+        r.id,
+        CASE WHEN r.status::text = 'exception_review' THEN r.exception_approver_id
+             ELSE e.manager_id END,
+        r.employee_id,
+        e.hr_identifier,
+        r.reporting_period_start,
+        r.reporting_period_end,
+        r.total_amount_cents,
+        r.total_reimbursable_cents,
+        r.currency,
+        COALESCE(r.submitted_at, queued_at, r.updated_at),
+        COALESCE(
+            (
+                SELECT jsonb_agg(
+                    jsonb_build_object(
+                        'id', i.id,
+                        'reportId', i.report_id,
+                        'expenseDate', i.expense_date,
+                        'category', i.category::text,
+                        'description', i.description,
+                        'amountCents', i.amount_cents,
+                        'reimbursable', i.reimbursable,
+                        'paymentMethod', i.payment_method,
+                        'isPolicyException', i.is_policy_exception
+                    )
+                    ORDER BY i.expense_date ASC, i.id ASC
+                )
+                FROM expense_items i
+                WHERE i.report_id = r.id AND i.deleted_at IS NULL
+            ),
+            '[]'::jsonb
+        ),
+        (
+            SELECT COUNT(*)
+            FROM expense_items i
+            WHERE i.report_id = r.id AND i.is_policy_exception AND i.deleted_at IS NULL
+        )
+    FROM expense_reports r
+    JOIN employees e ON e.id = r.employee_id
+    WHERE r.id = target_report AND r.status::text IN ('submitted', 'exception_review')
+      AND r.deleted_at IS NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Entries of reports that record their submission take that time.
+UPDATE manager_queue_entries q
+SET submitted_at = r.submitted_at
+FROM expense_reports r
+WHERE r.id = q.report_id AND r.submitted_at IS NOT NULL;
+
+COMMIT;
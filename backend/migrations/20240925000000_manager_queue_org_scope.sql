@@ -0,0 +1,96 @@
+-- Every manager sees all submitted reports of their organization again, as
+-- before the read model, while exception reviews stay with their assigned
+-- exception approver. Entries record the report's organization and status so
+-- the queue remains a single indexed lookup.
+BEGIN;
+
+ALTER TABLE manager_queue_entries
+    ADD COLUMN IF NOT EXISTS org_id UUID REFERENCES organizations(id),
+    ADD COLUMN IF NOT EXISTS status TEXT;
+
+CREATE OR REPLACE FUNCTION refresh_manager_queue_entry(target_report UUID)
+RETURNS VOID AS $$
+DECLARE
+    queued_at TIMESTAMPTZ;
+BEGIN
+    SELECT submitted_at INTO queued_at
+    FROM manager_queue_entries
+    WHERE report_id = target_report;
+
+    DELETE FROM manager_queue_entries WHERE report_id = target_report;
+
+    INSERT INTO manager_queue_entries (
+        report_id,
+        org_id,
+        status,
+        manager_id,
+        employee_id,
+        employee_hr_identifier,
+        reporting_period_start,
+        reporting_period_end,
+        total_amount_cents,
+        total_reimbursable_cents,
+        currency,
+        submitted_at,
+        line_items,
+        policy_flag_count
+    )
+    SELECT
+        r.id,
+        r.org_id,
+        r.status::text,
+        CASE WHEN r.status::text = 'exception_review' THEN r.exception_approver_id
+             ELSE e.manager_id END,
+        r.employee_id,
+        e.hr_identifier,
+        r.reporting_period_start,
+        r.reporting_period_end,
+        r.total_amount_cents,
+        r.total_reimbursable_cents,
+        r.currency,
+        COALESCE(r.submitted_at, queued_at, r.updated_at),
+        COALESCE(
+            (
+                SELECT jsonb_agg(
+                    jsonb_build_object(
+                        'id', i.id,
+                        'reportId', i.report_id,
+                        'expenseDate', i.expense_date,
+                        'category', i.category::text,
+                        'description', i.description,
+                        'amountCents', i.amount_cents,
+                        'reimbursable', i.reimbursable,
+                        'paymentMethod', i.payment_method,
+                        'isPolicyException', i.is_policy_exception
+                    )
+                    ORDER BY i.expense_date ASC, i.id ASC
+                )
+                FROM expense_items i
+                WHERE i.report_id = r.id AND i.deleted_at IS NULL
+            ),
+            '[]'::jsonb
+        ),
+        (
+            SELECT COUNT(*)
+            FROM expense_items i
+            WHERE i.report_id = r.id AND i.is_policy_exception AND i.deleted_at IS NULL
+        )
+    FROM expense_reports r
+    JOIN employees e ON e.id = r.employee_id
+    WHERE r.id = target_report AND r.status::text IN ('submitted', 'exception_review')
+      AND r.deleted_at IS NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+SELECT refresh_manager_queue_entry(id)
+FROM expense_reports
+WHERE status::text IN ('submitted', 'exception_review');
+
+ALTER TABLE manager_queue_entries
+    ALTER COLUMN org_id SET NOT NULL,
+    ALTER COLUMN status SET NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_manager_queue_entries_org_submitted
+    ON manager_queue_entries (org_id, submitted_at);
+
+COMMIT;
@@ -0,0 +1,171 @@
+//! The client address requests are rate limited and audited under.
+//!
+//! The address is the TCP peer's. Only when the peer is one of
+//! `app.trusted_proxies` is `X-Forwarded-For` read, from the right: trusted
+//! proxies are skipped and the first other hop is the client. Hops left of
+//! it were written by the client itself and are ignored, so a client cannot
+//! choose the address, and with it the rate-limit bucket, it is keyed on.
+
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::HeaderMap,
+    middleware::Next,
+    response::Response,
+};
+use ipnet::IpNet;
+use tracing::warn;
+
+const FORWARDED_FOR: &str = "x-forwarded-for";
+
+/// Request extension holding the resolved client address; `None` when the
+/// request did not come over a socket, as in tests.
+#[derive(Debug, Clone)]
+pub struct ClientIp(pub Option<IpAddr>);
+
+/// Proxies whose `X-Forwarded-For` is believed.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies(Vec<IpNet>);
+
+impl TrustedProxies {
+    /// Parses addresses and CIDR ranges, skipping invalid entries.
+    pub fn parse(entries: &[String]) -> Self {
+        let nets = entries
+            .iter()
+            .map(|entry| entry.trim())
+            .filter(|entry| !entry.is_empty())
+            .filter_map(|entry| {
+                let net = entry
+                    .parse::<IpNet>()
+                    .or_else(|_| entry.parse::<IpAddr>().map(IpNet::from));
+                if net.is_err() {
+                    warn!(%entry, "skipping invalid trusted proxy; use an IP address or CIDR range");
+                }
+                net.ok()
+            })
+            .collect();
+        Self(nets)
+    }
+
+    fn contains(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        self.0.iter().any(|net| net.contains(&ip))
+    }
+
+    /// The client behind `peer`, given the request's headers.
+    pub fn resolve(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        let mut client = peer;
+        if !self.contains(client) {
+            return client;
+        }
+        let hops: Vec<&str> = headers
+            .get_all(FORWARDED_FOR)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .collect();
+        for hop in hops.into_iter().rev() {
+            let Some(ip) = parse_hop(hop) else {
+                // Garbage from a trusted proxy; the last proxy is all we know.
+                break;
+            };
+            client = ip;
+            if !self.contains(ip) {
+                break;
+            }
+        }
+        client
+    }
+}
+
+/// A hop as a bare address or a socket address such as `[2001:db8::1]:443`.
+fn parse_hop(hop: &str) -> Option<IpAddr> {
+    hop.parse::<IpAddr>()
+        .ok()
+        .or_else(|| hop.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+}
+
+/// Middleware storing the request's `ClientIp` for the layers inside it.
+pub async fn resolve(
+    State(trusted): State<Arc<TrustedProxies>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let client = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| trusted.resolve(addr.ip(), request.headers()));
+    request.extensions_mut().insert(ClientIp(client));
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn headers(values: &[&str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for value in values {
+            headers.append(FORWARDED_FOR, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn forwarded_for_is_ignored_from_untrusted_peers() {
+        let trusted = TrustedProxies::parse(&["10.0.0.0/8".into()]);
+        let spoofed = headers(&["198.51.100.1"]);
+        assert_eq!(
+            trusted.resolve(ip("203.0.113.7"), &spoofed),
+            ip("203.0.113.7")
+        );
+        assert_eq!(
+            TrustedProxies::default().resolve(ip("10.0.0.2"), &spoofed),
+            ip("10.0.0.2")
+        );
+    }
+
+    #[test]
+    fn the_right_most_untrusted_hop_is_the_client() {
+        let trusted = TrustedProxies::parse(&["10.0.0.0/8".into(), " 192.0.2.9 ".into()]);
+        // The client prepended a made-up hop; the proxies appended theirs.
+        let forwarded = headers(&["198.51.100.1, 203.0.113.7", "192.0.2.9"]);
+        assert_eq!(
+            trusted.resolve(ip("10.0.0.2"), &forwarded),
+            ip("203.0.113.7")
+        );
+
+        assert_eq!(
+            trusted.resolve(ip("10.0.0.2"), &headers(&["10.1.1.1"])),
+            ip("10.1.1.1")
+        );
+        assert_eq!(
+            trusted.resolve(ip("10.0.0.2"), &headers(&["not-an-ip"])),
+            ip("10.0.0.2")
+        );
+        assert_eq!(
+            trusted.resolve(ip("10.0.0.2"), &headers(&["[2001:db8::1]:443"])),
+            ip("2001:db8::1")
+        );
+        assert_eq!(
+            trusted.resolve(ip("10.0.0.2"), &HeaderMap::new()),
+            ip("10.0.0.2")
+        );
+    }
+
+    #[test]
+    fn invalid_entries_are_skipped() {
+        let trusted = TrustedProxies::parse(&["nope".into(), "::ffff:10.0.0.0/104".into()]);
+        assert_eq!(trusted.0.len(), 1);
+    }
+}
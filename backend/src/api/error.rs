@@ -0,0 +1,261 @@
+//! The JSON error envelope every API route answers with.
+//!
+//! Failed requests get one body shape:
+//!
+//! ```json
+//! {
+//!   "error": "validation_failed",
+//!   "message": "reporting_period_end: must not be before the start",
+//!   "fields": { "reporting_period_end": ["must not be before the start"] },
+//!   "request_id": "0f6f6d3e-…"
+//! }
+//! ```
+//!
+//! `error` is a stable, machine-readable code clients branch on; `message`
+//! is for people and may change. `fields` maps payload field paths to their
+//! problems on validation failures, and `details` carries code-specific data
+//! such as blocking policy findings. `request_id` is added by
+//! `request_id::propagate` on the way out.
+
+use std::collections::BTreeMap;
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{infrastructure::auth::AuthError, services::errors::ServiceError};
+
+/// An error response with a stable code.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiError {
+    #[serde(skip)]
+    status: StatusCode,
+    #[serde(rename = "error")]
+    code: &'static str,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fields: Option<BTreeMap<String, Vec<String>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<Value>,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code,
+            message: message.into(),
+            fields: None,
+            details: None,
+        }
+    }
+
+    /// `422 validation_failed` listing the problems with each payload field.
+    pub fn validation(fields: BTreeMap<String, Vec<String>>) -> Self {
+        let message = fields
+            .iter()
+            .flat_map(|(field, problems)| {
+                problems
+                    .iter()
+                    .map(move |problem| format!("{field}: {problem}"))
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+        Self {
+            fields: Some(fields),
+            ..Self::new(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "validation_failed",
+                message,
+            )
+        }
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl From<ServiceError> for ApiError {
+    /// Reports internal errors, whose message stays in the logs rather than
+    /// the response.
+    fn from(err: ServiceError) -> Self {
+        err.report();
+        let status = err.status_code();
+        match err {
+            ServiceError::NotFound => Self::new(status, "not_found", "not found"),
+            ServiceError::Forbidden => Self::new(status, "forbidden", "forbidden"),
+            ServiceError::Validation(message) => Self::new(status, "validation_failed", message),
+            ServiceError::Conflict => Self::new(
+                status,
+                "conflict",
+                "the resource is not in a state that allows this",
+            ),
+            ServiceError::PreconditionFailed => {
+                Self::new(status, "precondition_failed", err.to_string())
+            }
+            ServiceError::ReportsRejected(ref rejected) => {
+                let details = serde_json::json!({ "rejected": rejected });
+                Self::new(status, "reports_rejected", err.to_string()).with_details(details)
+            }
+            ServiceError::PolicyBlocked(findings) => {
+                let messages: Vec<&str> = findings
+                    .iter()
+                    .map(|finding| finding.message.as_str())
+                    .collect();
+                let message = format!("report cannot be submitted: {}", messages.join("; "));
+                Self::new(status, "policy_blocked", message)
+                    .with_details(serde_json::json!({ "findings": findings }))
+            }
+            ServiceError::Internal(_) => {
+                Self::new(status, "internal_server_error", "internal server error")
+            }
+        }
+    }
+}
+
+impl From<AuthError> for ApiError {
+    fn from(err: AuthError) -> Self {
+        match err {
+            AuthError::Missing => Self::new(
+                StatusCode::UNAUTHORIZED,
+                "unauthenticated",
+                "missing authorization header",
+            ),
+            AuthError::Invalid => Self::new(
+                StatusCode::UNAUTHORIZED,
+                "unauthenticated",
+                "invalid authorization token",
+            ),
+            AuthError::MissingState => {
+                ServiceError::Internal("application state unavailable".into()).into()
+            }
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(self)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::policy::{FindingSeverity, PolicyFinding};
+
+    fn render(err: impl Into<ApiError>) -> (StatusCode, Value) {
+        let err = err.into();
+        (err.status(), serde_json::to_value(&err).unwrap())
+    }
+
+    #[test]
+    fn maps_conflict_errors_to_http_409() {
+        let (status, body) = render(ServiceError::Conflict);
+
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["error"], "conflict");
+    }
+
+    #[test]
+    fn maps_not_found_errors_to_http_404() {
+        let (status, body) = render(ServiceError::NotFound);
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(
+            body,
+            serde_json::json!({ "error": "not_found", "message": "not found" })
+        );
+    }
+
+    #[test]
+    fn maps_internal_errors_to_generic_http_500() {
+        let (status, body) = render(ServiceError::Internal("db offline".into()));
+
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "error": "internal_server_error",
+                "message": "internal server error",
+            })
+        );
+    }
+
+    #[test]
+    fn maps_validation_errors_to_http_422() {
+        let (status, body) = render(ServiceError::Validation("totals mismatch".into()));
+
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "error": "validation_failed",
+                "message": "totals mismatch",
+            })
+        );
+
+        let mut fields = BTreeMap::new();
+        fields.insert("currency".to_string(), vec!["must be ISO 4217".to_string()]);
+        fields.insert(
+            "items[0].amount_cents".to_string(),
+            vec!["must be positive".to_string()],
+        );
+        let (status, body) = render(ApiError::validation(fields));
+
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            body["message"],
+            "currency: must be ISO 4217; items[0].amount_cents: must be positive"
+        );
+        assert_eq!(body["fields"]["currency"][0], "must be ISO 4217");
+    }
+
+    #[test]
+    fn maps_policy_blocks_to_http_422_with_findings() {
+        let finding = PolicyFinding {
+            severity: FindingSeverity::Blocking,
+            code: "receipt_required".into(),
+            message: "Receipt required for $40.00".into(),
+            item_id: None,
+            cap: None,
+        };
+
+        let (status, body) = render(ServiceError::PolicyBlocked(vec![finding]));
+
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["error"], "policy_blocked");
+        assert_eq!(
+            body["message"],
+            "report cannot be submitted: Receipt required for $40.00"
+        );
+        assert_eq!(body["details"]["findings"][0]["code"], "receipt_required");
+        assert_eq!(body["details"]["findings"][0]["severity"], "blocking");
+    }
+
+    #[test]
+    fn maps_auth_errors_to_http_401() {
+        let (status, body) = render(AuthError::Invalid);
+
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"], "unauthenticated");
+    }
+}
@@ -0,0 +1,254 @@
+//! `Idempotency-Key` support for mutating requests.
+//!
+//! A `POST`, `PUT`, `PATCH`, or `DELETE` carrying the header is keyed by the
+//! header value and the authenticated employee. The first request runs and
+//! its response is stored; a retry of the same request gets the stored
+//! response back with `Idempotent-Replayed: true` instead of running again.
+//! Reusing a key for a different request is refused with `422`, and a retry
+//! arriving while the first request still runs gets `409`. `5xx` and `408`
+//! responses are not stored, so the retry runs again, and neither is a
+//! request the client abandoned: its key is released when the request is
+//! dropped, or, should that not happen, once its lease runs out.
+
+use std::{sync::Arc, time::Duration};
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::Request,
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    api::{error::ApiError, rest::receipts::upload_body_limit},
+    infrastructure::{auth, state::AppState},
+    services::{
+        errors::ServiceError,
+        idempotency::{self, Claim, StoredResponse},
+    },
+};
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+pub const REPLAYED_HEADER: &str = "idempotent-replayed";
+
+const MAX_KEY_LENGTH: usize = 255;
+
+/// Middleware replaying stored responses for retried keyed requests. Requests
+/// without the header, safe methods, and unauthenticated requests pass
+/// through untouched.
+pub async fn replay(request: Request, next: Next) -> Response {
+    if !is_mutating(request.method()) {
+        return next.run(request).await;
+    }
+    let Some(key) = request.headers().get(IDEMPOTENCY_KEY_HEADER).cloned() else {
+        return next.run(request).await;
+    };
+    let Some(state) = request.extensions().get::<Arc<AppState>>().cloned() else {
+        return next.run(request).await;
+    };
+    let key = match key.to_str().map(str::trim) {
+        Ok(key) if !key.is_empty() && key.len() <= MAX_KEY_LENGTH => key.to_string(),
+        _ => {
+            return error_response(ServiceError::Validation(format!(
+                "Idempotency-Key must be 1 to {MAX_KEY_LENGTH} visible ASCII characters"
+            )))
+        }
+    };
+
+    let (parts, body) = request.into_parts();
+    // The handler's own extractor answers requests that fail to authenticate.
+    let Ok(user) = auth::authenticate(&parts).await else {
+        return next.run(Request::from_parts(parts, body)).await;
+    };
+    // The largest of the router's body limits; the route's own limit still
+    // applies when the buffered body is handed on.
+    let config = &state.config;
+    let limit = config
+        .app
+        .max_body_bytes
+        .max(config.receipts.max_bytes)
+        .max(upload_body_limit(&config.receipts));
+    // Read before the route's timeout starts, so bounded by the longer one.
+    let timeout = state
+        .config
+        .app
+        .request_timeout_seconds
+        .max(state.config.app.upload_timeout_seconds);
+    let read = to_bytes(body, usize::try_from(limit).unwrap_or(usize::MAX));
+    let body = match tokio::time::timeout(Duration::from_secs(timeout), read).await {
+        Ok(Ok(body)) => body,
+        Ok(Err(_)) => {
+            return ApiError::new(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "payload_too_large",
+                "request body is too large",
+            )
+            .into_response()
+        }
+        Err(_) => {
+            return ApiError::new(
+                StatusCode::REQUEST_TIMEOUT,
+                "request_timeout",
+                "the request body was not received in time",
+            )
+            .into_response()
+        }
+    };
+    let request_hash = request_hash(parts.method.as_str(), &parts.uri.to_string(), body.as_ref());
+
+    // Outlasts the route's timeout and storing the response.
+    let lease = Duration::from_secs(timeout.saturating_mul(2));
+    match idempotency::claim(&state.pool, user.employee_id, &key, &request_hash, lease).await {
+        Ok(Claim::Started) => {}
+        Ok(Claim::InProgress) => {
+            return ApiError::new(
+                StatusCode::CONFLICT,
+                "idempotency_key_in_use",
+                "a request with this Idempotency-Key is still in progress",
+            )
+            .into_response()
+        }
+        Ok(Claim::Mismatch) => {
+            return error_response(ServiceError::Validation(
+                "Idempotency-Key was already used for a different request".into(),
+            ))
+        }
+        Ok(Claim::Completed(stored)) => return replayed(stored),
+        Err(err) => return error_response(err),
+    }
+
+    let claimed = ClaimedKey {
+        state,
+        employee_id: user.employee_id,
+        key: Some(key),
+    };
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    store(claimed, response).await
+}
+
+/// A claimed key not yet answered. Dropping it, as happens when the client
+/// disconnects and the request future is dropped, releases the key.
+struct ClaimedKey {
+    state: Arc<AppState>,
+    employee_id: Uuid,
+    key: Option<String>,
+}
+
+impl ClaimedKey {
+    async fn release(mut self) {
+        if let Some(key) = self.key.take() {
+            release(&self.state, self.employee_id, &key).await;
+        }
+    }
+}
+
+impl Drop for ClaimedKey {
+    fn drop(&mut self) {
+        let Some(key) = self.key.take() else {
+            return;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let state = Arc::clone(&self.state);
+        let employee_id = self.employee_id;
+        runtime.spawn(async move { release(&state, employee_id, &key).await });
+    }
+}
+
+async fn release(state: &AppState, employee_id: Uuid, key: &str) {
+    if let Err(err) = idempotency::release(&state.pool, employee_id, key).await {
+        warn!(error = %err, "failed to release idempotency key");
+    }
+}
+
+/// Stores `response` under the key, or frees the key when the response is a
+/// server error or a timeout, and hands the response on.
+async fn store(mut claimed: ClaimedKey, response: Response) -> Response {
+    if response.status().is_server_error() || response.status() == StatusCode::REQUEST_TIMEOUT {
+        claimed.release().await;
+        return response;
+    }
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(err) => {
+            warn!(error = %err, "failed to buffer response for idempotency key");
+            claimed.release().await;
+            return error_response(ServiceError::Internal(err.to_string()));
+        }
+    };
+    let stored = StoredResponse {
+        status: parts.status.as_u16(),
+        content_type: parts
+            .headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string),
+        body: body.to_vec(),
+    };
+    if let Some(key) = &claimed.key {
+        let pool = &claimed.state.pool;
+        if let Err(err) = idempotency::complete(pool, claimed.employee_id, key, &stored).await {
+            warn!(error = %err, "failed to store idempotent response");
+        }
+    }
+    claimed.key = None;
+    Response::from_parts(parts, Body::from(body))
+}
+
+fn replayed(stored: StoredResponse) -> Response {
+    let mut response = Response::new(Body::from(stored.body));
+    *response.status_mut() = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+    let headers = response.headers_mut();
+    if let Some(content_type) = stored
+        .content_type
+        .and_then(|value| HeaderValue::from_str(&value).ok())
+    {
+        headers.insert(header::CONTENT_TYPE, content_type);
+    }
+    headers.insert(REPLAYED_HEADER, HeaderValue::from_static("true"));
+    response
+}
+
+fn is_mutating(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    )
+}
+
+/// Hex SHA-256 of the method, path and query, and body.
+fn request_hash(method: &str, uri: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(uri.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(body);
+    format!("{:x}", hasher.finalize())
+}
+
+fn error_response(err: ServiceError) -> Response {
+    ApiError::from(err).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_hash_covers_method_target_and_body() {
+        let hash = request_hash("POST", "/api/expenses/reports", b"{}");
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, request_hash("POST", "/api/expenses/reports", b"{}"));
+        assert_ne!(hash, request_hash("PUT", "/api/expenses/reports", b"{}"));
+        assert_ne!(hash, request_hash("POST", "/api/finance/finalize", b"{}"));
+        assert_ne!(hash, request_hash("POST", "/api/expenses/reports", b"{ }"));
+        assert!(!is_mutating(&Method::GET));
+    }
+}
@@ -0,0 +1,278 @@
+//! Token-bucket rate limiting.
+//!
+//! Each request draws a token from a bucket picked by its route group and
+//! its subject: the authenticated employee, or the client IP for anonymous
+//! requests and for login, where the employee is not known yet. A bucket
+//! holds up to a minute of the group's quota and refills continuously, so a
+//! client may burst up to the quota and then settles at the per-minute rate.
+//! Responses carry `RateLimit-Limit`, `RateLimit-Remaining`, and
+//! `RateLimit-Reset`; a request finding its bucket empty is refused with
+//! `429` and `Retry-After` without reaching the handler.
+
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use parking_lot::Mutex;
+use uuid::Uuid;
+
+use crate::{
+    api::error::ApiError,
+    domain::models::Role,
+    infrastructure::{auth, config::RateLimitConfig},
+};
+
+pub const LIMIT_HEADER: &str = "ratelimit-limit";
+pub const REMAINING_HEADER: &str = "ratelimit-remaining";
+pub const RESET_HEADER: &str = "ratelimit-reset";
+
+/// Routes outside any quota: probes and the metrics scrape.
+const EXEMPT_ROUTES: &[&str] = &["/healthz/live", "/healthz/ready", "/metrics", "/api/health"];
+const LOGIN_ROUTES: &[&str] = &["/auth/login", "/api/auth/login"];
+const UPLOAD_ROUTES: &[&str] = &["/api/receipts"];
+/// How often idle buckets are dropped; a full bucket is the same as none.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Route groups with separate quotas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Group {
+    Default,
+    Login,
+    Uploads,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Subject {
+    Employee(Uuid),
+    Ip(String),
+    Unknown,
+}
+
+/// Shared bucket state for the router's rate limiting layer.
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    state: Mutex<Buckets>,
+}
+
+struct Buckets {
+    buckets: HashMap<(Group, Subject), Bucket>,
+    last_sweep: Instant,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    capacity: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn refilled(&self, now: Instant) -> f64 {
+        let per_second = self.capacity / 60.0;
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        (self.tokens + elapsed * per_second).min(self.capacity)
+    }
+}
+
+/// Outcome of drawing from a bucket.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Decision {
+    allowed: bool,
+    limit: u32,
+    remaining: u32,
+    /// Seconds until the bucket is full again.
+    reset: u64,
+    /// Seconds until the next token, when refused.
+    retry_after: u64,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(Buckets {
+                buckets: HashMap::new(),
+                last_sweep: Instant::now(),
+            }),
+        }
+    }
+
+    fn quota(&self, group: Group, role: Option<Role>) -> u32 {
+        match group {
+            Group::Login => self.config.login_per_minute,
+            Group::Uploads => self.config.uploads_per_minute,
+            Group::Default => role
+                .and_then(|role| self.config.role_requests_per_minute.get(role.as_str()))
+                .copied()
+                .unwrap_or(self.config.requests_per_minute),
+        }
+    }
+
+    fn check(&self, group: Group, subject: Subject, quota: u32, now: Instant) -> Decision {
+        let capacity = f64::from(quota.max(1));
+        let per_second = capacity / 60.0;
+        let mut state = self.state.lock();
+        if now.duration_since(state.last_sweep) >= SWEEP_INTERVAL {
+            state.last_sweep = now;
+            state
+                .buckets
+                .retain(|_, bucket| bucket.refilled(now) < bucket.capacity);
+        }
+        let bucket = state.buckets.entry((group, subject)).or_insert(Bucket {
+            tokens: capacity,
+            capacity,
+            updated: now,
+        });
+        // A changed quota (a new role, a new config) applies from now on.
+        bucket.capacity = capacity;
+        bucket.tokens = bucket.refilled(now);
+        bucket.updated = now;
+        let allowed = bucket.tokens >= 1.0;
+        if allowed {
+            bucket.tokens -= 1.0;
+        }
+        Decision {
+            allowed,
+            limit: quota.max(1),
+            remaining: bucket.tokens.floor() as u32,
+            reset: ((capacity - bucket.tokens) / per_second).ceil() as u64,
+            retry_after: ((1.0 - bucket.tokens).max(0.0) / per_second).ceil() as u64,
+        }
+    }
+}
+
+/// Middleware enforcing the quotas. Preflight requests and exempt routes pass
+/// through untouched.
+pub async fn enforce(
+    State(limiter): State<Arc<RateLimiter>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string());
+    let Some(group) = classify(request.method(), route.as_deref()) else {
+        return next.run(request).await;
+    };
+
+    let (parts, body) = request.into_parts();
+    // Unauthenticated requests are limited by IP; the handler refuses them.
+    let user = match group {
+        Group::Login => None,
+        _ => auth::authenticate(&parts).await.ok(),
+    };
+    let request = Request::from_parts(parts, body);
+    let quota = limiter.quota(group, user.as_ref().map(|user| user.role));
+    let subject = match (user, super::client_ip(&request)) {
+        (Some(user), _) => Subject::Employee(user.employee_id),
+        (None, Some(ip)) => Subject::Ip(ip),
+        (None, None) => Subject::Unknown,
+    };
+
+    let decision = limiter.check(group, subject, quota, Instant::now());
+    let mut response = if decision.allowed {
+        next.run(request).await
+    } else {
+        let mut response = ApiError::new(
+            StatusCode::TOO_MANY_REQUESTS,
+            "rate_limited",
+            "too many requests; retry later",
+        )
+        .into_response();
+        response.headers_mut().insert(
+            axum::http::header::RETRY_AFTER,
+            HeaderValue::from(decision.retry_after.max(1)),
+        );
+        response
+    };
+    set_headers(response.headers_mut(), &decision);
+    response
+}
+
+fn classify(method: &Method, route: Option<&str>) -> Option<Group> {
+    if method == Method::OPTIONS {
+        return None;
+    }
+    match route {
+        Some(route) if EXEMPT_ROUTES.contains(&route) => None,
+        Some(route) if LOGIN_ROUTES.contains(&route) => Some(Group::Login),
+        Some(route) if method == Method::POST && UPLOAD_ROUTES.contains(&route) => {
+            Some(Group::Uploads)
+        }
+        _ => Some(Group::Default),
+    }
+}
+
+fn set_headers(headers: &mut HeaderMap, decision: &Decision) {
+    headers.insert(LIMIT_HEADER, HeaderValue::from(decision.limit));
+    headers.insert(REMAINING_HEADER, HeaderValue::from(decision.remaining));
+    headers.insert(RESET_HEADER, HeaderValue::from(decision.reset));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buckets_allow_a_burst_then_refill_at_the_quota_rate() {
+        let limiter = RateLimiter::new(RateLimitConfig::default());
+        let start = Instant::now();
+        let subject = || Subject::Ip("203.0.113.7".into());
+        for remaining in (0..3).rev() {
+            let decision = limiter.check(Group::Login, subject(), 3, start);
+            assert!(decision.allowed);
+            assert_eq!(decision.remaining, remaining);
+        }
+        let refused = limiter.check(Group::Login, subject(), 3, start);
+        assert!(!refused.allowed);
+        assert_eq!(refused.retry_after, 20);
+        assert_eq!(refused.reset, 60);
+
+        // Other subjects and groups have their own buckets.
+        assert!(limiter.check(Group::Default, subject(), 3, start).allowed);
+        let other = Subject::Employee(Uuid::new_v4());
+        assert!(limiter.check(Group::Login, other, 3, start).allowed);
+
+        let later = start + Duration::from_secs(20);
+        assert!(limiter.check(Group::Login, subject(), 3, later).allowed);
+        assert!(!limiter.check(Group::Login, subject(), 3, later).allowed);
+    }
+
+    #[test]
+    fn routes_are_grouped_and_roles_override_the_default_quota() {
+        assert_eq!(classify(&Method::GET, Some("/metrics")), None);
+        assert_eq!(classify(&Method::OPTIONS, Some("/api/auth/login")), None);
+        assert_eq!(
+            classify(&Method::POST, Some("/auth/login")),
+            Some(Group::Login)
+        );
+        assert_eq!(
+            classify(&Method::POST, Some("/api/receipts")),
+            Some(Group::Uploads)
+        );
+        assert_eq!(
+            classify(&Method::GET, Some("/api/manager/queue")),
+            Some(Group::Default)
+        );
+        assert_eq!(classify(&Method::GET, None), Some(Group::Default));
+
+        let mut config = RateLimitConfig::default();
+        config
+            .role_requests_per_minute
+            .insert("finance".into(), 900);
+        let limiter = RateLimiter::new(config);
+        assert_eq!(limiter.quota(Group::Default, Some(Role::Finance)), 900);
+        assert_eq!(limiter.quota(Group::Default, Some(Role::Employee)), 300);
+        assert_eq!(limiter.quota(Group::Default, None), 300);
+        assert_eq!(limiter.quota(Group::Login, Some(Role::Finance)), 10);
+    }
+}
@@ -0,0 +1,74 @@
+//! HTTP caching for reference data lists.
+//!
+//! Responses carry an `ETag` and `Cache-Control: private, max-age=…`, so the
+//! SPA reuses its copy for `EXPENSES__APP__REFERENCE_MAX_AGE_SECONDS` and
+//! then revalidates with `If-None-Match`, getting `304` while the list is
+//! unchanged. Bodies are served from the in-process `ReferenceCache`.
+
+use std::future::Future;
+
+use axum::{
+    http::{
+        header::{CACHE_CONTROL, CONTENT_TYPE, ETAG},
+        HeaderMap, HeaderValue, StatusCode,
+    },
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+use crate::{
+    api::error::ApiError,
+    infrastructure::{
+        auth::AuthenticatedUser,
+        reference_cache::{CachedBody, ReferenceData},
+        state::AppState,
+    },
+    services::{errors::ServiceError, preconditions},
+};
+
+/// Answers with the cached `key` list, running `load` to fill the cache on a
+/// miss.
+pub async fn respond<T, F>(
+    state: &AppState,
+    user: &AuthenticatedUser,
+    headers: &HeaderMap,
+    key: ReferenceData,
+    load: F,
+) -> Result<Response, ApiError>
+where
+    T: Serialize,
+    F: Future<Output = Result<T, ServiceError>>,
+{
+    if !key.visible_to(user.role) {
+        return Err(ServiceError::Forbidden.into());
+    }
+    let cached = match state.reference_cache.get(key) {
+        Some(cached) => cached,
+        None => {
+            let generation = state.reference_cache.generation();
+            let body = serde_json::to_vec(&load.await?)
+                .map_err(|err| ServiceError::Internal(err.to_string()))?;
+            let cached = CachedBody::new(body);
+            state.reference_cache.store(key, generation, cached.clone());
+            cached
+        }
+    };
+
+    let cache_control = format!(
+        "private, max-age={}",
+        state.config.app.reference_max_age_seconds
+    );
+    let mut response = if preconditions::not_modified(headers, &cached.etag) {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        ([(CONTENT_TYPE, "application/json")], cached.body).into_response()
+    };
+    let response_headers = response.headers_mut();
+    if let Ok(etag) = HeaderValue::from_str(&cached.etag) {
+        response_headers.insert(ETAG, etag);
+    }
+    if let Ok(cache_control) = HeaderValue::from_str(&cache_control) {
+        response_headers.insert(CACHE_CONTROL, cache_control);
+    }
+    Ok(response)
+}
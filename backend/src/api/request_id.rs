@@ -0,0 +1,174 @@
+//! `X-Request-Id` handling for every API request.
+//!
+//! A caller-supplied ID is kept when it is short and printable, otherwise a
+//! UUID is generated. The ID is stored as a [`RequestId`] extension for
+//! handlers and the request's tracing span, echoed in the response header,
+//! and added as `request_id` to JSON error bodies so a user's report can be
+//! matched to the logs.
+
+use axum::{
+    async_trait,
+    body::{Body, HttpBody as _},
+    extract::{FromRequestParts, Request},
+    http::{header, request::Parts, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use serde_json::Value;
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+const MAX_LENGTH: usize = 128;
+/// Error bodies are small; anything larger is passed through untouched.
+const MAX_ERROR_BODY_BYTES: u64 = 64 * 1024;
+
+/// The ID of the request being handled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for RequestId {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .extensions
+            .get::<RequestId>()
+            .cloned()
+            .unwrap_or_else(|| RequestId(Uuid::new_v4().to_string())))
+    }
+}
+
+/// Middleware assigning the request ID. Must wrap the tracing layer so the
+/// span can record it.
+pub async fn propagate(mut request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|id| is_valid(id))
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    request.extensions_mut().insert(RequestId(id.clone()));
+
+    let response = next.run(request).await;
+    let mut response = if response.status().is_client_error() || response.status().is_server_error()
+    {
+        with_id_in_body(response, &id).await
+    } else {
+        response
+    };
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+fn is_valid(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_LENGTH
+        && id
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || b"-_.:".contains(&byte))
+}
+
+/// Adds `request_id` to a JSON object body.
+async fn with_id_in_body(response: Response, id: &str) -> Response {
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    let small = response
+        .body()
+        .size_hint()
+        .exact()
+        .is_some_and(|size| size <= MAX_ERROR_BODY_BYTES);
+    if !is_json || !small {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = axum::body::to_bytes(body, MAX_ERROR_BODY_BYTES as usize).await else {
+        return Response::from_parts(parts, Body::empty());
+    };
+    let body = match serde_json::from_slice::<Value>(&bytes) {
+        Ok(Value::Object(mut object)) => {
+            object.insert("request_id".into(), Value::String(id.to_string()));
+            let body = Value::Object(object).to_string();
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Body::from(body)
+        }
+        _ => Body::from(bytes),
+    };
+    Response::from_parts(parts, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, middleware, routing::get, Json, Router};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route(
+                "/ok",
+                get(|id: RequestId| async move { Json(serde_json::json!({ "id": id.0 })) }),
+            )
+            .route(
+                "/fail",
+                get(|| async {
+                    (
+                        StatusCode::UNPROCESSABLE_ENTITY,
+                        Json(serde_json::json!({ "error": "bad input" })),
+                    )
+                }),
+            )
+            .layer(middleware::from_fn(propagate))
+    }
+
+    async fn call(path: &str, id: Option<&str>) -> (Response, Value) {
+        let mut request = Request::builder().uri(path);
+        if let Some(id) = id {
+            request = request.header(REQUEST_ID_HEADER, id);
+        }
+        let response = app()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let (parts, body) = response.into_parts();
+        let bytes = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+        (
+            Response::from_parts(parts, Body::empty()),
+            serde_json::from_slice(&bytes).unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn keeps_a_valid_caller_id_and_echoes_it() {
+        let (response, body) = call("/ok", Some("support-1234")).await;
+
+        assert_eq!(response.headers()[REQUEST_ID_HEADER], "support-1234");
+        assert_eq!(body["id"], "support-1234");
+        assert!(body.get("request_id").is_none());
+    }
+
+    #[tokio::test]
+    async fn replaces_invalid_ids_and_adds_them_to_error_bodies() {
+        let (response, body) = call("/fail", Some("no spaces <allowed>")).await;
+
+        let id = response.headers()[REQUEST_ID_HEADER].to_str().unwrap();
+        assert!(Uuid::parse_str(id).is_ok());
+        assert_eq!(body["request_id"], id);
+        assert_eq!(body["error"], "bad input");
+    }
+}
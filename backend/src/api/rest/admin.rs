@@ -0,0 +1,404 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Extension, Path, Query},
+    http::StatusCode,
+    routing::{get, post, put},
+    Json, Router,
+};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    api::error::ApiError,
+    domain::models::{CostCenter, Department, Employee, ExpenseReport, Organization},
+    infrastructure::{auth::AuthenticatedUser, state::AppState},
+    services::{
+        archive::ArchiveService,
+        audit::{AuditService, ChainVerification, VerifyQuery},
+        departments::{CostCenterRequest, DepartmentRequest, DepartmentService},
+        expenses::ExpenseService,
+        job_runs::{JobRun, JobRunService, JobStatus},
+        organizations::{
+            CreateOrganizationRequest, EmployeeRequest, OrganizationService,
+            RenameOrganizationRequest,
+        },
+        roster::{RosterImport, RosterService},
+        terminations::{Termination, TerminationService},
+        webhooks::{
+            CreateWebhookRequest, CreatedWebhookSubscription, DeliveryQuery, UpdateWebhookRequest,
+            WebhookDelivery, WebhookService, WebhookSubscription,
+        },
+    },
+};
+
+#[derive(Serialize)]
+struct JobListResponse {
+    jobs: Vec<JobStatus>,
+}
+
+#[derive(Serialize)]
+struct WebhookListResponse {
+    webhooks: Vec<WebhookSubscription>,
+}
+
+#[derive(Serialize)]
+struct DeliveryListResponse {
+    deliveries: Vec<WebhookDelivery>,
+}
+
+#[derive(Serialize)]
+struct OrganizationListResponse {
+    organizations: Vec<Organization>,
+}
+
+#[derive(Serialize)]
+struct EmployeeListResponse {
+    employees: Vec<Employee>,
+}
+
+#[derive(Serialize)]
+struct DepartmentListResponse {
+    departments: Vec<Department>,
+}
+
+#[derive(Serialize)]
+struct CostCenterListResponse {
+    cost_centers: Vec<CostCenter>,
+}
+
+#[derive(Serialize)]
+struct ReportResponse {
+    report: ExpenseReport,
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/jobs", get(list_jobs))
+        .route("/jobs/:name/run", post(run_job))
+        .route("/webhooks", get(list_webhooks).post(create_webhook))
+        .route("/webhooks/:id", put(update_webhook).delete(delete_webhook))
+        .route("/webhooks/:id/deliveries", get(list_deliveries))
+        .route("/reports/:id/restore", post(restore_report))
+        .route("/reports/:id/rehydrate", post(rehydrate_report))
+        .route("/items/:id/restore", post(restore_item))
+        .route("/receipts/:id/restore", post(restore_receipt))
+        .route(
+            "/organization",
+            get(current_organization).put(rename_organization),
+        )
+        .route(
+            "/organizations",
+            get(list_organizations).post(create_organization),
+        )
+        .route("/employees", get(list_employees).post(create_employee))
+        .route("/employees/import", post(import_employees))
+        .route("/employees/:id", put(update_employee))
+        .route("/employees/:id/deactivate", post(deactivate_employee))
+        .route(
+            "/departments",
+            get(list_departments).post(create_department),
+        )
+        .route(
+            "/departments/:id",
+            put(update_department).delete(delete_department),
+        )
+        .route(
+            "/cost-centers",
+            get(list_cost_centers).post(create_cost_center),
+        )
+        .route(
+            "/cost-centers/:id",
+            put(update_cost_center).delete(delete_cost_center),
+        )
+        .route("/audit/verify", get(verify_audit_log))
+}
+
+async fn list_jobs(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> Result<Json<JobListResponse>, ApiError> {
+    let service = JobRunService::new(state);
+    let jobs = service.list(&user).await?;
+    Ok(Json(JobListResponse { jobs }))
+}
+
+async fn run_job(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(name): Path<String>,
+) -> Result<(StatusCode, Json<JobRun>), ApiError> {
+    let service = JobRunService::new(state);
+    let run = service.run_now(&user, &name).await?;
+    Ok((StatusCode::ACCEPTED, Json(run)))
+}
+
+async fn list_webhooks(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> Result<Json<WebhookListResponse>, ApiError> {
+    let service = WebhookService::new(state);
+    let webhooks = service.list(&user).await?;
+    Ok(Json(WebhookListResponse { webhooks }))
+}
+
+async fn create_webhook(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(payload): Json<CreateWebhookRequest>,
+) -> Result<(StatusCode, Json<CreatedWebhookSubscription>), ApiError> {
+    let service = WebhookService::new(state);
+    let webhook = service.create(&user, payload).await?;
+    Ok((StatusCode::CREATED, Json(webhook)))
+}
+
+async fn update_webhook(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateWebhookRequest>,
+) -> Result<Json<WebhookSubscription>, ApiError> {
+    let service = WebhookService::new(state);
+    let webhook = service.update(&user, id, payload).await?;
+    Ok(Json(webhook))
+}
+
+async fn delete_webhook(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let service = WebhookService::new(state);
+    service.delete(&user, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_deliveries(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Query(query): Query<DeliveryQuery>,
+) -> Result<Json<DeliveryListResponse>, ApiError> {
+    let service = WebhookService::new(state);
+    let deliveries = service.deliveries(&user, id, query).await?;
+    Ok(Json(DeliveryListResponse { deliveries }))
+}
+
+async fn restore_report(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let service = ExpenseService::new(state);
+    service.restore_report(&user, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn rehydrate_report(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ReportResponse>, ApiError> {
+    let service = ArchiveService::new(state);
+    let report = service.rehydrate(&user, id).await?;
+    Ok(Json(ReportResponse { report }))
+}
+
+async fn restore_item(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let service = ExpenseService::new(state);
+    service.restore_item(&user, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn restore_receipt(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let service = ExpenseService::new(state);
+    service.restore_receipt(&user, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn current_organization(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> Result<Json<Organization>, ApiError> {
+    let service = OrganizationService::new(state);
+    let organization = service.current(&user).await?;
+    Ok(Json(organization))
+}
+
+async fn rename_organization(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(payload): Json<RenameOrganizationRequest>,
+) -> Result<Json<Organization>, ApiError> {
+    let service = OrganizationService::new(state);
+    let organization = service.rename(&user, payload).await?;
+    Ok(Json(organization))
+}
+
+async fn list_organizations(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> Result<Json<OrganizationListResponse>, ApiError> {
+    let service = OrganizationService::new(state);
+    let organizations = service.list(&user).await?;
+    Ok(Json(OrganizationListResponse { organizations }))
+}
+
+async fn create_organization(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(payload): Json<CreateOrganizationRequest>,
+) -> Result<(StatusCode, Json<Organization>), ApiError> {
+    let service = OrganizationService::new(state);
+    let organization = service.create(&user, payload).await?;
+    Ok((StatusCode::CREATED, Json(organization)))
+}
+
+async fn list_employees(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> Result<Json<EmployeeListResponse>, ApiError> {
+    let service = OrganizationService::new(state);
+    let employees = service.employees(&user).await?;
+    Ok(Json(EmployeeListResponse { employees }))
+}
+
+async fn create_employee(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(payload): Json<EmployeeRequest>,
+) -> Result<(StatusCode, Json<Employee>), ApiError> {
+    let service = OrganizationService::new(state);
+    let employee = service.create_employee(&user, payload).await?;
+    Ok((StatusCode::CREATED, Json(employee)))
+}
+
+async fn update_employee(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<EmployeeRequest>,
+) -> Result<Json<Employee>, ApiError> {
+    let service = OrganizationService::new(state);
+    let employee = service.update_employee(&user, id, payload).await?;
+    Ok(Json(employee))
+}
+
+async fn deactivate_employee(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Termination>, ApiError> {
+    let service = TerminationService::new(state);
+    let termination = service.deactivate(&user, id).await?;
+    Ok(Json(termination))
+}
+
+async fn list_departments(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> Result<Json<DepartmentListResponse>, ApiError> {
+    let service = DepartmentService::new(state);
+    let departments = service.departments(&user).await?;
+    Ok(Json(DepartmentListResponse { departments }))
+}
+
+async fn create_department(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(payload): Json<DepartmentRequest>,
+) -> Result<(StatusCode, Json<Department>), ApiError> {
+    let service = DepartmentService::new(state);
+    let department = service.create_department(&user, payload).await?;
+    Ok((StatusCode::CREATED, Json(department)))
+}
+
+async fn update_department(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<DepartmentRequest>,
+) -> Result<Json<Department>, ApiError> {
+    let service = DepartmentService::new(state);
+    let department = service.update_department(&user, id, payload).await?;
+    Ok(Json(department))
+}
+
+async fn delete_department(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let service = DepartmentService::new(state);
+    service.delete_department(&user, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_cost_centers(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> Result<Json<CostCenterListResponse>, ApiError> {
+    let service = DepartmentService::new(state);
+    let cost_centers = service.cost_centers(&user).await?;
+    Ok(Json(CostCenterListResponse { cost_centers }))
+}
+
+async fn create_cost_center(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(payload): Json<CostCenterRequest>,
+) -> Result<(StatusCode, Json<CostCenter>), ApiError> {
+    let service = DepartmentService::new(state);
+    let cost_center = service.create_cost_center(&user, payload).await?;
+    Ok((StatusCode::CREATED, Json(cost_center)))
+}
+
+async fn update_cost_center(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<CostCenterRequest>,
+) -> Result<Json<CostCenter>, ApiError> {
+    let service = DepartmentService::new(state);
+    let cost_center = service.update_cost_center(&user, id, payload).await?;
+    Ok(Json(cost_center))
+}
+
+async fn delete_cost_center(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let service = DepartmentService::new(state);
+    service.delete_cost_center(&user, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Accepts the HR roster CSV as the request body.
+async fn import_employees(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    body: String,
+) -> Result<Json<RosterImport>, ApiError> {
+    let service = RosterService::new(state);
+    let report = service.import(&user, &body).await?;
+    Ok(Json(report))
+}
+
+async fn verify_audit_log(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Query(query): Query<VerifyQuery>,
+) -> Result<Json<ChainVerification>, ApiError> {
+    let service = AuditService::new(state);
+    let verification = service.verify(&user, &query).await?;
+    Ok(Json(verification))
+}
@@ -8,35 +8,61 @@ use axum::{
 use uuid::Uuid;
 
 use crate::{
+    api::{
+        error::ApiError,
+        validation::{Valid, Validate, Validator},
+    },
     infrastructure::auth::AuthenticatedUser,
+    infrastructure::config::Config,
     infrastructure::state::AppState,
-    services::{
-        approvals::{ApprovalService, DecisionRequest},
-        errors::ServiceError,
-    },
+    services::approvals::{ActionLinkRequest, ApprovalService, DecisionRequest},
 };
 
+/// Longest comment a decision may carry.
+const MAX_COMMENT_CHARS: usize = 2000;
+
 pub fn router() -> Router {
-    Router::new().route("/:id", post(decide))
+    Router::new()
+        .route("/actions", post(decide_from_link))
+        .route("/:id", post(decide))
+}
+
+impl Validate for DecisionRequest {
+    fn validate(&self, _config: &Config, v: &mut Validator) {
+        v.max_chars("comments", self.comments.as_deref(), MAX_COMMENT_CHARS);
+        v.max_chars(
+            "policy_exception_notes",
+            self.policy_exception_notes.as_deref(),
+            MAX_COMMENT_CHARS,
+        );
+    }
+}
+
+impl Validate for ActionLinkRequest {
+    fn validate(&self, _config: &Config, v: &mut Validator) {
+        v.required("token", &self.token);
+        v.max_chars("comments", self.comments.as_deref(), MAX_COMMENT_CHARS);
+    }
 }
 
 async fn decide(
     Extension(state): Extension<Arc<AppState>>,
     user: AuthenticatedUser,
     Path(id): Path<Uuid>,
-    Json(payload): Json<DecisionRequest>,
-) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    Valid(payload): Valid<DecisionRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
     let service = ApprovalService::new(state);
-    let approval = service
-        .record_decision(&user, id, payload)
-        .await
-        .map_err(to_response)?;
+    let approval = service.record_decision(&user, id, payload).await?;
     Ok(Json(serde_json::json!({ "approval": approval })))
 }
 
-fn to_response(err: ServiceError) -> (axum::http::StatusCode, Json<serde_json::Value>) {
-    (
-        err.status_code(),
-        Json(serde_json::json!({ "error": err.to_string() })),
-    )
+/// Redeems a signed approval link. The token itself authenticates the
+/// approver, so no bearer header is required.
+async fn decide_from_link(
+    Extension(state): Extension<Arc<AppState>>,
+    Valid(payload): Valid<ActionLinkRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let service = ApprovalService::new(state);
+    let approval = service.record_link_decision(payload).await?;
+    Ok(Json(serde_json::json!({ "approval": approval })))
 }
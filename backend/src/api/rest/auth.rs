@@ -1,17 +1,43 @@
 use std::sync::Arc;
 
-use axum::{extract::Extension, http::StatusCode, routing::post, Json, Router};
+use axum::{
+    extract::{Extension, Path},
+    http::StatusCode,
+    routing::{delete, get, post},
+    Json, Router,
+};
 use serde::{Deserialize, Serialize};
 use subtle::ConstantTimeEq;
+use uuid::Uuid;
 
 use crate::{
+    api::error::ApiError,
     domain::models::{Employee, Role},
-    infrastructure::{auth::issue_token, state::AppState},
-    services::errors::ServiceError,
+    infrastructure::{
+        auth::{issue_token, AuthenticatedUser},
+        state::AppState,
+    },
+    services::{
+        assistants::{AddAssistantRequest, AssistantLink, AssistantLinks, AssistantService},
+        bank_accounts::{BankAccountService, MaskedBankAccount, SetBankAccountRequest},
+        errors::ServiceError,
+        profile::{ProfilePreferences, ProfileService, UpdateProfilePreferences},
+    },
 };
 
 pub fn router() -> Router {
-    Router::new().route("/login", post(login))
+    Router::new()
+        .route("/login", post(login))
+        .route(
+            "/me/bank-account",
+            get(get_bank_account).put(set_bank_account),
+        )
+        .route(
+            "/me/preferences",
+            get(get_preferences).patch(update_preferences),
+        )
+        .route("/me/assistants", get(list_assistants).post(add_assistant))
+        .route("/me/assistants/:assistant_id", delete(remove_assistant))
 }
 
 #[derive(Debug, Deserialize)]
@@ -29,7 +55,7 @@ struct LoginResponse {
 async fn login(
     Extension(state): Extension<Arc<AppState>>,
     Json(payload): Json<LoginRequest>,
-) -> Result<Json<LoginResponse>, (StatusCode, Json<serde_json::Value>)> {
+) -> Result<Json<LoginResponse>, ApiError> {
     let Some(hr_identifier) = normalize_hr_identifier(&payload.hr_identifier) else {
         return Err(unauthorized());
     };
@@ -52,21 +78,22 @@ async fn login(
 
     let employee = sqlx::query_as::<_, Employee>(
         r#"
-        SELECT id, hr_identifier, manager_id, department, role, created_at
+        SELECT id, org_id, hr_identifier, manager_id, department, department_id,
+               cost_center_id, role, created_at, deactivated_at
         FROM employees
-        WHERE UPPER(hr_identifier) = $1
+        WHERE UPPER(hr_identifier) = $1 AND deactivated_at IS NULL
         "#,
     )
     .bind(&hr_identifier)
     .fetch_optional(&state.pool)
     .await
-    .map_err(|err| to_response(ServiceError::Internal(err.to_string())))?;
+    .map_err(|err| ApiError::from(ServiceError::Internal(err.to_string())))?;
 
     let Some(employee) = employee else {
         return Err(unauthorized());
     };
 
-    let token = issue_token(&state, &employee).map_err(to_response)?;
+    let token = issue_token(&state, &employee)?;
 
     Ok(Json(LoginResponse {
         token,
@@ -74,6 +101,73 @@ async fn login(
     }))
 }
 
+async fn get_bank_account(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> Result<Json<MaskedBankAccount>, ApiError> {
+    let service = BankAccountService::new(state);
+    let account = service.get(&user).await?;
+    Ok(Json(account))
+}
+
+async fn set_bank_account(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(payload): Json<SetBankAccountRequest>,
+) -> Result<Json<MaskedBankAccount>, ApiError> {
+    let service = BankAccountService::new(state);
+    let account = service.set(&user, payload).await?;
+    Ok(Json(account))
+}
+
+async fn get_preferences(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> Result<Json<ProfilePreferences>, ApiError> {
+    let service = ProfileService::new(state);
+    let preferences = service.preferences(&user).await?;
+    Ok(Json(preferences))
+}
+
+async fn update_preferences(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(payload): Json<UpdateProfilePreferences>,
+) -> Result<Json<ProfilePreferences>, ApiError> {
+    let service = ProfileService::new(state);
+    let preferences = service.update_preferences(&user, payload).await?;
+    Ok(Json(preferences))
+}
+
+async fn list_assistants(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> Result<Json<AssistantLinks>, ApiError> {
+    let service = AssistantService::new(state);
+    let links = service.links(&user).await?;
+    Ok(Json(links))
+}
+
+async fn add_assistant(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(payload): Json<AddAssistantRequest>,
+) -> Result<Json<AssistantLink>, ApiError> {
+    let service = AssistantService::new(state);
+    let link = service.add(&user, payload).await?;
+    Ok(Json(link))
+}
+
+async fn remove_assistant(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(assistant_id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let service = AssistantService::new(state);
+    service.remove(&user, assistant_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
 fn normalize_hr_identifier(value: &str) -> Option<String> {
     let trimmed = value.trim();
     if trimmed.is_empty() {
@@ -83,35 +177,24 @@ fn normalize_hr_identifier(value: &str) -> Option<String> {
     Some(trimmed.to_uppercase())
 }
 
-fn unauthorized() -> (StatusCode, Json<serde_json::Value>) {
-    (
+fn unauthorized() -> ApiError {
+    ApiError::new(
         StatusCode::UNAUTHORIZED,
-        Json(serde_json::json!({ "error": "invalid_credentials" })),
+        "invalid_credentials",
+        "the HR identifier or credential is incorrect",
     )
 }
 
-fn to_response(err: ServiceError) -> (StatusCode, Json<serde_json::Value>) {
-    let status = err.status_code();
-    let body = match err {
-        ServiceError::Internal(e) => {
-            tracing::error!("Internal error: {}", e);
-            serde_json::json!({ "error": "internal_server_error" })
-        }
-        _ => serde_json::json!({ "error": err.to_string() }),
-    };
-    (status, Json(body))
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn unauthorized_returns_expected_payload() {
-        let (status, Json(body)) = unauthorized();
+        let error = unauthorized();
 
-        assert_eq!(status, StatusCode::UNAUTHORIZED);
-        assert_eq!(body, serde_json::json!({ "error": "invalid_credentials" }));
+        assert_eq!(error.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(error.code(), "invalid_credentials");
     }
 
     #[test]
@@ -0,0 +1,515 @@
+//! `POST /api/batch`: several API calls in one round trip.
+//!
+//! A batch lists sub-requests, each an API method, path, and optional JSON
+//! body, which run one after another in the order given with the caller's
+//! credentials. A sub-request can use an earlier one's result: a
+//! `${id.field.path}` reference in its path, or as a whole string value in
+//! its body, is replaced by that field of the response body of the
+//! sub-request with that `id`. Sub-requests are not one transaction; each
+//! commits on its own. With `on_error: "stop"` (the default) the first
+//! failure ends the batch and the remaining sub-requests are answered
+//! `424 batch_aborted`; with `"continue"` they still run, except those
+//! referring to a failed one, which get `424 dependency_failed`.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+};
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Extension, Request},
+    http::{header, HeaderMap, HeaderName, HeaderValue, StatusCode, Uri},
+    routing::post,
+    Json, Router,
+};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tower::ServiceExt;
+
+use crate::{
+    api::{
+        client_ip::ClientIp,
+        error::ApiError,
+        request_id::RequestId,
+        validation::{Valid, Validate, Validator},
+    },
+    infrastructure::{auth::AuthenticatedUser, config::Config, state::AppState},
+};
+
+const METHODS: &[&str] = &["GET", "POST", "PUT", "PATCH", "DELETE"];
+/// Headers a sub-request may set itself; credentials come from the batch.
+const SUB_REQUEST_HEADERS: &[&str] = &["if-match", "if-none-match"];
+/// Headers of the batch passed on to every sub-request. Its client address
+/// is passed on already resolved, as `ClientIp`.
+const INHERITED_HEADERS: &[&str] = &["authorization", "user-agent"];
+/// Sub-response headers returned to the caller.
+const RETURNED_HEADERS: &[&str] = &["etag", "location", "retry-after"];
+/// Characters a referenced value may keep when placed in a path.
+const PATH_VALUE: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// `dispatch` is the router sub-requests run against. It must carry the
+/// rate limiter, as `build_router`'s does, so each sub-request draws a token
+/// from the caller's quota.
+pub fn router(dispatch: Router) -> Router {
+    Router::new()
+        .route("/batch", post(run))
+        .layer(Extension(Dispatch(dispatch)))
+}
+
+#[derive(Clone)]
+struct Dispatch(Router);
+
+#[derive(Debug, Deserialize)]
+pub struct BatchRequest {
+    pub requests: Vec<SubRequest>,
+    #[serde(default)]
+    pub on_error: OnError,
+}
+
+/// What a failed sub-request does to the rest of the batch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnError {
+    #[default]
+    Stop,
+    Continue,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubRequest {
+    /// Names the sub-request so later ones can refer to its response.
+    #[serde(default)]
+    pub id: Option<String>,
+    pub method: String,
+    pub path: String,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub body: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct SubResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    status: u16,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    headers: BTreeMap<String, String>,
+    body: Value,
+}
+
+impl SubResponse {
+    fn error(id: Option<String>, err: ApiError) -> Self {
+        Self {
+            id,
+            status: err.status().as_u16(),
+            headers: BTreeMap::new(),
+            body: serde_json::to_value(&err).unwrap_or(Value::Null),
+        }
+    }
+
+    fn succeeded(&self) -> bool {
+        self.status < 400
+    }
+}
+
+impl Validate for BatchRequest {
+    fn validate(&self, config: &Config, v: &mut Validator) {
+        validate_batch(self, config.app.batch_max_requests, v);
+    }
+}
+
+fn validate_batch(batch: &BatchRequest, max: usize, v: &mut Validator) {
+    v.check(
+        !batch.requests.is_empty(),
+        "requests",
+        "at least one request is required",
+    );
+    v.check(
+        batch.requests.len() <= max,
+        "requests",
+        format!("must list at most {max} requests"),
+    );
+    let mut ids = HashSet::new();
+    v.each("requests", &batch.requests, |v, request| {
+        if let Some(id) = &request.id {
+            let valid = !id.is_empty()
+                && id
+                    .chars()
+                    .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-');
+            if v.check(valid, "id", "must be letters, digits, `_`, or `-`") {
+                v.check(
+                    ids.insert(id.as_str()),
+                    "id",
+                    "is used by an earlier request",
+                );
+            }
+        }
+        v.check(
+            METHODS.contains(&request.method.as_str()),
+            "method",
+            "must be GET, POST, PUT, PATCH, or DELETE",
+        );
+        v.check(
+            request.path.starts_with("/api/") && !is_batch_path(&request.path),
+            "path",
+            "must be an API path such as /api/expenses/reports",
+        );
+        v.check(
+            !(request.method == "GET" && request.body.is_some()),
+            "body",
+            "GET requests take no body",
+        );
+        for name in request.headers.keys() {
+            v.check(
+                SUB_REQUEST_HEADERS.contains(&name.to_ascii_lowercase().as_str()),
+                "headers",
+                format!("{name} cannot be set on a sub-request"),
+            );
+        }
+    });
+}
+
+fn is_batch_path(path: &str) -> bool {
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    path.trim_end_matches('/') == "/api/batch"
+}
+
+/// Runs the sub-requests in order and answers `200` with one entry per
+/// sub-request, whatever their outcomes.
+async fn run(
+    Extension(Dispatch(dispatch)): Extension<Dispatch>,
+    Extension(state): Extension<Arc<AppState>>,
+    // Refuses unauthenticated batches as a whole rather than per sub-request.
+    _user: AuthenticatedUser,
+    request_id: Option<Extension<RequestId>>,
+    client_ip: Option<Extension<ClientIp>>,
+    headers: HeaderMap,
+    Valid(batch): Valid<BatchRequest>,
+) -> Json<Value> {
+    let mut results: HashMap<String, usize> = HashMap::new();
+    let mut responses: Vec<SubResponse> = Vec::with_capacity(batch.requests.len());
+    let mut aborted = false;
+
+    for sub in batch.requests {
+        let id = sub.id.clone();
+        let response = if aborted {
+            SubResponse::error(
+                id,
+                ApiError::new(
+                    StatusCode::FAILED_DEPENDENCY,
+                    "batch_aborted",
+                    "not run because an earlier request in the batch failed",
+                ),
+            )
+        } else {
+            let outcome = {
+                let lookup = |reference: &str| lookup(reference, &results, &responses);
+                prepare(&sub, lookup)
+            };
+            match outcome {
+                Ok((path, body)) => {
+                    let mut request = Request::builder()
+                        .method(sub.method.as_str())
+                        .uri(path)
+                        .body(match &body {
+                            Some(body) => Body::from(body.to_string()),
+                            None => Body::empty(),
+                        })
+                        .unwrap_or_default();
+                    copy_headers(&headers, &sub, body.is_some(), request.headers_mut());
+                    let extensions = request.extensions_mut();
+                    extensions.insert(Arc::clone(&state));
+                    if let Some(Extension(request_id)) = &request_id {
+                        extensions.insert(request_id.clone());
+                    }
+                    if let Some(Extension(client_ip)) = &client_ip {
+                        extensions.insert(client_ip.clone());
+                    }
+                    dispatch_one(dispatch.clone(), id, request).await
+                }
+                Err(err) => SubResponse::error(id, err),
+            }
+        };
+        if !response.succeeded() && batch.on_error == OnError::Stop {
+            aborted = true;
+        }
+        if let Some(id) = &response.id {
+            results.insert(id.clone(), responses.len());
+        }
+        responses.push(response);
+    }
+
+    Json(serde_json::json!({ "responses": responses }))
+}
+
+async fn dispatch_one(dispatch: Router, id: Option<String>, request: Request) -> SubResponse {
+    let response = dispatch
+        .oneshot(request)
+        .await
+        .unwrap_or_else(|never| match never {});
+    let (parts, body) = response.into_parts();
+    let headers = RETURNED_HEADERS
+        .iter()
+        .filter_map(|name| {
+            let value = parts.headers.get(*name)?.to_str().ok()?;
+            Some((name.to_string(), value.to_string()))
+        })
+        .collect();
+    let is_json = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) if bytes.is_empty() => Value::Null,
+        Ok(bytes) if is_json => serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        Ok(bytes) => String::from_utf8(bytes.to_vec())
+            .map(Value::String)
+            .unwrap_or(Value::Null),
+        Err(_) => Value::Null,
+    };
+    SubResponse {
+        id,
+        status: parts.status.as_u16(),
+        headers,
+        body,
+    }
+}
+
+fn copy_headers(batch: &HeaderMap, sub: &SubRequest, has_body: bool, headers: &mut HeaderMap) {
+    for &name in INHERITED_HEADERS {
+        if let Some(value) = batch.get(name) {
+            headers.insert(name, value.clone());
+        }
+    }
+    for (name, value) in &sub.headers {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::try_from(name.as_str()),
+            HeaderValue::from_str(value),
+        ) {
+            headers.insert(name, value);
+        }
+    }
+    if has_body {
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json"),
+        );
+    }
+}
+
+/// The sub-request's path and body with its references replaced.
+fn prepare<'a>(
+    sub: &SubRequest,
+    lookup: impl Fn(&str) -> Result<&'a Value, ApiError>,
+) -> Result<(String, Option<Value>), ApiError> {
+    let path = resolve_path(&sub.path, &lookup)?;
+    let body = match &sub.body {
+        Some(body) => {
+            let mut body = body.clone();
+            resolve_body(&mut body, &lookup)?;
+            Some(body)
+        }
+        None => None,
+    };
+    if path.parse::<Uri>().is_err() {
+        return Err(invalid_reference(format!(
+            "{path} is not a valid request target"
+        )));
+    }
+    Ok((path, body))
+}
+
+fn resolve_path<'a>(
+    path: &str,
+    lookup: &impl Fn(&str) -> Result<&'a Value, ApiError>,
+) -> Result<String, ApiError> {
+    let mut resolved = String::with_capacity(path.len());
+    let mut rest = path;
+    while let Some(start) = rest.find("${") {
+        resolved.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else {
+            return Err(invalid_reference(format!(
+                "unterminated reference in {path}"
+            )));
+        };
+        let reference = &rest[start + 2..start + end];
+        let value = match lookup(reference)? {
+            Value::String(value) => value.clone(),
+            Value::Number(value) => value.to_string(),
+            _ => {
+                return Err(invalid_reference(format!(
+                    "${{{reference}}} is not a string or number"
+                )))
+            }
+        };
+        resolved.extend(utf8_percent_encode(&value, PATH_VALUE));
+        rest = &rest[start + end + 1..];
+    }
+    resolved.push_str(rest);
+    Ok(resolved)
+}
+
+fn resolve_body<'a>(
+    body: &mut Value,
+    lookup: &impl Fn(&str) -> Result<&'a Value, ApiError>,
+) -> Result<(), ApiError> {
+    match body {
+        Value::String(value) => {
+            if let Some(reference) = value
+                .strip_prefix("${")
+                .and_then(|value| value.strip_suffix('}'))
+            {
+                *body = lookup(reference)?.clone();
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                resolve_body(item, lookup)?;
+            }
+        }
+        Value::Object(fields) => {
+            for value in fields.values_mut() {
+                resolve_body(value, lookup)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Resolves `id.field.path` against the response bodies so far.
+fn lookup<'a>(
+    reference: &str,
+    results: &HashMap<String, usize>,
+    responses: &'a [SubResponse],
+) -> Result<&'a Value, ApiError> {
+    let mut segments = reference.split('.');
+    let id = segments.next().unwrap_or_default();
+    let Some(response) = results.get(id).map(|&index| &responses[index]) else {
+        return Err(invalid_reference(format!(
+            "${{{reference}}} does not name an earlier request"
+        )));
+    };
+    if !response.succeeded() {
+        return Err(ApiError::new(
+            StatusCode::FAILED_DEPENDENCY,
+            "dependency_failed",
+            format!("not run because request {id} failed"),
+        ));
+    }
+    segments
+        .try_fold(&response.body, |value, segment| match value {
+            Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => value.get(segment),
+        })
+        .ok_or_else(|| {
+            invalid_reference(format!(
+                "${{{reference}}} is not in the response to request {id}"
+            ))
+        })
+}
+
+fn invalid_reference(message: String) -> ApiError {
+    ApiError::new(
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "invalid_reference",
+        message,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sub(id: &str, status: u16, body: Value) -> SubResponse {
+        SubResponse {
+            id: Some(id.into()),
+            status,
+            headers: BTreeMap::new(),
+            body,
+        }
+    }
+
+    #[test]
+    fn references_resolve_against_earlier_responses() {
+        let responses = vec![
+            sub(
+                "create",
+                200,
+                serde_json::json!({ "report": { "id": "9b1d/x", "items": [{ "amount_cents": 4200 }] } }),
+            ),
+            sub(
+                "failed",
+                422,
+                serde_json::json!({ "error": "validation_failed" }),
+            ),
+        ];
+        let results = HashMap::from([("create".to_string(), 0), ("failed".to_string(), 1)]);
+        let lookup = |reference: &str| lookup(reference, &results, &responses);
+
+        assert_eq!(
+            resolve_path("/api/expenses/reports/${create.report.id}/submit", &lookup).unwrap(),
+            "/api/expenses/reports/9b1d%2Fx/submit"
+        );
+        let mut body = serde_json::json!({
+            "amount": "${create.report.items.0.amount_cents}",
+            "note": "literal",
+        });
+        resolve_body(&mut body, &lookup).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({ "amount": 4200, "note": "literal" })
+        );
+
+        let code = |err: ApiError| err.code();
+        assert_eq!(
+            code(resolve_path("/api/x/${failed.id}", &lookup).unwrap_err()),
+            "dependency_failed"
+        );
+        assert_eq!(
+            code(resolve_path("/api/x/${missing.id}", &lookup).unwrap_err()),
+            "invalid_reference"
+        );
+        assert_eq!(
+            code(resolve_path("/api/x/${create.report.total}", &lookup).unwrap_err()),
+            "invalid_reference"
+        );
+    }
+
+    #[test]
+    fn batches_are_checked_before_anything_runs() {
+        let batch: BatchRequest = serde_json::from_value(serde_json::json!({
+            "requests": [
+                { "id": "a", "method": "POST", "path": "/api/expenses/reports", "body": {} },
+                { "id": "a", "method": "GET", "path": "/api/batch", "body": {} },
+                { "id": "b.c", "method": "TRACE", "path": "/auth/login",
+                  "headers": { "Authorization": "Bearer other" } },
+            ]
+        }))
+        .unwrap();
+        let mut v = Validator::new();
+        validate_batch(&batch, 20, &mut v);
+        let errors = v.into_errors();
+
+        assert!(!errors.keys().any(|field| field.starts_with("requests.0")));
+        assert_eq!(errors["requests.1.id"], ["is used by an earlier request"]);
+        assert!(errors.contains_key("requests.1.path"));
+        assert!(errors.contains_key("requests.1.body"));
+        assert!(errors.contains_key("requests.2.id"));
+        assert!(errors.contains_key("requests.2.method"));
+        assert!(errors.contains_key("requests.2.path"));
+        assert_eq!(
+            errors["requests.2.headers"],
+            ["Authorization cannot be set on a sub-request"]
+        );
+        assert_eq!(batch.on_error, OnError::Stop);
+    }
+}
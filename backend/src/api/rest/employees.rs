@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Extension, Path},
+    routing::get,
+    Json, Router,
+};
+use uuid::Uuid;
+
+use crate::{
+    api::error::ApiError,
+    infrastructure::{auth::AuthenticatedUser, state::AppState},
+    services::management_chain::{ManagementChain, ManagementChainService},
+};
+
+pub fn router() -> Router {
+    Router::new().route("/:id/management-chain", get(management_chain))
+}
+
+async fn management_chain(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ManagementChain>, ApiError> {
+    let service = ManagementChainService::new(state);
+    let chain = service.get(&user, id).await?;
+
+    Ok(Json(chain))
+}
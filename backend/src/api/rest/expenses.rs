@@ -1,338 +1,368 @@
-use std::{collections::BTreeMap, sync::Arc};
+use std::sync::Arc;
 
 use axum::http::StatusCode;
 use axum::{
-    extract::{Extension, Path},
-    routing::{get, post},
+    extract::{Extension, Path, Query},
+    http::{header::ETAG, HeaderMap},
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
     Json, Router,
 };
 use uuid::Uuid;
 
 use crate::{
-    domain::models::ExpenseCategory,
-    infrastructure::{auth::AuthenticatedUser, state::AppState},
-    services::errors::ServiceError,
-    services::expenses::{
-        CreateExpenseItem, CreateReceiptReference, CreateReportRequest, ExpenseService,
+    api::{
+        error::ApiError,
+        reference,
+        validation::{Valid, Validate, Validator},
     },
+    domain::models::{Currency, ExpenseCategory},
+    infrastructure::{auth::AuthenticatedUser, reference_cache::ReferenceData, state::AppState},
+    services::assistants::{AssistantService, OnBehalfOf},
+    services::expenses::{CreateExpenseItem, CreateReportRequest, ExpenseService},
+    services::preconditions::{self, IfMatch},
+    services::search::{SearchParams, SearchService},
 };
 
-use crate::infrastructure::config::ReceiptRules;
+use crate::infrastructure::config::{Config, ReceiptRules};
 
+/// Candidate item checked by `POST /policy/check` before it is saved.
 #[derive(Debug, serde::Deserialize)]
-struct CreateReportPayload {
-    reporting_period_start: chrono::NaiveDate,
-    reporting_period_end: chrono::NaiveDate,
+struct PolicyCheckPayload {
     currency: String,
-    #[serde(default)]
-    items: Vec<CreateReportItemPayload>,
-}
-
-#[derive(Debug, serde::Deserialize)]
-struct CreateReportItemPayload {
-    expense_date: chrono::NaiveDate,
-    category: ExpenseCategory,
-    #[serde(default)]
-    description: Option<String>,
-    #[serde(default)]
-    attendees: Option<String>,
-    #[serde(default)]
-    location: Option<String>,
-    amount_cents: i64,
-    reimbursable: bool,
-    #[serde(default)]
-    payment_method: Option<String>,
-    #[serde(default)]
-    receipts: Vec<ReceiptPayload>,
-}
-
-#[derive(Debug, serde::Deserialize)]
-struct ReceiptPayload {
-    file_key: String,
-    file_name: String,
-    mime_type: String,
-    size_bytes: i64,
+    item: CreateExpenseItem,
 }
 
 pub fn router() -> Router {
     Router::new()
         .route("/reports", post(create_report))
+        .route("/reports/:id", get(get_report).delete(delete_report))
         .route("/reports/:id/submit", post(submit_report))
         .route("/reports/:id/policy", get(evaluate_report))
+        .route("/reports/:id/policy/history", get(policy_history))
+        .route("/policy/check", post(check_item_policy))
+        .route("/categories", get(list_categories))
+        .route("/search", get(search_items))
+        .route("/items/:id", delete(delete_item))
+        .route("/receipts/:id", delete(delete_receipt))
 }
 
-async fn create_report(
+/// The expense categories items may use.
+async fn list_categories(
     Extension(state): Extension<Arc<AppState>>,
     user: AuthenticatedUser,
-    Json(payload): Json<CreateReportPayload>,
-) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, Json<serde_json::Value>)> {
-    let validation_errors = validate_create_report_payload(&payload, &state.config.receipts);
-    if !validation_errors.is_empty() {
-        return Err(validation_error_response(validation_errors));
-    }
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let load = async { Ok(serde_json::json!({ "categories": ExpenseCategory::ALL })) };
+    reference::respond(&state, &user, &headers, ReferenceData::Categories, load).await
+}
 
+async fn create_report(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    on_behalf_of: OnBehalfOf,
+    Valid(payload): Valid<CreateReportRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let acting = AssistantService::new(state.clone())
+        .act_for(&user, on_behalf_of)
+        .await?;
     let service = ExpenseService::new(state);
-    let report = service
-        .create_report(&user, payload.into_request())
-        .await
-        .map_err(to_response)?;
+    let report = acting
+        .run(service.create_report(&acting.user, payload))
+        .await?;
     Ok(Json(serde_json::json!({ "report": report })))
 }
 
+/// Returns the report with its ETag, or `304` when `If-None-Match` already
+/// names the current revision.
+async fn get_report(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    on_behalf_of: OnBehalfOf,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let acting = AssistantService::new(state.clone())
+        .act_for(&user, on_behalf_of)
+        .await?;
+    let service = ExpenseService::new(state);
+    let report = service.get_report(&acting.user, id).await?;
+    let etag = preconditions::etag(report.version, report.updated_at);
+    if preconditions::not_modified(&headers, &etag) {
+        return Ok((StatusCode::NOT_MODIFIED, [(ETAG, etag)]).into_response());
+    }
+    Ok((
+        [(ETAG, etag)],
+        Json(serde_json::json!({ "report": report })),
+    )
+        .into_response())
+}
+
+/// Submits the report; with `If-Match`, only while the report still has one
+/// of the given ETags.
 async fn submit_report(
     Extension(state): Extension<Arc<AppState>>,
     user: AuthenticatedUser,
+    on_behalf_of: OnBehalfOf,
     Path(id): Path<Uuid>,
-) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    if_match: IfMatch,
+) -> Result<Response, ApiError> {
+    let acting = AssistantService::new(state.clone())
+        .act_for(&user, on_behalf_of)
+        .await?;
     let service = ExpenseService::new(state);
-    let report = service
-        .submit_report(&user, id)
-        .await
-        .map_err(to_response)?;
-    Ok(Json(serde_json::json!({ "report": report })))
+    let report = acting
+        .run(service.submit_report(&acting.user, id, &if_match))
+        .await?;
+    let etag = preconditions::etag(report.version, report.updated_at);
+    Ok((
+        [(ETAG, etag)],
+        Json(serde_json::json!({ "report": report })),
+    )
+        .into_response())
+}
+
+/// Soft-deletes one of the caller's draft reports.
+async fn delete_report(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    on_behalf_of: OnBehalfOf,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let acting = AssistantService::new(state.clone())
+        .act_for(&user, on_behalf_of)
+        .await?;
+    let service = ExpenseService::new(state);
+    acting.run(service.delete_report(&acting.user, id)).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Soft-deletes an item on one of the caller's draft reports.
+async fn delete_item(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    on_behalf_of: OnBehalfOf,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let acting = AssistantService::new(state.clone())
+        .act_for(&user, on_behalf_of)
+        .await?;
+    let service = ExpenseService::new(state);
+    acting.run(service.delete_item(&acting.user, id)).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Soft-deletes a receipt on one of the caller's draft reports.
+async fn delete_receipt(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    on_behalf_of: OnBehalfOf,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let acting = AssistantService::new(state.clone())
+        .act_for(&user, on_behalf_of)
+        .await?;
+    let service = ExpenseService::new(state);
+    acting.run(service.delete_receipt(&acting.user, id)).await?;
+    Ok(StatusCode::NO_CONTENT)
 }
 
 async fn evaluate_report(
     Extension(state): Extension<Arc<AppState>>,
     user: AuthenticatedUser,
+    on_behalf_of: OnBehalfOf,
     Path(id): Path<Uuid>,
-) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let acting = AssistantService::new(state.clone())
+        .act_for(&user, on_behalf_of)
+        .await?;
+    let service = ExpenseService::new(state);
+    let result = acting
+        .run(service.evaluate_report(&acting.user, id))
+        .await?;
+    Ok(Json(serde_json::json!({ "evaluation": result })))
+}
+
+async fn policy_history(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    on_behalf_of: OnBehalfOf,
+    Path(id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let acting = AssistantService::new(state.clone())
+        .act_for(&user, on_behalf_of)
+        .await?;
+    let service = ExpenseService::new(state);
+    let runs = service.policy_history(&acting.user, id).await?;
+    Ok(Json(serde_json::json!({ "runs": runs })))
+}
+
+/// Items matching `q` by merchant or description, best match first.
+async fn search_items(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Query(params): Query<SearchParams>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let service = SearchService::new(state);
+    let hits = service.search_items(&user, &params).await?;
+    Ok(Json(serde_json::json!({ "items": hits })))
+}
+
+/// Evaluates one unsaved item against the item-level policy rules so the UI
+/// can warn while the employee is still typing.
+async fn check_item_policy(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Valid(payload): Valid<PolicyCheckPayload>,
+) -> Result<Json<serde_json::Value>, ApiError> {
     let service = ExpenseService::new(state);
     let result = service
-        .evaluate_report(&user, id)
-        .await
-        .map_err(to_response)?;
+        .check_item(&user, &payload.currency, payload.item)
+        .await?;
     Ok(Json(serde_json::json!({ "evaluation": result })))
 }
 
-fn to_response(err: ServiceError) -> (axum::http::StatusCode, Json<serde_json::Value>) {
-    match err {
-        ServiceError::Validation(message) => (
-            StatusCode::UNPROCESSABLE_ENTITY,
-            Json(serde_json::json!({
-                "error": "validation_failed",
-                "message": message,
-            })),
-        ),
-        ServiceError::Internal(message) => {
-            tracing::error!("Internal error: {}", message);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({ "error": "internal_server_error" })),
-            )
-        }
-        other => (
-            other.status_code(),
-            Json(serde_json::json!({ "error": other.to_string() })),
-        ),
+impl Validate for CreateReportRequest {
+    fn validate(&self, config: &Config, v: &mut Validator) {
+        validate_create_report_payload(self, &config.receipts, v);
     }
 }
 
-impl CreateReportPayload {
-    fn into_request(self) -> CreateReportRequest {
-        CreateReportRequest {
-            reporting_period_start: self.reporting_period_start,
-            reporting_period_end: self.reporting_period_end,
-            currency: self.currency,
-            items: self
-                .items
-                .into_iter()
-                .map(|item| CreateExpenseItem {
-                    expense_date: item.expense_date,
-                    category: item.category,
-                    description: item.description,
-                    attendees: item.attendees,
-                    location: item.location,
-                    amount_cents: item.amount_cents,
-                    reimbursable: item.reimbursable,
-                    payment_method: item.payment_method,
-                    receipts: item
-                        .receipts
-                        .into_iter()
-                        .map(|receipt| CreateReceiptReference {
-                            file_key: receipt.file_key,
-                            file_name: receipt.file_name,
-                            mime_type: receipt.mime_type,
-                            size_bytes: receipt.size_bytes,
-                        })
-                        .collect(),
-                })
-                .collect(),
-        }
+impl Validate for PolicyCheckPayload {
+    fn validate(&self, config: &Config, v: &mut Validator) {
+        validate_currency(v, &self.currency);
+        v.nested("item", |v| {
+            validate_item_payload(v, &self.item, &config.receipts)
+        });
     }
 }
 
 fn validate_create_report_payload(
-    payload: &CreateReportPayload,
+    payload: &CreateReportRequest,
     receipt_rules: &ReceiptRules,
-) -> BTreeMap<String, Vec<String>> {
-    let mut errors: BTreeMap<String, Vec<String>> = BTreeMap::new();
-
-    if payload.currency.trim().is_empty() {
-        push_error(&mut errors, "currency", "currency is required");
+    v: &mut Validator,
+) {
+    if let Some(currency) = &payload.currency {
+        validate_currency(v, currency);
+    }
+    v.check(
+        payload.reporting_period_end >= payload.reporting_period_start,
+        "reporting_period_end",
+        "must be on or after reporting_period_start",
+    );
+    if !v.check(
+        !payload.items.is_empty(),
+        "items",
+        "at least one expense item is required",
+    ) {
+        return;
     }
 
-    if payload.reporting_period_end < payload.reporting_period_start {
-        push_error(
-            &mut errors,
-            "reporting_period_end",
-            "must be on or after reporting_period_start",
+    v.each("items", &payload.items, |v, item| {
+        validate_item_payload(v, item, receipt_rules);
+        v.check(
+            item.expense_date >= payload.reporting_period_start
+                && item.expense_date <= payload.reporting_period_end,
+            "expense_date",
+            "must be within the reporting period",
+        );
+    });
+}
+
+fn validate_currency(v: &mut Validator, currency: &str) {
+    if v.required("currency", currency) {
+        v.check(
+            Currency::parse(currency).is_ok(),
+            "currency",
+            "must be a three-letter ISO 4217 code",
         );
     }
+}
 
-    if payload.items.is_empty() {
-        push_error(
-            &mut errors,
-            "items",
-            "at least one expense item is required",
+/// Checks one item, under whatever nesting the caller set up.
+fn validate_item_payload(
+    v: &mut Validator,
+    item: &CreateExpenseItem,
+    receipt_rules: &ReceiptRules,
+) {
+    v.check(
+        item.amount_cents > 0,
+        "amount_cents",
+        "must be greater than 0",
+    );
+
+    if let Some(tax_amount_cents) = item.tax_amount_cents {
+        v.check(
+            (0..=item.amount_cents).contains(&tax_amount_cents),
+            "tax_amount_cents",
+            "must be between 0 and amount_cents",
         );
-        return errors;
     }
 
-    for (index, item) in payload.items.iter().enumerate() {
-        if item.amount_cents <= 0 {
-            push_error(
-                &mut errors,
-                format!("items.{index}.amount_cents"),
+    if let Some(miles) = item.miles {
+        if v.check(
+            item.category == ExpenseCategory::Mileage,
+            "miles",
+            "is only accepted on mileage items",
+        ) {
+            v.check(
+                miles > 0.0 && miles < 1_000_000_000.0,
+                "miles",
                 "must be greater than 0",
             );
         }
+    }
 
-        if item.expense_date < payload.reporting_period_start
-            || item.expense_date > payload.reporting_period_end
-        {
-            push_error(
-                &mut errors,
-                format!("items.{index}.expense_date"),
-                "must be within the reporting period",
-            );
-        }
-
-        if item.receipts.len() as u32 > receipt_rules.max_files_per_item {
-            push_error(
-                &mut errors,
-                format!("items.{index}.receipts"),
-                format!(
-                    "cannot attach more than {} receipts",
-                    receipt_rules.max_files_per_item
-                ),
+    v.check(
+        !item.is_policy_exception
+            || item
+                .exception_justification
+                .as_deref()
+                .is_some_and(|justification| !justification.trim().is_empty()),
+        "exception_justification",
+        "is required when is_policy_exception is set",
+    );
+
+    v.check(
+        item.receipts.len() as u32 <= receipt_rules.max_files_per_item,
+        "receipts",
+        format!(
+            "cannot attach more than {} receipts",
+            receipt_rules.max_files_per_item
+        ),
+    );
+
+    v.each("receipts", &item.receipts, |v, receipt| {
+        v.required("file_key", &receipt.file_key);
+        v.required("file_name", &receipt.file_name);
+        v.required("mime_type", &receipt.mime_type);
+        if v.check(
+            receipt.size_bytes > 0,
+            "size_bytes",
+            "must be greater than 0",
+        ) {
+            v.check(
+                receipt.size_bytes as u64 <= receipt_rules.max_bytes,
+                "size_bytes",
+                format!("exceeds maximum size of {} bytes", receipt_rules.max_bytes),
             );
         }
-
-        for (receipt_index, receipt) in item.receipts.iter().enumerate() {
-            if receipt.file_key.trim().is_empty() {
-                push_error(
-                    &mut errors,
-                    format!("items.{index}.receipts.{receipt_index}.file_key"),
-                    "file_key is required",
-                );
-            }
-
-            if receipt.file_name.trim().is_empty() {
-                push_error(
-                    &mut errors,
-                    format!("items.{index}.receipts.{receipt_index}.file_name"),
-                    "file_name is required",
-                );
-            }
-
-            if receipt.mime_type.trim().is_empty() {
-                push_error(
-                    &mut errors,
-                    format!("items.{index}.receipts.{receipt_index}.mime_type"),
-                    "mime_type is required",
-                );
-            }
-
-            if receipt.size_bytes <= 0 {
-                push_error(
-                    &mut errors,
-                    format!("items.{index}.receipts.{receipt_index}.size_bytes"),
-                    "must be greater than 0",
-                );
-            } else if receipt.size_bytes as u64 > receipt_rules.max_bytes {
-                push_error(
-                    &mut errors,
-                    format!("items.{index}.receipts.{receipt_index}.size_bytes"),
-                    format!("exceeds maximum size of {} bytes", receipt_rules.max_bytes),
-                );
-            }
-        }
-    }
-
-    errors
-}
-
-fn push_error(
-    errors: &mut BTreeMap<String, Vec<String>>,
-    key: impl Into<String>,
-    message: impl Into<String>,
-) {
-    errors.entry(key.into()).or_default().push(message.into());
-}
-
-fn validation_error_response(
-    errors: BTreeMap<String, Vec<String>>,
-) -> (StatusCode, Json<serde_json::Value>) {
-    (
-        StatusCode::UNPROCESSABLE_ENTITY,
-        Json(serde_json::json!({ "errors": errors })),
-    )
+    });
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use axum::http::StatusCode;
-
-    #[test]
-    fn maps_conflict_errors_to_http_409() {
-        let (status, Json(body)) = to_response(ServiceError::Conflict);
-
-        assert_eq!(status, StatusCode::CONFLICT);
-        assert_eq!(body, serde_json::json!({ "error": "conflict" }));
-    }
-
-    #[test]
-    fn maps_not_found_errors_to_http_404() {
-        let (status, Json(body)) = to_response(ServiceError::NotFound);
-
-        assert_eq!(status, StatusCode::NOT_FOUND);
-        assert_eq!(body, serde_json::json!({ "error": "not found" }));
-    }
-
-    #[test]
-    fn maps_internal_errors_to_generic_http_500() {
-        let (status, Json(body)) = to_response(ServiceError::Internal("db offline".into()));
-
-        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
-        assert_eq!(
-            body,
-            serde_json::json!({ "error": "internal_server_error" })
-        );
-    }
-
-    #[test]
-    fn maps_validation_errors_to_http_422() {
-        let (status, Json(body)) = to_response(ServiceError::Validation("totals mismatch".into()));
-
-        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
-        assert_eq!(
-            body,
-            serde_json::json!({
-                "error": "validation_failed",
-                "message": "totals mismatch",
-            })
-        );
-    }
+    use crate::services::expenses::CreateReceiptReference;
 
     #[test]
     fn validate_create_report_payload_returns_structured_errors() {
-        let payload = CreateReportPayload {
+        let payload = CreateReportRequest {
             reporting_period_start: chrono::NaiveDate::from_ymd_opt(2024, 5, 1).unwrap(),
             reporting_period_end: chrono::NaiveDate::from_ymd_opt(2024, 5, 31).unwrap(),
-            currency: "".to_string(),
-            items: vec![CreateReportItemPayload {
+            currency: Some("".to_string()),
+            trip_id: None,
+            items: vec![CreateExpenseItem {
                 expense_date: chrono::NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
                 category: ExpenseCategory::Meal,
                 description: None,
@@ -341,7 +371,15 @@ mod tests {
                 amount_cents: 0,
                 reimbursable: true,
                 payment_method: None,
-                receipts: vec![ReceiptPayload {
+                is_policy_exception: true,
+                exception_justification: Some("  ".to_string()),
+                class: None,
+                tax_amount_cents: Some(500),
+                tax_jurisdiction: None,
+                miles: None,
+                merchant: None,
+                cost_center_id: None,
+                receipts: vec![CreateReceiptReference {
                     file_key: "".to_string(),
                     file_name: "".to_string(),
                     mime_type: "".to_string(),
@@ -350,11 +388,15 @@ mod tests {
             }],
         };
 
-        let errors = validate_create_report_payload(&payload, &ReceiptRules::default());
+        let mut v = Validator::new();
+        validate_create_report_payload(&payload, &ReceiptRules::default(), &mut v);
+        let errors = v.into_errors();
 
         assert_eq!(errors.get("currency").unwrap()[0], "currency is required");
         assert!(errors.contains_key("items.0.amount_cents"));
         assert!(errors.contains_key("items.0.expense_date"));
+        assert!(errors.contains_key("items.0.tax_amount_cents"));
+        assert!(errors.contains_key("items.0.exception_justification"));
         assert!(errors.contains_key("items.0.receipts.0.file_key"));
         assert!(errors.contains_key("items.0.receipts.0.size_bytes"));
     }
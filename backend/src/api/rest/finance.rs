@@ -1,59 +1,408 @@
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
-use axum::{extract::Extension, routing::get, routing::post, Json, Router};
-use serde::Serialize;
+use axum::{
+    extract::{Extension, Path, Query},
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
+    routing::get,
+    routing::{delete, post, put},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 use crate::{
-    domain::models::Role,
+    api::{
+        error::ApiError,
+        reference,
+        validation::{Valid, Validate, Validator},
+    },
+    domain::models::{
+        AccountingPeriod, ExpenseCategory, ExportPayload, GlAccountMapping, NetSuiteFieldMapping,
+        Role, TaxCodeMapping,
+    },
     infrastructure::auth::AuthenticatedUser,
+    infrastructure::config::Config,
+    infrastructure::reference_cache::ReferenceData,
     infrastructure::state::AppState,
     services::{
+        bank_accounts::{BankAccountService, PaymentRun},
         errors::ServiceError,
-        finance::{BatchSummary, FinalizeRequest, FinanceService},
+        escalations::{EscalatedReport, EscalationService},
+        finance::{
+            BatchFilter, BatchSummary, FinalizeRequest, FinanceService, UpdateGlMappingRequest,
+            UpsertNetSuiteMappingRequest, UpsertTaxCodeRequest,
+        },
+        journal_export::ExportFormat,
+        pagination::{PageRequest, Paginated},
+        periods::{CreatePeriodRequest, PeriodService},
     },
 };
 
 #[derive(Serialize)]
-struct BatchListResponse {
-    batches: Vec<BatchSummary>,
+struct GlMappingListResponse {
+    mappings: Vec<GlAccountMapping>,
+}
+
+#[derive(Serialize)]
+struct TaxCodeListResponse {
+    tax_codes: Vec<TaxCodeMapping>,
+}
+
+#[derive(Serialize)]
+struct NetSuiteMappingListResponse {
+    mappings: Vec<NetSuiteFieldMapping>,
+}
+
+#[derive(Serialize)]
+struct ExportPayloadListResponse {
+    payloads: Vec<ExportPayload>,
+}
+
+#[derive(Serialize)]
+struct PeriodListResponse {
+    periods: Vec<AccountingPeriod>,
+}
+
+#[derive(Serialize)]
+struct EscalationListResponse {
+    reports: Vec<EscalatedReport>,
 }
 
 pub fn router() -> Router {
     Router::new()
         .route("/finalize", post(finalize))
         .route("/batches", get(list_batches))
+        .route("/accruals", get(accruals))
+        .route("/summary", get(summary))
+        .route("/batches/:id/retry", post(retry_batch))
+        .route("/batches/:id/export", get(export_batch))
+        .route("/batches/:id/reverse", post(reverse_batch))
+        .route("/batches/:id/payloads", get(list_export_payloads))
+        .route("/batches/:id/payments", get(payment_run))
+        .route("/gl-mappings", get(list_gl_mappings))
+        .route("/gl-mappings/:category", put(update_gl_mapping))
+        .route("/tax-codes", get(list_tax_codes).put(upsert_tax_code))
+        .route("/tax-codes/:id", delete(delete_tax_code))
+        .route(
+            "/netsuite-mappings",
+            get(list_netsuite_mappings).put(upsert_netsuite_mapping),
+        )
+        .route("/netsuite-mappings/:id", delete(delete_netsuite_mapping))
+        .route("/escalations", get(list_escalations))
+        .route("/periods", get(list_periods).post(create_period))
+        .route("/periods/:id/close", post(close_period))
+        .route("/periods/:id/reopen", post(reopen_period))
+}
+
+impl Validate for FinalizeRequest {
+    fn validate(&self, _config: &Config, v: &mut Validator) {
+        v.check(
+            !self.report_ids.is_empty(),
+            "report_ids",
+            "at least one report is required",
+        );
+        let mut seen = HashSet::new();
+        v.each("report_ids", &self.report_ids, |v, id| {
+            v.check(seen.insert(*id), "", "is listed more than once");
+        });
+        v.required("batch_reference", &self.batch_reference);
+    }
+}
+
+impl Validate for UpdateGlMappingRequest {
+    fn validate(&self, _config: &Config, v: &mut Validator) {
+        v.required("gl_account", &self.gl_account);
+    }
+}
+
+impl Validate for UpsertTaxCodeRequest {
+    fn validate(&self, _config: &Config, v: &mut Validator) {
+        v.required("tax_code", &self.tax_code);
+    }
+}
+
+impl Validate for UpsertNetSuiteMappingRequest {
+    fn validate(&self, _config: &Config, v: &mut Validator) {
+        v.check(
+            matches!(self.target.trim(), "header" | "line"),
+            "target",
+            "must be header or line",
+        );
+        v.required("netsuite_field", &self.netsuite_field);
+        v.required("netsuite_value", &self.netsuite_value);
+    }
+}
+
+impl Validate for CreatePeriodRequest {
+    fn validate(&self, _config: &Config, v: &mut Validator) {
+        v.check(
+            self.period_end >= self.period_start,
+            "period_end",
+            "must be on or after period_start",
+        );
+    }
 }
 
 async fn finalize(
     Extension(state): Extension<Arc<AppState>>,
     user: AuthenticatedUser,
-    Json(payload): Json<FinalizeRequest>,
-) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    Valid(payload): Valid<FinalizeRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
     let service = FinanceService::new(state);
-    let batch = service
-        .finalize_reports(&user, payload)
-        .await
-        .map_err(to_response)?;
-    Ok(Json(serde_json::json!({ "batch": batch })))
+    let outcome = service.finalize_reports(&user, payload).await?;
+    Ok(Json(serde_json::json!({
+        "dry_run": outcome.preview.is_some(),
+        "batch": outcome.batch,
+        "rejected": outcome.rejected,
+        "preview": outcome.preview,
+    })))
 }
 
 async fn list_batches(
     Extension(state): Extension<Arc<AppState>>,
     user: AuthenticatedUser,
-) -> Result<Json<BatchListResponse>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    page: PageRequest,
+    Query(filter): Query<BatchFilter>,
+) -> Result<Json<Paginated<BatchSummary>>, ApiError> {
     if user.role != Role::Finance {
-        return Err(to_response(ServiceError::Forbidden));
+        return Err(ApiError::from(ServiceError::Forbidden));
     }
 
     let service = FinanceService::new(state);
-    let batches = service.recent_batches(&user).await.map_err(to_response)?;
+    let page = service.recent_batches(&user, &filter, &page).await?;
+
+    Ok(Json(page))
+}
+
+#[derive(Debug, Deserialize)]
+struct AccrualQuery {
+    period: String,
+}
+
+async fn accruals(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Query(query): Query<AccrualQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let service = FinanceService::new(state);
+    let report = service.accruals(&user, &query.period).await?;
+    Ok(Json(serde_json::json!({ "accruals": report })))
+}
+
+#[derive(Debug, Deserialize)]
+struct SummaryQuery {
+    period: Option<String>,
+}
+
+async fn summary(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Query(query): Query<SummaryQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let service = FinanceService::new(state);
+    let summary = service.summary(&user, query.period.as_deref()).await?;
+    Ok(Json(serde_json::json!({ "summary": summary })))
+}
 
-    Ok(Json(BatchListResponse { batches }))
+async fn retry_batch(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(batch_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let service = FinanceService::new(state);
+    let batch = service.retry_batch(&user, batch_id).await?;
+    Ok(Json(serde_json::json!({ "batch": batch })))
+}
+
+async fn reverse_batch(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(batch_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let service = FinanceService::new(state);
+    let reversal = service.reverse_batch(&user, batch_id).await?;
+    Ok(Json(serde_json::json!(reversal)))
+}
+
+#[derive(Debug, Deserialize)]
+struct ExportQuery {
+    format: ExportFormat,
+}
+
+/// Streams the batch's journal as a file attachment in the requested format.
+async fn export_batch(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(batch_id): Path<Uuid>,
+    Query(query): Query<ExportQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let service = FinanceService::new(state);
+    let file = service
+        .export_journal(&user, batch_id, query.format)
+        .await?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, file.content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", file.file_name),
+            ),
+        ],
+        file.body,
+    ))
 }
 
-fn to_response(err: ServiceError) -> (axum::http::StatusCode, Json<serde_json::Value>) {
-    (
-        err.status_code(),
-        Json(serde_json::json!({ "error": err.to_string() })),
-    )
+async fn list_gl_mappings(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let service = FinanceService::new(Arc::clone(&state));
+    let load = async {
+        let mappings = service.gl_mappings(&user).await?;
+        Ok(GlMappingListResponse { mappings })
+    };
+    reference::respond(&state, &user, &headers, ReferenceData::GlMappings, load).await
+}
+
+async fn update_gl_mapping(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(category): Path<ExpenseCategory>,
+    Valid(payload): Valid<UpdateGlMappingRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let service = FinanceService::new(state);
+    let mapping = service.update_gl_mapping(&user, category, payload).await?;
+    Ok(Json(serde_json::json!({ "mapping": mapping })))
+}
+
+async fn list_tax_codes(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let service = FinanceService::new(Arc::clone(&state));
+    let load = async {
+        let tax_codes = service.tax_codes(&user).await?;
+        Ok(TaxCodeListResponse { tax_codes })
+    };
+    reference::respond(&state, &user, &headers, ReferenceData::TaxCodes, load).await
+}
+
+async fn upsert_tax_code(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Valid(payload): Valid<UpsertTaxCodeRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let service = FinanceService::new(state);
+    let tax_code = service.upsert_tax_code(&user, payload).await?;
+    Ok(Json(serde_json::json!({ "tax_code": tax_code })))
+}
+
+async fn delete_tax_code(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> Result<axum::http::StatusCode, ApiError> {
+    let service = FinanceService::new(state);
+    service.delete_tax_code(&user, id).await?;
+    Ok(axum::http::StatusCode::NO_CONTENT)
+}
+
+async fn list_export_payloads(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(batch_id): Path<Uuid>,
+) -> Result<Json<ExportPayloadListResponse>, ApiError> {
+    let service = FinanceService::new(state);
+    let payloads = service.export_payloads(&user, batch_id).await?;
+    Ok(Json(ExportPayloadListResponse { payloads }))
+}
+
+async fn payment_run(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(batch_id): Path<Uuid>,
+) -> Result<Json<PaymentRun>, ApiError> {
+    let service = BankAccountService::new(state);
+    let run = service.payment_run(&user, batch_id).await?;
+    Ok(Json(run))
+}
+
+async fn list_netsuite_mappings(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> Result<Json<NetSuiteMappingListResponse>, ApiError> {
+    let service = FinanceService::new(state);
+    let mappings = service.netsuite_mappings(&user).await?;
+    Ok(Json(NetSuiteMappingListResponse { mappings }))
+}
+
+async fn upsert_netsuite_mapping(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Valid(payload): Valid<UpsertNetSuiteMappingRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let service = FinanceService::new(state);
+    let mapping = service.upsert_netsuite_mapping(&user, payload).await?;
+    Ok(Json(serde_json::json!({ "mapping": mapping })))
+}
+
+async fn delete_netsuite_mapping(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> Result<axum::http::StatusCode, ApiError> {
+    let service = FinanceService::new(state);
+    service.delete_netsuite_mapping(&user, id).await?;
+    Ok(axum::http::StatusCode::NO_CONTENT)
+}
+
+async fn list_escalations(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> Result<Json<EscalationListResponse>, ApiError> {
+    let service = EscalationService::new(state);
+    let reports = service.finance_queue(&user).await?;
+    Ok(Json(EscalationListResponse { reports }))
+}
+
+async fn list_periods(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> Result<Json<PeriodListResponse>, ApiError> {
+    let service = PeriodService::new(state);
+    let periods = service.list(&user).await?;
+    Ok(Json(PeriodListResponse { periods }))
+}
+
+async fn create_period(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Valid(payload): Valid<CreatePeriodRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let service = PeriodService::new(state);
+    let period = service.create(&user, payload).await?;
+    Ok(Json(serde_json::json!({ "period": period })))
+}
+
+async fn close_period(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let service = PeriodService::new(state);
+    let period = service.close(&user, id).await?;
+    Ok(Json(serde_json::json!({ "period": period })))
+}
+
+async fn reopen_period(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let service = PeriodService::new(state);
+    let period = service.reopen(&user, id).await?;
+    Ok(Json(serde_json::json!({ "period": period })))
 }
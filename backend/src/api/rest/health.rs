@@ -1,11 +1,230 @@
-use axum::Json;
-use serde::Serialize;
+//! Health probes.
+//!
+//! `GET /api/health` is the load balancer probe. Without parameters it only
+//! confirms the process is serving requests. `?deep=true` also pings the
+//! database and any read replica, writes and removes a storage object, and
+//! checks the NetSuite credentials, reporting each dependency's status and
+//! latency and answering `503` when any of them fails. Failure details are
+//! logged rather than returned, since the endpoint is unauthenticated.
+//!
+//! `GET /healthz/live` and `GET /healthz/ready` are the Kubernetes liveness
+//! and readiness probes. Liveness only needs the process to answer; readiness
+//! also needs every migration applied, the database reachable, and the
+//! background jobs running, and fails again once shutdown begins.
+
+use std::{
+    future::Future,
+    sync::{atomic::Ordering, Arc},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{Extension, Query},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::infrastructure::{db, netsuite, state::AppState};
+
+/// Longest a single dependency check may take before it counts as failed.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+/// Readiness probes time out after a second by default; answer before that.
+const READINESS_TIMEOUT: Duration = Duration::from_millis(800);
+
+#[derive(Debug, Default, Deserialize)]
+pub struct HealthQuery {
+    #[serde(default)]
+    deep: bool,
+}
 
 #[derive(Serialize)]
 pub struct HealthResponse {
     status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    checks: Option<DependencyChecks>,
+}
+
+#[derive(Serialize)]
+pub struct DependencyChecks {
+    database: CheckResult,
+    read_replica: CheckResult,
+    storage: CheckResult,
+    netsuite: CheckResult,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CheckResult {
+    /// `ok`, `error`, or `skipped` for a dependency that is not configured.
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latency_ms: Option<u64>,
+}
+
+impl CheckResult {
+    const SKIPPED: CheckResult = CheckResult {
+        status: "skipped",
+        latency_ms: None,
+    };
+
+    fn failed(&self) -> bool {
+        self.status == "error"
+    }
+}
+
+pub async fn healthcheck(
+    Extension(state): Extension<Arc<AppState>>,
+    Query(query): Query<HealthQuery>,
+) -> (StatusCode, Json<HealthResponse>) {
+    if !query.deep {
+        return (
+            StatusCode::OK,
+            Json(HealthResponse {
+                status: "ok",
+                checks: None,
+            }),
+        );
+    }
+
+    let (database, read_replica, storage, netsuite) = tokio::join!(
+        check("database", async {
+            sqlx::query("SELECT 1")
+                .execute(&state.pool)
+                .await
+                .map(|_| ())
+                .map_err(anyhow::Error::from)
+        }),
+        async {
+            if state.config.database.replica_url().is_some() {
+                check("read_replica", async {
+                    sqlx::query("SELECT 1")
+                        .execute(&state.read_pool)
+                        .await
+                        .map(|_| ())
+                        .map_err(anyhow::Error::from)
+                })
+                .await
+            } else {
+                CheckResult::SKIPPED
+            }
+        },
+        check("storage", state.storage.probe()),
+        async {
+            if netsuite::is_configured(&state.config.netsuite) {
+                check(
+                    "netsuite",
+                    netsuite::check_credentials(&state.config.netsuite),
+                )
+                .await
+            } else {
+                CheckResult::SKIPPED
+            }
+        },
+    );
+    let healthy = ![&database, &read_replica, &storage, &netsuite]
+        .iter()
+        .any(|result| result.failed());
+    (
+        if healthy {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        },
+        Json(HealthResponse {
+            status: if healthy { "ok" } else { "degraded" },
+            checks: Some(DependencyChecks {
+                database,
+                read_replica,
+                storage,
+                netsuite,
+            }),
+        }),
+    )
+}
+
+#[derive(Serialize)]
+pub struct ReadinessResponse {
+    status: &'static str,
+    migrations: bool,
+    database: bool,
+    workers: bool,
 }
 
-pub async fn healthcheck() -> Json<HealthResponse> {
-    Json(HealthResponse { status: "ok" })
+pub async fn live() -> Json<HealthResponse> {
+    Json(HealthResponse {
+        status: "ok",
+        checks: None,
+    })
+}
+
+pub async fn ready(
+    Extension(state): Extension<Arc<AppState>>,
+) -> (StatusCode, Json<ReadinessResponse>) {
+    let workers = state.workers_started.load(Ordering::Acquire) && !state.shutdown.is_cancelled();
+    let pending = tokio::time::timeout(READINESS_TIMEOUT, db::pending_migrations(&state.pool))
+        .await
+        .map_err(anyhow::Error::from)
+        .and_then(|pending| pending);
+    if let Err(err) = &pending {
+        warn!(error = %format!("{err:#}"), "readiness database check failed");
+    }
+    // Reading the migration table is the database round trip.
+    let database = pending.is_ok();
+    let migrations = matches!(pending, Ok(0));
+    let ready = workers && database && migrations;
+    (
+        if ready {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        },
+        Json(ReadinessResponse {
+            status: if ready { "ready" } else { "not_ready" },
+            migrations,
+            database,
+            workers,
+        }),
+    )
+}
+
+/// Runs one dependency check under `CHECK_TIMEOUT`, timing it.
+async fn check<F>(dependency: &'static str, probe: F) -> CheckResult
+where
+    F: Future<Output = anyhow::Result<()>>,
+{
+    let started = Instant::now();
+    let outcome = tokio::time::timeout(CHECK_TIMEOUT, probe).await;
+    let latency_ms = Some(started.elapsed().as_millis() as u64);
+    let error = match outcome {
+        Ok(Ok(())) => {
+            return CheckResult {
+                status: "ok",
+                latency_ms,
+            }
+        }
+        Ok(Err(err)) => format!("{err:#}"),
+        Err(_) => format!("timed out after {}s", CHECK_TIMEOUT.as_secs()),
+    };
+    warn!(dependency, error = %error, "health check failed");
+    CheckResult {
+        status: "error",
+        latency_ms,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn check_reports_failures_as_errors() {
+        let ok = check("ok", async { Ok(()) }).await;
+        assert_eq!(ok.status, "ok");
+        assert!(ok.latency_ms.is_some());
+
+        let failed = check("failed", async { anyhow::bail!("connection refused") }).await;
+        assert!(failed.failed());
+        assert!(failed.latency_ms.is_some());
+    }
 }
@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::Extension,
+    http::{HeaderMap, StatusCode},
+    routing::post,
+    Json, Router,
+};
+use tracing::warn;
+
+use crate::{
+    api::error::ApiError,
+    infrastructure::{netsuite, state::AppState},
+    services::{
+        errors::ServiceError,
+        netsuite_status::{self, CallbackOutcome, NetSuiteCallback},
+    },
+};
+
+const TIMESTAMP_HEADER: &str = "x-netsuite-timestamp";
+const SIGNATURE_HEADER: &str = "x-netsuite-signature";
+
+pub fn router() -> Router {
+    Router::new().route("/netsuite/callback", post(netsuite_callback))
+}
+
+/// Receives NetSuite's asynchronous processing result for an exported
+/// journal entry. Authenticated by HMAC signature rather than a user token.
+async fn netsuite_callback(
+    Extension(state): Extension<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<CallbackOutcome>, ApiError> {
+    let config = &state.config.netsuite;
+    if config.webhook_secret.is_none() {
+        return Err(ApiError::from(ServiceError::NotFound));
+    }
+
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+    };
+    if !netsuite::verify_callback(
+        config,
+        header(TIMESTAMP_HEADER),
+        &body,
+        header(SIGNATURE_HEADER),
+    ) {
+        warn!("rejected netsuite callback with an invalid signature");
+        return Err(invalid_signature());
+    }
+
+    let callback: NetSuiteCallback = serde_json::from_slice(&body).map_err(|err| {
+        ApiError::from(ServiceError::Validation(format!(
+            "invalid callback payload: {err}"
+        )))
+    })?;
+    let outcome = netsuite_status::apply_callback(&state, callback).await?;
+    Ok(Json(outcome))
+}
+
+fn invalid_signature() -> ApiError {
+    ApiError::new(
+        StatusCode::UNAUTHORIZED,
+        "invalid_signature",
+        "the callback signature is missing or does not match",
+    )
+}
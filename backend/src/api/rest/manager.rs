@@ -1,13 +1,13 @@
 use std::sync::Arc;
 
-use axum::{extract::Extension, http::StatusCode, routing::get, Json, Router};
-use serde::Serialize;
+use axum::{extract::Extension, routing::get, Json, Router};
 
 use crate::{
+    api::error::ApiError,
     infrastructure::{auth::AuthenticatedUser, state::AppState},
     services::{
-        errors::ServiceError,
         manager::{ManagerQueueEntry, ManagerService},
+        pagination::{PageRequest, Paginated},
     },
 };
 
@@ -18,22 +18,10 @@ pub fn router() -> Router {
 async fn queue(
     Extension(state): Extension<Arc<AppState>>,
     user: AuthenticatedUser,
-) -> Result<Json<ManagerQueueResponse>, (StatusCode, Json<serde_json::Value>)> {
+    page: PageRequest,
+) -> Result<Json<Paginated<ManagerQueueEntry>>, ApiError> {
     let service = ManagerService::new(state);
-    let queue = service.fetch_queue(&user).await.map_err(to_response)?;
+    let queue = service.fetch_queue(&user, &page).await?;
 
-    Ok(Json(ManagerQueueResponse { queue }))
-}
-
-#[derive(Serialize)]
-#[serde(rename_all = "camelCase")]
-struct ManagerQueueResponse {
-    queue: Vec<ManagerQueueEntry>,
-}
-
-fn to_response(err: ServiceError) -> (StatusCode, Json<serde_json::Value>) {
-    (
-        err.status_code(),
-        Json(serde_json::json!({ "error": err.to_string() })),
-    )
+    Ok(Json(queue))
 }
@@ -1,24 +1,43 @@
 use axum::{routing::get, Router};
 
 use crate::api::rest::{
-    approvals::router as approvals_router, auth::router as auth_router,
+    admin::router as admin_router, approvals::router as approvals_router,
+    auth::router as auth_router, employees::router as employees_router,
     expenses::router as expenses_router, finance::router as finance_router,
-    manager::router as manager_router,
+    integrations::router as integrations_router, manager::router as manager_router,
+    notifications::router as notifications_router, policy::router as policy_router,
+    retention::router as retention_router, trips::router as trips_router,
 };
 
+pub mod admin;
 pub mod approvals;
 pub mod auth;
+pub mod batch;
+pub mod employees;
 pub mod expenses;
 pub mod finance;
 pub mod health;
+pub mod integrations;
 pub mod manager;
+pub mod notifications;
+pub mod policy;
+pub mod receipts;
+pub mod retention;
+pub mod trips;
 
 pub fn router() -> Router {
     Router::new()
         .route("/health", get(health::healthcheck))
+        .nest("/admin", admin_router())
         .nest("/auth", auth_router())
+        .nest("/employees", employees_router())
         .nest("/expenses", expenses_router())
         .nest("/approvals", approvals_router())
         .nest("/finance", finance_router())
         .nest("/manager", manager_router())
+        .nest("/notifications", notifications_router())
+        .nest("/policy", policy_router())
+        .nest("/retention", retention_router())
+        .nest("/trips", trips_router())
+        .nest("/integrations", integrations_router())
 }
@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use axum::{extract::Extension, routing::get, Json, Router};
+
+use crate::{
+    api::error::ApiError,
+    infrastructure::{auth::AuthenticatedUser, state::AppState},
+    services::notifications::{
+        NotificationPreferences, NotificationService, UpdatePreferencesRequest,
+    },
+};
+
+pub fn router() -> Router {
+    Router::new().route("/preferences", get(get_preferences).put(update_preferences))
+}
+
+async fn get_preferences(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> Result<Json<NotificationPreferences>, ApiError> {
+    let service = NotificationService::new(state);
+    let preferences = service.preferences(&user).await?;
+    Ok(Json(preferences))
+}
+
+async fn update_preferences(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(payload): Json<UpdatePreferencesRequest>,
+) -> Result<Json<NotificationPreferences>, ApiError> {
+    let service = NotificationService::new(state);
+    let preferences = service.update_preferences(&user, payload).await?;
+    Ok(Json(preferences))
+}
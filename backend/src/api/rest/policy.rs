@@ -0,0 +1,362 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Extension, Path, Query},
+    http::{HeaderMap, StatusCode},
+    response::Response,
+    routing::{get, post, put},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    api::{error::ApiError, reference},
+    domain::models::{
+        Budget, DepartmentHead, Holiday, MileageRate, PerDiemRate, PolicyRule, PolicyVersion,
+    },
+    infrastructure::{auth::AuthenticatedUser, reference_cache::ReferenceData, state::AppState},
+    services::{
+        budgets::{BudgetRequest, BudgetService},
+        department_heads::{DepartmentHeadRequest, DepartmentHeadService},
+        holidays::{HolidayRequest, HolidayService},
+        mileage_rates::{IrsImportRequest, MileageRateRequest, MileageRateService},
+        per_diem::{PerDiemImport, PerDiemService},
+        policy_rules::{PolicyRuleRequest, PolicyRuleService},
+        policy_versions::PolicyVersionService,
+    },
+};
+
+#[derive(Serialize)]
+struct PolicyRuleListResponse {
+    rules: Vec<PolicyRule>,
+}
+
+#[derive(Serialize)]
+struct PolicyVersionListResponse {
+    versions: Vec<PolicyVersion>,
+}
+
+#[derive(Serialize)]
+struct BudgetListResponse {
+    budgets: Vec<Budget>,
+}
+
+#[derive(Serialize)]
+struct DepartmentHeadListResponse {
+    department_heads: Vec<DepartmentHead>,
+}
+
+#[derive(Serialize)]
+struct HolidayListResponse {
+    holidays: Vec<Holiday>,
+}
+
+#[derive(Deserialize)]
+struct HolidayListQuery {
+    year: Option<i32>,
+}
+
+#[derive(Serialize)]
+struct MileageRateListResponse {
+    rates: Vec<MileageRate>,
+}
+
+#[derive(Serialize)]
+struct PerDiemRateListResponse {
+    rates: Vec<PerDiemRate>,
+}
+
+#[derive(Deserialize)]
+struct PerDiemListQuery {
+    fiscal_year: Option<i32>,
+}
+
+#[derive(Deserialize)]
+struct PerDiemImportQuery {
+    fiscal_year: i32,
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/rules", get(list_rules).post(create_rule))
+        .route("/rules/:id", put(update_rule).delete(delete_rule))
+        .route("/versions", get(list_versions))
+        .route("/budgets", get(list_budgets).post(create_budget))
+        .route("/budgets/:id", put(update_budget).delete(delete_budget))
+        .route(
+            "/department-heads",
+            get(list_department_heads).post(create_department_head),
+        )
+        .route(
+            "/department-heads/:id",
+            put(update_department_head).delete(delete_department_head),
+        )
+        .route("/holidays", get(list_holidays).post(create_holiday))
+        .route("/holidays/:id", put(update_holiday).delete(delete_holiday))
+        .route(
+            "/mileage-rates",
+            get(list_mileage_rates).post(create_mileage_rate),
+        )
+        .route(
+            "/mileage-rates/:id",
+            put(update_mileage_rate).delete(delete_mileage_rate),
+        )
+        .route("/mileage-rates/import-irs", post(import_irs_mileage_rates))
+        .route("/per-diem-rates", get(list_per_diem_rates))
+        .route("/per-diem-rates/import", post(import_per_diem_rates))
+}
+
+async fn list_rules(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let service = PolicyRuleService::new(Arc::clone(&state));
+    let load = async {
+        let rules = service.list(&user).await?;
+        Ok(PolicyRuleListResponse { rules })
+    };
+    reference::respond(&state, &user, &headers, ReferenceData::PolicyRules, load).await
+}
+
+async fn create_rule(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(payload): Json<PolicyRuleRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let service = PolicyRuleService::new(state);
+    let rule = service.create(&user, payload).await?;
+    Ok(Json(serde_json::json!({ "rule": rule })))
+}
+
+async fn update_rule(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<PolicyRuleRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let service = PolicyRuleService::new(state);
+    let rule = service.update(&user, id, payload).await?;
+    Ok(Json(serde_json::json!({ "rule": rule })))
+}
+
+async fn delete_rule(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let service = PolicyRuleService::new(state);
+    service.delete(&user, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_versions(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> Result<Json<PolicyVersionListResponse>, ApiError> {
+    let service = PolicyVersionService::new(state);
+    let versions = service.list(&user).await?;
+    Ok(Json(PolicyVersionListResponse { versions }))
+}
+
+async fn list_budgets(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> Result<Json<BudgetListResponse>, ApiError> {
+    let service = BudgetService::new(state);
+    let budgets = service.list(&user).await?;
+    Ok(Json(BudgetListResponse { budgets }))
+}
+
+async fn create_budget(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(payload): Json<BudgetRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let service = BudgetService::new(state);
+    let budget = service.create(&user, payload).await?;
+    Ok(Json(serde_json::json!({ "budget": budget })))
+}
+
+async fn update_budget(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<BudgetRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let service = BudgetService::new(state);
+    let budget = service.update(&user, id, payload).await?;
+    Ok(Json(serde_json::json!({ "budget": budget })))
+}
+
+async fn delete_budget(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let service = BudgetService::new(state);
+    service.delete(&user, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_department_heads(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> Result<Json<DepartmentHeadListResponse>, ApiError> {
+    let service = DepartmentHeadService::new(state);
+    let department_heads = service.list(&user).await?;
+    Ok(Json(DepartmentHeadListResponse { department_heads }))
+}
+
+async fn create_department_head(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(payload): Json<DepartmentHeadRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let service = DepartmentHeadService::new(state);
+    let department_head = service.create(&user, payload).await?;
+    Ok(Json(
+        serde_json::json!({ "department_head": department_head }),
+    ))
+}
+
+async fn update_department_head(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<DepartmentHeadRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let service = DepartmentHeadService::new(state);
+    let department_head = service.update(&user, id, payload).await?;
+    Ok(Json(
+        serde_json::json!({ "department_head": department_head }),
+    ))
+}
+
+async fn delete_department_head(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let service = DepartmentHeadService::new(state);
+    service.delete(&user, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_holidays(
+    Extension(state): Extension<Arc<AppState>>,
+    _user: AuthenticatedUser,
+    Query(query): Query<HolidayListQuery>,
+) -> Result<Json<HolidayListResponse>, ApiError> {
+    let service = HolidayService::new(state);
+    let holidays = service.list(query.year).await?;
+    Ok(Json(HolidayListResponse { holidays }))
+}
+
+async fn create_holiday(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(payload): Json<HolidayRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let service = HolidayService::new(state);
+    let holiday = service.create(&user, payload).await?;
+    Ok(Json(serde_json::json!({ "holiday": holiday })))
+}
+
+async fn update_holiday(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<HolidayRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let service = HolidayService::new(state);
+    let holiday = service.update(&user, id, payload).await?;
+    Ok(Json(serde_json::json!({ "holiday": holiday })))
+}
+
+async fn delete_holiday(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let service = HolidayService::new(state);
+    service.delete(&user, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_mileage_rates(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let service = MileageRateService::new(Arc::clone(&state));
+    let load = async {
+        let rates = service.list().await?;
+        Ok(MileageRateListResponse { rates })
+    };
+    reference::respond(&state, &user, &headers, ReferenceData::MileageRates, load).await
+}
+
+async fn create_mileage_rate(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(payload): Json<MileageRateRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let service = MileageRateService::new(state);
+    let rate = service.create(&user, payload).await?;
+    Ok(Json(serde_json::json!({ "rate": rate })))
+}
+
+async fn update_mileage_rate(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<MileageRateRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let service = MileageRateService::new(state);
+    let rate = service.update(&user, id, payload).await?;
+    Ok(Json(serde_json::json!({ "rate": rate })))
+}
+
+async fn delete_mileage_rate(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let service = MileageRateService::new(state);
+    service.delete(&user, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn import_irs_mileage_rates(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(payload): Json<IrsImportRequest>,
+) -> Result<Json<MileageRateListResponse>, ApiError> {
+    let service = MileageRateService::new(state);
+    let rates = service.import_irs(&user, payload).await?;
+    Ok(Json(MileageRateListResponse { rates }))
+}
+
+async fn list_per_diem_rates(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Query(query): Query<PerDiemListQuery>,
+) -> Result<Json<PerDiemRateListResponse>, ApiError> {
+    let service = PerDiemService::new(state);
+    let rates = service.list(&user, query.fiscal_year).await?;
+    Ok(Json(PerDiemRateListResponse { rates }))
+}
+
+/// Accepts the GSA per-diem rates CSV as the request body.
+async fn import_per_diem_rates(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Query(query): Query<PerDiemImportQuery>,
+    body: String,
+) -> Result<Json<PerDiemImport>, ApiError> {
+    let service = PerDiemService::new(state);
+    let summary = service.import_gsa(&user, query.fiscal_year, &body).await?;
+    Ok(Json(summary))
+}
@@ -0,0 +1,140 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{multipart::Field, DefaultBodyLimit, Extension, Multipart},
+    routing::post,
+    Json, Router,
+};
+use bytes::{Bytes, BytesMut};
+
+use crate::{
+    api::error::ApiError,
+    infrastructure::{auth::AuthenticatedUser, config::ReceiptRules, state::AppState},
+    services::{
+        assistants::{AssistantService, OnBehalfOf},
+        errors::ServiceError,
+        receipts::ReceiptService,
+    },
+};
+
+/// Multipart part carrying a receipt file.
+const FILE_FIELD: &str = "file";
+
+/// Room for multipart boundaries and part headers on top of the files
+/// themselves when `build_router` sizes the body limit.
+pub const MULTIPART_OVERHEAD_BYTES: u64 = 64 * 1024;
+
+/// The receipt upload route, the only one, mounted at `/api/receipts` by
+/// `build_router` with a body limit of `upload_body_limit` and the upload
+/// timeout.
+pub fn upload_router() -> Router {
+    Router::new().route(
+        "/",
+        post(upload_receipts).layer(DefaultBodyLimit::disable()),
+    )
+}
+
+/// Largest body `POST /api/receipts` accepts: as many files of
+/// `receipts.max_bytes` as an item may carry, plus multipart framing.
+pub fn upload_body_limit(rules: &ReceiptRules) -> u64 {
+    rules
+        .max_bytes
+        .saturating_mul(u64::from(rules.max_files_per_item))
+        .saturating_add(MULTIPART_OVERHEAD_BYTES)
+}
+
+/// Stores each `file` part of a multipart body through `ReceiptService`,
+/// which strips EXIF metadata, and returns their keys and metadata, in the
+/// order they were sent. Other parts are ignored.
+async fn upload_receipts(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    on_behalf_of: OnBehalfOf,
+    mut multipart: Multipart,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let acting = AssistantService::new(state.clone())
+        .act_for(&user, on_behalf_of)
+        .await?;
+    let rules = state.config.receipts.clone();
+    let mut files = Vec::new();
+    while let Some(field) = multipart.next_field().await.map_err(invalid_body)? {
+        if field.name() != Some(FILE_FIELD) {
+            continue;
+        }
+        if files.len() as u32 >= rules.max_files_per_item {
+            return Err(ServiceError::Validation(format!(
+                "cannot upload more than {} receipts at once",
+                rules.max_files_per_item
+            ))
+            .into());
+        }
+        let file_name = field.file_name().unwrap_or_default().to_string();
+        let mime_type = field
+            .content_type()
+            .unwrap_or("application/octet-stream")
+            .to_string();
+        if file_name.trim().is_empty() {
+            return Err(ServiceError::Validation("each file needs a file name".into()).into());
+        }
+        let data = read_file(field, rules.max_bytes).await?;
+        if data.is_empty() {
+            return Err(ServiceError::Validation(format!("{file_name} is empty")).into());
+        }
+        files.push((file_name, mime_type, data));
+    }
+    if files.is_empty() {
+        return Err(ServiceError::Validation(format!("a `{FILE_FIELD}` part is required")).into());
+    }
+
+    // Every file is read before any is stored, so a rejected request stores
+    // nothing.
+    let service = ReceiptService::new(state);
+    let mut receipts = Vec::with_capacity(files.len());
+    for (file_name, mime_type, data) in files {
+        let receipt = acting
+            .run(service.upload(&acting.user, &file_name, &mime_type, data))
+            .await?;
+        receipts.push(receipt);
+    }
+    Ok(Json(serde_json::json!({ "receipts": receipts })))
+}
+
+/// Buffers one file, refusing it as soon as it exceeds `max_bytes`.
+async fn read_file(mut field: Field<'_>, max_bytes: u64) -> Result<Bytes, ApiError> {
+    let mut data = BytesMut::new();
+    while let Some(chunk) = field.chunk().await.map_err(invalid_body)? {
+        if (data.len() + chunk.len()) as u64 > max_bytes {
+            return Err(ServiceError::Validation(format!(
+                "exceeds maximum size of {max_bytes} bytes"
+            ))
+            .into());
+        }
+        data.extend_from_slice(&chunk);
+    }
+    Ok(data.freeze())
+}
+
+fn invalid_body(err: axum::extract::multipart::MultipartError) -> ApiError {
+    ServiceError::Validation(format!("invalid multipart body: {}", err.body_text())).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn body_limit_fits_every_file_an_item_may_carry() {
+        let rules = ReceiptRules {
+            max_bytes: 1_000,
+            max_files_per_item: 3,
+            ..ReceiptRules::default()
+        };
+        assert_eq!(upload_body_limit(&rules), 3_000 + MULTIPART_OVERHEAD_BYTES);
+
+        let unbounded = ReceiptRules {
+            max_bytes: u64::MAX,
+            ..rules
+        };
+        assert_eq!(upload_body_limit(&unbounded), u64::MAX);
+    }
+}
@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Extension, Path},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    api::error::ApiError,
+    infrastructure::{auth::AuthenticatedUser, state::AppState},
+    services::retention::{ExecuteRetentionRequest, RetentionRun, RetentionService},
+};
+
+#[derive(Serialize)]
+struct RetentionRunListResponse {
+    runs: Vec<RetentionRun>,
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/runs", get(list_runs).post(plan_run))
+        .route("/runs/:id/execute", post(execute_run))
+}
+
+async fn list_runs(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> Result<Json<RetentionRunListResponse>, ApiError> {
+    let service = RetentionService::new(state);
+    let runs = service.list(&user).await?;
+    Ok(Json(RetentionRunListResponse { runs }))
+}
+
+async fn plan_run(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> Result<Json<RetentionRun>, ApiError> {
+    let service = RetentionService::new(state);
+    let run = service.plan(&user).await?;
+    Ok(Json(run))
+}
+
+async fn execute_run(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(run_id): Path<Uuid>,
+    Json(payload): Json<ExecuteRetentionRequest>,
+) -> Result<Json<RetentionRun>, ApiError> {
+    let service = RetentionService::new(state);
+    let run = service.execute(&user, run_id, payload).await?;
+    Ok(Json(run))
+}
@@ -0,0 +1,57 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Extension, Path},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    api::error::ApiError,
+    domain::models::Trip,
+    infrastructure::{auth::AuthenticatedUser, state::AppState},
+    services::trips::{TripRequest, TripReviewRequest, TripService},
+};
+
+#[derive(Serialize)]
+struct TripListResponse {
+    trips: Vec<Trip>,
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/", get(list_trips).post(create_trip))
+        .route("/:id/review", post(review_trip))
+}
+
+async fn list_trips(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> Result<Json<TripListResponse>, ApiError> {
+    let service = TripService::new(state);
+    let trips = service.list(&user).await?;
+    Ok(Json(TripListResponse { trips }))
+}
+
+async fn create_trip(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(payload): Json<TripRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let service = TripService::new(state);
+    let trip = service.create(&user, payload).await?;
+    Ok(Json(serde_json::json!({ "trip": trip })))
+}
+
+async fn review_trip(
+    Extension(state): Extension<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<TripReviewRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let service = TripService::new(state);
+    let trip = service.review(&user, id, payload).await?;
+    Ok(Json(serde_json::json!({ "trip": trip })))
+}
@@ -0,0 +1,184 @@
+//! Declarative request validation.
+//!
+//! Payloads implement [`Validate`] by stating their rules against a
+//! [`Validator`], which collects every problem under its field path
+//! (`items.0.receipts.1.size_bytes`) rather than stopping at the first.
+//! Handlers extract [`Valid<T>`] in place of `Json<T>`: a body that does not
+//! parse, or parses but breaks a rule, is refused before the handler runs,
+//! the latter with `422 validation_failed` listing the fields.
+
+use std::{collections::BTreeMap, fmt::Display, sync::Arc};
+
+use axum::{
+    async_trait,
+    extract::{FromRequest, Request},
+    http::StatusCode,
+    Json,
+};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    api::error::ApiError,
+    infrastructure::{config::Config, state::AppState},
+    services::errors::ServiceError,
+};
+
+/// A request payload with rules beyond what deserializing it checks.
+pub trait Validate {
+    /// Records every problem with `self` on `v`. `config` supplies the
+    /// configured limits some rules depend on.
+    fn validate(&self, config: &Config, v: &mut Validator);
+}
+
+/// Collects validation problems by field path.
+#[derive(Debug, Default)]
+pub struct Validator {
+    errors: BTreeMap<String, Vec<String>>,
+    path: Vec<String>,
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records `message` against `field`, under the current nesting.
+    pub fn error(&mut self, field: &str, message: impl Into<String>) {
+        let key = self
+            .path
+            .iter()
+            .map(String::as_str)
+            .chain((!field.is_empty()).then_some(field))
+            .collect::<Vec<_>>()
+            .join(".");
+        self.errors.entry(key).or_default().push(message.into());
+    }
+
+    /// Records `message` unless `ok`, and returns `ok`.
+    pub fn check(&mut self, ok: bool, field: &str, message: impl Into<String>) -> bool {
+        if !ok {
+            self.error(field, message);
+        }
+        ok
+    }
+
+    /// Requires `value` to have non-whitespace content.
+    pub fn required(&mut self, field: &str, value: &str) -> bool {
+        self.check(
+            !value.trim().is_empty(),
+            field,
+            format!("{field} is required"),
+        )
+    }
+
+    /// Caps an optional free-text field at `max` characters.
+    pub fn max_chars(&mut self, field: &str, value: Option<&str>, max: usize) -> bool {
+        let ok = value.is_none_or(|value| value.chars().count() <= max);
+        self.check(ok, field, format!("must be at most {max} characters"))
+    }
+
+    /// Runs `rules` with field paths nested under `segment`.
+    pub fn nested(&mut self, segment: impl Display, rules: impl FnOnce(&mut Self)) {
+        self.path.push(segment.to_string());
+        rules(self);
+        self.path.pop();
+    }
+
+    /// Runs `rules` for each element of a list field, nesting its problems
+    /// under `field.<index>`.
+    pub fn each<'a, T: 'a>(
+        &mut self,
+        field: &str,
+        items: impl IntoIterator<Item = &'a T>,
+        mut rules: impl FnMut(&mut Self, &'a T),
+    ) {
+        self.nested(field, |v| {
+            for (index, item) in items.into_iter().enumerate() {
+                v.nested(index, |v| rules(v, item));
+            }
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn into_errors(self) -> BTreeMap<String, Vec<String>> {
+        self.errors
+    }
+
+    /// `Ok` when nothing was recorded, else the `422` listing every field.
+    pub fn finish(self) -> Result<(), ApiError> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ApiError::validation(self.errors))
+        }
+    }
+}
+
+/// A JSON body that deserialized and passed its [`Validate`] rules.
+#[derive(Debug)]
+pub struct Valid<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for Valid<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let app = request.extensions().get::<Arc<AppState>>().cloned();
+        let Json(payload) = Json::<T>::from_request(request, state)
+            .await
+            .map_err(|rejection| {
+                let status = rejection.status();
+                let code = if status == StatusCode::UNSUPPORTED_MEDIA_TYPE {
+                    "unsupported_media_type"
+                } else {
+                    "invalid_body"
+                };
+                ApiError::new(status, code, rejection.body_text())
+            })?;
+        let Some(app) = app else {
+            return Err(ServiceError::Internal("application state unavailable".into()).into());
+        };
+        let mut v = Validator::new();
+        payload.validate(&app.config, &mut v);
+        v.finish()?;
+        Ok(Valid(payload))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn problems_are_collected_under_nested_field_paths() {
+        let items = ["ok", " ", ""];
+        let mut v = Validator::new();
+        v.required("currency", "");
+        v.each("items", &items, |v, item| {
+            v.nested("receipts", |v| {
+                v.required("file_key", item);
+            });
+        });
+        v.max_chars("comments", Some("four"), 3);
+        v.max_chars("notes", None, 3);
+
+        let errors = v.into_errors();
+        assert_eq!(errors["currency"], ["currency is required"]);
+        assert!(!errors.contains_key("items.0.receipts.file_key"));
+        assert_eq!(
+            errors["items.1.receipts.file_key"],
+            ["file_key is required"]
+        );
+        assert!(errors.contains_key("items.2.receipts.file_key"));
+        assert_eq!(errors["comments"], ["must be at most 3 characters"]);
+        assert!(!errors.contains_key("notes"));
+        assert!(Validator::new().finish().is_ok());
+    }
+}
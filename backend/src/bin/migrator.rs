@@ -8,13 +8,17 @@ use tracing::info;
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     dotenv().ok();
-    telemetry::init();
+    let mut config = Config::from_env()?;
+    telemetry::init(&config.telemetry)?;
+    // Index builds and backfills may legitimately run longer than any
+    // request should.
+    config.database.statement_timeout_ms = 0;
 
-    let config = Config::from_env()?;
     let pool = db::connect(&config.database).await?;
     db::run_migrations(&pool).await?;
 
     info!("database migrations completed");
+    telemetry::shutdown();
 
     Ok(())
 }
@@ -0,0 +1,437 @@
+//! Populates a database with demo data: an employee in every role with a
+//! manager chain, policy caps, mileage rates, and sample reports in every
+//! status. Run after the migrator:
+//!
+//! ```text
+//! cargo run --bin seed -- [--managers N] [--employees-per-manager N]
+//!     [--reports-per-status N] [--no-policy] [--no-mileage] [--no-reports]
+//! ```
+//!
+//! Employees, policy caps, and mileage rates are keyed by fixed HR
+//! identifiers, ids, and dates, so re-running leaves them as they are; each
+//! run adds another set of sample reports unless `--no-reports` is passed.
+
+use std::env;
+
+use anyhow::{bail, Context};
+use chrono::{Duration, NaiveDate, Utc};
+use dotenvy::dotenv;
+use expense_portal::{
+    domain::models::{
+        ApprovalStatus, ExpenseCategory, ReportStatus, Role, RuleComparison, RuleCondition,
+        RuleScope, RuleSeverity,
+    },
+    infrastructure::{config::Config, db},
+    telemetry,
+};
+use sqlx::{PgPool, Postgres, Transaction};
+use tracing::info;
+use uuid::Uuid;
+
+const USAGE: &str = "usage: seed [--managers N] [--employees-per-manager N] \
+[--reports-per-status N] [--no-policy] [--no-mileage] [--no-reports]";
+
+/// Department every seeded employee belongs to.
+const DEPARTMENT: &str = "Operations";
+
+const STATUSES: [ReportStatus; 7] = [
+    ReportStatus::Draft,
+    ReportStatus::Submitted,
+    ReportStatus::NeedsChanges,
+    ReportStatus::Denied,
+    ReportStatus::ExceptionReview,
+    ReportStatus::ManagerApproved,
+    ReportStatus::FinanceFinalized,
+];
+
+/// Seeded policy caps: fixed id, name, category, scope, threshold, message.
+const POLICY_CAPS: [(u128, &str, ExpenseCategory, RuleScope, i64, &str); 3] = [
+    (
+        0x5eed_0001,
+        "Seed: meal cap",
+        ExpenseCategory::Meal,
+        RuleScope::Item,
+        7_500,
+        "Meal exceeds the limit of {limit}",
+    ),
+    (
+        0x5eed_0002,
+        "Seed: daily lodging cap",
+        ExpenseCategory::Lodging,
+        RuleScope::Day,
+        25_000,
+        "Lodging on {date} exceeds the nightly limit of {limit}",
+    ),
+    (
+        0x5eed_0003,
+        "Seed: airfare cap",
+        ExpenseCategory::Airfare,
+        RuleScope::Item,
+        80_000,
+        "Airfare exceeds the limit of {limit}",
+    ),
+];
+
+/// Seeded mileage rates: effective date and cents per mile.
+const MILEAGE_RATES: [((i32, u32, u32), f64); 2] = [((2023, 1, 1), 65.5), ((2024, 1, 1), 67.0)];
+
+#[derive(Debug)]
+struct Options {
+    managers: usize,
+    employees_per_manager: usize,
+    reports_per_status: usize,
+    policy: bool,
+    mileage: bool,
+    reports: bool,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> anyhow::Result<Self> {
+        let mut options = Options {
+            managers: 2,
+            employees_per_manager: 3,
+            reports_per_status: 1,
+            policy: true,
+            mileage: true,
+            reports: true,
+        };
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--managers" => options.managers = count(&arg, args.next())?,
+                "--employees-per-manager" => {
+                    options.employees_per_manager = count(&arg, args.next())?
+                }
+                "--reports-per-status" => options.reports_per_status = count(&arg, args.next())?,
+                "--no-policy" => options.policy = false,
+                "--no-mileage" => options.mileage = false,
+                "--no-reports" => options.reports = false,
+                _ => bail!("unrecognized argument `{arg}`\n{USAGE}"),
+            }
+        }
+        if options.managers == 0 || options.employees_per_manager == 0 {
+            bail!("--managers and --employees-per-manager must be at least 1");
+        }
+        Ok(options)
+    }
+}
+
+fn count(flag: &str, value: Option<String>) -> anyhow::Result<usize> {
+    value
+        .with_context(|| format!("{flag} needs a value\n{USAGE}"))?
+        .parse()
+        .with_context(|| format!("{flag} must be a non-negative number"))
+}
+
+/// The seeded employees who act on sample reports.
+struct Staff {
+    finance: Uuid,
+    director: Uuid,
+    /// Each manager with the employees reporting to them.
+    teams: Vec<(Uuid, Vec<Uuid>)>,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    dotenv().ok();
+    let options = Options::parse(env::args().skip(1))?;
+    let config = Config::from_env()?;
+    telemetry::init(&config.telemetry)?;
+
+    let pool = db::connect(&config.database).await?;
+    let staff = seed_employees(&pool, &options).await?;
+    if options.policy {
+        seed_policy_caps(&pool).await?;
+    }
+    if options.mileage {
+        seed_mileage_rates(&pool).await?;
+    }
+    if options.reports {
+        seed_reports(&pool, &options, &staff).await?;
+    }
+
+    info!(?options, "database seeded");
+    telemetry::shutdown();
+
+    Ok(())
+}
+
+/// An admin at the top, a finance user and a director reporting to them,
+/// and `--managers` managers under the director, each with
+/// `--employees-per-manager` employees. The director approves policy
+/// exceptions for the department.
+async fn seed_employees(pool: &PgPool, options: &Options) -> anyhow::Result<Staff> {
+    let mut tx = pool.begin().await?;
+    let admin = upsert_employee(&mut tx, "SEED-ADMIN", None, Role::Admin).await?;
+    let finance = upsert_employee(&mut tx, "SEED-FINANCE", Some(admin), Role::Finance).await?;
+    let director = upsert_employee(&mut tx, "SEED-DIRECTOR", Some(admin), Role::Manager).await?;
+    let mut teams = Vec::with_capacity(options.managers);
+    for m in 1..=options.managers {
+        let manager = upsert_employee(
+            &mut tx,
+            &format!("SEED-MANAGER-{m}"),
+            Some(director),
+            Role::Manager,
+        )
+        .await?;
+        let mut employees = Vec::with_capacity(options.employees_per_manager);
+        for e in 1..=options.employees_per_manager {
+            employees.push(
+                upsert_employee(
+                    &mut tx,
+                    &format!("SEED-EMPLOYEE-{m}-{e}"),
+                    Some(manager),
+                    Role::Employee,
+                )
+                .await?,
+            );
+        }
+        teams.push((manager, employees));
+    }
+    sqlx::query(
+        "INSERT INTO department_heads (id, department, employee_id, updated_by, updated_at)
+         VALUES ($1,$2,$3,$4,$5)
+         ON CONFLICT (org_id, department) DO NOTHING",
+    )
+    .bind(Uuid::new_v4())
+    .bind(DEPARTMENT)
+    .bind(director)
+    .bind(admin)
+    .bind(Utc::now())
+    .execute(tx.as_mut())
+    .await?;
+    tx.commit().await?;
+    info!(
+        managers = options.managers,
+        employees_per_manager = options.employees_per_manager,
+        "seeded employees"
+    );
+    Ok(Staff {
+        finance,
+        director,
+        teams,
+    })
+}
+
+/// Returns the id of the employee with `hr_identifier`, adding them first if
+/// they do not exist.
+async fn upsert_employee(
+    tx: &mut Transaction<'_, Postgres>,
+    hr_identifier: &str,
+    manager_id: Option<Uuid>,
+    role: Role,
+) -> anyhow::Result<Uuid> {
+    sqlx::query(
+        "INSERT INTO employees (id, hr_identifier, manager_id, department, role, created_at)
+         VALUES ($1,$2,$3,$4,$5,$6)
+         ON CONFLICT (hr_identifier) DO NOTHING",
+    )
+    .bind(Uuid::new_v4())
+    .bind(hr_identifier)
+    .bind(manager_id)
+    .bind(DEPARTMENT)
+    .bind(role)
+    .bind(Utc::now())
+    .execute(tx.as_mut())
+    .await?;
+    let id = sqlx::query_scalar("SELECT id FROM employees WHERE hr_identifier = $1")
+        .bind(hr_identifier)
+        .fetch_one(tx.as_mut())
+        .await?;
+    Ok(id)
+}
+
+async fn seed_policy_caps(pool: &PgPool) -> anyhow::Result<()> {
+    for (id, name, category, scope, threshold_cents, message) in POLICY_CAPS {
+        sqlx::query(
+            "INSERT INTO policy_rules
+                (id, name, category, comparison, threshold_cents, scope, severity, message,
+                 active_from, condition)
+             VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10)
+             ON CONFLICT (id) DO NOTHING",
+        )
+        .bind(Uuid::from_u128(id))
+        .bind(name)
+        .bind(category.as_str())
+        .bind(RuleComparison::Gt.as_str())
+        .bind(threshold_cents)
+        .bind(scope.as_str())
+        .bind(RuleSeverity::Violation.as_str())
+        .bind(message)
+        .bind(NaiveDate::from_ymd_opt(2023, 1, 1))
+        .bind(RuleCondition::Always.as_str())
+        .execute(pool)
+        .await?;
+    }
+    info!(count = POLICY_CAPS.len(), "seeded policy caps");
+    Ok(())
+}
+
+async fn seed_mileage_rates(pool: &PgPool) -> anyhow::Result<()> {
+    for ((year, month, day), rate) in MILEAGE_RATES {
+        sqlx::query(
+            "INSERT INTO mileage_rates (id, effective_date, rate_cents_per_mile, source_reference)
+             VALUES ($1,$2,$3::numeric,$4)
+             ON CONFLICT (effective_date) DO NOTHING",
+        )
+        .bind(Uuid::new_v4())
+        .bind(NaiveDate::from_ymd_opt(year, month, day))
+        .bind(rate)
+        .bind(format!("IRS standard mileage rate {year}"))
+        .execute(pool)
+        .await?;
+    }
+    info!(count = MILEAGE_RATES.len(), "seeded mileage rates");
+    Ok(())
+}
+
+/// `--reports-per-status` reports in every status, spread across the seeded
+/// employees, each with the approvals that would have moved it there.
+async fn seed_reports(pool: &PgPool, options: &Options, staff: &Staff) -> anyhow::Result<()> {
+    let owners: Vec<(Uuid, Uuid)> = staff
+        .teams
+        .iter()
+        .flat_map(|(manager, employees)| employees.iter().map(|employee| (*employee, *manager)))
+        .collect();
+    let mut tx = pool.begin().await?;
+    let mut seeded = 0;
+    for status in STATUSES {
+        for n in 0..options.reports_per_status {
+            let (employee, manager) = owners[seeded % owners.len()];
+            seed_report(&mut tx, staff, status, employee, manager, n).await?;
+            seeded += 1;
+        }
+    }
+    tx.commit().await?;
+    info!(count = seeded, "seeded reports");
+    Ok(())
+}
+
+async fn seed_report(
+    tx: &mut Transaction<'_, Postgres>,
+    staff: &Staff,
+    status: ReportStatus,
+    employee: Uuid,
+    manager: Uuid,
+    n: usize,
+) -> anyhow::Result<()> {
+    let now = Utc::now();
+    let period_end = now.date_naive() - Duration::days(7 * (n as i64 + 1));
+    let period_start = period_end - Duration::days(6);
+    let exception = status == ReportStatus::ExceptionReview;
+    // Meal, lodging, and a mileage claim at 2024's rate; the exception
+    // report's dinner runs over the seeded meal cap.
+    let items = [
+        (
+            period_start,
+            ExpenseCategory::Meal,
+            "Client dinner",
+            if exception { 12_500 } else { 4_800 },
+            None,
+        ),
+        (
+            period_start + Duration::days(1),
+            ExpenseCategory::Lodging,
+            "Hotel, one night",
+            18_900,
+            None,
+        ),
+        (
+            period_start + Duration::days(2),
+            ExpenseCategory::Mileage,
+            "Drive to terminal",
+            4_020,
+            Some(60.0),
+        ),
+    ];
+    let total: i64 = items.iter().map(|item| item.3).sum();
+    let submitted_at = (status != ReportStatus::Draft).then_some(now);
+
+    let report_id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO expense_reports
+            (id, employee_id, reporting_period_start, reporting_period_end, status,
+             total_amount_cents, total_reimbursable_cents, currency, created_at, updated_at,
+             submitted_at, exception_approver_id)
+         VALUES ($1,$2,$3,$4,$5,$6,$6,'USD',$7,$7,$8,$9)",
+    )
+    .bind(report_id)
+    .bind(employee)
+    .bind(period_start)
+    .bind(period_end)
+    .bind(status)
+    .bind(total)
+    .bind(now)
+    .bind(submitted_at)
+    .bind(exception.then_some(staff.director))
+    .execute(tx.as_mut())
+    .await?;
+
+    for (index, (expense_date, category, description, amount_cents, miles)) in
+        items.into_iter().enumerate()
+    {
+        let over_cap = exception && index == 0;
+        sqlx::query(
+            "INSERT INTO expense_items
+                (id, report_id, expense_date, category, description, amount_cents, reimbursable,
+                 is_policy_exception, exception_justification, miles)
+             VALUES ($1,$2,$3,$4,$5,$6,TRUE,$7,$8,$9::numeric)",
+        )
+        .bind(Uuid::new_v4())
+        .bind(report_id)
+        .bind(expense_date)
+        .bind(category)
+        .bind(description)
+        .bind(amount_cents)
+        .bind(over_cap)
+        .bind(over_cap.then_some("Hosted the customer's leadership team"))
+        .bind(miles)
+        .execute(tx.as_mut())
+        .await?;
+    }
+
+    let manager_decision = match status {
+        ReportStatus::Draft | ReportStatus::Submitted => None,
+        ReportStatus::NeedsChanges => Some(ApprovalStatus::NeedsChanges),
+        ReportStatus::Denied => Some(ApprovalStatus::Denied),
+        ReportStatus::ExceptionReview
+        | ReportStatus::ManagerApproved
+        | ReportStatus::FinanceFinalized => Some(ApprovalStatus::Approved),
+    };
+    if let Some(decision) = manager_decision {
+        insert_approval(tx, report_id, manager, Role::Manager, decision).await?;
+    }
+    if status == ReportStatus::FinanceFinalized {
+        insert_approval(
+            tx,
+            report_id,
+            staff.finance,
+            Role::Finance,
+            ApprovalStatus::Approved,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+async fn insert_approval(
+    tx: &mut Transaction<'_, Postgres>,
+    report_id: Uuid,
+    approver_id: Uuid,
+    role: Role,
+    status: ApprovalStatus,
+) -> anyhow::Result<()> {
+    sqlx::query(
+        "INSERT INTO approvals (id, report_id, approver_id, role, status, comments, created_at)
+         VALUES ($1,$2,$3,$4,$5,$6,$7)",
+    )
+    .bind(Uuid::new_v4())
+    .bind(report_id)
+    .bind(approver_id)
+    .bind(role)
+    .bind(status)
+    .bind(format!("Seeded {} decision", status.as_str()))
+    .bind(Utc::now())
+    .execute(tx.as_mut())
+    .await?;
+    Ok(())
+}
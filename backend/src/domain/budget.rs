@@ -0,0 +1,285 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Months, NaiveDate};
+use uuid::Uuid;
+
+use crate::domain::{
+    models::{Budget, BudgetPeriod, Currency, ExpenseItem, Money},
+    policy::{
+        current_fiscal_year, CapKind, CapReference, FindingSeverity, PolicyEvaluation, BUDGET_CODE,
+        CURRENCY_CODE, POLICY_CURRENCY,
+    },
+};
+
+/// Utilization, in percent, at which a budget produces a warning; reaching
+/// 100% is a violation.
+pub const BUDGET_WARNING_PERCENT: i64 = 80;
+
+/// Spend against one budget in one period: the report's own matching items
+/// plus `committed_cents` from the other reports counted in the period.
+#[derive(Debug, Clone)]
+pub struct BudgetUsage<'a> {
+    pub budget: &'a Budget,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub report_cents: i64,
+    pub committed_cents: i64,
+}
+
+/// Groups a report's items into the budget periods they fall in, for the
+/// budgets covering the report's `department` and each item's category and
+/// date. `committed_cents` starts at zero for the caller to fill in.
+pub fn report_usage<'a>(
+    items: &[ExpenseItem],
+    department: Option<&str>,
+    budgets: &'a [Budget],
+) -> Vec<BudgetUsage<'a>> {
+    let mut usage: BTreeMap<(Uuid, NaiveDate), BudgetUsage<'a>> = BTreeMap::new();
+    for budget in budgets.iter().filter(|budget| {
+        budget.enabled
+            && budget
+                .department
+                .as_deref()
+                .is_none_or(|wanted| department == Some(wanted))
+    }) {
+        for item in items.iter().filter(|item| budget_covers(budget, item)) {
+            let (period_start, period_end) = period_bounds(budget.period, item.expense_date);
+            usage
+                .entry((budget.id, period_start))
+                .or_insert(BudgetUsage {
+                    budget,
+                    period_start,
+                    period_end,
+                    report_cents: 0,
+                    committed_cents: 0,
+                })
+                .report_cents += item.amount_cents;
+        }
+    }
+    usage.into_values().collect()
+}
+
+/// Flags each budget period whose cumulative spend, including the report,
+/// reaches `BUDGET_WARNING_PERCENT` (warning) or 100% (violation) of the
+/// budget. Spend in other currencies than `POLICY_CURRENCY` is not compared
+/// and produces a warning instead; callers convert it first when an exchange
+/// rate is available.
+pub fn evaluate_budgets(usage: &[BudgetUsage<'_>], currency: Currency) -> PolicyEvaluation {
+    let mut evaluation = PolicyEvaluation::ok();
+    for entry in usage {
+        let budget = entry.budget;
+        let amount = Money::new(budget.amount_cents, POLICY_CURRENCY);
+        let label = period_label(budget.period, entry.period_start);
+        let cap = Some(CapReference {
+            kind: CapKind::Budget,
+            id: budget.id,
+            limit_cents: budget.amount_cents,
+        });
+        if currency != POLICY_CURRENCY {
+            evaluation.push(
+                FindingSeverity::Warning,
+                CURRENCY_CODE,
+                format!(
+                    "Cannot check {} spend against the {amount} {} budget for {label} without a current exchange rate",
+                    currency.code(),
+                    budget.name
+                ),
+                None,
+                cap,
+            );
+            continue;
+        }
+
+        let total = entry.committed_cents + entry.report_cents;
+        let percent = total.saturating_mul(100) / budget.amount_cents.max(1);
+        if percent < BUDGET_WARNING_PERCENT {
+            continue;
+        }
+        let message = format!(
+            "{} budget for {label} is at {percent}% ({} of {amount}) including this report",
+            budget.name,
+            Money::new(total, POLICY_CURRENCY),
+        );
+        let severity = if percent >= 100 {
+            FindingSeverity::Violation
+        } else {
+            FindingSeverity::Warning
+        };
+        evaluation.push(severity, BUDGET_CODE, message, None, cap);
+    }
+    evaluation
+}
+
+/// First and last day of the calendar month or quarter, or fiscal year,
+/// containing `date`.
+pub fn period_bounds(period: BudgetPeriod, date: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let (first_year, first_month, months) = match period {
+        BudgetPeriod::Month => (date.year(), date.month(), 1),
+        BudgetPeriod::Quarter => (date.year(), (date.month() - 1) / 3 * 3 + 1, 3),
+        BudgetPeriod::FiscalYear => (current_fiscal_year(date).0, 10, 12),
+    };
+    let start = NaiveDate::from_ymd_opt(first_year, first_month, 1).unwrap_or(date);
+    let end = start
+        .checked_add_months(Months::new(months))
+        .and_then(|next| next.pred_opt())
+        .unwrap_or(date);
+    (start, end)
+}
+
+fn period_label(period: BudgetPeriod, start: NaiveDate) -> String {
+    match period {
+        BudgetPeriod::Month => start.format("%B %Y").to_string(),
+        BudgetPeriod::Quarter => format!("Q{} {}", (start.month() - 1) / 3 + 1, start.year()),
+        BudgetPeriod::FiscalYear => format!("FY{}", current_fiscal_year(start).1),
+    }
+}
+
+fn budget_covers(budget: &Budget, item: &ExpenseItem) -> bool {
+    budget
+        .category
+        .is_none_or(|category| category == item.category)
+        && item.expense_date >= budget.active_from
+        && budget.active_to.is_none_or(|end| item.expense_date <= end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::ExpenseCategory;
+    use chrono::Utc;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
+
+    fn item(category: ExpenseCategory, expense_date: NaiveDate, amount_cents: i64) -> ExpenseItem {
+        ExpenseItem {
+            id: Uuid::new_v4(),
+            report_id: Uuid::new_v4(),
+            expense_date,
+            category,
+            gl_account_id: None,
+            description: None,
+            attendees: None,
+            location: None,
+            amount_cents,
+            reimbursable: true,
+            payment_method: None,
+            is_policy_exception: false,
+            exception_justification: None,
+            class: None,
+            tax_amount_cents: None,
+            tax_jurisdiction: None,
+            miles: None,
+            merchant: None,
+            cost_center_id: None,
+        }
+    }
+
+    fn budget(period: BudgetPeriod, department: Option<&str>, amount_cents: i64) -> Budget {
+        Budget {
+            id: Uuid::new_v4(),
+            name: "Travel".to_string(),
+            category: Some(ExpenseCategory::Airfare),
+            department: department.map(str::to_string),
+            per_employee: false,
+            period,
+            amount_cents,
+            active_from: date(1, 1),
+            active_to: None,
+            enabled: true,
+            updated_by: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn periods_cover_calendar_months_and_quarters() {
+        assert_eq!(
+            period_bounds(BudgetPeriod::Month, date(2, 14)),
+            (date(2, 1), date(2, 29))
+        );
+        assert_eq!(
+            period_bounds(BudgetPeriod::Quarter, date(8, 31)),
+            (date(7, 1), date(9, 30))
+        );
+        assert_eq!(period_label(BudgetPeriod::Quarter, date(7, 1)), "Q3 2024");
+        assert_eq!(
+            period_label(BudgetPeriod::Month, date(2, 1)),
+            "February 2024"
+        );
+    }
+
+    #[test]
+    fn fiscal_years_run_october_through_september() {
+        assert_eq!(
+            period_bounds(BudgetPeriod::FiscalYear, date(10, 1)),
+            (date(10, 1), NaiveDate::from_ymd_opt(2025, 9, 30).unwrap())
+        );
+        assert_eq!(
+            period_bounds(BudgetPeriod::FiscalYear, date(9, 30)),
+            (NaiveDate::from_ymd_opt(2023, 10, 1).unwrap(), date(9, 30))
+        );
+        assert_eq!(
+            period_label(BudgetPeriod::FiscalYear, date(10, 1)),
+            "FY2025"
+        );
+    }
+
+    #[test]
+    fn usage_groups_items_by_budget_period_and_department() {
+        let budgets = [
+            budget(BudgetPeriod::Quarter, Some("Sales"), 100_000),
+            budget(BudgetPeriod::Month, Some("Operations"), 100_000),
+        ];
+        let items = [
+            item(ExpenseCategory::Airfare, date(4, 2), 30_000),
+            item(ExpenseCategory::Airfare, date(6, 20), 20_000),
+            item(ExpenseCategory::Airfare, date(7, 1), 10_000),
+            item(ExpenseCategory::Lodging, date(4, 3), 90_000),
+        ];
+
+        let usage = report_usage(&items, Some("Sales"), &budgets);
+
+        let periods: Vec<(NaiveDate, i64)> = usage
+            .iter()
+            .map(|entry| (entry.period_start, entry.report_cents))
+            .collect();
+        assert_eq!(periods, vec![(date(4, 1), 50_000), (date(7, 1), 10_000)]);
+        assert!(report_usage(&items, None, &budgets).is_empty());
+    }
+
+    #[test]
+    fn utilization_warns_at_80_percent_and_violates_at_100() {
+        let travel = budget(BudgetPeriod::Month, None, 100_000);
+        let usage = |committed_cents| BudgetUsage {
+            budget: &travel,
+            period_start: date(5, 1),
+            period_end: date(5, 31),
+            report_cents: 30_000,
+            committed_cents,
+        };
+
+        assert!(evaluate_budgets(&[usage(40_000)], Currency::USD)
+            .findings
+            .is_empty());
+        let near = evaluate_budgets(&[usage(50_000)], Currency::USD);
+        assert!(near.is_valid);
+        assert_eq!(
+            near.warnings(),
+            vec![
+                "Travel budget for May 2024 is at 80% ($800.00 of $1000.00) including this report"
+            ]
+        );
+        let over = evaluate_budgets(&[usage(75_000)], Currency::USD);
+        assert!(!over.is_valid);
+        assert_eq!(over.findings[0].severity, FindingSeverity::Violation);
+        assert_eq!(over.findings[0].cap.map(|cap| cap.id), Some(travel.id));
+        assert!(over.violations()[0].contains("105%"));
+
+        let eur = Currency::parse("EUR").unwrap();
+        let foreign = evaluate_budgets(&[usage(75_000)], eur);
+        assert!(foreign.is_valid);
+        assert_eq!(foreign.warnings().len(), 1);
+    }
+}
@@ -0,0 +1,160 @@
+use std::fmt;
+
+use serde::{Serialize, Serializer};
+
+use crate::domain::models::{Currency, Money, MoneyError};
+
+/// Decimal places kept for exchange rates, matching `fx_rates.rate`.
+pub const RATE_DECIMALS: u32 = 10;
+
+const RATE_SCALE: i128 = 10_i128.pow(RATE_DECIMALS);
+
+/// Units of the quote currency per unit of the base currency, held as a
+/// fixed-point integer with `RATE_DECIMALS` decimal places so conversions
+/// never go through floating point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ExchangeRate(i128);
+
+impl ExchangeRate {
+    /// The rate between a currency and itself.
+    pub const ONE: ExchangeRate = ExchangeRate(RATE_SCALE);
+
+    /// Parses a positive decimal such as `1.0842` or `161.23`. Digits past
+    /// `RATE_DECIMALS` are dropped.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+        if whole.is_empty() && fraction.is_empty()
+            || !whole
+                .bytes()
+                .chain(fraction.bytes())
+                .all(|b| b.is_ascii_digit())
+        {
+            return None;
+        }
+        let whole: i128 = if whole.is_empty() {
+            0
+        } else {
+            whole.parse().ok()?
+        };
+        let kept = &fraction[..fraction.len().min(RATE_DECIMALS as usize)];
+        let fraction: i128 = if kept.is_empty() {
+            0
+        } else {
+            kept.parse().ok()?
+        };
+        let scaled = whole
+            .checked_mul(RATE_SCALE)?
+            .checked_add(fraction * 10_i128.pow(RATE_DECIMALS - kept.len() as u32))?;
+        (scaled > 0).then_some(ExchangeRate(scaled))
+    }
+
+    /// The rate from `from` to `to`, given the rates from a common base
+    /// currency to each of them.
+    pub fn cross(base_to_from: ExchangeRate, base_to_to: ExchangeRate) -> Option<ExchangeRate> {
+        let scaled = div_round(base_to_to.0.checked_mul(RATE_SCALE)?, base_to_from.0);
+        (scaled > 0).then_some(ExchangeRate(scaled))
+    }
+
+    /// Converts `amount` into `to` at this rate, rounding half away from zero
+    /// to `to`'s minor unit.
+    pub fn convert(&self, amount: Money, to: Currency) -> Result<Money, MoneyError> {
+        let numerator = i128::from(amount.amount_minor)
+            .checked_mul(self.0)
+            .and_then(|value| value.checked_mul(10_i128.pow(to.exponent())))
+            .ok_or(MoneyError::Overflow)?;
+        let denominator = RATE_SCALE * 10_i128.pow(amount.currency.exponent());
+        let minor =
+            i64::try_from(div_round(numerator, denominator)).map_err(|_| MoneyError::Overflow)?;
+        Ok(Money::new(minor, to))
+    }
+}
+
+impl fmt::Display for ExchangeRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fraction = format!(
+            "{:0width$}",
+            self.0 % RATE_SCALE,
+            width = RATE_DECIMALS as usize
+        );
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.is_empty() {
+            write!(f, "{}", self.0 / RATE_SCALE)
+        } else {
+            write!(f, "{}.{fraction}", self.0 / RATE_SCALE)
+        }
+    }
+}
+
+impl Serialize for ExchangeRate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Divides, rounding half away from zero. `denominator` must be positive.
+fn div_round(numerator: i128, denominator: i128) -> i128 {
+    let half = denominator / 2;
+    if numerator >= 0 {
+        (numerator + half) / denominator
+    } else {
+        (numerator - half) / denominator
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn currency(code: &str) -> Currency {
+        Currency::parse(code).unwrap()
+    }
+
+    #[test]
+    fn parses_and_prints_decimal_rates() {
+        assert_eq!(ExchangeRate::parse("1.0842").unwrap().to_string(), "1.0842");
+        assert_eq!(ExchangeRate::parse(" 161 ").unwrap().to_string(), "161");
+        assert_eq!(ExchangeRate::parse(".5").unwrap().to_string(), "0.5");
+        assert_eq!(
+            ExchangeRate::parse("0.000123456789012")
+                .unwrap()
+                .to_string(),
+            "0.0001234567"
+        );
+        assert!(ExchangeRate::parse("0").is_none());
+        assert!(ExchangeRate::parse("-1.2").is_none());
+        assert!(ExchangeRate::parse("1e3").is_none());
+        assert!(ExchangeRate::parse("").is_none());
+    }
+
+    #[test]
+    fn converts_between_minor_units_with_rounding() {
+        let eur_usd = ExchangeRate::parse("1.0842").unwrap();
+        let converted = eur_usd
+            .convert(Money::new(10_005, currency("EUR")), Currency::USD)
+            .unwrap();
+        // 100.05 EUR × 1.0842 = 108.47421 USD.
+        assert_eq!(converted, Money::new(10_847, Currency::USD));
+
+        let usd_jpy = ExchangeRate::parse("151.37").unwrap();
+        let converted = usd_jpy
+            .convert(Money::new(-1_250, Currency::USD), currency("JPY"))
+            .unwrap();
+        // -12.50 USD × 151.37 = -1892.125 JPY.
+        assert_eq!(converted, Money::new(-1_892, currency("JPY")));
+    }
+
+    #[test]
+    fn crosses_rates_through_a_common_base() {
+        // EUR base: 1 EUR = 1.0842 USD = 0.8531 GBP.
+        let eur_usd = ExchangeRate::parse("1.0842").unwrap();
+        let eur_gbp = ExchangeRate::parse("0.8531").unwrap();
+
+        let gbp_usd = ExchangeRate::cross(eur_gbp, eur_usd).unwrap();
+        assert_eq!(gbp_usd.to_string(), "1.2708943852");
+        let converted = gbp_usd
+            .convert(Money::new(5_000, currency("GBP")), Currency::USD)
+            .unwrap();
+        assert_eq!(converted, Money::new(6_354, Currency::USD));
+    }
+}
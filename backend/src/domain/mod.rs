@@ -1,2 +1,4 @@
+pub mod budget;
+pub mod fx;
 pub mod models;
 pub mod policy;
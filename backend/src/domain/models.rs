@@ -58,8 +58,12 @@ impl Type<Postgres> for Role {
         PgTypeInfo::with_name("employee_role")
     }
 
+    /// Postgres reports built-in types upper-cased (`TEXT`), so names are
+    /// compared case-insensitively.
     fn compatible(ty: &PgTypeInfo) -> bool {
-        matches!(ty.name(), "employee_role" | "text" | "varchar" | "bpchar")
+        ["employee_role", "text", "varchar", "bpchar"]
+            .iter()
+            .any(|name| ty.name().eq_ignore_ascii_case(name))
     }
 }
 
@@ -69,7 +73,7 @@ impl PgHasArrayType for Role {
     }
 }
 
-impl<'q> Encode<'q, Postgres> for Role {
+impl Encode<'_, Postgres> for Role {
     fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
         let value = self.as_str();
         <&str as Encode<Postgres>>::encode_by_ref(&value, buf)
@@ -108,14 +112,36 @@ impl fmt::Display for RoleParseError {
 
 impl std::error::Error for RoleParseError {}
 
+/// The organization that operates the deployment. Data from before
+/// organizations existed belongs to it, and only its administrators manage
+/// other organizations and deployment-wide settings.
+pub const DEFAULT_ORG_ID: Uuid = Uuid::from_u128(1);
+
+/// A subsidiary sharing the deployment. Employees, reports, batches, and
+/// organization-specific policy rules belong to exactly one.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Organization {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Employee {
     pub id: Uuid,
+    pub org_id: Uuid,
     pub hr_identifier: String,
     pub manager_id: Option<Uuid>,
+    /// Code of the department `department_id` points at.
     pub department: Option<String>,
+    pub department_id: Option<Uuid>,
+    /// Cost center the employee's spend is charged to by default.
+    pub cost_center_id: Option<Uuid>,
     pub role: Role,
     pub created_at: DateTime<Utc>,
+    /// When the employee left; deactivated employees cannot sign in or
+    /// approve.
+    pub deactivated_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Type)]
@@ -123,6 +149,9 @@ pub struct Employee {
 pub enum ReportStatus {
     Draft,
     Submitted,
+    /// Approved by the manager but holding policy exceptions, so waiting on
+    /// the report's `exception_approver_id`.
+    ExceptionReview,
     ManagerApproved,
     FinanceFinalized,
     NeedsChanges,
@@ -134,6 +163,7 @@ impl ReportStatus {
         match self {
             ReportStatus::Draft => "draft",
             ReportStatus::Submitted => "submitted",
+            ReportStatus::ExceptionReview => "exception_review",
             ReportStatus::ManagerApproved => "manager_approved",
             ReportStatus::FinanceFinalized => "finance_finalized",
             ReportStatus::NeedsChanges => "needs_changes",
@@ -146,6 +176,7 @@ impl ReportStatus {
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct ExpenseReport {
     pub id: Uuid,
+    pub org_id: Uuid,
     pub employee_id: Uuid,
     pub reporting_period_start: NaiveDate,
     pub reporting_period_end: NaiveDate,
@@ -156,6 +187,31 @@ pub struct ExpenseReport {
     pub version: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub submitted_at: Option<DateTime<Utc>>,
+    /// The trip the report's expenses were incurred on, if linked.
+    pub trip_id: Option<Uuid>,
+    /// Set on a draft whose owner was deactivated before submitting it.
+    pub owner_deactivated_at: Option<DateTime<Utc>>,
+    /// Who created and submitted the report: the owner, or an assistant
+    /// acting for them. `None` on reports from before assistants existed.
+    pub created_by: Option<Uuid>,
+    pub submitted_by: Option<Uuid>,
+}
+
+impl ExpenseReport {
+    pub fn total_amount(&self) -> Result<Money, MoneyError> {
+        Ok(Money::new(
+            self.total_amount_cents,
+            Currency::parse(&self.currency)?,
+        ))
+    }
+
+    pub fn total_reimbursable(&self) -> Result<Money, MoneyError> {
+        Ok(Money::new(
+            self.total_reimbursable_cents,
+            Currency::parse(&self.currency)?,
+        ))
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Type)]
@@ -172,6 +228,23 @@ pub enum ExpenseCategory {
 }
 
 impl ExpenseCategory {
+    pub const ALL: [ExpenseCategory; 7] = [
+        ExpenseCategory::Airfare,
+        ExpenseCategory::Lodging,
+        ExpenseCategory::Meal,
+        ExpenseCategory::GroundTransport,
+        ExpenseCategory::Mileage,
+        ExpenseCategory::Supplies,
+        ExpenseCategory::Other,
+    ];
+
+    /// Parses the snake_case database representation.
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|category| category.as_str() == value)
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             ExpenseCategory::Airfare => "airfare",
@@ -199,6 +272,47 @@ pub struct ExpenseItem {
     pub reimbursable: bool,
     pub payment_method: Option<String>,
     pub is_policy_exception: bool,
+    /// Why the employee marked the item a policy exception; required when
+    /// `is_policy_exception` is set on new items.
+    pub exception_justification: Option<String>,
+    /// Project or NetSuite class the item posts to, if the employee set one.
+    pub class: Option<String>,
+    /// VAT/GST portion included in `amount_cents`, when the receipt shows it.
+    pub tax_amount_cents: Option<i64>,
+    /// Upper-cased tax jurisdiction (`GB`, `CA-ON`) used to pick a tax code.
+    pub tax_jurisdiction: Option<String>,
+    /// Distance driven, for mileage items checked against the mileage rate.
+    pub miles: Option<f64>,
+    /// Where the expense was incurred, as printed on the receipt.
+    pub merchant: Option<String>,
+    /// Cost center the item is allocated to, if the employee picked one.
+    pub cost_center_id: Option<Uuid>,
+}
+
+impl ExpenseItem {
+    /// Names in `attendees`, which lists everyone at a meal (the employee
+    /// included) separated by semicolons, commas, or line breaks.
+    pub fn attendee_names(&self) -> Vec<&str> {
+        self.attendees
+            .as_deref()
+            .unwrap_or_default()
+            .split([';', ',', '\n'])
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .collect()
+    }
+
+    /// Number of people the item paid for; at least one.
+    pub fn headcount(&self) -> i64 {
+        self.attendee_names().len().max(1) as i64
+    }
+
+    /// `amount_cents` split evenly across `headcount`, rounded to the
+    /// nearest cent.
+    pub fn per_attendee_cents(&self) -> i64 {
+        let headcount = self.headcount();
+        (self.amount_cents + headcount / 2).div_euclid(headcount)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -252,6 +366,10 @@ pub struct NetSuiteBatch {
     pub status: String,
     pub exported_at: Option<DateTime<Utc>>,
     pub netsuite_response: Option<serde_json::Value>,
+    pub export_attempts: i32,
+    pub last_attempted_at: Option<DateTime<Utc>>,
+    pub reverses_batch_id: Option<Uuid>,
+    pub posting_date: NaiveDate,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -266,12 +384,161 @@ pub struct JournalLine {
     pub class: Option<String>,
     pub memo: Option<String>,
     pub tax_code: Option<String>,
+    pub tax_amount_cents: Option<i64>,
+    /// `expense` for item debits, `liability` for the per-report balancing
+    /// line; see `JournalLineKind`.
+    pub line_kind: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+/// Role a journal line plays in its batch. Expense lines debit the mapped GL
+/// accounts; each report gets one liability line crediting the
+/// reimbursement account so the batch nets to zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JournalLineKind {
+    Expense,
+    Liability,
+}
+
+impl JournalLineKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JournalLineKind::Expense => "expense",
+            JournalLineKind::Liability => "liability",
+        }
+    }
+}
+
+/// Admin-maintained mapping from an expense category to the GL account its
+/// journal lines post to (`POLICY.md` §"General Ledger Mapping").
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlAccountMapping {
+    pub category: ExpenseCategory,
+    pub gl_account: String,
+    pub description: Option<String>,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Accounting period finance can close to stop further postings into it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountingPeriod {
+    pub id: Uuid,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub status: String,
+    pub closed_by: Option<Uuid>,
+    pub closed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Tax code stamped on journal lines for a category. A mapping with a
+/// `jurisdiction` takes precedence over the category's fallback (`None`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxCodeMapping {
+    pub id: Uuid,
+    pub category: ExpenseCategory,
+    pub jurisdiction: Option<String>,
+    pub tax_code: String,
+    pub description: Option<String>,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Admin-maintained rule translating journal data into a NetSuite record
+/// field, written as a `{ "id": netsuite_value }` reference.
+///
+/// `target` is `header` or `line`. Without a `source_field` the field is set
+/// on every journal entry header or line (e.g. `subsidiary`, `location`);
+/// with one (`gl_account`, `department`, `class`, or `tax_code`) it is set
+/// only on lines whose value equals `source_value`, taking precedence over
+/// unconditional rules for the same field.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetSuiteFieldMapping {
+    pub id: Uuid,
+    pub target: String,
+    pub source_field: Option<String>,
+    pub source_value: Option<String>,
+    pub netsuite_field: String,
+    pub netsuite_value: String,
+    pub description: Option<String>,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Request body sent to NetSuite for one record of one export attempt,
+/// stored byte-for-byte in `payload` with its SHA-256 hex digest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportPayload {
+    pub id: Uuid,
+    pub batch_id: Uuid,
+    pub attempt: i32,
+    pub record_type: String,
+    pub external_id: String,
+    pub payload: String,
+    pub payload_sha256: String,
+    pub sent_at: DateTime<Utc>,
+}
+
+/// Trims and upper-cases a tax jurisdiction so `gb ` and `GB` select the same
+/// mapping; blank values mean "no jurisdiction".
+pub fn normalize_tax_jurisdiction(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_ascii_uppercase)
+}
+
+/// Reimbursement rate per mile in force from `effective_date` until the next
+/// rate's. Rates may be fractional (the IRS set 65.5 cents for 2023).
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MileageRate {
+    pub id: Uuid,
     pub effective_date: NaiveDate,
-    pub rate_cents_per_mile: i32,
+    pub rate_cents_per_mile: f64,
+    pub source_reference: Option<String>,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A department of an organization. `code` is unique within the
+/// organization, ignoring case, and is what employees' `department` and
+/// exported journal lines carry.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Department {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub code: String,
+    pub name: String,
+    /// Inactive departments stay on existing records but cannot be assigned.
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A cost center of an organization that employees and items are charged
+/// to, optionally within a department.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CostCenter {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub code: String,
+    pub name: String,
+    pub department_id: Option<Uuid>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Employee who approves policy exceptions on reports from `department`
+/// after the submitter's manager has approved.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DepartmentHead {
+    pub id: Uuid,
+    /// Matches `employees.department`.
+    pub department: String,
+    pub employee_id: Uuid,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -286,6 +553,351 @@ pub struct PolicyCap {
     pub active_to: Option<NaiveDate>,
 }
 
+/// Admin-maintained policy rule evaluated generically by `domain::policy`.
+///
+/// The rule fires when the spend in `scope` (a single item, the items on one
+/// day, or the whole report), restricted to `category` when set, compares to
+/// `threshold_cents` as `comparison` says, counting only items that meet
+/// `condition`. A `per_attendee` rule (item scope only) compares each item's
+/// cost per head instead. Thresholds are denominated in
+/// `domain::policy::POLICY_CURRENCY`. `message` may use the `{amount}`,
+/// `{limit}`, `{headcount}` (per-attendee rules), and (outside report scope)
+/// `{date}` placeholders; `non_working_day` rules add `{day}`,
+/// `late_booking` rules `{travel_date}` and `{days}`, and the duplicate
+/// conditions at item scope `{duplicate_report}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyRule {
+    pub id: Uuid,
+    /// The organization the rule applies to; shared by every organization
+    /// when empty, as are rules snapshotted before organizations existed.
+    #[serde(default)]
+    pub org_id: Option<Uuid>,
+    pub name: String,
+    pub category: Option<ExpenseCategory>,
+    pub comparison: RuleComparison,
+    pub threshold_cents: i64,
+    pub scope: RuleScope,
+    pub severity: RuleSeverity,
+    pub condition: RuleCondition,
+    pub per_attendee: bool,
+    /// Minimum days between purchase and departure; `late_booking` rules
+    /// only.
+    pub advance_days: Option<i32>,
+    pub message: String,
+    pub active_from: NaiveDate,
+    pub active_to: Option<NaiveDate>,
+    pub enabled: bool,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PolicyRule {
+    /// Whether the rule governs reports of `org_id`.
+    pub fn applies_to(&self, org_id: Uuid) -> bool {
+        self.org_id.is_none_or(|rule_org| rule_org == org_id)
+    }
+}
+
+/// Snapshot of every policy rule, recorded by a database trigger whenever
+/// the rules change. Version `version` is in force from `effective_from`
+/// until the next version's `effective_from` (`effective_to`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyVersion {
+    pub version: i64,
+    pub effective_from: DateTime<Utc>,
+    pub effective_to: Option<DateTime<Utc>>,
+    pub rules: Vec<PolicyRule>,
+}
+
+/// Daily per-diem allowance for a location in a federal fiscal year
+/// (October–September).
+///
+/// `city` and `state` narrow the rate: a row without `city` is the state's
+/// (or, without `state` as well, the country's) standard rate. `city` holds
+/// the destination as published, which may list several cities separated by
+/// `/`. When set, `season_begin` and `season_end` bound the rate to part of
+/// the year as `month * 100 + day` (e.g. `1001` for October 1), inclusive
+/// and possibly wrapping past December.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerDiemRate {
+    pub id: Uuid,
+    pub country: String,
+    pub state: Option<String>,
+    pub city: Option<String>,
+    pub county: Option<String>,
+    pub fiscal_year: i32,
+    pub season_begin: Option<i16>,
+    pub season_end: Option<i16>,
+    /// Meals and incidental expenses (M&IE) allowance.
+    pub meals_cents: i64,
+    pub lodging_cents: Option<i64>,
+    pub source: String,
+    pub imported_at: DateTime<Utc>,
+}
+
+/// How a rule compares spend against its threshold; the rule fires when the
+/// comparison holds.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleComparison {
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+impl RuleComparison {
+    pub const ALL: [RuleComparison; 4] = [
+        RuleComparison::Gt,
+        RuleComparison::Gte,
+        RuleComparison::Lt,
+        RuleComparison::Lte,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|other| other.as_str() == value)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RuleComparison::Gt => "gt",
+            RuleComparison::Gte => "gte",
+            RuleComparison::Lt => "lt",
+            RuleComparison::Lte => "lte",
+        }
+    }
+
+    /// Whether `spend.cmp(threshold) == ordering` satisfies the comparison.
+    pub fn holds(&self, ordering: std::cmp::Ordering) -> bool {
+        use std::cmp::Ordering::{Greater, Less};
+        match self {
+            RuleComparison::Gt => ordering == Greater,
+            RuleComparison::Gte => ordering != Less,
+            RuleComparison::Lt => ordering == Less,
+            RuleComparison::Lte => ordering != Greater,
+        }
+    }
+}
+
+/// Spend a rule is evaluated against.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleScope {
+    /// Each item on its own.
+    Item,
+    /// The total of the matching items sharing an `expense_date`.
+    Day,
+    /// The total of the matching items in the report.
+    Report,
+}
+
+impl RuleScope {
+    pub const ALL: [RuleScope; 3] = [RuleScope::Item, RuleScope::Day, RuleScope::Report];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|other| other.as_str() == value)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RuleScope::Item => "item",
+            RuleScope::Day => "day",
+            RuleScope::Report => "report",
+        }
+    }
+}
+
+/// Whether a firing rule invalidates the report or only warns reviewers.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleSeverity {
+    Violation,
+    Warning,
+    /// A violation that also prevents submission unless every item it covers
+    /// is a justified policy exception.
+    Blocking,
+}
+
+impl RuleSeverity {
+    pub const ALL: [RuleSeverity; 3] = [
+        RuleSeverity::Violation,
+        RuleSeverity::Warning,
+        RuleSeverity::Blocking,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|other| other.as_str() == value)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RuleSeverity::Violation => "violation",
+            RuleSeverity::Warning => "warning",
+            RuleSeverity::Blocking => "blocking",
+        }
+    }
+}
+
+/// Admin-maintained spending budget for a category and/or department over
+/// each calendar month or quarter. `domain::budget` compares the period's
+/// cumulative spend against `amount_cents` (in
+/// `domain::policy::POLICY_CURRENCY`) when reports are evaluated.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Budget {
+    pub id: Uuid,
+    pub name: String,
+    /// `None` covers every category.
+    pub category: Option<ExpenseCategory>,
+    /// Matches `employees.department`; `None` covers every department.
+    pub department: Option<String>,
+    /// Applies to each employee's own spend rather than the department's.
+    pub per_employee: bool,
+    pub period: BudgetPeriod,
+    pub amount_cents: i64,
+    pub active_from: NaiveDate,
+    pub active_to: Option<NaiveDate>,
+    pub enabled: bool,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Calendar period a budget resets over.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetPeriod {
+    Month,
+    Quarter,
+    /// October through September, per `domain::policy::current_fiscal_year`.
+    FiscalYear,
+}
+
+impl BudgetPeriod {
+    pub const ALL: [BudgetPeriod; 3] = [
+        BudgetPeriod::Month,
+        BudgetPeriod::Quarter,
+        BudgetPeriod::FiscalYear,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|other| other.as_str() == value)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BudgetPeriod::Month => "month",
+            BudgetPeriod::Quarter => "quarter",
+            BudgetPeriod::FiscalYear => "fiscal_year",
+        }
+    }
+}
+
+/// Which items a rule counts.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleCondition {
+    #[default]
+    Always,
+    /// Only items without an attached receipt. Mileage items are never
+    /// counted, since mileage is claimed by distance rather than receipt.
+    MissingReceipt,
+    /// Only items without any `attendees` listed.
+    MissingAttendees,
+    /// Only items dated on a weekend or a company holiday, unless the date
+    /// falls within the approved trip the report is linked to.
+    NonWorkingDay,
+    /// Only items dated fewer than the rule's `advance_days` before the
+    /// departure date of the trip the report is linked to.
+    LateBooking,
+    /// Only items matching an item on another of the employee's submitted
+    /// reports by date, amount, currency, and merchant.
+    DuplicateExpense,
+    /// Only items with a receipt whose file is identical to one attached to
+    /// another submitted report.
+    DuplicateReceipt,
+}
+
+impl RuleCondition {
+    pub const ALL: [RuleCondition; 7] = [
+        RuleCondition::Always,
+        RuleCondition::MissingReceipt,
+        RuleCondition::MissingAttendees,
+        RuleCondition::NonWorkingDay,
+        RuleCondition::LateBooking,
+        RuleCondition::DuplicateExpense,
+        RuleCondition::DuplicateReceipt,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|other| other.as_str() == value)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RuleCondition::Always => "always",
+            RuleCondition::MissingReceipt => "missing_receipt",
+            RuleCondition::MissingAttendees => "missing_attendees",
+            RuleCondition::NonWorkingDay => "non_working_day",
+            RuleCondition::LateBooking => "late_booking",
+            RuleCondition::DuplicateExpense => "duplicate_expense",
+            RuleCondition::DuplicateReceipt => "duplicate_receipt",
+        }
+    }
+}
+
+/// Company holiday on which expenses are flagged like weekend expenses.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Holiday {
+    pub id: Uuid,
+    pub holiday_date: NaiveDate,
+    pub name: String,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Business trip an employee plans ahead of travel for their manager to
+/// approve. Expense reports link to it through `ExpenseReport::trip_id`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Trip {
+    pub id: Uuid,
+    pub employee_id: Uuid,
+    pub purpose: String,
+    pub destination: Option<String>,
+    pub departure_date: NaiveDate,
+    pub return_date: NaiveDate,
+    pub status: TripStatus,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TripStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl TripStatus {
+    pub const ALL: [TripStatus; 3] = [
+        TripStatus::Pending,
+        TripStatus::Approved,
+        TripStatus::Rejected,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|other| other.as_str() == value)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TripStatus::Pending => "pending",
+            TripStatus::Approved => "approved",
+            TripStatus::Rejected => "rejected",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct AuditLog {
     pub id: Uuid,
@@ -295,8 +907,311 @@ pub struct AuditLog {
     pub old_value: Option<serde_json::Value>,
     pub new_value: Option<serde_json::Value>,
     pub performed_by: Option<Uuid>,
+    /// The employee `performed_by` acted for as their assistant.
+    pub on_behalf_of: Option<Uuid>,
     pub performed_at: DateTime<Utc>,
     pub ip_address: Option<String>,
     pub user_agent: Option<String>,
+    /// `X-Request-Id` of the API request that made the change.
+    pub request_id: Option<String>,
+    /// Position in the entity's audit trail, starting at 1.
+    pub chain_sequence: i64,
+    /// `signature_hash` of the entity's previous entry, or of all zeros for
+    /// its first. `None` on entries recorded before chaining began.
+    pub previous_hash: Option<String>,
     pub signature_hash: String,
 }
+
+/// ISO 4217 currency code.
+///
+/// Stored uppercase; the exponent drives how many minor units make up one
+/// major unit when amounts are formatted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Currency([u8; 3]);
+
+impl Currency {
+    pub const USD: Currency = Currency(*b"USD");
+
+    /// Parses a three-letter code, accepting any case and surrounding
+    /// whitespace.
+    pub fn parse(code: &str) -> Result<Self, MoneyError> {
+        let normalized = code.trim().to_ascii_uppercase();
+        let bytes: [u8; 3] = normalized
+            .as_bytes()
+            .try_into()
+            .map_err(|_| MoneyError::InvalidCurrency(code.to_owned()))?;
+        if !bytes.iter().all(u8::is_ascii_uppercase) {
+            return Err(MoneyError::InvalidCurrency(code.to_owned()));
+        }
+        Ok(Currency(bytes))
+    }
+
+    pub fn code(&self) -> &str {
+        // Only ASCII uppercase bytes are ever stored.
+        std::str::from_utf8(&self.0).unwrap_or("XXX")
+    }
+
+    /// Number of decimal places in the currency's minor unit.
+    pub fn exponent(&self) -> u32 {
+        match self.code() {
+            "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF"
+            | "UGX" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+            "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+            _ => 2,
+        }
+    }
+
+    fn symbol(&self) -> Option<&'static str> {
+        match self.code() {
+            "USD" => Some("$"),
+            "EUR" => Some("€"),
+            "GBP" => Some("£"),
+            "JPY" => Some("¥"),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl TryFrom<String> for Currency {
+    type Error = MoneyError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Currency::parse(&value)
+    }
+}
+
+impl From<Currency> for String {
+    fn from(value: Currency) -> Self {
+        value.code().to_owned()
+    }
+}
+
+/// Amount in a currency's minor unit (cents for USD).
+///
+/// Arithmetic is checked and refuses to combine different currencies, so
+/// totals can never silently mix USD and EUR or overflow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Money {
+    pub amount_minor: i64,
+    pub currency: Currency,
+}
+
+impl Money {
+    pub fn new(amount_minor: i64, currency: Currency) -> Self {
+        Self {
+            amount_minor,
+            currency,
+        }
+    }
+
+    pub fn zero(currency: Currency) -> Self {
+        Self::new(0, currency)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.amount_minor == 0
+    }
+
+    pub fn checked_add(self, other: Money) -> Result<Money, MoneyError> {
+        self.ensure_same_currency(&other)?;
+        self.amount_minor
+            .checked_add(other.amount_minor)
+            .map(|amount| Money::new(amount, self.currency))
+            .ok_or(MoneyError::Overflow)
+    }
+
+    pub fn checked_sub(self, other: Money) -> Result<Money, MoneyError> {
+        self.ensure_same_currency(&other)?;
+        self.amount_minor
+            .checked_sub(other.amount_minor)
+            .map(|amount| Money::new(amount, self.currency))
+            .ok_or(MoneyError::Overflow)
+    }
+
+    pub fn checked_mul(self, factor: i64) -> Result<Money, MoneyError> {
+        self.amount_minor
+            .checked_mul(factor)
+            .map(|amount| Money::new(amount, self.currency))
+            .ok_or(MoneyError::Overflow)
+    }
+
+    /// Orders two amounts of the same currency.
+    pub fn checked_cmp(&self, other: &Money) -> Result<std::cmp::Ordering, MoneyError> {
+        self.ensure_same_currency(other)?;
+        Ok(self.amount_minor.cmp(&other.amount_minor))
+    }
+
+    /// Sums `amounts`, starting from zero in `currency`.
+    /// Plain decimal amount in major units (`-1234.50`), without a currency
+    /// symbol, for ledger import files.
+    pub fn to_decimal_string(&self) -> String {
+        let sign = if self.amount_minor < 0 { "-" } else { "" };
+        format!("{sign}{}", self.abs_decimal())
+    }
+
+    fn abs_decimal(&self) -> String {
+        let exponent = self.currency.exponent();
+        let magnitude = self.amount_minor.unsigned_abs();
+        if exponent == 0 {
+            return magnitude.to_string();
+        }
+        let scale = 10_u64.pow(exponent);
+        format!(
+            "{}.{:0width$}",
+            magnitude / scale,
+            magnitude % scale,
+            width = exponent as usize
+        )
+    }
+
+    pub fn sum<I>(currency: Currency, amounts: I) -> Result<Money, MoneyError>
+    where
+        I: IntoIterator<Item = Money>,
+    {
+        amounts
+            .into_iter()
+            .try_fold(Money::zero(currency), Money::checked_add)
+    }
+
+    fn ensure_same_currency(&self, other: &Money) -> Result<(), MoneyError> {
+        if self.currency == other.currency {
+            Ok(())
+        } else {
+            Err(MoneyError::CurrencyMismatch {
+                left: self.currency,
+                right: other.currency,
+            })
+        }
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.amount_minor < 0 { "-" } else { "" };
+        let amount = self.abs_decimal();
+        match self.currency.symbol() {
+            Some(symbol) => write!(f, "{sign}{symbol}{amount}"),
+            None => write!(f, "{sign}{amount} {}", self.currency),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoneyError {
+    CurrencyMismatch { left: Currency, right: Currency },
+    Overflow,
+    InvalidCurrency(String),
+}
+
+impl fmt::Display for MoneyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoneyError::CurrencyMismatch { left, right } => {
+                write!(f, "cannot combine {left} and {right} amounts")
+            }
+            MoneyError::Overflow => f.write_str("monetary amount overflowed"),
+            MoneyError::InvalidCurrency(code) => write!(f, "unsupported currency code: {code}"),
+        }
+    }
+}
+
+impl std::error::Error for MoneyError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eur() -> Currency {
+        Currency::parse("eur").unwrap()
+    }
+
+    #[test]
+    fn role_is_compatible_with_text_columns() {
+        for name in ["TEXT", "text", "VARCHAR", "employee_role"] {
+            assert!(<Role as Type<Postgres>>::compatible(
+                &PgTypeInfo::with_name(name)
+            ));
+        }
+        assert!(!<Role as Type<Postgres>>::compatible(
+            &PgTypeInfo::with_name("INT4")
+        ));
+    }
+
+    #[test]
+    fn currency_parse_normalizes_and_validates() {
+        assert_eq!(Currency::parse(" usd ").unwrap(), Currency::USD);
+        assert!(Currency::parse("US").is_err());
+        assert!(Currency::parse("U5D").is_err());
+        assert_eq!(
+            serde_json::to_value(eur()).unwrap(),
+            serde_json::json!("EUR")
+        );
+        assert!(serde_json::from_value::<Currency>(serde_json::json!("dollars")).is_err());
+    }
+
+    #[test]
+    fn money_arithmetic_rejects_mixed_currencies_and_overflow() {
+        let usd = Money::new(1_250, Currency::USD);
+
+        assert_eq!(
+            usd.checked_add(Money::new(250, Currency::USD)).unwrap(),
+            Money::new(1_500, Currency::USD)
+        );
+        assert_eq!(
+            usd.checked_add(Money::new(100, eur())),
+            Err(MoneyError::CurrencyMismatch {
+                left: Currency::USD,
+                right: eur()
+            })
+        );
+        assert_eq!(
+            Money::new(i64::MAX, Currency::USD).checked_add(Money::new(1, Currency::USD)),
+            Err(MoneyError::Overflow)
+        );
+        assert_eq!(
+            Money::sum(Currency::USD, [usd, usd, usd])
+                .unwrap()
+                .amount_minor,
+            3_750
+        );
+    }
+
+    #[test]
+    fn money_formats_per_currency_exponent() {
+        assert_eq!(Money::new(7_500, Currency::USD).to_string(), "$75.00");
+        assert_eq!(Money::new(-5, Currency::USD).to_string(), "-$0.05");
+        assert_eq!(
+            Money::new(1_500, Currency::parse("JPY").unwrap()).to_string(),
+            "¥1500"
+        );
+        assert_eq!(
+            Money::new(12_345, Currency::parse("KWD").unwrap()).to_string(),
+            "12.345 KWD"
+        );
+        assert_eq!(
+            Money::new(-123_450, Currency::USD).to_decimal_string(),
+            "-1234.50"
+        );
+    }
+
+    #[test]
+    fn money_serializes_with_minor_units_and_code() {
+        let value = serde_json::to_value(Money::new(4_200, eur())).unwrap();
+
+        assert_eq!(
+            value,
+            serde_json::json!({ "amount_minor": 4200, "currency": "EUR" })
+        );
+        assert_eq!(
+            serde_json::from_value::<Money>(value).unwrap(),
+            Money::new(4_200, eur())
+        );
+    }
+}
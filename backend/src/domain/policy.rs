@@ -1,94 +1,925 @@
-use chrono::{Datelike, NaiveDate};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, Utc, Weekday};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::models::{
+    Currency, ExpenseCategory, ExpenseItem, MileageRate, Money, PerDiemRate, PolicyRule,
+    RuleCondition, RuleScope, RuleSeverity,
+};
 
-use crate::domain::models::{ExpenseCategory, ExpenseItem, PolicyCap};
+/// Currency in which `policy_rules` thresholds are denominated (`POLICY.md`
+/// quotes every limit in US dollars).
+pub const POLICY_CURRENCY: Currency = Currency::USD;
+
+/// How serious a finding is. `Blocking` findings also prevent submission.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingSeverity {
+    Warning,
+    Violation,
+    Blocking,
+}
+
+/// Kind of limit a finding was measured against.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CapKind {
+    Rule,
+    PerDiemRate,
+    MileageRate,
+    Budget,
+}
+
+/// The policy rule, per-diem or mileage rate, or budget behind a finding and its limit
+/// in `POLICY_CURRENCY` cents.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CapReference {
+    pub kind: CapKind,
+    pub id: Uuid,
+    pub limit_cents: i64,
+}
+
+/// One result of a policy check.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PolicyFinding {
+    pub severity: FindingSeverity,
+    /// Stable identifier of the check: the rule's `name` for policy rules,
+    /// otherwise one of the `*_CODE` constants.
+    pub code: String,
+    pub message: String,
+    /// Set when the finding concerns a single item.
+    pub item_id: Option<Uuid>,
+    pub cap: Option<CapReference>,
+}
+
+/// Code of findings from located meals over their per-diem rate.
+pub const PER_DIEM_CODE: &str = "per_diem";
+/// Code of findings from mileage claims over miles times the mileage rate.
+pub const MILEAGE_CODE: &str = "mileage_rate";
+/// Code of findings from budget utilization.
+pub const BUDGET_CODE: &str = "budget";
+/// Code of warnings for spend that could not be compared with a limit in
+/// another currency.
+pub const CURRENCY_CODE: &str = "currency_conversion";
+/// Code of warnings for receipts captured far from the expense date.
+pub const RECEIPT_CAPTURE_CODE: &str = "receipt_capture_date";
+/// Code of warnings for items the employee marked as policy exceptions.
+pub const POLICY_EXCEPTION_CODE: &str = "policy_exception";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PolicyEvaluation {
+    /// False when any finding is a violation or blocking.
     pub is_valid: bool,
-    pub violations: Vec<String>,
-    pub warnings: Vec<String>,
+    pub findings: Vec<PolicyFinding>,
+    /// The `policy_versions` entry whose rules were applied, when known.
+    #[serde(default)]
+    pub policy_version: Option<i64>,
 }
 
 impl PolicyEvaluation {
     pub fn ok() -> Self {
         Self {
             is_valid: true,
-            violations: Vec::new(),
-            warnings: Vec::new(),
+            findings: Vec::new(),
+            policy_version: None,
         }
     }
 
-    pub fn with_violation(message: impl Into<String>) -> Self {
-        Self {
-            is_valid: false,
-            violations: vec![message.into()],
-            warnings: Vec::new(),
+    pub fn with_violation(code: &str, message: impl Into<String>) -> Self {
+        let mut evaluation = Self::ok();
+        evaluation.push(FindingSeverity::Violation, code, message, None, None);
+        evaluation
+    }
+
+    /// Records a finding, invalidating the evaluation unless it is a warning.
+    pub fn push(
+        &mut self,
+        severity: FindingSeverity,
+        code: &str,
+        message: impl Into<String>,
+        item_id: Option<Uuid>,
+        cap: Option<CapReference>,
+    ) {
+        if severity > FindingSeverity::Warning {
+            self.is_valid = false;
         }
+        self.findings.push(PolicyFinding {
+            severity,
+            code: code.to_string(),
+            message: message.into(),
+            item_id,
+            cap,
+        });
     }
 
     pub fn merge(&mut self, other: PolicyEvaluation) {
         if !other.is_valid {
             self.is_valid = false;
         }
-        self.violations.extend(other.violations);
-        self.warnings.extend(other.warnings);
+        self.findings.extend(other.findings);
+    }
+
+    /// Messages of violations, blocking ones included.
+    pub fn violations(&self) -> Vec<&str> {
+        self.messages(|severity| severity >= FindingSeverity::Violation)
+    }
+
+    pub fn warnings(&self) -> Vec<&str> {
+        self.messages(|severity| severity == FindingSeverity::Warning)
     }
+
+    /// Messages of the findings that prevent submission.
+    pub fn blocking(&self) -> Vec<&str> {
+        self.messages(|severity| severity == FindingSeverity::Blocking)
+    }
+
+    fn messages(&self, wanted: impl Fn(FindingSeverity) -> bool) -> Vec<&str> {
+        self.findings
+            .iter()
+            .filter(|finding| wanted(finding.severity))
+            .map(|finding| finding.message.as_str())
+            .collect()
+    }
+}
+
+/// What caused a stored policy evaluation run.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EvaluationTrigger {
+    /// `GET /reports/:id/policy` on a draft.
+    Evaluation,
+    Submission,
 }
 
-pub fn evaluate_item(item: &ExpenseItem, caps: &[PolicyCap]) -> PolicyEvaluation {
-    match item.category {
-        ExpenseCategory::Meal => check_meal(item, caps),
-        ExpenseCategory::Mileage => check_mileage(item, caps),
-        _ => PolicyEvaluation::ok(),
+impl EvaluationTrigger {
+    pub const ALL: [EvaluationTrigger; 2] =
+        [EvaluationTrigger::Evaluation, EvaluationTrigger::Submission];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|other| other.as_str() == value)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EvaluationTrigger::Evaluation => "evaluation",
+            EvaluationTrigger::Submission => "submission",
+        }
     }
 }
 
-fn check_meal(item: &ExpenseItem, caps: &[PolicyCap]) -> PolicyEvaluation {
-    let mut violations = Vec::new();
-    for cap in caps.iter().filter(|c| c.category == ExpenseCategory::Meal) {
-        if !cap_active(cap, item.expense_date) {
-            continue;
+/// One stored evaluation of a report, with the findings it introduced and
+/// resolved compared with the run before it.
+#[derive(Debug, Clone, Serialize)]
+pub struct PolicyEvaluationRun {
+    pub id: i64,
+    pub trigger: EvaluationTrigger,
+    /// Backend version that evaluated the report; unknown for runs recorded
+    /// before history was kept.
+    pub engine_version: Option<String>,
+    pub evaluated_by: Option<Uuid>,
+    pub evaluated_at: DateTime<Utc>,
+    pub evaluation: PolicyEvaluation,
+    pub introduced: Vec<PolicyFinding>,
+    pub resolved: Vec<PolicyFinding>,
+}
+
+/// Splits the difference between two evaluations' findings into those only
+/// in `current` (introduced) and those only in `previous` (resolved).
+///
+/// Findings are matched by severity, code, and item rather than message, so
+/// an edited amount that still breaks the same rule is neither.
+pub fn diff_findings(
+    previous: &[PolicyFinding],
+    current: &[PolicyFinding],
+) -> (Vec<PolicyFinding>, Vec<PolicyFinding>) {
+    let key = |finding: &PolicyFinding| (finding.severity, finding.code.clone(), finding.item_id);
+    let mut unmatched: Vec<&PolicyFinding> = previous.iter().collect();
+    let mut introduced = Vec::new();
+    for finding in current {
+        match unmatched
+            .iter()
+            .position(|candidate| key(candidate) == key(finding))
+        {
+            Some(index) => {
+                unmatched.remove(index);
+            }
+            None => introduced.push(finding.clone()),
         }
-        if item.amount_cents > cap.amount_cents {
-            violations.push(format!(
-                "Meal exceeds per-diem limit of ${:.2}",
-                cap.amount_cents as f64 / 100.0
-            ));
+    }
+    let resolved = unmatched.into_iter().cloned().collect();
+    (introduced, resolved)
+}
+
+/// Reference rates the policy check resolves limits from.
+#[derive(Debug, Clone, Default)]
+pub struct RateTables {
+    /// Per-diem M&IE rates for the fiscal years of the report's meals.
+    pub per_diem: Vec<PerDiemRate>,
+    /// Mileage rates in force up to the report's last expense date.
+    pub mileage: Vec<MileageRate>,
+}
+
+/// Company holidays and the trip a report is linked to, which decide the
+/// dates `non_working_day` and `late_booking` rules count. The default
+/// calendar only knows weekends.
+#[derive(Debug, Clone, Default)]
+pub struct WorkCalendar {
+    /// Holiday names by date.
+    pub holidays: HashMap<NaiveDate, String>,
+    /// Departure and return dates of the report's approved trip, if any.
+    pub trip: Option<(NaiveDate, NaiveDate)>,
+    /// Departure date of the report's trip, approved or not.
+    pub travel_date: Option<NaiveDate>,
+}
+
+impl WorkCalendar {
+    /// The holiday's name when `date` is a holiday, or the weekday's name
+    /// when it falls on a weekend.
+    pub fn day_off(&self, date: NaiveDate) -> Option<String> {
+        if let Some(name) = self.holidays.get(&date) {
+            return Some(name.clone());
         }
+        matches!(date.weekday(), Weekday::Sat | Weekday::Sun).then(|| date.format("%A").to_string())
     }
-    if violations.is_empty() {
-        PolicyEvaluation::ok()
-    } else {
-        PolicyEvaluation {
-            is_valid: false,
-            violations,
-            warnings: Vec::new(),
+
+    /// Whether an expense on `date` needs explaining: a day off that is not
+    /// within the approved trip.
+    pub fn is_flagged(&self, date: NaiveDate) -> bool {
+        self.day_off(date).is_some()
+            && !self
+                .trip
+                .is_some_and(|(departure, ret)| departure <= date && date <= ret)
+    }
+
+    /// Whether a purchase on `date` came fewer than `advance_days` before
+    /// departure; always false without a linked trip.
+    pub fn is_late_booking(&self, date: NaiveDate, advance_days: i32) -> bool {
+        self.travel_date
+            .is_some_and(|travel| (travel - date).num_days() < i64::from(advance_days))
+    }
+}
+
+/// Items that repeat an item of another submitted report, by item ID, with
+/// the ID of the other report.
+#[derive(Debug, Clone, Default)]
+pub struct DuplicateMatches {
+    /// Same employee, date, amount, currency, and merchant.
+    pub expenses: HashMap<Uuid, Uuid>,
+    /// An attached receipt with the same content hash.
+    pub receipts: HashMap<Uuid, Uuid>,
+}
+
+impl DuplicateMatches {
+    /// The report `item` duplicates under `condition`, if any.
+    fn report_for(&self, condition: RuleCondition, item: &ExpenseItem) -> Option<Uuid> {
+        match condition {
+            RuleCondition::DuplicateExpense => self.expenses.get(&item.id).copied(),
+            RuleCondition::DuplicateReceipt => self.receipts.get(&item.id).copied(),
+            _ => None,
         }
     }
 }
 
-fn check_mileage(item: &ExpenseItem, caps: &[PolicyCap]) -> PolicyEvaluation {
-    let Some(cap) = caps
+/// Evaluates a single item against the item-scoped `rules`, interpreting its
+/// amount in the parent report's `currency`.
+///
+/// Thresholds are only compared against spend in `POLICY_CURRENCY`; other
+/// currencies produce a warning for manual review instead of a silently wrong
+/// comparison. `has_receipt` tells `missing_receipt` rules whether the item
+/// has a receipt attached; `non_working_day` rules only see weekends here and
+/// duplicate rules never fire.
+pub fn evaluate_item(
+    item: &ExpenseItem,
+    currency: Currency,
+    rules: &[PolicyRule],
+    has_receipt: bool,
+) -> PolicyEvaluation {
+    evaluate_item_rules(
+        item,
+        currency,
+        rules.iter(),
+        has_receipt,
+        &WorkCalendar::default(),
+        &DuplicateMatches::default(),
+    )
+}
+
+fn evaluate_item_rules<'a>(
+    item: &ExpenseItem,
+    currency: Currency,
+    rules: impl Iterator<Item = &'a PolicyRule>,
+    has_receipt: bool,
+    calendar: &WorkCalendar,
+    duplicates: &DuplicateMatches,
+) -> PolicyEvaluation {
+    let mut evaluation = PolicyEvaluation::ok();
+    for rule in rules.filter(|rule| {
+        rule.scope == RuleScope::Item
+            && rule_applies(rule, item)
+            && condition_holds(rule, item, has_receipt, calendar, duplicates)
+    }) {
+        let (amount_cents, headcount) = if rule.per_attendee {
+            (item.per_attendee_cents(), Some(item.headcount()))
+        } else {
+            (item.amount_cents, None)
+        };
+        let subject = Subject {
+            item_id: Some(item.id),
+            date: Some(item.expense_date),
+            day: calendar.day_off(item.expense_date),
+            travel_date: calendar.travel_date,
+            duplicate_report: duplicates.report_for(rule.condition, item),
+            exempt: is_justified_exception(item),
+            headcount,
+        };
+        apply_rule(
+            rule,
+            Money::new(amount_cents, currency),
+            &subject,
+            &mut evaluation,
+        );
+    }
+    evaluation
+}
+
+/// Evaluates every item in a report: item-scoped rules per item, day-scoped
+/// rules against each day's matching total, and report-scoped rules against
+/// the report's matching total.
+///
+/// Meals whose `location` resolves to one of the per-diem `rates` are checked
+/// against that location's daily M&IE allowance instead; item- and day-scoped
+/// meal rules that cap plain spend remain the cap for meals without a
+/// resolvable location. Likewise, mileage items that record `miles` are
+/// checked against miles times the mileage rate effective on their date
+/// instead of item- and day-scoped mileage rules.
+///
+/// `receipted` holds the IDs of items with at least one receipt attached;
+/// `calendar` decides which dates `non_working_day` rules count, and
+/// `duplicates` which items the duplicate conditions count.
+pub fn evaluate_items(
+    items: &[ExpenseItem],
+    currency: Currency,
+    rules: &[PolicyRule],
+    rates: &RateTables,
+    receipted: &HashSet<Uuid>,
+    calendar: &WorkCalendar,
+    duplicates: &DuplicateMatches,
+) -> PolicyEvaluation {
+    let per_diem: HashMap<Uuid, &PerDiemRate> = items
+        .iter()
+        .filter(|item| item.category == ExpenseCategory::Meal)
+        .filter_map(|item| resolve_item_rate(item, &rates.per_diem).map(|rate| (item.id, rate)))
+        .collect();
+    let mileage: HashMap<Uuid, (&MileageRate, f64)> = items
         .iter()
-        .find(|c| c.category == ExpenseCategory::Mileage && cap_active(c, item.expense_date))
-    else {
-        return PolicyEvaluation::ok();
+        .filter(|item| item.category == ExpenseCategory::Mileage)
+        .filter_map(|item| {
+            let rate = resolve_mileage_rate(item.expense_date, &rates.mileage)?;
+            Some((item.id, (rate, item.miles?)))
+        })
+        .collect();
+    // Category caps on plain spend give way to the rate an item resolved.
+    let covers = |rule: &PolicyRule, item: &ExpenseItem| {
+        rule_applies(rule, item)
+            && condition_holds(
+                rule,
+                item,
+                receipted.contains(&item.id),
+                calendar,
+                duplicates,
+            )
+            && !(rule.category.is_some()
+                && rule.scope != RuleScope::Report
+                && rule.condition == RuleCondition::Always
+                && !rule.per_attendee
+                && (per_diem.contains_key(&item.id) || mileage.contains_key(&item.id)))
     };
-    // For mileage the amount_cents represents the reimbursement amount already computed.
-    if item.amount_cents <= cap.amount_cents {
-        PolicyEvaluation::ok()
-    } else {
-        PolicyEvaluation::with_violation("Mileage exceeds configured reimbursement rate")
+
+    let mut evaluation = PolicyEvaluation::ok();
+    for item in items {
+        evaluation.merge(evaluate_item_rules(
+            item,
+            currency,
+            rules.iter().filter(|rule| covers(rule, item)),
+            receipted.contains(&item.id),
+            calendar,
+            duplicates,
+        ));
+    }
+
+    for rule in rules.iter().filter(|rule| rule.scope != RuleScope::Item) {
+        let matching: Vec<&ExpenseItem> = items.iter().filter(|item| covers(rule, item)).collect();
+        if rule.scope == RuleScope::Day {
+            // Per day: the total and whether every item is a justified exception.
+            let mut daily: BTreeMap<NaiveDate, (i64, bool)> = BTreeMap::new();
+            for item in matching {
+                let day = daily.entry(item.expense_date).or_insert((0, true));
+                day.0 += item.amount_cents;
+                day.1 &= is_justified_exception(item);
+            }
+            for (date, (total, exempt)) in daily {
+                let subject = Subject {
+                    item_id: None,
+                    date: Some(date),
+                    day: calendar.day_off(date),
+                    travel_date: calendar.travel_date,
+                    duplicate_report: None,
+                    exempt,
+                    headcount: None,
+                };
+                apply_rule(rule, Money::new(total, currency), &subject, &mut evaluation);
+            }
+        } else if !matching.is_empty() {
+            let total = matching.iter().map(|item| item.amount_cents).sum();
+            let subject = Subject {
+                item_id: None,
+                date: None,
+                day: None,
+                travel_date: None,
+                duplicate_report: None,
+                exempt: matching.iter().all(|item| is_justified_exception(item)),
+                headcount: None,
+            };
+            apply_rule(rule, Money::new(total, currency), &subject, &mut evaluation);
+        }
+    }
+
+    evaluation.merge(check_per_diem(items, currency, &per_diem));
+    evaluation.merge(check_mileage(items, currency, &mileage));
+    evaluation
+}
+
+/// Compares each day's meals at a location with that location's M&IE rate,
+/// counting the employee's share (`per_attendee_cents`) of group meals.
+fn check_per_diem(
+    items: &[ExpenseItem],
+    currency: Currency,
+    per_diem: &HashMap<Uuid, &PerDiemRate>,
+) -> PolicyEvaluation {
+    let mut daily: BTreeMap<(NaiveDate, Uuid), (&PerDiemRate, i64)> = BTreeMap::new();
+    for item in items {
+        if let Some(rate) = per_diem.get(&item.id) {
+            daily
+                .entry((item.expense_date, rate.id))
+                .or_insert((rate, 0))
+                .1 += item.per_attendee_cents();
+        }
+    }
+
+    let mut evaluation = PolicyEvaluation::ok();
+    for ((date, _), (rate, total)) in daily {
+        let spend = Money::new(total, currency);
+        let limit = Money::new(rate.meals_cents, POLICY_CURRENCY);
+        let cap = Some(CapReference {
+            kind: CapKind::PerDiemRate,
+            id: rate.id,
+            limit_cents: rate.meals_cents,
+        });
+        match spend.checked_cmp(&limit) {
+            Ok(std::cmp::Ordering::Greater) => evaluation.push(
+                FindingSeverity::Violation,
+                PER_DIEM_CODE,
+                format!(
+                    "Meals on {date} total {spend}, above the {limit} per-diem rate for {}",
+                    rate_label(rate)
+                ),
+                None,
+                cap,
+            ),
+            Ok(_) => {}
+            Err(_) => evaluation.push(
+                FindingSeverity::Warning,
+                CURRENCY_CODE,
+                unconverted_warning(spend, limit),
+                None,
+                cap,
+            ),
+        }
+    }
+    evaluation
+}
+
+/// Compares each mileage item with its miles at the rate effective on its
+/// date, rounded to the cent.
+fn check_mileage(
+    items: &[ExpenseItem],
+    currency: Currency,
+    mileage: &HashMap<Uuid, (&MileageRate, f64)>,
+) -> PolicyEvaluation {
+    let mut evaluation = PolicyEvaluation::ok();
+    for item in items {
+        let Some(&(rate, miles)) = mileage.get(&item.id) else {
+            continue;
+        };
+        let limit_cents = (miles * rate.rate_cents_per_mile).round() as i64;
+        let spend = Money::new(item.amount_cents, currency);
+        let limit = Money::new(limit_cents, POLICY_CURRENCY);
+        let cap = Some(CapReference {
+            kind: CapKind::MileageRate,
+            id: rate.id,
+            limit_cents,
+        });
+        match spend.checked_cmp(&limit) {
+            Ok(std::cmp::Ordering::Greater) => evaluation.push(
+                FindingSeverity::Violation,
+                MILEAGE_CODE,
+                format!(
+                    "The {spend} mileage claim on {} is above {limit} for {miles} miles at {} cents per mile",
+                    item.expense_date, rate.rate_cents_per_mile
+                ),
+                Some(item.id),
+                cap,
+            ),
+            Ok(_) => {}
+            Err(_) => evaluation.push(
+                FindingSeverity::Warning,
+                CURRENCY_CODE,
+                unconverted_warning(spend, limit),
+                Some(item.id),
+                cap,
+            ),
+        }
+    }
+    evaluation
+}
+
+/// The mileage rate in force on `date`: the latest effective on or before it.
+pub fn resolve_mileage_rate(date: NaiveDate, rates: &[MileageRate]) -> Option<&MileageRate> {
+    rates
+        .iter()
+        .filter(|rate| rate.effective_date <= date)
+        .max_by_key(|rate| rate.effective_date)
+}
+
+fn resolve_item_rate<'a>(item: &ExpenseItem, rates: &'a [PerDiemRate]) -> Option<&'a PerDiemRate> {
+    let location = parse_location(item.location.as_deref()?)?;
+    resolve_per_diem(&location, item.expense_date, rates)
+}
+
+/// Where an expense was incurred, as needed to look up a per-diem rate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PerDiemLocation {
+    pub city: String,
+    /// Two-letter state code; only set for US locations.
+    pub state: Option<String>,
+    pub country: String,
+}
+
+/// Parses an item's free-text `location`: `City, ST` or `City, State` for the
+/// US (a trailing ZIP code is ignored), `City, Country` elsewhere, or
+/// `City, Region, Country`. Returns `None` for anything less specific, since a
+/// bare city name cannot be placed reliably.
+pub fn parse_location(location: &str) -> Option<PerDiemLocation> {
+    let parts: Vec<&str> = location
+        .split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect();
+    let (city, region, country) = match parts.as_slice() {
+        [city, region] => (*city, *region, None),
+        [city, region, country] => (*city, *region, Some(*country)),
+        _ => return None,
+    };
+
+    let state = us_state_code(
+        region
+            .trim_end_matches(|c: char| c.is_ascii_digit() || c == '-')
+            .trim(),
+    );
+    let country = match country {
+        Some(country) if is_united_states(country) => "US".to_string(),
+        Some(country) => {
+            return Some(PerDiemLocation {
+                city: city.to_string(),
+                state: None,
+                country: country.to_uppercase(),
+            })
+        }
+        None if state.is_some() || is_united_states(region) => "US".to_string(),
+        None => region.to_uppercase(),
+    };
+    Some(PerDiemLocation {
+        city: city.to_string(),
+        state: state.map(str::to_string),
+        country,
+    })
+}
+
+/// Picks the most specific rate for `location` in effect on `date`: the
+/// city's, then the state's standard rate, then the country's.
+pub fn resolve_per_diem<'a>(
+    location: &PerDiemLocation,
+    date: NaiveDate,
+    rates: &'a [PerDiemRate],
+) -> Option<&'a PerDiemRate> {
+    let fiscal_year = current_fiscal_year(date).1;
+    let month_day = (date.month() * 100 + date.day()) as i16;
+    let mut best: Option<(u8, &PerDiemRate)> = None;
+    for rate in rates.iter().filter(|rate| {
+        rate.fiscal_year == fiscal_year
+            && rate.country.eq_ignore_ascii_case(&location.country)
+            && in_season(rate, month_day)
+    }) {
+        let Some(rank) = specificity(rate, location) else {
+            continue;
+        };
+        if best.is_none_or(|(best_rank, _)| rank > best_rank) {
+            best = Some((rank, rate));
+        }
+    }
+    best.map(|(_, rate)| rate)
+}
+
+/// How closely `rate` describes `location`, or `None` if it is for another
+/// place.
+fn specificity(rate: &PerDiemRate, location: &PerDiemLocation) -> Option<u8> {
+    if let Some(state) = &rate.state {
+        if !location
+            .state
+            .as_deref()
+            .is_some_and(|wanted| state.eq_ignore_ascii_case(wanted))
+        {
+            return None;
+        }
+    }
+    let city_match = match &rate.city {
+        Some(destination) if destination_includes(destination, &location.city) => true,
+        Some(_) => return None,
+        None => false,
+    };
+    Some(u8::from(rate.state.is_some()) * 2 + u8::from(city_match))
+}
+
+fn in_season(rate: &PerDiemRate, month_day: i16) -> bool {
+    match (rate.season_begin, rate.season_end) {
+        (Some(begin), Some(end)) if begin <= end => (begin..=end).contains(&month_day),
+        (Some(begin), Some(end)) => month_day >= begin || month_day <= end,
+        _ => true,
+    }
+}
+
+/// Whether a published destination such as `Boston / Cambridge` or
+/// `Washington DC (also the cities of ...)` names `city`.
+fn destination_includes(destination: &str, city: &str) -> bool {
+    let destination = destination.split('(').next().unwrap_or(destination);
+    let city = normalize_place(city);
+    destination
+        .split('/')
+        .any(|name| normalize_place(name) == city)
+}
+
+fn normalize_place(name: &str) -> String {
+    name.split_whitespace()
+        .map(|word| word.trim_matches('.').to_lowercase())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn rate_label(rate: &PerDiemRate) -> String {
+    match (&rate.city, &rate.state) {
+        (Some(city), Some(state)) => format!("{city}, {state}"),
+        (Some(city), None) => format!("{city}, {}", rate.country),
+        (None, Some(state)) => format!("{state} (standard rate)"),
+        (None, None) => format!("{} (standard rate)", rate.country),
+    }
+}
+
+fn is_united_states(name: &str) -> bool {
+    matches!(
+        normalize_place(name).as_str(),
+        "us" | "usa" | "u.s" | "u.s.a" | "united states" | "united states of america"
+    )
+}
+
+/// Maps a US state (or DC) name or two-letter code to its code.
+fn us_state_code(name: &str) -> Option<&'static str> {
+    let name = normalize_place(name);
+    US_STATES
+        .iter()
+        .find(|(code, full)| code.eq_ignore_ascii_case(&name) || full.eq_ignore_ascii_case(&name))
+        .map(|(code, _)| *code)
+}
+
+const US_STATES: [(&str, &str); 51] = [
+    ("AL", "Alabama"),
+    ("AK", "Alaska"),
+    ("AZ", "Arizona"),
+    ("AR", "Arkansas"),
+    ("CA", "California"),
+    ("CO", "Colorado"),
+    ("CT", "Connecticut"),
+    ("DE", "Delaware"),
+    ("DC", "District of Columbia"),
+    ("FL", "Florida"),
+    ("GA", "Georgia"),
+    ("HI", "Hawaii"),
+    ("ID", "Idaho"),
+    ("IL", "Illinois"),
+    ("IN", "Indiana"),
+    ("IA", "Iowa"),
+    ("KS", "Kansas"),
+    ("KY", "Kentucky"),
+    ("LA", "Louisiana"),
+    ("ME", "Maine"),
+    ("MD", "Maryland"),
+    ("MA", "Massachusetts"),
+    ("MI", "Michigan"),
+    ("MN", "Minnesota"),
+    ("MS", "Mississippi"),
+    ("MO", "Missouri"),
+    ("MT", "Montana"),
+    ("NE", "Nebraska"),
+    ("NV", "Nevada"),
+    ("NH", "New Hampshire"),
+    ("NJ", "New Jersey"),
+    ("NM", "New Mexico"),
+    ("NY", "New York"),
+    ("NC", "North Carolina"),
+    ("ND", "North Dakota"),
+    ("OH", "Ohio"),
+    ("OK", "Oklahoma"),
+    ("OR", "Oregon"),
+    ("PA", "Pennsylvania"),
+    ("RI", "Rhode Island"),
+    ("SC", "South Carolina"),
+    ("SD", "South Dakota"),
+    ("TN", "Tennessee"),
+    ("TX", "Texas"),
+    ("UT", "Utah"),
+    ("VT", "Vermont"),
+    ("VA", "Virginia"),
+    ("WA", "Washington"),
+    ("WV", "West Virginia"),
+    ("WI", "Wisconsin"),
+    ("WY", "Wyoming"),
+];
+
+/// Whether `rule` covers `item`: enabled, matching its category (when set),
+/// and active on the item's `expense_date`.
+fn rule_applies(rule: &PolicyRule, item: &ExpenseItem) -> bool {
+    rule.enabled
+        && rule
+            .category
+            .is_none_or(|category| category == item.category)
+        && item.expense_date >= rule.active_from
+        && rule.active_to.is_none_or(|end| item.expense_date <= end)
+}
+
+/// Whether `item` counts toward `rule` given its `condition`.
+fn condition_holds(
+    rule: &PolicyRule,
+    item: &ExpenseItem,
+    has_receipt: bool,
+    calendar: &WorkCalendar,
+    duplicates: &DuplicateMatches,
+) -> bool {
+    match rule.condition {
+        RuleCondition::Always => true,
+        RuleCondition::MissingReceipt => !has_receipt && item.category != ExpenseCategory::Mileage,
+        RuleCondition::MissingAttendees => item.attendee_names().is_empty(),
+        RuleCondition::NonWorkingDay => calendar.is_flagged(item.expense_date),
+        RuleCondition::LateBooking => rule
+            .advance_days
+            .is_some_and(|days| calendar.is_late_booking(item.expense_date, days)),
+        RuleCondition::DuplicateExpense | RuleCondition::DuplicateReceipt => {
+            duplicates.report_for(rule.condition, item).is_some()
+        }
+    }
+}
+
+/// Whether the employee marked `item` a policy exception and said why, which
+/// lets a report with `blocking` violations on the item be submitted.
+pub fn is_justified_exception(item: &ExpenseItem) -> bool {
+    item.is_policy_exception
+        && item
+            .exception_justification
+            .as_deref()
+            .is_some_and(|justification| !justification.trim().is_empty())
+}
+
+/// What a rule's spend was measured over.
+struct Subject {
+    /// Set at item scope.
+    item_id: Option<Uuid>,
+    /// The item's or day's date; `None` at report scope.
+    date: Option<NaiveDate>,
+    /// The holiday or weekday name when `date` is a day off.
+    day: Option<String>,
+    /// Departure date of the report's trip, if linked.
+    travel_date: Option<NaiveDate>,
+    /// The other report an item duplicates, for duplicate rules.
+    duplicate_report: Option<Uuid>,
+    /// Downgrades a blocking rule's finding to a violation, which does not
+    /// block submission.
+    exempt: bool,
+    /// Set when the spend is a per-attendee share.
+    headcount: Option<i64>,
+}
+
+/// Records `rule` against `spend` when its comparison holds.
+fn apply_rule(
+    rule: &PolicyRule,
+    spend: Money,
+    subject: &Subject,
+    evaluation: &mut PolicyEvaluation,
+) {
+    let limit = Money::new(rule.threshold_cents, POLICY_CURRENCY);
+    let cap = Some(CapReference {
+        kind: CapKind::Rule,
+        id: rule.id,
+        limit_cents: rule.threshold_cents,
+    });
+    match spend.checked_cmp(&limit) {
+        Ok(ordering) if rule.comparison.holds(ordering) => {
+            let severity = match rule.severity {
+                RuleSeverity::Warning => FindingSeverity::Warning,
+                RuleSeverity::Violation => FindingSeverity::Violation,
+                RuleSeverity::Blocking if subject.exempt => FindingSeverity::Violation,
+                RuleSeverity::Blocking => FindingSeverity::Blocking,
+            };
+            evaluation.push(
+                severity,
+                &rule.name,
+                render_message(&rule.message, spend, limit, subject),
+                subject.item_id,
+                cap,
+            );
+        }
+        Ok(_) => {}
+        Err(_) => evaluation.push(
+            FindingSeverity::Warning,
+            CURRENCY_CODE,
+            unconverted_warning(spend, limit),
+            subject.item_id,
+            cap,
+        ),
     }
 }
 
-fn cap_active(cap: &PolicyCap, date: NaiveDate) -> bool {
-    let after_start = date >= cap.active_from;
-    let before_end = cap.active_to.map(|d| date <= d).unwrap_or(true);
-    after_start && before_end
+fn render_message(template: &str, spend: Money, limit: Money, subject: &Subject) -> String {
+    let mut message = template
+        .replace("{amount}", &spend.to_string())
+        .replace("{limit}", &limit.to_string());
+    if let Some(date) = subject.date {
+        message = message.replace("{date}", &date.to_string());
+    }
+    if let Some(day) = &subject.day {
+        message = message.replace("{day}", day);
+    }
+    if let Some(travel_date) = subject.travel_date {
+        message = message.replace("{travel_date}", &travel_date.to_string());
+        if let Some(date) = subject.date {
+            message = message.replace("{days}", &(travel_date - date).num_days().to_string());
+        }
+    }
+    if let Some(report_id) = subject.duplicate_report {
+        message = message.replace("{duplicate_report}", &report_id.to_string());
+    }
+    if let Some(headcount) = subject.headcount {
+        message = message.replace("{headcount}", &headcount.to_string());
+    }
+    message
+}
+
+fn unconverted_warning(spend: Money, limit: Money) -> String {
+    format!("Cannot compare {spend} against the {limit} policy limit without currency conversion")
+}
+
+/// Compares EXIF capture times from an item's receipts with the claimed
+/// `expense_date`.
+///
+/// This is a soft signal: receipts are often photographed a few days after
+/// the purchase, so mismatches beyond `tolerance_days` only produce warnings
+/// for reviewers.
+pub fn check_receipt_capture_dates(
+    item: &ExpenseItem,
+    captured_at: &[NaiveDateTime],
+    tolerance_days: u32,
+) -> PolicyEvaluation {
+    let mut evaluation = PolicyEvaluation::ok();
+    for captured in captured_at {
+        let captured_on = captured.date();
+        let drift = (captured_on - item.expense_date).num_days().abs();
+        if drift > i64::from(tolerance_days) {
+            evaluation.push(
+                FindingSeverity::Warning,
+                RECEIPT_CAPTURE_CODE,
+                format!(
+                    "Receipt for expense item {} was captured on {}, {} days from the claimed expense date {}",
+                    item.id, captured_on, drift, item.expense_date
+                ),
+                Some(item.id),
+                None,
+            );
+        }
+    }
+    evaluation
 }
 
+/// The October–September fiscal year containing `date`, as the calendar years
+/// it starts and ends in.
 pub fn current_fiscal_year(date: NaiveDate) -> (i32, i32) {
     let year = date.year();
     if date.month() >= 10 {
@@ -97,3 +928,652 @@ pub fn current_fiscal_year(date: NaiveDate) -> (i32, i32) {
         (year - 1, year)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::RuleComparison;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 6, day).unwrap()
+    }
+
+    fn item(category: ExpenseCategory, day: u32, amount_cents: i64) -> ExpenseItem {
+        ExpenseItem {
+            id: Uuid::new_v4(),
+            report_id: Uuid::new_v4(),
+            expense_date: date(day),
+            category,
+            gl_account_id: None,
+            description: None,
+            attendees: None,
+            location: None,
+            amount_cents,
+            reimbursable: true,
+            payment_method: None,
+            is_policy_exception: false,
+            exception_justification: None,
+            class: None,
+            tax_amount_cents: None,
+            tax_jurisdiction: None,
+            miles: None,
+            merchant: None,
+            cost_center_id: None,
+        }
+    }
+
+    fn rule(
+        category: Option<ExpenseCategory>,
+        threshold_cents: i64,
+        scope: RuleScope,
+        severity: RuleSeverity,
+        message: &str,
+    ) -> PolicyRule {
+        PolicyRule {
+            id: Uuid::new_v4(),
+            org_id: None,
+            name: "rule".to_string(),
+            category,
+            comparison: RuleComparison::Gt,
+            threshold_cents,
+            scope,
+            severity,
+            condition: RuleCondition::Always,
+            per_attendee: false,
+            advance_days: None,
+            message: message.to_string(),
+            active_from: date(1),
+            active_to: None,
+            enabled: true,
+            updated_by: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn item_rules_match_category_and_render_placeholders() {
+        let rules = [rule(
+            Some(ExpenseCategory::Meal),
+            5_000,
+            RuleScope::Item,
+            RuleSeverity::Violation,
+            "Meal of {amount} on {date} exceeds {limit}",
+        )];
+
+        let over = evaluate_item(
+            &item(ExpenseCategory::Meal, 3, 6_000),
+            Currency::USD,
+            &rules,
+            false,
+        );
+        assert!(!over.is_valid);
+        assert_eq!(
+            over.violations(),
+            vec!["Meal of $60.00 on 2024-06-03 exceeds $50.00"]
+        );
+        let lodging = evaluate_item(
+            &item(ExpenseCategory::Lodging, 3, 60_000),
+            Currency::USD,
+            &rules,
+            false,
+        );
+        assert!(lodging.is_valid);
+
+        let mut disabled = rules[0].clone();
+        disabled.enabled = false;
+        let ignored = evaluate_item(
+            &item(ExpenseCategory::Meal, 3, 6_000),
+            Currency::USD,
+            &[disabled],
+            false,
+        );
+        assert!(ignored.is_valid);
+    }
+
+    #[test]
+    fn day_and_report_rules_compare_totals() {
+        let rules = [
+            rule(
+                Some(ExpenseCategory::Meal),
+                7_500,
+                RuleScope::Day,
+                RuleSeverity::Warning,
+                "Meals on {date} total {amount}",
+            ),
+            rule(
+                None,
+                20_000,
+                RuleScope::Report,
+                RuleSeverity::Violation,
+                "Report total {amount} exceeds {limit}",
+            ),
+        ];
+        let items = [
+            item(ExpenseCategory::Meal, 3, 4_000),
+            item(ExpenseCategory::Meal, 3, 4_000),
+            item(ExpenseCategory::Meal, 4, 4_000),
+            item(ExpenseCategory::Lodging, 4, 9_000),
+        ];
+
+        let evaluation = evaluate_items(
+            &items,
+            Currency::USD,
+            &rules,
+            &RateTables::default(),
+            &HashSet::new(),
+            &WorkCalendar::default(),
+            &DuplicateMatches::default(),
+        );
+
+        assert_eq!(
+            evaluation.warnings(),
+            vec!["Meals on 2024-06-03 total $80.00"]
+        );
+        assert_eq!(
+            evaluation.violations(),
+            vec!["Report total $210.00 exceeds $200.00"]
+        );
+        assert!(!evaluation.is_valid);
+    }
+
+    #[test]
+    fn foreign_currency_spend_is_flagged_for_review() {
+        let rules = [rule(
+            None,
+            5_000,
+            RuleScope::Item,
+            RuleSeverity::Violation,
+            "over",
+        )];
+        let eur = Currency::parse("EUR").unwrap();
+
+        let evaluation = evaluate_item(&item(ExpenseCategory::Meal, 3, 9_000), eur, &rules, false);
+
+        assert!(evaluation.is_valid);
+        assert_eq!(evaluation.warnings().len(), 1);
+    }
+
+    fn rate(state: Option<&str>, city: Option<&str>, meals_cents: i64) -> PerDiemRate {
+        PerDiemRate {
+            id: Uuid::new_v4(),
+            country: "US".to_string(),
+            state: state.map(str::to_string),
+            city: city.map(str::to_string),
+            county: None,
+            fiscal_year: 2024,
+            season_begin: None,
+            season_end: None,
+            meals_cents,
+            lodging_cents: None,
+            source: "gsa_conus".to_string(),
+            imported_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn locations_parse_us_and_foreign_forms() {
+        let chicago = PerDiemLocation {
+            city: "Chicago".to_string(),
+            state: Some("IL".to_string()),
+            country: "US".to_string(),
+        };
+        assert_eq!(parse_location("Chicago, IL"), Some(chicago.clone()));
+        assert_eq!(
+            parse_location(" Chicago , Illinois 60601"),
+            Some(chicago.clone())
+        );
+        assert_eq!(parse_location("Chicago, IL, USA"), Some(chicago));
+        assert_eq!(
+            parse_location("London, gb"),
+            Some(PerDiemLocation {
+                city: "London".to_string(),
+                state: None,
+                country: "GB".to_string(),
+            })
+        );
+        assert_eq!(parse_location("Chicago"), None);
+    }
+
+    #[test]
+    fn per_diem_prefers_city_then_state_then_country_rates() {
+        let mut winter = rate(Some("CO"), Some("Aspen"), 9_200);
+        winter.season_begin = Some(1201);
+        winter.season_end = Some(331);
+        let rates = [
+            rate(None, None, 5_900),
+            rate(Some("CO"), None, 6_400),
+            rate(Some("CO"), Some("Denver / Aurora"), 7_900),
+            winter,
+        ];
+        let location = |city: &str| parse_location(&format!("{city}, CO")).unwrap();
+        let meals_on = |city: &str, date: NaiveDate| {
+            resolve_per_diem(&location(city), date, &rates).map(|rate| rate.meals_cents)
+        };
+
+        assert_eq!(meals_on("aurora", date(3)), Some(7_900));
+        assert_eq!(meals_on("Boulder", date(3)), Some(6_400));
+        assert_eq!(meals_on("Aspen", date(3)), Some(6_400));
+        assert_eq!(
+            meals_on("Aspen", NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()),
+            Some(9_200)
+        );
+        let miami = parse_location("Miami, FL").unwrap();
+        assert_eq!(
+            resolve_per_diem(&miami, date(3), &rates).map(|rate| rate.meals_cents),
+            Some(5_900)
+        );
+        // FY2025 starts in October 2024.
+        let october = NaiveDate::from_ymd_opt(2024, 10, 2).unwrap();
+        assert!(resolve_per_diem(&miami, october, &rates).is_none());
+    }
+
+    #[test]
+    fn located_meals_use_the_per_diem_rate_instead_of_meal_rules() {
+        let rules = [rule(
+            Some(ExpenseCategory::Meal),
+            5_000,
+            RuleScope::Item,
+            RuleSeverity::Violation,
+            "Meal exceeds {limit}",
+        )];
+        let rates = RateTables {
+            per_diem: vec![rate(Some("IL"), Some("Chicago"), 7_900)],
+            ..RateTables::default()
+        };
+        let mut lunch = item(ExpenseCategory::Meal, 3, 6_000);
+        lunch.location = Some("Chicago, IL".to_string());
+        let mut dinner = lunch.clone();
+        dinner.id = Uuid::new_v4();
+        let unlocated = item(ExpenseCategory::Meal, 4, 6_000);
+
+        let within = evaluate_items(
+            &[lunch.clone(), unlocated.clone()],
+            Currency::USD,
+            &rules,
+            &rates,
+            &HashSet::new(),
+            &WorkCalendar::default(),
+            &DuplicateMatches::default(),
+        );
+        assert_eq!(within.violations(), vec!["Meal exceeds $50.00"]);
+
+        let over = evaluate_items(
+            &[lunch, dinner],
+            Currency::USD,
+            &rules,
+            &rates,
+            &HashSet::new(),
+            &WorkCalendar::default(),
+            &DuplicateMatches::default(),
+        );
+        assert_eq!(
+            over.violations(),
+            vec![
+                "Meals on 2024-06-03 total $120.00, above the $79.00 per-diem rate for Chicago, IL"
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_receipts_block_submission_unless_justified() {
+        let mut receipt_rule = rule(
+            None,
+            2_500,
+            RuleScope::Item,
+            RuleSeverity::Blocking,
+            "Receipt required for {amount}",
+        );
+        receipt_rule.condition = RuleCondition::MissingReceipt;
+        let rule_id = receipt_rule.id;
+        let rules = [receipt_rule];
+        let taxi = item(ExpenseCategory::GroundTransport, 3, 4_000);
+        let taxi_id = taxi.id;
+        let coffee = item(ExpenseCategory::Meal, 3, 900);
+        let drive = item(ExpenseCategory::Mileage, 3, 9_000);
+        let mut hotel = item(ExpenseCategory::Lodging, 3, 20_000);
+        hotel.is_policy_exception = true;
+        hotel.exception_justification = Some("Front desk could not print a folio".to_string());
+        let mut dinner = item(ExpenseCategory::Meal, 3, 6_000);
+        let receipted = HashSet::from([dinner.id]);
+        dinner.is_policy_exception = true;
+
+        let evaluation = evaluate_items(
+            &[taxi, coffee, drive, hotel, dinner],
+            Currency::USD,
+            &rules,
+            &RateTables::default(),
+            &receipted,
+            &WorkCalendar::default(),
+            &DuplicateMatches::default(),
+        );
+
+        assert!(!evaluation.is_valid);
+        assert_eq!(
+            evaluation.violations(),
+            vec![
+                "Receipt required for $40.00",
+                "Receipt required for $200.00"
+            ]
+        );
+        assert_eq!(evaluation.blocking(), vec!["Receipt required for $40.00"]);
+        let taxi_finding = &evaluation.findings[0];
+        assert_eq!(taxi_finding.severity, FindingSeverity::Blocking);
+        assert_eq!(taxi_finding.code, "rule");
+        assert_eq!(taxi_finding.item_id, Some(taxi_id));
+        assert_eq!(
+            taxi_finding.cap,
+            Some(CapReference {
+                kind: CapKind::Rule,
+                id: rule_id,
+                limit_cents: 2_500,
+            })
+        );
+        assert_eq!(evaluation.findings[1].severity, FindingSeverity::Violation);
+    }
+
+    #[test]
+    fn per_attendee_rules_split_group_meals() {
+        let mut per_head = rule(
+            Some(ExpenseCategory::Meal),
+            4_000,
+            RuleScope::Item,
+            RuleSeverity::Violation,
+            "{amount} per head for {headcount} exceeds {limit}",
+        );
+        per_head.per_attendee = true;
+        let mut attendees_required = rule(
+            Some(ExpenseCategory::Meal),
+            5_000,
+            RuleScope::Item,
+            RuleSeverity::Blocking,
+            "List attendees for the {amount} meal",
+        );
+        attendees_required.condition = RuleCondition::MissingAttendees;
+        let rules = [per_head, attendees_required];
+        let mut client_dinner = item(ExpenseCategory::Meal, 3, 15_001);
+        client_dinner.attendees = Some("S. Mills; A. Chen\nR. Ortiz".to_string());
+        let mut team_lunch = item(ExpenseCategory::Meal, 4, 16_000);
+        team_lunch.attendees = Some("S. Mills, A. Chen, R. Ortiz, J. Park, ".to_string());
+
+        assert_eq!(client_dinner.per_attendee_cents(), 5_000);
+        let evaluation = evaluate_items(
+            &[
+                client_dinner,
+                team_lunch,
+                item(ExpenseCategory::Meal, 5, 6_000),
+            ],
+            Currency::USD,
+            &rules,
+            &RateTables::default(),
+            &HashSet::new(),
+            &WorkCalendar::default(),
+            &DuplicateMatches::default(),
+        );
+
+        assert_eq!(
+            evaluation.violations(),
+            vec![
+                "$50.00 per head for 3 exceeds $40.00",
+                "$60.00 per head for 1 exceeds $40.00",
+                "List attendees for the $60.00 meal",
+            ]
+        );
+        assert_eq!(
+            evaluation.blocking(),
+            vec!["List attendees for the $60.00 meal"]
+        );
+    }
+    #[test]
+    fn non_working_day_rules_flag_weekends_and_holidays_outside_trips() {
+        let mut day_off = rule(
+            None,
+            0,
+            RuleScope::Item,
+            RuleSeverity::Warning,
+            "{amount} on {date} falls on {day}",
+        );
+        day_off.condition = RuleCondition::NonWorkingDay;
+        let rules = [day_off];
+        let items = [
+            item(ExpenseCategory::Meal, 1, 2_000),
+            item(ExpenseCategory::Lodging, 3, 15_000),
+            item(ExpenseCategory::Meal, 19, 3_000),
+        ];
+        let mut calendar = WorkCalendar {
+            holidays: HashMap::from([(date(19), "Juneteenth".to_string())]),
+            trip: None,
+            travel_date: None,
+        };
+
+        let evaluation = evaluate_items(
+            &items,
+            Currency::USD,
+            &rules,
+            &RateTables::default(),
+            &HashSet::new(),
+            &calendar,
+            &DuplicateMatches::default(),
+        );
+        assert!(evaluation.is_valid);
+        assert_eq!(
+            evaluation.warnings(),
+            vec![
+                "$20.00 on 2024-06-01 falls on Saturday",
+                "$30.00 on 2024-06-19 falls on Juneteenth"
+            ]
+        );
+
+        calendar.trip = Some((date(18), date(21)));
+        let on_trip = evaluate_items(
+            &items,
+            Currency::USD,
+            &rules,
+            &RateTables::default(),
+            &HashSet::new(),
+            &calendar,
+            &DuplicateMatches::default(),
+        );
+        assert_eq!(
+            on_trip.warnings(),
+            vec!["$20.00 on 2024-06-01 falls on Saturday"]
+        );
+    }
+
+    #[test]
+    fn late_booking_rules_measure_airfare_lead_time_against_the_trip() {
+        let mut advance = rule(
+            Some(ExpenseCategory::Airfare),
+            0,
+            RuleScope::Item,
+            RuleSeverity::Warning,
+            "Booked {days} days before the {travel_date} departure",
+        );
+        advance.condition = RuleCondition::LateBooking;
+        advance.advance_days = Some(14);
+        let rules = [advance];
+        let items = [
+            item(ExpenseCategory::Airfare, 3, 45_000),
+            item(ExpenseCategory::Airfare, 10, 38_000),
+            item(ExpenseCategory::Lodging, 20, 20_000),
+        ];
+        let mut calendar = WorkCalendar::default();
+
+        let unlinked = evaluate_items(
+            &items,
+            Currency::USD,
+            &rules,
+            &RateTables::default(),
+            &HashSet::new(),
+            &calendar,
+            &DuplicateMatches::default(),
+        );
+        assert!(unlinked.warnings().is_empty());
+
+        calendar.travel_date = Some(date(17));
+        let evaluation = evaluate_items(
+            &items,
+            Currency::USD,
+            &rules,
+            &RateTables::default(),
+            &HashSet::new(),
+            &calendar,
+            &DuplicateMatches::default(),
+        );
+        assert_eq!(
+            evaluation.warnings(),
+            vec!["Booked 7 days before the 2024-06-17 departure"]
+        );
+    }
+
+    #[test]
+    fn mileage_uses_the_rate_effective_on_the_expense_date() {
+        let rules = [rule(
+            Some(ExpenseCategory::Mileage),
+            5_000,
+            RuleScope::Item,
+            RuleSeverity::Violation,
+            "Mileage exceeds {limit}",
+        )];
+        let mileage_rate = |day: u32, cents: f64| MileageRate {
+            id: Uuid::new_v4(),
+            effective_date: date(day),
+            rate_cents_per_mile: cents,
+            source_reference: None,
+            updated_by: None,
+            updated_at: Utc::now(),
+        };
+        let rates = RateTables {
+            mileage: vec![mileage_rate(1, 65.5), mileage_rate(10, 67.0)],
+            ..RateTables::default()
+        };
+        let mut early = item(ExpenseCategory::Mileage, 5, 7_900);
+        early.miles = Some(120.0);
+        let mut late = item(ExpenseCategory::Mileage, 12, 8_100);
+        late.miles = Some(120.0);
+        let unmeasured = item(ExpenseCategory::Mileage, 12, 6_000);
+
+        let evaluation = evaluate_items(
+            &[early.clone(), late.clone(), unmeasured],
+            Currency::USD,
+            &rules,
+            &rates,
+            &HashSet::new(),
+            &WorkCalendar::default(),
+            &DuplicateMatches::default(),
+        );
+
+        assert_eq!(
+            evaluation.violations(),
+            vec![
+                "Mileage exceeds $50.00",
+                "The $79.00 mileage claim on 2024-06-05 is above $78.60 for 120 miles at 65.5 cents per mile",
+                "The $81.00 mileage claim on 2024-06-12 is above $80.40 for 120 miles at 67 cents per mile",
+            ]
+        );
+        let finding = &evaluation.findings[evaluation.findings.len() - 1];
+        assert_eq!(finding.item_id, Some(late.id));
+        assert_eq!(finding.cap.map(|cap| cap.kind), Some(CapKind::MileageRate));
+        assert_eq!(finding.cap.map(|cap| cap.limit_cents), Some(8_040));
+        assert!(resolve_mileage_rate(date(5), &rates.mileage)
+            .is_some_and(|rate| rate.rate_cents_per_mile == 65.5));
+        assert!(resolve_mileage_rate(
+            NaiveDate::from_ymd_opt(2024, 5, 31).unwrap(),
+            &rates.mileage
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn duplicate_rules_name_the_report_already_claiming_the_item() {
+        let mut same_expense = rule(
+            None,
+            0,
+            RuleScope::Item,
+            RuleSeverity::Warning,
+            "{amount} on {date} is already on report {duplicate_report}",
+        );
+        same_expense.condition = RuleCondition::DuplicateExpense;
+        let mut same_receipt = rule(
+            None,
+            0,
+            RuleScope::Item,
+            RuleSeverity::Blocking,
+            "Receipt already attached to report {duplicate_report}",
+        );
+        same_receipt.condition = RuleCondition::DuplicateReceipt;
+        let rules = [same_expense, same_receipt];
+        let taxi = item(ExpenseCategory::GroundTransport, 3, 4_000);
+        let hotel = item(ExpenseCategory::Lodging, 3, 20_000);
+        let earlier = Uuid::new_v4();
+        let duplicates = DuplicateMatches {
+            expenses: HashMap::from([(taxi.id, earlier)]),
+            receipts: HashMap::from([(hotel.id, earlier)]),
+        };
+
+        let evaluation = evaluate_items(
+            &[taxi, hotel.clone(), item(ExpenseCategory::Meal, 3, 900)],
+            Currency::USD,
+            &rules,
+            &RateTables::default(),
+            &HashSet::new(),
+            &WorkCalendar::default(),
+            &duplicates,
+        );
+
+        assert_eq!(
+            evaluation.warnings(),
+            vec![format!(
+                "$40.00 on 2024-06-03 is already on report {earlier}"
+            )]
+        );
+        assert_eq!(
+            evaluation.blocking(),
+            vec![format!("Receipt already attached to report {earlier}")]
+        );
+        assert_eq!(evaluation.findings[1].item_id, Some(hotel.id));
+    }
+
+    #[test]
+    fn diff_findings_matches_by_severity_code_and_item() {
+        let meal = Uuid::new_v4();
+        let mut before = PolicyEvaluation::ok();
+        before.push(
+            FindingSeverity::Blocking,
+            "receipt_required",
+            "$40.00",
+            Some(meal),
+            None,
+        );
+        before.push(
+            FindingSeverity::Warning,
+            "daily_meal_total",
+            "June 3",
+            None,
+            None,
+        );
+        let mut after = PolicyEvaluation::ok();
+        after.push(
+            FindingSeverity::Blocking,
+            "receipt_required",
+            "$45.00",
+            Some(meal),
+            None,
+        );
+        after.push(
+            FindingSeverity::Violation,
+            "budget",
+            "Travel at 100%",
+            None,
+            None,
+        );
+
+        let (introduced, resolved) = diff_findings(&before.findings, &after.findings);
+
+        assert_eq!(introduced.len(), 1);
+        assert_eq!(introduced[0].code, "budget");
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].code, "daily_meal_total");
+    }
+}
@@ -0,0 +1,145 @@
+//! `EmployeeDirectory`: employee lookup by id or HR identifier.
+
+use std::sync::Arc;
+
+use tonic::{Request, Response, Status};
+use uuid::Uuid;
+
+use crate::{
+    api::validation::Validator,
+    domain::models,
+    infrastructure::state::AppState,
+    services::employees::{EmployeeLookup, EmployeeService},
+};
+
+use super::v1::employee_directory_server::EmployeeDirectory;
+
+/// Names the employee by exactly one of `id` and `hr_identifier`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GetEmployeeRequest {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub hr_identifier: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Employee {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub hr_identifier: String,
+    #[prost(string, optional, tag = "3")]
+    pub manager_id: Option<String>,
+    #[prost(string, optional, tag = "4")]
+    pub department: Option<String>,
+    /// `employee`, `manager`, `finance`, or `admin`.
+    #[prost(string, tag = "5")]
+    pub role: String,
+    /// RFC 3339.
+    #[prost(string, tag = "6")]
+    pub created_at: String,
+}
+
+impl From<models::Employee> for Employee {
+    fn from(employee: models::Employee) -> Self {
+        Self {
+            id: employee.id.to_string(),
+            hr_identifier: employee.hr_identifier,
+            manager_id: employee.manager_id.map(|id| id.to_string()),
+            department: employee.department,
+            role: employee.role.as_str().to_string(),
+            created_at: employee.created_at.to_rfc3339(),
+        }
+    }
+}
+
+impl GetEmployeeRequest {
+    fn lookup(&self, v: &mut Validator) -> Option<EmployeeLookup> {
+        employee_lookup(&self.id, &self.hr_identifier, "id", v)
+    }
+}
+
+/// Reads an id-or-HR-identifier pair, recording a problem unless exactly
+/// one is set and the id is a UUID. `id_field` names the id for messages.
+pub(super) fn employee_lookup(
+    id: &str,
+    hr_identifier: &str,
+    id_field: &str,
+    v: &mut Validator,
+) -> Option<EmployeeLookup> {
+    let (id, hr_identifier) = (id.trim(), hr_identifier.trim());
+    match (id.is_empty(), hr_identifier.is_empty()) {
+        (false, true) => match Uuid::parse_str(id) {
+            Ok(id) => Some(EmployeeLookup::Id(id)),
+            Err(_) => {
+                v.error(id_field, "must be a UUID");
+                None
+            }
+        },
+        (true, false) => Some(EmployeeLookup::HrIdentifier(hr_identifier.to_string())),
+        _ => {
+            v.error(
+                id_field,
+                format!("exactly one of {id_field} and hr_identifier is required"),
+            );
+            None
+        }
+    }
+}
+
+pub struct Directory {
+    state: Arc<AppState>,
+}
+
+impl Directory {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+}
+
+#[tonic::async_trait]
+impl EmployeeDirectory for Directory {
+    async fn get_employee(
+        &self,
+        request: Request<GetEmployeeRequest>,
+    ) -> Result<Response<Employee>, Status> {
+        let mut v = Validator::new();
+        let lookup = request.get_ref().lookup(&mut v);
+        v.finish()?;
+        let Some(lookup) = lookup else {
+            return Err(Status::invalid_argument("no employee named"));
+        };
+        let employee = EmployeeService::new(Arc::clone(&self.state))
+            .find(&lookup)
+            .await?;
+        Ok(Response::new(employee.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn employees_are_named_by_exactly_one_key() {
+        let lookup = |id: &str, hr: &str| {
+            let mut v = Validator::new();
+            let lookup = employee_lookup(id, hr, "employee_id", &mut v);
+            (lookup, v.into_errors())
+        };
+        let id = Uuid::new_v4();
+
+        assert_eq!(lookup(&id.to_string(), "").0, Some(EmployeeLookup::Id(id)));
+        assert_eq!(
+            lookup("", " E100 ").0,
+            Some(EmployeeLookup::HrIdentifier("E100".into()))
+        );
+        assert_eq!(lookup("nope", "").1["employee_id"], ["must be a UUID"]);
+        assert_eq!(
+            lookup(&id.to_string(), "E100").1["employee_id"],
+            ["exactly one of employee_id and hr_identifier is required"]
+        );
+        assert!(lookup("", "").0.is_none());
+    }
+}
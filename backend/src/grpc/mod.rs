@@ -0,0 +1,162 @@
+//! gRPC interface for internal services.
+//!
+//! The card-feed and HR sync services call `expense_portal.v1` on
+//! `EXPENSES__GRPC__PORT`: `ReportIngestion.CreateReport` files a draft
+//! report for an employee and `EmployeeDirectory.GetEmployee` looks one up.
+//! Both go through the same service layer and validation rules as the REST
+//! API. Callers authenticate with the shared `EXPENSES__GRPC__AUTH_TOKEN`
+//! rather than employee tokens; errors carry the gRPC code matching the REST
+//! status and the same message. The contract is published in
+//! `proto/expense_portal/v1/expense_portal.proto`.
+
+use std::{future::Future, net::SocketAddr, sync::Arc};
+
+use axum::http::StatusCode;
+use subtle::ConstantTimeEq;
+use tonic::{service::interceptor::InterceptedService, transport::Server, Code, Request, Status};
+
+use crate::{
+    api::error::ApiError, infrastructure::state::AppState, services::errors::ServiceError,
+};
+
+pub mod employees;
+pub mod reports;
+
+/// Stubs generated by `build.rs`.
+pub mod v1 {
+    include!(concat!(
+        env!("OUT_DIR"),
+        "/expense_portal.v1.EmployeeDirectory.rs"
+    ));
+    include!(concat!(
+        env!("OUT_DIR"),
+        "/expense_portal.v1.ReportIngestion.rs"
+    ));
+}
+
+use v1::{
+    employee_directory_server::EmployeeDirectoryServer,
+    report_ingestion_server::ReportIngestionServer,
+};
+
+/// Serves both services on `addr` until `shutdown` resolves.
+pub async fn serve(
+    state: Arc<AppState>,
+    addr: SocketAddr,
+    shutdown: impl Future<Output = ()>,
+) -> Result<(), tonic::transport::Error> {
+    let auth = BearerAuth::new(&state.config.grpc.auth_token);
+    Server::builder()
+        .add_service(InterceptedService::new(
+            EmployeeDirectoryServer::new(employees::Directory::new(Arc::clone(&state))),
+            auth.clone(),
+        ))
+        .add_service(InterceptedService::new(
+            ReportIngestionServer::new(reports::Ingestion::new(state)),
+            auth,
+        ))
+        .serve_with_shutdown(addr, shutdown)
+        .await
+}
+
+/// Interceptor admitting calls whose `authorization` metadata is
+/// `Bearer <token>`.
+#[derive(Clone)]
+struct BearerAuth {
+    expected: Arc<str>,
+}
+
+impl BearerAuth {
+    fn new(token: &str) -> Self {
+        Self {
+            expected: format!("Bearer {}", token.trim()).into(),
+        }
+    }
+}
+
+impl tonic::service::Interceptor for BearerAuth {
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        let presented = request
+            .metadata()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        if bool::from(presented.as_bytes().ct_eq(self.expected.as_bytes())) {
+            Ok(request)
+        } else {
+            Err(Status::unauthenticated("missing or invalid service token"))
+        }
+    }
+}
+
+/// The gRPC form of an API error: the code matching its HTTP status, and its
+/// message. Internal errors are reported and not described.
+fn status(err: ApiError) -> Status {
+    let code = match err.status() {
+        StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => Code::InvalidArgument,
+        StatusCode::UNAUTHORIZED => Code::Unauthenticated,
+        StatusCode::FORBIDDEN => Code::PermissionDenied,
+        StatusCode::NOT_FOUND => Code::NotFound,
+        StatusCode::CONFLICT => Code::Aborted,
+        StatusCode::PRECONDITION_FAILED => Code::FailedPrecondition,
+        StatusCode::TOO_MANY_REQUESTS => Code::ResourceExhausted,
+        _ => Code::Internal,
+    };
+    Status::new(code, err.message())
+}
+
+impl From<ServiceError> for Status {
+    fn from(err: ServiceError) -> Self {
+        status(ApiError::from(err))
+    }
+}
+
+impl From<ApiError> for Status {
+    fn from(err: ApiError) -> Self {
+        status(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tonic::service::Interceptor;
+
+    use super::*;
+
+    #[test]
+    fn calls_need_the_service_token() {
+        let mut auth = BearerAuth::new("s3cret ");
+        let call = |auth: &mut BearerAuth, header: Option<&str>| {
+            let mut request = Request::new(());
+            if let Some(header) = header {
+                request
+                    .metadata_mut()
+                    .insert("authorization", header.parse().unwrap());
+            }
+            auth.call(request).map_err(|status| status.code())
+        };
+
+        assert!(call(&mut auth, Some("Bearer s3cret")).is_ok());
+        assert_eq!(
+            call(&mut auth, Some("Bearer other")).unwrap_err(),
+            Code::Unauthenticated
+        );
+        assert_eq!(call(&mut auth, None).unwrap_err(), Code::Unauthenticated);
+    }
+
+    #[test]
+    fn service_errors_keep_their_meaning() {
+        let code = |err: ServiceError| Status::from(err).code();
+        assert_eq!(code(ServiceError::NotFound), Code::NotFound);
+        assert_eq!(code(ServiceError::Forbidden), Code::PermissionDenied);
+        assert_eq!(
+            code(ServiceError::Validation("bad".into())),
+            Code::InvalidArgument
+        );
+        assert_eq!(code(ServiceError::Conflict), Code::Aborted);
+
+        let internal = Status::from(ServiceError::Internal("db offline".into()));
+        assert_eq!(internal.code(), Code::Internal);
+        assert_eq!(internal.message(), "internal server error");
+    }
+}
@@ -0,0 +1,269 @@
+//! `ReportIngestion`: draft reports filed on an employee's behalf.
+//!
+//! The report is created as if the employee had posted it to
+//! `POST /api/expenses/reports`: it passes the same validation, is owned by
+//! them, and stays a draft for them to review and submit.
+
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use tonic::{Request, Response, Status};
+
+use crate::{
+    api::validation::{Validate, Validator},
+    domain::models::{ExpenseCategory, ExpenseReport},
+    infrastructure::{auth::AuthenticatedUser, state::AppState},
+    services::{
+        employees::EmployeeService,
+        expenses::{self, CreateExpenseItem, CreateReceiptReference, ExpenseService},
+    },
+};
+
+use super::{employees::employee_lookup, v1::report_ingestion_server::ReportIngestion};
+
+/// Names the employee by exactly one of `employee_id` and `hr_identifier`.
+/// Dates are `YYYY-MM-DD`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CreateReportRequest {
+    #[prost(string, tag = "1")]
+    pub employee_id: String,
+    #[prost(string, tag = "2")]
+    pub hr_identifier: String,
+    #[prost(string, tag = "3")]
+    pub reporting_period_start: String,
+    #[prost(string, tag = "4")]
+    pub reporting_period_end: String,
+    #[prost(string, tag = "5")]
+    pub currency: String,
+    #[prost(message, repeated, tag = "6")]
+    pub items: Vec<ExpenseItem>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ExpenseItem {
+    #[prost(string, tag = "1")]
+    pub expense_date: String,
+    /// A category such as `meal` or `ground_transport`.
+    #[prost(string, tag = "2")]
+    pub category: String,
+    #[prost(int64, tag = "3")]
+    pub amount_cents: i64,
+    #[prost(bool, tag = "4")]
+    pub reimbursable: bool,
+    #[prost(string, optional, tag = "5")]
+    pub description: Option<String>,
+    #[prost(string, optional, tag = "6")]
+    pub merchant: Option<String>,
+    #[prost(string, optional, tag = "7")]
+    pub location: Option<String>,
+    #[prost(string, optional, tag = "8")]
+    pub payment_method: Option<String>,
+    #[prost(int64, optional, tag = "9")]
+    pub tax_amount_cents: Option<i64>,
+    #[prost(message, repeated, tag = "10")]
+    pub receipts: Vec<Receipt>,
+}
+
+/// A receipt already uploaded to receipt storage.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Receipt {
+    #[prost(string, tag = "1")]
+    pub file_key: String,
+    #[prost(string, tag = "2")]
+    pub file_name: String,
+    #[prost(string, tag = "3")]
+    pub mime_type: String,
+    #[prost(int64, tag = "4")]
+    pub size_bytes: i64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Report {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub employee_id: String,
+    #[prost(string, tag = "3")]
+    pub reporting_period_start: String,
+    #[prost(string, tag = "4")]
+    pub reporting_period_end: String,
+    #[prost(string, tag = "5")]
+    pub status: String,
+    #[prost(int64, tag = "6")]
+    pub total_amount_cents: i64,
+    #[prost(int64, tag = "7")]
+    pub total_reimbursable_cents: i64,
+    #[prost(string, tag = "8")]
+    pub currency: String,
+    #[prost(int32, tag = "9")]
+    pub version: i32,
+    /// RFC 3339.
+    #[prost(string, tag = "10")]
+    pub created_at: String,
+}
+
+impl From<ExpenseReport> for Report {
+    fn from(report: ExpenseReport) -> Self {
+        Self {
+            id: report.id.to_string(),
+            employee_id: report.employee_id.to_string(),
+            reporting_period_start: report.reporting_period_start.to_string(),
+            reporting_period_end: report.reporting_period_end.to_string(),
+            status: report.status.as_str().to_string(),
+            total_amount_cents: report.total_amount_cents,
+            total_reimbursable_cents: report.total_reimbursable_cents,
+            currency: report.currency,
+            version: report.version,
+            created_at: report.created_at.to_rfc3339(),
+        }
+    }
+}
+
+impl CreateReportRequest {
+    /// The service layer's request, or `None` with the fields that do not
+    /// convert recorded on `v`.
+    fn to_service(&self, v: &mut Validator) -> Option<expenses::CreateReportRequest> {
+        let start = date(v, "reporting_period_start", &self.reporting_period_start);
+        let end = date(v, "reporting_period_end", &self.reporting_period_end);
+        let mut items = Vec::with_capacity(self.items.len());
+        v.each("items", &self.items, |v, item| {
+            if let Some(item) = item.to_service(v) {
+                items.push(item);
+            }
+        });
+        Some(expenses::CreateReportRequest {
+            reporting_period_start: start?,
+            reporting_period_end: end?,
+            currency: (!self.currency.trim().is_empty()).then(|| self.currency.clone()),
+            trip_id: None,
+            items: (items.len() == self.items.len()).then_some(items)?,
+        })
+    }
+}
+
+impl ExpenseItem {
+    fn to_service(&self, v: &mut Validator) -> Option<CreateExpenseItem> {
+        let expense_date = date(v, "expense_date", &self.expense_date);
+        let category = ExpenseCategory::parse(&self.category);
+        if category.is_none() {
+            v.error("category", "is not a known expense category");
+        }
+        Some(CreateExpenseItem {
+            expense_date: expense_date?,
+            category: category?,
+            description: self.description.clone(),
+            attendees: None,
+            location: self.location.clone(),
+            amount_cents: self.amount_cents,
+            reimbursable: self.reimbursable,
+            payment_method: self.payment_method.clone(),
+            is_policy_exception: false,
+            exception_justification: None,
+            class: None,
+            tax_amount_cents: self.tax_amount_cents,
+            tax_jurisdiction: None,
+            miles: None,
+            merchant: self.merchant.clone(),
+            cost_center_id: None,
+            receipts: self
+                .receipts
+                .iter()
+                .map(|receipt| CreateReceiptReference {
+                    file_key: receipt.file_key.clone(),
+                    file_name: receipt.file_name.clone(),
+                    mime_type: receipt.mime_type.clone(),
+                    size_bytes: receipt.size_bytes,
+                })
+                .collect(),
+        })
+    }
+}
+
+fn date(v: &mut Validator, field: &str, value: &str) -> Option<NaiveDate> {
+    let parsed = NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").ok();
+    v.check(parsed.is_some(), field, "must be a YYYY-MM-DD date");
+    parsed
+}
+
+pub struct Ingestion {
+    state: Arc<AppState>,
+}
+
+impl Ingestion {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+}
+
+#[tonic::async_trait]
+impl ReportIngestion for Ingestion {
+    async fn create_report(
+        &self,
+        request: Request<CreateReportRequest>,
+    ) -> Result<Response<Report>, Status> {
+        let request = request.into_inner();
+        let mut v = Validator::new();
+        let lookup = employee_lookup(
+            &request.employee_id,
+            &request.hr_identifier,
+            "employee_id",
+            &mut v,
+        );
+        let payload = request.to_service(&mut v);
+        if let Some(payload) = &payload {
+            payload.validate(&self.state.config, &mut v);
+        }
+        v.finish()?;
+        let (Some(lookup), Some(payload)) = (lookup, payload) else {
+            return Err(Status::invalid_argument("the report could not be read"));
+        };
+
+        let employee = EmployeeService::new(Arc::clone(&self.state))
+            .find(&lookup)
+            .await?;
+        let actor = AuthenticatedUser {
+            employee_id: employee.id,
+            role: employee.role,
+            org_id: employee.org_id,
+        };
+        let report = ExpenseService::new(Arc::clone(&self.state))
+            .create_report(&actor, payload)
+            .await?;
+        Ok(Response::new(report.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unreadable_fields_are_reported_by_path() {
+        let request = CreateReportRequest {
+            hr_identifier: "E100".into(),
+            reporting_period_start: "2024-09-01".into(),
+            reporting_period_end: "09/30/2024".into(),
+            currency: "USD".into(),
+            items: vec![ExpenseItem {
+                expense_date: "2024-09-03".into(),
+                category: "snacks".into(),
+                amount_cents: 1250,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let mut v = Validator::new();
+
+        assert!(request.to_service(&mut v).is_none());
+        let errors = v.into_errors();
+        assert_eq!(
+            errors["reporting_period_end"],
+            ["must be a YYYY-MM-DD date"]
+        );
+        assert_eq!(
+            errors["items.0.category"],
+            ["is not a known expense category"]
+        );
+        assert!(!errors.contains_key("reporting_period_start"));
+    }
+}
@@ -1,15 +1,14 @@
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
-use axum::{
-    async_trait, extract::FromRequestParts, http::request::Parts, response::IntoResponse, Json,
-};
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts, response::IntoResponse};
 use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tracing::warn;
 
 use crate::{
-    domain::models::{Employee, Role},
+    api::error::ApiError,
+    domain::models::{ApprovalStatus, Employee, Role, DEFAULT_ORG_ID},
     infrastructure::state::AppState,
     services::errors::ServiceError,
 };
@@ -18,9 +17,17 @@ use crate::{
 pub struct Claims {
     pub sub: uuid::Uuid,
     pub role: Role,
+    /// The employee's organization. Tokens issued before organizations
+    /// existed carry none and belong to the default one.
+    #[serde(default = "default_org")]
+    pub org: uuid::Uuid,
     pub exp: usize,
 }
 
+fn default_org() -> uuid::Uuid {
+    DEFAULT_ORG_ID
+}
+
 #[derive(Clone)]
 pub struct JwtKeys {
     pub encoding: EncodingKey,
@@ -42,7 +49,8 @@ pub fn issue_token(state: &AppState, employee: &Employee) -> Result<String, Serv
             .map_err(|_| ServiceError::Internal("failed to calculate expiration".into()))?;
     let claims = Claims {
         sub: employee.id,
-        role: employee.role.clone(),
+        role: employee.role,
+        org: employee.org_id,
         exp: expiration.timestamp() as usize,
     };
     encode(
@@ -53,6 +61,41 @@ pub fn issue_token(state: &AppState, employee: &Employee) -> Result<String, Serv
     .map_err(|err| ServiceError::Internal(err.to_string()))
 }
 
+/// Purpose marker carried by approval link tokens so they can never be
+/// confused with session tokens signed by the same key.
+pub const APPROVAL_ACTION_PURPOSE: &str = "approval_action";
+
+/// Claims embedded in the signed approve/request-changes links sent to
+/// approvers. `jti` keys the `approval_action_tokens` row that makes each
+/// link single-use.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionClaims {
+    pub jti: uuid::Uuid,
+    pub sub: uuid::Uuid,
+    pub report_id: uuid::Uuid,
+    pub action: ApprovalStatus,
+    pub purpose: String,
+    pub exp: usize,
+}
+
+pub fn sign_action_token(keys: &JwtKeys, claims: &ActionClaims) -> Result<String, ServiceError> {
+    encode(&Header::new(Algorithm::HS256), claims, &keys.encoding)
+        .map_err(|err| ServiceError::Internal(err.to_string()))
+}
+
+/// Verifies the signature, expiry, and purpose of an approval link token.
+pub fn verify_action_token(keys: &JwtKeys, token: &str) -> Result<ActionClaims, AuthError> {
+    let validation = Validation::new(Algorithm::HS256);
+    let data = decode::<ActionClaims>(token, &keys.decoding, &validation).map_err(|err| {
+        warn!(error = ?err, "failed to decode approval action token");
+        AuthError::Invalid
+    })?;
+    if data.claims.purpose != APPROVAL_ACTION_PURPOSE {
+        return Err(AuthError::Invalid);
+    }
+    Ok(data.claims)
+}
+
 #[derive(Debug, Error)]
 pub enum AuthError {
     #[error("missing authorization header")]
@@ -65,13 +108,7 @@ pub enum AuthError {
 
 impl IntoResponse for AuthError {
     fn into_response(self) -> axum::response::Response {
-        let status = axum::http::StatusCode::UNAUTHORIZED;
-        let message = match self {
-            AuthError::Missing => "missing authorization header",
-            AuthError::Invalid => "invalid authorization token",
-            AuthError::MissingState => "application state unavailable",
-        };
-        (status, Json(serde_json::json!({ "error": message }))).into_response()
+        ApiError::from(self).into_response()
     }
 }
 
@@ -79,6 +116,27 @@ impl IntoResponse for AuthError {
 pub struct AuthenticatedUser {
     pub employee_id: uuid::Uuid,
     pub role: Role,
+    /// Organization every query the user makes is scoped to.
+    pub org_id: uuid::Uuid,
+}
+
+impl AuthenticatedUser {
+    /// Whether the user belongs to the organization operating the
+    /// deployment, whose administrators manage deployment-wide settings.
+    pub fn is_operator(&self) -> bool {
+        self.org_id == DEFAULT_ORG_ID
+    }
+}
+
+/// Request extension the access log installs so it can name the employee a
+/// request authenticated as; the `AuthenticatedUser` extractor fills it.
+#[derive(Clone, Debug, Default)]
+pub struct RequestEmployee(Arc<OnceLock<uuid::Uuid>>);
+
+impl RequestEmployee {
+    pub fn get(&self) -> Option<uuid::Uuid> {
+        self.0.get().copied()
+    }
 }
 
 #[async_trait]
@@ -86,35 +144,118 @@ impl FromRequestParts<()> for AuthenticatedUser {
     type Rejection = AuthError;
 
     async fn from_request_parts(parts: &mut Parts, _state: &()) -> Result<Self, Self::Rejection> {
-        let Some(state) = parts.extensions.get::<Arc<AppState>>() else {
-            return Err(AuthError::MissingState);
-        };
-
-        match state.resolve_bypass_user().await {
-            Ok(Some(user)) => return Ok(user),
-            Ok(None) => {}
-            Err(err) => {
-                warn!(error = ?err, "failed to resolve bypass user");
-            }
+        let user = authenticate(parts).await?;
+        if let Some(RequestEmployee(slot)) = parts.extensions.get::<RequestEmployee>() {
+            // A request may extract the user more than once; the first wins.
+            let _ = slot.set(user.employee_id);
+        }
+        Ok(user)
+    }
+}
+
+/// Resolves the request's employee from the bypass user or its bearer token.
+/// Middleware that needs the actor before the handler runs calls this
+/// directly; handlers use the `AuthenticatedUser` extractor.
+pub async fn authenticate(parts: &Parts) -> Result<AuthenticatedUser, AuthError> {
+    let Some(state) = parts.extensions.get::<Arc<AppState>>() else {
+        return Err(AuthError::MissingState);
+    };
+
+    match state.resolve_bypass_user().await {
+        Ok(Some(user)) => return Ok(user),
+        Ok(None) => {}
+        Err(err) => {
+            warn!(error = ?err, "failed to resolve bypass user");
+        }
+    }
+
+    let Some(header_value) = parts.headers.get(axum::http::header::AUTHORIZATION) else {
+        return Err(AuthError::Missing);
+    };
+    let header_str = header_value.to_str().map_err(|_| AuthError::Invalid)?;
+    let token = header_str
+        .strip_prefix("Bearer ")
+        .ok_or(AuthError::Invalid)?;
+    let validation = Validation::new(Algorithm::HS256);
+    match decode::<Claims>(token, &state.jwt_keys.decoding, &validation) {
+        Ok(data) => Ok(AuthenticatedUser {
+            employee_id: data.claims.sub,
+            role: data.claims.role,
+            org_id: data.claims.org,
+        }),
+        Err(err) => {
+            warn!(error = ?err, "failed to decode jwt");
+            Err(AuthError::Invalid)
         }
+    }
+}
 
-        let Some(header_value) = parts.headers.get(axum::http::header::AUTHORIZATION) else {
-            return Err(AuthError::Missing);
-        };
-        let header_str = header_value.to_str().map_err(|_| AuthError::Invalid)?;
-        let token = header_str
-            .strip_prefix("Bearer ")
-            .ok_or(AuthError::Invalid)?;
-        let validation = Validation::new(Algorithm::HS256);
-        match decode::<Claims>(token, &state.jwt_keys.decoding, &validation) {
-            Ok(data) => Ok(AuthenticatedUser {
-                employee_id: data.claims.sub,
-                role: data.claims.role,
-            }),
-            Err(err) => {
-                warn!(error = ?err, "failed to decode jwt");
-                Err(AuthError::Invalid)
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims(purpose: &str, exp: i64) -> ActionClaims {
+        ActionClaims {
+            jti: uuid::Uuid::new_v4(),
+            sub: uuid::Uuid::new_v4(),
+            report_id: uuid::Uuid::new_v4(),
+            action: ApprovalStatus::Approved,
+            purpose: purpose.to_string(),
+            exp: exp as usize,
         }
     }
+
+    fn in_one_hour() -> i64 {
+        (chrono::Utc::now() + chrono::Duration::hours(1)).timestamp()
+    }
+
+    #[test]
+    fn action_token_round_trips() {
+        let keys = JwtKeys::new("test-secret");
+        let original = claims(APPROVAL_ACTION_PURPOSE, in_one_hour());
+
+        let token = sign_action_token(&keys, &original).unwrap();
+        let verified = verify_action_token(&keys, &token).unwrap();
+
+        assert_eq!(verified.jti, original.jti);
+        assert_eq!(verified.report_id, original.report_id);
+        assert_eq!(verified.action, ApprovalStatus::Approved);
+    }
+
+    #[test]
+    fn action_token_rejects_wrong_key_purpose_and_expiry() {
+        let keys = JwtKeys::new("test-secret");
+
+        let forged = sign_action_token(
+            &JwtKeys::new("other-secret"),
+            &claims(APPROVAL_ACTION_PURPOSE, in_one_hour()),
+        )
+        .unwrap();
+        assert!(verify_action_token(&keys, &forged).is_err());
+
+        let wrong_purpose = sign_action_token(&keys, &claims("session", in_one_hour())).unwrap();
+        assert!(verify_action_token(&keys, &wrong_purpose).is_err());
+
+        let expired = (chrono::Utc::now() - chrono::Duration::hours(1)).timestamp();
+        let stale = sign_action_token(&keys, &claims(APPROVAL_ACTION_PURPOSE, expired)).unwrap();
+        assert!(verify_action_token(&keys, &stale).is_err());
+    }
+
+    #[test]
+    fn session_tokens_are_not_action_tokens() {
+        let keys = JwtKeys::new("test-secret");
+        let session = encode(
+            &Header::new(Algorithm::HS256),
+            &Claims {
+                sub: uuid::Uuid::new_v4(),
+                role: Role::Manager,
+                org: DEFAULT_ORG_ID,
+                exp: in_one_hour() as usize,
+            },
+            &keys.encoding,
+        )
+        .unwrap();
+
+        assert!(verify_action_token(&keys, &session).is_err());
+    }
 }
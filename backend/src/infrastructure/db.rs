@@ -1,6 +1,9 @@
+use std::time::Duration;
+
 use anyhow::Context;
 use sqlx::migrate::Migrator;
-use sqlx::postgres::PgPoolOptions;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::ConnectOptions;
 
 use super::config::DatabaseConfig;
 
@@ -8,17 +11,69 @@ pub type PgPool = sqlx::Pool<sqlx::Postgres>;
 
 static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
 
+/// Opens the pool. Statements running past `slow_query_threshold_ms` are
+/// logged by sqlx as warnings under the `sqlx::query` target, and Postgres
+/// cancels those running past `statement_timeout_ms`.
 pub async fn connect(config: &DatabaseConfig) -> anyhow::Result<PgPool> {
-    PgPoolOptions::new()
-        .max_connections(config.max_connections)
-        .connect(&config.url)
+    open(config, &config.url)
         .await
         .with_context(|| "failed to connect to PostgreSQL")
 }
 
+/// Opens the read replica pool, or `None` when `read_url` is unset.
+pub async fn connect_read(config: &DatabaseConfig) -> anyhow::Result<Option<PgPool>> {
+    let Some(url) = config.replica_url() else {
+        return Ok(None);
+    };
+    open(config, url)
+        .await
+        .map(Some)
+        .with_context(|| "failed to connect to the PostgreSQL read replica")
+}
+
+async fn open(config: &DatabaseConfig, url: &str) -> anyhow::Result<PgPool> {
+    let options = url
+        .parse::<PgConnectOptions>()
+        .with_context(|| "invalid database URL")?
+        .log_slow_statements(
+            log::LevelFilter::Warn,
+            Duration::from_millis(config.slow_query_threshold_ms),
+        )
+        .options([("statement_timeout", config.statement_timeout_ms.to_string())]);
+    Ok(pool_options(config).connect_with(options).await?)
+}
+
+fn pool_options(config: &DatabaseConfig) -> PgPoolOptions {
+    PgPoolOptions::new()
+        .max_connections(config.max_connections)
+        .acquire_timeout(Duration::from_secs(config.acquire_timeout_secs))
+        .idle_timeout(non_zero_secs(config.idle_timeout_secs))
+        .max_lifetime(non_zero_secs(config.max_lifetime_secs))
+}
+
+/// `0` means no limit.
+fn non_zero_secs(secs: u64) -> Option<Duration> {
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
 pub async fn run_migrations(pool: &PgPool) -> anyhow::Result<()> {
     MIGRATOR
         .run(pool)
         .await
         .with_context(|| "failed to run database migrations")
 }
+
+/// Number of this build's migrations the database has not successfully
+/// applied, for the readiness probe.
+pub async fn pending_migrations(pool: &PgPool) -> anyhow::Result<usize> {
+    let applied: Vec<i64> =
+        sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success")
+            .fetch_all(pool)
+            .await
+            .with_context(|| "failed to read applied migrations")?;
+    Ok(MIGRATOR
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .filter(|migration| !applied.contains(&migration.version))
+        .count())
+}
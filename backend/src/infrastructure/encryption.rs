@@ -0,0 +1,182 @@
+//! Envelope for sensitive fields kept at rest, such as employees' bank
+//! accounts.
+//!
+//! Values are sealed with AES-256-GCM under the key configured in
+//! `banking.encryption_key`, each with a fresh random nonce. The sealed value
+//! records the ID of the key that sealed it, so keys can be rotated: the new
+//! key seals from then on, and the old one stays in `banking.retired_keys` to
+//! open what it sealed. Callers bind each value to its row with associated
+//! data, so a ciphertext copied onto another row does not open.
+
+use std::collections::HashMap;
+
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    Aes256Gcm, Nonce,
+};
+use anyhow::Context;
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+use super::config::BankingConfig;
+
+/// A value sealed by `FieldCipher::seal`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sealed {
+    pub key_id: String,
+    pub nonce: Vec<u8>,
+    pub ciphertext: Vec<u8>,
+}
+
+/// Seals values under the active key and opens them under any configured
+/// one.
+pub struct FieldCipher {
+    key_id: String,
+    keys: HashMap<String, Aes256Gcm>,
+}
+
+impl std::fmt::Debug for FieldCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FieldCipher")
+            .field("key_id", &self.key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Builds the cipher for bank accounts, or `None` when no
+/// `banking.encryption_key` is set. Fails on a key, active or retired, that
+/// is not 32 bytes of base64, or a blank `key_id`.
+pub fn build_bank_cipher(config: &BankingConfig) -> anyhow::Result<Option<FieldCipher>> {
+    let Some(encoded) = config
+        .encryption_key
+        .as_deref()
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+    else {
+        return Ok(None);
+    };
+    // Environment variables name retired keys in lowercase, so IDs are
+    // compared that way.
+    let key_id = config.key_id.trim().to_lowercase();
+    anyhow::ensure!(!key_id.is_empty(), "`banking.key_id` is blank");
+    let mut keys = HashMap::new();
+    for (id, retired) in &config.retired_keys {
+        let key = parse_key(retired)
+            .with_context(|| format!("`banking.retired_keys.{id}` is not a valid key"))?;
+        keys.insert(id.trim().to_lowercase(), key);
+    }
+    let key = parse_key(encoded).context("`banking.encryption_key` is not a valid key")?;
+    keys.insert(key_id.clone(), key);
+    Ok(Some(FieldCipher { key_id, keys }))
+}
+
+fn parse_key(encoded: &str) -> anyhow::Result<Aes256Gcm> {
+    let bytes = STANDARD.decode(encoded.trim())?;
+    anyhow::ensure!(bytes.len() == 32, "expected 32 bytes, got {}", bytes.len());
+    Ok(Aes256Gcm::new_from_slice(&bytes)?)
+}
+
+impl FieldCipher {
+    /// Seals `plaintext` under the active key, bound to `aad`.
+    pub fn seal(&self, plaintext: &[u8], aad: &[u8]) -> anyhow::Result<Sealed> {
+        let cipher = &self.keys[&self.key_id];
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext,
+                    aad,
+                },
+            )
+            .map_err(|_| anyhow::anyhow!("encryption failed"))?;
+        Ok(Sealed {
+            key_id: self.key_id.clone(),
+            nonce: nonce.to_vec(),
+            ciphertext,
+        })
+    }
+
+    /// Opens a value sealed with `aad`. Fails when its key is not configured
+    /// or the value was altered or sealed for other associated data.
+    pub fn open(&self, sealed: &Sealed, aad: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let cipher = self
+            .keys
+            .get(&sealed.key_id)
+            .with_context(|| format!("encryption key `{}` is not configured", sealed.key_id))?;
+        let nonce: [u8; 12] = sealed
+            .nonce
+            .as_slice()
+            .try_into()
+            .context("invalid nonce")?;
+        cipher
+            .decrypt(
+                &Nonce::from(nonce),
+                Payload {
+                    msg: &sealed.ciphertext,
+                    aad,
+                },
+            )
+            .map_err(|_| anyhow::anyhow!("value does not open under key `{}`", sealed.key_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(key: u8, key_id: &str, retired: &[(&str, u8)]) -> BankingConfig {
+        BankingConfig {
+            encryption_key: Some(STANDARD.encode([key; 32])),
+            key_id: key_id.to_string(),
+            retired_keys: retired
+                .iter()
+                .map(|(id, key)| (id.to_string(), STANDARD.encode([*key; 32])))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn seals_and_opens_values_bound_to_their_associated_data() {
+        let cipher = build_bank_cipher(&config(1, "k1", &[])).unwrap().unwrap();
+        let sealed = cipher.seal(b"021000021:123456789", b"row-1").unwrap();
+
+        assert_eq!(sealed.key_id, "k1");
+        assert_ne!(sealed.ciphertext, b"021000021:123456789".to_vec());
+        assert_eq!(
+            cipher.open(&sealed, b"row-1").unwrap(),
+            b"021000021:123456789".to_vec()
+        );
+        assert!(cipher.open(&sealed, b"row-2").is_err());
+
+        let mut tampered = sealed.clone();
+        tampered.ciphertext[0] ^= 1;
+        assert!(cipher.open(&tampered, b"row-1").is_err());
+    }
+
+    #[test]
+    fn opens_values_sealed_under_retired_keys() {
+        let old = build_bank_cipher(&config(1, "k1", &[])).unwrap().unwrap();
+        let sealed = old.seal(b"secret", b"row").unwrap();
+
+        let rotated = build_bank_cipher(&config(2, "k2", &[("k1", 1)]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(rotated.open(&sealed, b"row").unwrap(), b"secret".to_vec());
+        assert_eq!(rotated.seal(b"secret", b"row").unwrap().key_id, "k2");
+
+        let forgotten = build_bank_cipher(&config(2, "k2", &[])).unwrap().unwrap();
+        assert!(forgotten.open(&sealed, b"row").is_err());
+    }
+
+    #[test]
+    fn rejects_missing_and_malformed_keys() {
+        assert!(build_bank_cipher(&BankingConfig::default())
+            .unwrap()
+            .is_none());
+        let short = BankingConfig {
+            encryption_key: Some(STANDARD.encode([1u8; 16])),
+            ..BankingConfig::default()
+        };
+        assert!(build_bank_cipher(&short).is_err());
+    }
+}
@@ -0,0 +1,210 @@
+//! Daily exchange rate providers.
+//!
+//! `fetch_rates` downloads the latest reference rates from the provider
+//! selected in `FxConfig`: the European Central Bank's daily feed (EUR based,
+//! no key) or Open Exchange Rates (USD based on the free plan, `app_id`
+//! required).
+
+use std::{sync::OnceLock, time::Duration};
+
+use anyhow::Context;
+use chrono::{DateTime, NaiveDate};
+use serde_json::Value;
+
+use crate::{
+    domain::{fx::ExchangeRate, models::Currency},
+    infrastructure::config::{FxConfig, FxProvider},
+};
+
+const ECB_URL: &str = "https://www.ecb.europa.eu/stats/eurofxref/eurofxref-daily.xml";
+const OPEN_EXCHANGE_RATES_URL: &str = "https://openexchangerates.org/api/latest.json";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+/// Rates published by a provider for one day, from `base` to each quote
+/// currency.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateSheet {
+    pub base: Currency,
+    pub rate_date: NaiveDate,
+    pub rates: Vec<(Currency, ExchangeRate)>,
+}
+
+/// Downloads the provider's latest rates.
+pub async fn fetch_rates(config: &FxConfig) -> anyhow::Result<RateSheet> {
+    let url = config
+        .base_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|url| !url.is_empty());
+    match config.provider {
+        FxProvider::Ecb => {
+            let body = client()
+                .get(url.unwrap_or(ECB_URL))
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status)
+                .context("ECB reference rate request failed")?
+                .text()
+                .await
+                .context("ECB reference rate response could not be read")?;
+            parse_ecb(&body)
+        }
+        FxProvider::OpenExchangeRates => {
+            let app_id = config
+                .app_id
+                .as_deref()
+                .map(str::trim)
+                .filter(|app_id| !app_id.is_empty())
+                .context("Open Exchange Rates needs `fx.app_id`")?;
+            let body: Value = client()
+                .get(url.unwrap_or(OPEN_EXCHANGE_RATES_URL))
+                .query(&[("app_id", app_id)])
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status)
+                .context("Open Exchange Rates request failed")?
+                .json()
+                .await
+                .context("Open Exchange Rates response was not JSON")?;
+            parse_open_exchange_rates(&body)
+        }
+    }
+}
+
+fn client() -> &'static reqwest::Client {
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("FX HTTP client configuration is static")
+    })
+}
+
+/// Parses the ECB `eurofxref-daily.xml` feed: one dated `Cube` holding a
+/// `Cube currency='USD' rate='1.0842'` element per currency.
+fn parse_ecb(body: &str) -> anyhow::Result<RateSheet> {
+    let rate_date = attribute(body, "time")
+        .and_then(|time| NaiveDate::parse_from_str(time, "%Y-%m-%d").ok())
+        .context("ECB feed has no rate date")?;
+    let mut rates = Vec::new();
+    for element in body.split("<Cube").skip(1) {
+        let (Some(code), Some(rate)) = (attribute(element, "currency"), attribute(element, "rate"))
+        else {
+            continue;
+        };
+        if let (Ok(currency), Some(rate)) = (Currency::parse(code), ExchangeRate::parse(rate)) {
+            rates.push((currency, rate));
+        }
+    }
+    anyhow::ensure!(!rates.is_empty(), "ECB feed has no rates");
+    Ok(RateSheet {
+        base: Currency::parse("EUR")?,
+        rate_date,
+        rates,
+    })
+}
+
+/// Parses an Open Exchange Rates `latest.json` body. Entries that are not a
+/// three-letter currency with a positive rate, such as crypto codes, are
+/// skipped.
+fn parse_open_exchange_rates(body: &Value) -> anyhow::Result<RateSheet> {
+    let base = body
+        .get("base")
+        .and_then(Value::as_str)
+        .context("Open Exchange Rates response has no base currency")?;
+    let rate_date = body
+        .get("timestamp")
+        .and_then(Value::as_i64)
+        .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+        .map(|timestamp| timestamp.date_naive())
+        .context("Open Exchange Rates response has no timestamp")?;
+    let mut rates: Vec<(Currency, ExchangeRate)> = body
+        .get("rates")
+        .and_then(Value::as_object)
+        .context("Open Exchange Rates response has no rates")?
+        .iter()
+        .filter_map(|(code, rate)| {
+            let rate = rate.as_number()?.to_string();
+            Some((Currency::parse(code).ok()?, ExchangeRate::parse(&rate)?))
+        })
+        .collect();
+    anyhow::ensure!(
+        !rates.is_empty(),
+        "Open Exchange Rates response has no rates"
+    );
+    rates.sort();
+    Ok(RateSheet {
+        base: Currency::parse(base)?,
+        rate_date,
+        rates,
+    })
+}
+
+/// Value of the first `name='…'` or `name="…"` attribute in `element`.
+fn attribute<'a>(element: &'a str, name: &str) -> Option<&'a str> {
+    ['\'', '"'].into_iter().find_map(|quote| {
+        let start = element.find(&format!("{name}={quote}"))? + name.len() + 2;
+        let len = element[start..].find(quote)?;
+        Some(&element[start..start + len])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_the_ecb_daily_feed() {
+        let body = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gesmes:Envelope xmlns:gesmes="http://www.gesmes.org/xml/2002-08-01" xmlns="http://www.ecb.int/vocabulary/2002-08-01/eurofxref">
+	<gesmes:subject>Reference rates</gesmes:subject>
+	<Cube>
+		<Cube time='2024-05-08'>
+			<Cube currency='USD' rate='1.0751'/>
+			<Cube currency='JPY' rate='167.14'/>
+			<Cube currency='GBP' rate='0.86075'/>
+		</Cube>
+	</Cube>
+</gesmes:Envelope>"#;
+
+        let sheet = parse_ecb(body).unwrap();
+
+        assert_eq!(sheet.base.code(), "EUR");
+        assert_eq!(
+            sheet.rate_date,
+            NaiveDate::from_ymd_opt(2024, 5, 8).unwrap()
+        );
+        assert_eq!(sheet.rates.len(), 3);
+        assert_eq!(sheet.rates[0].0, Currency::USD);
+        assert_eq!(sheet.rates[0].1.to_string(), "1.0751");
+    }
+
+    #[test]
+    fn parses_open_exchange_rates_and_skips_unusable_entries() {
+        let body = json!({
+            "timestamp": 1_715_212_800,
+            "base": "USD",
+            "rates": { "EUR": 0.930123, "JPY": 155.42, "USDT": 1.0, "XAU": 0 }
+        });
+
+        let sheet = parse_open_exchange_rates(&body).unwrap();
+
+        assert_eq!(sheet.base, Currency::USD);
+        assert_eq!(
+            sheet.rate_date,
+            NaiveDate::from_ymd_opt(2024, 5, 9).unwrap()
+        );
+        let codes: Vec<&str> = sheet.rates.iter().map(|(code, _)| code.code()).collect();
+        assert_eq!(codes, ["EUR", "JPY"]);
+    }
+
+    #[test]
+    fn rejects_feeds_without_rates() {
+        assert!(parse_ecb("<Cube><Cube time='2024-05-08'></Cube></Cube>").is_err());
+        assert!(parse_open_exchange_rates(&json!({ "base": "USD" })).is_err());
+    }
+}
@@ -0,0 +1,221 @@
+//! HR system connectors.
+//!
+//! `fetch_roster` downloads the worker roster from the HR system selected in
+//! `HrSyncConfig`: a Workday custom report published as a RaaS JSON feed
+//! (integration user credentials), or BambooHR's custom report API (API
+//! key). Either way the result is every worker's HR identifier, manager,
+//! department, and whether they are still employed.
+
+use std::{collections::HashMap, sync::OnceLock, time::Duration};
+
+use anyhow::Context;
+use serde_json::{json, Value};
+
+use crate::infrastructure::config::{HrProvider, HrSyncConfig};
+
+const BAMBOOHR_URL: &str = "https://api.bamboohr.com";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+/// One worker as the HR system reports them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HrWorker {
+    pub hr_identifier: String,
+    /// HR identifier of the worker's manager.
+    pub manager: Option<String>,
+    pub department: Option<String>,
+    pub active: bool,
+}
+
+/// Downloads the HR system's current roster.
+pub async fn fetch_roster(config: &HrSyncConfig) -> anyhow::Result<Vec<HrWorker>> {
+    let url = config
+        .base_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|url| !url.is_empty());
+    match config.provider {
+        HrProvider::Workday => {
+            let url = url.context("Workday needs `hr_sync.base_url`, the RaaS report URL")?;
+            let username = setting(&config.username).context("Workday needs `hr_sync.username`")?;
+            let body: Value = client()
+                .get(url)
+                .query(&[("format", "json")])
+                .basic_auth(username, config.password.as_deref())
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status)
+                .context("Workday report request failed")?
+                .json()
+                .await
+                .context("Workday report response was not JSON")?;
+            parse_workday(&body)
+        }
+        HrProvider::BambooHr => {
+            let domain = setting(&config.company_domain)
+                .context("BambooHR needs `hr_sync.company_domain`")?;
+            let api_key = setting(&config.api_key).context("BambooHR needs `hr_sync.api_key`")?;
+            let body: Value = client()
+                .post(format!(
+                    "{}/api/gateway.php/{domain}/v1/reports/custom",
+                    url.unwrap_or(BAMBOOHR_URL).trim_end_matches('/')
+                ))
+                .query(&[("format", "JSON"), ("onlyCurrent", "true")])
+                .basic_auth(api_key, Some("x"))
+                .header(reqwest::header::ACCEPT, "application/json")
+                .json(&json!({
+                    "fields": ["id", "employeeNumber", "supervisorEId", "department", "status"],
+                }))
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status)
+                .context("BambooHR report request failed")?
+                .json()
+                .await
+                .context("BambooHR report response was not JSON")?;
+            parse_bamboohr(&body)
+        }
+    }
+}
+
+fn setting(value: &Option<String>) -> Option<&str> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+fn client() -> &'static reqwest::Client {
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("HR HTTP client configuration is static")
+    })
+}
+
+/// Parses a Workday RaaS JSON feed: a `Report_Entry` array whose entries
+/// carry `Employee_ID`, `Manager_Employee_ID`, `Department`, and `Active`
+/// (`1`/`0`, or `true`/`false`). Entries without an employee ID are skipped,
+/// and a missing `Active` counts as employed.
+fn parse_workday(body: &Value) -> anyhow::Result<Vec<HrWorker>> {
+    let entries = body
+        .get("Report_Entry")
+        .and_then(Value::as_array)
+        .context("Workday report has no Report_Entry array")?;
+    Ok(entries
+        .iter()
+        .filter_map(|entry| {
+            Some(HrWorker {
+                hr_identifier: text(entry, "Employee_ID")?,
+                manager: text(entry, "Manager_Employee_ID"),
+                department: text(entry, "Department"),
+                active: text(entry, "Active")
+                    .is_none_or(|active| matches!(active.as_str(), "1" | "true" | "True")),
+            })
+        })
+        .collect())
+}
+
+/// Parses a BambooHR custom report. Supervisors are named by BambooHR's own
+/// employee `id`, so they are mapped to the supervisor's `employeeNumber`;
+/// employees without an employee number are skipped.
+fn parse_bamboohr(body: &Value) -> anyhow::Result<Vec<HrWorker>> {
+    let employees = body
+        .get("employees")
+        .and_then(Value::as_array)
+        .context("BambooHR report has no employees array")?;
+    let numbers: HashMap<String, String> = employees
+        .iter()
+        .filter_map(|employee| Some((text(employee, "id")?, text(employee, "employeeNumber")?)))
+        .collect();
+    Ok(employees
+        .iter()
+        .filter_map(|employee| {
+            Some(HrWorker {
+                hr_identifier: text(employee, "employeeNumber")?,
+                manager: text(employee, "supervisorEId")
+                    .and_then(|supervisor| numbers.get(&supervisor).cloned()),
+                department: text(employee, "department"),
+                active: text(employee, "status").is_none_or(|status| status == "Active"),
+            })
+        })
+        .collect())
+}
+
+/// The trimmed, non-empty string or number at `field`.
+fn text(value: &Value, field: &str) -> Option<String> {
+    let text = match value.get(field)? {
+        Value::String(text) => text.trim().to_string(),
+        Value::Number(number) => number.to_string(),
+        Value::Bool(flag) => flag.to_string(),
+        _ => return None,
+    };
+    (!text.is_empty()).then_some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_workday_raas_feed() {
+        let body = json!({
+            "Report_Entry": [
+                { "Employee_ID": "E100", "Department": "Ops", "Active": "1" },
+                { "Employee_ID": "E101", "Manager_Employee_ID": "E100", "Active": "0" },
+                { "Employee_ID": " ", "Department": "Ops" },
+                { "Employee_ID": 102, "Manager_Employee_ID": "E100", "Department": "" }
+            ]
+        });
+
+        let workers = parse_workday(&body).unwrap();
+
+        assert_eq!(
+            workers,
+            vec![
+                HrWorker {
+                    hr_identifier: "E100".into(),
+                    manager: None,
+                    department: Some("Ops".into()),
+                    active: true,
+                },
+                HrWorker {
+                    hr_identifier: "E101".into(),
+                    manager: Some("E100".into()),
+                    department: None,
+                    active: false,
+                },
+                HrWorker {
+                    hr_identifier: "102".into(),
+                    manager: Some("E100".into()),
+                    department: None,
+                    active: true,
+                },
+            ]
+        );
+        assert!(parse_workday(&json!({ "entries": [] })).is_err());
+    }
+
+    #[test]
+    fn maps_bamboohr_supervisors_to_employee_numbers() {
+        let body = json!({
+            "fields": [],
+            "employees": [
+                { "id": "7", "employeeNumber": "E100", "supervisorEId": null, "department": "Ops", "status": "Active" },
+                { "id": "8", "employeeNumber": "E101", "supervisorEId": "7", "department": "Ops", "status": "Inactive" },
+                { "id": "9", "employeeNumber": "", "supervisorEId": "7" }
+            ]
+        });
+
+        let workers = parse_bamboohr(&body).unwrap();
+
+        assert_eq!(workers.len(), 2);
+        assert_eq!(workers[0].manager, None);
+        assert!(workers[0].active);
+        assert_eq!(workers[1].manager.as_deref(), Some("E100"));
+        assert!(!workers[1].active);
+    }
+}
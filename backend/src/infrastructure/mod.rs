@@ -1,6 +1,14 @@
 pub mod auth;
 pub mod config;
 pub mod db;
+pub mod encryption;
+pub mod fx;
+pub mod hr;
 pub mod netsuite;
+pub mod notifications;
+pub mod org_chart_cache;
+pub mod policy_cache;
+pub mod reference_cache;
 pub mod state;
 pub mod storage;
+pub mod webhooks;
@@ -1,14 +1,45 @@
+//! NetSuite SuiteTalk REST client.
+//!
+//! Each batch is upserted as one journal entry at
+//! `/services/rest/record/v1/journalEntry/eid:<batch id>`, or in vendor-bill
+//! mode as one `vendorBill` per employee, signed with OAuth 1.0a Token-Based
+//! Authentication (HMAC-SHA256) from the `NetSuiteConfig` credentials.
+//! NetSuite answers with `204 No Content` and a `Location` header ending in
+//! the record's internal ID; the IDs, comma-separated when there are several,
+//! are returned as `NetSuiteResponse::reference`. When the credentials are not
+//! configured the export is simulated so development environments can still
+//! finalize batches.
+
+use std::{
+    collections::HashMap,
+    sync::OnceLock,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Context;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use hmac::{Hmac, Mac};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use rand::{distributions::Alphanumeric, Rng};
 use serde::{Deserialize, Serialize};
-use tracing::info;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+
+use uuid::Uuid;
 
-use crate::domain::models::{JournalLine, NetSuiteBatch};
+use crate::{
+    domain::models::{JournalLine, JournalLineKind, NetSuiteBatch, NetSuiteFieldMapping},
+    infrastructure::config::{NetSuiteConfig, NetSuiteExportMode},
+    telemetry,
+};
 
 #[cfg(test)]
-use std::sync::{Arc, Mutex, OnceLock};
+use std::sync::{Arc, Mutex};
 
 #[cfg(test)]
 type ExportBatchOverride =
-    dyn Fn(&NetSuiteBatch, &[JournalLine]) -> anyhow::Result<NetSuiteResponse> + Send + Sync;
+    dyn Fn(&NetSuiteBatch, &[ExportRecord]) -> anyhow::Result<NetSuiteResponse> + Send + Sync;
 
 #[cfg(test)]
 static EXPORT_BATCH_OVERRIDE: OnceLock<Mutex<Option<Arc<ExportBatchOverride>>>> = OnceLock::new();
@@ -30,7 +61,7 @@ impl Drop for ExportBatchOverrideGuard {
 #[cfg(test)]
 pub fn install_export_batch_override<F>(override_fn: F) -> ExportBatchOverrideGuard
 where
-    F: Fn(&NetSuiteBatch, &[JournalLine]) -> anyhow::Result<NetSuiteResponse>
+    F: Fn(&NetSuiteBatch, &[ExportRecord]) -> anyhow::Result<NetSuiteResponse>
         + Send
         + Sync
         + 'static,
@@ -48,9 +79,43 @@ pub struct NetSuiteResponse {
     pub message: Option<String>,
 }
 
+/// Journal entry record collection, relative to the account's SuiteTalk host.
+const RECORD_PATH: &str = "/services/rest/record/v1";
+const JOURNAL_ENTRY_PATH: &str = "/services/rest/record/v1/journalEntry";
+const SIGNATURE_METHOD: &str = "HMAC-SHA256";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// RFC 5849 §3.6 encoding: everything except ALPHA, DIGIT, `-`, `.`, `_` and
+/// `~` is percent-encoded.
+const OAUTH_ENCODE: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+static BREAKER: parking_lot::Mutex<CircuitBreaker> = parking_lot::Mutex::new(CircuitBreaker::new());
+
+/// Everything the exporter reads besides the batch and its lines.
+#[derive(Debug, Clone, Default)]
+pub struct ExportContext {
+    pub mappings: Vec<NetSuiteFieldMapping>,
+    /// HR identifier of each report's employee, keyed by report ID. Vendor
+    /// bills reference the employee's vendor record by this external ID.
+    pub payees: HashMap<Uuid, String>,
+}
+
+/// Upserts `records`, built by `export_records`, into NetSuite for `batch`.
+///
+/// Transport failures, `429` and `5xx` responses are retried with jittered
+/// exponential backoff; when every attempt fails, or the circuit breaker is
+/// open, the error is returned as `Err` so callers can keep the batch for a
+/// later retry. A request NetSuite rejects outright comes back as an
+/// unsuccessful `NetSuiteResponse` carrying NetSuite's error detail.
 pub async fn export_batch(
-    _batch: &NetSuiteBatch,
-    _lines: &[JournalLine],
+    config: &NetSuiteConfig,
+    records: &[ExportRecord],
+    batch: &NetSuiteBatch,
 ) -> anyhow::Result<NetSuiteResponse> {
     #[cfg(test)]
     {
@@ -58,15 +123,1017 @@ pub async fn export_batch(
             .get()
             .and_then(|cell| cell.lock().ok().and_then(|guard| guard.as_ref().cloned()))
         {
-            return override_fn(_batch, _lines);
+            return override_fn(batch, records);
+        }
+    }
+
+    let Some(credentials) = Credentials::from_config(config) else {
+        info!("netsuite credentials not configured; simulating export");
+        return Ok(NetSuiteResponse {
+            succeeded: true,
+            reference: Some("STUB-REF".to_string()),
+            message: Some("Simulated export".to_string()),
+        });
+    };
+
+    if !BREAKER.lock().allow(Instant::now()) {
+        anyhow::bail!("NetSuite circuit breaker is open; export deferred");
+    }
+
+    let max_attempts = config.max_attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        match upsert_records(config, &credentials, batch, records).await {
+            Ok(response) => {
+                BREAKER.lock().record_success();
+                return Ok(response);
+            }
+            Err(err) if attempt < max_attempts => {
+                let delay = backoff_delay(config, attempt, rand::thread_rng().gen());
+                warn!(
+                    batch = %batch.batch_reference,
+                    attempt,
+                    delay_ms = delay.as_millis() as u64,
+                    error = %err,
+                    "netsuite export attempt failed; retrying"
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(err) => {
+                BREAKER.lock().record_failure(
+                    Instant::now(),
+                    config.breaker_failure_threshold,
+                    Duration::from_secs(config.breaker_cooldown_seconds),
+                );
+                return Err(err.context(format!("NetSuite export failed after {attempt} attempts")));
+            }
+        }
+    }
+}
+
+/// Sends one upsert per record, keyed by its external ID, so a retry after a
+/// lost response updates records instead of duplicating them; that also makes
+/// it safe to re-send every record of a batch when a later one fails. Returns
+/// `Err` only for failures worth retrying.
+async fn upsert_records(
+    config: &NetSuiteConfig,
+    credentials: &Credentials<'_>,
+    batch: &NetSuiteBatch,
+    records: &[ExportRecord],
+) -> anyhow::Result<NetSuiteResponse> {
+    let mut references = Vec::with_capacity(records.len());
+    for record in records {
+        let url = format!(
+            "{}{RECORD_PATH}/{}/eid:{}",
+            base_url(config, credentials.account),
+            record.record_type,
+            record.external_id
+        );
+        let authorization =
+            credentials.authorization_header("PUT", &url, &oauth_nonce(), unix_timestamp());
+        let response = client()
+            .put(&url)
+            .headers(telemetry::trace_headers())
+            .header(reqwest::header::AUTHORIZATION, authorization)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(record.serialized())
+            .send()
+            .await
+            .with_context(|| format!("NetSuite {} request failed", record.record_type))?;
+
+        let status = response.status();
+        if status.is_success() {
+            let reference = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|value| value.to_str().ok())
+                .and_then(internal_id_from_location);
+            info!(
+                batch = %batch.batch_reference,
+                record_type = record.record_type,
+                internal_id = ?reference,
+                "netsuite record upserted"
+            );
+            references.push(reference);
+            continue;
         }
+
+        let body: Value = response.json().await.unwrap_or(Value::Null);
+        let mut message = error_message(status, &body);
+        if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            anyhow::bail!(message);
+        }
+        warn!(
+            batch = %batch.batch_reference,
+            record_type = record.record_type,
+            %status,
+            "netsuite rejected record"
+        );
+        if records.len() > 1 {
+            message = format!("{} {}: {message}", record.record_type, record.external_id);
+        }
+        return Ok(NetSuiteResponse {
+            succeeded: false,
+            reference: None,
+            message: Some(message),
+        });
     }
 
-    // Stub implementation – integrate with REST/SOAP client once credentials available.
-    info!("netsuite export stub invoked");
+    let message = references
+        .iter()
+        .any(Option::is_none)
+        .then(|| "NetSuite did not return every record's internal ID".to_string());
+    let reference = references
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(",");
     Ok(NetSuiteResponse {
         succeeded: true,
-        reference: Some("STUB-REF".to_string()),
-        message: Some("Simulated export".to_string()),
+        reference: (!reference.is_empty()).then_some(reference),
+        message,
+    })
+}
+
+/// Posting state of an exported journal entry as NetSuite reports it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostingStatus {
+    /// Saved but still pending journal approval.
+    Accepted,
+    Posted,
+    /// Rejected by a NetSuite approver, or deleted since it was exported.
+    Rejected,
+}
+
+/// Whether every Token-Based Authentication credential is configured, i.e.
+/// whether exports reach NetSuite rather than being simulated.
+pub fn is_configured(config: &NetSuiteConfig) -> bool {
+    Credentials::from_config(config).is_some()
+}
+
+/// Reads the posting state of the journal entry with `internal_id`.
+pub async fn fetch_posting_status(
+    config: &NetSuiteConfig,
+    internal_id: &str,
+) -> anyhow::Result<PostingStatus> {
+    let credentials =
+        Credentials::from_config(config).context("NetSuite credentials are not configured")?;
+    let url = format!(
+        "{}{JOURNAL_ENTRY_PATH}/{internal_id}",
+        base_url(config, credentials.account)
+    );
+    let authorization =
+        credentials.authorization_header("GET", &url, &oauth_nonce(), unix_timestamp());
+    let response = client()
+        .get(&url)
+        .headers(telemetry::trace_headers())
+        .header(reqwest::header::AUTHORIZATION, authorization)
+        .send()
+        .await
+        .context("NetSuite journal entry lookup failed")?;
+
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Ok(PostingStatus::Rejected);
+    }
+    let body: Value = response.json().await.unwrap_or(Value::Null);
+    if !status.is_success() {
+        anyhow::bail!(error_message(status, &body));
+    }
+    Ok(posting_status_from_record(&body))
+}
+
+/// Maps a journal entry record onto its posting state. Accounts without
+/// journal approvals omit `approvalStatus`; their entries post on save.
+fn posting_status_from_record(record: &Value) -> PostingStatus {
+    match record
+        .get("approvalStatus")
+        .and_then(|status| status.get("id"))
+        .and_then(Value::as_str)
+    {
+        Some("1") => PostingStatus::Accepted,
+        Some("3") => PostingStatus::Rejected,
+        _ => PostingStatus::Posted,
+    }
+}
+
+/// Confirms NetSuite accepts the configured credentials with a signed read
+/// of the record metadata catalog, which needs no particular record.
+pub async fn check_credentials(config: &NetSuiteConfig) -> anyhow::Result<()> {
+    let credentials =
+        Credentials::from_config(config).context("NetSuite credentials are not configured")?;
+    let url = format!(
+        "{}{RECORD_PATH}/metadata-catalog",
+        base_url(config, credentials.account)
+    );
+    let authorization =
+        credentials.authorization_header("GET", &url, &oauth_nonce(), unix_timestamp());
+    let response = client()
+        .get(&url)
+        .headers(telemetry::trace_headers())
+        .header(reqwest::header::AUTHORIZATION, authorization)
+        .send()
+        .await
+        .context("NetSuite metadata catalog lookup failed")?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body: Value = response.json().await.unwrap_or(Value::Null);
+        anyhow::bail!(error_message(status, &body));
+    }
+    Ok(())
+}
+
+/// Whether a processing callback is authentic:`webhook_secret` is set,
+/// `signature` is the base64 HMAC-SHA256 of `<timestamp>.<body>` under it, and
+/// `timestamp` (Unix seconds) is within `webhook_tolerance_seconds` of now, so
+/// a captured callback cannot be replayed later.
+pub fn verify_callback(
+    config: &NetSuiteConfig,
+    timestamp: &str,
+    body: &[u8],
+    signature: &str,
+) -> bool {
+    let Some(secret) = config
+        .webhook_secret
+        .as_deref()
+        .filter(|secret| !secret.trim().is_empty())
+    else {
+        return false;
+    };
+    callback_signature_valid(
+        secret,
+        timestamp,
+        body,
+        signature,
+        unix_timestamp(),
+        config.webhook_tolerance_seconds,
+    )
+}
+
+fn callback_signature_valid(
+    secret: &str,
+    timestamp: &str,
+    body: &[u8],
+    signature: &str,
+    now: u64,
+    tolerance_seconds: u64,
+) -> bool {
+    let Ok(signed_at) = timestamp.trim().parse::<u64>() else {
+        return false;
+    };
+    if signed_at.abs_diff(now) > tolerance_seconds {
+        return false;
+    }
+    let Ok(signature) = BASE64.decode(signature.trim()) else {
+        return false;
+    };
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(timestamp.trim().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// Delay before retrying after failed attempt `attempt` (1-based):
+/// `initial_backoff_ms` doubled per attempt and capped at `max_backoff_ms`,
+/// with `jitter` (0.0–1.0) spreading it over the upper half of that window so
+/// concurrent exporters do not retry in lockstep.
+fn backoff_delay(config: &NetSuiteConfig, attempt: u32, jitter: f64) -> Duration {
+    let doublings = attempt.saturating_sub(1).min(32);
+    let capped = config
+        .initial_backoff_ms
+        .saturating_mul(1 << doublings)
+        .min(config.max_backoff_ms);
+    let half = capped / 2;
+    Duration::from_millis(capped - half + (half as f64 * jitter.clamp(0.0, 1.0)) as u64)
+}
+
+/// Process-wide breaker over failed exports. Once `threshold` exports in a
+/// row have exhausted their retries, calls fail fast until the cooldown
+/// passes; the circuit is then half-open, so one more failure reopens it and
+/// a success closes it.
+#[derive(Debug)]
+struct CircuitBreaker {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    const fn new() -> Self {
+        Self {
+            consecutive_failures: 0,
+            open_until: None,
+        }
+    }
+
+    fn allow(&self, now: Instant) -> bool {
+        self.open_until.is_none_or(|until| now >= until)
+    }
+
+    fn record_success(&mut self) {
+        *self = Self::new();
+    }
+
+    fn record_failure(&mut self, now: Instant, threshold: u32, cooldown: Duration) {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        if self.consecutive_failures >= threshold.max(1) {
+            self.open_until = Some(now + cooldown);
+        }
+    }
+}
+
+/// Token-Based Authentication credentials; present only when every
+/// `NetSuiteConfig` secret is set.
+struct Credentials<'a> {
+    account: &'a str,
+    consumer_key: &'a str,
+    consumer_secret: &'a str,
+    token_id: &'a str,
+    token_secret: &'a str,
+}
+
+impl<'a> Credentials<'a> {
+    fn from_config(config: &'a NetSuiteConfig) -> Option<Self> {
+        let field = |value: &'a Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+        };
+        Some(Self {
+            account: field(&config.account)?,
+            consumer_key: field(&config.consumer_key)?,
+            consumer_secret: field(&config.consumer_secret)?,
+            token_id: field(&config.token_id)?,
+            token_secret: field(&config.token_secret)?,
+        })
+    }
+
+    /// Builds the OAuth 1.0a `Authorization` header for a request without
+    /// query parameters. The realm is the account ID, which NetSuite expects
+    /// in upper case (e.g. `1234567_SB1`).
+    fn authorization_header(&self, method: &str, url: &str, nonce: &str, timestamp: u64) -> String {
+        let timestamp = timestamp.to_string();
+        // Already in the lexicographic order the signature base string needs.
+        let params = [
+            ("oauth_consumer_key", self.consumer_key),
+            ("oauth_nonce", nonce),
+            ("oauth_signature_method", SIGNATURE_METHOD),
+            ("oauth_timestamp", timestamp.as_str()),
+            ("oauth_token", self.token_id),
+            ("oauth_version", "1.0"),
+        ];
+        let normalized = params
+            .iter()
+            .map(|(key, value)| format!("{key}={}", encode(value)))
+            .collect::<Vec<_>>()
+            .join("&");
+        let base_string = format!("{method}&{}&{}", encode(url), encode(&normalized));
+        let signing_key = format!(
+            "{}&{}",
+            encode(self.consumer_secret),
+            encode(self.token_secret)
+        );
+        let mut mac = Hmac::<Sha256>::new_from_slice(signing_key.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(base_string.as_bytes());
+        let signature = BASE64.encode(mac.finalize().into_bytes());
+
+        let mut header = format!("OAuth realm=\"{}\"", self.account.to_uppercase());
+        for (key, value) in params {
+            header.push_str(&format!(", {key}=\"{}\"", encode(value)));
+        }
+        header.push_str(&format!(", oauth_signature=\"{}\"", encode(&signature)));
+        header
+    }
+}
+
+fn encode(value: &str) -> String {
+    utf8_percent_encode(value, OAUTH_ENCODE).to_string()
+}
+
+fn oauth_nonce() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(20)
+        .map(char::from)
+        .collect()
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+fn client() -> &'static reqwest::Client {
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("NetSuite HTTP client configuration is static")
     })
 }
+
+/// Uses `base_url` when configured, otherwise the account's standard
+/// SuiteTalk host (`1234567_SB1` → `https://1234567-sb1.suitetalk.api.netsuite.com`).
+fn base_url(config: &NetSuiteConfig, account: &str) -> String {
+    match config
+        .base_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+    {
+        Some(url) => url.trim_end_matches('/').to_string(),
+        None => format!(
+            "https://{}.suitetalk.api.netsuite.com",
+            account.to_lowercase().replace('_', "-")
+        ),
+    }
+}
+
+/// Journal line fields a `NetSuiteFieldMapping` can match on.
+pub const MAPPABLE_LINE_FIELDS: [&str; 4] = ["gl_account", "department", "class", "tax_code"];
+
+/// One SuiteTalk record upserted for a batch.
+#[derive(Debug, Clone)]
+pub struct ExportRecord {
+    pub record_type: &'static str,
+    pub external_id: String,
+    pub body: Value,
+}
+
+impl ExportRecord {
+    /// The exact request body sent for this record.
+    pub fn serialized(&self) -> String {
+        self.body.to_string()
+    }
+}
+
+/// Hex SHA-256 digest of a serialized payload, archived next to it.
+pub fn payload_digest(payload: &str) -> String {
+    format!("{:x}", Sha256::digest(payload.as_bytes()))
+}
+
+/// Builds the records a batch exports as: a single journal entry, or one
+/// vendor bill per employee in vendor-bill mode.
+pub fn export_records(
+    mode: NetSuiteExportMode,
+    context: &ExportContext,
+    batch: &NetSuiteBatch,
+    lines: &[JournalLine],
+) -> Vec<ExportRecord> {
+    match mode {
+        NetSuiteExportMode::JournalEntry => vec![ExportRecord {
+            record_type: "journalEntry",
+            external_id: batch.id.to_string(),
+            body: journal_entry_payload(batch, lines, &context.mappings),
+        }],
+        NetSuiteExportMode::VendorBill => vendor_bill_records(batch, lines, context),
+    }
+}
+
+/// The body (or, in vendor-bill mode, the bodies) the exporter would send
+/// for `batch`, as shown by dry-run finalization.
+pub fn export_payload(
+    config: &NetSuiteConfig,
+    context: &ExportContext,
+    batch: &NetSuiteBatch,
+    lines: &[JournalLine],
+) -> Value {
+    let mut records = export_records(config.export_mode, context, batch, lines);
+    match config.export_mode {
+        NetSuiteExportMode::JournalEntry => records.remove(0).body,
+        NetSuiteExportMode::VendorBill => {
+            Value::Array(records.into_iter().map(|record| record.body).collect())
+        }
+    }
+}
+
+/// Maps a batch onto a SuiteTalk `journalEntry` body. Positive amounts are
+/// debits and negative amounts credits; GL accounts, departments, classes and
+/// tax codes are sent as NetSuite internal IDs unless `mappings` translate
+/// them. Unconditional mappings are applied next, then value-matched line
+/// mappings, so the most specific rule for a field wins.
+pub fn journal_entry_payload(
+    batch: &NetSuiteBatch,
+    lines: &[JournalLine],
+    mappings: &[NetSuiteFieldMapping],
+) -> Value {
+    let items: Vec<Value> = lines
+        .iter()
+        .map(|line| {
+            let mut item = json!({ "account": { "id": line.gl_account } });
+            let side = if line.amount_cents < 0 {
+                "credit"
+            } else {
+                "debit"
+            };
+            item[side] = json!(line.amount_cents.unsigned_abs() as f64 / 100.0);
+            if let Some(memo) = &line.memo {
+                item["memo"] = json!(memo);
+            }
+            if let Some(department) = &line.department {
+                item["department"] = json!({ "id": department });
+            }
+            if let Some(class) = &line.class {
+                item["class"] = json!({ "id": class });
+            }
+            if let Some(tax_code) = &line.tax_code {
+                item["taxCode"] = json!({ "id": tax_code });
+            }
+            apply_mappings(&mut item, mappings, "line", Some(line));
+            item
+        })
+        .collect();
+
+    let mut entry = json!({
+        "tranDate": batch.posting_date.to_string(),
+        "memo": format!("Expense batch {}", batch.batch_reference),
+        "line": { "items": items },
+    });
+    apply_mappings(&mut entry, mappings, "header", None);
+    entry
+}
+
+/// Maps a batch onto one SuiteTalk `vendorBill` per employee, in the order
+/// employees first appear. Each bill's payee is the vendor whose external ID
+/// is the employee's HR identifier and its external ID is
+/// `<batch id>-<HR identifier>`. Expense lines become `expense` items; the
+/// report's liability line only supplies the bill's payables `account`,
+/// since the bill itself credits accounts payable. A reversal batch becomes
+/// `vendorCredit` records with the amounts flipped back to positive.
+fn vendor_bill_records(
+    batch: &NetSuiteBatch,
+    lines: &[JournalLine],
+    context: &ExportContext,
+) -> Vec<ExportRecord> {
+    let (record_type, sign) = match batch.reverses_batch_id {
+        Some(_) => ("vendorCredit", -1),
+        None => ("vendorBill", 1),
+    };
+    let mut payees: Vec<&str> = Vec::new();
+    let mut grouped: HashMap<&str, (Option<&str>, Vec<Value>)> = HashMap::new();
+    for line in lines {
+        let payee = context
+            .payees
+            .get(&line.report_id)
+            .map(String::as_str)
+            .unwrap_or_default();
+        let (account, items) = grouped.entry(payee).or_insert_with(|| {
+            payees.push(payee);
+            (None, Vec::new())
+        });
+        if line.line_kind == JournalLineKind::Liability.as_str() {
+            account.get_or_insert(line.gl_account.as_str());
+            continue;
+        }
+        let mut item = json!({
+            "account": { "id": line.gl_account },
+            "amount": (sign * line.amount_cents) as f64 / 100.0,
+        });
+        if let Some(memo) = &line.memo {
+            item["memo"] = json!(memo);
+        }
+        if let Some(department) = &line.department {
+            item["department"] = json!({ "id": department });
+        }
+        if let Some(class) = &line.class {
+            item["class"] = json!({ "id": class });
+        }
+        if let Some(tax_code) = &line.tax_code {
+            item["taxCode"] = json!({ "id": tax_code });
+        }
+        apply_mappings(&mut item, &context.mappings, "line", Some(line));
+        items.push(item);
+    }
+
+    payees
+        .into_iter()
+        .map(|payee| {
+            let (account, items) = grouped.remove(payee).unwrap_or_default();
+            let external_id = format!("{}-{payee}", batch.id);
+            let mut body = json!({
+                "externalId": external_id,
+                "entity": { "externalId": payee },
+                "tranDate": batch.posting_date.to_string(),
+                "memo": format!("Expense batch {}", batch.batch_reference),
+                "expense": { "items": items },
+            });
+            if let Some(account) = account {
+                body["account"] = json!({ "id": account });
+            }
+            apply_mappings(&mut body, &context.mappings, "header", None);
+            ExportRecord {
+                record_type,
+                external_id,
+                body,
+            }
+        })
+        .collect()
+}
+
+fn apply_mappings(
+    record: &mut Value,
+    mappings: &[NetSuiteFieldMapping],
+    target: &str,
+    line: Option<&JournalLine>,
+) {
+    let applicable = mappings.iter().filter(|mapping| mapping.target == target);
+    for mapping in applicable
+        .clone()
+        .filter(|mapping| mapping.source_field.is_none())
+    {
+        record[mapping.netsuite_field.as_str()] = json!({ "id": mapping.netsuite_value });
+    }
+    let Some(line) = line else {
+        return;
+    };
+    for mapping in applicable {
+        let matches = match (mapping.source_field.as_deref(), &mapping.source_value) {
+            (Some(field), Some(value)) => line_field(line, field) == Some(value.as_str()),
+            _ => false,
+        };
+        if matches {
+            record[mapping.netsuite_field.as_str()] = json!({ "id": mapping.netsuite_value });
+        }
+    }
+}
+
+fn line_field<'a>(line: &'a JournalLine, field: &str) -> Option<&'a str> {
+    match field {
+        "gl_account" => Some(line.gl_account.as_str()),
+        "department" => line.department.as_deref(),
+        "class" => line.class.as_deref(),
+        "tax_code" => line.tax_code.as_deref(),
+        _ => None,
+    }
+}
+
+/// Extracts the record internal ID from a `Location` header such as
+/// `https://…/record/v1/journalEntry/4711`.
+fn internal_id_from_location(location: &str) -> Option<String> {
+    location
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .filter(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()))
+        .map(str::to_string)
+}
+
+/// Summarizes a SuiteTalk error body, preferring its `o:errorDetails`.
+fn error_message(status: reqwest::StatusCode, body: &Value) -> String {
+    let details: Vec<&str> = body
+        .get("o:errorDetails")
+        .and_then(Value::as_array)
+        .map(|details| {
+            details
+                .iter()
+                .filter_map(|detail| detail.get("detail").and_then(Value::as_str))
+                .collect()
+        })
+        .unwrap_or_default();
+    if !details.is_empty() {
+        return format!("NetSuite returned {status}: {}", details.join("; "));
+    }
+    match body.get("title").and_then(Value::as_str) {
+        Some(title) => format!("NetSuite returned {status}: {title}"),
+        None => format!("NetSuite returned {status}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, Utc};
+    use uuid::Uuid;
+
+    fn config() -> NetSuiteConfig {
+        NetSuiteConfig {
+            base_url: None,
+            account: Some("1234567_sb1".to_string()),
+            consumer_key: Some("consumerkey".to_string()),
+            consumer_secret: Some("consumer secret".to_string()),
+            token_id: Some("tokenid".to_string()),
+            token_secret: Some("token&secret".to_string()),
+            ..NetSuiteConfig::default()
+        }
+    }
+
+    #[test]
+    fn credentials_require_every_secret() {
+        assert!(Credentials::from_config(&config()).is_some());
+        let missing = NetSuiteConfig {
+            token_secret: Some("  ".to_string()),
+            ..config()
+        };
+        assert!(Credentials::from_config(&missing).is_none());
+        assert!(Credentials::from_config(&NetSuiteConfig::default()).is_none());
+    }
+
+    #[test]
+    fn authorization_header_signs_with_hmac_sha256() {
+        let config = config();
+        let credentials = Credentials::from_config(&config).unwrap();
+        let url = format!(
+            "{}{JOURNAL_ENTRY_PATH}",
+            base_url(&config, credentials.account)
+        );
+        assert_eq!(
+            url,
+            "https://1234567-sb1.suitetalk.api.netsuite.com/services/rest/record/v1/journalEntry"
+        );
+
+        let header = credentials.authorization_header("POST", &url, "abc123", 1_700_000_000);
+
+        assert_eq!(
+            header,
+            "OAuth realm=\"1234567_SB1\", oauth_consumer_key=\"consumerkey\", \
+             oauth_nonce=\"abc123\", oauth_signature_method=\"HMAC-SHA256\", \
+             oauth_timestamp=\"1700000000\", oauth_token=\"tokenid\", oauth_version=\"1.0\", \
+             oauth_signature=\"JnmQfEzz3saN9l2wk2pgZgOksM4Nro9mrzxrBJX1ZzA%3D\""
+        );
+    }
+
+    #[test]
+    fn callback_signature_covers_timestamp_and_body() {
+        let body = br#"{"external_id":"1","status":"posted"}"#;
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"hook-secret").unwrap();
+        mac.update(b"1700000000.");
+        mac.update(body);
+        let signature = BASE64.encode(mac.finalize().into_bytes());
+
+        let valid = |timestamp: &str, body: &[u8], signature: &str, now: u64| {
+            callback_signature_valid("hook-secret", timestamp, body, signature, now, 300)
+        };
+        assert!(valid("1700000000", body, &signature, 1_700_000_100));
+        // Tampered body, shifted timestamp, or a different secret all fail.
+        assert!(!valid("1700000000", b"{}", &signature, 1_700_000_100));
+        assert!(!valid("1700000001", body, &signature, 1_700_000_100));
+        assert!(!callback_signature_valid(
+            "other",
+            "1700000000",
+            body,
+            &signature,
+            1_700_000_100,
+            300
+        ));
+        // Stale or malformed signatures are refused before the MAC is checked.
+        assert!(!valid("1700000000", body, &signature, 1_700_000_301));
+        assert!(!valid("yesterday", body, &signature, 1_700_000_100));
+        assert!(!valid("1700000000", body, "not base64!", 1_700_000_100));
+    }
+
+    #[test]
+    fn callbacks_are_refused_without_a_secret() {
+        assert!(!verify_callback(&config(), "1700000000", b"{}", "c2ln"));
+    }
+
+    #[test]
+    fn posting_status_follows_journal_approval() {
+        let record = |id: &str| json!({ "approvalStatus": { "id": id, "refName": "" } });
+        assert_eq!(
+            posting_status_from_record(&record("1")),
+            PostingStatus::Accepted
+        );
+        assert_eq!(
+            posting_status_from_record(&record("2")),
+            PostingStatus::Posted
+        );
+        assert_eq!(
+            posting_status_from_record(&record("3")),
+            PostingStatus::Rejected
+        );
+        assert_eq!(
+            posting_status_from_record(&json!({ "tranId": "JE42" })),
+            PostingStatus::Posted
+        );
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_cap_with_jitter() {
+        let config = NetSuiteConfig {
+            initial_backoff_ms: 500,
+            max_backoff_ms: 3_000,
+            ..NetSuiteConfig::default()
+        };
+        let millis = |attempt, jitter| backoff_delay(&config, attempt, jitter).as_millis();
+
+        assert_eq!(millis(1, 1.0), 500);
+        assert_eq!(millis(1, 0.0), 250);
+        assert_eq!(millis(2, 1.0), 1_000);
+        assert_eq!(millis(3, 0.5), 1_500);
+        assert_eq!(millis(4, 1.0), 3_000);
+        assert_eq!(millis(40, 0.0), 1_500);
+    }
+
+    #[test]
+    fn breaker_opens_after_threshold_and_half_opens_after_cooldown() {
+        let cooldown = Duration::from_secs(60);
+        let start = Instant::now();
+        let mut breaker = CircuitBreaker::new();
+
+        breaker.record_failure(start, 2, cooldown);
+        assert!(breaker.allow(start));
+        breaker.record_failure(start, 2, cooldown);
+        assert!(!breaker.allow(start + Duration::from_secs(59)));
+
+        // Half-open: a trial call goes through and a single failure reopens.
+        let later = start + cooldown;
+        assert!(breaker.allow(later));
+        breaker.record_failure(later, 2, cooldown);
+        assert!(!breaker.allow(later + Duration::from_secs(1)));
+
+        breaker.record_success();
+        assert!(breaker.allow(later + Duration::from_secs(1)));
+    }
+
+    fn batch() -> NetSuiteBatch {
+        NetSuiteBatch {
+            id: Uuid::new_v4(),
+            batch_reference: "B-1".to_string(),
+            finalized_by: Uuid::new_v4(),
+            finalized_at: Utc::now(),
+            status: "pending".to_string(),
+            exported_at: None,
+            netsuite_response: None,
+            export_attempts: 1,
+            last_attempted_at: None,
+            reverses_batch_id: None,
+            posting_date: NaiveDate::from_ymd_opt(2024, 5, 31).unwrap(),
+        }
+    }
+
+    fn line(batch: &NetSuiteBatch, gl_account: &str, amount_cents: i64, kind: &str) -> JournalLine {
+        JournalLine {
+            id: Uuid::new_v4(),
+            batch_id: batch.id,
+            report_id: Uuid::new_v4(),
+            line_number: 1,
+            gl_account: gl_account.to_string(),
+            amount_cents,
+            department: (kind == "expense").then(|| "12".to_string()),
+            class: None,
+            memo: Some("memo".to_string()),
+            tax_code: None,
+            tax_amount_cents: None,
+            line_kind: kind.to_string(),
+        }
+    }
+
+    fn mapping(
+        target: &str,
+        source: Option<(&str, &str)>,
+        netsuite_field: &str,
+        netsuite_value: &str,
+    ) -> NetSuiteFieldMapping {
+        NetSuiteFieldMapping {
+            id: Uuid::new_v4(),
+            target: target.to_string(),
+            source_field: source.map(|(field, _)| field.to_string()),
+            source_value: source.map(|(_, value)| value.to_string()),
+            netsuite_field: netsuite_field.to_string(),
+            netsuite_value: netsuite_value.to_string(),
+            description: None,
+            updated_by: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn payload_splits_debits_and_credits() {
+        let batch = batch();
+        let payload = journal_entry_payload(
+            &batch,
+            &[
+                line(&batch, "64190", 12_345, "expense"),
+                line(&batch, "21100", -12_345, "liability"),
+            ],
+            &[],
+        );
+
+        assert_eq!(payload["tranDate"], "2024-05-31");
+        assert_eq!(payload["memo"], "Expense batch B-1");
+        let items = payload["line"]["items"].as_array().unwrap();
+        assert_eq!(items[0]["account"]["id"], "64190");
+        assert_eq!(items[0]["debit"], 123.45);
+        assert_eq!(items[0]["department"]["id"], "12");
+        assert!(items[0].get("credit").is_none());
+        assert_eq!(items[1]["credit"], 123.45);
+        assert!(items[1].get("department").is_none());
+    }
+
+    #[test]
+    fn payload_applies_field_mappings_most_specific_last() {
+        let batch = batch();
+        let mappings = [
+            mapping("header", None, "subsidiary", "3"),
+            mapping("line", None, "location", "7"),
+            mapping("line", Some(("department", "12")), "location", "9"),
+            mapping("line", Some(("gl_account", "64190")), "account", "345"),
+            mapping("line", Some(("department", "12")), "cseg_region", "2"),
+        ];
+
+        let payload = journal_entry_payload(
+            &batch,
+            &[
+                line(&batch, "64190", 5_000, "expense"),
+                line(&batch, "21100", -5_000, "liability"),
+            ],
+            &mappings,
+        );
+
+        assert_eq!(payload["subsidiary"]["id"], "3");
+        let items = payload["line"]["items"].as_array().unwrap();
+        assert_eq!(items[0]["account"]["id"], "345");
+        assert_eq!(items[0]["location"]["id"], "9");
+        assert_eq!(items[0]["cseg_region"]["id"], "2");
+        assert_eq!(items[1]["account"]["id"], "21100");
+        assert_eq!(items[1]["location"]["id"], "7");
+        assert!(items[1].get("cseg_region").is_none());
+        assert!(items[0].get("subsidiary").is_none());
+    }
+
+    #[test]
+    fn vendor_bill_mode_groups_lines_by_employee() {
+        let batch = batch();
+        let first = line(&batch, "64190", 5_000, "expense");
+        let mut second = line(&batch, "64180", 1_250, "expense");
+        second.report_id = first.report_id;
+        let mut liability = line(&batch, "21100", -6_250, "liability");
+        liability.report_id = first.report_id;
+        let other = line(&batch, "62090", 800, "expense");
+        let context = ExportContext {
+            mappings: vec![mapping("header", None, "subsidiary", "3")],
+            payees: HashMap::from([
+                (first.report_id, "EMP3101".to_string()),
+                (other.report_id, "EMP3102".to_string()),
+            ]),
+        };
+        let lines = [first, second, liability, other];
+
+        let records = export_records(NetSuiteExportMode::VendorBill, &context, &batch, &lines);
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].record_type, "vendorBill");
+        assert_eq!(records[0].external_id, format!("{}-EMP3101", batch.id));
+        let bill = &records[0].body;
+        assert_eq!(bill["entity"]["externalId"], "EMP3101");
+        assert_eq!(bill["account"]["id"], "21100");
+        assert_eq!(bill["subsidiary"]["id"], "3");
+        let items = bill["expense"]["items"].as_array().unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0]["amount"], 50.0);
+        assert_eq!(items[1]["account"]["id"], "64180");
+        assert_eq!(records[1].body["entity"]["externalId"], "EMP3102");
+        assert!(records[1].body.get("account").is_none());
+
+        let reversal = NetSuiteBatch {
+            reverses_batch_id: Some(batch.id),
+            ..batch.clone()
+        };
+        let negated: Vec<JournalLine> = lines
+            .iter()
+            .map(|line| JournalLine {
+                amount_cents: -line.amount_cents,
+                ..line.clone()
+            })
+            .collect();
+        let credits = export_records(
+            NetSuiteExportMode::VendorBill,
+            &context,
+            &reversal,
+            &negated,
+        );
+        assert_eq!(credits[0].record_type, "vendorCredit");
+        assert_eq!(credits[0].body["expense"]["items"][0]["amount"], 50.0);
+    }
+
+    #[test]
+    fn reads_internal_id_and_errors_from_responses() {
+        assert_eq!(
+            internal_id_from_location(
+                "https://1234567.suitetalk.api.netsuite.com/services/rest/record/v1/journalEntry/4711"
+            ),
+            Some("4711".to_string())
+        );
+        assert_eq!(internal_id_from_location("https://example.com/"), None);
+
+        let body = json!({
+            "title": "Bad Request",
+            "o:errorDetails": [{ "detail": "Invalid account reference key 99." }],
+        });
+        assert_eq!(
+            error_message(reqwest::StatusCode::BAD_REQUEST, &body),
+            "NetSuite returned 400 Bad Request: Invalid account reference key 99."
+        );
+        assert_eq!(
+            error_message(reqwest::StatusCode::UNAUTHORIZED, &Value::Null),
+            "NetSuite returned 401 Unauthorized"
+        );
+    }
+}
@@ -0,0 +1,215 @@
+//! Slack and Microsoft Teams incoming webhooks.
+//!
+//! `ChatWebhook` posts a `ChatMessage` in the format of the provider selected
+//! in `ChatConfig`: Slack Block Kit, or a Teams `MessageCard`. Links become
+//! buttons in either.
+
+use std::{sync::OnceLock, time::Duration};
+
+use anyhow::Context;
+use serde_json::{json, Value};
+
+use crate::infrastructure::config::{ChatConfig, ChatProvider};
+
+use super::setting;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+/// A chat message ready to post.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatMessage {
+    pub title: String,
+    pub text: String,
+    pub links: Vec<ChatLink>,
+}
+
+/// A button opening `url`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatLink {
+    pub label: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChatWebhook {
+    provider: ChatProvider,
+    default_url: Option<String>,
+}
+
+impl ChatWebhook {
+    /// The deployment-wide webhook, if one is configured.
+    pub fn default_url(&self) -> Option<&str> {
+        self.default_url.as_deref()
+    }
+
+    /// Posts `message` to the incoming webhook at `url`.
+    pub async fn post(&self, url: &str, message: &ChatMessage) -> anyhow::Result<()> {
+        let response = client()
+            .post(url)
+            .json(&payload(self.provider, message))
+            .send()
+            .await
+            // Webhook URLs carry their credential; keep it out of the error.
+            .map_err(reqwest::Error::without_url)
+            .context("chat webhook request failed")?;
+        let status = response.status();
+        if !status.is_success() {
+            let detail = response.text().await.unwrap_or_default();
+            anyhow::bail!("chat webhook answered {status}: {detail}");
+        }
+        Ok(())
+    }
+}
+
+/// Builds the configured webhook client, or `None` when chat is disabled.
+/// Fails on a `webhook_url` that is not an HTTPS URL.
+pub fn build_chat_webhook(config: &ChatConfig) -> anyhow::Result<Option<ChatWebhook>> {
+    if !config.enabled {
+        return Ok(None);
+    }
+    let default_url = setting(&config.webhook_url)
+        .map(|url| {
+            validate_webhook_url(url).context("`chat.webhook_url` is not an HTTPS URL")?;
+            Ok::<_, anyhow::Error>(url.to_string())
+        })
+        .transpose()?;
+    Ok(Some(ChatWebhook {
+        provider: config.provider,
+        default_url,
+    }))
+}
+
+/// Checks that `url` is an absolute HTTPS URL, as both providers issue.
+pub fn validate_webhook_url(url: &str) -> anyhow::Result<()> {
+    let parsed = url::Url::parse(url.trim())?;
+    anyhow::ensure!(
+        parsed.scheme() == "https" && parsed.host_str().is_some(),
+        "webhook URLs must use https"
+    );
+    Ok(())
+}
+
+fn client() -> &'static reqwest::Client {
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("chat HTTP client configuration is static")
+    })
+}
+
+fn payload(provider: ChatProvider, message: &ChatMessage) -> Value {
+    match provider {
+        ChatProvider::Slack => {
+            let mut blocks = vec![json!({
+                "type": "section",
+                "text": {
+                    "type": "mrkdwn",
+                    "text": format!(
+                        "*{}*\n{}",
+                        slack_escape(&message.title),
+                        slack_escape(&message.text)
+                    ),
+                },
+            })];
+            if !message.links.is_empty() {
+                let buttons: Vec<Value> = message
+                    .links
+                    .iter()
+                    .map(|link| {
+                        json!({
+                            "type": "button",
+                            "text": { "type": "plain_text", "text": link.label },
+                            "url": link.url,
+                        })
+                    })
+                    .collect();
+                blocks.push(json!({ "type": "actions", "elements": buttons }));
+            }
+            json!({ "text": message.title, "blocks": blocks })
+        }
+        ChatProvider::Teams => {
+            let actions: Vec<Value> = message
+                .links
+                .iter()
+                .map(|link| {
+                    json!({
+                        "@type": "OpenUri",
+                        "name": link.label,
+                        "targets": [{ "os": "default", "uri": link.url }],
+                    })
+                })
+                .collect();
+            json!({
+                "@type": "MessageCard",
+                "@context": "https://schema.org/extensions",
+                "summary": message.title,
+                "title": message.title,
+                "text": message.text,
+                "potentialAction": actions,
+            })
+        }
+    }
+}
+
+/// Escapes the characters Slack's `mrkdwn` treats as control sequences.
+fn slack_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message() -> ChatMessage {
+        ChatMessage {
+            title: "Expense report awaiting approval".into(),
+            text: "Meals & travel <May>".into(),
+            links: vec![ChatLink {
+                label: "Approve".into(),
+                url: "https://portal.example.com/approve?token=a".into(),
+            }],
+        }
+    }
+
+    #[test]
+    fn formats_slack_blocks_with_escaped_text_and_buttons() {
+        let body = payload(ChatProvider::Slack, &message());
+
+        assert_eq!(
+            body["blocks"][0]["text"]["text"],
+            "*Expense report awaiting approval*\nMeals &amp; travel &lt;May&gt;"
+        );
+        assert_eq!(
+            body["blocks"][1]["elements"][0]["url"],
+            "https://portal.example.com/approve?token=a"
+        );
+    }
+
+    #[test]
+    fn formats_teams_message_cards() {
+        let body = payload(ChatProvider::Teams, &message());
+
+        assert_eq!(body["@type"], "MessageCard");
+        assert_eq!(body["title"], "Expense report awaiting approval");
+        assert_eq!(body["potentialAction"][0]["name"], "Approve");
+    }
+
+    #[test]
+    fn requires_https_webhooks() {
+        assert!(validate_webhook_url("https://hooks.slack.com/services/T/B/X").is_ok());
+        assert!(validate_webhook_url("http://hooks.slack.com/services/T/B/X").is_err());
+        assert!(validate_webhook_url("hooks.slack.com").is_err());
+
+        let config = ChatConfig {
+            enabled: true,
+            webhook_url: Some("ftp://example.com".into()),
+            ..ChatConfig::default()
+        };
+        assert!(build_chat_webhook(&config).is_err());
+    }
+}
@@ -0,0 +1,132 @@
+//! Per-locale message catalogs for notification templates.
+//!
+//! Each supported language has a Fluent resource in `locales/`, compiled into
+//! the binary. A recipient's `employees.locale`, a BCP 47 tag such as
+//! `de-AT`, selects the catalog for its language; a missing or unsupported
+//! locale gets English, as does any message a catalog lacks.
+
+use std::sync::OnceLock;
+
+use anyhow::{bail, Context};
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource};
+use unic_langid::LanguageIdentifier;
+
+/// Language used when the recipient's locale has no catalog.
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Language subtags with a catalog, and their Fluent sources.
+const RESOURCES: [(&str, &str); 4] = [
+    (DEFAULT_LOCALE, include_str!("locales/en.ftl")),
+    ("de", include_str!("locales/de.ftl")),
+    ("es", include_str!("locales/es.ftl")),
+    ("fr", include_str!("locales/fr.ftl")),
+];
+
+type Bundle = FluentBundle<FluentResource>;
+
+static BUNDLES: OnceLock<Vec<(LanguageIdentifier, Bundle)>> = OnceLock::new();
+
+/// The messages of one locale, falling back to English.
+#[derive(Clone, Copy)]
+pub struct Catalog {
+    bundle: &'static Bundle,
+    fallback: &'static Bundle,
+}
+
+impl Catalog {
+    /// The catalog matching the language of `locale`. Accepts `_` as well as
+    /// `-` between subtags.
+    pub fn for_locale(locale: Option<&str>) -> Self {
+        let bundles = bundles();
+        let fallback = &bundles[0].1;
+        let requested = locale
+            .map(|tag| tag.trim().replace('_', "-"))
+            .and_then(|tag| tag.parse::<LanguageIdentifier>().ok());
+        let bundle = requested
+            .and_then(|requested| {
+                bundles
+                    .iter()
+                    .find(|(langid, _)| langid.language == requested.language)
+            })
+            .map_or(fallback, |(_, bundle)| bundle);
+        Catalog { bundle, fallback }
+    }
+
+    /// Formats message `id` with `args`.
+    pub fn format(&self, id: &str, args: &[(&str, &str)]) -> anyhow::Result<String> {
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+            fluent_args.set(*name, *value);
+        }
+        for bundle in [self.bundle, self.fallback] {
+            let Some(pattern) = bundle.get_message(id).and_then(|message| message.value()) else {
+                continue;
+            };
+            let mut errors = Vec::new();
+            let text = bundle.format_pattern(pattern, Some(&fluent_args), &mut errors);
+            if let Some(err) = errors.first() {
+                bail!("cannot format message {id}: {err:?}");
+            }
+            return Ok(text.into_owned());
+        }
+        bail!("no message {id} in the notification catalogs")
+    }
+}
+
+fn bundles() -> &'static [(LanguageIdentifier, Bundle)] {
+    BUNDLES.get_or_init(|| {
+        RESOURCES
+            .iter()
+            .map(|(tag, source)| load(tag, source))
+            .collect::<anyhow::Result<_>>()
+            .expect("bundled notification catalogs are valid")
+    })
+}
+
+fn load(tag: &str, source: &str) -> anyhow::Result<(LanguageIdentifier, Bundle)> {
+    let langid: LanguageIdentifier = tag.parse().with_context(|| format!("bad locale {tag}"))?;
+    let resource = FluentResource::try_new(source.to_string())
+        .map_err(|(_, errors)| anyhow::anyhow!("cannot parse {tag} catalog: {errors:?}"))?;
+    let mut bundle = FluentBundle::new_concurrent(vec![langid.clone()]);
+    // Isolation marks are for bidirectional UI text; they would show up as
+    // stray characters in plain-text email.
+    bundle.set_use_isolating(false);
+    bundle
+        .add_resource(resource)
+        .map_err(|errors| anyhow::anyhow!("duplicate messages in {tag} catalog: {errors:?}"))?;
+    Ok((langid, bundle))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_catalog_defines_every_english_message() {
+        let ids: Vec<&str> = RESOURCES[0]
+            .1
+            .lines()
+            .filter(|line| line.starts_with(|c: char| c.is_ascii_lowercase()))
+            .filter_map(|line| line.split_once(" =").map(|(id, _)| id))
+            .collect();
+        assert!(ids.contains(&"report-submitted-subject"));
+        for (langid, bundle) in bundles() {
+            for id in &ids {
+                assert!(bundle.has_message(id), "{langid} catalog lacks {id}");
+            }
+        }
+    }
+
+    #[test]
+    fn selects_the_catalog_by_language_and_falls_back_to_english() {
+        let args = [("start", "2024-05-01"), ("end", "2024-05-31")];
+        let period = |locale| Catalog::for_locale(locale).format("period", &args).unwrap();
+
+        assert_eq!(period(Some("de-AT")), "2024-05-01 bis 2024-05-31");
+        assert_eq!(period(Some("fr_BE")), "du 2024-05-01 au 2024-05-31");
+        assert_eq!(period(Some("pt-BR")), "2024-05-01 to 2024-05-31");
+        assert_eq!(period(Some("not a locale")), "2024-05-01 to 2024-05-31");
+        assert_eq!(period(None), "2024-05-01 to 2024-05-31");
+        assert!(Catalog::for_locale(None).format("missing", &[]).is_err());
+    }
+}
@@ -0,0 +1,44 @@
+# Benachrichtigungsvorlagen auf Deutsch.
+
+period = { $start } bis { $end }
+report-id = Abrechnungs-ID: { $report_id }
+
+report-submitted-subject = Spesenabrechnung für { $period } eingegangen
+report-submitted-body = Ihre Spesenabrechnung für { $period } über { $total } wurde eingereicht und wartet auf die Freigabe durch Ihre Führungskraft. Sie erhalten eine weitere E-Mail, sobald sie geprüft wurde.
+
+approval-request-subject = Spesenabrechnung wartet auf Ihre Freigabe ({ $period })
+approval-request-body = Eine Spesenabrechnung für { $period } über { $total } wartet auf Ihre Freigabe.
+approval-request-links =
+    Freigeben: { $approve }
+    Änderungen anfordern: { $request_changes }
+
+    Diese Links können einmal verwendet werden und laufen am { $expires_at } ab.
+
+approval-decision-subject =
+    { $status ->
+        [approved] Spesenabrechnung freigegeben ({ $period })
+        [needs_changes] Spesenabrechnung zur Überarbeitung zurückgegeben ({ $period })
+       *[denied] Spesenabrechnung abgelehnt ({ $period })
+    }
+approval-decision-body =
+    { $status ->
+        [approved] Ihre Spesenabrechnung für { $period } über { $total } wurde freigegeben. Sie geht nun in den nächsten Prüfschritt.
+        [needs_changes] Ihre Spesenabrechnung für { $period } über { $total } wurde zur Überarbeitung zurückgegeben. Die Anmerkungen der prüfenden Person finden Sie unten.
+       *[denied] Ihre Spesenabrechnung für { $period } über { $total } wurde abgelehnt. Sie wird in der eingereichten Form nicht erstattet.
+    }
+reviewer-comments = Anmerkungen der prüfenden Person:
+
+reimbursement-paid-subject = Ihre Spesenerstattung wurde ausgezahlt
+reimbursement-paid-intro = Die folgenden Spesenabrechnungen wurden im Stapel { $batch_reference } zur Erstattung gebucht:
+reimbursement-paid-outro = Die Beträge gehen mit dem nächsten Zahlungslauf auf Ihrem Konto ein.
+
+chat-approval-request-title = Spesenabrechnung wartet auf Ihre Freigabe
+chat-approval-request-text = { $period } über { $total }. Abrechnungs-ID: { $report_id }
+chat-approve-link = Freigeben
+chat-request-changes-link = Änderungen anfordern
+chat-approval-decision-title =
+    { $status ->
+        [approved] Spesenabrechnung freigegeben
+        [needs_changes] Spesenabrechnung zur Überarbeitung zurückgegeben
+       *[denied] Spesenabrechnung abgelehnt
+    }
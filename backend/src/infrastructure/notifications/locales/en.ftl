@@ -0,0 +1,46 @@
+# Notification templates in English, the fallback for every other locale.
+# Arguments arrive formatted: dates as YYYY-MM-DD and amounts with their
+# currency symbol.
+
+period = { $start } to { $end }
+report-id = Report ID: { $report_id }
+
+report-submitted-subject = Expense report received for { $period }
+report-submitted-body = Your expense report for { $period } totalling { $total } was submitted and is waiting for your manager's approval. You will get another email once it has been reviewed.
+
+approval-request-subject = Expense report awaiting your approval ({ $period })
+approval-request-body = An expense report for { $period } totalling { $total } is waiting for your approval.
+approval-request-links =
+    Approve: { $approve }
+    Request changes: { $request_changes }
+
+    These links can be used once and expire on { $expires_at }.
+
+approval-decision-subject =
+    { $status ->
+        [approved] Expense report approved ({ $period })
+        [needs_changes] Expense report returned for changes ({ $period })
+       *[denied] Expense report denied ({ $period })
+    }
+approval-decision-body =
+    { $status ->
+        [approved] Your expense report for { $period } totalling { $total } was approved. It moves on to the next review step.
+        [needs_changes] Your expense report for { $period } totalling { $total } was returned for changes. See the reviewer's comments below.
+       *[denied] Your expense report for { $period } totalling { $total } was denied. It will not be reimbursed as submitted.
+    }
+reviewer-comments = Reviewer comments:
+
+reimbursement-paid-subject = Your expense reimbursement has been paid
+reimbursement-paid-intro = The following expense reports were posted for reimbursement in batch { $batch_reference }:
+reimbursement-paid-outro = The amounts will reach your account with the next payment run.
+
+chat-approval-request-title = Expense report awaiting your approval
+chat-approval-request-text = { $period } totalling { $total }. Report ID: { $report_id }
+chat-approve-link = Approve
+chat-request-changes-link = Request changes
+chat-approval-decision-title =
+    { $status ->
+        [approved] Expense report approved
+        [needs_changes] Expense report returned for changes
+       *[denied] Expense report denied
+    }
@@ -0,0 +1,44 @@
+# Plantillas de notificación en español.
+
+period = del { $start } al { $end }
+report-id = ID del informe: { $report_id }
+
+report-submitted-subject = Informe de gastos recibido para el período { $period }
+report-submitted-body = Su informe de gastos del período { $period } por un total de { $total } se ha enviado y está pendiente de la aprobación de su responsable. Recibirá otro correo electrónico cuando se haya revisado.
+
+approval-request-subject = Informe de gastos pendiente de su aprobación ({ $period })
+approval-request-body = Un informe de gastos del período { $period } por un total de { $total } está pendiente de su aprobación.
+approval-request-links =
+    Aprobar: { $approve }
+    Solicitar cambios: { $request_changes }
+
+    Estos enlaces solo pueden usarse una vez y caducan el { $expires_at }.
+
+approval-decision-subject =
+    { $status ->
+        [approved] Informe de gastos aprobado ({ $period })
+        [needs_changes] Informe de gastos devuelto para cambios ({ $period })
+       *[denied] Informe de gastos rechazado ({ $period })
+    }
+approval-decision-body =
+    { $status ->
+        [approved] Su informe de gastos del período { $period } por un total de { $total } ha sido aprobado. Pasa al siguiente paso de revisión.
+        [needs_changes] Su informe de gastos del período { $period } por un total de { $total } ha sido devuelto para cambios. Consulte los comentarios del revisor a continuación.
+       *[denied] Su informe de gastos del período { $period } por un total de { $total } ha sido rechazado. No se reembolsará tal como se presentó.
+    }
+reviewer-comments = Comentarios del revisor:
+
+reimbursement-paid-subject = Se ha pagado su reembolso de gastos
+reimbursement-paid-intro = Los siguientes informes de gastos se registraron para reembolso en el lote { $batch_reference }:
+reimbursement-paid-outro = Los importes llegarán a su cuenta con el próximo ciclo de pagos.
+
+chat-approval-request-title = Informe de gastos pendiente de su aprobación
+chat-approval-request-text = Período { $period } por un total de { $total }. ID del informe: { $report_id }
+chat-approve-link = Aprobar
+chat-request-changes-link = Solicitar cambios
+chat-approval-decision-title =
+    { $status ->
+        [approved] Informe de gastos aprobado
+        [needs_changes] Informe de gastos devuelto para cambios
+       *[denied] Informe de gastos rechazado
+    }
@@ -0,0 +1,44 @@
+# Modèles de notification en français.
+
+period = du { $start } au { $end }
+report-id = Identifiant de la note : { $report_id }
+
+report-submitted-subject = Note de frais reçue pour la période { $period }
+report-submitted-body = Votre note de frais pour la période { $period }, d'un total de { $total }, a été soumise et attend l'approbation de votre responsable. Vous recevrez un autre e-mail une fois qu'elle aura été examinée.
+
+approval-request-subject = Note de frais en attente de votre approbation ({ $period })
+approval-request-body = Une note de frais pour la période { $period }, d'un total de { $total }, attend votre approbation.
+approval-request-links =
+    Approuver : { $approve }
+    Demander des modifications : { $request_changes }
+
+    Ces liens ne peuvent être utilisés qu'une seule fois et expirent le { $expires_at }.
+
+approval-decision-subject =
+    { $status ->
+        [approved] Note de frais approuvée ({ $period })
+        [needs_changes] Note de frais renvoyée pour modifications ({ $period })
+       *[denied] Note de frais refusée ({ $period })
+    }
+approval-decision-body =
+    { $status ->
+        [approved] Votre note de frais pour la période { $period }, d'un total de { $total }, a été approuvée. Elle passe à l'étape de validation suivante.
+        [needs_changes] Votre note de frais pour la période { $period }, d'un total de { $total }, a été renvoyée pour modifications. Consultez les commentaires ci-dessous.
+       *[denied] Votre note de frais pour la période { $period }, d'un total de { $total }, a été refusée. Elle ne sera pas remboursée en l'état.
+    }
+reviewer-comments = Commentaires de la personne chargée de la validation :
+
+reimbursement-paid-subject = Vos frais ont été remboursés
+reimbursement-paid-intro = Les notes de frais suivantes ont été comptabilisées pour remboursement dans le lot { $batch_reference } :
+reimbursement-paid-outro = Les montants seront versés sur votre compte lors du prochain cycle de paiement.
+
+chat-approval-request-title = Note de frais en attente de votre approbation
+chat-approval-request-text = Période { $period }, d'un total de { $total }. Identifiant de la note : { $report_id }
+chat-approve-link = Approuver
+chat-request-changes-link = Demander des modifications
+chat-approval-decision-title =
+    { $status ->
+        [approved] Note de frais approuvée
+        [needs_changes] Note de frais renvoyée pour modifications
+       *[denied] Note de frais refusée
+    }
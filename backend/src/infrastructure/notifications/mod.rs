@@ -0,0 +1,85 @@
+//! Outbound email and chat for queued notifications.
+//!
+//! `EmailSender` hides the provider selected in `EmailConfig`: an SMTP relay
+//! (`smtp`) or Amazon SES (`ses`). `chat` posts to Slack or Teams incoming
+//! webhooks. `templates` turns a notification's kind and payload into the
+//! message sent, in the recipient's language from the `locales` catalogs.
+//! Nothing here reads the queue; the `email` and `chat` jobs
+//! hand each pending notification to the client built at startup.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::infrastructure::config::{EmailConfig, EmailProvider};
+
+pub mod chat;
+pub mod locales;
+pub mod ses;
+pub mod smtp;
+pub mod templates;
+
+/// A plain-text email ready to send.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[async_trait]
+pub trait EmailSender: Send + Sync {
+    async fn send(&self, message: &EmailMessage) -> anyhow::Result<()>;
+}
+
+/// Builds the configured sender, or `None` when email is disabled. Fails on
+/// incomplete provider settings so bad configuration stops startup.
+pub fn build_email_sender(config: &EmailConfig) -> anyhow::Result<Option<Arc<dyn EmailSender>>> {
+    if !config.enabled {
+        return Ok(None);
+    }
+    if config.from_address.trim().is_empty() {
+        anyhow::bail!("email is enabled but `email.from_address` is blank");
+    }
+    let sender: Arc<dyn EmailSender> = match config.provider {
+        EmailProvider::Smtp => Arc::new(smtp::SmtpSender::new(config)?),
+        EmailProvider::Ses => Arc::new(ses::SesSender::new(config)?),
+    };
+    Ok(Some(sender))
+}
+
+/// Trimmed value of an optional setting, treating blank as unset.
+fn setting(value: &Option<String>) -> Option<&str> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_email_builds_no_sender() {
+        assert!(build_email_sender(&EmailConfig::default())
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn enabled_email_needs_a_sender_address_and_provider_settings() {
+        let mut config = EmailConfig {
+            enabled: true,
+            smtp_host: Some("smtp.example.com".into()),
+            ..EmailConfig::default()
+        };
+        assert!(build_email_sender(&config).is_err());
+
+        config.from_address = "Expense Portal <expenses@example.com>".into();
+        assert!(build_email_sender(&config).unwrap().is_some());
+
+        config.provider = EmailProvider::Ses;
+        assert!(build_email_sender(&config).is_err());
+    }
+}
@@ -0,0 +1,68 @@
+//! SMTP delivery through any relay, authenticated when a username is set.
+
+use anyhow::Context;
+use async_trait::async_trait;
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+
+use crate::infrastructure::config::{EmailConfig, SmtpSecurity};
+
+use super::{setting, EmailMessage, EmailSender};
+
+pub struct SmtpSender {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpSender {
+    pub fn new(config: &EmailConfig) -> anyhow::Result<Self> {
+        let host = setting(&config.smtp_host)
+            .context("the SMTP email provider needs `email.smtp_host`")?;
+        let from = config
+            .from_address
+            .trim()
+            .parse()
+            .context("`email.from_address` is not a valid mailbox")?;
+
+        let mut builder = match config.smtp_security {
+            SmtpSecurity::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?,
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host)?,
+            SmtpSecurity::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+        };
+        if let Some(port) = config.smtp_port {
+            builder = builder.port(port);
+        }
+        if let Some(username) = setting(&config.smtp_username) {
+            let password = setting(&config.smtp_password).unwrap_or_default();
+            builder = builder.credentials(Credentials::new(username.into(), password.into()));
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from,
+        })
+    }
+}
+
+#[async_trait]
+impl EmailSender for SmtpSender {
+    async fn send(&self, message: &EmailMessage) -> anyhow::Result<()> {
+        let email = Message::builder()
+            .from(self.from.clone())
+            .to(message
+                .to
+                .parse()
+                .context("recipient is not a valid mailbox")?)
+            .subject(&message.subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(message.body.clone())?;
+        self.transport
+            .send(email)
+            .await
+            .context("SMTP relay rejected the message")?;
+        Ok(())
+    }
+}
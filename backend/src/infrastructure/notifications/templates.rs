@@ -0,0 +1,489 @@
+//! Plain-text email and chat templates, keyed by notification kind.
+//!
+//! Each template reads the payload its notification was queued with and
+//! takes its wording from the recipient's `locales::Catalog`. Kinds without a
+//! template are not emailed; payloads that already carry a rendered `subject`
+//! and `body`, such as the weekly digest, are sent as they are. Chat covers
+//! approval requests and decisions only.
+
+use anyhow::Context;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{ApprovalStatus, Currency, Money},
+    services::{approvals, expenses, netsuite_status},
+};
+
+use super::{
+    chat::{ChatLink, ChatMessage},
+    locales::Catalog,
+};
+
+/// Subject and body rendered for one notification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderedEmail {
+    pub subject: String,
+    pub body: String,
+}
+
+/// Renders the email for a notification of `kind` in the recipient's
+/// `locale`, or `None` when the kind is not emailed. Fails when the payload
+/// does not match the kind.
+pub fn render(
+    kind: &str,
+    payload: &Value,
+    locale: Option<&str>,
+) -> anyhow::Result<Option<RenderedEmail>> {
+    let catalog = Catalog::for_locale(locale);
+    let rendered = match kind {
+        expenses::REPORT_SUBMITTED_KIND => submission_received(&catalog, parse(kind, payload)?)?,
+        expenses::APPROVAL_REQUEST_KIND => approval_request(&catalog, parse(kind, payload)?)?,
+        approvals::APPROVAL_DECISION_KIND => approval_decision(&catalog, parse(kind, payload)?)?,
+        netsuite_status::REIMBURSEMENT_PAID_KIND => {
+            reimbursement_paid(&catalog, parse(kind, payload)?)?
+        }
+        _ => match (
+            payload.get("subject").and_then(Value::as_str),
+            payload.get("body").and_then(Value::as_str),
+        ) {
+            (Some(subject), Some(body)) => RenderedEmail {
+                subject: subject.to_string(),
+                body: body.to_string(),
+            },
+            _ => return Ok(None),
+        },
+    };
+    Ok(Some(rendered))
+}
+
+/// Renders the chat message for a notification of `kind` in the recipient's
+/// `locale`, or `None` when the kind is not posted to chat. Fails when the
+/// payload does not match the kind.
+pub fn render_chat(
+    kind: &str,
+    payload: &Value,
+    locale: Option<&str>,
+) -> anyhow::Result<Option<ChatMessage>> {
+    let catalog = Catalog::for_locale(locale);
+    let message = match kind {
+        expenses::APPROVAL_REQUEST_KIND => {
+            let request: ApprovalRequest = parse(kind, payload)?;
+            let report = &request.report;
+            let links = match request.links {
+                Some(links) => vec![
+                    ChatLink {
+                        label: catalog.format("chat-approve-link", &[])?,
+                        url: links.approve,
+                    },
+                    ChatLink {
+                        label: catalog.format("chat-request-changes-link", &[])?,
+                        url: links.request_changes,
+                    },
+                ],
+                None => Vec::new(),
+            };
+            ChatMessage {
+                title: catalog.format("chat-approval-request-title", &[])?,
+                text: catalog.format(
+                    "chat-approval-request-text",
+                    &borrow(&report.args(&catalog)?),
+                )?,
+                links,
+            }
+        }
+        approvals::APPROVAL_DECISION_KIND => {
+            let decision: ApprovalDecision = parse(kind, payload)?;
+            let status = decision_status(&decision.status)?;
+            let mut text = decision.summary(&catalog, status)?;
+            if let Some(comments) = decision.comments() {
+                text.push_str(&format!(
+                    "\n{} {comments}",
+                    catalog.format("reviewer-comments", &[])?
+                ));
+            }
+            ChatMessage {
+                title: catalog.format("chat-approval-decision-title", &[("status", status)])?,
+                text,
+                links: Vec::new(),
+            }
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(message))
+}
+
+fn parse<T: for<'de> Deserialize<'de>>(kind: &str, payload: &Value) -> anyhow::Result<T> {
+    T::deserialize(payload).with_context(|| format!("invalid {kind} payload"))
+}
+
+#[derive(Debug, Deserialize)]
+struct ReportSummary {
+    report_id: Uuid,
+    reporting_period_start: NaiveDate,
+    reporting_period_end: NaiveDate,
+    total_amount_cents: i64,
+    currency: String,
+}
+
+impl ReportSummary {
+    fn period(&self, catalog: &Catalog) -> anyhow::Result<String> {
+        period(
+            catalog,
+            self.reporting_period_start,
+            self.reporting_period_end,
+        )
+    }
+
+    fn total(&self) -> anyhow::Result<Money> {
+        money(self.total_amount_cents, &self.currency)
+    }
+
+    /// The report's period, total, and ID as message arguments.
+    fn args(&self, catalog: &Catalog) -> anyhow::Result<[(&'static str, String); 3]> {
+        Ok([
+            ("period", self.period(catalog)?),
+            ("total", self.total()?.to_string()),
+            ("report_id", self.report_id.to_string()),
+        ])
+    }
+
+    fn report_id_line(&self, catalog: &Catalog) -> anyhow::Result<String> {
+        catalog.format("report-id", &[("report_id", &self.report_id.to_string())])
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ApprovalRequest {
+    #[serde(flatten)]
+    report: ReportSummary,
+    links: Option<ActionLinks>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ActionLinks {
+    approve: String,
+    request_changes: String,
+    expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApprovalDecision {
+    #[serde(flatten)]
+    report: ReportSummary,
+    /// `ApprovalStatus::as_str` of the decision.
+    status: String,
+    comments: Option<String>,
+}
+
+impl ApprovalDecision {
+    fn comments(&self) -> Option<&str> {
+        self.comments
+            .as_deref()
+            .map(str::trim)
+            .filter(|comments| !comments.is_empty())
+    }
+
+    /// The outcome sentence and follow-up shared by email and chat.
+    fn summary(&self, catalog: &Catalog, status: &str) -> anyhow::Result<String> {
+        let args = self.report.args(catalog)?;
+        catalog.format(
+            "approval-decision-body",
+            &[&borrow(&args)[..], &[("status", status)]].concat(),
+        )
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ReimbursementPaid {
+    batch_reference: String,
+    reports: Vec<PaidReport>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PaidReport {
+    reporting_period_start: NaiveDate,
+    reporting_period_end: NaiveDate,
+    reimbursable_cents: i64,
+    currency: String,
+}
+
+fn submission_received(catalog: &Catalog, report: ReportSummary) -> anyhow::Result<RenderedEmail> {
+    let args = report.args(catalog)?;
+    let args = borrow(&args);
+    Ok(RenderedEmail {
+        subject: catalog.format("report-submitted-subject", &args)?,
+        body: format!(
+            "{}\n\n{}\n",
+            catalog.format("report-submitted-body", &args)?,
+            report.report_id_line(catalog)?
+        ),
+    })
+}
+
+fn approval_request(catalog: &Catalog, request: ApprovalRequest) -> anyhow::Result<RenderedEmail> {
+    let report = &request.report;
+    let args = report.args(catalog)?;
+    let args = borrow(&args);
+    let mut body = format!(
+        "{}\n\n{}\n",
+        catalog.format("approval-request-body", &args)?,
+        report.report_id_line(catalog)?
+    );
+    if let Some(links) = &request.links {
+        let expires_at = links.expires_at.format("%Y-%m-%d %H:%M UTC").to_string();
+        body.push_str(&format!(
+            "\n{}\n",
+            catalog.format(
+                "approval-request-links",
+                &[
+                    ("approve", &links.approve),
+                    ("request_changes", &links.request_changes),
+                    ("expires_at", &expires_at),
+                ],
+            )?
+        ));
+    }
+    Ok(RenderedEmail {
+        subject: catalog.format("approval-request-subject", &args)?,
+        body,
+    })
+}
+
+fn approval_decision(
+    catalog: &Catalog,
+    decision: ApprovalDecision,
+) -> anyhow::Result<RenderedEmail> {
+    let report = &decision.report;
+    let status = decision_status(&decision.status)?;
+    let mut body = format!("{}\n", decision.summary(catalog, status)?);
+    if let Some(comments) = decision.comments() {
+        body.push_str(&format!(
+            "\n{}\n{comments}\n",
+            catalog.format("reviewer-comments", &[])?
+        ));
+    }
+    body.push_str(&format!("\n{}\n", report.report_id_line(catalog)?));
+    Ok(RenderedEmail {
+        subject: catalog.format(
+            "approval-decision-subject",
+            &[("status", status), ("period", &report.period(catalog)?)],
+        )?,
+        body,
+    })
+}
+
+/// Checks that `status` is an `ApprovalStatus::as_str` with a decision
+/// message, which the catalogs select on.
+fn decision_status(status: &str) -> anyhow::Result<&'static str> {
+    [
+        ApprovalStatus::Approved,
+        ApprovalStatus::NeedsChanges,
+        ApprovalStatus::Denied,
+    ]
+    .into_iter()
+    .map(|candidate| candidate.as_str())
+    .find(|candidate| *candidate == status)
+    .with_context(|| format!("unknown approval status {status}"))
+}
+
+fn reimbursement_paid(catalog: &Catalog, paid: ReimbursementPaid) -> anyhow::Result<RenderedEmail> {
+    let mut body = format!(
+        "{}\n\n",
+        catalog.format(
+            "reimbursement-paid-intro",
+            &[("batch_reference", &paid.batch_reference)],
+        )?
+    );
+    for report in &paid.reports {
+        body.push_str(&format!(
+            "- {}: {}\n",
+            period(
+                catalog,
+                report.reporting_period_start,
+                report.reporting_period_end
+            )?,
+            money(report.reimbursable_cents, &report.currency)?
+        ));
+    }
+    body.push_str(&format!(
+        "\n{}\n",
+        catalog.format("reimbursement-paid-outro", &[])?
+    ));
+    Ok(RenderedEmail {
+        subject: catalog.format("reimbursement-paid-subject", &[])?,
+        body,
+    })
+}
+
+fn period(catalog: &Catalog, start: NaiveDate, end: NaiveDate) -> anyhow::Result<String> {
+    catalog.format(
+        "period",
+        &[("start", &start.to_string()), ("end", &end.to_string())],
+    )
+}
+
+fn borrow<'a, const N: usize>(
+    args: &'a [(&'static str, String); N],
+) -> [(&'static str, &'a str); N] {
+    args.each_ref().map(|(name, value)| (*name, value.as_str()))
+}
+
+fn money(amount_cents: i64, currency: &str) -> anyhow::Result<Money> {
+    Ok(Money::new(amount_cents, Currency::parse(currency)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn report() -> Value {
+        json!({
+            "report_id": "5b0c3f59-2f7e-4f55-9a57-58d9a0d6a7a1",
+            "employee_id": "0f6e5d0b-8c1f-4cf3-9d6c-2d8f0c7f1e11",
+            "reporting_period_start": "2024-05-01",
+            "reporting_period_end": "2024-05-31",
+            "total_amount_cents": 12_345,
+            "currency": "USD",
+        })
+    }
+
+    #[test]
+    fn renders_a_decision_with_reviewer_comments() {
+        let mut payload = report();
+        payload["status"] = json!("needs_changes");
+        payload["comments"] = json!("Attach the hotel folio.");
+
+        let email = render(approvals::APPROVAL_DECISION_KIND, &payload, None)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            email.subject,
+            "Expense report returned for changes (2024-05-01 to 2024-05-31)"
+        );
+        assert!(email
+            .body
+            .contains("totalling $123.45 was returned for changes"));
+        assert!(email.body.contains("Attach the hotel folio."));
+    }
+
+    #[test]
+    fn renders_in_the_recipients_locale() {
+        let mut payload = report();
+        payload["links"] = json!({
+            "approve": "https://portal.example.com/approve?token=a",
+            "request_changes": "https://portal.example.com/approve?token=b",
+            "expires_at": "2024-06-03T12:00:00Z",
+        });
+
+        let email = render(expenses::APPROVAL_REQUEST_KIND, &payload, Some("de-DE"))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            email.subject,
+            "Spesenabrechnung wartet auf Ihre Freigabe (2024-05-01 bis 2024-05-31)"
+        );
+        assert!(email.body.contains(
+            "Freigeben: https://portal.example.com/approve?token=a\n\
+             Änderungen anfordern: https://portal.example.com/approve?token=b\n\n"
+        ));
+
+        let message = render_chat(expenses::APPROVAL_REQUEST_KIND, &payload, Some("fr"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(message.links[0].label, "Approuver");
+    }
+
+    #[test]
+    fn renders_approval_links() {
+        let mut payload = report();
+        payload["links"] = json!({
+            "approve": "https://portal.example.com/approve?token=a",
+            "request_changes": "https://portal.example.com/approve?token=b",
+            "expires_at": "2024-06-03T12:00:00Z",
+        });
+
+        let email = render(expenses::APPROVAL_REQUEST_KIND, &payload, None)
+            .unwrap()
+            .unwrap();
+
+        assert!(email
+            .body
+            .contains("Approve: https://portal.example.com/approve?token=a"));
+        assert!(email.body.contains("expire on 2024-06-03 12:00 UTC"));
+    }
+
+    #[test]
+    fn renders_chat_messages_for_approval_kinds_only() {
+        let mut payload = report();
+        payload["links"] = json!({
+            "approve": "https://portal.example.com/approve?token=a",
+            "request_changes": "https://portal.example.com/approve?token=b",
+            "expires_at": "2024-06-03T12:00:00Z",
+        });
+
+        let message = render_chat(expenses::APPROVAL_REQUEST_KIND, &payload, None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(message.title, "Expense report awaiting your approval");
+        assert!(message
+            .text
+            .starts_with("2024-05-01 to 2024-05-31 totalling $123.45"));
+        assert_eq!(message.links.len(), 2);
+
+        let mut decision = report();
+        decision["status"] = json!("approved");
+        let message = render_chat(approvals::APPROVAL_DECISION_KIND, &decision, None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(message.title, "Expense report approved");
+        assert!(message.links.is_empty());
+
+        assert!(
+            render_chat(expenses::REPORT_SUBMITTED_KIND, &report(), None)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn lists_each_paid_report() {
+        let payload = json!({
+            "batch_id": "6a4f0c2e-4f4b-4c3b-8f1a-9f3f1b2c3d4e",
+            "batch_reference": "BATCH-2024-06-01",
+            "reports": [
+                {
+                    "report_id": "5b0c3f59-2f7e-4f55-9a57-58d9a0d6a7a1",
+                    "reporting_period_start": "2024-05-01",
+                    "reporting_period_end": "2024-05-31",
+                    "reimbursable_cents": 9_900,
+                    "currency": "USD",
+                },
+            ],
+        });
+
+        let email = render(netsuite_status::REIMBURSEMENT_PAID_KIND, &payload, None)
+            .unwrap()
+            .unwrap();
+
+        assert!(email.body.contains("batch BATCH-2024-06-01"));
+        assert!(email.body.contains("- 2024-05-01 to 2024-05-31: $99.00"));
+    }
+
+    #[test]
+    fn passes_prerendered_payloads_through_and_skips_unknown_kinds() {
+        let digest = json!({ "subject": "Weekly digest", "body": "2 reports" });
+        let email = render("weekly_digest", &digest, None).unwrap().unwrap();
+        assert_eq!(email.subject, "Weekly digest");
+
+        assert!(render("approval_reminder", &json!({ "reports": [] }), None)
+            .unwrap()
+            .is_none());
+        assert!(render(expenses::REPORT_SUBMITTED_KIND, &json!({}), None).is_err());
+    }
+}
@@ -0,0 +1,149 @@
+//! In-process cache of the reporting lines.
+//!
+//! Approval routing and the escalation sweep walk an employee's management
+//! chain, which takes one lookup per level. `OrgChartCache` keeps every
+//! employee's manager in `AppState` for
+//! `EXPENSES__APP__ORG_CHART_CACHE_TTL_SECONDS`; roster edits through the API
+//! and HR syncs invalidate it immediately, and the TTL bounds how long other
+//! instances (or direct database edits) can serve stale reporting lines.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, RwLock,
+    },
+    time::{Duration, Instant},
+};
+
+use uuid::Uuid;
+
+use crate::domain::models::Role;
+
+/// One employee's place in the organization chart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrgChartEntry {
+    pub org_id: Uuid,
+    pub hr_identifier: String,
+    pub manager_id: Option<Uuid>,
+    pub department: Option<String>,
+    pub role: Role,
+}
+
+/// Every employee on the deployment, by ID.
+#[derive(Debug, Clone, Default)]
+pub struct OrgChart {
+    pub employees: HashMap<Uuid, OrgChartEntry>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    chart: Arc<OrgChart>,
+    loaded_at: Instant,
+}
+
+#[derive(Debug)]
+pub struct OrgChartCache {
+    ttl: Duration,
+    entry: RwLock<Option<Entry>>,
+    /// Bumped by `invalidate`, so a load that started before an edit is not
+    /// stored after it.
+    generation: AtomicU64,
+}
+
+impl OrgChartCache {
+    /// A zero `ttl` disables caching.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entry: RwLock::new(None),
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    /// The cached chart, unless it is missing or older than the TTL.
+    pub fn get(&self) -> Option<Arc<OrgChart>> {
+        let entry = self.entry.read().unwrap_or_else(|err| err.into_inner());
+        entry
+            .as_ref()
+            .filter(|entry| entry.loaded_at.elapsed() < self.ttl)
+            .map(|entry| entry.chart.clone())
+    }
+
+    /// Token to pass to `store` for a chart about to be loaded.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Caches `chart`, loaded after `generation` was read, unless the cache
+    /// was invalidated in the meantime.
+    pub fn store(&self, generation: u64, chart: Arc<OrgChart>) {
+        let mut entry = self.entry.write().unwrap_or_else(|err| err.into_inner());
+        if self.generation() == generation {
+            *entry = Some(Entry {
+                chart,
+                loaded_at: Instant::now(),
+            });
+        }
+    }
+
+    /// Drops the cached chart; called after every roster change.
+    pub fn invalidate(&self) {
+        let mut entry = self.entry.write().unwrap_or_else(|err| err.into_inner());
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        *entry = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chart(size: u128) -> Arc<OrgChart> {
+        let employees = (0..size)
+            .map(|id| {
+                (
+                    Uuid::from_u128(id),
+                    OrgChartEntry {
+                        org_id: Uuid::nil(),
+                        hr_identifier: format!("E{id}"),
+                        manager_id: None,
+                        department: None,
+                        role: Role::Employee,
+                    },
+                )
+            })
+            .collect();
+        Arc::new(OrgChart { employees })
+    }
+
+    #[test]
+    fn serves_charts_until_invalidated() {
+        let cache = OrgChartCache::new(Duration::from_secs(60));
+        assert!(cache.get().is_none());
+
+        cache.store(cache.generation(), chart(3));
+        assert_eq!(cache.get().map(|cached| cached.employees.len()), Some(3));
+
+        cache.invalidate();
+        assert!(cache.get().is_none());
+    }
+
+    #[test]
+    fn drops_loads_that_raced_an_invalidation() {
+        let cache = OrgChartCache::new(Duration::from_secs(60));
+        let generation = cache.generation();
+        cache.invalidate();
+
+        cache.store(generation, chart(3));
+
+        assert!(cache.get().is_none());
+    }
+
+    #[test]
+    fn zero_ttl_disables_caching() {
+        let cache = OrgChartCache::new(Duration::ZERO);
+        cache.store(cache.generation(), chart(3));
+        assert!(cache.get().is_none());
+    }
+}
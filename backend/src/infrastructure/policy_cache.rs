@@ -0,0 +1,125 @@
+//! In-process cache of the current policy rules.
+//!
+//! Draft evaluation and submission read the enabled `policy_rules` and the
+//! current policy version on every call. `PolicyCache` keeps the last loaded
+//! snapshot in `AppState` for `EXPENSES__POLICY__CACHE_TTL_SECONDS`; rule
+//! edits through the API invalidate it immediately, and the TTL bounds how
+//! long other instances (or direct database edits) can serve stale rules.
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, RwLock,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::domain::models::PolicyRule;
+
+/// Enabled policy rules and the `policy_versions` entry they belong to.
+#[derive(Debug, Clone)]
+pub struct PolicySnapshot {
+    pub version: Option<i64>,
+    pub rules: Arc<Vec<PolicyRule>>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    snapshot: PolicySnapshot,
+    loaded_at: Instant,
+}
+
+#[derive(Debug)]
+pub struct PolicyCache {
+    ttl: Duration,
+    entry: RwLock<Option<Entry>>,
+    /// Bumped by `invalidate`, so a load that started before an edit is not
+    /// stored after it.
+    generation: AtomicU64,
+}
+
+impl PolicyCache {
+    /// A zero `ttl` disables caching.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entry: RwLock::new(None),
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    /// The cached snapshot, unless it is missing or older than the TTL.
+    pub fn get(&self) -> Option<PolicySnapshot> {
+        let entry = self.entry.read().unwrap_or_else(|err| err.into_inner());
+        entry
+            .as_ref()
+            .filter(|entry| entry.loaded_at.elapsed() < self.ttl)
+            .map(|entry| entry.snapshot.clone())
+    }
+
+    /// Token to pass to `store` for a snapshot about to be loaded.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Caches `snapshot`, loaded after `generation` was read, unless the cache
+    /// was invalidated in the meantime.
+    pub fn store(&self, generation: u64, snapshot: PolicySnapshot) {
+        let mut entry = self.entry.write().unwrap_or_else(|err| err.into_inner());
+        if self.generation() == generation {
+            *entry = Some(Entry {
+                snapshot,
+                loaded_at: Instant::now(),
+            });
+        }
+    }
+
+    /// Drops the cached snapshot; called after every rule edit.
+    pub fn invalidate(&self) {
+        let mut entry = self.entry.write().unwrap_or_else(|err| err.into_inner());
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        *entry = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(version: i64) -> PolicySnapshot {
+        PolicySnapshot {
+            version: Some(version),
+            rules: Arc::new(Vec::new()),
+        }
+    }
+
+    #[test]
+    fn serves_snapshots_until_invalidated() {
+        let cache = PolicyCache::new(Duration::from_secs(60));
+        assert!(cache.get().is_none());
+
+        cache.store(cache.generation(), snapshot(3));
+        assert_eq!(cache.get().and_then(|cached| cached.version), Some(3));
+
+        cache.invalidate();
+        assert!(cache.get().is_none());
+    }
+
+    #[test]
+    fn drops_loads_that_raced_an_invalidation() {
+        let cache = PolicyCache::new(Duration::from_secs(60));
+        let generation = cache.generation();
+        cache.invalidate();
+
+        cache.store(generation, snapshot(3));
+
+        assert!(cache.get().is_none());
+    }
+
+    #[test]
+    fn zero_ttl_disables_caching() {
+        let cache = PolicyCache::new(Duration::ZERO);
+        cache.store(cache.generation(), snapshot(3));
+        assert!(cache.get().is_none());
+    }
+}
@@ -0,0 +1,180 @@
+//! In-process cache of rarely changing reference data responses.
+//!
+//! Policy rules, mileage rates, expense categories, GL mappings, and tax
+//! codes are read on nearly every SPA page but edited a few times a year.
+//! `ReferenceCache` keeps each list's serialized response body and its ETag
+//! in `AppState` for `EXPENSES__APP__REFERENCE_CACHE_TTL_SECONDS`. Edits
+//! through the API invalidate the affected list immediately; the TTL bounds
+//! how long other instances (or direct database edits) can serve a stale one.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        RwLock,
+    },
+    time::{Duration, Instant},
+};
+
+use bytes::Bytes;
+use sha2::{Digest, Sha256};
+
+use crate::domain::models::Role;
+
+/// The cached reference lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReferenceData {
+    PolicyRules,
+    MileageRates,
+    Categories,
+    GlMappings,
+    TaxCodes,
+}
+
+impl ReferenceData {
+    /// Mirrors the role checks of the services listing the data, which a
+    /// cache hit does not reach.
+    pub fn visible_to(self, role: Role) -> bool {
+        match self {
+            ReferenceData::MileageRates | ReferenceData::Categories => true,
+            ReferenceData::PolicyRules => role != Role::Employee,
+            ReferenceData::GlMappings | ReferenceData::TaxCodes => {
+                matches!(role, Role::Finance | Role::Admin)
+            }
+        }
+    }
+}
+
+/// A serialized response body and its strong ETag.
+#[derive(Debug, Clone)]
+pub struct CachedBody {
+    pub etag: String,
+    pub body: Bytes,
+}
+
+impl CachedBody {
+    pub fn new(body: Vec<u8>) -> Self {
+        let hash = format!("{:x}", Sha256::digest(&body));
+        Self {
+            etag: format!("\"{}\"", &hash[..32]),
+            body: Bytes::from(body),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    body: CachedBody,
+    loaded_at: Instant,
+}
+
+#[derive(Debug)]
+pub struct ReferenceCache {
+    ttl: Duration,
+    entries: RwLock<HashMap<ReferenceData, Entry>>,
+    /// Bumped by `invalidate`, so a load that started before an edit is not
+    /// stored after it.
+    generation: AtomicU64,
+}
+
+impl ReferenceCache {
+    /// A zero `ttl` disables caching.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: RwLock::new(HashMap::new()),
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    /// The cached body, unless it is missing or older than the TTL.
+    pub fn get(&self, key: ReferenceData) -> Option<CachedBody> {
+        let entries = self.entries.read().unwrap_or_else(|err| err.into_inner());
+        entries
+            .get(&key)
+            .filter(|entry| entry.loaded_at.elapsed() < self.ttl)
+            .map(|entry| entry.body.clone())
+    }
+
+    /// Token to pass to `store` for a body about to be loaded.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Caches `body`, loaded after `generation` was read, unless the cache
+    /// was invalidated in the meantime.
+    pub fn store(&self, key: ReferenceData, generation: u64, body: CachedBody) {
+        let mut entries = self.entries.write().unwrap_or_else(|err| err.into_inner());
+        if self.generation() == generation {
+            entries.insert(
+                key,
+                Entry {
+                    body,
+                    loaded_at: Instant::now(),
+                },
+            );
+        }
+    }
+
+    /// Drops the cached `key`; called after every edit to its data.
+    pub fn invalidate(&self, key: ReferenceData) {
+        let mut entries = self.entries.write().unwrap_or_else(|err| err.into_inner());
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        entries.remove(&key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serves_bodies_until_their_list_is_invalidated() {
+        let cache = ReferenceCache::new(Duration::from_secs(60));
+        assert!(cache.get(ReferenceData::MileageRates).is_none());
+
+        let body = CachedBody::new(b"{\"rates\":[]}".to_vec());
+        cache.store(
+            ReferenceData::MileageRates,
+            cache.generation(),
+            body.clone(),
+        );
+        cache.store(
+            ReferenceData::TaxCodes,
+            cache.generation(),
+            CachedBody::new(b"{}".to_vec()),
+        );
+        let cached = cache.get(ReferenceData::MileageRates).unwrap();
+        assert_eq!(cached.etag, body.etag);
+        assert_eq!(cached.etag.len(), 34);
+        assert_ne!(cached.etag, CachedBody::new(b"{}".to_vec()).etag);
+
+        cache.invalidate(ReferenceData::MileageRates);
+        assert!(cache.get(ReferenceData::MileageRates).is_none());
+        assert!(cache.get(ReferenceData::TaxCodes).is_some());
+    }
+
+    #[test]
+    fn drops_loads_that_raced_an_invalidation() {
+        let cache = ReferenceCache::new(Duration::from_secs(60));
+        let generation = cache.generation();
+        cache.invalidate(ReferenceData::GlMappings);
+
+        cache.store(
+            ReferenceData::GlMappings,
+            generation,
+            CachedBody::new(Vec::new()),
+        );
+
+        assert!(cache.get(ReferenceData::GlMappings).is_none());
+    }
+
+    #[test]
+    fn restricted_lists_follow_the_service_role_checks() {
+        assert!(ReferenceData::MileageRates.visible_to(Role::Employee));
+        assert!(!ReferenceData::PolicyRules.visible_to(Role::Employee));
+        assert!(ReferenceData::PolicyRules.visible_to(Role::Manager));
+        assert!(!ReferenceData::GlMappings.visible_to(Role::Manager));
+        assert!(ReferenceData::TaxCodes.visible_to(Role::Admin));
+    }
+}
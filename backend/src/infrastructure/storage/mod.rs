@@ -13,8 +13,19 @@ use crate::infrastructure::config::StorageConfig;
 #[async_trait]
 pub trait StorageBackend: Send + Sync {
     async fn put(&self, key: &str, data: Bytes, content_type: &str) -> anyhow::Result<()>;
+    /// Reads the object stored under `key`; fails when there is none.
+    async fn get(&self, key: &str) -> anyhow::Result<Bytes>;
     async fn delete(&self, key: &str) -> anyhow::Result<()>;
     async fn presigned_url(&self, key: &str) -> anyhow::Result<Option<String>>;
+
+    /// Writes and removes a throwaway object to confirm the backend accepts
+    /// writes, for the deep health check.
+    async fn probe(&self) -> anyhow::Result<()> {
+        let key = format!("healthchecks/{}", uuid::Uuid::new_v4());
+        self.put(&key, Bytes::from_static(b"ok"), "text/plain")
+            .await?;
+        self.delete(&key).await
+    }
 }
 
 pub fn build_storage(config: &StorageConfig) -> anyhow::Result<Arc<dyn StorageBackend>> {
@@ -87,6 +98,11 @@ impl StorageBackend for LocalStorage {
         Ok(())
     }
 
+    async fn get(&self, key: &str) -> anyhow::Result<Bytes> {
+        let sanitized = self.validate_key(key)?;
+        Ok(Bytes::from(fs::read(self.root.join(sanitized)).await?))
+    }
+
     async fn delete(&self, key: &str) -> anyhow::Result<()> {
         let sanitized = self.validate_key(key)?;
         let path = self.root.join(sanitized);
@@ -116,6 +132,14 @@ impl StorageBackend for MemoryStorage {
         Ok(())
     }
 
+    async fn get(&self, key: &str) -> anyhow::Result<Bytes> {
+        self.objects
+            .read()
+            .get(key)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("no stored object {key}"))
+    }
+
     async fn delete(&self, key: &str) -> anyhow::Result<()> {
         self.objects.write().remove(key);
         Ok(())
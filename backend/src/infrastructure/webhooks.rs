@@ -0,0 +1,114 @@
+//! HTTP delivery of outbound webhook events.
+//!
+//! Each request carries the event type, delivery ID, and a Unix timestamp in
+//! headers, plus an HMAC-SHA256 of `"{timestamp}.{body}"` keyed with the
+//! subscription's secret. Receivers recompute it to check that the event came
+//! from this deployment and reject stale timestamps to stop replays.
+
+use std::{fmt::Write, sync::OnceLock, time::Duration};
+
+use anyhow::Context;
+use hmac::{Hmac, Mac};
+use rand::{distributions::Alphanumeric, Rng};
+use sha2::Sha256;
+use uuid::Uuid;
+
+pub const EVENT_HEADER: &str = "x-expenses-event";
+pub const DELIVERY_HEADER: &str = "x-expenses-delivery";
+pub const TIMESTAMP_HEADER: &str = "x-expenses-timestamp";
+/// `sha256=` followed by the hex-encoded signature.
+pub const SIGNATURE_HEADER: &str = "x-expenses-signature";
+
+const SECRET_PREFIX: &str = "whsec_";
+const SECRET_LENGTH: usize = 40;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+/// A new random signing secret.
+pub fn generate_secret() -> String {
+    let random: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(SECRET_LENGTH)
+        .map(char::from)
+        .collect();
+    format!("{SECRET_PREFIX}{random}")
+}
+
+/// Hex-encoded HMAC-SHA256 of `"{timestamp}.{body}"` keyed with `secret`.
+pub fn signature(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+/// Posts a signed event to `url` and returns the receiver's HTTP status.
+/// Fails only when no response arrives.
+pub async fn post(
+    url: &str,
+    secret: &str,
+    event_type: &str,
+    delivery_id: Uuid,
+    body: Vec<u8>,
+) -> anyhow::Result<u16> {
+    let timestamp = chrono::Utc::now().timestamp();
+    let response = client()
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(EVENT_HEADER, event_type)
+        .header(DELIVERY_HEADER, delivery_id.to_string())
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(
+            SIGNATURE_HEADER,
+            format!("sha256={}", signature(secret, timestamp, &body)),
+        )
+        .body(body)
+        .send()
+        .await
+        .context("webhook request failed")?;
+    Ok(response.status().as_u16())
+}
+
+fn client() -> &'static reqwest::Client {
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .expect("webhook HTTP client configuration is static")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_the_timestamp_and_body() {
+        assert_eq!(
+            signature(
+                "whsec_test",
+                1_717_243_200,
+                br#"{"type":"report.submitted"}"#
+            ),
+            "11c8b2c35063086e50ffb3d24dca08ca08c1163bdad0389e8210f130f342eee4"
+        );
+    }
+
+    #[test]
+    fn generates_distinct_prefixed_secrets() {
+        let secret = generate_secret();
+        assert!(secret.starts_with(SECRET_PREFIX));
+        assert_eq!(secret.len(), SECRET_PREFIX.len() + SECRET_LENGTH);
+        assert_ne!(secret, generate_secret());
+    }
+}
@@ -0,0 +1,23 @@
+//! Report archiving worker.
+//!
+//! Moves reports finalized more than `archive.after_years` ago into
+//! compressed archives in the storage backend; see `services::archive`.
+
+use std::sync::Arc;
+
+use tracing::info;
+
+use crate::{infrastructure::state::AppState, services::archive};
+
+/// Scheduler entry point.
+pub async fn sweep(state: Arc<AppState>) -> anyhow::Result<()> {
+    let summary = archive::archive_due(&state)
+        .await
+        .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+    info!(
+        archived = summary.archived,
+        failed = summary.failed,
+        "report archiving completed"
+    );
+    Ok(())
+}
@@ -0,0 +1,68 @@
+//! Audit chain verification worker.
+//!
+//! Walks every audit chain on schedule and, when an entry was altered or
+//! removed, tells the operating organization's administrators and fails the
+//! run so it shows up in `GET /admin/jobs`. See `services::audit`.
+
+use std::sync::Arc;
+
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{Role, DEFAULT_ORG_ID},
+    infrastructure::state::AppState,
+    services::{
+        audit::{self, VerifyQuery},
+        notifications,
+    },
+};
+
+/// Notification kind telling administrators the audit log failed verification.
+pub const AUDIT_CHAIN_BROKEN_KIND: &str = "audit_chain_broken";
+
+/// Scheduler entry point.
+pub async fn sweep(state: Arc<AppState>) -> anyhow::Result<()> {
+    let verification = audit::verify_chains(&state.read_pool, &VerifyQuery::default())
+        .await
+        .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+    info!(
+        entries_checked = verification.entries_checked,
+        chains_checked = verification.chains_checked,
+        trimmed_chains = verification.trimmed_chains,
+        problem_count = verification.problem_count,
+        "audit chains verified"
+    );
+    if verification.is_intact() {
+        return Ok(());
+    }
+    for problem in &verification.problems {
+        warn!(
+            entity_type = %problem.entity_type,
+            entity_id = %problem.entity_id,
+            audit_log_id = %problem.audit_log_id,
+            chain_sequence = problem.chain_sequence,
+            kind = ?problem.kind,
+            "audit chain problem"
+        );
+    }
+
+    let admins: Vec<Uuid> =
+        sqlx::query_scalar("SELECT id FROM employees WHERE role::text = $1 AND org_id = $2")
+            .bind(Role::Admin.as_str())
+            .bind(DEFAULT_ORG_ID)
+            .fetch_all(&state.pool)
+            .await?;
+    let payload = serde_json::to_value(&verification)?;
+    let mut tx = state.pool.begin().await?;
+    for admin in admins {
+        notifications::enqueue(&mut *tx, admin, AUDIT_CHAIN_BROKEN_KIND, payload.clone())
+            .await
+            .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+    }
+    tx.commit().await?;
+    anyhow::bail!(
+        "audit log failed verification with {} problems",
+        verification.problem_count
+    )
+}
@@ -0,0 +1,31 @@
+//! Chat delivery worker.
+//!
+//! Posts the `pending` chat notifications queued by the approval flow to
+//! Slack or Teams; see `services::notifications::deliver_pending_chat` for
+//! where each one goes and what is skipped and retried.
+
+use std::sync::Arc;
+
+use tracing::info;
+
+use crate::{infrastructure::state::AppState, services::notifications};
+
+/// Scheduler entry point. Does nothing while `chat.enabled` is off.
+pub async fn sweep(state: Arc<AppState>) -> anyhow::Result<()> {
+    let Some(webhook) = state.chat.as_ref() else {
+        info!("chat delivery is disabled; notifications stay queued");
+        return Ok(());
+    };
+    let summary = notifications::deliver_pending_chat(&state, webhook)
+        .await
+        .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+    if summary.sent > 0 || summary.skipped > 0 || summary.failed > 0 {
+        info!(
+            sent = summary.sent,
+            skipped = summary.skipped,
+            failed = summary.failed,
+            "chat delivery completed"
+        );
+    }
+    Ok(())
+}
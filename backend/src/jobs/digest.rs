@@ -0,0 +1,527 @@
+//! Reviewer digest worker.
+//!
+//! On each scheduled run, summarizes each reviewer's open
+//! work and queues one `weekly_digest` notification per reviewer. Managers see
+//! the `submitted` reports of their direct reports and the `exception_review`
+//! reports routed to them; finance users see every `manager_approved` report
+//! still waiting to be finalized, NetSuite batches whose export failed, and
+//! open accounting periods about to end. Unlike reminders, the digest lists
+//! all open work regardless of how long it has waited, and reviewers with
+//! nothing pending receive nothing.
+//!
+//! The job runs daily by default. Each reviewer's `digest_frequency`
+//! preference decides whether a run includes them: `daily` and `weekly`
+//! reviewers are due once their previous digest is a day or a week old, and
+//! `none` opts out.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{Currency, Money, ReportStatus, Role},
+    infrastructure::state::AppState,
+    services::{
+        notifications::{self, DigestFrequency},
+        periods,
+    },
+};
+
+/// Notification kind recorded on queued digests, whatever the recipient's
+/// frequency; kept for existing consumers.
+pub const WEEKLY_DIGEST_KIND: &str = "weekly_digest";
+
+/// How much earlier than a full day or week a reviewer is due again, so a run
+/// that starts a little ahead of the previous one's time still includes them.
+const SCHEDULE_SLACK_HOURS: i64 = 4;
+
+/// Scheduler entry point.
+pub async fn sweep(state: Arc<AppState>) -> anyhow::Result<()> {
+    let sent = run_digest(&state).await?;
+    info!(digests = sent, "digest sweep completed");
+    Ok(())
+}
+
+/// Builds and queues one digest per reviewer with open work, returning the
+/// number queued.
+pub async fn run_digest(state: &AppState) -> anyhow::Result<usize> {
+    let now = Utc::now();
+    let open: Vec<OpenReport> = sqlx::query_as(
+        r#"
+        SELECT e.manager_id AS recipient_id, r.id AS report_id, r.status,
+               e.hr_identifier AS employee_hr_identifier, r.updated_at AS pending_since,
+               r.total_amount_cents, r.currency
+        FROM expense_reports r
+        JOIN employees e ON e.id = r.employee_id
+        WHERE r.status = $1 AND e.manager_id IS NOT NULL
+        UNION ALL
+        SELECT r.exception_approver_id AS recipient_id, r.id AS report_id, r.status,
+               e.hr_identifier AS employee_hr_identifier, r.updated_at AS pending_since,
+               r.total_amount_cents, r.currency
+        FROM expense_reports r
+        JOIN employees e ON e.id = r.employee_id
+        WHERE r.status = $2 AND r.exception_approver_id IS NOT NULL
+        UNION ALL
+        SELECT f.id AS recipient_id, r.id AS report_id, r.status,
+               e.hr_identifier AS employee_hr_identifier, r.updated_at AS pending_since,
+               r.total_amount_cents, r.currency
+        FROM expense_reports r
+        JOIN employees e ON e.id = r.employee_id
+        CROSS JOIN employees f
+        WHERE r.status = $3 AND f.role = $4
+        ORDER BY pending_since ASC
+        "#,
+    )
+    .bind(ReportStatus::Submitted)
+    .bind(ReportStatus::ExceptionReview)
+    .bind(ReportStatus::ManagerApproved)
+    .bind(Role::Finance)
+    .fetch_all(&state.pool)
+    .await?;
+    let alerts = finance_alerts(state, now.date_naive()).await?;
+
+    let mut digests = build_digests(open, &alerts);
+    if digests.is_empty() {
+        return Ok(0);
+    }
+
+    let recipients: Vec<Uuid> = digests.keys().copied().collect();
+    let frequencies: HashMap<Uuid, String> = sqlx::query_as(
+        "SELECT employee_id, digest_frequency FROM notification_preferences
+         WHERE employee_id = ANY($1)",
+    )
+    .bind(&recipients)
+    .fetch_all(&state.pool)
+    .await?
+    .into_iter()
+    .collect();
+    let last_sent: HashMap<Uuid, DateTime<Utc>> = sqlx::query_as(
+        "SELECT recipient_id, MAX(created_at) FROM notifications
+         WHERE kind = $1 AND recipient_id = ANY($2)
+         GROUP BY recipient_id",
+    )
+    .bind(WEEKLY_DIGEST_KIND)
+    .bind(&recipients)
+    .fetch_all(&state.pool)
+    .await?
+    .into_iter()
+    .collect();
+    digests.retain(|recipient_id, _| {
+        let frequency = frequencies
+            .get(recipient_id)
+            .and_then(|value| DigestFrequency::parse(value))
+            .unwrap_or_default();
+        is_due(frequency, last_sent.get(recipient_id).copied(), now)
+    });
+    if digests.is_empty() {
+        return Ok(0);
+    }
+
+    let mut tx = state.pool.begin().await?;
+    for (recipient_id, digest) in &digests {
+        let payload = serde_json::to_value(digest)?;
+        notifications::enqueue(&mut *tx, *recipient_id, WEEKLY_DIGEST_KIND, payload)
+            .await
+            .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+    }
+    tx.commit().await?;
+
+    Ok(digests.len())
+}
+
+/// Loads what every finance digest lists besides reports: failed exports and
+/// open periods ending within `digest.period_close_lookahead_days` of `today`.
+/// Both are skipped when there is no finance user to send them to.
+async fn finance_alerts(state: &AppState, today: NaiveDate) -> anyhow::Result<FinanceAlerts> {
+    let recipients: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM employees WHERE role = $1")
+        .bind(Role::Finance)
+        .fetch_all(&state.pool)
+        .await?;
+    if recipients.is_empty() {
+        return Ok(FinanceAlerts::default());
+    }
+
+    let export_failures: Vec<ExportFailure> = sqlx::query_as(
+        "SELECT id AS batch_id, batch_reference, status, export_attempts, last_attempted_at
+         FROM netsuite_batches
+         WHERE status IN ('failed', 'pending_retry')
+         ORDER BY finalized_at",
+    )
+    .fetch_all(&state.pool)
+    .await?;
+
+    let horizon =
+        today + Duration::days(i64::from(state.config.digest.period_close_lookahead_days));
+    let closing_periods = sqlx::query_as::<_, (Uuid, NaiveDate, NaiveDate)>(
+        "SELECT id, period_start, period_end FROM periods
+         WHERE status = $1 AND period_end <= $2
+         ORDER BY period_end",
+    )
+    .bind(periods::PERIOD_OPEN)
+    .bind(horizon)
+    .fetch_all(&state.pool)
+    .await?
+    .into_iter()
+    .map(|(period_id, period_start, period_end)| ClosingPeriod {
+        period_id,
+        period_start,
+        period_end,
+        days_remaining: (period_end - today).num_days(),
+    })
+    .collect();
+
+    Ok(FinanceAlerts {
+        recipients,
+        export_failures,
+        closing_periods,
+    })
+}
+
+/// Whether a reviewer with `frequency`, last sent a digest at `last_sent`, is
+/// due one at `now`.
+fn is_due(
+    frequency: DigestFrequency,
+    last_sent: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> bool {
+    let interval = match frequency {
+        DigestFrequency::None => return false,
+        DigestFrequency::Daily => Duration::days(1),
+        DigestFrequency::Weekly => Duration::weeks(1),
+    };
+    last_sent.is_none_or(|sent| now - sent >= interval - Duration::hours(SCHEDULE_SLACK_HOURS))
+}
+
+#[derive(Debug, Default)]
+struct FinanceAlerts {
+    recipients: Vec<Uuid>,
+    export_failures: Vec<ExportFailure>,
+    closing_periods: Vec<ClosingPeriod>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct OpenReport {
+    recipient_id: Uuid,
+    report_id: Uuid,
+    status: ReportStatus,
+    employee_hr_identifier: String,
+    pending_since: DateTime<Utc>,
+    total_amount_cents: i64,
+    currency: String,
+}
+
+/// Single report listed in a digest section.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct DigestLine {
+    pub report_id: Uuid,
+    pub status: ReportStatus,
+    pub employee_hr_identifier: String,
+    pub pending_since: DateTime<Utc>,
+    pub total_amount_cents: i64,
+    pub currency: String,
+}
+
+/// Reports awaiting one kind of action, with per-currency totals.
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub struct DigestSection {
+    pub count: usize,
+    pub totals_cents: BTreeMap<String, i64>,
+    pub oldest_pending_since: Option<DateTime<Utc>>,
+    pub reports: Vec<DigestLine>,
+}
+
+/// NetSuite batch whose export failed or is waiting to be retried.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, FromRow)]
+pub struct ExportFailure {
+    pub batch_id: Uuid,
+    pub batch_reference: String,
+    /// `failed` or `pending_retry`.
+    pub status: String,
+    pub export_attempts: i32,
+    pub last_attempted_at: Option<DateTime<Utc>>,
+}
+
+/// Open accounting period that ends soon, or has ended without being closed.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ClosingPeriod {
+    pub period_id: Uuid,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    /// Days until `period_end`; negative once it has passed.
+    pub days_remaining: i64,
+}
+
+/// Payload of a `weekly_digest` notification. `subject` and `body` are the
+/// rendered summary; the sections carry the same data for richer templates.
+/// `export_failures` and `closing_periods` are only filled in for finance.
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub struct Digest {
+    pub subject: String,
+    pub body: String,
+    pub pending_approval: DigestSection,
+    pub awaiting_finalization: DigestSection,
+    pub export_failures: Vec<ExportFailure>,
+    pub closing_periods: Vec<ClosingPeriod>,
+}
+
+/// Groups open reports by recipient and renders each recipient's digest.
+/// `manager_approved` reports go to the finalization section, everything else
+/// to pending approval. Every finance user also gets `alerts`, even with no
+/// reports pending.
+fn build_digests(open: Vec<OpenReport>, alerts: &FinanceAlerts) -> BTreeMap<Uuid, Digest> {
+    let mut digests: BTreeMap<Uuid, Digest> = BTreeMap::new();
+    if !alerts.export_failures.is_empty() || !alerts.closing_periods.is_empty() {
+        for recipient_id in &alerts.recipients {
+            let digest = digests.entry(*recipient_id).or_default();
+            digest.export_failures = alerts.export_failures.clone();
+            digest.closing_periods = alerts.closing_periods.clone();
+        }
+    }
+    for report in open {
+        let digest = digests.entry(report.recipient_id).or_default();
+        let section = if report.status == ReportStatus::ManagerApproved {
+            &mut digest.awaiting_finalization
+        } else {
+            &mut digest.pending_approval
+        };
+        section.count += 1;
+        *section
+            .totals_cents
+            .entry(report.currency.clone())
+            .or_default() += report.total_amount_cents;
+        section.oldest_pending_since = Some(match section.oldest_pending_since {
+            Some(oldest) => oldest.min(report.pending_since),
+            None => report.pending_since,
+        });
+        section.reports.push(DigestLine {
+            report_id: report.report_id,
+            status: report.status,
+            employee_hr_identifier: report.employee_hr_identifier,
+            pending_since: report.pending_since,
+            total_amount_cents: report.total_amount_cents,
+            currency: report.currency,
+        });
+    }
+    for digest in digests.values_mut() {
+        render(digest);
+    }
+    digests
+}
+
+fn render(digest: &mut Digest) {
+    let mut headline = Vec::new();
+    let mut body = String::new();
+    for (title, section) in [
+        ("awaiting your approval", &digest.pending_approval),
+        ("awaiting finalization", &digest.awaiting_finalization),
+    ] {
+        if section.count == 0 {
+            continue;
+        }
+        let noun = if section.count == 1 {
+            "report"
+        } else {
+            "reports"
+        };
+        headline.push(format!("{} {noun} {title}", section.count));
+        let totals: Vec<String> = section
+            .totals_cents
+            .iter()
+            .map(|(currency, cents)| format_amount(*cents, currency))
+            .collect();
+        body.push_str(&format!(
+            "{} {noun} {title} ({})\n",
+            section.count,
+            totals.join(", ")
+        ));
+        for line in &section.reports {
+            body.push_str(&format!(
+                "- {} {} pending since {}\n",
+                line.employee_hr_identifier,
+                format_amount(line.total_amount_cents, &line.currency),
+                line.pending_since.date_naive()
+            ));
+        }
+        body.push('\n');
+    }
+    if !digest.export_failures.is_empty() {
+        let count = digest.export_failures.len();
+        let noun = if count == 1 { "export" } else { "exports" };
+        headline.push(format!("{count} failed NetSuite {noun}"));
+        body.push_str(&format!("{count} NetSuite {noun} need attention\n"));
+        for failure in &digest.export_failures {
+            body.push_str(&format!(
+                "- {} {} after {} attempt{}",
+                failure.batch_reference,
+                failure.status.replace('_', " "),
+                failure.export_attempts,
+                if failure.export_attempts == 1 {
+                    ""
+                } else {
+                    "s"
+                }
+            ));
+            if let Some(attempted) = failure.last_attempted_at {
+                body.push_str(&format!(", last tried {}", attempted.date_naive()));
+            }
+            body.push('\n');
+        }
+        body.push('\n');
+    }
+    if !digest.closing_periods.is_empty() {
+        let count = digest.closing_periods.len();
+        let noun = if count == 1 { "period" } else { "periods" };
+        headline.push(format!("{count} accounting {noun} to close"));
+        body.push_str(&format!("{count} open accounting {noun} to close\n"));
+        for period in &digest.closing_periods {
+            let countdown = match period.days_remaining {
+                -1 => "ended yesterday".to_string(),
+                days if days < 0 => format!("ended {} days ago", -days),
+                0 => "ends today".to_string(),
+                1 => "ends tomorrow".to_string(),
+                days => format!("ends in {days} days"),
+            };
+            body.push_str(&format!(
+                "- {} to {} {countdown}\n",
+                period.period_start, period.period_end
+            ));
+        }
+        body.push('\n');
+    }
+    digest.subject = format!("Expense digest: {}", headline.join(", "));
+    digest.body = body.trim_end().to_string();
+}
+
+fn format_amount(cents: i64, currency: &str) -> String {
+    match Currency::parse(currency) {
+        Ok(parsed) => Money::new(cents, parsed).to_string(),
+        Err(_) => format!("{cents} {currency}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn open(recipient_id: Uuid, status: ReportStatus, day: u32, cents: i64) -> OpenReport {
+        OpenReport {
+            recipient_id,
+            report_id: Uuid::new_v4(),
+            status,
+            employee_hr_identifier: "EMP3101".to_string(),
+            pending_since: Utc.with_ymd_and_hms(2024, 5, day, 9, 0, 0).unwrap(),
+            total_amount_cents: cents,
+            currency: "USD".to_string(),
+        }
+    }
+
+    #[test]
+    fn build_digests_splits_approvals_from_finalization() {
+        let manager = Uuid::new_v4();
+        let finance = Uuid::new_v4();
+
+        let digests = build_digests(
+            vec![
+                open(manager, ReportStatus::Submitted, 1, 12_500),
+                open(manager, ReportStatus::ExceptionReview, 3, 4_000),
+                open(finance, ReportStatus::ManagerApproved, 2, 9_900),
+            ],
+            &FinanceAlerts::default(),
+        );
+
+        assert_eq!(digests.len(), 2);
+        let manager_digest = digests.get(&manager).expect("manager digest");
+        assert_eq!(manager_digest.pending_approval.count, 2);
+        assert_eq!(manager_digest.pending_approval.totals_cents["USD"], 16_500);
+        assert_eq!(
+            manager_digest.pending_approval.oldest_pending_since,
+            Some(Utc.with_ymd_and_hms(2024, 5, 1, 9, 0, 0).unwrap())
+        );
+        assert_eq!(manager_digest.awaiting_finalization.count, 0);
+        assert_eq!(
+            manager_digest.subject,
+            "Expense digest: 2 reports awaiting your approval"
+        );
+        assert!(manager_digest
+            .body
+            .starts_with("2 reports awaiting your approval ($165.00)\n- EMP3101 $125.00 pending since 2024-05-01"));
+
+        let finance_digest = digests.get(&finance).expect("finance digest");
+        assert_eq!(finance_digest.awaiting_finalization.count, 1);
+        assert_eq!(
+            finance_digest.subject,
+            "Expense digest: 1 report awaiting finalization"
+        );
+    }
+
+    #[test]
+    fn finance_digests_list_failed_exports_and_closing_periods() {
+        let finance = Uuid::new_v4();
+        let alerts = FinanceAlerts {
+            recipients: vec![finance],
+            export_failures: vec![ExportFailure {
+                batch_id: Uuid::new_v4(),
+                batch_reference: "BATCH-2024-05".to_string(),
+                status: "pending_retry".to_string(),
+                export_attempts: 3,
+                last_attempted_at: Some(Utc.with_ymd_and_hms(2024, 5, 30, 22, 0, 0).unwrap()),
+            }],
+            closing_periods: vec![ClosingPeriod {
+                period_id: Uuid::new_v4(),
+                period_start: NaiveDate::from_ymd_opt(2024, 5, 1).unwrap(),
+                period_end: NaiveDate::from_ymd_opt(2024, 5, 31).unwrap(),
+                days_remaining: 1,
+            }],
+        };
+
+        let digests = build_digests(Vec::new(), &alerts);
+
+        let digest = digests.get(&finance).expect("finance digest");
+        assert_eq!(
+            digest.subject,
+            "Expense digest: 1 failed NetSuite export, 1 accounting period to close"
+        );
+        assert!(digest
+            .body
+            .contains("- BATCH-2024-05 pending retry after 3 attempts, last tried 2024-05-30"));
+        assert!(digest
+            .body
+            .contains("- 2024-05-01 to 2024-05-31 ends tomorrow"));
+    }
+
+    #[test]
+    fn reviewers_are_due_by_their_frequency() {
+        let now = Utc.with_ymd_and_hms(2024, 5, 20, 8, 0, 0).unwrap();
+
+        assert!(is_due(DigestFrequency::Weekly, None, now));
+        assert!(!is_due(
+            DigestFrequency::Weekly,
+            Some(now - Duration::days(3)),
+            now
+        ));
+        assert!(is_due(
+            DigestFrequency::Weekly,
+            Some(now - Duration::days(7) + Duration::minutes(5)),
+            now
+        ));
+        assert!(is_due(
+            DigestFrequency::Daily,
+            Some(now - Duration::days(1)),
+            now
+        ));
+        assert!(!is_due(
+            DigestFrequency::Daily,
+            Some(now - Duration::hours(2)),
+            now
+        ));
+        assert!(!is_due(DigestFrequency::None, None, now));
+    }
+}
@@ -0,0 +1,33 @@
+//! Email delivery worker.
+//!
+//! Sends the `pending` notifications queued by the services and other jobs
+//! through the provider configured in `email`; see
+//! `services::notifications::deliver_pending` for what is sent, skipped, and
+//! retried.
+
+use std::sync::Arc;
+
+use tracing::info;
+
+use crate::{infrastructure::state::AppState, services::notifications};
+
+/// Scheduler entry point. Does nothing while `email.enabled` is off, which
+/// leaves notifications queued.
+pub async fn sweep(state: Arc<AppState>) -> anyhow::Result<()> {
+    let Some(sender) = state.email.clone() else {
+        info!("email delivery is disabled; notifications stay queued");
+        return Ok(());
+    };
+    let summary = notifications::deliver_pending(&state, sender.as_ref())
+        .await
+        .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+    if summary.sent > 0 || summary.skipped > 0 || summary.failed > 0 {
+        info!(
+            sent = summary.sent,
+            skipped = summary.skipped,
+            failed = summary.failed,
+            "email delivery completed"
+        );
+    }
+    Ok(())
+}
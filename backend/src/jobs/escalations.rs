@@ -0,0 +1,368 @@
+//! Approval escalation worker.
+//!
+//! Reports waiting on their manager (`submitted`) or exception approver
+//! (`exception_review`) escalate in two steps, counted in business days like
+//! reminders. After `escalations.skip_level_business_days` the approver's own
+//! manager is notified, walking further up the management chain (see
+//! `services::management_chain`) past the submitter. After
+//! `escalations.finance_business_days` the report is flagged in the finance
+//! escalation queue (`GET /finance/escalations`) and finance users are
+//! notified. Each step is recorded in `approval_escalations` once per approval
+//! round, so repeated sweeps do not notify again.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{ReportStatus, Role},
+    infrastructure::{config::EscalationConfig, state::AppState},
+    services::{escalations::EscalationLevel, management_chain, notifications},
+};
+
+use super::reminders::business_days_between;
+
+/// Notification kind sent to the skip-level manager.
+pub const APPROVAL_ESCALATION_KIND: &str = "approval_escalation";
+/// Notification kind sent to finance users.
+pub const FINANCE_ESCALATION_KIND: &str = "finance_escalation";
+
+/// Scheduler entry point.
+pub async fn sweep(state: Arc<AppState>) -> anyhow::Result<()> {
+    let summary = run_escalations(&state).await?;
+    info!(
+        skip_level = summary.skip_level,
+        finance = summary.finance,
+        "approval escalation sweep completed"
+    );
+    Ok(())
+}
+
+/// Reports escalated by one sweep, per level.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EscalationSummary {
+    pub skip_level: usize,
+    pub finance: usize,
+}
+
+/// Runs a single escalation sweep.
+pub async fn run_escalations(state: &AppState) -> anyhow::Result<EscalationSummary> {
+    let config = &state.config.escalations;
+    let today = Utc::now().date_naive();
+
+    let pending: Vec<PendingApproval> = sqlx::query_as(
+        r#"
+        SELECT pending.*,
+               EXISTS (
+                   SELECT 1 FROM approval_escalations x
+                   WHERE x.report_id = pending.report_id AND x.level = $3
+                     AND x.pending_since = pending.pending_since
+               ) AS skip_level_escalated,
+               EXISTS (
+                   SELECT 1 FROM approval_escalations x
+                   WHERE x.report_id = pending.report_id AND x.level = $4
+                     AND x.pending_since = pending.pending_since
+               ) AS finance_escalated
+        FROM (
+            SELECT r.id AS report_id, r.status, r.employee_id, e.manager_id AS approver_id,
+                   e.hr_identifier AS employee_hr_identifier, r.updated_at AS pending_since,
+                   r.total_amount_cents, r.currency
+            FROM expense_reports r
+            JOIN employees e ON e.id = r.employee_id
+            WHERE r.status = $1 AND e.manager_id IS NOT NULL
+            UNION ALL
+            SELECT r.id AS report_id, r.status, r.employee_id,
+                   r.exception_approver_id AS approver_id,
+                   e.hr_identifier AS employee_hr_identifier, r.updated_at AS pending_since,
+                   r.total_amount_cents, r.currency
+            FROM expense_reports r
+            JOIN employees e ON e.id = r.employee_id
+            WHERE r.status = $2 AND r.exception_approver_id IS NOT NULL
+        ) pending
+        ORDER BY pending.pending_since ASC
+        "#,
+    )
+    .bind(ReportStatus::Submitted)
+    .bind(ReportStatus::ExceptionReview)
+    .bind(EscalationLevel::SkipLevel.as_str())
+    .bind(EscalationLevel::Finance.as_str())
+    .fetch_all(&state.pool)
+    .await?;
+
+    let due = due_escalations(&pending, config, today);
+    if due.is_empty() {
+        return Ok(EscalationSummary::default());
+    }
+
+    let approvers: Vec<Uuid> = due
+        .iter()
+        .filter(|(_, level)| *level == EscalationLevel::SkipLevel)
+        .map(|(approval, _)| approval.approver_id)
+        .collect();
+    let chains = manager_chains(state, &approvers).await?;
+    let finance_users: Vec<Uuid> =
+        sqlx::query_scalar("SELECT id FROM employees WHERE role::text = $1")
+            .bind(Role::Finance.as_str())
+            .fetch_all(&state.pool)
+            .await?;
+
+    let mut summary = EscalationSummary::default();
+    let mut skip_level: BTreeMap<Uuid, Vec<EscalationLine>> = BTreeMap::new();
+    let mut finance: Vec<EscalationLine> = Vec::new();
+    let mut tx = state.pool.begin().await?;
+    for (approval, level) in due {
+        let escalated_to = match level {
+            EscalationLevel::SkipLevel => {
+                let chain = chains
+                    .get(&approval.approver_id)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                match escalation_target(chain, approval.employee_id, approval.approver_id) {
+                    Some(target) => Some(target),
+                    // Nobody above the approver; the finance step still applies.
+                    None => continue,
+                }
+            }
+            EscalationLevel::Finance => None,
+        };
+        let inserted = sqlx::query(
+            "INSERT INTO approval_escalations
+                (id, report_id, level, approver_id, escalated_to, pending_since, created_at)
+             VALUES ($1,$2,$3,$4,$5,$6,$7)
+             ON CONFLICT (report_id, level, pending_since) DO NOTHING",
+        )
+        .bind(Uuid::new_v4())
+        .bind(approval.report_id)
+        .bind(level.as_str())
+        .bind(approval.approver_id)
+        .bind(escalated_to)
+        .bind(approval.pending_since)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        if inserted == 0 {
+            continue;
+        }
+        let line = EscalationLine::new(approval, today);
+        match escalated_to {
+            Some(target) => {
+                summary.skip_level += 1;
+                skip_level.entry(target).or_default().push(line);
+            }
+            None => {
+                summary.finance += 1;
+                finance.push(line);
+            }
+        }
+    }
+
+    for (recipient, reports) in skip_level {
+        let payload = serde_json::json!({
+            "business_days": config.skip_level_business_days,
+            "reports": reports,
+        });
+        notifications::enqueue(&mut *tx, recipient, APPROVAL_ESCALATION_KIND, payload)
+            .await
+            .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+    }
+    if !finance.is_empty() {
+        let payload = serde_json::json!({
+            "business_days": config.finance_business_days,
+            "reports": finance,
+        });
+        for recipient in finance_users {
+            notifications::enqueue(
+                &mut *tx,
+                recipient,
+                FINANCE_ESCALATION_KIND,
+                payload.clone(),
+            )
+            .await
+            .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+        }
+    }
+    tx.commit().await?;
+
+    Ok(summary)
+}
+
+/// Each approver's managers, nearest first, from the cached organization
+/// chart; a broken reporting line ends the approver's chain where it breaks.
+async fn manager_chains(
+    state: &AppState,
+    approvers: &[Uuid],
+) -> anyhow::Result<HashMap<Uuid, Vec<Uuid>>> {
+    let mut chains: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for &approver in approvers {
+        if chains.contains_key(&approver) {
+            continue;
+        }
+        let chain = management_chain::resolve(state, approver)
+            .await
+            .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+        let managers = chain
+            .map(|chain| chain.manager_ids().collect())
+            .unwrap_or_default();
+        chains.insert(approver, managers);
+    }
+    Ok(chains)
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct PendingApproval {
+    report_id: Uuid,
+    status: ReportStatus,
+    employee_id: Uuid,
+    approver_id: Uuid,
+    employee_hr_identifier: String,
+    pending_since: DateTime<Utc>,
+    total_amount_cents: i64,
+    currency: String,
+    skip_level_escalated: bool,
+    finance_escalated: bool,
+}
+
+/// Single report listed in an escalation notification.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct EscalationLine {
+    pub report_id: Uuid,
+    pub status: ReportStatus,
+    pub approver_id: Uuid,
+    pub employee_hr_identifier: String,
+    pub pending_since: DateTime<Utc>,
+    pub business_days_pending: i64,
+    pub total_amount_cents: i64,
+    pub currency: String,
+}
+
+impl EscalationLine {
+    fn new(approval: &PendingApproval, today: NaiveDate) -> Self {
+        Self {
+            report_id: approval.report_id,
+            status: approval.status,
+            approver_id: approval.approver_id,
+            employee_hr_identifier: approval.employee_hr_identifier.clone(),
+            pending_since: approval.pending_since,
+            business_days_pending: business_days_between(
+                approval.pending_since.date_naive(),
+                today,
+            ),
+            total_amount_cents: approval.total_amount_cents,
+            currency: approval.currency.clone(),
+        }
+    }
+}
+
+/// Escalation steps due as of `today` that have not been taken yet.
+fn due_escalations<'a>(
+    pending: &'a [PendingApproval],
+    config: &EscalationConfig,
+    today: NaiveDate,
+) -> Vec<(&'a PendingApproval, EscalationLevel)> {
+    let mut due = Vec::new();
+    for approval in pending {
+        let waited = business_days_between(approval.pending_since.date_naive(), today);
+        if !approval.skip_level_escalated && waited >= i64::from(config.skip_level_business_days) {
+            due.push((approval, EscalationLevel::SkipLevel));
+        }
+        if !approval.finance_escalated && waited >= i64::from(config.finance_business_days) {
+            due.push((approval, EscalationLevel::Finance));
+        }
+    }
+    due
+}
+
+/// The nearest manager in `chain` (the approver's managers, nearest first)
+/// who is neither the submitter nor the approver.
+fn escalation_target(chain: &[Uuid], employee_id: Uuid, approver_id: Uuid) -> Option<Uuid> {
+    chain
+        .iter()
+        .copied()
+        .find(|manager| *manager != employee_id && *manager != approver_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn pending(since: NaiveDate) -> PendingApproval {
+        PendingApproval {
+            report_id: Uuid::new_v4(),
+            status: ReportStatus::Submitted,
+            employee_id: Uuid::new_v4(),
+            approver_id: Uuid::new_v4(),
+            employee_hr_identifier: "EMP3101".to_string(),
+            pending_since: Utc.from_utc_datetime(&since.and_hms_opt(9, 0, 0).unwrap()),
+            total_amount_cents: 12_500,
+            currency: "USD".to_string(),
+            skip_level_escalated: false,
+            finance_escalated: false,
+        }
+    }
+
+    #[test]
+    fn due_escalations_step_from_skip_level_to_finance() {
+        let config = EscalationConfig {
+            enabled: true,
+            skip_level_business_days: 5,
+            finance_business_days: 10,
+        };
+        let today = date(2024, 5, 24);
+
+        // Four, six, and fourteen business days before Friday 2024-05-24.
+        let fresh = pending(date(2024, 5, 20));
+        let stalled = pending(date(2024, 5, 16));
+        let mut abandoned = pending(date(2024, 5, 6));
+        abandoned.skip_level_escalated = true;
+        let pending = [fresh, stalled, abandoned];
+
+        let due: Vec<(Uuid, EscalationLevel)> = due_escalations(&pending, &config, today)
+            .into_iter()
+            .map(|(approval, level)| (approval.report_id, level))
+            .collect();
+
+        assert_eq!(
+            due,
+            vec![
+                (pending[1].report_id, EscalationLevel::SkipLevel),
+                (pending[2].report_id, EscalationLevel::Finance),
+            ]
+        );
+    }
+
+    #[test]
+    fn escalation_target_skips_the_submitter_and_approver() {
+        let (employee, approver, director, vp) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+
+        assert_eq!(
+            escalation_target(&[director, vp], employee, approver),
+            Some(director)
+        );
+        // An exception approver who reports to the submitter.
+        assert_eq!(
+            escalation_target(&[employee, director], employee, approver),
+            Some(director)
+        );
+        // A reporting cycle back to the approver.
+        assert_eq!(escalation_target(&[approver], employee, approver), None);
+        assert_eq!(escalation_target(&[], employee, approver), None);
+    }
+}
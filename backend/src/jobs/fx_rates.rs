@@ -0,0 +1,120 @@
+//! Nightly exchange rate refresh.
+//!
+//! Pulls the latest rates from the provider configured in `fx` into
+//! `fx_rates`. A failed download alerts every finance user with an
+//! `fx_refresh_failed` notification; whether or not it succeeded, the job then
+//! checks the newest stored rate date and sends `fx_rates_stale` once it is
+//! more than `fx.stale_after_days` old, since conversions stop using rates
+//! past that age.
+
+use std::sync::Arc;
+
+use chrono::{NaiveDate, Utc};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    domain::models::Role,
+    infrastructure::{fx, state::AppState},
+    services::{fx as fx_rates, notifications},
+};
+
+/// Notification kind sent to finance when the provider could not be read.
+pub const FX_REFRESH_FAILED_KIND: &str = "fx_refresh_failed";
+
+/// Notification kind sent to finance when the newest rates are too old.
+pub const FX_RATES_STALE_KIND: &str = "fx_rates_stale";
+
+/// Scheduler entry point.
+pub async fn sweep(state: Arc<AppState>) -> anyhow::Result<()> {
+    let config = &state.config.fx;
+    let refreshed = match fx::fetch_rates(config).await {
+        Ok(sheet) => {
+            let stored = fx_rates::store(&state.pool, &sheet, config.provider)
+                .await
+                .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+            info!(
+                provider = config.provider.as_str(),
+                base = %sheet.base,
+                rate_date = %sheet.rate_date,
+                rates = stored,
+                "fx rates refreshed"
+            );
+            Ok(())
+        }
+        Err(err) => {
+            warn!(provider = config.provider.as_str(), error = ?err, "fx rate refresh failed");
+            alert_finance(
+                &state,
+                FX_REFRESH_FAILED_KIND,
+                serde_json::json!({
+                    "provider": config.provider.as_str(),
+                    "error": format!("{err:#}"),
+                }),
+            )
+            .await?;
+            Err(err)
+        }
+    };
+
+    let latest = fx_rates::latest_rate_date(&state.pool)
+        .await
+        .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+    let today = Utc::now().date_naive();
+    if is_stale(latest, today, config.stale_after_days) {
+        warn!(latest_rate_date = ?latest, "fx rates are stale");
+        alert_finance(
+            &state,
+            FX_RATES_STALE_KIND,
+            serde_json::json!({
+                "latest_rate_date": latest,
+                "stale_after_days": config.stale_after_days,
+            }),
+        )
+        .await?;
+    }
+
+    refreshed
+}
+
+/// Whether rates last dated `latest` are too old to use on `today`.
+fn is_stale(latest: Option<NaiveDate>, today: NaiveDate, stale_after_days: u32) -> bool {
+    latest.is_none_or(|latest| (today - latest).num_days() > i64::from(stale_after_days))
+}
+
+async fn alert_finance(
+    state: &AppState,
+    kind: &str,
+    payload: serde_json::Value,
+) -> anyhow::Result<()> {
+    let finance_users: Vec<Uuid> =
+        sqlx::query_scalar("SELECT id FROM employees WHERE role::text = $1")
+            .bind(Role::Finance.as_str())
+            .fetch_all(&state.pool)
+            .await?;
+    let mut tx = state.pool.begin().await?;
+    for recipient in finance_users {
+        notifications::enqueue(&mut *tx, recipient, kind, payload.clone())
+            .await
+            .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 5, day).unwrap()
+    }
+
+    #[test]
+    fn rates_go_stale_after_the_configured_days() {
+        // Friday's rates still cover the following Tuesday with four days.
+        assert!(!is_stale(Some(date(10)), date(14), 4));
+        assert!(is_stale(Some(date(10)), date(15), 4));
+        assert!(is_stale(None, date(15), 4));
+    }
+}
@@ -0,0 +1,80 @@
+//! HR roster synchronization worker.
+//!
+//! Pulls the roster from the HR system configured in `hr_sync` and reconciles
+//! the organization's employees with it; see `services::hr_sync`. When the
+//! sync changed anything, rejected a worker, or found terminated employees,
+//! the organization's administrators get the diff as an `hr_sync_report`
+//! notification. A rejected worker fails the run, since nothing was saved.
+
+use std::sync::Arc;
+
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{Role, DEFAULT_ORG_ID},
+    infrastructure::{hr, state::AppState},
+    services::{hr_sync, management_chain, notifications},
+};
+
+/// Notification kind carrying a sync's differences to administrators.
+pub const HR_SYNC_REPORT_KIND: &str = "hr_sync_report";
+
+/// Scheduler entry point.
+pub async fn sweep(state: Arc<AppState>) -> anyhow::Result<()> {
+    let config = &state.config.hr_sync;
+    let org_id = config.org_id.unwrap_or(DEFAULT_ORG_ID);
+    let workers = hr::fetch_roster(config).await?;
+    let report = hr_sync::sync(&state.pool, org_id, &workers, config.dry_run)
+        .await
+        .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+    if report.roster.applied {
+        management_chain::invalidate(&state);
+    }
+    info!(
+        provider = config.provider.as_str(),
+        %org_id,
+        dry_run = report.dry_run,
+        applied = report.roster.applied,
+        created = report.roster.created,
+        updated = report.roster.updated,
+        unchanged = report.roster.unchanged,
+        failed = report.roster.failed,
+        departments_created = report.departments_created,
+        terminated = report.terminated.len(),
+        "hr roster synchronized"
+    );
+    if !report.has_changes() {
+        return Ok(());
+    }
+
+    let admins: Vec<Uuid> =
+        sqlx::query_scalar("SELECT id FROM employees WHERE role::text = $1 AND org_id = $2")
+            .bind(Role::Admin.as_str())
+            .bind(org_id)
+            .fetch_all(&state.pool)
+            .await?;
+    let payload = serde_json::to_value(&report)?;
+    let mut tx = state.pool.begin().await?;
+    for admin in admins {
+        notifications::enqueue(&mut *tx, admin, HR_SYNC_REPORT_KIND, payload.clone())
+            .await
+            .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+    }
+    tx.commit().await?;
+
+    if report.roster.failed > 0 {
+        for row in report.roster.rows.iter().filter(|row| row.error.is_some()) {
+            warn!(
+                hr_identifier = %row.hr_identifier,
+                error = row.error.as_deref().unwrap_or_default(),
+                "hr worker rejected"
+            );
+        }
+        anyhow::bail!(
+            "HR sync saved nothing: {} workers were rejected",
+            report.roster.failed
+        );
+    }
+    Ok(())
+}
@@ -1,14 +1,234 @@
 use std::sync::Arc;
-use tokio::task::JoinHandle;
-use tracing::info;
-
-use crate::infrastructure::state::AppState;
-
-pub fn spawn_digest_worker(_state: Arc<AppState>) -> JoinHandle<()> {
-    tokio::spawn(async move {
-        loop {
-            info!("digest worker stub running");
-            tokio::time::sleep(std::time::Duration::from_secs(60 * 60 * 24)).await;
-        }
-    })
+
+use crate::infrastructure::{config::Config, state::AppState};
+
+pub mod archive;
+pub mod audit_verify;
+pub mod auto_finalize;
+pub mod chat;
+pub mod digest;
+pub mod email;
+pub mod escalations;
+pub mod fx_rates;
+pub mod hr_sync;
+pub mod outbox;
+pub mod purge;
+pub mod reconciliation;
+pub mod reminders;
+pub mod retention;
+pub mod scheduler;
+pub mod stale_drafts;
+pub mod webhooks;
+
+pub use scheduler::{Scheduler, SchedulerHandle, Trigger};
+
+/// Every background job, in registration order.
+pub const JOB_NAMES: [&str; 16] = [
+    "digest",
+    "reminders",
+    "escalations",
+    "auto_finalize",
+    "reconciliation",
+    "outbox",
+    "email",
+    "chat",
+    "webhooks",
+    "fx_rates",
+    "purge",
+    "stale_drafts",
+    "retention",
+    "archive",
+    "audit_verify",
+    "hr_sync",
+];
+
+/// Weekdays at 08:00 UTC, unless `jobs.escalations_schedule` overrides it.
+const DEFAULT_ESCALATION_SCHEDULE: &str = "0 0 8 * * Mon-Fri";
+
+/// Daily at 22:00 UTC, after the ECB publishes, unless
+/// `jobs.fx_rates_schedule` overrides it.
+const DEFAULT_FX_RATES_SCHEDULE: &str = "0 0 22 * * *";
+
+/// Daily at 03:30 UTC, unless `jobs.purge_schedule` overrides it.
+const DEFAULT_PURGE_SCHEDULE: &str = "0 30 3 * * *";
+
+/// Sundays at 04:00 UTC, unless `jobs.retention_schedule` overrides it.
+const DEFAULT_RETENTION_SCHEDULE: &str = "0 0 4 * * Sun";
+
+/// Daily at 02:00 UTC, unless `jobs.archive_schedule` overrides it.
+const DEFAULT_ARCHIVE_SCHEDULE: &str = "0 0 2 * * *";
+
+/// Daily at 05:00 UTC, after the purge and retention jobs, unless
+/// `jobs.audit_verify_schedule` overrides it.
+const DEFAULT_AUDIT_VERIFY_SCHEDULE: &str = "0 0 5 * * *";
+
+/// Daily at 01:00 UTC, before the working day's approvals, unless
+/// `jobs.hr_sync_schedule` overrides it.
+const DEFAULT_HR_SYNC_SCHEDULE: &str = "0 0 1 * * *";
+
+/// Weekdays at 09:00 UTC, unless `jobs.stale_drafts_schedule` overrides it.
+const DEFAULT_STALE_DRAFT_SCHEDULE: &str = "0 0 9 * * Mon-Fri";
+
+/// Registers every enabled background job with its configured trigger.
+/// Fails on a malformed cron expression so bad configuration stops startup.
+pub fn build_scheduler(state: Arc<AppState>) -> anyhow::Result<Scheduler> {
+    let config = Arc::clone(&state.config);
+    let schedules = &config.jobs;
+    let mut scheduler = Scheduler::new(state);
+
+    if is_enabled(&config, "digest") {
+        let trigger = trigger(&schedules.digest_schedule, || {
+            Ok(Trigger::Every(config.digest_interval()))
+        })?;
+        scheduler.register("digest", trigger, digest::sweep);
+    }
+    if is_enabled(&config, "reminders") {
+        let trigger = trigger(&schedules.reminders_schedule, || {
+            Ok(Trigger::Every(config.reminder_interval()))
+        })?;
+        scheduler.register("reminders", trigger, reminders::sweep);
+    }
+    if is_enabled(&config, "escalations") {
+        let trigger = trigger(&schedules.escalations_schedule, || {
+            Trigger::cron(DEFAULT_ESCALATION_SCHEDULE)
+        })?;
+        scheduler.register("escalations", trigger, escalations::sweep);
+    }
+    if is_enabled(&config, "auto_finalize") {
+        let trigger = trigger(&schedules.auto_finalize_schedule, || {
+            Trigger::cron(&auto_finalize::default_schedule(&config.auto_finalize))
+        })?;
+        scheduler.register("auto_finalize", trigger, auto_finalize::sweep);
+    }
+    if is_enabled(&config, "reconciliation") {
+        let trigger = trigger(&schedules.reconciliation_schedule, || {
+            Ok(Trigger::Every(config.reconciliation_interval()))
+        })?;
+        scheduler.register("reconciliation", trigger, reconciliation::sweep);
+    }
+    if is_enabled(&config, "outbox") {
+        let trigger = trigger(&schedules.outbox_schedule, || {
+            Ok(Trigger::Every(config.outbox_interval()))
+        })?;
+        scheduler.register("outbox", trigger, outbox::sweep);
+    }
+    if is_enabled(&config, "email") {
+        let trigger = trigger(&schedules.email_schedule, || {
+            Ok(Trigger::Every(config.email_interval()))
+        })?;
+        scheduler.register("email", trigger, email::sweep);
+    }
+    if is_enabled(&config, "chat") {
+        let trigger = trigger(&schedules.chat_schedule, || {
+            Ok(Trigger::Every(config.chat_interval()))
+        })?;
+        scheduler.register("chat", trigger, chat::sweep);
+    }
+    if is_enabled(&config, "webhooks") {
+        let trigger = trigger(&schedules.webhooks_schedule, || {
+            Ok(Trigger::Every(config.webhooks_interval()))
+        })?;
+        scheduler.register("webhooks", trigger, webhooks::sweep);
+    }
+    if is_enabled(&config, "fx_rates") {
+        let trigger = trigger(&schedules.fx_rates_schedule, || {
+            Trigger::cron(DEFAULT_FX_RATES_SCHEDULE)
+        })?;
+        scheduler.register("fx_rates", trigger, fx_rates::sweep);
+    }
+    if is_enabled(&config, "purge") {
+        let trigger = trigger(&schedules.purge_schedule, || {
+            Trigger::cron(DEFAULT_PURGE_SCHEDULE)
+        })?;
+        scheduler.register("purge", trigger, purge::sweep);
+    }
+    if is_enabled(&config, "stale_drafts") {
+        let trigger = trigger(&schedules.stale_drafts_schedule, || {
+            Trigger::cron(DEFAULT_STALE_DRAFT_SCHEDULE)
+        })?;
+        scheduler.register("stale_drafts", trigger, stale_drafts::sweep);
+    }
+    if is_enabled(&config, "retention") {
+        let trigger = trigger(&schedules.retention_schedule, || {
+            Trigger::cron(DEFAULT_RETENTION_SCHEDULE)
+        })?;
+        scheduler.register("retention", trigger, retention::sweep);
+    }
+    if is_enabled(&config, "archive") {
+        let trigger = trigger(&schedules.archive_schedule, || {
+            Trigger::cron(DEFAULT_ARCHIVE_SCHEDULE)
+        })?;
+        scheduler.register("archive", trigger, archive::sweep);
+    }
+    if is_enabled(&config, "audit_verify") {
+        let trigger = trigger(&schedules.audit_verify_schedule, || {
+            Trigger::cron(DEFAULT_AUDIT_VERIFY_SCHEDULE)
+        })?;
+        scheduler.register("audit_verify", trigger, audit_verify::sweep);
+    }
+    if is_enabled(&config, "hr_sync") {
+        let trigger = trigger(&schedules.hr_sync_schedule, || {
+            Trigger::cron(DEFAULT_HR_SYNC_SCHEDULE)
+        })?;
+        scheduler.register("hr_sync", trigger, hr_sync::sweep);
+    }
+
+    Ok(scheduler)
+}
+
+/// Whether the scheduler runs the job named `name`, per its `ENABLED` flag.
+pub fn is_enabled(config: &Config, name: &str) -> bool {
+    match name {
+        "digest" => config.digest.enabled,
+        "reminders" => config.reminders.enabled,
+        "escalations" => config.escalations.enabled,
+        "auto_finalize" => config.auto_finalize.enabled,
+        "reconciliation" => config.reconciliation.enabled,
+        "outbox" => config.outbox.enabled,
+        "email" => config.email.enabled,
+        "chat" => config.chat.enabled,
+        "webhooks" => config.webhooks.enabled,
+        "fx_rates" => config.fx.enabled,
+        "purge" => config.purge.enabled,
+        "stale_drafts" => config.stale_drafts.enabled,
+        "retention" => config.retention.enabled,
+        "archive" => config.archive.enabled,
+        "audit_verify" => config.audit_verify.enabled,
+        "hr_sync" => config.hr_sync.enabled,
+        _ => false,
+    }
+}
+
+/// Runs the job named `name` once, whether or not it is enabled.
+pub async fn run(name: &str, state: Arc<AppState>) -> anyhow::Result<()> {
+    match name {
+        "digest" => digest::sweep(state).await,
+        "reminders" => reminders::sweep(state).await,
+        "escalations" => escalations::sweep(state).await,
+        "auto_finalize" => auto_finalize::sweep(state).await,
+        "reconciliation" => reconciliation::sweep(state).await,
+        "outbox" => outbox::sweep(state).await,
+        "email" => email::sweep(state).await,
+        "chat" => chat::sweep(state).await,
+        "webhooks" => webhooks::sweep(state).await,
+        "fx_rates" => fx_rates::sweep(state).await,
+        "purge" => purge::sweep(state).await,
+        "stale_drafts" => stale_drafts::sweep(state).await,
+        "retention" => retention::sweep(state).await,
+        "archive" => archive::sweep(state).await,
+        "audit_verify" => audit_verify::sweep(state).await,
+        "hr_sync" => hr_sync::sweep(state).await,
+        other => anyhow::bail!("unknown job {other}"),
+    }
+}
+
+/// The configured cron expression, or `default` when none is set.
+fn trigger(
+    expression: &Option<String>,
+    default: impl FnOnce() -> anyhow::Result<Trigger>,
+) -> anyhow::Result<Trigger> {
+    match expression.as_deref().map(str::trim) {
+        Some(expression) if !expression.is_empty() => Trigger::cron(expression),
+        _ => default(),
+    }
 }
@@ -0,0 +1,27 @@
+//! Outbox delivery worker.
+//!
+//! Drains `outbox_events` that were committed but not delivered, either
+//! because the process stopped before the request could deliver them or
+//! because an earlier delivery failed and its backoff has run out. See
+//! `services::outbox` for the delivery guarantees.
+
+use std::sync::Arc;
+
+use tracing::info;
+
+use crate::{infrastructure::state::AppState, services::outbox};
+
+/// Scheduler entry point.
+pub async fn sweep(state: Arc<AppState>) -> anyhow::Result<()> {
+    let summary = outbox::drain(&state)
+        .await
+        .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+    if summary.delivered > 0 || summary.failed > 0 {
+        info!(
+            delivered = summary.delivered,
+            failed = summary.failed,
+            "outbox drain completed"
+        );
+    }
+    Ok(())
+}
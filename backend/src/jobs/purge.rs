@@ -0,0 +1,89 @@
+//! Retention purge worker.
+//!
+//! Deletes rows that only matter for a short while: notifications that have
+//! left the `pending` queue, approval link tokens that were used or have
+//! expired, finished background job runs, and finished webhook deliveries.
+//! All are kept for `purge.retention_days` after they stop being actionable
+//! so recent deliveries, link clicks, and job failures can still be traced.
+//! Idempotency keys go once they expire, after `KEY_TTL_HOURS`.
+
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use tracing::info;
+
+use crate::{
+    infrastructure::state::AppState,
+    services::idempotency::{self, KEY_TTL_HOURS},
+};
+
+/// Rows removed by one purge.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PurgeSummary {
+    pub notifications: u64,
+    pub approval_tokens: u64,
+    pub job_runs: u64,
+    pub webhook_deliveries: u64,
+    pub idempotency_keys: u64,
+}
+
+/// Scheduler entry point.
+pub async fn sweep(state: Arc<AppState>) -> anyhow::Result<()> {
+    let summary = run_purge(&state).await?;
+    info!(
+        notifications = summary.notifications,
+        approval_tokens = summary.approval_tokens,
+        job_runs = summary.job_runs,
+        webhook_deliveries = summary.webhook_deliveries,
+        idempotency_keys = summary.idempotency_keys,
+        "retention purge completed"
+    );
+    Ok(())
+}
+
+/// Runs a single purge and returns how many rows were deleted.
+pub async fn run_purge(state: &AppState) -> anyhow::Result<PurgeSummary> {
+    let cutoff = Utc::now() - Duration::days(i64::from(state.config.purge.retention_days));
+
+    let mut tx = state.pool.begin().await?;
+    let notifications =
+        sqlx::query("DELETE FROM notifications WHERE status <> 'pending' AND created_at < $1")
+            .bind(cutoff)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+    let approval_tokens = sqlx::query(
+        "DELETE FROM approval_action_tokens
+         WHERE COALESCE(used_at, expires_at) < $1",
+    )
+    .bind(cutoff)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    let job_runs =
+        sqlx::query("DELETE FROM job_runs WHERE status <> 'running' AND finished_at < $1")
+            .bind(cutoff)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+    let webhook_deliveries =
+        sqlx::query("DELETE FROM webhook_deliveries WHERE status <> 'pending' AND created_at < $1")
+            .bind(cutoff)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+    let idempotency_keys = idempotency::purge_expired(
+        &mut *tx,
+        Utc::now() - Duration::hours(i64::from(KEY_TTL_HOURS)),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(PurgeSummary {
+        notifications,
+        approval_tokens,
+        job_runs,
+        webhook_deliveries,
+        idempotency_keys,
+    })
+}
@@ -0,0 +1,140 @@
+//! NetSuite posting status reconciliation worker.
+//!
+//! Periodically asks NetSuite about each `exported` batch and records the
+//! answer: entries that posted move the batch to `posted`, entries a NetSuite
+//! approver rejected (or that were deleted) move it to `rejected` and release
+//! its reports back to `manager_approved` so they can be corrected and
+//! finalized again. Every rejection found in a sweep is reported to finance
+//! users in one queued notification. Entries still awaiting approval are left
+//! `exported` and checked again on a later sweep. The status transitions
+//! live in `services::netsuite_status`, shared with the NetSuite callback.
+
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use serde::Serialize;
+use sqlx::{postgres::PgRow, Row};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    infrastructure::{
+        config::NetSuiteExportMode,
+        netsuite::{self, PostingStatus},
+        state::AppState,
+    },
+    services::netsuite_status::{self, RejectedBatch},
+};
+
+/// Upper bound on batches checked per sweep, keeping each sweep well inside
+/// NetSuite's concurrency and request limits.
+const MAX_BATCHES_PER_SWEEP: i64 = 200;
+
+/// Scheduler entry point.
+pub async fn sweep(state: Arc<AppState>) -> anyhow::Result<()> {
+    let summary = run_reconciliation(&state).await?;
+    info!(
+        checked = summary.checked,
+        posted = summary.posted,
+        rejected = summary.rejected.len(),
+        errors = summary.errors,
+        "netsuite reconciliation sweep completed"
+    );
+    Ok(())
+}
+
+/// Outcome of one sweep; `rejected` is queued verbatim as the alert payload.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReconciliationSummary {
+    pub checked: usize,
+    pub posted: usize,
+    pub rejected: Vec<RejectedBatch>,
+    pub errors: usize,
+}
+
+/// Runs a single reconciliation sweep.
+pub async fn run_reconciliation(state: &AppState) -> anyhow::Result<ReconciliationSummary> {
+    let config = &state.config;
+    // Only journal entries are polled; vendor bills report back through the
+    // NetSuite callback.
+    if !netsuite::is_configured(&config.netsuite)
+        || config.netsuite.export_mode != NetSuiteExportMode::JournalEntry
+    {
+        return Ok(ReconciliationSummary::default());
+    }
+
+    // Simulated exports carry a non-numeric reference and are never polled;
+    // reversal batches are settled through their original batch.
+    let exported_since =
+        Utc::now() - Duration::days(i64::from(config.reconciliation.lookback_days));
+    let candidates: Vec<ExportedBatch> = sqlx::query(
+        "SELECT id, batch_reference, netsuite_response->>'reference' AS netsuite_id
+         FROM netsuite_batches
+         WHERE status = 'exported'
+           AND reverses_batch_id IS NULL
+           AND exported_at >= $1
+           AND netsuite_response->>'reference' ~ '^[0-9]+$'
+         ORDER BY last_reconciled_at NULLS FIRST, exported_at
+         LIMIT $2",
+    )
+    .bind(exported_since)
+    .bind(MAX_BATCHES_PER_SWEEP)
+    .map(|row: PgRow| ExportedBatch {
+        id: row.get("id"),
+        batch_reference: row.get("batch_reference"),
+        netsuite_id: row.get("netsuite_id"),
+    })
+    .fetch_all(&state.pool)
+    .await?;
+
+    let mut summary = ReconciliationSummary::default();
+    for batch in candidates {
+        // Unchecked batches sort first on the next sweep.
+        if state.shutdown.is_cancelled() {
+            break;
+        }
+        summary.checked += 1;
+        let status = match netsuite::fetch_posting_status(&config.netsuite, &batch.netsuite_id)
+            .await
+        {
+            Ok(status) => status,
+            Err(err) => {
+                warn!(batch = %batch.batch_reference, error = ?err, "netsuite status lookup failed");
+                summary.errors += 1;
+                continue;
+            }
+        };
+        match status {
+            PostingStatus::Accepted => netsuite_status::touch(&state.pool, batch.id).await?,
+            PostingStatus::Posted => {
+                if netsuite_status::mark_posted(&state.pool, batch.id).await? {
+                    summary.posted += 1;
+                }
+            }
+            PostingStatus::Rejected => {
+                if let Some(released_report_ids) =
+                    netsuite_status::reject(&state.pool, batch.id).await?
+                {
+                    summary.rejected.push(RejectedBatch {
+                        batch_id: batch.id,
+                        batch_reference: batch.batch_reference,
+                        netsuite_id: batch.netsuite_id,
+                        released_report_ids,
+                    });
+                }
+            }
+        }
+    }
+
+    if !summary.rejected.is_empty() {
+        netsuite_status::alert_finance(&state.pool, &summary.rejected).await?;
+    }
+    Ok(summary)
+}
+
+#[derive(Debug, Clone)]
+struct ExportedBatch {
+    id: Uuid,
+    batch_reference: String,
+    netsuite_id: String,
+}
@@ -0,0 +1,51 @@
+//! Data retention planning worker.
+//!
+//! Records a dry-run retention plan on schedule and, when the plan would touch
+//! anything, asks every administrator to review it. The plan is only applied
+//! through `POST /retention/runs/:id/execute`; see `services::retention`.
+
+use std::sync::Arc;
+
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    domain::models::Role,
+    infrastructure::state::AppState,
+    services::{notifications, retention},
+};
+
+/// Notification kind asking administrators to review a retention plan.
+pub const RETENTION_PLAN_KIND: &str = "retention_plan";
+
+/// Scheduler entry point.
+pub async fn sweep(state: Arc<AppState>) -> anyhow::Result<()> {
+    let run = retention::plan(&state, None)
+        .await
+        .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+    info!(
+        run_id = %run.id,
+        reports_anonymized = run.reports_anonymized,
+        reports_deleted = run.reports_deleted,
+        receipts_removed = run.receipts_removed,
+        audit_logs_removed = run.audit_logs_removed,
+        "retention plan recorded"
+    );
+    if run.reports_anonymized + run.reports_deleted + run.audit_logs_removed == 0 {
+        return Ok(());
+    }
+
+    let admins: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM employees WHERE role::text = $1")
+        .bind(Role::Admin.as_str())
+        .fetch_all(&state.pool)
+        .await?;
+    let payload = serde_json::to_value(&run)?;
+    let mut tx = state.pool.begin().await?;
+    for admin in admins {
+        notifications::enqueue(&mut *tx, admin, RETENTION_PLAN_KIND, payload.clone())
+            .await
+            .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
@@ -0,0 +1,224 @@
+//! Scheduler running the background jobs.
+//!
+//! Each job is registered under a name with a `Trigger`: a cron expression
+//! (`sec min hour day-of-month month day-of-week`, evaluated in UTC) or a fixed
+//! interval. `Scheduler::start` spawns one task per job on
+//! `AppState::job_tasks`, each inside a `job` tracing span carrying the job
+//! name, and returns a handle whose `stop` cancels `AppState::shutdown`. Jobs
+//! waiting for their next run stop at once; a run in progress finishes, or
+//! stops at its next checkpoint, within the shutdown grace period.
+//! Every run is recorded in `job_runs`.
+
+use std::{future::Future, pin::Pin, str::FromStr, sync::Arc, time::Duration};
+
+use chrono::{DateTime, Utc};
+use tokio::time::Instant;
+use tracing::{info, info_span, warn, Instrument};
+
+use crate::{
+    infrastructure::state::AppState,
+    services::{
+        errors::ServiceError,
+        job_runs::{self, RunTrigger},
+    },
+};
+
+type JobFuture = Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send>>;
+type JobFn = Arc<dyn Fn(Arc<AppState>) -> JobFuture + Send + Sync>;
+
+/// When a job runs.
+#[derive(Debug, Clone)]
+pub enum Trigger {
+    /// At every time matching the cron schedule.
+    Cron(Box<cron::Schedule>),
+    /// Once at startup, then `Duration` after each run finishes.
+    Every(Duration),
+}
+
+impl Trigger {
+    /// Parses a six- or seven-field cron expression.
+    pub fn cron(expression: &str) -> anyhow::Result<Self> {
+        cron::Schedule::from_str(expression.trim())
+            .map(|schedule| Trigger::Cron(Box::new(schedule)))
+            .map_err(|err| anyhow::anyhow!("invalid cron expression {expression:?}: {err}"))
+    }
+
+    /// The run following one that finished at `now`, if the schedule has any
+    /// left.
+    pub fn next_after(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Trigger::Cron(schedule) => schedule.after(&now).next(),
+            Trigger::Every(interval) => chrono::Duration::from_std(*interval)
+                .ok()
+                .map(|interval| now + interval),
+        }
+    }
+
+    fn first_run(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Trigger::Cron(_) => self.next_after(now),
+            Trigger::Every(_) => Some(now),
+        }
+    }
+}
+
+struct Job {
+    name: &'static str,
+    trigger: Trigger,
+    run: JobFn,
+}
+
+/// Collects jobs before they are started.
+pub struct Scheduler {
+    state: Arc<AppState>,
+    jobs: Vec<Job>,
+}
+
+impl Scheduler {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self {
+            state,
+            jobs: Vec::new(),
+        }
+    }
+
+    /// Adds `job` under `name`, to run on `trigger` once started.
+    pub fn register<F, Fut>(&mut self, name: &'static str, trigger: Trigger, job: F)
+    where
+        F: Fn(Arc<AppState>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        self.jobs.push(Job {
+            name,
+            trigger,
+            run: Arc::new(move |state| Box::pin(job(state))),
+        });
+    }
+
+    /// Names of the registered jobs, in registration order.
+    pub fn job_names(&self) -> Vec<&'static str> {
+        self.jobs.iter().map(|job| job.name).collect()
+    }
+
+    /// Spawns every registered job.
+    pub fn start(self) -> SchedulerHandle {
+        for job in self.jobs {
+            let span = info_span!("job", name = job.name);
+            self.state
+                .job_tasks
+                .spawn(run_job(Arc::clone(&self.state), job).instrument(span));
+        }
+        SchedulerHandle { state: self.state }
+    }
+}
+
+/// Controls the jobs started by `Scheduler::start`.
+pub struct SchedulerHandle {
+    state: Arc<AppState>,
+}
+
+impl SchedulerHandle {
+    /// Signals shutdown and waits up to `grace` for every job run, scheduled
+    /// or manual, to finish. Returns `false` if some were still running.
+    pub async fn stop(self, grace: Duration) -> bool {
+        self.state.shutdown.cancel();
+        self.state.job_tasks.close();
+        match tokio::time::timeout(grace, self.state.job_tasks.wait()).await {
+            Ok(()) => true,
+            Err(_) => {
+                warn!(
+                    running = self.state.job_tasks.len(),
+                    "job runs still in progress after the shutdown grace period"
+                );
+                false
+            }
+        }
+    }
+}
+
+async fn run_job(state: Arc<AppState>, job: Job) {
+    let mut next = job.trigger.first_run(Utc::now());
+    while let Some(at) = next {
+        info!(next_run = %at, "job scheduled");
+        let wait = (at - Utc::now()).to_std().unwrap_or_default();
+        tokio::select! {
+            _ = tokio::time::sleep(wait) => {}
+            _ = state.shutdown.cancelled() => break,
+        }
+        run_once(&state, &job).await;
+        if state.shutdown.is_cancelled() {
+            break;
+        }
+        next = job.trigger.next_after(Utc::now());
+    }
+    info!("job stopped");
+}
+
+/// Runs `job` once and records the run in `job_runs`. A run is skipped while
+/// a manual run of the same job is still going; when the history cannot be
+/// written the job runs anyway.
+async fn run_once(state: &Arc<AppState>, job: &Job) {
+    let run_id = match job_runs::start(&state.pool, job.name, RunTrigger::Scheduled, None).await {
+        Ok(run) => Some(run.id),
+        Err(ServiceError::Conflict) => {
+            info!("job already running, skipping this run");
+            return;
+        }
+        Err(err) => {
+            warn!(error = %err, "could not record job run");
+            None
+        }
+    };
+    let started = Instant::now();
+    let outcome = (job.run)(Arc::clone(state)).await;
+    match &outcome {
+        Ok(()) => info!(elapsed_ms = started.elapsed().as_millis(), "job completed"),
+        Err(err) => warn!(error = ?err, "job failed"),
+    }
+    if let Some(run_id) = run_id {
+        if let Err(err) = job_runs::finish(&state.pool, run_id, &outcome).await {
+            warn!(error = %err, "could not record job run");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    #[test]
+    fn cron_triggers_run_at_the_next_matching_time() {
+        // 08:00 UTC on Mondays.
+        let trigger = Trigger::cron("0 0 8 * * Mon").unwrap();
+
+        // Wednesday 2024-05-08 → Monday 2024-05-13.
+        assert_eq!(
+            trigger.next_after(at(2024, 5, 8, 12, 0)),
+            Some(at(2024, 5, 13, 8, 0))
+        );
+        assert_eq!(
+            trigger.first_run(at(2024, 5, 13, 7, 59)),
+            Some(at(2024, 5, 13, 8, 0))
+        );
+    }
+
+    #[test]
+    fn interval_triggers_run_immediately_then_every_interval() {
+        let trigger = Trigger::Every(Duration::from_secs(60 * 60));
+        let now = at(2024, 5, 8, 12, 0);
+
+        assert_eq!(trigger.first_run(now), Some(now));
+        assert_eq!(trigger.next_after(now), Some(at(2024, 5, 8, 13, 0)));
+    }
+
+    #[test]
+    fn rejects_malformed_cron_expressions() {
+        assert!(Trigger::cron("every monday").is_err());
+        assert!(Trigger::cron("0 0 25 * * *").is_err());
+    }
+}
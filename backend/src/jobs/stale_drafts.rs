@@ -0,0 +1,203 @@
+//! Stale draft reminder worker.
+//!
+//! Finds draft reports that have not been edited for
+//! `stale_drafts.stale_after_days`, or whose reporting period has already
+//! ended, and queues one notification per owner asking them to submit or
+//! discard each draft, so finance is not left waiting on forgotten expenses
+//! when closing a period. A period ends at midnight in the owner's
+//! `timezone` preference (UTC when unset). An owner is reminded at most once
+//! per `stale_after_days`, however often the job runs.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    domain::models::ReportStatus, infrastructure::state::AppState, services::notifications,
+};
+
+/// Notification kind recorded on queued stale draft reminders.
+pub const STALE_DRAFT_KIND: &str = "stale_draft_reminder";
+
+/// Scheduler entry point.
+pub async fn sweep(state: Arc<AppState>) -> anyhow::Result<()> {
+    let sent = run_stale_drafts(&state).await?;
+    info!(reminders = sent, "stale draft sweep completed");
+    Ok(())
+}
+
+/// Runs a single sweep and returns the number of reminders queued.
+pub async fn run_stale_drafts(state: &AppState) -> anyhow::Result<usize> {
+    let stale_after_days = state.config.stale_drafts.stale_after_days;
+    let now = Utc::now();
+    let cutoff = now - Duration::days(i64::from(stale_after_days));
+
+    let drafts: Vec<StaleDraft> = sqlx::query_as(
+        r#"
+        SELECT r.employee_id, r.id AS report_id, r.reporting_period_start,
+               r.reporting_period_end, r.updated_at, r.total_amount_cents, r.currency,
+               ($3::timestamptz AT TIME ZONE COALESCE(e.timezone, 'UTC'))::date AS owner_today
+        FROM expense_reports r
+        JOIN employees e ON e.id = r.employee_id
+        WHERE r.status = $1
+          AND r.deleted_at IS NULL
+          AND (r.updated_at <= $2
+               OR r.reporting_period_end
+                  < ($3::timestamptz AT TIME ZONE COALESCE(e.timezone, 'UTC'))::date)
+          AND NOT EXISTS (
+              SELECT 1 FROM notifications n
+              WHERE n.recipient_id = r.employee_id AND n.kind = $4 AND n.created_at > $2
+          )
+        ORDER BY r.reporting_period_end, r.updated_at
+        "#,
+    )
+    .bind(ReportStatus::Draft)
+    .bind(cutoff)
+    .bind(now)
+    .bind(STALE_DRAFT_KIND)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let reminders = batch_by_owner(drafts, cutoff);
+    if reminders.is_empty() {
+        return Ok(0);
+    }
+
+    let mut tx = state.pool.begin().await?;
+    for (owner_id, reports) in &reminders {
+        let payload = serde_json::json!({
+            "stale_after_days": stale_after_days,
+            "reports": reports,
+        });
+        notifications::enqueue(&mut *tx, *owner_id, STALE_DRAFT_KIND, payload)
+            .await
+            .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+    }
+    tx.commit().await?;
+
+    Ok(reminders.len())
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct StaleDraft {
+    employee_id: Uuid,
+    report_id: Uuid,
+    reporting_period_start: NaiveDate,
+    reporting_period_end: NaiveDate,
+    updated_at: DateTime<Utc>,
+    total_amount_cents: i64,
+    currency: String,
+    /// The current date in the owner's time zone.
+    owner_today: NaiveDate,
+}
+
+/// Why a draft was included in a reminder.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StaleReason {
+    /// The reporting period ended before the owner's today.
+    PeriodEnded,
+    /// Not edited since the staleness cutoff.
+    Untouched,
+}
+
+/// Single draft listed in an owner's reminder.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct StaleDraftLine {
+    pub report_id: Uuid,
+    pub reporting_period_start: NaiveDate,
+    pub reporting_period_end: NaiveDate,
+    pub last_edited_at: DateTime<Utc>,
+    pub total_amount_cents: i64,
+    pub currency: String,
+    pub reason: StaleReason,
+}
+
+/// Groups stale drafts by owner. A draft whose period has ended is reported
+/// as such even if it was edited recently.
+fn batch_by_owner(
+    drafts: Vec<StaleDraft>,
+    cutoff: DateTime<Utc>,
+) -> BTreeMap<Uuid, Vec<StaleDraftLine>> {
+    let mut reminders: BTreeMap<Uuid, Vec<StaleDraftLine>> = BTreeMap::new();
+    for draft in drafts {
+        let reason = if draft.reporting_period_end < draft.owner_today {
+            StaleReason::PeriodEnded
+        } else if draft.updated_at <= cutoff {
+            StaleReason::Untouched
+        } else {
+            continue;
+        };
+        reminders
+            .entry(draft.employee_id)
+            .or_default()
+            .push(StaleDraftLine {
+                report_id: draft.report_id,
+                reporting_period_start: draft.reporting_period_start,
+                reporting_period_end: draft.reporting_period_end,
+                last_edited_at: draft.updated_at,
+                total_amount_cents: draft.total_amount_cents,
+                currency: draft.currency,
+                reason,
+            });
+    }
+    reminders
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn draft(owner: Uuid, period_end: NaiveDate, edited: NaiveDate) -> StaleDraft {
+        StaleDraft {
+            employee_id: owner,
+            report_id: Uuid::new_v4(),
+            reporting_period_start: date(2024, 5, 1),
+            reporting_period_end: period_end,
+            updated_at: Utc.from_utc_datetime(&edited.and_hms_opt(9, 0, 0).unwrap()),
+            total_amount_cents: 4_200,
+            currency: "USD".to_string(),
+            owner_today: date(2024, 6, 3),
+        }
+    }
+
+    #[test]
+    fn batch_by_owner_flags_ended_periods_and_untouched_drafts() {
+        let owner = Uuid::new_v4();
+        let other = Uuid::new_v4();
+        let behind = Uuid::new_v4();
+        let cutoff = Utc.with_ymd_and_hms(2024, 5, 20, 0, 0, 0).unwrap();
+
+        let reminders = batch_by_owner(
+            vec![
+                // Period ended last week, edited yesterday.
+                draft(owner, date(2024, 5, 31), date(2024, 6, 2)),
+                // Period still open, untouched for weeks.
+                draft(owner, date(2024, 6, 30), date(2024, 5, 10)),
+                // Period still open, edited recently.
+                draft(other, date(2024, 6, 30), date(2024, 6, 1)),
+                // Period ends today where the owner is, edited recently.
+                StaleDraft {
+                    owner_today: date(2024, 5, 31),
+                    ..draft(behind, date(2024, 5, 31), date(2024, 5, 30))
+                },
+            ],
+            cutoff,
+        );
+
+        assert_eq!(reminders.len(), 1);
+        let lines = &reminders[&owner];
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].reason, StaleReason::PeriodEnded);
+        assert_eq!(lines[1].reason, StaleReason::Untouched);
+    }
+}
@@ -0,0 +1,25 @@
+//! Webhook delivery worker.
+//!
+//! Posts the `pending` rows of `webhook_deliveries` to their subscriptions;
+//! see `services::webhooks::deliver_pending` for how failures are retried.
+
+use std::sync::Arc;
+
+use tracing::info;
+
+use crate::{infrastructure::state::AppState, services::webhooks};
+
+/// Scheduler entry point.
+pub async fn sweep(state: Arc<AppState>) -> anyhow::Result<()> {
+    let summary = webhooks::deliver_pending(&state)
+        .await
+        .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+    if summary.delivered > 0 || summary.failed > 0 {
+        info!(
+            delivered = summary.delivered,
+            failed = summary.failed,
+            "webhook delivery completed"
+        );
+    }
+    Ok(())
+}
@@ -1,5 +1,6 @@
 pub mod api;
 pub mod domain;
+pub mod grpc;
 pub mod infrastructure;
 pub mod jobs;
 pub mod services;
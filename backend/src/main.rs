@@ -1,26 +1,36 @@
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::sync::{atomic::Ordering, Arc};
 
 use axum::{serve, Extension};
 use dotenvy::dotenv;
 use expense_portal::{
-    api,
+    api, grpc,
     infrastructure::{config::Config, db, state::AppState, storage},
-    jobs, telemetry,
+    jobs,
+    services::job_runs,
+    telemetry,
 };
-use tokio::signal;
+use tokio::{signal, sync::watch};
 use tracing::{info, warn};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     dotenv().ok();
-    telemetry::init();
     let config = Arc::new(Config::from_env()?);
+    if config.grpc.enabled && config.grpc.auth_token.trim().is_empty() {
+        anyhow::bail!("EXPENSES__GRPC__AUTH_TOKEN must be set when gRPC is enabled");
+    }
+    telemetry::init(&config.telemetry)?;
     let pool = db::connect(&config.database).await?;
     db::run_migrations(&pool).await?;
     info!("database migrations completed successfully");
     let storage = storage::build_storage(&config.storage)?;
-    let state = Arc::new(AppState::new(Arc::clone(&config), pool, storage)?);
+    let mut state = AppState::new(Arc::clone(&config), pool, storage)?;
+    if let Some(read_pool) = db::connect_read(&config.database).await? {
+        info!("routing read-only queries to the database read replica");
+        state = state.with_read_pool(read_pool);
+    }
+    let state = Arc::new(state);
 
     let router = api::build_router(Arc::clone(&config)).layer(Extension(Arc::clone(&state)));
 
@@ -29,21 +39,62 @@ async fn main() -> anyhow::Result<()> {
 
     let listener = tokio::net::TcpListener::bind(&addr).await?;
 
-    let _digest_handle = jobs::spawn_digest_worker(Arc::clone(&state));
+    let interrupted = job_runs::fail_interrupted(&state.pool).await?;
+    if interrupted > 0 {
+        warn!(
+            runs = interrupted,
+            "marked job runs interrupted by the last shutdown as failed"
+        );
+    }
+    let scheduler = jobs::build_scheduler(Arc::clone(&state))?;
+    info!(jobs = ?scheduler.job_names(), "starting background jobs");
+    let scheduler = scheduler.start();
+    state.workers_started.store(true, Ordering::Release);
 
-    let server = serve(listener, router.into_make_service());
+    // One signal stops both servers.
+    let (stop, stopped) = watch::channel(false);
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        let _ = stop.send(true);
+    });
+    let until_stopped = |mut stopped: watch::Receiver<bool>| async move {
+        let _ = stopped.wait_for(|stop| *stop).await;
+    };
 
-    tokio::select! {
-        res = server => {
-            if let Err(err) = res {
-                warn!(error = ?err, "server exited with error");
-            }
-        }
-        _ = shutdown_signal() => {
-            info!("shutdown signal received");
+    let grpc_server = if config.grpc.enabled {
+        let grpc_addr: SocketAddr = config.grpc_bind_address().parse()?;
+        info!(addr = %grpc_addr, "starting expense portal grpc");
+        Some(tokio::spawn(grpc::serve(
+            Arc::clone(&state),
+            grpc_addr,
+            until_stopped(stopped.clone()),
+        )))
+    } else {
+        None
+    };
+
+    let server = serve(
+        listener,
+        router.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(until_stopped(stopped));
+    if let Err(err) = server.await {
+        warn!(error = ?err, "server exited with error");
+    }
+    if let Some(grpc_server) = grpc_server {
+        match grpc_server.await {
+            Ok(Err(err)) => warn!(error = ?err, "grpc server exited with error"),
+            Err(err) => warn!(error = ?err, "grpc server task failed"),
+            Ok(Ok(())) => {}
         }
     }
 
+    info!("stopping background jobs");
+    if scheduler.stop(config.shutdown_grace()).await {
+        info!("background jobs stopped");
+    }
+    telemetry::shutdown();
+
     Ok(())
 }
 
@@ -69,4 +120,5 @@ async fn shutdown_signal() {
         _ = ctrl_c => {},
         _ = terminate => {},
     }
+    info!("shutdown signal received");
 }
@@ -4,20 +4,45 @@
 //! `backend/src/api/rest/approvals.rs`, ensuring role-based transitions mirror
 //! the governance spelled out in `POLICY.md` §"Approvals and Reimbursement
 //! Process".
+//!
+//! Managers can also decide from their inbox: submission issues signed,
+//! single-use approve/request-changes links (see [`issue_action_links`]) that
+//! are redeemed through `POST /approvals/actions`.
 
 use std::sync::Arc;
 
-use chrono::Utc;
-use serde::Deserialize;
-use sqlx::{postgres::PgRow, Postgres, Row, Transaction};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Postgres, Transaction};
 use uuid::Uuid;
 
 use crate::{
     domain::models::{Approval, ApprovalStatus, ReportStatus, Role},
-    infrastructure::{auth::AuthenticatedUser, state::AppState},
+    infrastructure::{
+        auth::{
+            sign_action_token, verify_action_token, ActionClaims, AuthenticatedUser,
+            APPROVAL_ACTION_PURPOSE,
+        },
+        state::AppState,
+    },
+};
+
+use super::{
+    audit, department_heads,
+    errors::ServiceError,
+    events::{self, EventKind},
+    management_chain, notifications,
+    report_versions::{self, ReportVersion},
+    terminations, webhooks,
 };
 
-use super::errors::ServiceError;
+/// Notification kind queued for the exception approver when a report with
+/// policy exceptions is routed to them.
+pub const EXCEPTION_APPROVAL_REQUEST_KIND: &str = "exception_approval_request";
+
+/// Notification kind queued for the employee when a reviewer decides their
+/// report.
+pub const APPROVAL_DECISION_KIND: &str = "approval_decision";
 
 /// Manager or finance decision recorded through `POST /approvals/:id`.
 ///
@@ -31,6 +56,22 @@ pub struct DecisionRequest {
     pub policy_exception_notes: Option<String>,
 }
 
+/// Body accepted by `POST /approvals/actions` when a manager follows an
+/// emailed approval link.
+#[derive(Debug, Deserialize)]
+pub struct ActionLinkRequest {
+    pub token: String,
+    pub comments: Option<String>,
+}
+
+/// Signed links embedded in the approval request notification.
+#[derive(Debug, Clone, Serialize)]
+pub struct ActionLinks {
+    pub approve: String,
+    pub request_changes: String,
+    pub expires_at: DateTime<Utc>,
+}
+
 /// Service coordinating approval persistence and report status transitions.
 pub struct ApprovalService {
     pub state: Arc<AppState>,
@@ -46,7 +87,7 @@ impl ApprovalService {
     /// appropriate.
     ///
     /// * `actor` — authenticated approver; role is validated against
-    ///   `Role::Manager` or `Role::Finance` per `POLICY.md`
+    ///   `Role::Manager`, `Role::Finance`, or `Role::Admin` per `POLICY.md`
     ///   §"Approvals and Reimbursement Process".
     /// * `report_id` — target report awaiting action.
     /// * `payload` — desired decision, optional comments, and policy exception
@@ -54,20 +95,65 @@ impl ApprovalService {
     ///
     /// Side effects:
     /// * Persists an `Approval` row and ensures history capture.
+    /// * Queues an `approval_decision` notification for the report's
+    ///   employee, whatever the decision, plus a chat copy when
+    ///   `chat.enabled` is set.
     /// * Promotes report status to `ReportStatus::ManagerApproved` or
     ///   `ReportStatus::FinanceFinalized`, coordinating hand-offs to the
     ///   finance export pipeline implemented in `FinanceService`.
+    /// * Routes a manager-approved report with policy exception items to
+    ///   `ReportStatus::ExceptionReview` instead; its exception approver (or
+    ///   an administrator) then approves it to `ManagerApproved`.
+    /// * Records a `report.approved` webhook event when the report reaches
+    ///   `ManagerApproved` and `webhooks.enabled` is set.
+    ///
+    /// Fails with `ServiceError::Forbidden` when the actor was deactivated or
+    /// their role is outside of the allowed reviewers, leveraging the same
+    /// `Role` model used elsewhere in the domain, when anyone but the
+    /// exception approver or an administrator decides a report in exception
+    /// review, or when an administrator decides any other report. Reports of
+    /// other organizations than the actor's are `ServiceError::NotFound`.
     ///
-    /// Fails with `ServiceError::Forbidden` when the actor's role is outside of
-    /// the allowed reviewers, leveraging the same `Role` model used elsewhere
-    /// in the domain.
+    /// Status changes are version-checked (`report_versions`), so a decision
+    /// racing another write to the report fails with `ServiceError::Conflict`.
     pub async fn record_decision(
         &self,
         actor: &AuthenticatedUser,
         report_id: Uuid,
         payload: DecisionRequest,
     ) -> Result<Approval, ServiceError> {
-        ensure_role(actor, &[Role::Manager, Role::Finance])?;
+        ensure_role(actor, &[Role::Manager, Role::Finance, Role::Admin])?;
+        let mut tx = self
+            .state
+            .pool
+            .begin()
+            .await
+            .map_err(|err| ServiceError::Internal(err.to_string()))?;
+        let approval = self
+            .insert_decision(&mut tx, actor, report_id, payload)
+            .await?;
+        tx.commit()
+            .await
+            .map_err(|err| ServiceError::Internal(err.to_string()))?;
+        Ok(approval)
+    }
+
+    /// Records the decision carried by an emailed approval link.
+    ///
+    /// The token must verify against the server key, match an unused row in
+    /// `approval_action_tokens`, and the report must still be awaiting the
+    /// linked manager. Redeeming one link also expires its sibling so a
+    /// report cannot be both approved and sent back from the same email.
+    ///
+    /// Fails with `ServiceError::Forbidden` for forged, expired, or unknown
+    /// tokens and `ServiceError::Conflict` when the link was already used or
+    /// the report has moved on.
+    pub async fn record_link_decision(
+        &self,
+        payload: ActionLinkRequest,
+    ) -> Result<Approval, ServiceError> {
+        let claims = verify_action_token(&self.state.jwt_keys, &payload.token)
+            .map_err(|_| ServiceError::Forbidden)?;
         let mut tx = self
             .state
             .pool
@@ -75,58 +161,423 @@ impl ApprovalService {
             .await
             .map_err(|err| ServiceError::Internal(err.to_string()))?;
         let now = Utc::now();
-        let approval = sqlx::query(
-            "INSERT INTO approvals (id, report_id, approver_id, role, status, comments, policy_exception_notes, created_at)
-             VALUES ($1,$2,$3,$4,$5,$6,$7,$8)
-             RETURNING *",
+
+        let token = sqlx::query!(
+            "SELECT report_id, approver_id, action, expires_at, used_at
+             FROM approval_action_tokens
+             WHERE id = $1
+             FOR UPDATE",
+            claims.jti,
         )
-        .bind(Uuid::new_v4())
-        .bind(report_id)
-        .bind(actor.employee_id)
-        .bind(actor.role)
-        .bind(payload.status)
-        .bind(payload.comments)
-        .bind(payload.policy_exception_notes)
-        .bind(now)
-        .map(|row: PgRow| map_approval(row))
-        .fetch_one(&mut *tx)
+        .fetch_optional(&mut *tx)
         .await
-        .map_err(|err| ServiceError::Internal(err.to_string()))?;
+        .map_err(|err| ServiceError::Internal(err.to_string()))?
+        .ok_or(ServiceError::Forbidden)?;
 
-        if actor.role == Role::Manager && payload.status == ApprovalStatus::Approved {
-            self.transition_report(&mut tx, report_id, ReportStatus::ManagerApproved)
-                .await?;
+        let report_id = token.report_id;
+        let approver_id = token.approver_id;
+        if report_id != claims.report_id
+            || approver_id != claims.sub
+            || token.action != claims.action.as_str()
+            || token.expires_at <= now
+        {
+            return Err(ServiceError::Forbidden);
         }
-        if actor.role == Role::Finance && payload.status == ApprovalStatus::Approved {
-            self.transition_report(&mut tx, report_id, ReportStatus::FinanceFinalized)
-                .await?;
+        if token.used_at.is_some() {
+            return Err(ServiceError::Conflict);
         }
+
+        sqlx::query!(
+            "UPDATE approval_action_tokens SET used_at = $1
+             WHERE report_id = $2 AND approver_id = $3 AND used_at IS NULL",
+            now,
+            report_id,
+            approver_id,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| ServiceError::Internal(err.to_string()))?;
+
+        let pending = sqlx::query!(
+            r#"SELECT r.status::text AS "status!", e.manager_id,
+                      m.role AS "approver_role: Role", m.org_id AS approver_org
+               FROM expense_reports r
+               JOIN employees e ON e.id = r.employee_id
+               JOIN employees m ON m.id = $2
+               WHERE r.id = $1
+               FOR UPDATE OF r"#,
+            report_id,
+            approver_id,
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|err| ServiceError::Internal(err.to_string()))?
+        .ok_or(ServiceError::NotFound)?;
+
+        if pending.status != ReportStatus::Submitted.as_str()
+            || pending.manager_id != Some(approver_id)
+        {
+            return Err(ServiceError::Conflict);
+        }
+
+        let actor = AuthenticatedUser {
+            employee_id: approver_id,
+            role: pending.approver_role,
+            org_id: pending.approver_org,
+        };
+        ensure_role(&actor, &[Role::Manager])?;
+        let approval = self
+            .insert_decision(
+                &mut tx,
+                &actor,
+                report_id,
+                DecisionRequest {
+                    status: claims.action,
+                    comments: payload.comments,
+                    policy_exception_notes: None,
+                },
+            )
+            .await?;
         tx.commit()
             .await
             .map_err(|err| ServiceError::Internal(err.to_string()))?;
         Ok(approval)
     }
 
-    async fn transition_report(
+    async fn insert_decision(
         &self,
         tx: &mut Transaction<'_, Postgres>,
+        actor: &AuthenticatedUser,
         report_id: Uuid,
-        status: ReportStatus,
+        payload: DecisionRequest,
+    ) -> Result<Approval, ServiceError> {
+        terminations::ensure_active(tx.as_mut(), actor.employee_id).await?;
+        let report = sqlx::query_as!(
+            DecisionReport,
+            r#"SELECT r.status::text AS "status!", r.version, r.employee_id,
+                      r.exception_approver_id, r.reporting_period_start, r.reporting_period_end,
+                      r.total_amount_cents, r.currency, e.department,
+                      EXISTS (
+                          SELECT 1 FROM expense_items i
+                          WHERE i.report_id = r.id AND i.is_policy_exception
+                            AND i.deleted_at IS NULL
+                      ) AS "has_exceptions!"
+               FROM expense_reports r
+               JOIN employees e ON e.id = r.employee_id
+               WHERE r.id = $1 AND r.org_id = $2
+               FOR UPDATE OF r"#,
+            report_id,
+            actor.org_id,
+        )
+        .fetch_optional(tx.as_mut())
+        .await
+        .map_err(|err| ServiceError::Internal(err.to_string()))?
+        .ok_or(ServiceError::NotFound)?;
+        let before = audit::EXPENSE_REPORT
+            .snapshot(tx.as_mut(), report_id)
+            .await?;
+        let reviewed = ReportVersion {
+            id: report_id,
+            version: report.version,
+        };
+        let in_exception_review = report.status == ReportStatus::ExceptionReview.as_str();
+        if in_exception_review {
+            if report.exception_approver_id != Some(actor.employee_id) && actor.role != Role::Admin
+            {
+                return Err(ServiceError::Forbidden);
+            }
+        } else if actor.role == Role::Admin {
+            return Err(ServiceError::Forbidden);
+        }
+
+        let now = Utc::now();
+        let approval = sqlx::query_as!(
+            Approval,
+            r#"INSERT INTO approvals (id, report_id, approver_id, role, status, comments, policy_exception_notes, created_at)
+               VALUES ($1,$2,$3,$4,$5,$6,$7,$8)
+               RETURNING id, report_id, approver_id, role AS "role: Role",
+                         status AS "status: ApprovalStatus", comments, policy_exception_notes,
+                         created_at"#,
+            Uuid::new_v4(),
+            report_id,
+            actor.employee_id,
+            actor.role.as_str(),
+            payload.status.as_str(),
+            payload.comments,
+            payload.policy_exception_notes,
+            now,
+        )
+        .fetch_one(tx.as_mut())
+        .await
+        .map_err(|err| ServiceError::Internal(err.to_string()))?;
+
+        let decision = serde_json::json!({
+            "report_id": report_id,
+            "employee_id": report.employee_id,
+            "reporting_period_start": report.reporting_period_start,
+            "reporting_period_end": report.reporting_period_end,
+            "total_amount_cents": report.total_amount_cents,
+            "currency": report.currency,
+            "status": approval.status.as_str(),
+            "role": approval.role.as_str(),
+            "comments": approval.comments,
+        });
+        let mut recorded = decision.clone();
+        recorded["approval_id"] = serde_json::json!(approval.id);
+        let event_id = events::record(
+            tx.as_mut(),
+            EventKind::DecisionRecorded,
+            report_id,
+            Some(actor.employee_id),
+            recorded,
+        )
+        .await?;
+        if self.state.config.chat.enabled {
+            notifications::enqueue_chat(
+                tx.as_mut(),
+                report.employee_id,
+                APPROVAL_DECISION_KIND,
+                decision.clone(),
+            )
+            .await?;
+        }
+        notifications::enqueue(
+            tx.as_mut(),
+            report.employee_id,
+            APPROVAL_DECISION_KIND,
+            decision,
+        )
+        .await?;
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::APPROVAL,
+            approval.id,
+            audit::CREATED,
+            None,
+        )
+        .await?;
+
+        if payload.status != ApprovalStatus::Approved {
+            return Ok(approval);
+        }
+        match actor.role {
+            _ if in_exception_review => {
+                self.approve_report(tx, actor, &report, reviewed, event_id)
+                    .await?
+            }
+            Role::Manager if report.has_exceptions => {
+                self.route_exception_review(tx, actor, &report, reviewed)
+                    .await?
+            }
+            Role::Manager => {
+                self.approve_report(tx, actor, &report, reviewed, event_id)
+                    .await?
+            }
+            Role::Finance => {
+                report_versions::set_status(
+                    tx.as_mut(),
+                    &[reviewed],
+                    ReportStatus::FinanceFinalized,
+                )
+                .await?
+            }
+            Role::Employee | Role::Admin => return Ok(approval),
+        }
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::EXPENSE_REPORT,
+            report_id,
+            audit::STATUS_CHANGED,
+            before,
+        )
+        .await?;
+        Ok(approval)
+    }
+
+    /// Moves a report to `manager_approved` and, when `webhooks.enabled` is
+    /// set, records a `report.approved` webhook event under `event_id`, the
+    /// decision's domain event.
+    async fn approve_report(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        actor: &AuthenticatedUser,
+        report: &DecisionReport,
+        reviewed: ReportVersion,
+        event_id: Uuid,
     ) -> Result<(), ServiceError> {
-        let result = sqlx::query("UPDATE expense_reports SET status=$1, updated_at=$2 WHERE id=$3")
-            .bind(status)
-            .bind(Utc::now())
-            .bind(report_id)
-            .execute(tx.as_mut())
-            .await
-            .map_err(|err| ServiceError::Internal(err.to_string()))?;
+        report_versions::set_status(tx.as_mut(), &[reviewed], ReportStatus::ManagerApproved)
+            .await?;
+        if !self.state.config.webhooks.enabled {
+            return Ok(());
+        }
+        let data = serde_json::json!({
+            "report_id": reviewed.id,
+            "employee_id": report.employee_id,
+            "approved_by": actor.employee_id,
+            "reporting_period_start": report.reporting_period_start,
+            "reporting_period_end": report.reporting_period_end,
+            "total_amount_cents": report.total_amount_cents,
+            "currency": report.currency,
+        });
+        webhooks::emit(tx.as_mut(), event_id, webhooks::REPORT_APPROVED_EVENT, data).await
+    }
+
+    /// Moves a manager-approved report with policy exceptions to
+    /// `exception_review` and asks its exception approver to review it.
+    async fn route_exception_review(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        actor: &AuthenticatedUser,
+        report: &DecisionReport,
+        reviewed: ReportVersion,
+    ) -> Result<(), ServiceError> {
+        let employee_id = report.employee_id;
+        let department_head = match report.department.as_deref() {
+            Some(department) => {
+                department_heads::head_of(tx.as_mut(), actor.org_id, department).await?
+            }
+            None => None,
+        };
+        let skip_levels: Vec<Uuid> = management_chain::resolve(&self.state, actor.employee_id)
+            .await?
+            .map(|chain| chain.manager_ids().collect())
+            .unwrap_or_default();
+        let approver_id = exception_approver(
+            employee_id,
+            actor.employee_id,
+            department_head,
+            &skip_levels,
+        );
+
+        let result = sqlx::query!(
+            "UPDATE expense_reports
+             SET status = $1, exception_approver_id = $2, version = version + 1, updated_at = $3
+             WHERE id = $4 AND version = $5",
+            ReportStatus::ExceptionReview.as_str(),
+            approver_id,
+            Utc::now(),
+            reviewed.id,
+            reviewed.version,
+        )
+        .execute(tx.as_mut())
+        .await
+        .map_err(|err| ServiceError::Internal(err.to_string()))?;
         if result.rows_affected() == 0 {
-            return Err(ServiceError::NotFound);
+            return Err(ServiceError::Conflict);
         }
+
+        let Some(approver_id) = approver_id else {
+            return Ok(());
+        };
+        let payload = serde_json::json!({
+            "report_id": reviewed.id,
+            "employee_id": employee_id,
+            "approved_by": actor.employee_id,
+            "total_amount_cents": report.total_amount_cents,
+            "currency": report.currency,
+        });
+        notifications::enqueue(
+            tx.as_mut(),
+            approver_id,
+            EXCEPTION_APPROVAL_REQUEST_KIND,
+            payload,
+        )
+        .await?;
         Ok(())
     }
 }
 
+/// The report a decision is recorded on, locked for the decision's
+/// transaction.
+struct DecisionReport {
+    status: String,
+    version: i32,
+    employee_id: Uuid,
+    exception_approver_id: Option<Uuid>,
+    reporting_period_start: NaiveDate,
+    reporting_period_end: NaiveDate,
+    total_amount_cents: i64,
+    currency: String,
+    department: Option<String>,
+    has_exceptions: bool,
+}
+
+/// Picks who approves a report's policy exceptions after `manager_id` has
+/// approved it: the submitter's department head, else the nearest manager
+/// on the approving manager's chain (`skip_levels`, nearest first). Neither
+/// the submitter nor the approving manager qualifies; `None` leaves the
+/// review to administrators.
+fn exception_approver(
+    employee_id: Uuid,
+    manager_id: Uuid,
+    department_head: Option<Uuid>,
+    skip_levels: &[Uuid],
+) -> Option<Uuid> {
+    department_head
+        .into_iter()
+        .chain(skip_levels.iter().copied())
+        .find(|&candidate| candidate != employee_id && candidate != manager_id)
+}
+
+/// Issues approve and request-changes tokens for `approver_id` on
+/// `report_id` and returns the links to embed in the notification.
+///
+/// Runs on the caller's transaction so links only exist for submissions that
+/// actually commit.
+pub async fn issue_action_links(
+    tx: &mut Transaction<'_, Postgres>,
+    state: &AppState,
+    report_id: Uuid,
+    approver_id: Uuid,
+) -> Result<ActionLinks, ServiceError> {
+    let ttl = chrono::Duration::from_std(state.config.approval_link_ttl())
+        .map_err(|_| ServiceError::Internal("failed to calculate link expiration".into()))?;
+    let expires_at = Utc::now() + ttl;
+    let base_url = &state.config.approval_links.base_url;
+
+    let mut urls = Vec::with_capacity(2);
+    for action in [ApprovalStatus::Approved, ApprovalStatus::NeedsChanges] {
+        let claims = ActionClaims {
+            jti: Uuid::new_v4(),
+            sub: approver_id,
+            report_id,
+            action,
+            purpose: APPROVAL_ACTION_PURPOSE.to_string(),
+            exp: expires_at.timestamp() as usize,
+        };
+        sqlx::query!(
+            "INSERT INTO approval_action_tokens (id, report_id, approver_id, action, expires_at)
+             VALUES ($1,$2,$3,$4,$5)",
+            claims.jti,
+            report_id,
+            approver_id,
+            action.as_str(),
+            expires_at,
+        )
+        .execute(tx.as_mut())
+        .await
+        .map_err(|err| ServiceError::Internal(err.to_string()))?;
+        let token = sign_action_token(&state.jwt_keys, &claims)?;
+        urls.push(action_link_url(base_url, &token));
+    }
+
+    let request_changes = urls.pop().unwrap_or_default();
+    let approve = urls.pop().unwrap_or_default();
+    Ok(ActionLinks {
+        approve,
+        request_changes,
+        expires_at,
+    })
+}
+
+/// Appends the token as a query parameter; JWTs are already URL-safe.
+fn action_link_url(base_url: &str, token: &str) -> String {
+    let separator = if base_url.contains('?') { '&' } else { '?' };
+    format!("{base_url}{separator}token={token}")
+}
+
 fn ensure_role(user: &AuthenticatedUser, allowed: &[Role]) -> Result<(), ServiceError> {
     if allowed.iter().any(|r| r == &user.role) {
         Ok(())
@@ -135,19 +586,6 @@ fn ensure_role(user: &AuthenticatedUser, allowed: &[Role]) -> Result<(), Service
     }
 }
 
-fn map_approval(row: PgRow) -> Approval {
-    Approval {
-        id: row.get("id"),
-        report_id: row.get("report_id"),
-        approver_id: row.get("approver_id"),
-        role: row.get("role"),
-        status: row.get("status"),
-        comments: row.get("comments"),
-        policy_exception_notes: row.get("policy_exception_notes"),
-        created_at: row.get("created_at"),
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -156,6 +594,7 @@ mod tests {
     fn actor(role: Role) -> AuthenticatedUser {
         AuthenticatedUser {
             employee_id: uuid::Uuid::new_v4(),
+            org_id: crate::domain::models::DEFAULT_ORG_ID,
             role,
         }
     }
@@ -175,4 +614,40 @@ mod tests {
 
         assert!(matches!(result, Err(ServiceError::Forbidden)));
     }
+
+    #[test]
+    fn exception_approver_skips_the_submitter_and_approving_manager() {
+        let (employee, manager, head, skip_level) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+
+        assert_eq!(
+            exception_approver(employee, manager, Some(head), &[skip_level]),
+            Some(head)
+        );
+        assert_eq!(
+            exception_approver(employee, manager, Some(manager), &[skip_level]),
+            Some(skip_level)
+        );
+        assert_eq!(exception_approver(head, manager, Some(head), &[]), None);
+        assert_eq!(
+            exception_approver(employee, manager, None, &[employee, skip_level]),
+            Some(skip_level)
+        );
+    }
+
+    #[test]
+    fn action_link_url_appends_token_query() {
+        assert_eq!(
+            action_link_url("https://portal.example.com/approvals/action", "abc.def.ghi"),
+            "https://portal.example.com/approvals/action?token=abc.def.ghi"
+        );
+        assert_eq!(
+            action_link_url("https://portal.example.com/act?source=email", "abc"),
+            "https://portal.example.com/act?source=email&token=abc"
+        );
+    }
 }
@@ -0,0 +1,359 @@
+//! Cold storage for reports finalized long ago.
+//!
+//! The `archive` job picks `finance_finalized` reports untouched for
+//! `archive.after_years` and, one transaction per report, writes their items,
+//! receipts, approvals, and policy evaluations as a gzip-compressed JSON
+//! document to the storage backend, deletes those rows, and marks the report
+//! row, which stays behind as a stub with its totals, with `archived_at` and
+//! the object's `archive_key`. Journal lines and audit entries are untouched,
+//! and receipt files stay where they are.
+//!
+//! An administrator rehydrates a report through
+//! `POST /admin/reports/:id/rehydrate`, which reinserts the archived rows and
+//! removes the object; the job then leaves the report alone for
+//! `archive.rehydrated_hold_days`.
+
+use std::sync::Arc;
+
+use bytes::Bytes;
+use chrono::{Datelike, Duration, Utc};
+use serde_json::{Map, Value};
+use sqlx::{PgConnection, Row};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{ExpenseReport, ReportStatus, Role},
+    infrastructure::{auth::AuthenticatedUser, state::AppState},
+};
+
+use super::{audit, errors::ServiceError};
+
+/// Version of the archive document layout.
+const FORMAT_VERSION: i64 = 1;
+
+/// Reports archived by one run, at most.
+const ARCHIVE_LIMIT: i64 = 500;
+
+/// Tables an archive holds, as the document field, the table, and the
+/// condition selecting the report's rows with the report id as `$1`, in the
+/// order rehydration reinserts them.
+const ARCHIVED_TABLES: [(&str, &str, &str); 5] = [
+    ("items", "expense_items", "t.report_id = $1"),
+    (
+        "receipts",
+        "receipts",
+        "t.expense_item_id IN (SELECT id FROM expense_items WHERE report_id = $1)",
+    ),
+    ("approvals", "approvals", "t.report_id = $1"),
+    (
+        "policy_evaluations",
+        "report_policy_evaluations",
+        "t.report_id = $1",
+    ),
+    (
+        "policy_evaluation_runs",
+        "policy_evaluation_runs",
+        "t.report_id = $1",
+    ),
+];
+
+/// Outcome of one archive run.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArchiveSummary {
+    pub archived: usize,
+    pub failed: usize,
+}
+
+/// Service rehydrating archived reports.
+pub struct ArchiveService {
+    state: Arc<AppState>,
+}
+
+impl ArchiveService {
+    /// Constructs the service from shared application state.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// Restores an archived report's rows from its archive and returns the
+    /// report. Restricted to administrators of the report's organization; a
+    /// report that is not archived is a conflict.
+    pub async fn rehydrate(
+        &self,
+        actor: &AuthenticatedUser,
+        report_id: Uuid,
+    ) -> Result<ExpenseReport, ServiceError> {
+        if actor.role != Role::Admin {
+            return Err(ServiceError::Forbidden);
+        }
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let archive_key: Option<String> = sqlx::query_scalar(
+            "SELECT archive_key FROM expense_reports WHERE id = $1 AND org_id = $2 FOR UPDATE",
+        )
+        .bind(report_id)
+        .bind(actor.org_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(internal)?
+        .ok_or(ServiceError::NotFound)?;
+        let Some(archive_key) = archive_key else {
+            return Err(ServiceError::Conflict);
+        };
+        let before = audit::EXPENSE_REPORT.snapshot(&mut tx, report_id).await?;
+
+        let stored = self
+            .state
+            .storage
+            .get(&archive_key)
+            .await
+            .map_err(|err| ServiceError::Internal(format!("reading {archive_key}: {err}")))?;
+        let document: Value = serde_json::from_slice(&gunzip(&stored)?)
+            .map_err(|err| ServiceError::Internal(format!("parsing {archive_key}: {err}")))?;
+        for (field, table, _) in ARCHIVED_TABLES {
+            let rows = document.get(field).cloned().unwrap_or(Value::Array(vec![]));
+            restore_rows(&mut tx, table, rows).await?;
+        }
+
+        let report = sqlx::query_as!(
+            ExpenseReport,
+            r#"UPDATE expense_reports
+               SET archived_at = NULL, archive_key = NULL, rehydrated_at = $2
+               WHERE id = $1
+               RETURNING id, org_id, employee_id, reporting_period_start, reporting_period_end,
+                         status AS "status: ReportStatus", total_amount_cents,
+                         total_reimbursable_cents, currency, version, created_at, updated_at,
+                         submitted_at, trip_id, owner_deactivated_at, created_by, submitted_by"#,
+            report_id,
+            Utc::now(),
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(internal)?;
+        audit::record_change(
+            &mut tx,
+            Some(actor.employee_id),
+            audit::EXPENSE_REPORT,
+            report_id,
+            audit::REHYDRATED,
+            before,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+
+        // The rows are back for good, so the archive is no longer needed.
+        if let Err(err) = self.state.storage.delete(&archive_key).await {
+            warn!(%report_id, %archive_key, error = %err, "could not delete rehydrated archive");
+        }
+        Ok(report)
+    }
+}
+
+/// Archives every due report, oldest first, up to `ARCHIVE_LIMIT` per call.
+/// A report that fails is logged and left for the next run. Stops early once
+/// shutdown begins.
+pub async fn archive_due(state: &AppState) -> Result<ArchiveSummary, ServiceError> {
+    let config = &state.config.archive;
+    let now = Utc::now();
+    let cutoff = now - Duration::days(365 * i64::from(config.after_years));
+    let hold = now - Duration::days(i64::from(config.rehydrated_hold_days));
+    let due: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT id FROM expense_reports
+         WHERE status::text = $1 AND archived_at IS NULL AND deleted_at IS NULL
+           AND updated_at < $2 AND (rehydrated_at IS NULL OR rehydrated_at < $3)
+         ORDER BY updated_at, id
+         LIMIT $4",
+    )
+    .bind(ReportStatus::FinanceFinalized.as_str())
+    .bind(cutoff)
+    .bind(hold)
+    .bind(ARCHIVE_LIMIT)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(internal)?;
+
+    let mut summary = ArchiveSummary::default();
+    for report_id in due {
+        if state.shutdown.is_cancelled() {
+            break;
+        }
+        match archive_report(state, report_id).await {
+            Ok(true) => summary.archived += 1,
+            Ok(false) => {}
+            Err(err) => {
+                warn!(%report_id, error = %err, "report archiving failed");
+                summary.failed += 1;
+            }
+        }
+    }
+    Ok(summary)
+}
+
+/// Moves one report's rows into an archive object. Returns `Ok(false)` when
+/// the report was archived, or left `finance_finalized`, in the meantime.
+async fn archive_report(state: &AppState, report_id: Uuid) -> Result<bool, ServiceError> {
+    let mut tx = state.pool.begin().await.map_err(internal)?;
+    let report = sqlx::query(
+        "SELECT updated_at FROM expense_reports
+         WHERE id = $1 AND status::text = $2 AND archived_at IS NULL
+         FOR UPDATE",
+    )
+    .bind(report_id)
+    .bind(ReportStatus::FinanceFinalized.as_str())
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(internal)?;
+    let Some(report) = report else {
+        return Ok(false);
+    };
+    let updated_at: chrono::DateTime<Utc> = report.try_get("updated_at").map_err(internal)?;
+    let before = audit::EXPENSE_REPORT.snapshot(&mut tx, report_id).await?;
+
+    let now = Utc::now();
+    let mut document = Map::new();
+    document.insert("format".into(), Value::from(FORMAT_VERSION));
+    document.insert("report_id".into(), Value::from(report_id.to_string()));
+    document.insert("archived_at".into(), Value::from(now.to_rfc3339()));
+    document.insert("report".into(), before.clone().unwrap_or(Value::Null));
+    for (field, table, condition) in ARCHIVED_TABLES {
+        let rows: Value = sqlx::query_scalar(&format!(
+            "SELECT COALESCE(jsonb_agg(to_jsonb(t) - 'search_vector'), '[]'::jsonb)
+             FROM {table} t WHERE {condition}"
+        ))
+        .bind(report_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(internal)?;
+        document.insert(field.into(), rows);
+    }
+    let body = serde_json::to_vec(&Value::Object(document))
+        .map_err(|err| ServiceError::Internal(err.to_string()))?;
+
+    // Written before the rows go, so a failed upload leaves the report as it
+    // was; an upload whose transaction then fails is overwritten next time.
+    let key = archive_key(report_id, updated_at.year());
+    state
+        .storage
+        .put(&key, Bytes::from(gzip(&body)), "application/gzip")
+        .await
+        .map_err(|err| ServiceError::Internal(format!("writing {key}: {err}")))?;
+
+    for (_, table, condition) in ARCHIVED_TABLES.iter().rev() {
+        sqlx::query(&format!("DELETE FROM {table} t WHERE {condition}"))
+            .bind(report_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(internal)?;
+    }
+    sqlx::query("UPDATE expense_reports SET archived_at = $2, archive_key = $3 WHERE id = $1")
+        .bind(report_id)
+        .bind(now)
+        .bind(&key)
+        .execute(&mut *tx)
+        .await
+        .map_err(internal)?;
+    audit::record_change(
+        &mut tx,
+        None,
+        audit::EXPENSE_REPORT,
+        report_id,
+        audit::ARCHIVED,
+        before,
+    )
+    .await?;
+    tx.commit().await.map_err(internal)?;
+    Ok(true)
+}
+
+/// Storage key of a report's archive, grouped by the year it was finalized.
+fn archive_key(report_id: Uuid, year: i32) -> String {
+    format!("archives/reports/{year}/{report_id}.json.gz")
+}
+
+/// Reinserts archived `rows` into `table`. Generated columns are left for
+/// Postgres to compute.
+async fn restore_rows(
+    conn: &mut PgConnection,
+    table: &str,
+    rows: Value,
+) -> Result<(), ServiceError> {
+    let columns: String = sqlx::query_scalar(
+        "SELECT string_agg(quote_ident(column_name), ', ' ORDER BY ordinal_position)
+         FROM information_schema.columns
+         WHERE table_schema = current_schema() AND table_name = $1 AND is_generated = 'NEVER'",
+    )
+    .bind(table)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(internal)?;
+    sqlx::query(&format!(
+        "INSERT INTO {table} ({columns})
+         SELECT {columns} FROM jsonb_populate_recordset(NULL::{table}, $1)"
+    ))
+    .bind(rows)
+    .execute(&mut *conn)
+    .await
+    .map_err(internal)?;
+    Ok(())
+}
+
+/// Header of a gzip member with no name, timestamp, or extra fields.
+const GZIP_HEADER: [u8; 10] = [0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 0xff];
+
+/// Compresses `data` as a single gzip member.
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut out = GZIP_HEADER.to_vec();
+    out.extend(miniz_oxide::deflate::compress_to_vec(data, 6));
+    out.extend(crc32fast::hash(data).to_le_bytes());
+    out.extend((data.len() as u32).to_le_bytes());
+    out
+}
+
+/// Decompresses a gzip member written by `gzip`, checking its CRC and size.
+fn gunzip(data: &[u8]) -> Result<Vec<u8>, ServiceError> {
+    let corrupt = |reason: &str| ServiceError::Internal(format!("corrupt archive: {reason}"));
+    if data.len() < GZIP_HEADER.len() + 8 || data[..4] != GZIP_HEADER[..4] {
+        return Err(corrupt("not a gzip stream without header fields"));
+    }
+    let (body, trailer) = data[GZIP_HEADER.len()..].split_at(data.len() - GZIP_HEADER.len() - 8);
+    let inflated = miniz_oxide::inflate::decompress_to_vec(body)
+        .map_err(|err| corrupt(&format!("{err:?}")))?;
+    let crc = u32::from_le_bytes(trailer[..4].try_into().expect("four bytes"));
+    let size = u32::from_le_bytes(trailer[4..].try_into().expect("four bytes"));
+    if crc32fast::hash(&inflated) != crc || inflated.len() as u32 != size {
+        return Err(corrupt("checksum mismatch"));
+    }
+    Ok(inflated)
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gzip_round_trips_and_rejects_tampering() {
+        let document = br#"{"format":1,"items":[{"merchant":"Blue Bottle"}]}"#.repeat(20);
+        let compressed = gzip(&document);
+        assert!(compressed.len() < document.len());
+        assert_eq!(gunzip(&compressed).unwrap(), document);
+
+        let mut tampered = compressed.clone();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        assert!(gunzip(&tampered).is_err());
+        assert!(gunzip(b"{}").is_err());
+    }
+
+    #[test]
+    fn archive_keys_group_reports_by_year() {
+        let id = Uuid::nil();
+        assert_eq!(
+            archive_key(id, 2021),
+            "archives/reports/2021/00000000-0000-0000-0000-000000000000.json.gz"
+        );
+    }
+}
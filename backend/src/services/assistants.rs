@@ -0,0 +1,291 @@
+//! Assistants who create and submit reports for the employees they support.
+//!
+//! An employee links their assistants through `/auth/me/assistants`. A
+//! linked assistant names the employee in the `X-On-Behalf-Of` header of a
+//! report request, and the request then runs as that employee: the reports
+//! are theirs, while `created_by`/`submitted_by` and the audit entries'
+//! `performed_by` name the assistant, with `on_behalf_of` naming the
+//! employee.
+
+use std::{future::Future, sync::Arc};
+
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::{
+    api::error::ApiError,
+    domain::models::Role,
+    infrastructure::{auth::AuthenticatedUser, state::AppState},
+};
+
+use super::{
+    audit::{self, Delegation},
+    errors::ServiceError,
+    terminations,
+};
+
+/// Header naming the employee an assistant is acting for.
+pub const ON_BEHALF_OF_HEADER: &str = "x-on-behalf-of";
+
+/// An assistant allowed to act for `principal_id`.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct AssistantLink {
+    pub id: Uuid,
+    pub principal_id: Uuid,
+    pub assistant_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The caller's links: the assistants acting for them and the employees
+/// they assist.
+#[derive(Debug, Clone, Serialize)]
+pub struct AssistantLinks {
+    pub assistants: Vec<AssistantLink>,
+    pub principals: Vec<AssistantLink>,
+}
+
+/// Payload accepted by `POST /auth/me/assistants`.
+#[derive(Debug, Deserialize)]
+pub struct AddAssistantRequest {
+    pub assistant_id: Uuid,
+}
+
+/// The `X-On-Behalf-Of` header of a request, if it has one.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OnBehalfOf(pub Option<Uuid>);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for OnBehalfOf
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Some(value) = parts.headers.get(ON_BEHALF_OF_HEADER) else {
+            return Ok(OnBehalfOf(None));
+        };
+        value
+            .to_str()
+            .ok()
+            .and_then(|value| Uuid::parse_str(value.trim()).ok())
+            .map(|principal_id| OnBehalfOf(Some(principal_id)))
+            .ok_or_else(|| {
+                ServiceError::Validation("X-On-Behalf-Of must be an employee ID".into()).into()
+            })
+    }
+}
+
+/// The identity a report request runs as.
+#[derive(Debug, Clone)]
+pub struct Acting {
+    /// The employee the request acts for: the caller, or their principal.
+    pub user: AuthenticatedUser,
+    pub delegation: Option<Delegation>,
+}
+
+impl Acting {
+    /// Runs `future`, attributing the audit entries it records for the
+    /// principal to the assistant.
+    pub async fn run<F: Future>(&self, future: F) -> F::Output {
+        match self.delegation {
+            Some(delegation) => audit::acting_for(delegation, future).await,
+            None => future.await,
+        }
+    }
+}
+
+/// Service behind the assistant link routes and `X-On-Behalf-Of`.
+pub struct AssistantService {
+    state: Arc<AppState>,
+}
+
+impl AssistantService {
+    /// Constructs the service from shared application state.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// The actor's assistants and the employees they assist.
+    pub async fn links(&self, actor: &AuthenticatedUser) -> Result<AssistantLinks, ServiceError> {
+        let links = sqlx::query_as::<_, AssistantLink>(
+            "SELECT id, principal_id, assistant_id, created_at FROM assistant_links
+             WHERE principal_id = $1 OR assistant_id = $1
+             ORDER BY created_at",
+        )
+        .bind(actor.employee_id)
+        .fetch_all(&self.state.pool)
+        .await
+        .map_err(internal)?;
+        let (assistants, principals) = links
+            .into_iter()
+            .partition(|link| link.principal_id == actor.employee_id);
+        Ok(AssistantLinks {
+            assistants,
+            principals,
+        })
+    }
+
+    /// Lets `payload.assistant_id`, an active employee of the actor's
+    /// organization, act for the actor. Linking an existing assistant again
+    /// returns the existing link.
+    pub async fn add(
+        &self,
+        actor: &AuthenticatedUser,
+        payload: AddAssistantRequest,
+    ) -> Result<AssistantLink, ServiceError> {
+        if payload.assistant_id == actor.employee_id {
+            return Err(ServiceError::Validation(
+                "assistant_id must name another employee".into(),
+            ));
+        }
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let assistant_is_active: bool = sqlx::query_scalar(
+            "SELECT EXISTS (
+                 SELECT 1 FROM employees
+                 WHERE id = $1 AND org_id = $2 AND deactivated_at IS NULL
+             )",
+        )
+        .bind(payload.assistant_id)
+        .bind(actor.org_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(internal)?;
+        if !assistant_is_active {
+            return Err(ServiceError::Validation(
+                "assistant_id must name an active employee of your organization".into(),
+            ));
+        }
+
+        let inserted = sqlx::query_as::<_, AssistantLink>(
+            "INSERT INTO assistant_links (id, principal_id, assistant_id, created_at)
+             VALUES ($1,$2,$3,$4)
+             ON CONFLICT (principal_id, assistant_id) DO NOTHING
+             RETURNING id, principal_id, assistant_id, created_at",
+        )
+        .bind(Uuid::new_v4())
+        .bind(actor.employee_id)
+        .bind(payload.assistant_id)
+        .bind(Utc::now())
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(internal)?;
+        let link = match inserted {
+            Some(link) => {
+                audit::record_change(
+                    &mut tx,
+                    Some(actor.employee_id),
+                    audit::ASSISTANT_LINK,
+                    link.id,
+                    audit::CREATED,
+                    None,
+                )
+                .await?;
+                link
+            }
+            None => sqlx::query_as::<_, AssistantLink>(
+                "SELECT id, principal_id, assistant_id, created_at FROM assistant_links
+                 WHERE principal_id = $1 AND assistant_id = $2",
+            )
+            .bind(actor.employee_id)
+            .bind(payload.assistant_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(internal)?,
+        };
+        tx.commit().await.map_err(internal)?;
+        Ok(link)
+    }
+
+    /// Stops `assistant_id` from acting for the actor.
+    pub async fn remove(
+        &self,
+        actor: &AuthenticatedUser,
+        assistant_id: Uuid,
+    ) -> Result<(), ServiceError> {
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let id: Uuid = sqlx::query_scalar(
+            "SELECT id FROM assistant_links WHERE principal_id = $1 AND assistant_id = $2",
+        )
+        .bind(actor.employee_id)
+        .bind(assistant_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(internal)?
+        .ok_or(ServiceError::NotFound)?;
+        let before = audit::ASSISTANT_LINK.snapshot(&mut tx, id).await?;
+        sqlx::query("DELETE FROM assistant_links WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(internal)?;
+        audit::record_change(
+            &mut tx,
+            Some(actor.employee_id),
+            audit::ASSISTANT_LINK,
+            id,
+            audit::DELETED,
+            before,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        Ok(())
+    }
+
+    /// The identity a report request from `actor` runs as. Without a
+    /// principal, or when the actor names themselves, that is the actor.
+    /// Otherwise the actor must be an active assistant linked by the
+    /// principal, an active employee of the same organization, and the
+    /// request runs as the principal with the employee role, so it reaches
+    /// their own reports only. Fails with `ServiceError::Forbidden`
+    /// otherwise.
+    pub async fn act_for(
+        &self,
+        actor: &AuthenticatedUser,
+        OnBehalfOf(principal_id): OnBehalfOf,
+    ) -> Result<Acting, ServiceError> {
+        let Some(principal_id) = principal_id.filter(|id| *id != actor.employee_id) else {
+            return Ok(Acting {
+                user: actor.clone(),
+                delegation: None,
+            });
+        };
+        let mut conn = self.state.pool.acquire().await.map_err(internal)?;
+        terminations::ensure_active(&mut conn, actor.employee_id).await?;
+        let linked: bool = sqlx::query_scalar(
+            "SELECT EXISTS (
+                 SELECT 1 FROM assistant_links l
+                 JOIN employees e ON e.id = l.principal_id
+                 WHERE l.principal_id = $1 AND l.assistant_id = $2
+                   AND e.org_id = $3 AND e.deactivated_at IS NULL
+             )",
+        )
+        .bind(principal_id)
+        .bind(actor.employee_id)
+        .bind(actor.org_id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(internal)?;
+        if !linked {
+            return Err(ServiceError::Forbidden);
+        }
+        Ok(Acting {
+            user: AuthenticatedUser {
+                employee_id: principal_id,
+                role: Role::Employee,
+                org_id: actor.org_id,
+            },
+            delegation: Some(Delegation {
+                assistant_id: actor.employee_id,
+                principal_id,
+            }),
+        })
+    }
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
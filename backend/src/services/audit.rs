@@ -0,0 +1,664 @@
+//! Audit trail of report, approval, finance, and admin changes.
+//!
+//! Services call `record_change` in the transaction that made a change, with
+//! the row's `snapshot` from before it; the row as it is afterwards becomes
+//! the entry's `new_value`. The actor comes from the caller, while the client
+//! IP, user agent, and request ID come from the `RequestContext` the API
+//! installs around each request. When an assistant acts for an employee,
+//! entries recorded for the employee name the assistant as `performed_by`
+//! and the employee as `on_behalf_of`. Every row carries a `signature_hash` over
+//! its contents, so an edited row no longer matches `signature_hash(&row)`.
+//!
+//! Each entity's entries form a hash chain: they are numbered from 1 by
+//! `chain_sequence`, and each hash covers the previous entry's hash as
+//! `previous_hash`, so a removed or replaced entry breaks the chain after
+//! it. `verify_chains` walks the chains and reports what no longer holds.
+//! Entries recorded before chaining began have no `previous_hash` and are
+//! only checked against their own hash and sequence. Data retention removes
+//! the oldest entries, so a chain starting after 1 is reported as trimmed
+//! rather than broken.
+
+use std::{fmt::Write as _, future::Future, sync::Arc};
+
+use chrono::{DateTime, SubsecRound, Utc};
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{AuditLog, Role},
+    infrastructure::{auth::AuthenticatedUser, state::AppState},
+};
+
+use super::errors::ServiceError;
+
+pub const CREATED: &str = "created";
+pub const UPDATED: &str = "updated";
+pub const DELETED: &str = "deleted";
+pub const RESTORED: &str = "restored";
+pub const STATUS_CHANGED: &str = "status_changed";
+pub const ARCHIVED: &str = "archived";
+pub const REHYDRATED: &str = "rehydrated";
+
+/// `previous_hash` of the first entry in a chain.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Problems listed in a `ChainVerification`; the rest are only counted.
+const MAX_REPORTED_PROBLEMS: usize = 500;
+
+/// An audited table and the `entity_type` its rows are logged under.
+#[derive(Debug, Clone, Copy)]
+pub struct Entity {
+    pub table: &'static str,
+    pub entity_type: &'static str,
+    /// Columns left out of snapshots, such as signing secrets and derived
+    /// search vectors.
+    pub redacted: &'static [&'static str],
+}
+
+pub const ACCOUNTING_PERIOD: Entity = entity("periods", "accounting_period");
+pub const APPROVAL: Entity = entity("approvals", "approval");
+pub const ASSISTANT_LINK: Entity = entity("assistant_links", "assistant_link");
+pub const BANK_ACCOUNT: Entity = Entity {
+    table: "employee_bank_accounts",
+    entity_type: "bank_account",
+    redacted: &["nonce", "ciphertext"],
+};
+pub const BUDGET: Entity = entity("budgets", "budget");
+pub const COST_CENTER: Entity = entity("cost_centers", "cost_center");
+pub const DEPARTMENT: Entity = entity("departments", "department");
+pub const DEPARTMENT_HEAD: Entity = entity("department_heads", "department_head");
+pub const EMPLOYEE: Entity = entity("employees", "employee");
+pub const EXPENSE_ITEM: Entity = Entity {
+    table: "expense_items",
+    entity_type: "expense_item",
+    redacted: &["search_vector"],
+};
+pub const EXPENSE_REPORT: Entity = entity("expense_reports", "expense_report");
+pub const HOLIDAY: Entity = entity("holidays", "holiday");
+pub const JOB_RUN: Entity = entity("job_runs", "job_run");
+pub const MILEAGE_RATE: Entity = entity("mileage_rates", "mileage_rate");
+pub const NETSUITE_BATCH: Entity = entity("netsuite_batches", "netsuite_batch");
+pub const NETSUITE_FIELD_MAPPING: Entity =
+    entity("netsuite_field_mappings", "netsuite_field_mapping");
+pub const ORGANIZATION: Entity = entity("organizations", "organization");
+pub const POLICY_RULE: Entity = entity("policy_rules", "policy_rule");
+pub const RECEIPT: Entity = entity("receipts", "receipt");
+pub const RETENTION_RUN: Entity = entity("retention_runs", "retention_run");
+pub const TAX_CODE_MAPPING: Entity = entity("tax_code_mappings", "tax_code_mapping");
+pub const TRIP: Entity = entity("trips", "trip");
+pub const WEBHOOK_SUBSCRIPTION: Entity = Entity {
+    table: "webhook_subscriptions",
+    entity_type: "webhook_subscription",
+    redacted: &["secret"],
+};
+
+const fn entity(table: &'static str, entity_type: &'static str) -> Entity {
+    Entity {
+        table,
+        entity_type,
+        redacted: &[],
+    }
+}
+
+impl Entity {
+    /// The row with `id` as JSON, or `None` when it does not exist.
+    pub async fn snapshot(
+        self,
+        conn: &mut PgConnection,
+        id: Uuid,
+    ) -> Result<Option<Value>, ServiceError> {
+        let redacted: Vec<&str> = self.redacted.to_vec();
+        sqlx::query_scalar(&format!(
+            "SELECT to_jsonb(t) - $2::text[] FROM {} t WHERE id = $1",
+            self.table
+        ))
+        .bind(id)
+        .bind(redacted)
+        .fetch_optional(conn)
+        .await
+        .map_err(internal)
+    }
+}
+
+/// Client details of the API request being handled.
+#[derive(Debug, Clone, Default)]
+pub struct RequestContext {
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub request_id: Option<String>,
+    /// Set while an assistant acts for another employee.
+    pub delegation: Option<Delegation>,
+}
+
+/// An assistant acting for an employee who linked them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Delegation {
+    pub assistant_id: Uuid,
+    pub principal_id: Uuid,
+}
+
+tokio::task_local! {
+    static REQUEST_CONTEXT: RequestContext;
+}
+
+/// Runs `future` with `context` attached to the audit entries it records.
+pub async fn with_request_context<F: Future>(context: RequestContext, future: F) -> F::Output {
+    REQUEST_CONTEXT.scope(context, future).await
+}
+
+/// Runs `future` with `delegation`'s assistant acting for its principal.
+pub async fn acting_for<F: Future>(delegation: Delegation, future: F) -> F::Output {
+    let context = RequestContext {
+        delegation: Some(delegation),
+        ..current_context()
+    };
+    REQUEST_CONTEXT.scope(context, future).await
+}
+
+/// The assistant acting for `employee_id` in the current request, if any.
+pub fn assistant_for(employee_id: Uuid) -> Option<Uuid> {
+    current_context()
+        .delegation
+        .filter(|delegation| delegation.principal_id == employee_id)
+        .map(|delegation| delegation.assistant_id)
+}
+
+fn current_context() -> RequestContext {
+    REQUEST_CONTEXT
+        .try_with(RequestContext::clone)
+        .unwrap_or_default()
+}
+
+/// Records a change to `entity`'s row `id` made by `performed_by`, with the
+/// row's snapshot from before the change. Entries recorded outside an API
+/// request, such as by background jobs, have no client details.
+pub async fn record_change(
+    conn: &mut PgConnection,
+    performed_by: Option<Uuid>,
+    entity: Entity,
+    id: Uuid,
+    event_type: &str,
+    old_value: Option<Value>,
+) -> Result<(), ServiceError> {
+    let new_value = entity.snapshot(conn, id).await?;
+    record(
+        conn,
+        performed_by,
+        entity.entity_type,
+        id,
+        event_type,
+        old_value,
+        new_value,
+    )
+    .await
+}
+
+/// Records a change with explicit values, for rows without a UUID key.
+pub async fn record(
+    conn: &mut PgConnection,
+    performed_by: Option<Uuid>,
+    entity_type: &str,
+    entity_id: Uuid,
+    event_type: &str,
+    old_value: Option<Value>,
+    new_value: Option<Value>,
+) -> Result<(), ServiceError> {
+    let context = current_context();
+    let (performed_by, on_behalf_of) = match context.delegation {
+        Some(delegation) if performed_by == Some(delegation.principal_id) => {
+            (Some(delegation.assistant_id), Some(delegation.principal_id))
+        }
+        _ => (performed_by, None),
+    };
+
+    // Writers of one entity's entries take turns until their transactions
+    // commit, so each links to the latest entry; the unique chain index
+    // rejects a duplicate sequence from a caller outside a transaction.
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1 || ':' || $2::text, 0))")
+        .bind(entity_type)
+        .bind(entity_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(internal)?;
+    let latest: Option<(i64, String)> = sqlx::query_as(
+        "SELECT chain_sequence, signature_hash FROM audit_logs
+         WHERE entity_type = $1 AND entity_id = $2
+         ORDER BY chain_sequence DESC
+         LIMIT 1",
+    )
+    .bind(entity_type)
+    .bind(entity_id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(internal)?;
+    let (chain_sequence, previous_hash) = match latest {
+        Some((sequence, hash)) => (sequence + 1, hash),
+        None => (1, GENESIS_HASH.to_string()),
+    };
+
+    let mut entry = AuditLog {
+        id: Uuid::new_v4(),
+        entity_type: entity_type.to_string(),
+        entity_id,
+        event_type: event_type.to_string(),
+        old_value,
+        new_value,
+        performed_by,
+        on_behalf_of,
+        // Postgres keeps microseconds; the hash must match the stored value.
+        performed_at: Utc::now().trunc_subsecs(6),
+        ip_address: context.ip_address,
+        user_agent: context.user_agent,
+        request_id: context.request_id,
+        chain_sequence,
+        previous_hash: Some(previous_hash),
+        signature_hash: String::new(),
+    };
+    entry.signature_hash = signature_hash(&entry);
+
+    sqlx::query(
+        "INSERT INTO audit_logs
+            (id, entity_type, entity_id, event_type, old_value, new_value, performed_by,
+             on_behalf_of, performed_at, ip_address, user_agent, request_id, chain_sequence,
+             previous_hash, signature_hash)
+         VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,$13,$14,$15)",
+    )
+    .bind(entry.id)
+    .bind(&entry.entity_type)
+    .bind(entry.entity_id)
+    .bind(&entry.event_type)
+    .bind(&entry.old_value)
+    .bind(&entry.new_value)
+    .bind(entry.performed_by)
+    .bind(entry.on_behalf_of)
+    .bind(entry.performed_at)
+    .bind(&entry.ip_address)
+    .bind(&entry.user_agent)
+    .bind(&entry.request_id)
+    .bind(entry.chain_sequence)
+    .bind(&entry.previous_hash)
+    .bind(&entry.signature_hash)
+    .execute(conn)
+    .await
+    .map_err(internal)?;
+    Ok(())
+}
+
+/// Hex SHA-256 of the entry's fields, other than the hash itself, as a JSON
+/// object with sorted keys and `performed_at` in RFC 3339 with microseconds.
+/// `chain_sequence` and `previous_hash` are only covered on chained entries,
+/// and `on_behalf_of` only when set, so entries recorded before either
+/// existed keep their hashes.
+pub fn signature_hash(entry: &AuditLog) -> String {
+    let mut canonical = json!({
+        "id": entry.id,
+        "entity_type": entry.entity_type,
+        "entity_id": entry.entity_id,
+        "event_type": entry.event_type,
+        "old_value": entry.old_value,
+        "new_value": entry.new_value,
+        "performed_by": entry.performed_by,
+        "performed_at": rfc3339_micros(entry.performed_at),
+        "ip_address": entry.ip_address,
+        "user_agent": entry.user_agent,
+        "request_id": entry.request_id,
+    });
+    if let Some(previous_hash) = &entry.previous_hash {
+        canonical["chain_sequence"] = json!(entry.chain_sequence);
+        canonical["previous_hash"] = json!(previous_hash);
+    }
+    if let Some(on_behalf_of) = entry.on_behalf_of {
+        canonical["on_behalf_of"] = json!(on_behalf_of);
+    }
+    Sha256::digest(canonical.to_string().as_bytes())
+        .iter()
+        .fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+/// Parameters accepted by `GET /admin/audit/verify`; without them, every
+/// chain is checked.
+#[derive(Debug, Default, Deserialize)]
+pub struct VerifyQuery {
+    pub entity_type: Option<String>,
+    pub entity_id: Option<Uuid>,
+}
+
+/// Outcome of checking audit chains.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ChainVerification {
+    pub entries_checked: i64,
+    pub chains_checked: i64,
+    /// Chains whose oldest entries were removed by data retention.
+    pub trimmed_chains: i64,
+    pub problem_count: i64,
+    /// The first problems found, in chain order.
+    pub problems: Vec<ChainProblem>,
+}
+
+impl ChainVerification {
+    pub fn is_intact(&self) -> bool {
+        self.problem_count == 0
+    }
+}
+
+/// An entry at which a chain no longer holds.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ChainProblem {
+    pub entity_type: String,
+    pub entity_id: Uuid,
+    pub audit_log_id: Uuid,
+    pub chain_sequence: i64,
+    pub kind: ChainProblemKind,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainProblemKind {
+    /// The entry no longer matches its `signature_hash`.
+    Tampered,
+    /// Entries between the previous one and this one are missing.
+    Gap,
+    /// `previous_hash` is not the hash of the entry before it.
+    BrokenLink,
+    /// The entry is not chained although the one before it is.
+    Unchained,
+}
+
+/// Checks entries one at a time, sorted by entity and `chain_sequence`.
+#[derive(Debug, Default)]
+struct ChainVerifier {
+    verification: ChainVerification,
+    previous: Option<AuditLog>,
+}
+
+impl ChainVerifier {
+    fn push(&mut self, entry: AuditLog) {
+        self.verification.entries_checked += 1;
+        let previous = self.previous.take().filter(|previous| {
+            previous.entity_type == entry.entity_type && previous.entity_id == entry.entity_id
+        });
+
+        if signature_hash(&entry) != entry.signature_hash {
+            self.problem(&entry, ChainProblemKind::Tampered);
+        }
+        match previous {
+            None => {
+                self.verification.chains_checked += 1;
+                if entry.chain_sequence > 1 {
+                    self.verification.trimmed_chains += 1;
+                } else if entry
+                    .previous_hash
+                    .as_deref()
+                    .is_some_and(|hash| hash != GENESIS_HASH)
+                {
+                    self.problem(&entry, ChainProblemKind::BrokenLink);
+                }
+            }
+            Some(previous) if entry.chain_sequence != previous.chain_sequence + 1 => {
+                self.problem(&entry, ChainProblemKind::Gap);
+            }
+            Some(previous) => match &entry.previous_hash {
+                Some(hash) if *hash != previous.signature_hash => {
+                    self.problem(&entry, ChainProblemKind::BrokenLink);
+                }
+                None if previous.previous_hash.is_some() => {
+                    self.problem(&entry, ChainProblemKind::Unchained);
+                }
+                _ => {}
+            },
+        }
+        self.previous = Some(entry);
+    }
+
+    fn problem(&mut self, entry: &AuditLog, kind: ChainProblemKind) {
+        self.verification.problem_count += 1;
+        if self.verification.problems.len() < MAX_REPORTED_PROBLEMS {
+            self.verification.problems.push(ChainProblem {
+                entity_type: entry.entity_type.clone(),
+                entity_id: entry.entity_id,
+                audit_log_id: entry.id,
+                chain_sequence: entry.chain_sequence,
+                kind,
+            });
+        }
+    }
+}
+
+/// Checks the audit chains matching `query`, reading entries as a stream so
+/// the whole log is never held in memory.
+pub async fn verify_chains(
+    pool: &PgPool,
+    query: &VerifyQuery,
+) -> Result<ChainVerification, ServiceError> {
+    let mut entries = sqlx::query_as::<_, AuditLog>(
+        "SELECT id, entity_type, entity_id, event_type, old_value, new_value, performed_by,
+                on_behalf_of, performed_at, ip_address, user_agent, request_id, chain_sequence,
+                previous_hash, signature_hash
+         FROM audit_logs
+         WHERE ($1::text IS NULL OR entity_type = $1)
+           AND ($2::uuid IS NULL OR entity_id = $2)
+         ORDER BY entity_type, entity_id, chain_sequence",
+    )
+    .bind(&query.entity_type)
+    .bind(query.entity_id)
+    .fetch(pool);
+
+    let mut verifier = ChainVerifier::default();
+    while let Some(entry) = entries.try_next().await.map_err(internal)? {
+        verifier.push(entry);
+    }
+    Ok(verifier.verification)
+}
+
+/// Service exposing audit chain verification to administrators.
+pub struct AuditService {
+    state: Arc<AppState>,
+}
+
+impl AuditService {
+    /// Constructs the service from shared application state.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// Checks the audit chains matching `query`. The audit log spans every
+    /// organization, so only administrators of the operating organization
+    /// may verify it.
+    pub async fn verify(
+        &self,
+        actor: &AuthenticatedUser,
+        query: &VerifyQuery,
+    ) -> Result<ChainVerification, ServiceError> {
+        if actor.role != Role::Admin || !actor.is_operator() {
+            return Err(ServiceError::Forbidden);
+        }
+        verify_chains(&self.state.read_pool, query).await
+    }
+}
+
+fn rfc3339_micros(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(chrono::SecondsFormat::Micros, true)
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn hash_covers_every_field_but_itself() {
+        let mut entry = AuditLog {
+            id: Uuid::nil(),
+            entity_type: "budget".into(),
+            entity_id: Uuid::nil(),
+            event_type: UPDATED.into(),
+            old_value: Some(json!({ "amount_cents": 100 })),
+            new_value: Some(json!({ "amount_cents": 200 })),
+            performed_by: None,
+            on_behalf_of: None,
+            performed_at: Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap(),
+            ip_address: Some("203.0.113.7".into()),
+            user_agent: None,
+            request_id: Some("req-1".into()),
+            chain_sequence: 1,
+            previous_hash: Some(GENESIS_HASH.into()),
+            signature_hash: String::new(),
+        };
+        let hash = signature_hash(&entry);
+        assert_eq!(hash.len(), 64);
+
+        entry.signature_hash = "ignored".into();
+        assert_eq!(signature_hash(&entry), hash);
+
+        entry.new_value = Some(json!({ "amount_cents": 2_000 }));
+        assert_ne!(signature_hash(&entry), hash);
+    }
+
+    #[test]
+    fn chain_fields_are_only_hashed_on_chained_entries() {
+        let mut entry = chain(Uuid::nil(), 1).remove(0);
+        let chained = signature_hash(&entry);
+        entry.previous_hash = Some("f".repeat(64));
+        assert_ne!(signature_hash(&entry), chained);
+
+        entry.previous_hash = None;
+        let legacy = signature_hash(&entry);
+        entry.chain_sequence = 7;
+        assert_eq!(signature_hash(&entry), legacy);
+    }
+
+    #[test]
+    fn on_behalf_of_is_only_hashed_when_set() {
+        let mut entry = chain(Uuid::nil(), 1).remove(0);
+        let own = signature_hash(&entry);
+        entry.on_behalf_of = Some(Uuid::from_u128(7));
+        let delegated = signature_hash(&entry);
+        assert_ne!(delegated, own);
+
+        entry.on_behalf_of = Some(Uuid::from_u128(8));
+        assert_ne!(signature_hash(&entry), delegated);
+    }
+
+    #[tokio::test]
+    async fn delegation_applies_to_its_principal_only() {
+        let delegation = Delegation {
+            assistant_id: Uuid::from_u128(1),
+            principal_id: Uuid::from_u128(2),
+        };
+        assert_eq!(assistant_for(delegation.principal_id), None);
+        acting_for(delegation, async {
+            assert_eq!(
+                assistant_for(delegation.principal_id),
+                Some(delegation.assistant_id)
+            );
+            assert_eq!(assistant_for(Uuid::from_u128(3)), None);
+        })
+        .await;
+    }
+
+    #[test]
+    fn intact_and_trimmed_chains_have_no_problems() {
+        let mut legacy = chain(Uuid::from_u128(1), 2);
+        for entry in &mut legacy {
+            entry.previous_hash = None;
+            entry.signature_hash = signature_hash(entry);
+        }
+        let mut continued = chain(Uuid::from_u128(1), 4).split_off(2);
+        continued[0].previous_hash = Some(legacy[1].signature_hash.clone());
+        continued[0].signature_hash = signature_hash(&continued[0]);
+        relink(&mut continued);
+        let trimmed = chain(Uuid::from_u128(2), 5).split_off(3);
+
+        let verification = verify(legacy.into_iter().chain(continued).chain(trimmed));
+        assert!(verification.is_intact(), "{:?}", verification.problems);
+        assert_eq!(verification.entries_checked, 6);
+        assert_eq!(verification.chains_checked, 2);
+        assert_eq!(verification.trimmed_chains, 1);
+    }
+
+    #[test]
+    fn reports_tampered_missing_relinked_and_unchained_entries() {
+        let kinds = |entries: Vec<AuditLog>| -> Vec<(i64, ChainProblemKind)> {
+            verify(entries)
+                .problems
+                .into_iter()
+                .map(|problem| (problem.chain_sequence, problem.kind))
+                .collect()
+        };
+
+        let mut tampered = chain(Uuid::nil(), 3);
+        tampered[1].new_value = Some(json!({ "amount_cents": 1 }));
+        assert_eq!(kinds(tampered), vec![(2, ChainProblemKind::Tampered)]);
+
+        let mut missing = chain(Uuid::nil(), 3);
+        missing.remove(1);
+        assert_eq!(kinds(missing), vec![(3, ChainProblemKind::Gap)]);
+
+        // Rewriting an entry with a fresh hash breaks the next entry's link.
+        let mut rewritten = chain(Uuid::nil(), 3);
+        rewritten[1].new_value = Some(json!({ "amount_cents": 1 }));
+        rewritten[1].signature_hash = signature_hash(&rewritten[1]);
+        assert_eq!(kinds(rewritten), vec![(3, ChainProblemKind::BrokenLink)]);
+
+        let mut forged_start = chain(Uuid::nil(), 1);
+        forged_start[0].previous_hash = Some("f".repeat(64));
+        forged_start[0].signature_hash = signature_hash(&forged_start[0]);
+        assert_eq!(kinds(forged_start), vec![(1, ChainProblemKind::BrokenLink)]);
+
+        let mut unchained = chain(Uuid::nil(), 2);
+        unchained[1].previous_hash = None;
+        unchained[1].signature_hash = signature_hash(&unchained[1]);
+        assert_eq!(kinds(unchained), vec![(2, ChainProblemKind::Unchained)]);
+    }
+
+    /// `len` correctly chained entries for `entity_id`.
+    fn chain(entity_id: Uuid, len: i64) -> Vec<AuditLog> {
+        let mut entries: Vec<AuditLog> = (1..=len)
+            .map(|sequence| AuditLog {
+                id: Uuid::from_u128(sequence as u128),
+                entity_type: "budget".into(),
+                entity_id,
+                event_type: UPDATED.into(),
+                old_value: None,
+                new_value: Some(json!({ "amount_cents": sequence })),
+                performed_by: None,
+                on_behalf_of: None,
+                performed_at: Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap(),
+                ip_address: None,
+                user_agent: None,
+                request_id: None,
+                chain_sequence: sequence,
+                previous_hash: Some(GENESIS_HASH.into()),
+                signature_hash: String::new(),
+            })
+            .collect();
+        entries[0].signature_hash = signature_hash(&entries[0]);
+        relink(&mut entries);
+        entries
+    }
+
+    /// Links every entry after the first to the one before it.
+    fn relink(entries: &mut [AuditLog]) {
+        for index in 1..entries.len() {
+            entries[index].previous_hash = Some(entries[index - 1].signature_hash.clone());
+            entries[index].signature_hash = signature_hash(&entries[index]);
+        }
+    }
+
+    fn verify(entries: impl IntoIterator<Item = AuditLog>) -> ChainVerification {
+        let mut verifier = ChainVerifier::default();
+        entries.into_iter().for_each(|entry| verifier.push(entry));
+        verifier.verification
+    }
+}
@@ -0,0 +1,410 @@
+//! Employees' reimbursement bank accounts.
+//!
+//! Employees keep their own account through `GET`/`PUT /auth/me/bank-account`.
+//! The routing and account numbers are sealed with the deployment's banking
+//! key (`infrastructure::encryption`) before they are stored and are never
+//! returned in full: responses, audit entries, and the payment run finance
+//! reviews before generating the ACH file (`GET /finance/batches/:id/payments`)
+//! carry the last four digits only. The endpoints are disabled while no
+//! banking key is configured.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgRow, PgConnection, Row};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    domain::models::Role,
+    infrastructure::{
+        auth::AuthenticatedUser,
+        encryption::{FieldCipher, Sealed},
+        state::AppState,
+    },
+};
+
+use super::{audit, errors::ServiceError, notifications};
+
+/// Notification kind queued for the employee whenever their bank account is
+/// set, so a change they did not make does not go unnoticed.
+pub const BANK_ACCOUNT_CHANGED_KIND: &str = "bank_account_changed";
+
+/// Kind of deposit account, as named in the ACH entry.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountType {
+    Checking,
+    Savings,
+}
+
+impl AccountType {
+    fn as_str(self) -> &'static str {
+        match self {
+            AccountType::Checking => "checking",
+            AccountType::Savings => "savings",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "checking" => Some(AccountType::Checking),
+            "savings" => Some(AccountType::Savings),
+            _ => None,
+        }
+    }
+}
+
+/// Payload accepted by `PUT /auth/me/bank-account`. The account number is
+/// entered twice so a typo does not send reimbursements elsewhere.
+#[derive(Deserialize)]
+pub struct SetBankAccountRequest {
+    pub account_holder: String,
+    pub account_type: AccountType,
+    pub routing_number: String,
+    pub account_number: String,
+    pub account_number_confirmation: String,
+}
+
+/// A bank account as the API shows it.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct MaskedBankAccount {
+    pub account_holder: String,
+    pub account_type: AccountType,
+    pub routing_number_last4: String,
+    pub account_number_last4: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The reimbursements owed for a batch, one payment per employee and
+/// currency.
+#[derive(Debug, Clone, Serialize)]
+pub struct PaymentRun {
+    pub batch_id: Uuid,
+    pub batch_reference: String,
+    pub payments: Vec<Payment>,
+    /// Employees owed money without a usable bank account; the ACH file
+    /// cannot be generated until they are resolved.
+    pub missing_accounts: usize,
+}
+
+/// One employee's reimbursement in a payment run.
+#[derive(Debug, Clone, Serialize)]
+pub struct Payment {
+    pub employee_id: Uuid,
+    pub hr_identifier: String,
+    pub currency: String,
+    pub amount_cents: i64,
+    pub report_ids: Vec<Uuid>,
+    /// `None` when the employee has not set an account or it cannot be
+    /// opened with the configured keys.
+    pub bank_account: Option<MaskedBankAccount>,
+}
+
+/// The sealed part of an account.
+#[derive(Serialize, Deserialize)]
+struct AccountNumbers {
+    routing_number: String,
+    account_number: String,
+}
+
+/// Service behind the bank account and payment run routes.
+pub struct BankAccountService {
+    state: Arc<AppState>,
+}
+
+impl BankAccountService {
+    /// Constructs the service from shared application state.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// The actor's account, masked; `ServiceError::NotFound` when they have
+    /// not set one.
+    pub async fn get(&self, actor: &AuthenticatedUser) -> Result<MaskedBankAccount, ServiceError> {
+        let cipher = self.cipher()?;
+        let row = sqlx::query("SELECT * FROM employee_bank_accounts WHERE employee_id = $1")
+            .bind(actor.employee_id)
+            .fetch_optional(&self.state.pool)
+            .await
+            .map_err(internal)?
+            .ok_or(ServiceError::NotFound)?;
+        open_account(cipher, &row)
+    }
+
+    /// Replaces the actor's account after checking the routing number's
+    /// checksum and that both account number entries match.
+    pub async fn set(
+        &self,
+        actor: &AuthenticatedUser,
+        payload: SetBankAccountRequest,
+    ) -> Result<MaskedBankAccount, ServiceError> {
+        let cipher = self.cipher()?;
+        let account_holder = payload.account_holder.trim();
+        if account_holder.is_empty() {
+            return Err(ServiceError::Validation(
+                "account_holder is required".into(),
+            ));
+        }
+        let routing_number = digits(&payload.routing_number);
+        if !routing_number_is_valid(&routing_number) {
+            return Err(ServiceError::Validation(
+                "routing_number is not a valid ABA routing number".into(),
+            ));
+        }
+        let account_number = digits(&payload.account_number);
+        if !(4..=17).contains(&account_number.len()) {
+            return Err(ServiceError::Validation(
+                "account_number must have 4 to 17 digits".into(),
+            ));
+        }
+        if account_number != digits(&payload.account_number_confirmation) {
+            return Err(ServiceError::Validation(
+                "account_number_confirmation does not match account_number".into(),
+            ));
+        }
+
+        let numbers = serde_json::to_vec(&AccountNumbers {
+            routing_number,
+            account_number,
+        })
+        .map_err(|err| ServiceError::Internal(err.to_string()))?;
+        let sealed = cipher
+            .seal(&numbers, actor.employee_id.as_bytes())
+            .map_err(|err| ServiceError::Internal(err.to_string()))?;
+
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let existing: Option<Uuid> = sqlx::query_scalar(
+            "SELECT id FROM employee_bank_accounts WHERE employee_id = $1 FOR UPDATE",
+        )
+        .bind(actor.employee_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(internal)?;
+        let id = existing.unwrap_or_else(Uuid::new_v4);
+        let before = audit::BANK_ACCOUNT.snapshot(&mut tx, id).await?;
+        let row = sqlx::query(
+            "INSERT INTO employee_bank_accounts
+                 (id, employee_id, account_holder, account_type, key_id, nonce, ciphertext,
+                  created_at, updated_at)
+             VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$8)
+             ON CONFLICT (employee_id) DO UPDATE
+             SET account_holder = EXCLUDED.account_holder,
+                 account_type = EXCLUDED.account_type,
+                 key_id = EXCLUDED.key_id,
+                 nonce = EXCLUDED.nonce,
+                 ciphertext = EXCLUDED.ciphertext,
+                 updated_at = EXCLUDED.updated_at
+             RETURNING *",
+        )
+        .bind(id)
+        .bind(actor.employee_id)
+        .bind(account_holder)
+        .bind(payload.account_type.as_str())
+        .bind(&sealed.key_id)
+        .bind(&sealed.nonce)
+        .bind(&sealed.ciphertext)
+        .bind(Utc::now())
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(internal)?;
+        let event = if before.is_some() {
+            audit::UPDATED
+        } else {
+            audit::CREATED
+        };
+        audit::record_change(
+            &mut tx,
+            Some(actor.employee_id),
+            audit::BANK_ACCOUNT,
+            id,
+            event,
+            before,
+        )
+        .await?;
+
+        let account = open_account(cipher, &row)?;
+        notifications::enqueue(
+            &mut *tx,
+            actor.employee_id,
+            BANK_ACCOUNT_CHANGED_KIND,
+            serde_json::json!({
+                "account_type": account.account_type,
+                "account_number_last4": account.account_number_last4,
+                "updated_at": account.updated_at,
+            }),
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        Ok(account)
+    }
+
+    /// The payments owed for a batch of the finance user's organization,
+    /// with each employee's account masked, for review before the ACH file
+    /// is generated.
+    pub async fn payment_run(
+        &self,
+        actor: &AuthenticatedUser,
+        batch_id: Uuid,
+    ) -> Result<PaymentRun, ServiceError> {
+        if actor.role != Role::Finance {
+            return Err(ServiceError::Forbidden);
+        }
+        let cipher = self.cipher()?;
+        let batch_reference: String = sqlx::query_scalar(
+            "SELECT batch_reference FROM netsuite_batches WHERE id = $1 AND org_id = $2",
+        )
+        .bind(batch_id)
+        .bind(actor.org_id)
+        .fetch_optional(&self.state.read_pool)
+        .await
+        .map_err(internal)?
+        .ok_or(ServiceError::NotFound)?;
+
+        let mut conn = self.state.read_pool.acquire().await.map_err(internal)?;
+        let payments = load_payments(&mut conn, cipher, batch_id).await?;
+        let missing_accounts = payments
+            .iter()
+            .filter(|payment| payment.bank_account.is_none())
+            .count();
+        Ok(PaymentRun {
+            batch_id,
+            batch_reference,
+            payments,
+            missing_accounts,
+        })
+    }
+
+    fn cipher(&self) -> Result<&FieldCipher, ServiceError> {
+        self.state
+            .bank_cipher
+            .as_ref()
+            .ok_or(ServiceError::NotFound)
+    }
+}
+
+async fn load_payments(
+    conn: &mut PgConnection,
+    cipher: &FieldCipher,
+    batch_id: Uuid,
+) -> Result<Vec<Payment>, ServiceError> {
+    let rows = sqlx::query(
+        "SELECT r.employee_id, e.hr_identifier, r.currency,
+                SUM(r.total_reimbursable_cents)::BIGINT AS amount_cents,
+                ARRAY_AGG(r.id ORDER BY r.id) AS report_ids,
+                a.account_holder, a.account_type, a.key_id, a.nonce, a.ciphertext,
+                a.updated_at
+         FROM expense_reports r
+         JOIN employees e ON e.id = r.employee_id
+         LEFT JOIN employee_bank_accounts a ON a.employee_id = r.employee_id
+         WHERE r.id IN (SELECT report_id FROM journal_lines WHERE batch_id = $1)
+           AND r.total_reimbursable_cents > 0
+         GROUP BY r.employee_id, e.hr_identifier, r.currency, a.id
+         ORDER BY e.hr_identifier, r.currency",
+    )
+    .bind(batch_id)
+    .fetch_all(conn)
+    .await
+    .map_err(internal)?;
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let employee_id: Uuid = row.get("employee_id");
+            let bank_account = row.get::<Option<String>, _>("key_id").and_then(|_| {
+                match open_account(cipher, row) {
+                    Ok(account) => Some(account),
+                    Err(err) => {
+                        warn!(%employee_id, error = %err, "bank account cannot be opened");
+                        None
+                    }
+                }
+            });
+            Payment {
+                employee_id,
+                hr_identifier: row.get("hr_identifier"),
+                currency: row.get("currency"),
+                amount_cents: row.get("amount_cents"),
+                report_ids: row.get("report_ids"),
+                bank_account,
+            }
+        })
+        .collect())
+}
+
+/// Opens a row's sealed numbers and masks them.
+fn open_account(cipher: &FieldCipher, row: &PgRow) -> Result<MaskedBankAccount, ServiceError> {
+    let employee_id: Uuid = row.get("employee_id");
+    let sealed = Sealed {
+        key_id: row.get("key_id"),
+        nonce: row.get("nonce"),
+        ciphertext: row.get("ciphertext"),
+    };
+    let numbers: AccountNumbers = cipher
+        .open(&sealed, employee_id.as_bytes())
+        .and_then(|plaintext| Ok(serde_json::from_slice(&plaintext)?))
+        .map_err(|err| ServiceError::Internal(format!("bank account of {employee_id}: {err}")))?;
+    let account_type = row.get::<&str, _>("account_type");
+    Ok(MaskedBankAccount {
+        account_holder: row.get("account_holder"),
+        account_type: AccountType::parse(account_type).ok_or_else(|| {
+            ServiceError::Internal(format!("unknown account type `{account_type}`"))
+        })?,
+        routing_number_last4: last4(&numbers.routing_number),
+        account_number_last4: last4(&numbers.account_number),
+        updated_at: row.get("updated_at"),
+    })
+}
+
+/// `value` without the spaces and dashes people type into account numbers;
+/// any other character is kept so the number fails validation.
+fn digits(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect()
+}
+
+fn last4(value: &str) -> String {
+    value[value.len().saturating_sub(4)..].to_string()
+}
+
+/// Whether `value` is nine digits passing the ABA checksum
+/// (3·d1 + 7·d2 + d3 + 3·d4 + 7·d5 + d6 + 3·d7 + 7·d8 + d9 ≡ 0 mod 10).
+fn routing_number_is_valid(value: &str) -> bool {
+    if value.len() != 9 || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return false;
+    }
+    let sum: u32 = value
+        .bytes()
+        .zip([3, 7, 1].iter().cycle())
+        .map(|(b, weight)| u32::from(b - b'0') * weight)
+        .sum();
+    sum % 10 == 0
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_aba_routing_numbers() {
+        assert!(routing_number_is_valid("021000021"));
+        assert!(routing_number_is_valid("011401533"));
+        assert!(!routing_number_is_valid("021000022"));
+        assert!(!routing_number_is_valid("02100002"));
+        assert!(!routing_number_is_valid("02100002a"));
+    }
+
+    #[test]
+    fn normalizes_and_masks_account_numbers() {
+        assert_eq!(digits(" 1234-5678 90 "), "1234567890");
+        assert_eq!(digits("12a4"), "12a4");
+        assert_eq!(last4("1234567890"), "7890");
+        assert_eq!(last4("123"), "123");
+    }
+}
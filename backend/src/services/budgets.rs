@@ -0,0 +1,352 @@
+//! Budget maintenance and cumulative spend lookups.
+//!
+//! Administrators manage monthly, quarterly, and fiscal-year budgets through
+//! `/policy/budgets`; finance and managers can read them. `load_usage` gathers
+//! what `domain::budget::evaluate_budgets` needs to check a report: the
+//! budgets covering it and the spend already committed in each period.
+
+use std::sync::Arc;
+
+use chrono::{NaiveDate, Utc};
+use serde::Deserialize;
+use sqlx::{postgres::PgRow, PgPool, Row};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        budget::{report_usage, BudgetUsage},
+        models::{Budget, BudgetPeriod, ExpenseCategory, ExpenseItem, ReportStatus, Role},
+        policy::POLICY_CURRENCY,
+    },
+    infrastructure::{auth::AuthenticatedUser, state::AppState},
+};
+
+use super::{audit, errors::ServiceError};
+
+/// Statuses whose spend counts against budgets and duplicate checks.
+pub(crate) const COMMITTED_STATUSES: [ReportStatus; 4] = [
+    ReportStatus::Submitted,
+    ReportStatus::ExceptionReview,
+    ReportStatus::ManagerApproved,
+    ReportStatus::FinanceFinalized,
+];
+
+/// Payload accepted by `POST /policy/budgets` and `PUT /policy/budgets/:id`.
+#[derive(Debug, Deserialize)]
+pub struct BudgetRequest {
+    pub name: String,
+    #[serde(default)]
+    pub category: Option<ExpenseCategory>,
+    #[serde(default)]
+    pub department: Option<String>,
+    #[serde(default)]
+    pub per_employee: bool,
+    pub period: BudgetPeriod,
+    pub amount_cents: i64,
+    /// Defaults to today.
+    #[serde(default)]
+    pub active_from: Option<NaiveDate>,
+    #[serde(default)]
+    pub active_to: Option<NaiveDate>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Service managing the `budgets` table.
+pub struct BudgetService {
+    state: Arc<AppState>,
+}
+
+impl BudgetService {
+    /// Constructs the service from shared application state.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// Lists every budget, including disabled ones.
+    pub async fn list(&self, actor: &AuthenticatedUser) -> Result<Vec<Budget>, ServiceError> {
+        if actor.role == Role::Employee {
+            return Err(ServiceError::Forbidden);
+        }
+        sqlx::query(
+            "SELECT * FROM budgets
+             ORDER BY department NULLS FIRST, category NULLS FIRST, period, name, id",
+        )
+        .fetch_all(&self.state.pool)
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .map(map_budget)
+        .collect()
+    }
+
+    /// Adds a budget. Restricted to administrators.
+    pub async fn create(
+        &self,
+        actor: &AuthenticatedUser,
+        payload: BudgetRequest,
+    ) -> Result<Budget, ServiceError> {
+        require_admin(actor)?;
+        let (name, department, active_from) = validate(&payload)?;
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let row = sqlx::query(
+            "INSERT INTO budgets
+                (id, name, category, department, period, amount_cents, active_from, active_to,
+                 enabled, updated_by, updated_at, per_employee)
+             VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12)
+             RETURNING *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(name)
+        .bind(payload.category.map(|category| category.as_str()))
+        .bind(department)
+        .bind(payload.period.as_str())
+        .bind(payload.amount_cents)
+        .bind(active_from)
+        .bind(payload.active_to)
+        .bind(payload.enabled)
+        .bind(actor.employee_id)
+        .bind(Utc::now())
+        .bind(payload.per_employee)
+        .fetch_one(tx.as_mut())
+        .await
+        .map_err(internal)?;
+        let budget = map_budget(row)?;
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::BUDGET,
+            budget.id,
+            audit::CREATED,
+            None,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        Ok(budget)
+    }
+
+    /// Replaces a budget. Restricted to administrators.
+    pub async fn update(
+        &self,
+        actor: &AuthenticatedUser,
+        budget_id: Uuid,
+        payload: BudgetRequest,
+    ) -> Result<Budget, ServiceError> {
+        require_admin(actor)?;
+        let (name, department, active_from) = validate(&payload)?;
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let before = audit::BUDGET.snapshot(tx.as_mut(), budget_id).await?;
+        let row = sqlx::query(
+            "UPDATE budgets
+             SET name = $2, category = $3, department = $4, period = $5, amount_cents = $6,
+                 active_from = $7, active_to = $8, enabled = $9, updated_by = $10,
+                 updated_at = $11, per_employee = $12
+             WHERE id = $1
+             RETURNING *",
+        )
+        .bind(budget_id)
+        .bind(name)
+        .bind(payload.category.map(|category| category.as_str()))
+        .bind(department)
+        .bind(payload.period.as_str())
+        .bind(payload.amount_cents)
+        .bind(active_from)
+        .bind(payload.active_to)
+        .bind(payload.enabled)
+        .bind(actor.employee_id)
+        .bind(Utc::now())
+        .bind(payload.per_employee)
+        .fetch_optional(tx.as_mut())
+        .await
+        .map_err(internal)?
+        .ok_or(ServiceError::NotFound)?;
+        let budget = map_budget(row)?;
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::BUDGET,
+            budget_id,
+            audit::UPDATED,
+            before,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        Ok(budget)
+    }
+
+    /// Deletes a budget. Restricted to administrators.
+    pub async fn delete(
+        &self,
+        actor: &AuthenticatedUser,
+        budget_id: Uuid,
+    ) -> Result<(), ServiceError> {
+        require_admin(actor)?;
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let before = audit::BUDGET.snapshot(tx.as_mut(), budget_id).await?;
+        if before.is_none() {
+            return Err(ServiceError::NotFound);
+        }
+        sqlx::query("DELETE FROM budgets WHERE id = $1")
+            .bind(budget_id)
+            .execute(tx.as_mut())
+            .await
+            .map_err(internal)?;
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::BUDGET,
+            budget_id,
+            audit::DELETED,
+            before,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        Ok(())
+    }
+}
+
+/// Loads the enabled budgets covering `report_id`'s items and fills in the
+/// spend other reports have committed in each budget period.
+///
+/// Committed spend comes from reports in `POLICY_CURRENCY` by employees in the
+/// budget's department (or anyone, for budgets without one), or only by
+/// `employee_id` for per-employee budgets, within `report_id`'s organization. Month and quarter budgets count
+/// submitted, approved, and finalized reports; fiscal-year budgets keep a
+/// running total of finalized reports only.
+pub async fn load_usage<'a>(
+    pool: &PgPool,
+    report_id: Uuid,
+    employee_id: Uuid,
+    department: Option<&str>,
+    items: &[ExpenseItem],
+    budgets: &'a [Budget],
+) -> Result<Vec<BudgetUsage<'a>>, ServiceError> {
+    let mut usage = report_usage(items, department, budgets);
+    let committed: Vec<&str> = COMMITTED_STATUSES
+        .iter()
+        .map(ReportStatus::as_str)
+        .collect();
+    let finalized = vec![ReportStatus::FinanceFinalized.as_str()];
+    for entry in &mut usage {
+        let statuses = match entry.budget.period {
+            BudgetPeriod::FiscalYear => &finalized,
+            BudgetPeriod::Month | BudgetPeriod::Quarter => &committed,
+        };
+        entry.committed_cents = sqlx::query_scalar(
+            "SELECT COALESCE(SUM(i.amount_cents), 0)::BIGINT
+             FROM expense_items i
+             JOIN expense_reports r ON r.id = i.report_id
+             JOIN employees e ON e.id = r.employee_id
+             WHERE r.id <> $1
+               AND r.status::text = ANY($2)
+               AND i.deleted_at IS NULL
+               AND r.currency = $3
+               AND i.expense_date BETWEEN $4 AND $5
+               AND ($6::text IS NULL OR i.category::text = $6)
+               AND ($7::text IS NULL OR e.department = $7)
+               AND ($8::uuid IS NULL OR r.employee_id = $8)
+               AND r.org_id = (SELECT org_id FROM expense_reports WHERE id = $1)",
+        )
+        .bind(report_id)
+        .bind(statuses)
+        .bind(POLICY_CURRENCY.code())
+        .bind(entry.period_start)
+        .bind(entry.period_end)
+        .bind(entry.budget.category.map(|category| category.as_str()))
+        .bind(entry.budget.department.as_deref())
+        .bind(entry.budget.per_employee.then_some(employee_id))
+        .fetch_one(pool)
+        .await
+        .map_err(internal)?;
+    }
+    Ok(usage)
+}
+
+/// Loads the enabled budgets for `department` (plus department-wide ones).
+pub async fn applicable_budgets(
+    pool: &PgPool,
+    department: Option<&str>,
+) -> Result<Vec<Budget>, ServiceError> {
+    sqlx::query(
+        "SELECT * FROM budgets
+         WHERE enabled AND (department IS NULL OR department = $1)
+         ORDER BY name, id",
+    )
+    .bind(department)
+    .fetch_all(pool)
+    .await
+    .map_err(internal)?
+    .into_iter()
+    .map(map_budget)
+    .collect()
+}
+
+/// Returns the trimmed name and department and the effective `active_from`.
+fn validate(payload: &BudgetRequest) -> Result<(&str, Option<&str>, NaiveDate), ServiceError> {
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err(ServiceError::Validation("name is required".into()));
+    }
+    if payload.amount_cents <= 0 {
+        return Err(ServiceError::Validation(
+            "amount_cents must be greater than 0".into(),
+        ));
+    }
+    let department = payload
+        .department
+        .as_deref()
+        .map(str::trim)
+        .filter(|department| !department.is_empty());
+    let active_from = payload
+        .active_from
+        .unwrap_or_else(|| Utc::now().date_naive());
+    if payload.active_to.is_some_and(|end| end < active_from) {
+        return Err(ServiceError::Validation(
+            "active_to must be on or after active_from".into(),
+        ));
+    }
+    Ok((name, department, active_from))
+}
+
+/// Deployment-wide settings are maintained by administrators of the
+/// operating organization.
+fn require_admin(actor: &AuthenticatedUser) -> Result<(), ServiceError> {
+    if actor.role != Role::Admin || !actor.is_operator() {
+        return Err(ServiceError::Forbidden);
+    }
+    Ok(())
+}
+
+fn map_budget(row: PgRow) -> Result<Budget, ServiceError> {
+    let invalid = |column: &str, value: &str| {
+        ServiceError::Internal(format!("budgets.{column} has unknown value {value}"))
+    };
+    let category = row
+        .try_get::<Option<String>, _>("category")
+        .map_err(internal)?
+        .map(|value| ExpenseCategory::parse(&value).ok_or_else(|| invalid("category", &value)))
+        .transpose()?;
+    let period: String = row.try_get("period").map_err(internal)?;
+    Ok(Budget {
+        id: row.get("id"),
+        name: row.get("name"),
+        category,
+        department: row.get("department"),
+        per_employee: row.get("per_employee"),
+        period: BudgetPeriod::parse(&period).ok_or_else(|| invalid("period", &period))?,
+        amount_cents: row.get("amount_cents"),
+        active_from: row.get("active_from"),
+        active_to: row.get("active_to"),
+        enabled: row.get("enabled"),
+        updated_by: row.get("updated_by"),
+        updated_at: row.get("updated_at"),
+    })
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
@@ -0,0 +1,262 @@
+//! Department heads, the elevated approvers for policy exceptions.
+//!
+//! Administrators assign one head per `employees.department` of their
+//! organization through `/policy/department-heads`; managers, finance, and
+//! administrators can list their organization's heads. `services::approvals` routes a report with policy exceptions to its
+//! submitter's department head once the manager has approved it.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use serde::Deserialize;
+use sqlx::{postgres::PgRow, PgConnection, Row};
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{DepartmentHead, Role},
+    infrastructure::{auth::AuthenticatedUser, state::AppState},
+};
+
+use super::{audit, errors::ServiceError};
+
+/// Payload accepted by `POST /policy/department-heads` and
+/// `PUT /policy/department-heads/:id`.
+#[derive(Debug, Deserialize)]
+pub struct DepartmentHeadRequest {
+    pub department: String,
+    pub employee_id: Uuid,
+}
+
+/// Service managing the `department_heads` table.
+pub struct DepartmentHeadService {
+    state: Arc<AppState>,
+}
+
+impl DepartmentHeadService {
+    /// Constructs the service from shared application state.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// Lists the department heads of the actor's organization by department.
+    pub async fn list(
+        &self,
+        actor: &AuthenticatedUser,
+    ) -> Result<Vec<DepartmentHead>, ServiceError> {
+        if actor.role == Role::Employee {
+            return Err(ServiceError::Forbidden);
+        }
+        sqlx::query("SELECT * FROM department_heads WHERE org_id = $1 ORDER BY department")
+            .bind(actor.org_id)
+            .fetch_all(&self.state.pool)
+            .await
+            .map_err(internal)?
+            .into_iter()
+            .map(map_head)
+            .collect()
+    }
+
+    /// Assigns a department's head. Restricted to administrators; a
+    /// department that already has one is a conflict.
+    pub async fn create(
+        &self,
+        actor: &AuthenticatedUser,
+        payload: DepartmentHeadRequest,
+    ) -> Result<DepartmentHead, ServiceError> {
+        require_admin(actor)?;
+        let department = validate(&payload)?;
+        self.ensure_employee(actor, payload.employee_id).await?;
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let row = sqlx::query(
+            "INSERT INTO department_heads
+                (id, department, employee_id, updated_by, updated_at, org_id)
+             VALUES ($1,$2,$3,$4,$5,$6)
+             ON CONFLICT (org_id, department) DO NOTHING
+             RETURNING *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(department)
+        .bind(payload.employee_id)
+        .bind(actor.employee_id)
+        .bind(Utc::now())
+        .bind(actor.org_id)
+        .fetch_optional(tx.as_mut())
+        .await
+        .map_err(internal)?
+        .ok_or(ServiceError::Conflict)?;
+        let head = map_head(row)?;
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::DEPARTMENT_HEAD,
+            head.id,
+            audit::CREATED,
+            None,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        Ok(head)
+    }
+
+    /// Replaces a department head assignment. Restricted to administrators.
+    pub async fn update(
+        &self,
+        actor: &AuthenticatedUser,
+        head_id: Uuid,
+        payload: DepartmentHeadRequest,
+    ) -> Result<DepartmentHead, ServiceError> {
+        require_admin(actor)?;
+        let department = validate(&payload)?;
+        self.ensure_employee(actor, payload.employee_id).await?;
+        let taken: bool = sqlx::query_scalar(
+            "SELECT EXISTS (
+                 SELECT 1 FROM department_heads
+                 WHERE department = $1 AND id <> $2 AND org_id = $3
+             )",
+        )
+        .bind(department)
+        .bind(head_id)
+        .bind(actor.org_id)
+        .fetch_one(&self.state.pool)
+        .await
+        .map_err(internal)?;
+        if taken {
+            return Err(ServiceError::Conflict);
+        }
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let before = audit::DEPARTMENT_HEAD
+            .snapshot(tx.as_mut(), head_id)
+            .await?;
+        let row = sqlx::query(
+            "UPDATE department_heads
+             SET department = $2, employee_id = $3, updated_by = $4, updated_at = $5
+             WHERE id = $1 AND org_id = $6
+             RETURNING *",
+        )
+        .bind(head_id)
+        .bind(department)
+        .bind(payload.employee_id)
+        .bind(actor.employee_id)
+        .bind(Utc::now())
+        .bind(actor.org_id)
+        .fetch_optional(tx.as_mut())
+        .await
+        .map_err(internal)?
+        .ok_or(ServiceError::NotFound)?;
+        let head = map_head(row)?;
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::DEPARTMENT_HEAD,
+            head_id,
+            audit::UPDATED,
+            before,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        Ok(head)
+    }
+
+    /// Removes a department head. Restricted to administrators.
+    pub async fn delete(
+        &self,
+        actor: &AuthenticatedUser,
+        head_id: Uuid,
+    ) -> Result<(), ServiceError> {
+        require_admin(actor)?;
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let before = audit::DEPARTMENT_HEAD
+            .snapshot(tx.as_mut(), head_id)
+            .await?;
+        let result = sqlx::query("DELETE FROM department_heads WHERE id = $1 AND org_id = $2")
+            .bind(head_id)
+            .bind(actor.org_id)
+            .execute(tx.as_mut())
+            .await
+            .map_err(internal)?;
+        if result.rows_affected() == 0 {
+            return Err(ServiceError::NotFound);
+        }
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::DEPARTMENT_HEAD,
+            head_id,
+            audit::DELETED,
+            before,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        Ok(())
+    }
+
+    async fn ensure_employee(
+        &self,
+        actor: &AuthenticatedUser,
+        employee_id: Uuid,
+    ) -> Result<(), ServiceError> {
+        let known: bool = sqlx::query_scalar(
+            "SELECT EXISTS (
+                 SELECT 1 FROM employees
+                 WHERE id = $1 AND org_id = $2 AND deactivated_at IS NULL
+             )",
+        )
+        .bind(employee_id)
+        .bind(actor.org_id)
+        .fetch_one(&self.state.pool)
+        .await
+        .map_err(internal)?;
+        if !known {
+            return Err(ServiceError::Validation(
+                "employee_id does not match an active employee".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The head of `org_id`'s `department`, if one is assigned.
+pub async fn head_of(
+    conn: &mut PgConnection,
+    org_id: Uuid,
+    department: &str,
+) -> Result<Option<Uuid>, ServiceError> {
+    sqlx::query_scalar(
+        "SELECT employee_id FROM department_heads WHERE org_id = $1 AND department = $2",
+    )
+    .bind(org_id)
+    .bind(department)
+    .fetch_optional(conn)
+    .await
+    .map_err(internal)
+}
+
+/// Returns the trimmed department.
+fn validate(payload: &DepartmentHeadRequest) -> Result<&str, ServiceError> {
+    let department = payload.department.trim();
+    if department.is_empty() {
+        return Err(ServiceError::Validation("department is required".into()));
+    }
+    Ok(department)
+}
+
+fn require_admin(actor: &AuthenticatedUser) -> Result<(), ServiceError> {
+    if actor.role != Role::Admin {
+        return Err(ServiceError::Forbidden);
+    }
+    Ok(())
+}
+
+fn map_head(row: PgRow) -> Result<DepartmentHead, ServiceError> {
+    Ok(DepartmentHead {
+        id: row.try_get("id").map_err(internal)?,
+        department: row.try_get("department").map_err(internal)?,
+        employee_id: row.try_get("employee_id").map_err(internal)?,
+        updated_by: row.try_get("updated_by").map_err(internal)?,
+        updated_at: row.try_get("updated_at").map_err(internal)?,
+    })
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
@@ -0,0 +1,535 @@
+//! Departments and cost centers, the reference data employees and items are
+//! charged to.
+//!
+//! Each organization keeps its own through `/admin/departments` and
+//! `/admin/cost-centers`: every employee can list them, and administrators
+//! maintain them. Codes are unique within an organization, ignoring case. An
+//! employee's `department` is the code of the department `department_id`
+//! points at, so department heads and budgets keep working on it, and
+//! finalization exports the code as the journal line's department. Entries
+//! still referenced cannot be deleted; deactivating them stops new
+//! assignments instead.
+
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::Utc;
+use serde::Deserialize;
+use sqlx::{PgConnection, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{CostCenter, Department, Role},
+    infrastructure::{auth::AuthenticatedUser, state::AppState},
+};
+
+use super::{audit, errors::ServiceError, management_chain};
+
+/// Payload accepted by `POST /admin/departments` and
+/// `PUT /admin/departments/:id`.
+#[derive(Debug, Deserialize)]
+pub struct DepartmentRequest {
+    pub code: String,
+    pub name: String,
+    #[serde(default = "default_active")]
+    pub active: bool,
+}
+
+/// Payload accepted by `POST /admin/cost-centers` and
+/// `PUT /admin/cost-centers/:id`.
+#[derive(Debug, Deserialize)]
+pub struct CostCenterRequest {
+    pub code: String,
+    pub name: String,
+    #[serde(default)]
+    pub department_id: Option<Uuid>,
+    #[serde(default = "default_active")]
+    pub active: bool,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+/// Service managing the `departments` and `cost_centers` tables.
+pub struct DepartmentService {
+    state: Arc<AppState>,
+}
+
+impl DepartmentService {
+    /// Constructs the service from shared application state.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// The actor's organization's departments by code.
+    pub async fn departments(
+        &self,
+        actor: &AuthenticatedUser,
+    ) -> Result<Vec<Department>, ServiceError> {
+        sqlx::query_as::<_, Department>(
+            "SELECT * FROM departments WHERE org_id = $1 ORDER BY UPPER(code)",
+        )
+        .bind(actor.org_id)
+        .fetch_all(&self.state.pool)
+        .await
+        .map_err(internal)
+    }
+
+    /// Adds a department. Restricted to administrators; a taken code is a
+    /// conflict.
+    pub async fn create_department(
+        &self,
+        actor: &AuthenticatedUser,
+        payload: DepartmentRequest,
+    ) -> Result<Department, ServiceError> {
+        require_admin(actor)?;
+        let (code, name) = validate(&payload.code, &payload.name)?;
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let department = insert_department(
+            &mut tx,
+            Some(actor.employee_id),
+            actor.org_id,
+            code,
+            name,
+            payload.active,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        Ok(department)
+    }
+
+    /// Replaces a department. Restricted to administrators. A new code is
+    /// carried over to its employees and department head.
+    pub async fn update_department(
+        &self,
+        actor: &AuthenticatedUser,
+        department_id: Uuid,
+        payload: DepartmentRequest,
+    ) -> Result<Department, ServiceError> {
+        require_admin(actor)?;
+        let (code, name) = validate(&payload.code, &payload.name)?;
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let before = audit::DEPARTMENT
+            .snapshot(tx.as_mut(), department_id)
+            .await?;
+        let previous_code: String = sqlx::query_scalar(
+            "SELECT code FROM departments WHERE id = $1 AND org_id = $2 FOR UPDATE",
+        )
+        .bind(department_id)
+        .bind(actor.org_id)
+        .fetch_optional(tx.as_mut())
+        .await
+        .map_err(internal)?
+        .ok_or(ServiceError::NotFound)?;
+        let department = sqlx::query_as::<_, Department>(
+            "UPDATE departments SET code = $2, name = $3, active = $4, updated_at = $5
+             WHERE id = $1
+             RETURNING *",
+        )
+        .bind(department_id)
+        .bind(code)
+        .bind(name)
+        .bind(payload.active)
+        .bind(Utc::now())
+        .fetch_one(tx.as_mut())
+        .await
+        .map_err(conflict_or_internal)?;
+        let renamed = previous_code != department.code;
+        if renamed {
+            sqlx::query("UPDATE employees SET department = $2 WHERE department_id = $1")
+                .bind(department_id)
+                .bind(&department.code)
+                .execute(tx.as_mut())
+                .await
+                .map_err(internal)?;
+            sqlx::query(
+                "UPDATE department_heads SET department = $3
+                 WHERE org_id = $1 AND department = $2",
+            )
+            .bind(actor.org_id)
+            .bind(&previous_code)
+            .bind(&department.code)
+            .execute(tx.as_mut())
+            .await
+            .map_err(conflict_or_internal)?;
+        }
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::DEPARTMENT,
+            department_id,
+            audit::UPDATED,
+            before,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        if renamed {
+            management_chain::invalidate(&self.state);
+        }
+        Ok(department)
+    }
+
+    /// Removes a department no employee or cost center references.
+    /// Restricted to administrators.
+    pub async fn delete_department(
+        &self,
+        actor: &AuthenticatedUser,
+        department_id: Uuid,
+    ) -> Result<(), ServiceError> {
+        require_admin(actor)?;
+        self.delete(actor, audit::DEPARTMENT, department_id).await
+    }
+
+    /// The actor's organization's cost centers by code.
+    pub async fn cost_centers(
+        &self,
+        actor: &AuthenticatedUser,
+    ) -> Result<Vec<CostCenter>, ServiceError> {
+        sqlx::query_as::<_, CostCenter>(
+            "SELECT * FROM cost_centers WHERE org_id = $1 ORDER BY UPPER(code)",
+        )
+        .bind(actor.org_id)
+        .fetch_all(&self.state.pool)
+        .await
+        .map_err(internal)
+    }
+
+    /// Adds a cost center. Restricted to administrators; a taken code is a
+    /// conflict, and the department must belong to the organization.
+    pub async fn create_cost_center(
+        &self,
+        actor: &AuthenticatedUser,
+        payload: CostCenterRequest,
+    ) -> Result<CostCenter, ServiceError> {
+        require_admin(actor)?;
+        let (code, name) = validate(&payload.code, &payload.name)?;
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        ensure_department(tx.as_mut(), actor.org_id, payload.department_id).await?;
+        let now = Utc::now();
+        let cost_center = sqlx::query_as::<_, CostCenter>(
+            "INSERT INTO cost_centers
+                (id, org_id, code, name, department_id, active, created_at, updated_at)
+             VALUES ($1,$2,$3,$4,$5,$6,$7,$7)
+             RETURNING *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(actor.org_id)
+        .bind(code)
+        .bind(name)
+        .bind(payload.department_id)
+        .bind(payload.active)
+        .bind(now)
+        .fetch_one(tx.as_mut())
+        .await
+        .map_err(conflict_or_internal)?;
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::COST_CENTER,
+            cost_center.id,
+            audit::CREATED,
+            None,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        Ok(cost_center)
+    }
+
+    /// Replaces a cost center. Restricted to administrators.
+    pub async fn update_cost_center(
+        &self,
+        actor: &AuthenticatedUser,
+        cost_center_id: Uuid,
+        payload: CostCenterRequest,
+    ) -> Result<CostCenter, ServiceError> {
+        require_admin(actor)?;
+        let (code, name) = validate(&payload.code, &payload.name)?;
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        ensure_department(tx.as_mut(), actor.org_id, payload.department_id).await?;
+        let before = audit::COST_CENTER
+            .snapshot(tx.as_mut(), cost_center_id)
+            .await?;
+        let cost_center = sqlx::query_as::<_, CostCenter>(
+            "UPDATE cost_centers
+             SET code = $3, name = $4, department_id = $5, active = $6, updated_at = $7
+             WHERE id = $1 AND org_id = $2
+             RETURNING *",
+        )
+        .bind(cost_center_id)
+        .bind(actor.org_id)
+        .bind(code)
+        .bind(name)
+        .bind(payload.department_id)
+        .bind(payload.active)
+        .bind(Utc::now())
+        .fetch_optional(tx.as_mut())
+        .await
+        .map_err(conflict_or_internal)?
+        .ok_or(ServiceError::NotFound)?;
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::COST_CENTER,
+            cost_center_id,
+            audit::UPDATED,
+            before,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        Ok(cost_center)
+    }
+
+    /// Removes a cost center no employee or item references. Restricted to
+    /// administrators.
+    pub async fn delete_cost_center(
+        &self,
+        actor: &AuthenticatedUser,
+        cost_center_id: Uuid,
+    ) -> Result<(), ServiceError> {
+        require_admin(actor)?;
+        self.delete(actor, audit::COST_CENTER, cost_center_id).await
+    }
+
+    async fn delete(
+        &self,
+        actor: &AuthenticatedUser,
+        entity: audit::Entity,
+        id: Uuid,
+    ) -> Result<(), ServiceError> {
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let before = entity.snapshot(tx.as_mut(), id).await?;
+        let result = sqlx::query(&format!(
+            "DELETE FROM {} WHERE id = $1 AND org_id = $2",
+            entity.table
+        ))
+        .bind(id)
+        .bind(actor.org_id)
+        .execute(tx.as_mut())
+        .await
+        .map_err(conflict_or_internal)?;
+        if result.rows_affected() == 0 {
+            return Err(ServiceError::NotFound);
+        }
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            entity,
+            id,
+            audit::DELETED,
+            before,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        Ok(())
+    }
+}
+
+/// `org_id`'s departments keyed by upper-cased code.
+pub async fn departments_by_code(
+    conn: &mut PgConnection,
+    org_id: Uuid,
+) -> Result<HashMap<String, Department>, ServiceError> {
+    let departments =
+        sqlx::query_as::<_, Department>("SELECT * FROM departments WHERE org_id = $1")
+            .bind(org_id)
+            .fetch_all(conn)
+            .await
+            .map_err(internal)?;
+    Ok(departments
+        .into_iter()
+        .map(|department| (department.code.to_uppercase(), department))
+        .collect())
+}
+
+/// The department of `org_id` whose code is `code`, ignoring case. Inactive
+/// departments are refused unless `current` is theirs, so employees already
+/// in one keep it.
+pub async fn resolve_department(
+    conn: &mut PgConnection,
+    org_id: Uuid,
+    code: &str,
+    current: Option<Uuid>,
+) -> Result<Department, ServiceError> {
+    let department = sqlx::query_as::<_, Department>(
+        "SELECT * FROM departments WHERE org_id = $1 AND UPPER(code) = UPPER($2)",
+    )
+    .bind(org_id)
+    .bind(code)
+    .fetch_optional(conn)
+    .await
+    .map_err(internal)?;
+    match department {
+        Some(department) if department.active || current == Some(department.id) => Ok(department),
+        Some(_) => Err(ServiceError::Validation(format!(
+            "department {code} is inactive"
+        ))),
+        None => Err(ServiceError::Validation(format!(
+            "department {code} not found"
+        ))),
+    }
+}
+
+/// Adds departments for the `codes` `org_id` does not have yet, ignoring
+/// case, and returns how many were added.
+pub async fn create_missing_departments(
+    tx: &mut Transaction<'_, Postgres>,
+    performed_by: Option<Uuid>,
+    org_id: Uuid,
+    codes: &[&str],
+) -> Result<usize, ServiceError> {
+    let mut known = departments_by_code(tx.as_mut(), org_id).await?;
+    let mut created = 0;
+    for code in codes {
+        let key = code.to_uppercase();
+        if code.is_empty() || known.contains_key(&key) {
+            continue;
+        }
+        let department = insert_department(tx, performed_by, org_id, code, code, true).await?;
+        known.insert(key, department);
+        created += 1;
+    }
+    Ok(created)
+}
+
+/// Checks that every one of `cost_center_ids` is an active cost center of
+/// `org_id`.
+pub async fn ensure_cost_centers(
+    conn: &mut PgConnection,
+    org_id: Uuid,
+    cost_center_ids: &[Uuid],
+) -> Result<(), ServiceError> {
+    if cost_center_ids.is_empty() {
+        return Ok(());
+    }
+    let found: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM cost_centers WHERE id = ANY($1) AND org_id = $2 AND active",
+    )
+    .bind(cost_center_ids)
+    .bind(org_id)
+    .fetch_one(conn)
+    .await
+    .map_err(internal)?;
+    let mut distinct = cost_center_ids.to_vec();
+    distinct.sort_unstable();
+    distinct.dedup();
+    if found != distinct.len() as i64 {
+        return Err(ServiceError::Validation(
+            "cost_center_id does not match an active cost center".into(),
+        ));
+    }
+    Ok(())
+}
+
+async fn insert_department(
+    tx: &mut Transaction<'_, Postgres>,
+    performed_by: Option<Uuid>,
+    org_id: Uuid,
+    code: &str,
+    name: &str,
+    active: bool,
+) -> Result<Department, ServiceError> {
+    let now = Utc::now();
+    let department = sqlx::query_as::<_, Department>(
+        "INSERT INTO departments (id, org_id, code, name, active, created_at, updated_at)
+         VALUES ($1,$2,$3,$4,$5,$6,$6)
+         RETURNING *",
+    )
+    .bind(Uuid::new_v4())
+    .bind(org_id)
+    .bind(code)
+    .bind(name)
+    .bind(active)
+    .bind(now)
+    .fetch_one(tx.as_mut())
+    .await
+    .map_err(conflict_or_internal)?;
+    audit::record_change(
+        tx.as_mut(),
+        performed_by,
+        audit::DEPARTMENT,
+        department.id,
+        audit::CREATED,
+        None,
+    )
+    .await?;
+    Ok(department)
+}
+
+/// A cost center's department must belong to the same organization.
+async fn ensure_department(
+    conn: &mut PgConnection,
+    org_id: Uuid,
+    department_id: Option<Uuid>,
+) -> Result<(), ServiceError> {
+    let Some(department_id) = department_id else {
+        return Ok(());
+    };
+    let exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM departments WHERE id = $1 AND org_id = $2)",
+    )
+    .bind(department_id)
+    .bind(org_id)
+    .fetch_one(conn)
+    .await
+    .map_err(internal)?;
+    if !exists {
+        return Err(ServiceError::Validation(
+            "department_id does not match a department".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the trimmed code and name.
+fn validate<'a>(code: &'a str, name: &'a str) -> Result<(&'a str, &'a str), ServiceError> {
+    let (code, name) = (code.trim(), name.trim());
+    if code.is_empty() {
+        return Err(ServiceError::Validation("code is required".into()));
+    }
+    if name.is_empty() {
+        return Err(ServiceError::Validation("name is required".into()));
+    }
+    Ok((code, name))
+}
+
+fn require_admin(actor: &AuthenticatedUser) -> Result<(), ServiceError> {
+    if actor.role != Role::Admin {
+        return Err(ServiceError::Forbidden);
+    }
+    Ok(())
+}
+
+/// Taken codes and deleting entries still referenced are conflicts.
+fn conflict_or_internal(err: sqlx::Error) -> ServiceError {
+    match err {
+        sqlx::Error::Database(db) if matches!(db.code().as_deref(), Some("23505" | "23503")) => {
+            ServiceError::Conflict
+        }
+        other => internal(other),
+    }
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_trims_and_requires_code_and_name() {
+        assert_eq!(
+            validate(" OPS ", " Operations ").unwrap(),
+            ("OPS", "Operations")
+        );
+        assert!(matches!(
+            validate(" ", "Operations"),
+            Err(ServiceError::Validation(message)) if message == "code is required"
+        ));
+        assert!(matches!(
+            validate("OPS", ""),
+            Err(ServiceError::Validation(message)) if message == "name is required"
+        ));
+    }
+}
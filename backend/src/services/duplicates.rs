@@ -0,0 +1,81 @@
+//! Cross-report duplicate detection.
+//!
+//! `find_duplicates` matches a report's items against the items of other
+//! submitted, approved, and finalized reports so the `duplicate_expense` and
+//! `duplicate_receipt` policy rules can flag claims made twice.
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::{models::ReportStatus, policy::DuplicateMatches};
+
+use super::{budgets::COMMITTED_STATUSES, errors::ServiceError};
+
+/// Finds the items of `report_id` that repeat an item on another committed
+/// report: one of the same employee's with the same date, amount, currency,
+/// and merchant (ignoring case and surrounding spaces; two items without a
+/// merchant match), or anyone's with an identical receipt file.
+pub async fn find_duplicates(
+    pool: &PgPool,
+    report_id: Uuid,
+) -> Result<DuplicateMatches, ServiceError> {
+    let statuses: Vec<&str> = COMMITTED_STATUSES
+        .iter()
+        .map(ReportStatus::as_str)
+        .collect();
+
+    let expenses = sqlx::query_as::<_, (Uuid, Uuid)>(
+        "SELECT DISTINCT ON (i.id) i.id, o.report_id
+         FROM expense_items i
+         JOIN expense_reports r ON r.id = i.report_id
+         JOIN expense_items o
+           ON o.expense_date = i.expense_date
+          AND o.amount_cents = i.amount_cents
+          AND LOWER(BTRIM(o.merchant)) IS NOT DISTINCT FROM LOWER(BTRIM(i.merchant))
+         JOIN expense_reports p ON p.id = o.report_id
+         WHERE i.report_id = $1
+           AND i.deleted_at IS NULL
+           AND o.deleted_at IS NULL
+           AND p.id <> r.id
+           AND p.employee_id = r.employee_id
+           AND p.currency = r.currency
+           AND p.status::text = ANY($2)
+         ORDER BY i.id, p.submitted_at NULLS LAST",
+    )
+    .bind(report_id)
+    .bind(&statuses)
+    .fetch_all(pool)
+    .await
+    .map_err(internal)?;
+
+    let receipts = sqlx::query_as::<_, (Uuid, Uuid)>(
+        "SELECT DISTINCT ON (rc.expense_item_id) rc.expense_item_id, oi.report_id
+         FROM receipts rc
+         JOIN expense_items i ON i.id = rc.expense_item_id
+         JOIN receipts orc ON orc.content_sha256 = rc.content_sha256
+         JOIN expense_items oi ON oi.id = orc.expense_item_id
+         JOIN expense_reports p ON p.id = oi.report_id
+         WHERE i.report_id = $1
+           AND rc.deleted_at IS NULL
+           AND i.deleted_at IS NULL
+           AND orc.deleted_at IS NULL
+           AND oi.deleted_at IS NULL
+           AND oi.report_id <> $1
+           AND p.status::text = ANY($2)
+         ORDER BY rc.expense_item_id, p.submitted_at NULLS LAST",
+    )
+    .bind(report_id)
+    .bind(&statuses)
+    .fetch_all(pool)
+    .await
+    .map_err(internal)?;
+
+    Ok(DuplicateMatches {
+        expenses: expenses.into_iter().collect(),
+        receipts: receipts.into_iter().collect(),
+    })
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
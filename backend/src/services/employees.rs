@@ -0,0 +1,61 @@
+//! Employee directory lookups for internal callers.
+//!
+//! The gRPC `EmployeeDirectory` service resolves employees for the card-feed
+//! and HR sync services, which know them by id or by HR identifier. HR
+//! identifiers match case-insensitively, as at login.
+
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::{domain::models::Employee, infrastructure::state::AppState};
+
+use super::errors::ServiceError;
+
+/// How a caller names the employee it wants.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EmployeeLookup {
+    Id(Uuid),
+    HrIdentifier(String),
+}
+
+/// Service reading the `employees` table.
+pub struct EmployeeService {
+    state: Arc<AppState>,
+}
+
+impl EmployeeService {
+    /// Constructs the service from shared application state.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// The employee `lookup` names, or `NotFound`.
+    pub async fn find(&self, lookup: &EmployeeLookup) -> Result<Employee, ServiceError> {
+        let query = match lookup {
+            EmployeeLookup::Id(id) => sqlx::query_as::<_, Employee>(
+                r#"
+                SELECT id, org_id, hr_identifier, manager_id, department, department_id,
+                       cost_center_id, role, created_at, deactivated_at
+                FROM employees
+                WHERE id = $1
+                "#,
+            )
+            .bind(*id),
+            EmployeeLookup::HrIdentifier(hr_identifier) => sqlx::query_as::<_, Employee>(
+                r#"
+                SELECT id, org_id, hr_identifier, manager_id, department, department_id,
+                       cost_center_id, role, created_at, deactivated_at
+                FROM employees
+                WHERE UPPER(hr_identifier) = UPPER($1)
+                "#,
+            )
+            .bind(hr_identifier.trim().to_string()),
+        };
+        query
+            .fetch_optional(&self.state.pool)
+            .await
+            .map_err(|err| ServiceError::Internal(err.to_string()))?
+            .ok_or(ServiceError::NotFound)
+    }
+}
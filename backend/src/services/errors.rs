@@ -1,5 +1,10 @@
 use axum::http::StatusCode;
+use serde::Serialize;
 use thiserror::Error;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::domain::{models::MoneyError, policy::PolicyFinding};
 
 #[derive(Debug, Error)]
 pub enum ServiceError {
@@ -11,6 +16,14 @@ pub enum ServiceError {
     Validation(String),
     #[error("conflict")]
     Conflict,
+    /// The resource changed since the client read it (`If-Match` failed).
+    #[error("precondition failed: the resource has changed")]
+    PreconditionFailed,
+    #[error("{} report(s) cannot be processed", .0.len())]
+    ReportsRejected(Vec<ReportRejection>),
+    /// Blocking policy findings that prevent a report's submission.
+    #[error("report cannot be submitted: {} blocking finding(s)", .0.len())]
+    PolicyBlocked(Vec<PolicyFinding>),
     #[error("internal error: {0}")]
     Internal(String),
 }
@@ -22,7 +35,33 @@ impl ServiceError {
             ServiceError::Forbidden => StatusCode::FORBIDDEN,
             ServiceError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
             ServiceError::Conflict => StatusCode::CONFLICT,
+            ServiceError::PreconditionFailed => StatusCode::PRECONDITION_FAILED,
+            ServiceError::ReportsRejected(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ServiceError::PolicyBlocked(_) => StatusCode::UNPROCESSABLE_ENTITY,
             ServiceError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
+
+    /// Logs an internal error at `error` level, which also sends it to the
+    /// error reporting backend when one is configured. Called where an error
+    /// becomes an HTTP response; the other variants are the caller's doing
+    /// and are not reported.
+    pub fn report(&self) {
+        if let ServiceError::Internal(message) = self {
+            error!(error = %message, "internal error");
+        }
+    }
+}
+
+/// Why a single report in a bulk request was refused.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReportRejection {
+    pub report_id: Uuid,
+    pub reason: String,
+}
+
+impl From<MoneyError> for ServiceError {
+    fn from(err: MoneyError) -> Self {
+        ServiceError::Validation(err.to_string())
+    }
 }
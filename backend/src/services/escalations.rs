@@ -0,0 +1,138 @@
+//! Approval escalations recorded by `jobs::escalations`.
+//!
+//! Finance reads the reports escalated to it through
+//! `GET /finance/escalations`. An escalation stays in that queue only while
+//! the report is still waiting in the approval round that was escalated; once
+//! the approver acts, or the report is recalled and resubmitted, it drops out.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{ReportStatus, Role},
+    infrastructure::{auth::AuthenticatedUser, state::AppState},
+};
+
+use super::{
+    errors::ServiceError,
+    repositories::{ApprovalRepo, PgApprovalRepo},
+};
+
+/// How far a stalled approval has been escalated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EscalationLevel {
+    /// The approver's own manager was notified.
+    SkipLevel,
+    /// The report was flagged in the finance escalation queue.
+    Finance,
+}
+
+impl EscalationLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EscalationLevel::SkipLevel => "skip_level",
+            EscalationLevel::Finance => "finance",
+        }
+    }
+}
+
+/// Report in the finance escalation queue.
+#[derive(Debug, Clone, Serialize)]
+pub struct EscalatedReport {
+    pub report_id: Uuid,
+    pub status: ReportStatus,
+    pub employee_id: Uuid,
+    pub approver_id: Option<Uuid>,
+    /// Skip-level manager already notified, if the report got that far.
+    pub skip_level_manager_id: Option<Uuid>,
+    pub pending_since: DateTime<Utc>,
+    pub escalated_at: DateTime<Utc>,
+    pub total_amount_cents: i64,
+    pub currency: String,
+}
+
+/// Service reading the `approval_escalations` table.
+pub struct EscalationService {
+    approvals: Arc<dyn ApprovalRepo>,
+}
+
+impl EscalationService {
+    /// Constructs the service from shared application state.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self::with_approval_repo(Arc::new(PgApprovalRepo::new(state.read_pool.clone())))
+    }
+
+    /// Builds the service over `approvals` instead of the database.
+    pub fn with_approval_repo(approvals: Arc<dyn ApprovalRepo>) -> Self {
+        Self { approvals }
+    }
+
+    /// Reports of the actor's organization escalated to finance that are
+    /// still awaiting the same approval, longest waiting first.
+    pub async fn finance_queue(
+        &self,
+        actor: &AuthenticatedUser,
+    ) -> Result<Vec<EscalatedReport>, ServiceError> {
+        if actor.role != Role::Finance {
+            return Err(ServiceError::Forbidden);
+        }
+        self.approvals.finance_escalations(actor.org_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    use crate::{domain::models::DEFAULT_ORG_ID, services::repositories::MemoryApprovalRepo};
+
+    fn escalated(pending_since: DateTime<Utc>) -> EscalatedReport {
+        EscalatedReport {
+            report_id: Uuid::new_v4(),
+            status: ReportStatus::Submitted,
+            employee_id: Uuid::new_v4(),
+            approver_id: Some(Uuid::new_v4()),
+            skip_level_manager_id: None,
+            pending_since,
+            escalated_at: pending_since + Duration::days(5),
+            total_amount_cents: 12_500,
+            currency: "USD".into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn finance_queue_lists_longest_waiting_first_for_finance_only() {
+        let now = Utc::now();
+        let approvals = Arc::new(MemoryApprovalRepo::default());
+        let recent = escalated(now - Duration::days(6));
+        let oldest = escalated(now - Duration::days(9));
+        approvals.escalate(DEFAULT_ORG_ID, recent.clone());
+        approvals.escalate(DEFAULT_ORG_ID, oldest.clone());
+        approvals.escalate(Uuid::new_v4(), escalated(now - Duration::days(12)));
+        let service = EscalationService::with_approval_repo(approvals);
+
+        let finance = AuthenticatedUser {
+            employee_id: Uuid::new_v4(),
+            role: Role::Finance,
+            org_id: DEFAULT_ORG_ID,
+        };
+        let queue = service.finance_queue(&finance).await.unwrap();
+        let ids: Vec<Uuid> = queue.iter().map(|report| report.report_id).collect();
+        assert_eq!(ids, vec![oldest.report_id, recent.report_id]);
+
+        let manager = AuthenticatedUser {
+            employee_id: finance.employee_id,
+            role: Role::Manager,
+            org_id: DEFAULT_ORG_ID,
+        };
+        assert!(matches!(
+            service.finance_queue(&manager).await,
+            Err(ServiceError::Forbidden)
+        ));
+    }
+}
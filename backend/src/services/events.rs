@@ -0,0 +1,158 @@
+//! Domain events recorded alongside workflow state changes.
+//!
+//! `record` appends a `domain_events` row inside the transaction that made the
+//! change, so an event exists exactly when its change committed. Events are
+//! the one record of what happened in the workflow: webhooks reuse an event's
+//! `id` as the delivery's event id, and notifications or a future event store
+//! can catch up by reading `since` in `sequence` order.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{postgres::PgRow, PgExecutor, Row};
+use uuid::Uuid;
+
+use super::errors::ServiceError;
+
+/// Most events returned by one `since` call.
+pub const MAX_READ: i64 = 500;
+
+/// What happened. Each kind belongs to one aggregate, whose id the event is
+/// recorded under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum EventKind {
+    /// An employee submitted a draft report for approval.
+    ReportSubmitted,
+    /// A manager, exception approver, or finance user approved or rejected a
+    /// report.
+    DecisionRecorded,
+    /// NetSuite accepted a finalized batch.
+    BatchExported,
+}
+
+impl EventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::ReportSubmitted => "report_submitted",
+            EventKind::DecisionRecorded => "decision_recorded",
+            EventKind::BatchExported => "batch_exported",
+        }
+    }
+
+    /// The `aggregate_type` events of this kind are recorded under.
+    pub fn aggregate_type(&self) -> &'static str {
+        match self {
+            EventKind::ReportSubmitted | EventKind::DecisionRecorded => "expense_report",
+            EventKind::BatchExported => "netsuite_batch",
+        }
+    }
+}
+
+/// A recorded `domain_events` row.
+#[derive(Debug, Clone, Serialize)]
+pub struct DomainEvent {
+    pub id: Uuid,
+    pub sequence: i64,
+    pub event_type: String,
+    pub aggregate_type: String,
+    pub aggregate_id: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub payload: serde_json::Value,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Appends a `kind` event about `aggregate_id` and returns its id.
+///
+/// Accepts any executor so callers record inside the transaction that made
+/// the change; the event is discarded if it rolls back. `actor_id` is empty
+/// for changes no employee made, such as a batch exported by the outbox.
+pub async fn record<'e, E>(
+    executor: E,
+    kind: EventKind,
+    aggregate_id: Uuid,
+    actor_id: Option<Uuid>,
+    payload: serde_json::Value,
+) -> Result<Uuid, ServiceError>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query_scalar(
+        "INSERT INTO domain_events
+            (id, event_type, aggregate_type, aggregate_id, actor_id, payload, occurred_at)
+         VALUES ($1,$2,$3,$4,$5,$6,$7)
+         RETURNING id",
+    )
+    .bind(Uuid::new_v4())
+    .bind(kind.as_str())
+    .bind(kind.aggregate_type())
+    .bind(aggregate_id)
+    .bind(actor_id)
+    .bind(payload)
+    .bind(Utc::now())
+    .fetch_one(executor)
+    .await
+    .map_err(internal)
+}
+
+/// Events recorded after `after_sequence`, oldest first, up to `limit` (at
+/// most `MAX_READ`).
+///
+/// Sequences are assigned on insert, so a transaction that commits late can
+/// land behind one a reader has already passed; readers that must see every
+/// event should re-read a short window behind their position.
+pub async fn since<'e, E>(
+    executor: E,
+    after_sequence: i64,
+    limit: i64,
+) -> Result<Vec<DomainEvent>, ServiceError>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query(
+        "SELECT id, sequence, event_type, aggregate_type, aggregate_id, actor_id, payload,
+                occurred_at
+         FROM domain_events
+         WHERE sequence > $1
+         ORDER BY sequence
+         LIMIT $2",
+    )
+    .bind(after_sequence)
+    .bind(limit.clamp(1, MAX_READ))
+    .fetch_all(executor)
+    .await
+    .map_err(internal)?
+    .into_iter()
+    .map(map_event)
+    .collect()
+}
+
+fn map_event(row: PgRow) -> Result<DomainEvent, ServiceError> {
+    Ok(DomainEvent {
+        id: row.try_get("id").map_err(internal)?,
+        sequence: row.try_get("sequence").map_err(internal)?,
+        event_type: row.try_get("event_type").map_err(internal)?,
+        aggregate_type: row.try_get("aggregate_type").map_err(internal)?,
+        aggregate_id: row.try_get("aggregate_id").map_err(internal)?,
+        actor_id: row.try_get("actor_id").map_err(internal)?,
+        payload: row.try_get("payload").map_err(internal)?,
+        occurred_at: row.try_get("occurred_at").map_err(internal)?,
+    })
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_events_share_the_report_stream() {
+        assert_eq!(
+            EventKind::ReportSubmitted.aggregate_type(),
+            EventKind::DecisionRecorded.aggregate_type()
+        );
+        assert_eq!(EventKind::BatchExported.aggregate_type(), "netsuite_batch");
+        assert_eq!(EventKind::DecisionRecorded.as_str(), "decision_recorded");
+    }
+}
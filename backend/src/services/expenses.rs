@@ -1,26 +1,53 @@
 //! Coordinates expense report submission and policy evaluation workflows.
 //!
 //! This service powers the REST handlers mounted under `/reports`,
-//! `/reports/:id/submit`, and `/reports/:id/policy` in
+//! `/reports/:id/submit`, `/reports/:id/policy`, and the soft deletions of
+//! reports, items, and receipts in
 //! `backend/src/api/rest/expenses.rs`, stitching together persistence and
 //! domain policy checks so UI flows can surface actionable results.
 
-use std::{collections::HashSet, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
-use chrono::Utc;
+use chrono::{NaiveDateTime, Utc};
 use serde::Deserialize;
-use sqlx::{postgres::PgRow, Row};
 use uuid::Uuid;
 
 use crate::{
     domain::{
-        models::{ExpenseCategory, ExpenseItem, ExpenseReport, PolicyCap, ReportStatus, Role},
-        policy::{evaluate_item, PolicyEvaluation},
+        budget::evaluate_budgets,
+        models::{
+            normalize_tax_jurisdiction, Currency, ExpenseCategory, ExpenseItem, ExpenseReport,
+            Money, MoneyError, PolicyRule, ReportStatus, Role, TripStatus,
+        },
+        policy::{
+            check_receipt_capture_dates, current_fiscal_year, evaluate_item, evaluate_items,
+            DuplicateMatches, EvaluationTrigger, FindingSeverity, PolicyEvaluation,
+            PolicyEvaluationRun, RateTables, WorkCalendar, POLICY_CURRENCY, POLICY_EXCEPTION_CODE,
+        },
     },
     infrastructure::state::AppState,
 };
 
-use super::errors::ServiceError;
+use super::{
+    approvals, audit, budgets, departments, duplicates,
+    errors::ServiceError,
+    events::{self, EventKind},
+    fx, holidays, mileage_rates, notifications, per_diem, policy_history, policy_rules,
+    policy_versions,
+    preconditions::{self, IfMatch},
+    profile,
+    repositories::{PgReportRepo, ReportRepo},
+    webhooks,
+};
+
+/// Notification kind queued for the manager when a report is submitted.
+pub const APPROVAL_REQUEST_KIND: &str = "approval_request";
+
+/// Notification kind queued for the employee confirming their submission.
+pub const REPORT_SUBMITTED_KIND: &str = "report_submitted";
 
 /// Request payload accepted by `POST /reports` for starting a draft report.
 ///
@@ -31,7 +58,12 @@ use super::errors::ServiceError;
 pub struct CreateReportRequest {
     pub reporting_period_start: chrono::NaiveDate,
     pub reporting_period_end: chrono::NaiveDate,
-    pub currency: String,
+    /// Defaults to the actor's `default_currency` preference.
+    #[serde(default)]
+    pub currency: Option<String>,
+    /// The actor's own trip the expenses were incurred on.
+    #[serde(default)]
+    pub trip_id: Option<Uuid>,
     #[serde(default)]
     pub items: Vec<CreateExpenseItem>,
 }
@@ -50,6 +82,29 @@ pub struct CreateExpenseItem {
     pub reimbursable: bool,
     #[serde(default)]
     pub payment_method: Option<String>,
+    /// Marks the item an exception to policy, e.g. a missing receipt;
+    /// requires `exception_justification`.
+    #[serde(default)]
+    pub is_policy_exception: bool,
+    #[serde(default)]
+    pub exception_justification: Option<String>,
+    #[serde(default)]
+    pub class: Option<String>,
+    #[serde(default)]
+    pub tax_amount_cents: Option<i64>,
+    #[serde(default)]
+    pub tax_jurisdiction: Option<String>,
+    /// Distance driven; mileage items with miles are checked against the
+    /// mileage rate.
+    #[serde(default)]
+    pub miles: Option<f64>,
+    /// Compared with other reports' items to flag duplicate claims.
+    #[serde(default)]
+    pub merchant: Option<String>,
+    /// An active cost center of the employee's organization to allocate the
+    /// item to.
+    #[serde(default)]
+    pub cost_center_id: Option<Uuid>,
     #[serde(default)]
     pub receipts: Vec<CreateReceiptReference>,
 }
@@ -66,19 +121,28 @@ pub struct CreateReceiptReference {
 /// an expense report from draft through submission.
 pub struct ExpenseService {
     pub state: Arc<AppState>,
+    reports: Arc<dyn ReportRepo>,
 }
 
 impl ExpenseService {
     /// Builds a new expense service with the shared application state holding
     /// database pools and policy caches.
     pub fn new(state: Arc<AppState>) -> Self {
-        Self { state }
+        let reports = Arc::new(PgReportRepo::new(state.pool.clone()));
+        Self::with_report_repo(state, reports)
+    }
+
+    /// Builds the service over `reports` instead of the database, e.g. a
+    /// `MemoryReportRepo` in tests.
+    pub fn with_report_repo(state: Arc<AppState>, reports: Arc<dyn ReportRepo>) -> Self {
+        Self { state, reports }
     }
 
     /// Creates a draft expense report for the authenticated employee.
     ///
     /// * `actor` — employee identity from the session, used to scope the new
-    ///   record.
+    ///   record. When an assistant acts for them, the assistant is recorded
+    ///   as `created_by`.
     /// * `payload` — reporting window and currency details supplied by the UI.
     ///
     /// Side effects:
@@ -106,72 +170,135 @@ impl ExpenseService {
             reporting_period_start,
             reporting_period_end,
             currency,
+            trip_id,
             items,
         } = payload;
 
-        let (total_amount_cents, total_reimbursable_cents) = calculate_totals(&items);
+        let currency = match currency {
+            Some(currency) => Currency::parse(&currency)?,
+            None => profile::default_currency(&mut tx, actor.employee_id)
+                .await?
+                .ok_or_else(|| ServiceError::Validation("currency is required".into()))?,
+        };
+        let (total_amount, total_reimbursable) = calculate_totals(&items, currency)?;
 
-        let record = sqlx::query(
-            "INSERT INTO expense_reports (id, employee_id, reporting_period_start, reporting_period_end, status, total_amount_cents, total_reimbursable_cents, currency, version, created_at, updated_at)
-             VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11)
-             RETURNING *",
+        if let Some(trip_id) = trip_id {
+            let owns_trip = sqlx::query_scalar!(
+                r#"SELECT EXISTS (SELECT 1 FROM trips WHERE id = $1 AND employee_id = $2) AS "exists!""#,
+                trip_id,
+                actor.employee_id,
+            )
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|err| ServiceError::Internal(err.to_string()))?;
+            if !owns_trip {
+                return Err(ServiceError::Validation(
+                    "trip_id must reference one of your trips".into(),
+                ));
+            }
+        }
+
+        let cost_center_ids: Vec<Uuid> = items
+            .iter()
+            .filter_map(|item| item.cost_center_id)
+            .collect();
+        departments::ensure_cost_centers(&mut tx, actor.org_id, &cost_center_ids).await?;
+
+        let record = sqlx::query_as!(
+            ExpenseReport,
+            r#"INSERT INTO expense_reports (id, employee_id, reporting_period_start, reporting_period_end, status, total_amount_cents, total_reimbursable_cents, currency, version, created_at, updated_at, trip_id, created_by)
+               VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,$13)
+               RETURNING id, org_id, employee_id, reporting_period_start, reporting_period_end,
+                         status AS "status: ReportStatus", total_amount_cents,
+                         total_reimbursable_cents, currency, version, created_at, updated_at,
+                         submitted_at, trip_id, owner_deactivated_at, created_by, submitted_by"#,
+            id,
+            actor.employee_id,
+            reporting_period_start,
+            reporting_period_end,
+            status.as_str(),
+            total_amount.amount_minor,
+            total_reimbursable.amount_minor,
+            currency.code(),
+            1_i32,
+            now,
+            now,
+            trip_id,
+            audit::assistant_for(actor.employee_id).unwrap_or(actor.employee_id),
         )
-        .bind(id)
-        .bind(actor.employee_id)
-        .bind(reporting_period_start)
-        .bind(reporting_period_end)
-        .bind(status)
-        .bind(total_amount_cents)
-        .bind(total_reimbursable_cents)
-        .bind(&currency)
-        .bind(1_i32)
-        .bind(now)
-        .bind(now)
-        .map(|row: PgRow| map_report(row))
         .fetch_one(&mut *tx)
         .await
         .map_err(|err| ServiceError::Internal(err.to_string()))?;
 
         for item in items {
             let item_id = Uuid::new_v4();
-            sqlx::query(
-                "INSERT INTO expense_items (id, report_id, expense_date, category, gl_account_id, description, attendees, location, amount_cents, reimbursable, payment_method, is_policy_exception)
-                 VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12)",
+            sqlx::query!(
+                "INSERT INTO expense_items (id, report_id, expense_date, category, gl_account_id, description, attendees, location, amount_cents, reimbursable, payment_method, is_policy_exception, tax_amount_cents, tax_jurisdiction, class, exception_justification, miles, merchant, cost_center_id)
+                 VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,$13,$14,$15,$16,$17::float8,$18,$19)",
+                item_id,
+                id,
+                item.expense_date,
+                item.category.as_str(),
+                None::<Uuid>,
+                item.description,
+                item.attendees,
+                item.location,
+                item.amount_cents,
+                item.reimbursable,
+                item.payment_method,
+                item.is_policy_exception,
+                item.tax_amount_cents,
+                normalize_tax_jurisdiction(item.tax_jurisdiction.as_deref()),
+                item.class
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|class| !class.is_empty()),
+                item.exception_justification
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|justification| !justification.is_empty()),
+                item.miles,
+                item.merchant
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|merchant| !merchant.is_empty()),
+                item.cost_center_id,
             )
-            .bind(item_id)
-            .bind(id)
-            .bind(item.expense_date)
-            .bind(item.category)
-            .bind::<Option<Uuid>>(None)
-            .bind(item.description)
-            .bind(item.attendees)
-            .bind(item.location)
-            .bind(item.amount_cents)
-            .bind(item.reimbursable)
-            .bind(item.payment_method)
-            .bind(false)
             .execute(&mut *tx)
             .await
             .map_err(|err| ServiceError::Internal(err.to_string()))?;
 
             for receipt in item.receipts {
-                sqlx::query(
-                    "INSERT INTO receipts (id, expense_item_id, file_key, file_name, mime_type, size_bytes, uploaded_by)
-                     VALUES ($1,$2,$3,$4,$5,$6,$7)",
+                // Carry over the EXIF capture time and content hash recorded
+                // when the file was uploaded through `ReceiptService`, if any.
+                sqlx::query!(
+                    "INSERT INTO receipts (id, expense_item_id, file_key, file_name, mime_type, size_bytes, uploaded_by, captured_at, content_sha256)
+                     VALUES ($1,$2,$3,$4,$5,$6,$7,
+                        (SELECT captured_at FROM receipt_uploads WHERE file_key = $3 AND uploaded_by = $7),
+                        (SELECT content_sha256 FROM receipt_uploads WHERE file_key = $3 AND uploaded_by = $7))",
+                    Uuid::new_v4(),
+                    item_id,
+                    receipt.file_key,
+                    receipt.file_name,
+                    receipt.mime_type,
+                    receipt.size_bytes,
+                    actor.employee_id,
                 )
-                .bind(Uuid::new_v4())
-                .bind(item_id)
-                .bind(receipt.file_key)
-                .bind(receipt.file_name)
-                .bind(receipt.mime_type)
-                .bind(receipt.size_bytes)
-                .bind(actor.employee_id)
                 .execute(&mut *tx)
                 .await
                 .map_err(|err| ServiceError::Internal(err.to_string()))?;
             }
         }
 
+        audit::record_change(
+            &mut tx,
+            Some(actor.employee_id),
+            audit::EXPENSE_REPORT,
+            id,
+            audit::CREATED,
+            None,
+        )
+        .await?;
         tx.commit()
             .await
             .map_err(|err| ServiceError::Internal(err.to_string()))?;
@@ -182,48 +309,173 @@ impl ExpenseService {
     /// Submits a draft report for approval by promoting it to
     /// `ReportStatus::Submitted`.
     ///
-    /// * `actor` — employee requesting submission; must own the report.
+    /// * `actor` — employee requesting submission; must own the report. The
+    ///   assistant acting for them, if any, is recorded as `submitted_by`.
     /// * `report_id` — identifier for the draft being submitted.
+    /// * `if_match` — the report's `If-Match` precondition; a report whose
+    ///   ETag no longer matches is refused with `PreconditionFailed`.
     ///
     /// The transition unlocks the manager approval gate noted in
     /// `POLICY.md` §"Approvals and Reimbursement Process". If the actor no
     /// longer owns the report or the status has changed, conflicts are surfaced
-    /// back to the REST caller for UI resolution.
+    /// back to the REST caller for UI resolution. The update is
+    /// version-checked, so a submission racing another write to the report
+    /// also fails with `ServiceError::Conflict`.
+    ///
+    /// Submission is refused with `ServiceError::Validation` while the policy
+    /// evaluation reports `blocking` violations, such as a missing receipt on
+    /// an item that is not a justified policy exception. Otherwise the
+    /// evaluation is stored with the report along with its policy version.
+    ///
+    /// A `report_submitted` notification confirming the submission is queued
+    /// for the employee in the same transaction. When the employee has a
+    /// manager, an `approval_request` notification carrying signed
+    /// approve/request-changes links is queued for email if
+    /// `approval_links.enabled` is set, and for chat if `chat.enabled` is. A
+    /// `report.submitted` webhook event is recorded when `webhooks.enabled`
+    /// is set.
     pub async fn submit_report(
         &self,
         actor: &crate::infrastructure::auth::AuthenticatedUser,
         report_id: Uuid,
+        if_match: &IfMatch,
     ) -> Result<ExpenseReport, ServiceError> {
-        let record = sqlx::query(
-            "UPDATE expense_reports SET status=$1, version=version+1, updated_at=$2 WHERE id=$3 AND employee_id=$4 AND status='draft' RETURNING *",
+        let mut tx = self
+            .state
+            .pool
+            .begin()
+            .await
+            .map_err(|err| ServiceError::Internal(err.to_string()))?;
+        let current = sqlx::query!(
+            "SELECT version, updated_at FROM expense_reports
+             WHERE id = $1 AND employee_id = $2 AND deleted_at IS NULL",
+            report_id,
+            actor.employee_id,
         )
-        .bind(ReportStatus::Submitted)
-        .bind(Utc::now())
-        .bind(report_id)
-        .bind(actor.employee_id)
-        .map(|row: PgRow| map_report(row))
-        .fetch_optional(&self.state.pool)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(map_sqlx_error)?
+        .ok_or(ServiceError::NotFound)?;
+        let version = current.version;
+        if_match.check(&preconditions::etag(version, current.updated_at))?;
+        let before = audit::EXPENSE_REPORT.snapshot(&mut tx, report_id).await?;
+        let record = sqlx::query_as!(
+            ExpenseReport,
+            r#"UPDATE expense_reports SET status=$1, version=version+1, updated_at=$2, submitted_at=$2, submitted_by=$6 WHERE id=$3 AND employee_id=$4 AND status='draft' AND version=$5
+               RETURNING id, org_id, employee_id, reporting_period_start, reporting_period_end,
+                         status AS "status: ReportStatus", total_amount_cents,
+                         total_reimbursable_cents, currency, version, created_at, updated_at,
+                         submitted_at, trip_id, owner_deactivated_at, created_by, submitted_by"#,
+            ReportStatus::Submitted.as_str(),
+            Utc::now(),
+            report_id,
+            actor.employee_id,
+            version,
+            audit::assistant_for(actor.employee_id).unwrap_or(actor.employee_id),
+        )
+        .fetch_optional(&mut *tx)
         .await
         .map_err(|err| ServiceError::Internal(err.to_string()))?;
 
         if let Some(record) = record {
+            let (evaluation, _) = self.run_evaluation(actor, report_id).await?;
+            let blocking: Vec<_> = evaluation
+                .findings
+                .iter()
+                .filter(|finding| finding.severity == FindingSeverity::Blocking)
+                .cloned()
+                .collect();
+            if !blocking.is_empty() {
+                return Err(ServiceError::PolicyBlocked(blocking));
+            }
+            policy_versions::store_evaluation(&mut tx, report_id, &evaluation).await?;
+            policy_history::record_run(
+                &mut *tx,
+                report_id,
+                EvaluationTrigger::Submission,
+                &evaluation,
+                actor.employee_id,
+            )
+            .await?;
+            notifications::enqueue(
+                tx.as_mut(),
+                record.employee_id,
+                REPORT_SUBMITTED_KIND,
+                report_summary(&record),
+            )
+            .await?;
+            if self.state.config.approval_links.enabled || self.state.config.chat.enabled {
+                self.queue_approval_request(&mut tx, &record).await?;
+            }
+            let event_id = events::record(
+                tx.as_mut(),
+                EventKind::ReportSubmitted,
+                report_id,
+                Some(actor.employee_id),
+                report_summary(&record),
+            )
+            .await?;
+            if self.state.config.webhooks.enabled {
+                webhooks::emit(
+                    tx.as_mut(),
+                    event_id,
+                    webhooks::REPORT_SUBMITTED_EVENT,
+                    report_summary(&record),
+                )
+                .await?;
+            }
+            audit::record_change(
+                &mut tx,
+                Some(actor.employee_id),
+                audit::EXPENSE_REPORT,
+                report_id,
+                audit::STATUS_CHANGED,
+                before,
+            )
+            .await?;
+            tx.commit()
+                .await
+                .map_err(|err| ServiceError::Internal(err.to_string()))?;
             return Ok(record);
         }
+        // Not a draft, or changed since `version` was read.
+        Err(ServiceError::Conflict)
+    }
 
-        let exists = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(1) FROM expense_reports WHERE id = $1 AND employee_id = $2",
+    async fn queue_approval_request(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        report: &ExpenseReport,
+    ) -> Result<(), ServiceError> {
+        let manager_id = sqlx::query_scalar!(
+            "SELECT manager_id FROM employees WHERE id = $1",
+            report.employee_id,
         )
-        .bind(report_id)
-        .bind(actor.employee_id)
-        .fetch_one(&self.state.pool)
+        .fetch_one(tx.as_mut())
         .await
         .map_err(|err| ServiceError::Internal(err.to_string()))?;
+        let Some(manager_id) = manager_id else {
+            return Ok(());
+        };
 
-        if exists == 0 {
-            Err(ServiceError::NotFound)
-        } else {
-            Err(ServiceError::Conflict)
+        let mut payload = report_summary(report);
+        if self.state.config.approval_links.enabled {
+            let links =
+                approvals::issue_action_links(tx, &self.state, report.id, manager_id).await?;
+            payload["links"] = serde_json::json!(links);
+            notifications::enqueue(
+                tx.as_mut(),
+                manager_id,
+                APPROVAL_REQUEST_KIND,
+                payload.clone(),
+            )
+            .await?;
+        }
+        if self.state.config.chat.enabled {
+            notifications::enqueue_chat(tx.as_mut(), manager_id, APPROVAL_REQUEST_KIND, payload)
+                .await?;
         }
+        Ok(())
     }
 
     /// Evaluates all items in the specified report against the policy engine.
@@ -231,55 +483,401 @@ impl ExpenseService {
     /// * `report_id` — identifies which report to aggregate.
     ///
     /// Side effects:
-    /// * Reads the associated items and applicable `PolicyCap` records.
-    /// * Delegates per-item checks to `domain::policy::evaluate_item`, which
-    ///   encodes rules such as meal per-diem limits documented in
-    ///   `POLICY.md` §"Meals" and mileage thresholds in §"Other Transportation".
+    /// * Reads the associated items, the enabled `policy_rules` for their
+    ///   categories, and, when meals carry a location, the per-diem rates for
+    ///   their fiscal years.
+    /// * Delegates the checks to `domain::policy::evaluate_items`, which
+    ///   evaluates each rule at its item, day, or report scope and checks
+    ///   located meals against their city's per-diem rate (`POLICY.md`
+    ///   §"Meals").
+    /// * Checks the period spend against the budgets covering the employee's
+    ///   department through `domain::budget::evaluate_budgets`, converting a
+    ///   non-USD report's spend at the latest rate in `fx_rates` when one is
+    ///   fresh enough.
+    /// * Loads the holidays the items fall on and the linked trip's dates for
+    ///   `non_working_day` (approved trips only) and `late_booking` rules.
+    /// * Matches the items against other submitted reports for the
+    ///   `duplicate_expense` and `duplicate_receipt` rules.
+    ///
+    /// Drafts are evaluated against the current rules. Once submitted, the
+    /// evaluation stored at submission is returned as is; reports submitted
+    /// before evaluations were stored are re-evaluated against the policy
+    /// version in force at `submitted_at`.
     ///
     /// Returns a merged `PolicyEvaluation` describing violations and warnings
-    /// that upstream REST handlers serialize for the UI.
+    /// that upstream REST handlers serialize for the UI, tagged with the
+    /// `policy_version` applied. Draft evaluations are recorded in the
+    /// report's policy history when their findings changed.
     pub async fn evaluate_report(
         &self,
         actor: &crate::infrastructure::auth::AuthenticatedUser,
         report_id: Uuid,
     ) -> Result<PolicyEvaluation, ServiceError> {
-        let owner_id =
-            sqlx::query_scalar::<_, Uuid>("SELECT employee_id FROM expense_reports WHERE id = $1")
-                .bind(report_id)
-                .fetch_optional(&self.state.pool)
-                .await
-                .map_err(|err| ServiceError::Internal(err.to_string()))?;
+        let (evaluation, is_draft) = self.run_evaluation(actor, report_id).await?;
+        if is_draft {
+            policy_history::record_run(
+                &self.state.pool,
+                report_id,
+                EvaluationTrigger::Evaluation,
+                &evaluation,
+                actor.employee_id,
+            )
+            .await?;
+        }
+        Ok(evaluation)
+    }
 
-        let Some(owner_id) = owner_id else {
+    /// Loads a report for its owner or a reviewer.
+    pub async fn get_report(
+        &self,
+        actor: &crate::infrastructure::auth::AuthenticatedUser,
+        report_id: Uuid,
+    ) -> Result<ExpenseReport, ServiceError> {
+        let report = self
+            .reports
+            .find(report_id)
+            .await?
+            .ok_or(ServiceError::NotFound)?;
+        if report.org_id != actor.org_id {
             return Err(ServiceError::NotFound);
-        };
+        }
+        let is_reviewer = matches!(actor.role, Role::Manager | Role::Finance | Role::Admin);
+        if actor.employee_id != report.employee_id && !is_reviewer {
+            return Err(ServiceError::Forbidden);
+        }
+        Ok(report)
+    }
 
+    /// Lists the report's stored policy evaluation runs, oldest first, with
+    /// the findings each introduced and resolved. Visible to the report's
+    /// owner and to reviewers.
+    pub async fn policy_history(
+        &self,
+        actor: &crate::infrastructure::auth::AuthenticatedUser,
+        report_id: Uuid,
+    ) -> Result<Vec<PolicyEvaluationRun>, ServiceError> {
+        let report = self
+            .reports
+            .find(report_id)
+            .await?
+            .filter(|report| report.org_id == actor.org_id)
+            .ok_or(ServiceError::NotFound)?;
+        let is_reviewer = matches!(actor.role, Role::Manager | Role::Finance | Role::Admin);
+        if actor.employee_id != report.employee_id && !is_reviewer {
+            return Err(ServiceError::Forbidden);
+        }
+        policy_history::runs(&self.state.pool, report_id).await
+    }
+
+    /// Soft-deletes a draft report owned by the actor, hiding it with its
+    /// items and receipts until an administrator restores it.
+    ///
+    /// Submitted reports are part of the approval trail and are refused with
+    /// `ServiceError::Conflict`; a report already deleted is `NotFound`.
+    pub async fn delete_report(
+        &self,
+        actor: &crate::infrastructure::auth::AuthenticatedUser,
+        report_id: Uuid,
+    ) -> Result<(), ServiceError> {
+        let mut tx = self.state.pool.begin().await.map_err(map_sqlx_error)?;
+        lock_draft(&mut tx, actor, report_id).await?;
+        let before = audit::EXPENSE_REPORT.snapshot(&mut tx, report_id).await?;
+        sqlx::query!(
+            "UPDATE expense_reports
+             SET deleted_at = NOW(), version = version + 1, updated_at = NOW()
+             WHERE id = $1",
+            report_id,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(map_sqlx_error)?;
+        audit::record_change(
+            &mut tx,
+            Some(actor.employee_id),
+            audit::EXPENSE_REPORT,
+            report_id,
+            audit::DELETED,
+            before,
+        )
+        .await?;
+        tx.commit().await.map_err(map_sqlx_error)
+    }
+
+    /// Soft-deletes an item on one of the actor's draft reports and
+    /// recalculates the report's totals without it.
+    pub async fn delete_item(
+        &self,
+        actor: &crate::infrastructure::auth::AuthenticatedUser,
+        item_id: Uuid,
+    ) -> Result<(), ServiceError> {
+        let mut tx = self.state.pool.begin().await.map_err(map_sqlx_error)?;
+        let report_id = sqlx::query_scalar!(
+            "SELECT report_id FROM expense_items WHERE id = $1 AND deleted_at IS NULL",
+            item_id,
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(map_sqlx_error)?
+        .ok_or(ServiceError::NotFound)?;
+        lock_draft(&mut tx, actor, report_id).await?;
+        let before = audit::EXPENSE_ITEM.snapshot(&mut tx, item_id).await?;
+        sqlx::query!(
+            "UPDATE expense_items SET deleted_at = NOW() WHERE id = $1",
+            item_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(map_sqlx_error)?;
+        refresh_totals(&mut tx, report_id).await?;
+        audit::record_change(
+            &mut tx,
+            Some(actor.employee_id),
+            audit::EXPENSE_ITEM,
+            item_id,
+            audit::DELETED,
+            before,
+        )
+        .await?;
+        tx.commit().await.map_err(map_sqlx_error)
+    }
+
+    /// Soft-deletes a receipt attached to an item on one of the actor's draft
+    /// reports. The stored file is kept so the receipt can be restored.
+    pub async fn delete_receipt(
+        &self,
+        actor: &crate::infrastructure::auth::AuthenticatedUser,
+        receipt_id: Uuid,
+    ) -> Result<(), ServiceError> {
+        let mut tx = self.state.pool.begin().await.map_err(map_sqlx_error)?;
+        let report_id = sqlx::query_scalar!(
+            "SELECT i.report_id
+             FROM receipts rc
+             JOIN expense_items i ON i.id = rc.expense_item_id
+             WHERE rc.id = $1 AND rc.deleted_at IS NULL AND i.deleted_at IS NULL",
+            receipt_id,
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(map_sqlx_error)?
+        .ok_or(ServiceError::NotFound)?;
+        lock_draft(&mut tx, actor, report_id).await?;
+        let before = audit::RECEIPT.snapshot(&mut tx, receipt_id).await?;
+        sqlx::query!(
+            "UPDATE receipts SET deleted_at = NOW() WHERE id = $1",
+            receipt_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(map_sqlx_error)?;
+        // Bump the version so the report's ETag changes with its receipts.
+        sqlx::query!(
+            "UPDATE expense_reports SET version = version + 1, updated_at = NOW() WHERE id = $1",
+            report_id,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(map_sqlx_error)?;
+        audit::record_change(
+            &mut tx,
+            Some(actor.employee_id),
+            audit::RECEIPT,
+            receipt_id,
+            audit::DELETED,
+            before,
+        )
+        .await?;
+        tx.commit().await.map_err(map_sqlx_error)
+    }
+
+    /// Restores a soft-deleted report. Restricted to administrators; a report
+    /// that is not deleted is `NotFound`.
+    pub async fn restore_report(
+        &self,
+        actor: &crate::infrastructure::auth::AuthenticatedUser,
+        report_id: Uuid,
+    ) -> Result<(), ServiceError> {
+        require_admin(actor)?;
+        let mut tx = self.state.pool.begin().await.map_err(map_sqlx_error)?;
+        let before = audit::EXPENSE_REPORT.snapshot(&mut tx, report_id).await?;
+        let result = sqlx::query!(
+            "UPDATE expense_reports
+             SET deleted_at = NULL, version = version + 1, updated_at = NOW()
+             WHERE id = $1 AND org_id = $2 AND deleted_at IS NOT NULL",
+            report_id,
+            actor.org_id,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(map_sqlx_error)?;
+        if result.rows_affected() == 0 {
+            return Err(ServiceError::NotFound);
+        }
+        audit::record_change(
+            &mut tx,
+            Some(actor.employee_id),
+            audit::EXPENSE_REPORT,
+            report_id,
+            audit::RESTORED,
+            before,
+        )
+        .await?;
+        tx.commit().await.map_err(map_sqlx_error)
+    }
+
+    /// Restores a soft-deleted item and adds it back into its report's
+    /// totals. Restricted to administrators, and only while the report is
+    /// still a draft, so submitted amounts never change under a reviewer.
+    pub async fn restore_item(
+        &self,
+        actor: &crate::infrastructure::auth::AuthenticatedUser,
+        item_id: Uuid,
+    ) -> Result<(), ServiceError> {
+        require_admin(actor)?;
+        let mut tx = self.state.pool.begin().await.map_err(map_sqlx_error)?;
+        let report_id = sqlx::query_scalar!(
+            "SELECT report_id FROM expense_items WHERE id = $1 AND deleted_at IS NOT NULL",
+            item_id,
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(map_sqlx_error)?
+        .ok_or(ServiceError::NotFound)?;
+        lock_restorable(&mut tx, actor, report_id).await?;
+        let before = audit::EXPENSE_ITEM.snapshot(&mut tx, item_id).await?;
+        sqlx::query!(
+            "UPDATE expense_items SET deleted_at = NULL WHERE id = $1",
+            item_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(map_sqlx_error)?;
+        refresh_totals(&mut tx, report_id).await?;
+        audit::record_change(
+            &mut tx,
+            Some(actor.employee_id),
+            audit::EXPENSE_ITEM,
+            item_id,
+            audit::RESTORED,
+            before,
+        )
+        .await?;
+        tx.commit().await.map_err(map_sqlx_error)
+    }
+
+    /// Restores a soft-deleted receipt. Restricted to administrators, and
+    /// only while its report is still a draft.
+    pub async fn restore_receipt(
+        &self,
+        actor: &crate::infrastructure::auth::AuthenticatedUser,
+        receipt_id: Uuid,
+    ) -> Result<(), ServiceError> {
+        require_admin(actor)?;
+        let mut tx = self.state.pool.begin().await.map_err(map_sqlx_error)?;
+        let report_id = sqlx::query_scalar!(
+            "SELECT i.report_id
+             FROM receipts rc
+             JOIN expense_items i ON i.id = rc.expense_item_id
+             WHERE rc.id = $1 AND rc.deleted_at IS NOT NULL",
+            receipt_id,
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(map_sqlx_error)?
+        .ok_or(ServiceError::NotFound)?;
+        lock_restorable(&mut tx, actor, report_id).await?;
+        let before = audit::RECEIPT.snapshot(&mut tx, receipt_id).await?;
+        sqlx::query!(
+            "UPDATE receipts SET deleted_at = NULL WHERE id = $1",
+            receipt_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(map_sqlx_error)?;
+        sqlx::query!(
+            "UPDATE expense_reports SET version = version + 1, updated_at = NOW() WHERE id = $1",
+            report_id,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(map_sqlx_error)?;
+        audit::record_change(
+            &mut tx,
+            Some(actor.employee_id),
+            audit::RECEIPT,
+            receipt_id,
+            audit::RESTORED,
+            before,
+        )
+        .await?;
+        tx.commit().await.map_err(map_sqlx_error)
+    }
+
+    /// Evaluates the report; the flag tells whether it was a draft.
+    async fn run_evaluation(
+        &self,
+        actor: &crate::infrastructure::auth::AuthenticatedUser,
+        report_id: Uuid,
+    ) -> Result<(PolicyEvaluation, bool), ServiceError> {
+        let report = sqlx::query!(
+            r#"SELECT r.employee_id, r.org_id, r.currency, r.status::text AS "status!", r.submitted_at,
+                      e.department, t.departure_date AS "departure_date?",
+                      t.return_date AS "return_date?", t.status = $2 AS trip_approved
+               FROM expense_reports r
+               JOIN employees e ON e.id = r.employee_id
+               LEFT JOIN trips t ON t.id = r.trip_id
+               WHERE r.id = $1 AND r.deleted_at IS NULL"#,
+            report_id,
+            TripStatus::Approved.as_str(),
+        )
+        .fetch_optional(&self.state.pool)
+        .await
+        .map_err(map_sqlx_error)?
+        .ok_or(ServiceError::NotFound)?;
+        let owner_id = report.employee_id;
+        let org_id = report.org_id;
+        let department = report.department;
+        let departure_date = report.departure_date;
+        let return_date = report.return_date;
+        let trip_approved = report.trip_approved;
+
+        if org_id != actor.org_id {
+            return Err(ServiceError::NotFound);
+        }
         let is_reviewer = matches!(actor.role, Role::Manager | Role::Finance | Role::Admin);
         if actor.employee_id != owner_id && !is_reviewer {
             return Err(ServiceError::Forbidden);
         }
 
-        let item_rows = sqlx::query(
+        let is_draft = report.status == ReportStatus::Draft.as_str();
+        if !is_draft {
+            if let Some(stored) =
+                policy_versions::stored_evaluation(&self.state.pool, report_id).await?
+            {
+                return Ok((stored, false));
+            }
+        }
+
+        let items = sqlx::query_as!(
+            ExpenseItem,
             r#"
-            SELECT id, report_id, expense_date, category, gl_account_id, description,
-                   attendees, location, amount_cents, reimbursable, payment_method, is_policy_exception
+            SELECT id, report_id, expense_date, category AS "category: ExpenseCategory",
+                   gl_account_id, description, attendees, location, amount_cents, reimbursable,
+                   payment_method, is_policy_exception, exception_justification, class,
+                   tax_amount_cents, tax_jurisdiction, miles::float8 AS miles, merchant,
+                   cost_center_id
             FROM expense_items
-            WHERE report_id = $1
+            WHERE report_id = $1 AND deleted_at IS NULL
             "#,
+            report_id,
         )
-        .bind(report_id)
         .fetch_all(&self.state.pool)
         .await
         .map_err(map_sqlx_error)?;
 
-        let mut items = Vec::with_capacity(item_rows.len());
-        for row in item_rows {
-            items.push(map_expense_item(row)?);
-        }
-
         if items.is_empty() {
-            return Ok(PolicyEvaluation::ok());
+            return Ok((PolicyEvaluation::ok(), is_draft));
         }
+        let currency = Currency::parse(&report.currency)?;
 
         let mut category_keys: HashSet<ExpenseCategory> = HashSet::new();
         for item in &items {
@@ -287,135 +885,329 @@ impl ExpenseService {
         }
         let categories: Vec<ExpenseCategory> = category_keys.into_iter().collect();
 
-        let cap_rows = if categories.is_empty() {
+        let historical = match report.submitted_at.filter(|_| !is_draft) {
+            Some(at) => policy_versions::version_at(&self.state.pool, org_id, at).await?,
+            None => None,
+        };
+        let (policy_version, rules) = match historical {
+            Some((version, rules)) => (Some(version), rules),
+            None => policy_rules::current_rules(&self.state, org_id, &categories).await?,
+        };
+
+        let mut fiscal_years: Vec<i32> = items
+            .iter()
+            .filter(|item| item.category == ExpenseCategory::Meal && item.location.is_some())
+            .map(|item| current_fiscal_year(item.expense_date).1)
+            .collect();
+        fiscal_years.sort_unstable();
+        fiscal_years.dedup();
+        let per_diem_rates = if fiscal_years.is_empty() {
             Vec::new()
         } else {
-            sqlx::query(
-                r#"
-                SELECT id, policy_key, category, limit_type, amount_cents, notes, active_from, active_to
-                FROM policy_caps
-                WHERE category = ANY($1)
-                "#,
-            )
-            .bind(categories)
-            .fetch_all(&self.state.pool)
-            .await
-            .map_err(map_sqlx_error)?
+            per_diem::applicable_rates(&self.state.pool, &fiscal_years).await?
         };
+        let last_mileage = items
+            .iter()
+            .filter(|item| item.category == ExpenseCategory::Mileage && item.miles.is_some())
+            .map(|item| item.expense_date)
+            .max();
+        let rates = RateTables {
+            per_diem: per_diem_rates,
+            mileage: match last_mileage {
+                Some(date) => mileage_rates::rates_through(&self.state.pool, date).await?,
+                None => Vec::new(),
+            },
+        };
+
+        let captured = sqlx::query!(
+            r#"
+            SELECT r.expense_item_id, r.captured_at
+            FROM receipts r
+            JOIN expense_items i ON i.id = r.expense_item_id
+            WHERE i.report_id = $1 AND i.deleted_at IS NULL AND r.deleted_at IS NULL
+            "#,
+            report_id,
+        )
+        .fetch_all(&self.state.pool)
+        .await
+        .map_err(map_sqlx_error)?;
 
-        let mut caps = Vec::with_capacity(cap_rows.len());
-        for row in cap_rows {
-            caps.push(map_policy_cap(row)?);
+        let mut receipted: HashSet<Uuid> = HashSet::new();
+        let mut captures: HashMap<Uuid, Vec<NaiveDateTime>> = HashMap::new();
+        for receipt in captured {
+            receipted.insert(receipt.expense_item_id);
+            if let Some(captured_at) = receipt.captured_at {
+                captures
+                    .entry(receipt.expense_item_id)
+                    .or_default()
+                    .push(captured_at);
+            }
         }
 
-        Ok(aggregate_policy_evaluation(&items, &caps))
+        let first_date = items.iter().map(|item| item.expense_date).min();
+        let last_date = items.iter().map(|item| item.expense_date).max();
+        let calendar = WorkCalendar {
+            holidays: match first_date.zip(last_date) {
+                Some((from, to)) => holidays::holidays_between(&self.state.pool, from, to).await?,
+                None => HashMap::new(),
+            },
+            trip: departure_date
+                .zip(return_date)
+                .filter(|_| trip_approved == Some(true)),
+            travel_date: departure_date,
+        };
+        let duplicates = duplicates::find_duplicates(&self.state.pool, report_id).await?;
+
+        let mut evaluation = aggregate_policy_evaluation(
+            &items,
+            currency,
+            &rules,
+            &rates,
+            &receipted,
+            &calendar,
+            &duplicates,
+            &captures,
+            self.state.config.receipts.capture_date_tolerance_days,
+        );
+
+        let department = department.as_deref();
+        let budgets = budgets::applicable_budgets(&self.state.pool, department).await?;
+        let mut usage = budgets::load_usage(
+            &self.state.pool,
+            report_id,
+            owner_id,
+            department,
+            &items,
+            &budgets,
+        )
+        .await?;
+        let mut budget_currency = currency;
+        if currency != POLICY_CURRENCY && !usage.is_empty() {
+            let quote = fx::rate(
+                &self.state.pool,
+                currency,
+                POLICY_CURRENCY,
+                Utc::now().date_naive(),
+                self.state.config.fx.stale_after_days,
+            )
+            .await?;
+            if let Some(quote) = quote {
+                for entry in &mut usage {
+                    entry.report_cents = quote
+                        .rate
+                        .convert(Money::new(entry.report_cents, currency), POLICY_CURRENCY)?
+                        .amount_minor;
+                }
+                budget_currency = POLICY_CURRENCY;
+            }
+        }
+        evaluation.merge(evaluate_budgets(&usage, budget_currency));
+        evaluation.policy_version = policy_version;
+        Ok((evaluation, is_draft))
+    }
+
+    /// Evaluates one unsaved item against the current item-level rules and
+    /// category caps, for warnings while the employee fills in the item.
+    /// Day and report rules, per-diem and mileage rates, holidays, budgets,
+    /// and duplicate checks need the saved report and run on
+    /// `evaluate_report`. Rules are those of the actor's organization.
+    pub async fn check_item(
+        &self,
+        actor: &crate::infrastructure::auth::AuthenticatedUser,
+        currency: &str,
+        item: CreateExpenseItem,
+    ) -> Result<PolicyEvaluation, ServiceError> {
+        let currency = Currency::parse(currency)?;
+        let has_receipt = !item.receipts.is_empty();
+        let item = ExpenseItem {
+            id: Uuid::nil(),
+            report_id: Uuid::nil(),
+            expense_date: item.expense_date,
+            category: item.category,
+            gl_account_id: None,
+            description: item.description,
+            attendees: item.attendees,
+            location: item.location,
+            amount_cents: item.amount_cents,
+            reimbursable: item.reimbursable,
+            payment_method: item.payment_method,
+            is_policy_exception: item.is_policy_exception,
+            exception_justification: item.exception_justification,
+            class: item.class,
+            tax_amount_cents: item.tax_amount_cents,
+            tax_jurisdiction: normalize_tax_jurisdiction(item.tax_jurisdiction.as_deref()),
+            miles: item.miles,
+            merchant: item.merchant,
+            cost_center_id: item.cost_center_id,
+        };
+        let (policy_version, rules) =
+            policy_rules::current_rules(&self.state, actor.org_id, &[item.category]).await?;
+
+        let mut evaluation = evaluate_item(&item, currency, &rules, has_receipt);
+        evaluation.policy_version = policy_version;
+        Ok(evaluation)
     }
 }
 
-fn calculate_totals(items: &[CreateExpenseItem]) -> (i64, i64) {
-    let mut total_amount = 0_i64;
-    let mut total_reimbursable = 0_i64;
+fn calculate_totals(
+    items: &[CreateExpenseItem],
+    currency: Currency,
+) -> Result<(Money, Money), MoneyError> {
+    let mut total_amount = Money::zero(currency);
+    let mut total_reimbursable = Money::zero(currency);
 
     for item in items {
-        total_amount += item.amount_cents;
+        let amount = Money::new(item.amount_cents, currency);
+        total_amount = total_amount.checked_add(amount)?;
         if item.reimbursable {
-            total_reimbursable += item.amount_cents;
+            total_reimbursable = total_reimbursable.checked_add(amount)?;
         }
     }
 
-    (total_amount, total_reimbursable)
+    Ok((total_amount, total_reimbursable))
+}
+
+/// Report fields shared by the submission notifications' payloads.
+fn report_summary(report: &ExpenseReport) -> serde_json::Value {
+    serde_json::json!({
+        "report_id": report.id,
+        "employee_id": report.employee_id,
+        "reporting_period_start": report.reporting_period_start,
+        "reporting_period_end": report.reporting_period_end,
+        "total_amount_cents": report.total_amount_cents,
+        "currency": report.currency,
+    })
 }
 
-fn map_report(row: PgRow) -> ExpenseReport {
-    ExpenseReport {
-        id: row.get("id"),
-        employee_id: row.get("employee_id"),
-        reporting_period_start: row.get("reporting_period_start"),
-        reporting_period_end: row.get("reporting_period_end"),
-        status: row.get("status"),
-        total_amount_cents: row.get("total_amount_cents"),
-        total_reimbursable_cents: row.get("total_reimbursable_cents"),
-        currency: row.get("currency"),
-        version: row.get("version"),
-        created_at: row.get("created_at"),
-        updated_at: row.get("updated_at"),
+/// Locks the actor's report for a deletion: `NotFound` when it is deleted or
+/// missing, `Forbidden` when someone else owns it, and `Conflict` once it has
+/// left `draft`.
+async fn lock_draft(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    actor: &crate::infrastructure::auth::AuthenticatedUser,
+    report_id: Uuid,
+) -> Result<(), ServiceError> {
+    let report = sqlx::query!(
+        r#"SELECT employee_id, status::text AS "status!" FROM expense_reports
+           WHERE id = $1 AND deleted_at IS NULL
+           FOR UPDATE"#,
+        report_id,
+    )
+    .fetch_optional(tx.as_mut())
+    .await
+    .map_err(map_sqlx_error)?
+    .ok_or(ServiceError::NotFound)?;
+    if report.employee_id != actor.employee_id {
+        return Err(ServiceError::Forbidden);
+    }
+    if report.status != ReportStatus::Draft.as_str() {
+        return Err(ServiceError::Conflict);
     }
+    Ok(())
 }
 
-fn map_expense_item(row: PgRow) -> Result<ExpenseItem, ServiceError> {
-    let category = row
-        .try_get::<ExpenseCategory, _>("category")
-        .map_err(map_sqlx_error)?;
-    Ok(ExpenseItem {
-        id: row.try_get("id").map_err(map_sqlx_error)?,
-        report_id: row.try_get("report_id").map_err(map_sqlx_error)?,
-        expense_date: row.try_get("expense_date").map_err(map_sqlx_error)?,
-        category,
-        gl_account_id: row
-            .try_get::<Option<Uuid>, _>("gl_account_id")
-            .map_err(map_sqlx_error)?,
-        description: row
-            .try_get::<Option<String>, _>("description")
-            .map_err(map_sqlx_error)?,
-        attendees: row
-            .try_get::<Option<String>, _>("attendees")
-            .map_err(map_sqlx_error)?,
-        location: row
-            .try_get::<Option<String>, _>("location")
-            .map_err(map_sqlx_error)?,
-        amount_cents: row
-            .try_get::<i64, _>("amount_cents")
-            .map_err(map_sqlx_error)?,
-        reimbursable: row
-            .try_get::<bool, _>("reimbursable")
-            .map_err(map_sqlx_error)?,
-        payment_method: row
-            .try_get::<Option<String>, _>("payment_method")
-            .map_err(map_sqlx_error)?,
-        is_policy_exception: row
-            .try_get::<bool, _>("is_policy_exception")
-            .map_err(map_sqlx_error)?,
-    })
+/// Locks the report an item or receipt is restored onto: `NotFound` while the
+/// report itself is deleted or belongs to another organization than the
+/// actor's, `Conflict` once it has left `draft`.
+async fn lock_restorable(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    actor: &crate::infrastructure::auth::AuthenticatedUser,
+    report_id: Uuid,
+) -> Result<(), ServiceError> {
+    let status = sqlx::query_scalar!(
+        r#"SELECT status::text AS "status!" FROM expense_reports
+           WHERE id = $1 AND org_id = $2 AND deleted_at IS NULL
+           FOR UPDATE"#,
+        report_id,
+        actor.org_id,
+    )
+    .fetch_optional(tx.as_mut())
+    .await
+    .map_err(map_sqlx_error)?
+    .ok_or(ServiceError::NotFound)?;
+    if status != ReportStatus::Draft.as_str() {
+        return Err(ServiceError::Conflict);
+    }
+    Ok(())
 }
 
-fn map_policy_cap(row: PgRow) -> Result<PolicyCap, ServiceError> {
-    let category = row
-        .try_get::<ExpenseCategory, _>("category")
-        .map_err(map_sqlx_error)?;
-    Ok(PolicyCap {
-        id: row.try_get("id").map_err(map_sqlx_error)?,
-        policy_key: row.try_get("policy_key").map_err(map_sqlx_error)?,
-        category,
-        limit_type: row
-            .try_get::<String, _>("limit_type")
-            .map_err(map_sqlx_error)?,
-        amount_cents: row
-            .try_get::<i64, _>("amount_cents")
-            .map_err(map_sqlx_error)?,
-        notes: row
-            .try_get::<Option<String>, _>("notes")
-            .map_err(map_sqlx_error)?,
-        active_from: row
-            .try_get::<chrono::NaiveDate, _>("active_from")
-            .map_err(map_sqlx_error)?,
-        active_to: row
-            .try_get::<Option<chrono::NaiveDate>, _>("active_to")
-            .map_err(map_sqlx_error)?,
-    })
+/// Recomputes the report's totals from its remaining items and bumps its
+/// version.
+async fn refresh_totals(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    report_id: Uuid,
+) -> Result<(), ServiceError> {
+    sqlx::query!(
+        "UPDATE expense_reports r
+         SET total_amount_cents = totals.amount,
+             total_reimbursable_cents = totals.reimbursable,
+             version = r.version + 1,
+             updated_at = NOW()
+         FROM (
+             SELECT COALESCE(SUM(amount_cents), 0)::BIGINT AS amount,
+                    COALESCE(SUM(amount_cents) FILTER (WHERE reimbursable), 0)::BIGINT
+                        AS reimbursable
+             FROM expense_items
+             WHERE report_id = $1 AND deleted_at IS NULL
+         ) totals
+         WHERE r.id = $1",
+        report_id,
+    )
+    .execute(tx.as_mut())
+    .await
+    .map_err(map_sqlx_error)?;
+    Ok(())
+}
+
+fn require_admin(
+    actor: &crate::infrastructure::auth::AuthenticatedUser,
+) -> Result<(), ServiceError> {
+    if actor.role != Role::Admin {
+        return Err(ServiceError::Forbidden);
+    }
+    Ok(())
 }
 
-fn aggregate_policy_evaluation(items: &[ExpenseItem], caps: &[PolicyCap]) -> PolicyEvaluation {
-    let mut evaluation = PolicyEvaluation::ok();
+#[allow(clippy::too_many_arguments)]
+fn aggregate_policy_evaluation(
+    items: &[ExpenseItem],
+    currency: Currency,
+    rules: &[PolicyRule],
+    rates: &RateTables,
+    receipted: &HashSet<Uuid>,
+    calendar: &WorkCalendar,
+    duplicates: &DuplicateMatches,
+    captures: &HashMap<Uuid, Vec<NaiveDateTime>>,
+    capture_tolerance_days: u32,
+) -> PolicyEvaluation {
+    let mut evaluation = evaluate_items(
+        items, currency, rules, rates, receipted, calendar, duplicates,
+    );
 
     for item in items {
-        let item_evaluation = evaluate_item(item, caps);
-        evaluation.merge(item_evaluation);
-        if item.is_policy_exception {
-            evaluation.warnings.push(format!(
-                "Expense item {} marked as a policy exception",
-                item.id
+        if let Some(captured_at) = captures.get(&item.id) {
+            evaluation.merge(check_receipt_capture_dates(
+                item,
+                captured_at,
+                capture_tolerance_days,
             ));
         }
+        if item.is_policy_exception {
+            let message = match &item.exception_justification {
+                Some(justification) => format!(
+                    "Expense item {} marked as a policy exception: {}",
+                    item.id, justification
+                ),
+                None => format!("Expense item {} marked as a policy exception", item.id),
+            };
+            evaluation.push(
+                FindingSeverity::Warning,
+                POLICY_EXCEPTION_CODE,
+                message,
+                Some(item.id),
+                None,
+            );
+        }
     }
 
     evaluation
@@ -429,16 +1221,23 @@ fn map_sqlx_error(err: sqlx::Error) -> ServiceError {
 mod tests {
     use super::*;
     use chrono::NaiveDate;
-    use sqlx::{postgres::PgPoolOptions, PgPool};
+    use sqlx::{postgres::PgPoolOptions, PgPool, Row};
     use uuid::Uuid;
 
     use crate::{
-        domain::models::Role,
+        domain::models::{
+            Role, RuleComparison, RuleCondition, RuleScope, RuleSeverity, DEFAULT_ORG_ID,
+        },
         infrastructure::{
             auth::AuthenticatedUser,
             config::{
-                AppConfig, AuthConfig, Config, DatabaseConfig, NetSuiteConfig, ReceiptRules,
-                StorageConfig,
+                AppConfig, ApprovalLinkConfig, ArchiveConfig, AuditVerifyConfig, AuthConfig,
+                AutoFinalizeConfig, BankingConfig, ChatConfig, Config, DatabaseConfig,
+                DigestConfig, EmailConfig, EscalationConfig, FinalizationConfig, FxConfig,
+                GrpcConfig, HrSyncConfig, JobsConfig, JournalExportConfig, NetSuiteConfig,
+                OutboxConfig, PolicyConfig, PurgeConfig, ReceiptRules, ReconciliationConfig,
+                ReminderConfig, RetentionConfig, StaleDraftConfig, StorageConfig, TelemetryConfig,
+                WebhooksConfig,
             },
             state::AppState,
             storage,
@@ -464,19 +1263,36 @@ mod tests {
             reimbursable: true,
             payment_method: None,
             is_policy_exception: is_exception,
+            exception_justification: None,
+            class: None,
+            tax_amount_cents: None,
+            tax_jurisdiction: None,
+            miles: None,
+            merchant: None,
+            cost_center_id: None,
         }
     }
 
-    fn meal_cap(amount_cents: i64, active_from: NaiveDate) -> PolicyCap {
-        PolicyCap {
+    fn meal_cap(amount_cents: i64, active_from: NaiveDate) -> PolicyRule {
+        PolicyRule {
             id: Uuid::new_v4(),
-            policy_key: "meal_per_diem".to_string(),
-            category: ExpenseCategory::Meal,
-            limit_type: "per_diem".to_string(),
-            amount_cents,
-            notes: None,
+            org_id: None,
+            name: "meal_per_diem".to_string(),
+            category: Some(ExpenseCategory::Meal),
+            comparison: RuleComparison::Gt,
+            threshold_cents: amount_cents,
+            scope: RuleScope::Day,
+            severity: RuleSeverity::Violation,
+            condition: RuleCondition::Always,
+            per_attendee: false,
+            advance_days: None,
+            message: "Meals on {date} total {amount}, above the per-diem limit of {limit}"
+                .to_string(),
             active_from,
             active_to: None,
+            enabled: true,
+            updated_by: None,
+            updated_at: chrono::Utc::now(),
         }
     }
 
@@ -486,11 +1302,62 @@ mod tests {
         let caps = vec![meal_cap(5_000, date)];
         let items = vec![expense_item(Uuid::new_v4(), date, 4_000, false)];
 
-        let evaluation = aggregate_policy_evaluation(&items, &caps);
+        let evaluation = aggregate_policy_evaluation(
+            &items,
+            Currency::USD,
+            &caps,
+            &RateTables::default(),
+            &HashSet::new(),
+            &WorkCalendar::default(),
+            &DuplicateMatches::default(),
+            &HashMap::new(),
+            3,
+        );
+
+        assert!(evaluation.is_valid);
+        assert!(evaluation.violations().is_empty());
+        assert!(evaluation.warnings().is_empty());
+    }
+
+    #[test]
+    fn aggregate_policy_evaluation_warns_on_receipt_capture_drift() {
+        let date = NaiveDate::from_ymd_opt(2024, 5, 10).unwrap();
+        let on_time = Uuid::new_v4();
+        let drifted = Uuid::new_v4();
+        let items = vec![
+            expense_item(on_time, date, 2_000, false),
+            expense_item(drifted, date, 2_000, false),
+        ];
+        let captures = HashMap::from([
+            (
+                on_time,
+                vec![date.pred_opt().unwrap().and_hms_opt(19, 0, 0).unwrap()],
+            ),
+            (
+                drifted,
+                vec![NaiveDate::from_ymd_opt(2024, 4, 20)
+                    .unwrap()
+                    .and_hms_opt(8, 0, 0)
+                    .unwrap()],
+            ),
+        ]);
+
+        let evaluation = aggregate_policy_evaluation(
+            &items,
+            Currency::USD,
+            &[],
+            &RateTables::default(),
+            &HashSet::new(),
+            &WorkCalendar::default(),
+            &DuplicateMatches::default(),
+            &captures,
+            3,
+        );
 
         assert!(evaluation.is_valid);
-        assert!(evaluation.violations.is_empty());
-        assert!(evaluation.warnings.is_empty());
+        assert_eq!(evaluation.warnings().len(), 1);
+        assert!(evaluation.warnings()[0].contains(&drifted.to_string()));
+        assert!(evaluation.warnings()[0].contains("20 days"));
     }
 
     #[test]
@@ -500,15 +1367,53 @@ mod tests {
         let item_id = Uuid::new_v4();
         let items = vec![expense_item(item_id, date, 7_500, true)];
 
-        let evaluation = aggregate_policy_evaluation(&items, &caps);
+        let evaluation = aggregate_policy_evaluation(
+            &items,
+            Currency::USD,
+            &caps,
+            &RateTables::default(),
+            &HashSet::new(),
+            &WorkCalendar::default(),
+            &DuplicateMatches::default(),
+            &HashMap::new(),
+            3,
+        );
 
         assert!(!evaluation.is_valid);
         assert!(evaluation
-            .violations
+            .violations()
             .iter()
-            .any(|msg| msg.contains("Meal exceeds per-diem limit")));
-        assert_eq!(evaluation.warnings.len(), 1);
-        assert!(evaluation.warnings[0].contains(item_id.to_string().as_str()));
+            .any(|msg| msg.contains("above the per-diem limit")));
+        assert_eq!(evaluation.warnings().len(), 1);
+        assert!(evaluation.warnings()[0].contains(item_id.to_string().as_str()));
+    }
+
+    #[test]
+    fn aggregate_policy_evaluation_caps_meals_per_day() {
+        let date = NaiveDate::from_ymd_opt(2024, 4, 2).unwrap();
+        let caps = vec![meal_cap(7_500, date)];
+        let items: Vec<ExpenseItem> = [date, date, date, date.succ_opt().unwrap()]
+            .into_iter()
+            .map(|day| expense_item(Uuid::new_v4(), day, 4_000, false))
+            .collect();
+
+        let evaluation = aggregate_policy_evaluation(
+            &items,
+            Currency::USD,
+            &caps,
+            &RateTables::default(),
+            &HashSet::new(),
+            &WorkCalendar::default(),
+            &DuplicateMatches::default(),
+            &HashMap::new(),
+            3,
+        );
+
+        assert!(!evaluation.is_valid);
+        assert_eq!(
+            evaluation.violations(),
+            vec!["Meals on 2024-04-02 total $120.00, above the per-diem limit of $75.00"]
+        );
     }
 
     #[test]
@@ -524,6 +1429,14 @@ mod tests {
                 amount_cents: 2_500,
                 reimbursable: true,
                 payment_method: None,
+                is_policy_exception: false,
+                exception_justification: None,
+                class: None,
+                tax_amount_cents: None,
+                tax_jurisdiction: None,
+                miles: None,
+                merchant: None,
+                cost_center_id: None,
                 receipts: Vec::new(),
             },
             CreateExpenseItem {
@@ -535,14 +1448,50 @@ mod tests {
                 amount_cents: 7_500,
                 reimbursable: false,
                 payment_method: None,
+                is_policy_exception: false,
+                exception_justification: None,
+                class: None,
+                tax_amount_cents: None,
+                tax_jurisdiction: None,
+                miles: None,
+                merchant: None,
+                cost_center_id: None,
                 receipts: Vec::new(),
             },
         ];
 
-        let (total, reimbursable) = calculate_totals(&items);
+        let (total, reimbursable) = calculate_totals(&items, Currency::USD).unwrap();
 
-        assert_eq!(total, 10_000);
-        assert_eq!(reimbursable, 2_500);
+        assert_eq!(total, Money::new(10_000, Currency::USD));
+        assert_eq!(reimbursable, Money::new(2_500, Currency::USD));
+    }
+
+    #[test]
+    fn calculate_totals_rejects_overflowing_amounts() {
+        let date = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        let item = CreateExpenseItem {
+            expense_date: date,
+            category: ExpenseCategory::Other,
+            description: None,
+            attendees: None,
+            location: None,
+            amount_cents: i64::MAX,
+            reimbursable: true,
+            payment_method: None,
+            is_policy_exception: false,
+            exception_justification: None,
+            class: None,
+            tax_amount_cents: None,
+            tax_jurisdiction: None,
+            miles: None,
+            merchant: None,
+            cost_center_id: None,
+            receipts: Vec::new(),
+        };
+
+        let result = calculate_totals(&[item.clone(), item], Currency::USD);
+
+        assert_eq!(result, Err(MoneyError::Overflow));
     }
 
     #[tokio::test]
@@ -584,14 +1533,22 @@ mod tests {
         .execute(&pool)
         .await?;
 
-        let mut storage_config = StorageConfig::default();
-        storage_config.provider = "memory".to_string();
+        let storage_config = StorageConfig {
+            provider: "memory".to_string(),
+            ..StorageConfig::default()
+        };
 
         let config = Arc::new(Config {
             app: AppConfig::default(),
             database: DatabaseConfig {
                 url: "postgres://integration".to_string(),
                 max_connections: 5,
+                slow_query_threshold_ms: 500,
+                read_url: None,
+                acquire_timeout_secs: 30,
+                idle_timeout_secs: 600,
+                max_lifetime_secs: 1800,
+                statement_timeout_ms: 60_000,
             },
             auth: AuthConfig {
                 jwt_secret: "integration-secret".to_string(),
@@ -603,6 +1560,30 @@ mod tests {
             storage: storage_config,
             netsuite: NetSuiteConfig::default(),
             receipts: ReceiptRules::default(),
+            reminders: ReminderConfig::default(),
+            digest: DigestConfig::default(),
+            purge: PurgeConfig::default(),
+            stale_drafts: StaleDraftConfig::default(),
+            escalations: EscalationConfig::default(),
+            retention: RetentionConfig::default(),
+            archive: ArchiveConfig::default(),
+            audit_verify: AuditVerifyConfig::default(),
+            outbox: OutboxConfig::default(),
+            fx: FxConfig::default(),
+            hr_sync: HrSyncConfig::default(),
+            email: EmailConfig::default(),
+            chat: ChatConfig::default(),
+            webhooks: WebhooksConfig::default(),
+            telemetry: TelemetryConfig::default(),
+            jobs: JobsConfig::default(),
+            approval_links: ApprovalLinkConfig::default(),
+            journal_export: JournalExportConfig::default(),
+            auto_finalize: AutoFinalizeConfig::default(),
+            reconciliation: ReconciliationConfig::default(),
+            finalization: FinalizationConfig::default(),
+            policy: PolicyConfig::default(),
+            grpc: GrpcConfig::default(),
+            banking: BankingConfig::default(),
         });
 
         let storage = storage::build_storage(&config.storage)?;
@@ -611,6 +1592,7 @@ mod tests {
         let actor = AuthenticatedUser {
             employee_id,
             role: Role::Employee,
+            org_id: DEFAULT_ORG_ID,
         };
 
         let reporting_period_start = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
@@ -618,7 +1600,8 @@ mod tests {
         let payload = CreateReportRequest {
             reporting_period_start,
             reporting_period_end,
-            currency: "USD".to_string(),
+            currency: Some("USD".to_string()),
+            trip_id: None,
             items: vec![
                 CreateExpenseItem {
                     expense_date: reporting_period_start,
@@ -629,6 +1612,14 @@ mod tests {
                     amount_cents: 4_200,
                     reimbursable: true,
                     payment_method: Some("corporate_card".to_string()),
+                    is_policy_exception: false,
+                    exception_justification: None,
+                    class: Some("PRJ-204".to_string()),
+                    tax_amount_cents: Some(350),
+                    tax_jurisdiction: Some(" us-or ".to_string()),
+                    miles: None,
+                    merchant: None,
+                    cost_center_id: None,
                     receipts: vec![CreateReceiptReference {
                         file_key: "draft-receipt-1".to_string(),
                         file_name: "lunch.pdf".to_string(),
@@ -645,6 +1636,14 @@ mod tests {
                     amount_cents: 18_500,
                     reimbursable: false,
                     payment_method: Some("personal_card".to_string()),
+                    is_policy_exception: false,
+                    exception_justification: None,
+                    class: None,
+                    tax_amount_cents: None,
+                    tax_jurisdiction: None,
+                    miles: None,
+                    merchant: None,
+                    cost_center_id: None,
                     receipts: Vec::new(),
                 },
             ],
@@ -653,7 +1652,8 @@ mod tests {
         let report = service.create_report(&actor, payload).await?;
 
         let stored_items = sqlx::query(
-            "SELECT amount_cents, reimbursable FROM expense_items WHERE report_id = $1",
+            "SELECT amount_cents, reimbursable, tax_amount_cents, tax_jurisdiction
+             FROM expense_items WHERE report_id = $1",
         )
         .bind(report.id)
         .fetch_all(&pool)
@@ -661,7 +1661,10 @@ mod tests {
 
         assert_eq!(stored_items.len(), 2);
         assert!(stored_items.iter().any(|row| {
-            row.get::<bool, _>("reimbursable") && row.get::<i64, _>("amount_cents") == 4_200
+            row.get::<bool, _>("reimbursable")
+                && row.get::<i64, _>("amount_cents") == 4_200
+                && row.get::<Option<i64>, _>("tax_amount_cents") == Some(350)
+                && row.get::<Option<String>, _>("tax_jurisdiction").as_deref() == Some("US-OR")
         }));
         assert!(stored_items.iter().any(|row| {
             !row.get::<bool, _>("reimbursable") && row.get::<i64, _>("amount_cents") == 18_500
@@ -678,10 +1681,44 @@ mod tests {
         assert_eq!(report.total_amount_cents, 22_700);
         assert_eq!(report.total_reimbursable_cents, 4_200);
 
+        let lodging_id: Uuid = sqlx::query_scalar(
+            "SELECT id FROM expense_items WHERE report_id = $1 AND NOT reimbursable",
+        )
+        .bind(report.id)
+        .fetch_one(&pool)
+        .await?;
+        service.delete_item(&actor, lodging_id).await?;
+        let trimmed = service.get_report(&actor, report.id).await?;
+        assert_eq!(trimmed.total_amount_cents, 4_200);
+        assert_eq!(trimmed.version, report.version + 1);
+
+        let admin = AuthenticatedUser {
+            employee_id,
+            role: Role::Admin,
+            org_id: DEFAULT_ORG_ID,
+        };
+        service.delete_report(&actor, report.id).await?;
+        assert!(matches!(
+            service.get_report(&actor, report.id).await,
+            Err(ServiceError::NotFound)
+        ));
+        assert!(matches!(
+            service.restore_report(&actor, report.id).await,
+            Err(ServiceError::Forbidden)
+        ));
+        service.restore_report(&admin, report.id).await?;
+        service.restore_item(&admin, lodging_id).await?;
+        let restored = service.get_report(&actor, report.id).await?;
+        assert_eq!(restored.total_amount_cents, 22_700);
+
         sqlx::query("DELETE FROM expense_reports WHERE id = $1")
             .bind(report.id)
             .execute(&pool)
             .await?;
+        sqlx::query("DELETE FROM audit_logs WHERE performed_by = $1")
+            .bind(employee_id)
+            .execute(&pool)
+            .await?;
         sqlx::query("DELETE FROM employees WHERE id = $1")
             .bind(employee_id)
             .execute(&pool)
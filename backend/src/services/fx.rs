@@ -0,0 +1,192 @@
+//! Exchange rates stored in `fx_rates` by `jobs::fx_rates`.
+//!
+//! Rates are kept as published, against the provider's base currency, and
+//! crossed through that base when neither side of a conversion is the base.
+//! A lookup uses the newest rate date on or before the requested day, and
+//! finds nothing once that date is more than `fx.stale_after_days` old, so
+//! callers never convert at a stale rate.
+
+use std::collections::HashMap;
+
+use chrono::{Duration, NaiveDate, Utc};
+use serde::Serialize;
+use sqlx::{postgres::PgRow, PgPool, Row};
+
+use crate::{
+    domain::{fx::ExchangeRate, models::Currency},
+    infrastructure::{config::FxProvider, fx::RateSheet},
+};
+
+use super::errors::ServiceError;
+
+/// Rate used to convert between two currencies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct FxQuote {
+    pub from: Currency,
+    pub to: Currency,
+    pub rate: ExchangeRate,
+    pub rate_date: NaiveDate,
+}
+
+/// Stores a provider's rates, replacing any already stored for the same day.
+/// Returns the number of rates written.
+pub async fn store(
+    pool: &PgPool,
+    sheet: &RateSheet,
+    provider: FxProvider,
+) -> Result<usize, ServiceError> {
+    let mut tx = pool.begin().await.map_err(internal)?;
+    for (quote, rate) in &sheet.rates {
+        sqlx::query(
+            "INSERT INTO fx_rates (base_currency, quote_currency, rate_date, rate, provider, fetched_at)
+             VALUES ($1,$2,$3,$4::numeric,$5,$6)
+             ON CONFLICT (base_currency, quote_currency, rate_date)
+             DO UPDATE SET rate = EXCLUDED.rate, provider = EXCLUDED.provider,
+                           fetched_at = EXCLUDED.fetched_at",
+        )
+        .bind(sheet.base.code())
+        .bind(quote.code())
+        .bind(sheet.rate_date)
+        .bind(rate.to_string())
+        .bind(provider.as_str())
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await
+        .map_err(internal)?;
+    }
+    tx.commit().await.map_err(internal)?;
+    Ok(sheet.rates.len())
+}
+
+/// Date of the newest stored rates, if any.
+pub async fn latest_rate_date(pool: &PgPool) -> Result<Option<NaiveDate>, ServiceError> {
+    sqlx::query_scalar("SELECT MAX(rate_date) FROM fx_rates")
+        .fetch_one(pool)
+        .await
+        .map_err(internal)
+}
+
+/// Rate converting `from` into `to` on `on`, or `None` when no rate dated
+/// within `stale_after_days` before `on` covers both currencies.
+pub async fn rate(
+    pool: &PgPool,
+    from: Currency,
+    to: Currency,
+    on: NaiveDate,
+    stale_after_days: u32,
+) -> Result<Option<FxQuote>, ServiceError> {
+    if from == to {
+        return Ok(Some(FxQuote {
+            from,
+            to,
+            rate: ExchangeRate::ONE,
+            rate_date: on,
+        }));
+    }
+    let rows = sqlx::query(
+        "SELECT base_currency, quote_currency, rate::text AS rate, rate_date
+         FROM fx_rates
+         WHERE rate_date = (
+                 SELECT MAX(rate_date) FROM fx_rates
+                 WHERE rate_date <= $1 AND rate_date >= $2
+                   AND (base_currency IN ($3, $4) OR quote_currency IN ($3, $4))
+             )
+           AND (base_currency IN ($3, $4) OR quote_currency IN ($3, $4))",
+    )
+    .bind(on)
+    .bind(on - Duration::days(i64::from(stale_after_days)))
+    .bind(from.code())
+    .bind(to.code())
+    .fetch_all(pool)
+    .await
+    .map_err(internal)?
+    .into_iter()
+    .map(map_rate)
+    .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(resolve(&rows, from, to))
+}
+
+/// One stored rate.
+#[derive(Debug, Clone, Copy)]
+struct StoredRate {
+    base: Currency,
+    quote: Currency,
+    rate: ExchangeRate,
+    rate_date: NaiveDate,
+}
+
+/// Finds a base currency with rates to both `from` and `to` (or that is one of
+/// them) and crosses the two.
+fn resolve(rows: &[StoredRate], from: Currency, to: Currency) -> Option<FxQuote> {
+    let mut by_base: HashMap<Currency, (NaiveDate, HashMap<Currency, ExchangeRate>)> =
+        HashMap::new();
+    for row in rows {
+        let (_, rates) = by_base.entry(row.base).or_insert_with(|| {
+            (
+                row.rate_date,
+                HashMap::from([(row.base, ExchangeRate::ONE)]),
+            )
+        });
+        rates.insert(row.quote, row.rate);
+    }
+    let mut bases: Vec<_> = by_base.into_iter().collect();
+    bases.sort_by_key(|(base, _)| *base);
+    bases.into_iter().find_map(|(_, (rate_date, rates))| {
+        let rate = ExchangeRate::cross(*rates.get(&from)?, *rates.get(&to)?)?;
+        Some(FxQuote {
+            from,
+            to,
+            rate,
+            rate_date,
+        })
+    })
+}
+
+fn map_rate(row: PgRow) -> Result<StoredRate, ServiceError> {
+    let currency = |column: &str| -> Result<Currency, ServiceError> {
+        let code: String = row.try_get(column).map_err(internal)?;
+        Ok(Currency::parse(&code)?)
+    };
+    let rate: String = row.try_get("rate").map_err(internal)?;
+    Ok(StoredRate {
+        base: currency("base_currency")?,
+        quote: currency("quote_currency")?,
+        rate: ExchangeRate::parse(&rate)
+            .ok_or_else(|| ServiceError::Internal(format!("invalid stored rate {rate}")))?,
+        rate_date: row.try_get("rate_date").map_err(internal)?,
+    })
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored(base: &str, quote: &str, rate: &str) -> StoredRate {
+        StoredRate {
+            base: Currency::parse(base).unwrap(),
+            quote: Currency::parse(quote).unwrap(),
+            rate: ExchangeRate::parse(rate).unwrap(),
+            rate_date: NaiveDate::from_ymd_opt(2024, 5, 8).unwrap(),
+        }
+    }
+
+    #[test]
+    fn resolve_uses_direct_inverse_and_crossed_rates() {
+        let eur = Currency::parse("EUR").unwrap();
+        let gbp = Currency::parse("GBP").unwrap();
+        let rows = [stored("EUR", "USD", "1.25"), stored("EUR", "GBP", "0.5")];
+
+        let direct = resolve(&rows, eur, Currency::USD).unwrap();
+        assert_eq!(direct.rate.to_string(), "1.25");
+        let inverse = resolve(&rows, Currency::USD, eur).unwrap();
+        assert_eq!(inverse.rate.to_string(), "0.8");
+        let crossed = resolve(&rows, gbp, Currency::USD).unwrap();
+        assert_eq!(crossed.rate.to_string(), "2.5");
+        assert!(resolve(&rows, Currency::parse("JPY").unwrap(), Currency::USD).is_none());
+    }
+}
@@ -0,0 +1,223 @@
+//! Company holiday maintenance.
+//!
+//! Administrators manage holidays through `/policy/holidays`; anyone signed
+//! in can list them. `holidays_between` loads the ones a report's items fall
+//! on so `non_working_day` policy rules can flag them like weekends.
+
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::{NaiveDate, Utc};
+use serde::Deserialize;
+use sqlx::{postgres::PgRow, PgPool, Row};
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{Holiday, Role},
+    infrastructure::{auth::AuthenticatedUser, state::AppState},
+};
+
+use super::{audit, errors::ServiceError};
+
+/// Payload accepted by `POST /policy/holidays` and `PUT /policy/holidays/:id`.
+#[derive(Debug, Deserialize)]
+pub struct HolidayRequest {
+    pub holiday_date: NaiveDate,
+    pub name: String,
+}
+
+/// Service managing the `holidays` table.
+pub struct HolidayService {
+    state: Arc<AppState>,
+}
+
+impl HolidayService {
+    /// Constructs the service from shared application state.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// Lists holidays by date, optionally only those in `year`.
+    pub async fn list(&self, year: Option<i32>) -> Result<Vec<Holiday>, ServiceError> {
+        sqlx::query(
+            "SELECT * FROM holidays
+             WHERE $1::int IS NULL OR EXTRACT(YEAR FROM holiday_date)::int = $1
+             ORDER BY holiday_date",
+        )
+        .bind(year)
+        .fetch_all(&self.state.pool)
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .map(map_holiday)
+        .collect()
+    }
+
+    /// Adds a holiday. Restricted to administrators; a second holiday on the
+    /// same date is a conflict.
+    pub async fn create(
+        &self,
+        actor: &AuthenticatedUser,
+        payload: HolidayRequest,
+    ) -> Result<Holiday, ServiceError> {
+        require_admin(actor)?;
+        let name = validate(&payload)?;
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let row = sqlx::query(
+            "INSERT INTO holidays (id, holiday_date, name, updated_by, updated_at)
+             VALUES ($1,$2,$3,$4,$5)
+             ON CONFLICT (holiday_date) DO NOTHING
+             RETURNING *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(payload.holiday_date)
+        .bind(name)
+        .bind(actor.employee_id)
+        .bind(Utc::now())
+        .fetch_optional(tx.as_mut())
+        .await
+        .map_err(internal)?
+        .ok_or(ServiceError::Conflict)?;
+        let holiday = map_holiday(row)?;
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::HOLIDAY,
+            holiday.id,
+            audit::CREATED,
+            None,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        Ok(holiday)
+    }
+
+    /// Replaces a holiday. Restricted to administrators.
+    pub async fn update(
+        &self,
+        actor: &AuthenticatedUser,
+        holiday_id: Uuid,
+        payload: HolidayRequest,
+    ) -> Result<Holiday, ServiceError> {
+        require_admin(actor)?;
+        let name = validate(&payload)?;
+        let taken: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM holidays WHERE holiday_date = $1 AND id <> $2)",
+        )
+        .bind(payload.holiday_date)
+        .bind(holiday_id)
+        .fetch_one(&self.state.pool)
+        .await
+        .map_err(internal)?;
+        if taken {
+            return Err(ServiceError::Conflict);
+        }
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let before = audit::HOLIDAY.snapshot(tx.as_mut(), holiday_id).await?;
+        let row = sqlx::query(
+            "UPDATE holidays
+             SET holiday_date = $2, name = $3, updated_by = $4, updated_at = $5
+             WHERE id = $1
+             RETURNING *",
+        )
+        .bind(holiday_id)
+        .bind(payload.holiday_date)
+        .bind(name)
+        .bind(actor.employee_id)
+        .bind(Utc::now())
+        .fetch_optional(tx.as_mut())
+        .await
+        .map_err(internal)?
+        .ok_or(ServiceError::NotFound)?;
+        let holiday = map_holiday(row)?;
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::HOLIDAY,
+            holiday_id,
+            audit::UPDATED,
+            before,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        Ok(holiday)
+    }
+
+    /// Deletes a holiday. Restricted to administrators.
+    pub async fn delete(
+        &self,
+        actor: &AuthenticatedUser,
+        holiday_id: Uuid,
+    ) -> Result<(), ServiceError> {
+        require_admin(actor)?;
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let before = audit::HOLIDAY.snapshot(tx.as_mut(), holiday_id).await?;
+        if before.is_none() {
+            return Err(ServiceError::NotFound);
+        }
+        sqlx::query("DELETE FROM holidays WHERE id = $1")
+            .bind(holiday_id)
+            .execute(tx.as_mut())
+            .await
+            .map_err(internal)?;
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::HOLIDAY,
+            holiday_id,
+            audit::DELETED,
+            before,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        Ok(())
+    }
+}
+
+/// Loads holiday names by date for `from..=to`.
+pub async fn holidays_between(
+    pool: &PgPool,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<HashMap<NaiveDate, String>, ServiceError> {
+    let rows = sqlx::query_as::<_, (NaiveDate, String)>(
+        "SELECT holiday_date, name FROM holidays WHERE holiday_date BETWEEN $1 AND $2",
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+    .map_err(internal)?;
+    Ok(rows.into_iter().collect())
+}
+
+/// Returns the trimmed name.
+fn validate(payload: &HolidayRequest) -> Result<&str, ServiceError> {
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err(ServiceError::Validation("name is required".into()));
+    }
+    Ok(name)
+}
+
+/// Deployment-wide settings are maintained by administrators of the
+/// operating organization.
+fn require_admin(actor: &AuthenticatedUser) -> Result<(), ServiceError> {
+    if actor.role != Role::Admin || !actor.is_operator() {
+        return Err(ServiceError::Forbidden);
+    }
+    Ok(())
+}
+
+fn map_holiday(row: PgRow) -> Result<Holiday, ServiceError> {
+    Ok(Holiday {
+        id: row.try_get("id").map_err(internal)?,
+        holiday_date: row.try_get("holiday_date").map_err(internal)?,
+        name: row.try_get("name").map_err(internal)?,
+        updated_by: row.try_get("updated_by").map_err(internal)?,
+        updated_at: row.try_get("updated_at").map_err(internal)?,
+    })
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
@@ -0,0 +1,261 @@
+//! Roster synchronization from the HR system.
+//!
+//! The `hr_sync` job fetches the roster through `infrastructure::hr` and
+//! applies the workers still employed as a roster import for one
+//! organization (see `services::roster`), so adds and changes follow the
+//! same rules and audit trail as an uploaded file. HR is the source of
+//! departments, so ones the organization does not have yet are created.
+//! Managers who are no longer employed are dropped from their reports.
+//! Employees on file that the HR system no longer lists as employed are
+//! deactivated (see `services::terminations`) in the same transaction.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::Utc;
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{Employee, Role},
+    infrastructure::hr::HrWorker,
+};
+
+use super::{
+    departments,
+    errors::ServiceError,
+    roster::{self, RosterImport, RosterOutcome, RosterRow},
+    terminations,
+};
+
+/// Differences between the HR system and the roster on file.
+#[derive(Debug, Clone, Serialize)]
+pub struct HrSyncReport {
+    pub org_id: Uuid,
+    pub dry_run: bool,
+    /// The import of the employed workers. Only rows that created, updated,
+    /// or failed are listed; unchanged ones are counted.
+    pub roster: RosterImport,
+    /// Departments added for workers in departments not on file.
+    pub departments_created: usize,
+    /// Employees deactivated, or that would have been, because the HR system
+    /// no longer lists them as employed.
+    pub terminated: Vec<TerminatedEmployee>,
+}
+
+impl HrSyncReport {
+    /// Whether the sync changed, or would have changed, anything or needs
+    /// attention.
+    pub fn has_changes(&self) -> bool {
+        self.roster.created + self.roster.updated + self.roster.failed + self.departments_created
+            > 0
+            || !self.terminated.is_empty()
+    }
+}
+
+/// An employee on file the HR system no longer lists as employed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TerminatedEmployee {
+    pub employee_id: Uuid,
+    pub hr_identifier: String,
+}
+
+/// Reconciles `org_id`'s employees with `workers` and deactivates the ones
+/// no longer employed. Nothing is saved with `dry_run`, or when any worker
+/// cannot be imported.
+pub async fn sync(
+    pool: &PgPool,
+    org_id: Uuid,
+    workers: &[HrWorker],
+    dry_run: bool,
+) -> Result<HrSyncReport, ServiceError> {
+    if !workers.iter().any(|worker| worker.active) {
+        // An empty feed is far more likely a broken report than a company
+        // without staff.
+        return Err(ServiceError::Validation(
+            "the HR system returned no employed workers".into(),
+        ));
+    }
+    let mut tx = pool.begin().await.map_err(internal)?;
+    let employees = roster::lock_roster(&mut tx, org_id).await?;
+    let (rows, terminated) = reconcile(workers, &employees);
+    let codes: Vec<&str> = rows.iter().map(|row| row.department.as_str()).collect();
+    let departments_created =
+        departments::create_missing_departments(&mut tx, None, org_id, &codes).await?;
+    let mut import = roster::apply_roster(&mut tx, None, org_id, &rows).await?;
+    if import.applied {
+        let now = Utc::now();
+        for employee in &terminated {
+            terminations::deactivate(&mut tx, None, org_id, employee.employee_id, now).await?;
+        }
+    }
+    if import.applied && !dry_run {
+        tx.commit().await.map_err(internal)?;
+    } else {
+        import.applied = false;
+    }
+
+    import
+        .rows
+        .retain(|row| row.outcome != RosterOutcome::Unchanged);
+    if dry_run {
+        for row in &mut import.rows {
+            if row.outcome == RosterOutcome::Created {
+                row.employee_id = None;
+            }
+        }
+    }
+    Ok(HrSyncReport {
+        org_id,
+        dry_run,
+        roster: import,
+        departments_created,
+        terminated,
+    })
+}
+
+/// Roster rows for the employed `workers`, numbered by their position in the
+/// feed, and the active `employees` missing from them. Employees keep their role;
+/// new ones become managers when someone reports to them.
+fn reconcile(
+    workers: &[HrWorker],
+    employees: &[Employee],
+) -> (Vec<RosterRow>, Vec<TerminatedEmployee>) {
+    let employed: HashSet<String> = workers
+        .iter()
+        .filter(|worker| worker.active)
+        .map(|worker| key(&worker.hr_identifier))
+        .collect();
+    let managers: HashSet<String> = workers
+        .iter()
+        .filter(|worker| worker.active)
+        .filter_map(|worker| worker.manager.as_deref().map(key))
+        .collect();
+    let roles: HashMap<String, Role> = employees
+        .iter()
+        .map(|employee| (key(&employee.hr_identifier), employee.role))
+        .collect();
+
+    let rows = workers
+        .iter()
+        .zip(1..)
+        .filter(|(worker, _)| worker.active)
+        .map(|(worker, line)| {
+            let worker_key = key(&worker.hr_identifier);
+            let role = match roles.get(&worker_key) {
+                Some(role) => *role,
+                None if managers.contains(&worker_key) => Role::Manager,
+                None => Role::Employee,
+            };
+            RosterRow {
+                line,
+                hr_identifier: worker.hr_identifier.clone(),
+                manager: worker
+                    .manager
+                    .clone()
+                    .filter(|manager| employed.contains(&key(manager)))
+                    .unwrap_or_default(),
+                department: worker.department.clone().unwrap_or_default(),
+                role: role.as_str().to_string(),
+            }
+        })
+        .collect();
+
+    let mut terminated: Vec<TerminatedEmployee> = employees
+        .iter()
+        .filter(|employee| {
+            employee.deactivated_at.is_none() && !employed.contains(&key(&employee.hr_identifier))
+        })
+        .map(|employee| TerminatedEmployee {
+            employee_id: employee.id,
+            hr_identifier: employee.hr_identifier.clone(),
+        })
+        .collect();
+    terminated.sort_by(|a, b| a.hr_identifier.cmp(&b.hr_identifier));
+    (rows, terminated)
+}
+
+fn key(hr_identifier: &str) -> String {
+    hr_identifier.to_uppercase()
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn worker(hr_identifier: &str, manager: Option<&str>, active: bool) -> HrWorker {
+        HrWorker {
+            hr_identifier: hr_identifier.into(),
+            manager: manager.map(Into::into),
+            department: Some("Ops".into()),
+            active,
+        }
+    }
+
+    fn employee(id: u128, hr_identifier: &str, role: Role) -> Employee {
+        Employee {
+            id: Uuid::from_u128(id),
+            org_id: Uuid::nil(),
+            hr_identifier: hr_identifier.into(),
+            manager_id: None,
+            department: None,
+            department_id: None,
+            cost_center_id: None,
+            role,
+            created_at: Utc::now(),
+            deactivated_at: None,
+        }
+    }
+
+    #[test]
+    fn keeps_roles_drops_departed_managers_and_reports_terminations() {
+        let workers = [
+            worker("E100", Some("E090"), true),
+            worker("E101", Some("e100"), true),
+            worker("E090", None, false),
+            worker("E102", Some("E100"), true),
+        ];
+        let employees = [
+            employee(1, "E090", Role::Manager),
+            employee(2, "e101", Role::Finance),
+            employee(3, "ADMIN1", Role::Admin),
+            Employee {
+                deactivated_at: Some(Utc::now()),
+                ..employee(4, "E080", Role::Employee)
+            },
+        ];
+
+        let (rows, terminated) = reconcile(&workers, &employees);
+
+        let summary: Vec<(u64, &str, &str, &str)> = rows
+            .iter()
+            .map(|row| {
+                (
+                    row.line,
+                    row.hr_identifier.as_str(),
+                    row.manager.as_str(),
+                    row.role.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (1, "E100", "", "manager"),
+                (2, "E101", "e100", "finance"),
+                (4, "E102", "E100", "employee"),
+            ]
+        );
+        assert_eq!(
+            terminated
+                .iter()
+                .map(|employee| employee.hr_identifier.as_str())
+                .collect::<Vec<_>>(),
+            vec!["ADMIN1", "E090"]
+        );
+    }
+}
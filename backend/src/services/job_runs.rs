@@ -0,0 +1,271 @@
+//! Background job history and manual runs.
+//!
+//! The scheduler records every run in `job_runs` through `start` and
+//! `finish`. Administrators read the history and start a job out of schedule
+//! through `GET /admin/jobs` and `POST /admin/jobs/:name/run`; a manual run
+//! executes in the background and is recorded like a scheduled one.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{postgres::PgRow, PgPool, Row};
+use tracing::{info_span, warn, Instrument};
+use uuid::Uuid;
+
+use crate::{
+    domain::models::Role,
+    infrastructure::{auth::AuthenticatedUser, state::AppState},
+    jobs,
+};
+
+use super::{audit, errors::ServiceError};
+
+/// Runs listed per job by `JobRunService::list`.
+const RECENT_RUNS_PER_JOB: i64 = 10;
+
+/// Longest error message stored for a failed run.
+const MAX_ERROR_CHARS: usize = 2_000;
+
+/// What started a job run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunTrigger {
+    Scheduled,
+    Manual,
+}
+
+impl RunTrigger {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunTrigger::Scheduled => "scheduled",
+            RunTrigger::Manual => "manual",
+        }
+    }
+}
+
+/// One recorded run of a background job.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobRun {
+    pub id: Uuid,
+    pub job_name: String,
+    pub trigger: String,
+    pub triggered_by: Option<Uuid>,
+    pub status: String,
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// A background job with its configuration and latest runs, newest first.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobStatus {
+    pub name: &'static str,
+    /// Whether the scheduler runs the job; disabled jobs can still be run by
+    /// hand.
+    pub enabled: bool,
+    pub running: bool,
+    pub recent_runs: Vec<JobRun>,
+}
+
+/// Service reading the `job_runs` table and starting manual runs.
+pub struct JobRunService {
+    state: Arc<AppState>,
+}
+
+impl JobRunService {
+    /// Constructs the service from shared application state.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// Every background job with its most recent runs.
+    pub async fn list(&self, actor: &AuthenticatedUser) -> Result<Vec<JobStatus>, ServiceError> {
+        require_admin(actor)?;
+        let runs = sqlx::query(
+            "SELECT id, job_name, trigger, triggered_by, status, error, started_at, finished_at
+             FROM (
+                 SELECT *, ROW_NUMBER() OVER (PARTITION BY job_name ORDER BY started_at DESC) AS rank
+                 FROM job_runs
+             ) ranked
+             WHERE rank <= $1
+             ORDER BY job_name, started_at DESC",
+        )
+        .bind(RECENT_RUNS_PER_JOB)
+        .fetch_all(&self.state.pool)
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .map(map_run)
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(jobs::JOB_NAMES
+            .iter()
+            .map(|name| {
+                let recent_runs: Vec<JobRun> = runs
+                    .iter()
+                    .filter(|run| run.job_name == *name)
+                    .cloned()
+                    .collect();
+                JobStatus {
+                    name,
+                    enabled: jobs::is_enabled(&self.state.config, name),
+                    running: recent_runs.iter().any(|run| run.status == "running"),
+                    recent_runs,
+                }
+            })
+            .collect())
+    }
+
+    /// Starts `name` in the background and returns its `running` record.
+    ///
+    /// Fails with `ServiceError::NotFound` for an unknown job and
+    /// `ServiceError::Conflict` while the job is already running or the
+    /// process is shutting down.
+    pub async fn run_now(
+        &self,
+        actor: &AuthenticatedUser,
+        name: &str,
+    ) -> Result<JobRun, ServiceError> {
+        require_admin(actor)?;
+        if self.state.shutdown.is_cancelled() {
+            return Err(ServiceError::Conflict);
+        }
+        let name = jobs::JOB_NAMES
+            .into_iter()
+            .find(|job| *job == name)
+            .ok_or(ServiceError::NotFound)?;
+
+        let run = start(
+            &self.state.pool,
+            name,
+            RunTrigger::Manual,
+            Some(actor.employee_id),
+        )
+        .await?;
+        let mut conn = self.state.pool.acquire().await.map_err(internal)?;
+        audit::record_change(
+            &mut conn,
+            Some(actor.employee_id),
+            audit::JOB_RUN,
+            run.id,
+            audit::CREATED,
+            None,
+        )
+        .await?;
+        let state = Arc::clone(&self.state);
+        let run_id = run.id;
+        self.state.job_tasks.spawn(
+            async move {
+                let outcome = jobs::run(name, Arc::clone(&state)).await;
+                if let Err(err) = &outcome {
+                    warn!(error = ?err, "job failed");
+                }
+                if let Err(err) = finish(&state.pool, run_id, &outcome).await {
+                    warn!(error = %err, "could not record job run");
+                }
+            }
+            .instrument(info_span!("job", name)),
+        );
+        Ok(run)
+    }
+}
+
+/// Records the start of a run. Fails with `ServiceError::Conflict` while
+/// another run of the job is still `running`.
+pub async fn start(
+    pool: &PgPool,
+    name: &str,
+    trigger: RunTrigger,
+    triggered_by: Option<Uuid>,
+) -> Result<JobRun, ServiceError> {
+    sqlx::query(
+        "INSERT INTO job_runs (id, job_name, trigger, triggered_by, status, started_at)
+         VALUES ($1,$2,$3,$4,'running',$5)
+         RETURNING id, job_name, trigger, triggered_by, status, error, started_at, finished_at",
+    )
+    .bind(Uuid::new_v4())
+    .bind(name)
+    .bind(trigger.as_str())
+    .bind(triggered_by)
+    .bind(Utc::now())
+    .fetch_one(pool)
+    .await
+    .map_err(|err| match err {
+        sqlx::Error::Database(db) if db.code().as_deref() == Some("23505") => {
+            ServiceError::Conflict
+        }
+        other => internal(other),
+    })
+    .and_then(map_run)
+}
+
+/// Records how a run started with `start` ended.
+pub async fn finish(
+    pool: &PgPool,
+    run_id: Uuid,
+    outcome: &anyhow::Result<()>,
+) -> Result<(), ServiceError> {
+    let (status, error) = match outcome {
+        Ok(()) => ("succeeded", None),
+        Err(err) => (
+            "failed",
+            Some(
+                format!("{err:#}")
+                    .chars()
+                    .take(MAX_ERROR_CHARS)
+                    .collect::<String>(),
+            ),
+        ),
+    };
+    sqlx::query("UPDATE job_runs SET status = $2, error = $3, finished_at = $4 WHERE id = $1")
+        .bind(run_id)
+        .bind(status)
+        .bind(error)
+        .bind(Utc::now())
+        .execute(pool)
+        .await
+        .map_err(internal)?;
+    Ok(())
+}
+
+/// Marks runs left `running` by a previous process as failed. Called at
+/// startup, before any job starts.
+pub async fn fail_interrupted(pool: &PgPool) -> Result<u64, ServiceError> {
+    sqlx::query(
+        "UPDATE job_runs
+         SET status = 'failed', error = 'interrupted by a restart', finished_at = $1
+         WHERE status = 'running'",
+    )
+    .bind(Utc::now())
+    .execute(pool)
+    .await
+    .map(|result| result.rows_affected())
+    .map_err(internal)
+}
+
+/// Deployment-wide settings are maintained by administrators of the
+/// operating organization.
+fn require_admin(actor: &AuthenticatedUser) -> Result<(), ServiceError> {
+    if actor.role != Role::Admin || !actor.is_operator() {
+        return Err(ServiceError::Forbidden);
+    }
+    Ok(())
+}
+
+fn map_run(row: PgRow) -> Result<JobRun, ServiceError> {
+    Ok(JobRun {
+        id: row.try_get("id").map_err(internal)?,
+        job_name: row.try_get("job_name").map_err(internal)?,
+        trigger: row.try_get("trigger").map_err(internal)?,
+        triggered_by: row.try_get("triggered_by").map_err(internal)?,
+        status: row.try_get("status").map_err(internal)?,
+        error: row.try_get("error").map_err(internal)?,
+        started_at: row.try_get("started_at").map_err(internal)?,
+        finished_at: row.try_get("finished_at").map_err(internal)?,
+    })
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
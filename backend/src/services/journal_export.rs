@@ -0,0 +1,371 @@
+//! Renders stored journal lines as downloadable ledger import files.
+//!
+//! Serves `GET /finance/batches/:id/export` for customers who post expenses
+//! outside NetSuite. Files are generated from `journal_lines` exactly as they
+//! were written at finalization, so a re-download always matches what was
+//! exported. Each report's liability line becomes its credit; batches
+//! finalized before liability lines were stored get one synthesized against
+//! the configured reimbursement account.
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::domain::models::{Money, MoneyError};
+
+/// File formats accepted by the `format` query parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// Comma-separated general journal with debit and credit columns.
+    Csv,
+    /// QuickBooks Desktop Intuit Interchange Format, one general journal
+    /// transaction per report.
+    Iif,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Iif => "iif",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Iif => "text/plain; charset=utf-8",
+        }
+    }
+}
+
+/// A stored journal line joined with the report details an import file needs.
+#[derive(Debug, Clone)]
+pub struct ExportLine {
+    pub report_id: Uuid,
+    pub employee: String,
+    pub line_number: i32,
+    pub gl_account: String,
+    pub department: Option<String>,
+    pub class: Option<String>,
+    pub memo: Option<String>,
+    pub amount: Money,
+    /// The report's balancing line rather than an expense debit.
+    pub liability: bool,
+}
+
+/// Rendered export ready to be served as an attachment.
+#[derive(Debug, Clone)]
+pub struct JournalFile {
+    pub file_name: String,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+const CSV_HEADER: &str =
+    "batch_reference,journal_date,report_id,employee,line_number,account,department,class,memo,debit,credit,currency";
+
+/// Renders `lines` (ordered by line number) in `format`, dated `journal_date`.
+pub fn render(
+    format: ExportFormat,
+    batch_reference: &str,
+    journal_date: NaiveDate,
+    lines: &[ExportLine],
+    reimbursement_account: &str,
+) -> Result<JournalFile, MoneyError> {
+    let body = match format {
+        ExportFormat::Csv => {
+            render_csv(batch_reference, journal_date, lines, reimbursement_account)?
+        }
+        ExportFormat::Iif => {
+            render_iif(batch_reference, journal_date, lines, reimbursement_account)?
+        }
+    };
+    Ok(JournalFile {
+        file_name: format!("{}.{}", file_stem(batch_reference), format.extension()),
+        content_type: format.content_type(),
+        body,
+    })
+}
+
+fn render_csv(
+    batch_reference: &str,
+    journal_date: NaiveDate,
+    lines: &[ExportLine],
+    reimbursement_account: &str,
+) -> Result<String, MoneyError> {
+    let date = journal_date.format("%Y-%m-%d").to_string();
+    let mut out = format!("{CSV_HEADER}\r\n");
+    for report in by_report(lines) {
+        for line in balanced(report, reimbursement_account)? {
+            let (debit, credit) = if line.amount.amount_minor < 0 {
+                (
+                    String::new(),
+                    line.amount.checked_mul(-1)?.to_decimal_string(),
+                )
+            } else {
+                (line.amount.to_decimal_string(), String::new())
+            };
+            let row = [
+                batch_reference.to_string(),
+                date.clone(),
+                line.report_id.to_string(),
+                line.employee.clone(),
+                line_number(&line),
+                line.gl_account.clone(),
+                line.department.clone().unwrap_or_default(),
+                line.class.clone().unwrap_or_default(),
+                line.memo.clone().unwrap_or_default(),
+                debit,
+                credit,
+                line.amount.currency.to_string(),
+            ];
+            push_csv_row(&mut out, &row);
+        }
+    }
+    Ok(out)
+}
+
+fn render_iif(
+    batch_reference: &str,
+    journal_date: NaiveDate,
+    lines: &[ExportLine],
+    reimbursement_account: &str,
+) -> Result<String, MoneyError> {
+    let date = journal_date.format("%m/%d/%Y").to_string();
+    let mut out = String::from(
+        "!TRNS\tTRNSTYPE\tDATE\tACCNT\tNAME\tCLASS\tAMOUNT\tDOCNUM\tMEMO\r\n\
+         !SPL\tTRNSTYPE\tDATE\tACCNT\tNAME\tCLASS\tAMOUNT\tDOCNUM\tMEMO\r\n\
+         !ENDTRNS\r\n",
+    );
+    for report in by_report(lines) {
+        // The liability line heads the transaction; the debits are its splits.
+        let (liabilities, expenses): (Vec<ExportLine>, Vec<ExportLine>) =
+            balanced(report, reimbursement_account)?
+                .into_iter()
+                .partition(|line| line.liability);
+        for (kind, line) in liabilities
+            .iter()
+            .map(|line| ("TRNS", line))
+            .chain(expenses.iter().map(|line| ("SPL", line)))
+        {
+            push_iif_row(
+                &mut out,
+                kind,
+                &[
+                    &date,
+                    &line.gl_account,
+                    &line.employee,
+                    line.class
+                        .as_deref()
+                        .or(line.department.as_deref())
+                        .unwrap_or_default(),
+                    &line.amount.to_decimal_string(),
+                    batch_reference,
+                    line.memo.as_deref().unwrap_or_default(),
+                ],
+            );
+        }
+        out.push_str("ENDTRNS\r\n");
+    }
+    Ok(out)
+}
+
+/// Splits `lines` into consecutive runs belonging to the same report;
+/// finalization numbers lines report by report, so each report is one run.
+fn by_report(lines: &[ExportLine]) -> impl Iterator<Item = &[ExportLine]> {
+    lines.chunk_by(|a, b| a.report_id == b.report_id)
+}
+
+/// Returns a report's lines, appending a reimbursement credit when the
+/// stored lines predate liability lines and so do not balance on their own.
+fn balanced(
+    report: &[ExportLine],
+    reimbursement_account: &str,
+) -> Result<Vec<ExportLine>, MoneyError> {
+    let mut lines = report.to_vec();
+    if report.iter().any(|line| line.liability) {
+        return Ok(lines);
+    }
+    let first = &report[0];
+    let total = Money::sum(first.amount.currency, report.iter().map(|line| line.amount))?;
+    lines.push(ExportLine {
+        report_id: first.report_id,
+        employee: first.employee.clone(),
+        line_number: 0,
+        gl_account: reimbursement_account.to_string(),
+        department: None,
+        class: None,
+        memo: Some(format!("Reimbursement {}", first.employee)),
+        amount: total.checked_mul(-1)?,
+        liability: true,
+    });
+    Ok(lines)
+}
+
+/// Synthesized lines have no stored line number and leave the column blank.
+fn line_number(line: &ExportLine) -> String {
+    if line.line_number == 0 {
+        String::new()
+    } else {
+        line.line_number.to_string()
+    }
+}
+
+fn push_csv_row(out: &mut String, fields: &[String]) {
+    let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+    out.push_str(&row.join(","));
+    out.push_str("\r\n");
+}
+
+fn csv_field(value: &str) -> String {
+    // Leading formula characters are neutralized so spreadsheet tools do not
+    // evaluate employee-entered memos.
+    let value = if value.starts_with(['=', '+', '@']) {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+fn push_iif_row(out: &mut String, kind: &str, fields: &[&str]) {
+    out.push_str(kind);
+    out.push('\t');
+    out.push_str("GENERAL JOURNAL");
+    for field in fields {
+        out.push('\t');
+        // IIF has no quoting, so delimiters inside values are flattened.
+        out.extend(field.chars().map(|c| if c.is_control() { ' ' } else { c }));
+    }
+    out.push_str("\r\n");
+}
+
+fn file_stem(batch_reference: &str) -> String {
+    let stem: String = batch_reference
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if stem.is_empty() {
+        "journal".to_string()
+    } else {
+        stem
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::Currency;
+
+    fn line(
+        report_id: Uuid,
+        line_number: i32,
+        account: &str,
+        cents: i64,
+        memo: &str,
+    ) -> ExportLine {
+        ExportLine {
+            report_id,
+            employee: "EMP3101".to_string(),
+            line_number,
+            gl_account: account.to_string(),
+            department: Some("Ops".to_string()),
+            class: None,
+            memo: Some(memo.to_string()),
+            amount: Money::new(cents, Currency::USD),
+            liability: false,
+        }
+    }
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 6, 30).unwrap()
+    }
+
+    #[test]
+    fn csv_balances_each_report_with_a_reimbursement_credit() {
+        let report = Uuid::nil();
+        let lines = vec![
+            line(report, 1, "64180", 4_250, "2024-06-03 meal: Lunch, client"),
+            line(report, 2, "62090", 1_899, "=SUM(A1)"),
+        ];
+
+        let file = render(
+            ExportFormat::Csv,
+            "JUN/2024",
+            date(),
+            &lines,
+            "Reimb Payable",
+        )
+        .unwrap();
+
+        assert_eq!(file.file_name, "JUN_2024.csv");
+        let rows: Vec<&str> = file.body.split("\r\n").collect();
+        assert_eq!(rows[0], CSV_HEADER);
+        assert!(rows[1].ends_with(",64180,Ops,,\"2024-06-03 meal: Lunch, client\",42.50,,USD"));
+        assert!(rows[2].contains(",'=SUM(A1),18.99,,USD"));
+        assert!(rows[3].ends_with(",Reimb Payable,,,Reimbursement EMP3101,,61.49,USD"));
+        assert_eq!(rows[4], "");
+    }
+
+    #[test]
+    fn stored_liability_lines_are_exported_as_credits_without_synthesis() {
+        let report = Uuid::nil();
+        let mut payable = line(report, 2, "20100", -4_250, "Reimbursement EMP3101");
+        payable.liability = true;
+        let lines = vec![line(report, 1, "64180", 4_250, "meal"), payable];
+
+        let csv = render(ExportFormat::Csv, "JUN", date(), &lines, "Unused").unwrap();
+        let rows: Vec<&str> = csv.body.split("\r\n").collect();
+        assert_eq!(rows.len(), 4);
+        assert!(rows[2].ends_with(",2,20100,Ops,,Reimbursement EMP3101,,42.50,USD"));
+        assert!(!csv.body.contains("Unused"));
+
+        let iif = render(ExportFormat::Iif, "JUN", date(), &lines, "Unused").unwrap();
+        assert!(iif.body.contains(
+            "TRNS\tGENERAL JOURNAL\t06/30/2024\t20100\tEMP3101\tOps\t-42.50\tJUN\tReimbursement EMP3101\r\nSPL\t"
+        ));
+    }
+
+    #[test]
+    fn iif_emits_one_balanced_transaction_per_report() {
+        let first = Uuid::nil();
+        let second = Uuid::from_u128(1);
+        let lines = vec![
+            line(first, 1, "64180", 4_250, "meal"),
+            line(second, 2, "64190", 10_000, "air\tfare"),
+            line(second, 3, "64190", 2_500, "bag fee"),
+        ];
+
+        let file = render(
+            ExportFormat::Iif,
+            "JUN-2024",
+            date(),
+            &lines,
+            "Reimb Payable",
+        )
+        .unwrap();
+
+        assert_eq!(file.file_name, "JUN-2024.iif");
+        let body = file.body;
+        assert_eq!(body.matches("\r\nTRNS\t").count(), 2);
+        assert_eq!(body.matches("ENDTRNS\r\n").count(), 3);
+        assert!(body.contains(
+            "TRNS\tGENERAL JOURNAL\t06/30/2024\tReimb Payable\tEMP3101\t\t-125.00\tJUN-2024\tReimbursement EMP3101"
+        ));
+        assert!(body.contains(
+            "SPL\tGENERAL JOURNAL\t06/30/2024\t64190\tEMP3101\tOps\t100.00\tJUN-2024\tair fare"
+        ));
+    }
+}
@@ -0,0 +1,270 @@
+//! Management-chain resolution.
+//!
+//! Walks an employee's reporting line upward through the organization chart
+//! cached in `AppState::org_chart_cache`. Approval routing uses it to find a
+//! skip-level approver and the escalation sweep to find whom an overdue
+//! approval goes to; `GET /api/employees/:id/management-chain` exposes it.
+//! Broken reporting lines do not fail the walk: it stops at a manager that
+//! does not exist or belongs to another organization, and at the first
+//! manager already on the chain, and says why in `ManagementChain::end`.
+
+use std::{collections::HashSet, sync::Arc};
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{Employee, Role},
+    infrastructure::{
+        auth::AuthenticatedUser,
+        org_chart_cache::{OrgChart, OrgChartEntry},
+        state::AppState,
+    },
+};
+
+use super::{errors::ServiceError, organizations::EMPLOYEE_COLUMNS};
+
+/// An employee's managers, nearest first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ManagementChain {
+    pub employee_id: Uuid,
+    pub managers: Vec<ChainManager>,
+    pub end: ChainEnd,
+}
+
+impl ManagementChain {
+    /// The managers' IDs, nearest first.
+    pub fn manager_ids(&self) -> impl Iterator<Item = Uuid> + '_ {
+        self.managers.iter().map(|manager| manager.employee_id)
+    }
+}
+
+/// One manager on a chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChainManager {
+    pub employee_id: Uuid,
+    pub hr_identifier: String,
+    pub department: Option<String>,
+    pub role: Role,
+    /// `1` for the direct manager, `2` for theirs, and so on.
+    pub depth: u32,
+}
+
+/// Why a chain stops where it does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ChainEnd {
+    /// The last employee on it has no manager.
+    Top,
+    /// The last employee's manager is not an employee of the organization.
+    MissingManager { manager_id: Uuid },
+    /// The last employee's manager is already on the chain.
+    Cycle { manager_id: Uuid },
+}
+
+/// Service behind the management-chain route.
+pub struct ManagementChainService {
+    state: Arc<AppState>,
+}
+
+impl ManagementChainService {
+    /// Constructs the service from shared application state.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// `employee_id`'s management chain. Employees may look up their own;
+    /// managers, finance, and administrators anyone's in their organization.
+    /// Employees of other organizations are reported as not found.
+    pub async fn get(
+        &self,
+        actor: &AuthenticatedUser,
+        employee_id: Uuid,
+    ) -> Result<ManagementChain, ServiceError> {
+        if actor.role == Role::Employee && actor.employee_id != employee_id {
+            return Err(ServiceError::Forbidden);
+        }
+        let chart = chart_for(&self.state, employee_id).await?;
+        match chart.employees.get(&employee_id) {
+            Some(employee) if employee.org_id == actor.org_id => {
+                walk(&chart, employee_id).ok_or(ServiceError::NotFound)
+            }
+            _ => Err(ServiceError::NotFound),
+        }
+    }
+}
+
+/// `employee_id`'s management chain, or `None` for an unknown employee.
+pub async fn resolve(
+    state: &AppState,
+    employee_id: Uuid,
+) -> Result<Option<ManagementChain>, ServiceError> {
+    let chart = chart_for(state, employee_id).await?;
+    Ok(walk(&chart, employee_id))
+}
+
+/// Drops the cached chart; call after changing anyone's manager, role, or
+/// department, or adding employees.
+pub fn invalidate(state: &AppState) {
+    state.org_chart_cache.invalidate();
+}
+
+/// The cached chart, reloaded when it cannot resolve `employee_id`'s chain
+/// completely: an employee or manager it does not know was most likely
+/// added since it was loaded.
+async fn chart_for(state: &AppState, employee_id: Uuid) -> Result<Arc<OrgChart>, ServiceError> {
+    if let Some(chart) = state.org_chart_cache.get() {
+        let complete = match walk(&chart, employee_id) {
+            Some(chain) => match chain.end {
+                ChainEnd::MissingManager { manager_id } => {
+                    chart.employees.contains_key(&manager_id)
+                }
+                ChainEnd::Top | ChainEnd::Cycle { .. } => true,
+            },
+            None => false,
+        };
+        if complete {
+            return Ok(chart);
+        }
+    }
+    let generation = state.org_chart_cache.generation();
+    let chart = Arc::new(load_chart(state).await?);
+    state.org_chart_cache.store(generation, chart.clone());
+    Ok(chart)
+}
+
+async fn load_chart(state: &AppState) -> Result<OrgChart, ServiceError> {
+    let employees =
+        sqlx::query_as::<_, Employee>(&format!("SELECT {EMPLOYEE_COLUMNS} FROM employees"))
+            .fetch_all(&state.pool)
+            .await
+            .map_err(|err| ServiceError::Internal(err.to_string()))?;
+    Ok(OrgChart {
+        employees: employees
+            .into_iter()
+            .map(|employee| {
+                (
+                    employee.id,
+                    OrgChartEntry {
+                        org_id: employee.org_id,
+                        hr_identifier: employee.hr_identifier,
+                        manager_id: employee.manager_id,
+                        department: employee.department,
+                        role: employee.role,
+                    },
+                )
+            })
+            .collect(),
+    })
+}
+
+/// Follows `manager_id` links from `employee_id` until one is missing,
+/// leaves the employee's organization, or repeats.
+fn walk(chart: &OrgChart, employee_id: Uuid) -> Option<ManagementChain> {
+    let employee = chart.employees.get(&employee_id)?;
+    let mut seen = HashSet::from([employee_id]);
+    let mut managers = Vec::new();
+    let mut next = employee.manager_id;
+    let end = loop {
+        let Some(manager_id) = next else {
+            break ChainEnd::Top;
+        };
+        if !seen.insert(manager_id) {
+            break ChainEnd::Cycle { manager_id };
+        }
+        let Some(manager) = chart
+            .employees
+            .get(&manager_id)
+            .filter(|manager| manager.org_id == employee.org_id)
+        else {
+            break ChainEnd::MissingManager { manager_id };
+        };
+        managers.push(ChainManager {
+            employee_id: manager_id,
+            hr_identifier: manager.hr_identifier.clone(),
+            department: manager.department.clone(),
+            role: manager.role,
+            depth: managers.len() as u32 + 1,
+        });
+        next = manager.manager_id;
+    };
+    Some(ManagementChain {
+        employee_id,
+        managers,
+        end,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chart(links: &[(u128, Option<u128>)]) -> OrgChart {
+        OrgChart {
+            employees: links
+                .iter()
+                .map(|&(id, manager)| {
+                    (
+                        Uuid::from_u128(id),
+                        OrgChartEntry {
+                            org_id: Uuid::nil(),
+                            hr_identifier: format!("E{id}"),
+                            manager_id: manager.map(Uuid::from_u128),
+                            department: None,
+                            role: Role::Manager,
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    fn ids(chain: &ManagementChain) -> Vec<u128> {
+        chain.manager_ids().map(|id| id.as_u128()).collect()
+    }
+
+    #[test]
+    fn walks_to_the_top_nearest_manager_first() {
+        let chart = chart(&[(1, Some(2)), (2, Some(3)), (3, None)]);
+
+        let chain = walk(&chart, Uuid::from_u128(1)).unwrap();
+
+        assert_eq!(ids(&chain), vec![2, 3]);
+        assert_eq!(chain.managers[1].depth, 2);
+        assert_eq!(chain.end, ChainEnd::Top);
+        assert!(walk(&chart, Uuid::from_u128(9)).is_none());
+    }
+
+    #[test]
+    fn stops_at_cycles_and_missing_managers() {
+        let mut chart = chart(&[(1, Some(2)), (2, Some(3)), (3, Some(1)), (4, Some(9))]);
+
+        let cycle = walk(&chart, Uuid::from_u128(1)).unwrap();
+        assert_eq!(ids(&cycle), vec![2, 3]);
+        assert_eq!(
+            cycle.end,
+            ChainEnd::Cycle {
+                manager_id: Uuid::from_u128(1)
+            }
+        );
+
+        let missing = walk(&chart, Uuid::from_u128(4)).unwrap();
+        assert!(missing.managers.is_empty());
+        assert_eq!(
+            missing.end,
+            ChainEnd::MissingManager {
+                manager_id: Uuid::from_u128(9)
+            }
+        );
+
+        chart.employees.get_mut(&Uuid::from_u128(3)).unwrap().org_id = Uuid::from_u128(77);
+        let other_org = walk(&chart, Uuid::from_u128(1)).unwrap();
+        assert_eq!(ids(&other_org), vec![2]);
+        assert_eq!(
+            other_org.end,
+            ChainEnd::MissingManager {
+                manager_id: Uuid::from_u128(3)
+            }
+        );
+    }
+}
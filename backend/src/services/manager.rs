@@ -1,112 +1,64 @@
-use std::{collections::HashMap, sync::Arc};
+use std::sync::Arc;
 
 use chrono::{DateTime, NaiveDate, Utc};
-use serde::Serialize;
-use sqlx::FromRow;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
-    domain::models::{ReportStatus, Role},
+    domain::models::Role,
     infrastructure::{auth::AuthenticatedUser, state::AppState},
 };
 
-use super::errors::ServiceError;
+use super::{
+    errors::ServiceError,
+    pagination::{Cursor, PageRequest, Paginated},
+    repositories::{approvals::QueuedReport, ApprovalRepo, PgApprovalRepo},
+};
 
 /// Service exposing manager-focused aggregates for pending expense reports.
 pub struct ManagerService {
-    state: Arc<AppState>,
+    approvals: Arc<dyn ApprovalRepo>,
 }
 
 impl ManagerService {
     /// Constructs the service from shared application state.
     pub fn new(state: Arc<AppState>) -> Self {
-        Self { state }
+        Self::with_approval_repo(Arc::new(PgApprovalRepo::new(state.read_pool.clone())))
+    }
+
+    /// Builds the service over `approvals` instead of the database.
+    pub fn with_approval_repo(approvals: Arc<dyn ApprovalRepo>) -> Self {
+        Self { approvals }
     }
 
     /// Returns the queue of submitted expense reports awaiting manager review.
     ///
     /// Only actors with the `Role::Manager` designation may access the queue.
+    /// It lists every submitted report of the actor's organization, plus the
+    /// exception reviews assigned to the actor. Entries are read from the
+    /// `manager_queue_entries` read model, which database triggers keep in
+    /// sync with report, item, and employee changes. Pages run oldest
+    /// submission first.
     pub async fn fetch_queue(
         &self,
         actor: &AuthenticatedUser,
-    ) -> Result<Vec<ManagerQueueEntry>, ServiceError> {
+        page: &PageRequest,
+    ) -> Result<Paginated<ManagerQueueEntry>, ServiceError> {
         if actor.role != Role::Manager {
             return Err(ServiceError::Forbidden);
         }
 
-        let reports: Vec<ReportRow> = sqlx::query_as(
-            r#"
-            SELECT
-                r.id,
-                r.employee_id,
-                e.hr_identifier,
-                r.reporting_period_start,
-                r.reporting_period_end,
-                r.total_amount_cents,
-                r.total_reimbursable_cents,
-                r.currency,
-                r.updated_at AS submitted_at
-            FROM expense_reports r
-            JOIN employees e ON e.id = r.employee_id
-            WHERE r.status = $1
-            ORDER BY submitted_at ASC, r.id ASC
-            "#,
-        )
-        .bind(ReportStatus::Submitted.as_str())
-        .fetch_all(&self.state.pool)
-        .await
-        .map_err(|err| ServiceError::Internal(err.to_string()))?;
-
-        if reports.is_empty() {
-            return Ok(Vec::new());
-        }
-
-        let report_ids: Vec<Uuid> = reports.iter().map(|report| report.id).collect();
-
-        let items: Vec<ItemRow> = sqlx::query_as(
-            r#"
-            SELECT
-                id,
-                report_id,
-                expense_date,
-                category,
-                description,
-                amount_cents,
-                reimbursable,
-                payment_method,
-                is_policy_exception
-            FROM expense_items
-            WHERE report_id = ANY($1)
-            ORDER BY expense_date ASC, id ASC
-            "#,
-        )
-        .bind(&report_ids)
-        .fetch_all(&self.state.pool)
-        .await
-        .map_err(|err| ServiceError::Internal(err.to_string()))?;
-
-        let mut items_by_report: HashMap<Uuid, Vec<ManagerQueueLineItem>> = HashMap::new();
-        for item in items {
-            let entry = ManagerQueueLineItem {
-                id: item.id,
-                report_id: item.report_id,
-                expense_date: item.expense_date,
-                category: item.category,
-                description: item.description,
-                amount_cents: item.amount_cents,
-                reimbursable: item.reimbursable,
-                payment_method: item.payment_method,
-                is_policy_exception: item.is_policy_exception,
-            };
-            items_by_report
-                .entry(entry.report_id)
-                .or_default()
-                .push(entry);
-        }
+        let (total, reports) = self
+            .approvals
+            .manager_queue(actor.org_id, actor.employee_id, page)
+            .await?;
 
         let mut queue = Vec::with_capacity(reports.len());
-        for report in reports {
-            let items = items_by_report.remove(&report.id).unwrap_or_default();
+        for QueuedReport {
+            report,
+            line_items: items,
+        } in reports
+        {
             let policy_flags = items
                 .iter()
                 .filter(|item| item.is_policy_exception)
@@ -119,58 +71,18 @@ impl ManagerService {
                 .collect();
 
             queue.push(ManagerQueueEntry {
-                report: report.into(),
+                report,
                 line_items: items,
                 policy_flags,
             });
         }
 
-        Ok(queue)
-    }
-}
-
-#[derive(Debug, FromRow)]
-struct ReportRow {
-    id: Uuid,
-    employee_id: Uuid,
-    hr_identifier: String,
-    reporting_period_start: NaiveDate,
-    reporting_period_end: NaiveDate,
-    total_amount_cents: i64,
-    total_reimbursable_cents: i64,
-    currency: String,
-    submitted_at: DateTime<Utc>,
-}
-
-impl From<ReportRow> for ManagerQueueReport {
-    fn from(value: ReportRow) -> Self {
-        Self {
-            id: value.id,
-            employee_id: value.employee_id,
-            employee_hr_identifier: value.hr_identifier,
-            reporting_period_start: value.reporting_period_start,
-            reporting_period_end: value.reporting_period_end,
-            submitted_at: value.submitted_at,
-            total_amount_cents: value.total_amount_cents,
-            total_reimbursable_cents: value.total_reimbursable_cents,
-            currency: value.currency,
-        }
+        Ok(Paginated::new(queue, page, total, |entry| {
+            Cursor::new(entry.report.submitted_at, entry.report.id)
+        }))
     }
 }
 
-#[derive(Debug, FromRow)]
-struct ItemRow {
-    id: Uuid,
-    report_id: Uuid,
-    expense_date: NaiveDate,
-    category: String,
-    description: Option<String>,
-    amount_cents: i64,
-    reimbursable: bool,
-    payment_method: Option<String>,
-    is_policy_exception: bool,
-}
-
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ManagerQueueEntry {
@@ -179,7 +91,7 @@ pub struct ManagerQueueEntry {
     pub policy_flags: Vec<ManagerPolicyFlag>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ManagerQueueReport {
     pub id: Uuid,
@@ -193,7 +105,7 @@ pub struct ManagerQueueReport {
     pub currency: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ManagerQueueLineItem {
     pub id: Uuid,
@@ -215,3 +127,101 @@ pub struct ManagerPolicyFlag {
     pub expense_date: NaiveDate,
     pub description: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, SubsecRound};
+
+    use crate::{domain::models::DEFAULT_ORG_ID, services::repositories::MemoryApprovalRepo};
+
+    fn queued(submitted_at: DateTime<Utc>, exception: bool) -> QueuedReport {
+        let id = Uuid::new_v4();
+        let day = NaiveDate::from_ymd_opt(2024, 5, 6).unwrap();
+        QueuedReport {
+            report: ManagerQueueReport {
+                id,
+                employee_id: Uuid::new_v4(),
+                employee_hr_identifier: "E100".into(),
+                reporting_period_start: day,
+                reporting_period_end: day,
+                submitted_at,
+                total_amount_cents: 4_200,
+                total_reimbursable_cents: 4_200,
+                currency: "USD".into(),
+            },
+            line_items: vec![ManagerQueueLineItem {
+                id: Uuid::new_v4(),
+                report_id: id,
+                expense_date: day,
+                category: "meals".into(),
+                description: Some("Client dinner".into()),
+                amount_cents: 4_200,
+                reimbursable: true,
+                payment_method: None,
+                is_policy_exception: exception,
+            }],
+        }
+    }
+
+    #[tokio::test]
+    async fn fetch_queue_pages_organization_reports_and_flags_policy_exceptions() {
+        let manager = AuthenticatedUser {
+            employee_id: Uuid::new_v4(),
+            role: Role::Manager,
+            org_id: DEFAULT_ORG_ID,
+        };
+        let approvals = Arc::new(MemoryApprovalRepo::default());
+        // Cursors carry microseconds, as Postgres timestamps do.
+        let start = (Utc::now() - Duration::days(3)).trunc_subsecs(6);
+        approvals.queue(DEFAULT_ORG_ID, queued(start, true));
+        approvals.queue(DEFAULT_ORG_ID, queued(start + Duration::hours(1), false));
+        approvals.queue_exception_review(
+            DEFAULT_ORG_ID,
+            manager.employee_id,
+            queued(start + Duration::hours(2), false),
+        );
+        // Another organization's report and another approver's exception
+        // review stay out.
+        approvals.queue(Uuid::new_v4(), queued(start, true));
+        approvals.queue_exception_review(DEFAULT_ORG_ID, Uuid::new_v4(), queued(start, true));
+        let service = ManagerService::with_approval_repo(approvals);
+
+        let page = PageRequest {
+            limit: 2,
+            after: None,
+        };
+        let first = service.fetch_queue(&manager, &page).await.unwrap();
+        assert_eq!(first.total, 3);
+        assert_eq!(first.items.len(), 2);
+        assert_eq!(first.items[0].report.submitted_at, start);
+        assert_eq!(first.items[0].policy_flags.len(), 1);
+        assert_eq!(
+            first.items[0].policy_flags[0].item_id,
+            first.items[0].line_items[0].id
+        );
+        assert!(first.items[1].policy_flags.is_empty());
+
+        let next = PageRequest {
+            limit: 2,
+            after: Cursor::decode(first.next_cursor.as_deref().unwrap()),
+        };
+        let second = service.fetch_queue(&manager, &next).await.unwrap();
+        assert_eq!(second.items.len(), 1);
+        assert_eq!(
+            second.items[0].report.submitted_at,
+            start + Duration::hours(2)
+        );
+        assert!(second.next_cursor.is_none());
+
+        let employee = AuthenticatedUser {
+            employee_id: manager.employee_id,
+            role: Role::Employee,
+            org_id: DEFAULT_ORG_ID,
+        };
+        assert!(matches!(
+            service.fetch_queue(&employee, &page).await,
+            Err(ServiceError::Forbidden)
+        ));
+    }
+}
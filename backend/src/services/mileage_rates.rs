@@ -0,0 +1,402 @@
+//! Mileage rate administration and the IRS standard-rate import.
+//!
+//! Administrators manage the rates through `/policy/mileage-rates`; anyone
+//! signed in can list them. `POST /policy/mileage-rates/import-irs` loads a
+//! year's IRS business standard mileage rate, and `rates_through` feeds
+//! `domain::policy`, which checks mileage claims against the rate in force on
+//! each expense date.
+
+use std::sync::Arc;
+
+use chrono::{NaiveDate, Utc};
+use serde::Deserialize;
+use sqlx::{postgres::PgRow, PgPool, Row};
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{MileageRate, Role},
+    infrastructure::{auth::AuthenticatedUser, reference_cache::ReferenceData, state::AppState},
+};
+
+use super::{audit, errors::ServiceError};
+
+/// IRS business standard mileage rates in cents per mile by effective date,
+/// as published in the IRS notice for each year. 2022 had a mid-year
+/// increase.
+pub const IRS_BUSINESS_RATES: &[((i32, u32, u32), f64)] = &[
+    ((2019, 1, 1), 58.0),
+    ((2020, 1, 1), 57.5),
+    ((2021, 1, 1), 56.0),
+    ((2022, 1, 1), 58.5),
+    ((2022, 7, 1), 62.5),
+    ((2023, 1, 1), 65.5),
+    ((2024, 1, 1), 67.0),
+    ((2025, 1, 1), 70.0),
+];
+
+/// Payload accepted by `POST /policy/mileage-rates` and
+/// `PUT /policy/mileage-rates/:id`.
+#[derive(Debug, Deserialize)]
+pub struct MileageRateRequest {
+    pub effective_date: NaiveDate,
+    pub rate_cents_per_mile: f64,
+    #[serde(default)]
+    pub source_reference: Option<String>,
+}
+
+/// Payload accepted by `POST /policy/mileage-rates/import-irs`.
+#[derive(Debug, Deserialize)]
+pub struct IrsImportRequest {
+    pub year: i32,
+    /// Required for years missing from `IRS_BUSINESS_RATES`; overrides the
+    /// built-in rate otherwise.
+    #[serde(default)]
+    pub rate_cents_per_mile: Option<f64>,
+}
+
+/// Service managing the `mileage_rates` table.
+pub struct MileageRateService {
+    state: Arc<AppState>,
+}
+
+impl MileageRateService {
+    /// Constructs the service from shared application state.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// Lists rates, newest effective date first.
+    pub async fn list(&self) -> Result<Vec<MileageRate>, ServiceError> {
+        sqlx::query(&format!(
+            "SELECT {COLUMNS} FROM mileage_rates ORDER BY effective_date DESC"
+        ))
+        .fetch_all(&self.state.pool)
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .map(map_rate)
+        .collect()
+    }
+
+    /// Adds a rate. Restricted to administrators; a second rate on the same
+    /// effective date is a conflict.
+    pub async fn create(
+        &self,
+        actor: &AuthenticatedUser,
+        payload: MileageRateRequest,
+    ) -> Result<MileageRate, ServiceError> {
+        require_admin(actor)?;
+        validate(&payload)?;
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let row = sqlx::query(&format!(
+            "INSERT INTO mileage_rates
+                (id, effective_date, rate_cents_per_mile, source_reference, updated_by,
+                 updated_at)
+             VALUES ($1,$2,$3,$4,$5,$6)
+             ON CONFLICT (effective_date) DO NOTHING
+             RETURNING {COLUMNS}"
+        ))
+        .bind(Uuid::new_v4())
+        .bind(payload.effective_date)
+        .bind(payload.rate_cents_per_mile)
+        .bind(source_reference(&payload))
+        .bind(actor.employee_id)
+        .bind(Utc::now())
+        .fetch_optional(tx.as_mut())
+        .await
+        .map_err(internal)?
+        .ok_or(ServiceError::Conflict)?;
+        let rate = map_rate(row)?;
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::MILEAGE_RATE,
+            rate.id,
+            audit::CREATED,
+            None,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        self.state
+            .reference_cache
+            .invalidate(ReferenceData::MileageRates);
+        Ok(rate)
+    }
+
+    /// Replaces a rate. Restricted to administrators.
+    pub async fn update(
+        &self,
+        actor: &AuthenticatedUser,
+        rate_id: Uuid,
+        payload: MileageRateRequest,
+    ) -> Result<MileageRate, ServiceError> {
+        require_admin(actor)?;
+        validate(&payload)?;
+        let taken: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM mileage_rates WHERE effective_date = $1 AND id <> $2)",
+        )
+        .bind(payload.effective_date)
+        .bind(rate_id)
+        .fetch_one(&self.state.pool)
+        .await
+        .map_err(internal)?;
+        if taken {
+            return Err(ServiceError::Conflict);
+        }
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let before = audit::MILEAGE_RATE.snapshot(tx.as_mut(), rate_id).await?;
+        let row = sqlx::query(&format!(
+            "UPDATE mileage_rates
+             SET effective_date = $2, rate_cents_per_mile = $3, source_reference = $4,
+                 updated_by = $5, updated_at = $6
+             WHERE id = $1
+             RETURNING {COLUMNS}"
+        ))
+        .bind(rate_id)
+        .bind(payload.effective_date)
+        .bind(payload.rate_cents_per_mile)
+        .bind(source_reference(&payload))
+        .bind(actor.employee_id)
+        .bind(Utc::now())
+        .fetch_optional(tx.as_mut())
+        .await
+        .map_err(internal)?
+        .ok_or(ServiceError::NotFound)?;
+        let rate = map_rate(row)?;
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::MILEAGE_RATE,
+            rate_id,
+            audit::UPDATED,
+            before,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        self.state
+            .reference_cache
+            .invalidate(ReferenceData::MileageRates);
+        Ok(rate)
+    }
+
+    /// Deletes a rate. Restricted to administrators.
+    pub async fn delete(
+        &self,
+        actor: &AuthenticatedUser,
+        rate_id: Uuid,
+    ) -> Result<(), ServiceError> {
+        require_admin(actor)?;
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let before = audit::MILEAGE_RATE.snapshot(tx.as_mut(), rate_id).await?;
+        if before.is_none() {
+            return Err(ServiceError::NotFound);
+        }
+        sqlx::query("DELETE FROM mileage_rates WHERE id = $1")
+            .bind(rate_id)
+            .execute(tx.as_mut())
+            .await
+            .map_err(internal)?;
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::MILEAGE_RATE,
+            rate_id,
+            audit::DELETED,
+            before,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        self.state
+            .reference_cache
+            .invalidate(ReferenceData::MileageRates);
+        Ok(())
+    }
+
+    /// Loads `year`'s IRS business standard rates, replacing any rate already
+    /// effective on the same dates. Restricted to administrators.
+    pub async fn import_irs(
+        &self,
+        actor: &AuthenticatedUser,
+        payload: IrsImportRequest,
+    ) -> Result<Vec<MileageRate>, ServiceError> {
+        require_admin(actor)?;
+        let rates = irs_rates(payload.year, payload.rate_cents_per_mile)?;
+
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let mut imported = Vec::with_capacity(rates.len());
+        for (effective_date, rate_cents_per_mile) in rates {
+            let existing: Option<Uuid> = sqlx::query_scalar(
+                "SELECT id FROM mileage_rates WHERE effective_date = $1 FOR UPDATE",
+            )
+            .bind(effective_date)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(internal)?;
+            let before = match existing {
+                Some(id) => audit::MILEAGE_RATE.snapshot(&mut tx, id).await?,
+                None => None,
+            };
+            let row = sqlx::query(&format!(
+                "INSERT INTO mileage_rates
+                    (id, effective_date, rate_cents_per_mile, source_reference, updated_by,
+                     updated_at)
+                 VALUES ($1,$2,$3,$4,$5,$6)
+                 ON CONFLICT (effective_date) DO UPDATE
+                 SET rate_cents_per_mile = EXCLUDED.rate_cents_per_mile,
+                     source_reference = EXCLUDED.source_reference,
+                     updated_by = EXCLUDED.updated_by, updated_at = EXCLUDED.updated_at
+                 RETURNING {COLUMNS}"
+            ))
+            .bind(Uuid::new_v4())
+            .bind(effective_date)
+            .bind(rate_cents_per_mile)
+            .bind(format!("IRS standard mileage rate {}", payload.year))
+            .bind(actor.employee_id)
+            .bind(Utc::now())
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(internal)?;
+            let rate = map_rate(row)?;
+            audit::record_change(
+                &mut tx,
+                Some(actor.employee_id),
+                audit::MILEAGE_RATE,
+                rate.id,
+                if existing.is_some() {
+                    audit::UPDATED
+                } else {
+                    audit::CREATED
+                },
+                before,
+            )
+            .await?;
+            imported.push(rate);
+        }
+        tx.commit().await.map_err(internal)?;
+        self.state
+            .reference_cache
+            .invalidate(ReferenceData::MileageRates);
+        Ok(imported)
+    }
+}
+
+/// Loads every rate effective on or before `date`, for
+/// `domain::policy::resolve_mileage_rate`.
+pub async fn rates_through(
+    pool: &PgPool,
+    date: NaiveDate,
+) -> Result<Vec<MileageRate>, ServiceError> {
+    sqlx::query(&format!(
+        "SELECT {COLUMNS} FROM mileage_rates WHERE effective_date <= $1 ORDER BY effective_date"
+    ))
+    .bind(date)
+    .fetch_all(pool)
+    .await
+    .map_err(internal)?
+    .into_iter()
+    .map(map_rate)
+    .collect()
+}
+
+/// The IRS rates to load for `year`: a January 1 rate of `rate` when given,
+/// otherwise the year's entries in `IRS_BUSINESS_RATES`.
+pub fn irs_rates(year: i32, rate: Option<f64>) -> Result<Vec<(NaiveDate, f64)>, ServiceError> {
+    if !(2000..=2100).contains(&year) {
+        return Err(ServiceError::Validation(
+            "year must be between 2000 and 2100".into(),
+        ));
+    }
+    if let Some(rate) = rate {
+        validate_rate(rate)?;
+        let january = NaiveDate::from_ymd_opt(year, 1, 1).expect("January 1 exists");
+        return Ok(vec![(january, rate)]);
+    }
+    let rates: Vec<(NaiveDate, f64)> = IRS_BUSINESS_RATES
+        .iter()
+        .filter(|((rate_year, _, _), _)| *rate_year == year)
+        .filter_map(|&((y, m, d), rate)| Some((NaiveDate::from_ymd_opt(y, m, d)?, rate)))
+        .collect();
+    if rates.is_empty() {
+        return Err(ServiceError::Validation(format!(
+            "no built-in IRS rate for {year}; pass rate_cents_per_mile from the IRS notice"
+        )));
+    }
+    Ok(rates)
+}
+
+/// Columns of a `MileageRate`, with the rate read as a float.
+const COLUMNS: &str = "id, effective_date, rate_cents_per_mile::float8 AS rate_cents_per_mile, \
+                       source_reference, updated_by, updated_at";
+
+fn validate(payload: &MileageRateRequest) -> Result<(), ServiceError> {
+    validate_rate(payload.rate_cents_per_mile)
+}
+
+fn validate_rate(rate: f64) -> Result<(), ServiceError> {
+    // NUMERIC(6, 2) holds up to 9999.99.
+    if !(rate > 0.0 && rate < 10_000.0) {
+        return Err(ServiceError::Validation(
+            "rate_cents_per_mile must be greater than 0 and below 10000".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn source_reference(payload: &MileageRateRequest) -> Option<&str> {
+    payload
+        .source_reference
+        .as_deref()
+        .map(str::trim)
+        .filter(|source| !source.is_empty())
+}
+
+/// Deployment-wide settings are maintained by administrators of the
+/// operating organization.
+fn require_admin(actor: &AuthenticatedUser) -> Result<(), ServiceError> {
+    if actor.role != Role::Admin || !actor.is_operator() {
+        return Err(ServiceError::Forbidden);
+    }
+    Ok(())
+}
+
+fn map_rate(row: PgRow) -> Result<MileageRate, ServiceError> {
+    Ok(MileageRate {
+        id: row.try_get("id").map_err(internal)?,
+        effective_date: row.try_get("effective_date").map_err(internal)?,
+        rate_cents_per_mile: row.try_get("rate_cents_per_mile").map_err(internal)?,
+        source_reference: row.try_get("source_reference").map_err(internal)?,
+        updated_by: row.try_get("updated_by").map_err(internal)?,
+        updated_at: row.try_get("updated_at").map_err(internal)?,
+    })
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn irs_rates_use_the_built_in_table_or_an_explicit_rate() {
+        let date = |y, m| NaiveDate::from_ymd_opt(y, m, 1).unwrap();
+        assert_eq!(
+            irs_rates(2022, None).unwrap(),
+            vec![(date(2022, 1), 58.5), (date(2022, 7), 62.5)]
+        );
+        assert_eq!(irs_rates(2024, None).unwrap(), vec![(date(2024, 1), 67.0)]);
+        assert_eq!(
+            irs_rates(2024, Some(68.0)).unwrap(),
+            vec![(date(2024, 1), 68.0)]
+        );
+        assert!(matches!(
+            irs_rates(2031, None),
+            Err(ServiceError::Validation(_))
+        ));
+        assert!(matches!(
+            irs_rates(2031, Some(0.0)),
+            Err(ServiceError::Validation(_))
+        ));
+    }
+}
@@ -1,5 +1,45 @@
 pub mod approvals;
+pub mod archive;
+pub mod assistants;
+pub mod audit;
+pub mod bank_accounts;
+pub mod budgets;
+pub mod department_heads;
+pub mod departments;
+pub mod duplicates;
+pub mod employees;
 pub mod errors;
+pub mod escalations;
+pub mod events;
 pub mod expenses;
 pub mod finance;
+pub mod fx;
+pub mod holidays;
+pub mod hr_sync;
+pub mod idempotency;
+pub mod job_runs;
+pub mod journal_export;
+pub mod management_chain;
 pub mod manager;
+pub mod mileage_rates;
+pub mod netsuite_status;
+pub mod notifications;
+pub mod organizations;
+pub mod outbox;
+pub mod pagination;
+pub mod per_diem;
+pub mod periods;
+pub mod policy_history;
+pub mod policy_rules;
+pub mod policy_versions;
+pub mod preconditions;
+pub mod profile;
+pub mod receipts;
+pub mod report_versions;
+pub mod repositories;
+pub mod retention;
+pub mod roster;
+pub mod search;
+pub mod terminations;
+pub mod trips;
+pub mod webhooks;
@@ -0,0 +1,322 @@
+//! NetSuite-driven batch status transitions.
+//!
+//! Shared by the reconciliation worker, which polls NetSuite for each
+//! `exported` batch, and `POST /api/integrations/netsuite/callback`, through
+//! which NetSuite pushes the same answer once it has processed an entry.
+//! Either way a batch only leaves `exported` once: `posted` is final and
+//! tells each employee in the batch their reimbursement is paid, and
+//! `rejected` releases the batch's reports back to `manager_approved` and
+//! alerts finance users.
+
+use std::collections::BTreeMap;
+
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgRow, PgPool, Row};
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{ReportStatus, Role},
+    infrastructure::state::AppState,
+};
+
+use super::{audit, errors::ServiceError, notifications};
+
+/// Notification kind recorded on queued rejection alerts.
+pub const BATCH_REJECTED_KIND: &str = "netsuite_batch_rejected";
+
+/// Notification kind queued for each employee with reports in a posted batch.
+pub const REIMBURSEMENT_PAID_KIND: &str = "reimbursement_paid";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RejectedBatch {
+    pub batch_id: Uuid,
+    pub batch_reference: String,
+    pub netsuite_id: String,
+    pub released_report_ids: Vec<Uuid>,
+}
+
+/// Final processing result NetSuite reports for an exported record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CallbackStatus {
+    Posted,
+    Rejected,
+}
+
+/// Payload accepted by `POST /api/integrations/netsuite/callback`.
+///
+/// `external_id` is the external ID the record was upserted under: the batch
+/// ID for a journal entry, `<batch id>-<HR identifier>` for a vendor bill.
+#[derive(Debug, Deserialize)]
+pub struct NetSuiteCallback {
+    pub external_id: String,
+    pub status: CallbackStatus,
+    #[serde(default)]
+    pub internal_id: Option<String>,
+}
+
+/// Result of applying a callback. `applied` is false when the batch had
+/// already left `exported`, e.g. on a redelivered callback.
+#[derive(Debug, Clone, Serialize)]
+pub struct CallbackOutcome {
+    pub batch_id: Uuid,
+    pub status: String,
+    pub applied: bool,
+}
+
+/// Records a NetSuite callback against its batch. Fails with
+/// `ServiceError::NotFound` when no batch has the callback's external ID.
+///
+/// A vendor-bill batch settles on the first callback for any of its bills.
+pub async fn apply_callback(
+    state: &AppState,
+    callback: NetSuiteCallback,
+) -> Result<CallbackOutcome, ServiceError> {
+    let batch_id = batch_id_from_external_id(&callback.external_id).ok_or_else(|| {
+        ServiceError::Validation("external_id does not name an expense batch".into())
+    })?;
+    let batch: Option<(String, Option<String>)> = sqlx::query(
+        "SELECT batch_reference, netsuite_response->>'reference' AS netsuite_id
+         FROM netsuite_batches
+         WHERE id = $1",
+    )
+    .bind(batch_id)
+    .map(|row: PgRow| (row.get("batch_reference"), row.get("netsuite_id")))
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(internal)?;
+    let (batch_reference, stored_id) = batch.ok_or(ServiceError::NotFound)?;
+
+    let applied = match callback.status {
+        CallbackStatus::Posted => mark_posted(&state.pool, batch_id).await?,
+        CallbackStatus::Rejected => match reject(&state.pool, batch_id).await? {
+            Some(released_report_ids) => {
+                let rejected = RejectedBatch {
+                    batch_id,
+                    batch_reference,
+                    netsuite_id: callback.internal_id.or(stored_id).unwrap_or_default(),
+                    released_report_ids,
+                };
+                alert_finance(&state.pool, std::slice::from_ref(&rejected)).await?;
+                true
+            }
+            None => false,
+        },
+    };
+
+    let status: String = sqlx::query_scalar("SELECT status FROM netsuite_batches WHERE id = $1")
+        .bind(batch_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(internal)?;
+    Ok(CallbackOutcome {
+        batch_id,
+        status,
+        applied,
+    })
+}
+
+/// Stamps `last_reconciled_at` on a batch whose entry is still pending.
+pub async fn touch(pool: &PgPool, batch_id: Uuid) -> Result<(), ServiceError> {
+    sqlx::query("UPDATE netsuite_batches SET last_reconciled_at = $2 WHERE id = $1")
+        .bind(batch_id)
+        .bind(Utc::now())
+        .execute(pool)
+        .await
+        .map_err(internal)?;
+    Ok(())
+}
+
+/// Marks the batch `posted` and queues one `reimbursement_paid` notification
+/// per employee listing their reports in it. Returns whether the batch was
+/// still `exported`; a batch reversed or settled meanwhile is left alone.
+pub async fn mark_posted(pool: &PgPool, batch_id: Uuid) -> Result<bool, ServiceError> {
+    let mut tx = pool.begin().await.map_err(internal)?;
+    let before = audit::NETSUITE_BATCH.snapshot(&mut tx, batch_id).await?;
+    let batch_reference: Option<String> = sqlx::query_scalar(
+        "UPDATE netsuite_batches SET status = 'posted', last_reconciled_at = $2
+         WHERE id = $1 AND status = 'exported'
+         RETURNING batch_reference",
+    )
+    .bind(batch_id)
+    .bind(Utc::now())
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(internal)?;
+    let Some(batch_reference) = batch_reference else {
+        return Ok(false);
+    };
+    audit::record_change(
+        &mut tx,
+        None,
+        audit::NETSUITE_BATCH,
+        batch_id,
+        audit::STATUS_CHANGED,
+        before,
+    )
+    .await?;
+
+    let reports = sqlx::query(
+        "SELECT r.id, r.employee_id, r.reporting_period_start, r.reporting_period_end,
+                r.total_reimbursable_cents, r.currency
+         FROM expense_reports r
+         WHERE r.id IN (SELECT report_id FROM journal_lines WHERE batch_id = $1)
+         ORDER BY r.employee_id, r.reporting_period_start",
+    )
+    .bind(batch_id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(internal)?;
+    let mut paid: BTreeMap<Uuid, Vec<serde_json::Value>> = BTreeMap::new();
+    for report in reports {
+        paid.entry(report.try_get("employee_id").map_err(internal)?)
+            .or_default()
+            .push(serde_json::json!({
+                "report_id": report.try_get::<Uuid, _>("id").map_err(internal)?,
+                "reporting_period_start": report
+                    .try_get::<NaiveDate, _>("reporting_period_start")
+                    .map_err(internal)?,
+                "reporting_period_end": report
+                    .try_get::<NaiveDate, _>("reporting_period_end")
+                    .map_err(internal)?,
+                "reimbursable_cents": report
+                    .try_get::<i64, _>("total_reimbursable_cents")
+                    .map_err(internal)?,
+                "currency": report.try_get::<String, _>("currency").map_err(internal)?,
+            }));
+    }
+    for (employee_id, reports) in paid {
+        let payload = serde_json::json!({
+            "batch_id": batch_id,
+            "batch_reference": batch_reference,
+            "reports": reports,
+        });
+        notifications::enqueue(&mut *tx, employee_id, REIMBURSEMENT_PAID_KIND, payload).await?;
+    }
+    tx.commit().await.map_err(internal)?;
+    Ok(true)
+}
+
+/// Marks the batch `rejected` and returns the reports released back to
+/// `manager_approved`, or `None` if the batch left `exported` meanwhile.
+pub async fn reject(pool: &PgPool, batch_id: Uuid) -> Result<Option<Vec<Uuid>>, ServiceError> {
+    let mut tx = pool.begin().await.map_err(internal)?;
+    let before = audit::NETSUITE_BATCH.snapshot(&mut tx, batch_id).await?;
+    let updated = sqlx::query(
+        "UPDATE netsuite_batches SET status = 'rejected', last_reconciled_at = $2
+         WHERE id = $1 AND status = 'exported'",
+    )
+    .bind(batch_id)
+    .bind(Utc::now())
+    .execute(&mut *tx)
+    .await
+    .map_err(internal)?;
+    if updated.rows_affected() == 0 {
+        return Ok(None);
+    }
+    audit::record_change(
+        &mut tx,
+        None,
+        audit::NETSUITE_BATCH,
+        batch_id,
+        audit::STATUS_CHANGED,
+        before,
+    )
+    .await?;
+
+    let released: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT id FROM expense_reports
+         WHERE status::text = 'finance_finalized'
+           AND id IN (SELECT report_id FROM journal_lines WHERE batch_id = $1)
+         ORDER BY id
+         FOR UPDATE",
+    )
+    .bind(batch_id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(internal)?;
+    for report_id in &released {
+        let before = audit::EXPENSE_REPORT.snapshot(&mut tx, *report_id).await?;
+        sqlx::query("UPDATE expense_reports SET status = $2, updated_at = NOW() WHERE id = $1")
+            .bind(report_id)
+            .bind(ReportStatus::ManagerApproved)
+            .execute(&mut *tx)
+            .await
+            .map_err(internal)?;
+        audit::record_change(
+            &mut tx,
+            None,
+            audit::EXPENSE_REPORT,
+            *report_id,
+            audit::STATUS_CHANGED,
+            before,
+        )
+        .await?;
+    }
+    tx.commit().await.map_err(internal)?;
+    Ok(Some(released))
+}
+
+/// Queues one `netsuite_batch_rejected` notification per finance user
+/// listing the batches of `rejected` that belong to the user's organization.
+pub async fn alert_finance(pool: &PgPool, rejected: &[RejectedBatch]) -> Result<(), ServiceError> {
+    let batch_ids: Vec<Uuid> = rejected.iter().map(|batch| batch.batch_id).collect();
+    let recipients: Vec<(Uuid, Vec<Uuid>)> = sqlx::query_as(
+        "SELECT e.id, ARRAY_AGG(b.id)
+         FROM employees e
+         JOIN netsuite_batches b ON b.org_id = e.org_id
+         WHERE e.role::text = $1 AND b.id = ANY($2)
+         GROUP BY e.id",
+    )
+    .bind(Role::Finance.as_str())
+    .bind(&batch_ids)
+    .fetch_all(pool)
+    .await
+    .map_err(internal)?;
+    let mut tx = pool.begin().await.map_err(internal)?;
+    for (recipient, batches) in recipients {
+        let visible: Vec<&RejectedBatch> = rejected
+            .iter()
+            .filter(|batch| batches.contains(&batch.batch_id))
+            .collect();
+        let payload = serde_json::json!({ "rejected": visible });
+        notifications::enqueue(&mut *tx, recipient, BATCH_REJECTED_KIND, payload).await?;
+    }
+    tx.commit().await.map_err(internal)?;
+    Ok(())
+}
+
+/// Extracts the batch ID leading a record's external ID.
+fn batch_id_from_external_id(external_id: &str) -> Option<Uuid> {
+    let external_id = external_id.trim();
+    let batch_id = external_id.get(..36)?;
+    if !matches!(external_id.as_bytes().get(36), None | Some(b'-')) {
+        return None;
+    }
+    Uuid::parse_str(batch_id).ok()
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_id_is_read_from_journal_and_vendor_bill_external_ids() {
+        let batch_id = Uuid::new_v4();
+        assert_eq!(
+            batch_id_from_external_id(&batch_id.to_string()),
+            Some(batch_id)
+        );
+        assert_eq!(
+            batch_id_from_external_id(&format!("{batch_id}-EMP3101")),
+            Some(batch_id)
+        );
+        assert_eq!(batch_id_from_external_id(&format!("{batch_id}x")), None);
+        assert_eq!(batch_id_from_external_id("EMP3101"), None);
+    }
+}
@@ -0,0 +1,507 @@
+//! Queues outbound notifications and manages per-employee delivery
+//! preferences.
+//!
+//! Notifications are persisted to the `notifications` table and drained by a
+//! delivery worker, so callers never block on email or chat providers. The
+//! `email` job hands each due notification to `deliver_pending`, which renders
+//! it with `infrastructure::notifications::templates` in the recipient's
+//! `employees.locale` and sends it to their `employees.email`. The `chat` job does the same for the chat
+//! channel through `deliver_pending_chat`, posting to the recipient's own
+//! webhook or the deployment's. The preference endpoints in
+//! `backend/src/api/rest/notifications.rs` let reviewers opt out of approval
+//! reminders, pick how often they get a digest, and register a personal chat
+//! webhook.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgRow, PgExecutor, Row};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::infrastructure::{
+    auth::AuthenticatedUser,
+    notifications::{
+        chat::{validate_webhook_url, ChatWebhook},
+        templates, EmailMessage, EmailSender,
+    },
+    state::AppState,
+};
+
+use super::{errors::ServiceError, outbox};
+
+/// Channel of notifications delivered by email.
+pub const EMAIL_CHANNEL: &str = "email";
+
+/// Channel of notifications posted to Slack or Teams webhooks.
+pub const CHAT_CHANNEL: &str = "chat";
+
+/// Notifications claimed by one delivery run.
+const DELIVERY_LIMIT: i64 = 100;
+
+/// How long a claimed notification is hidden from other delivery runs. A run
+/// that crashes mid-send leaves it due again once the lease runs out.
+const CLAIM_LEASE_SECONDS: i64 = 300;
+
+/// Outcome of one delivery run.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DeliverySummary {
+    pub sent: usize,
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+/// How often a reviewer receives the `digest` job's summary.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DigestFrequency {
+    Daily,
+    #[default]
+    Weekly,
+    None,
+}
+
+impl DigestFrequency {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DigestFrequency::Daily => "daily",
+            DigestFrequency::Weekly => "weekly",
+            DigestFrequency::None => "none",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "daily" => Some(DigestFrequency::Daily),
+            "weekly" => Some(DigestFrequency::Weekly),
+            "none" => Some(DigestFrequency::None),
+            _ => None,
+        }
+    }
+}
+
+/// Delivery preferences stored per employee.
+#[derive(Debug, Clone, Serialize)]
+pub struct NotificationPreferences {
+    pub employee_id: Uuid,
+    pub approval_reminders_opt_out: bool,
+    pub digest_frequency: DigestFrequency,
+    /// Personal Slack or Teams incoming webhook; chat notifications go to the
+    /// deployment's `chat.webhook_url` when unset.
+    pub chat_webhook_url: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Payload accepted by `PUT /notifications/preferences`.
+#[derive(Debug, Deserialize)]
+pub struct UpdatePreferencesRequest {
+    pub approval_reminders_opt_out: bool,
+    /// Omit to go back to the weekly default.
+    #[serde(default)]
+    pub digest_frequency: DigestFrequency,
+    /// Must be an HTTPS URL; omit or send `null` to clear it.
+    #[serde(default)]
+    pub chat_webhook_url: Option<String>,
+}
+
+/// Service coordinating the notification queue and preference lookups.
+pub struct NotificationService {
+    state: Arc<AppState>,
+}
+
+impl NotificationService {
+    /// Constructs the service from shared application state.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// Returns the caller's preferences, falling back to defaults when no row
+    /// has been stored yet.
+    pub async fn preferences(
+        &self,
+        actor: &AuthenticatedUser,
+    ) -> Result<NotificationPreferences, ServiceError> {
+        let stored = sqlx::query(
+            "SELECT employee_id, approval_reminders_opt_out, digest_frequency, chat_webhook_url,
+                    updated_at
+             FROM notification_preferences
+             WHERE employee_id = $1",
+        )
+        .bind(actor.employee_id)
+        .map(|row: PgRow| map_preferences(row))
+        .fetch_optional(&self.state.pool)
+        .await
+        .map_err(|err| ServiceError::Internal(err.to_string()))?;
+
+        Ok(stored.unwrap_or(NotificationPreferences {
+            employee_id: actor.employee_id,
+            approval_reminders_opt_out: false,
+            digest_frequency: DigestFrequency::default(),
+            chat_webhook_url: None,
+            updated_at: None,
+        }))
+    }
+
+    /// Upserts the caller's preferences. Rejects a chat webhook that is not an
+    /// HTTPS URL.
+    pub async fn update_preferences(
+        &self,
+        actor: &AuthenticatedUser,
+        payload: UpdatePreferencesRequest,
+    ) -> Result<NotificationPreferences, ServiceError> {
+        let chat_webhook_url = payload
+            .chat_webhook_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty());
+        if let Some(url) = chat_webhook_url {
+            validate_webhook_url(url).map_err(|_| {
+                ServiceError::Validation("chat_webhook_url must be an HTTPS URL".into())
+            })?;
+        }
+
+        sqlx::query(
+            "INSERT INTO notification_preferences
+                (employee_id, approval_reminders_opt_out, digest_frequency, chat_webhook_url,
+                 updated_at)
+             VALUES ($1,$2,$3,$4,$5)
+             ON CONFLICT (employee_id) DO UPDATE
+                SET approval_reminders_opt_out = EXCLUDED.approval_reminders_opt_out,
+                    digest_frequency = EXCLUDED.digest_frequency,
+                    chat_webhook_url = EXCLUDED.chat_webhook_url,
+                    updated_at = EXCLUDED.updated_at
+             RETURNING employee_id, approval_reminders_opt_out, digest_frequency,
+                       chat_webhook_url, updated_at",
+        )
+        .bind(actor.employee_id)
+        .bind(payload.approval_reminders_opt_out)
+        .bind(payload.digest_frequency.as_str())
+        .bind(chat_webhook_url)
+        .bind(Utc::now())
+        .map(|row: PgRow| map_preferences(row))
+        .fetch_one(&self.state.pool)
+        .await
+        .map_err(|err| ServiceError::Internal(err.to_string()))
+    }
+}
+
+/// Inserts a pending email notification for `recipient_id`.
+///
+/// Accepts any executor so callers can enqueue inside the transaction that
+/// produced the state change.
+pub async fn enqueue<'e, E>(
+    executor: E,
+    recipient_id: Uuid,
+    kind: &str,
+    payload: serde_json::Value,
+) -> Result<Uuid, ServiceError>
+where
+    E: PgExecutor<'e>,
+{
+    insert(executor, recipient_id, EMAIL_CHANNEL, kind, payload).await
+}
+
+/// Inserts a pending chat notification for `recipient_id`, like `enqueue`.
+pub async fn enqueue_chat<'e, E>(
+    executor: E,
+    recipient_id: Uuid,
+    kind: &str,
+    payload: serde_json::Value,
+) -> Result<Uuid, ServiceError>
+where
+    E: PgExecutor<'e>,
+{
+    insert(executor, recipient_id, CHAT_CHANNEL, kind, payload).await
+}
+
+async fn insert<'e, E>(
+    executor: E,
+    recipient_id: Uuid,
+    channel: &str,
+    kind: &str,
+    payload: serde_json::Value,
+) -> Result<Uuid, ServiceError>
+where
+    E: PgExecutor<'e>,
+{
+    let id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO notifications (id, recipient_id, channel, kind, payload, status, next_attempt_at, created_at)
+         VALUES ($1,$2,$3,$4,$5,'pending',$6,$6)",
+    )
+    .bind(id)
+    .bind(recipient_id)
+    .bind(channel)
+    .bind(kind)
+    .bind(payload)
+    .bind(Utc::now())
+    .execute(executor)
+    .await
+    .map_err(|err| ServiceError::Internal(err.to_string()))?;
+    Ok(id)
+}
+
+/// Emails every due `pending` notification, oldest first, up to
+/// `DELIVERY_LIMIT` per call.
+///
+/// A notification is marked `sent` once the provider accepts it, or `skipped`
+/// when its kind has no email template or its recipient has no email
+/// address. A failed send is retried with `outbox::retry_delay` and marked
+/// `failed` after `email.max_attempts`, as is a payload its template cannot
+/// read. Stops early once shutdown begins; the rest become due again when
+/// their claim lapses.
+pub async fn deliver_pending(
+    state: &AppState,
+    sender: &dyn EmailSender,
+) -> Result<DeliverySummary, ServiceError> {
+    let claimed = claim(state, EMAIL_CHANNEL).await?;
+
+    let mut summary = DeliverySummary::default();
+    for notification in claimed {
+        if state.shutdown.is_cancelled() {
+            break;
+        }
+        let to = notification
+            .email
+            .as_deref()
+            .map(str::trim)
+            .filter(|email| !email.is_empty());
+        let rendered = templates::render(
+            &notification.kind,
+            &notification.payload,
+            notification.locale.as_deref(),
+        );
+        let outcome = match (rendered, to) {
+            (Err(err), _) => Outcome::Failed(notification.retry_count, format!("{err:#}")),
+            (Ok(None), _) => Outcome::Skipped("no email template for this kind"),
+            (Ok(Some(_)), None) => Outcome::Skipped("recipient has no email address"),
+            (Ok(Some(rendered)), Some(to)) => {
+                let message = EmailMessage {
+                    to: to.to_string(),
+                    subject: rendered.subject,
+                    body: rendered.body,
+                };
+                match sender.send(&message).await {
+                    Ok(()) => Outcome::Sent,
+                    Err(err) => {
+                        let attempts = notification.retry_count.saturating_add(1);
+                        // The provider's error can quote the address, so it
+                        // is only stored, not logged.
+                        warn!(
+                            notification_id = %notification.id,
+                            kind = %notification.kind,
+                            attempts,
+                            "email delivery failed"
+                        );
+                        Outcome::after_failure(attempts, state.config.email.max_attempts, err)
+                    }
+                }
+            }
+        };
+        summary.count(&outcome);
+        record_outcome(state, notification.id, outcome).await?;
+    }
+    Ok(summary)
+}
+
+/// Posts every due `pending` chat notification, oldest first, up to
+/// `DELIVERY_LIMIT` per call.
+///
+/// A notification goes to its recipient's personal webhook, or else to the
+/// deployment's `chat.webhook_url` without its action links: those are
+/// single-use tokens for the recipient alone and must not land in a shared
+/// channel. It is `skipped` when its kind has no chat template or there is
+/// no webhook to post to; failures are retried as in `deliver_pending`, up to
+/// `chat.max_attempts`.
+pub async fn deliver_pending_chat(
+    state: &AppState,
+    webhook: &ChatWebhook,
+) -> Result<DeliverySummary, ServiceError> {
+    let claimed = claim(state, CHAT_CHANNEL).await?;
+
+    let mut summary = DeliverySummary::default();
+    for notification in claimed {
+        if state.shutdown.is_cancelled() {
+            break;
+        }
+        let personal = notification
+            .chat_webhook_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty());
+        let target = personal
+            .map(|url| (url, true))
+            .or_else(|| webhook.default_url().map(|url| (url, false)));
+        let rendered = templates::render_chat(
+            &notification.kind,
+            &notification.payload,
+            notification.locale.as_deref(),
+        );
+        let outcome = match (rendered, target) {
+            (Err(err), _) => Outcome::Failed(notification.retry_count, format!("{err:#}")),
+            (Ok(None), _) => Outcome::Skipped("no chat template for this kind"),
+            (Ok(Some(_)), None) => Outcome::Skipped("no chat webhook for this recipient"),
+            (Ok(Some(mut message)), Some((url, is_personal))) => {
+                if !is_personal {
+                    message.links.clear();
+                }
+                match webhook.post(url, &message).await {
+                    Ok(()) => Outcome::Sent,
+                    Err(err) => {
+                        let attempts = notification.retry_count.saturating_add(1);
+                        // Webhook URLs embed their secret, so the error is
+                        // only stored, not logged.
+                        warn!(
+                            notification_id = %notification.id,
+                            kind = %notification.kind,
+                            attempts,
+                            "chat delivery failed"
+                        );
+                        Outcome::after_failure(attempts, state.config.chat.max_attempts, err)
+                    }
+                }
+            }
+        };
+        summary.count(&outcome);
+        record_outcome(state, notification.id, outcome).await?;
+    }
+    Ok(summary)
+}
+
+/// Claims up to `DELIVERY_LIMIT` due notifications on `channel` for
+/// `CLAIM_LEASE_SECONDS`, with the recipient's delivery addresses and locale.
+async fn claim(state: &AppState, channel: &str) -> Result<Vec<PendingNotification>, ServiceError> {
+    let now = Utc::now();
+    sqlx::query(
+        "WITH claimed AS (
+             UPDATE notifications SET next_attempt_at = $1
+             WHERE id IN (
+                 SELECT id FROM notifications
+                 WHERE status = 'pending' AND channel = $2 AND next_attempt_at <= $3
+                 ORDER BY created_at
+                 LIMIT $4
+                 FOR UPDATE SKIP LOCKED
+             )
+             RETURNING id, recipient_id, kind, payload, retry_count, created_at
+         )
+         SELECT c.id, c.kind, c.payload, c.retry_count, e.email, e.locale, p.chat_webhook_url
+         FROM claimed c
+         JOIN employees e ON e.id = c.recipient_id
+         LEFT JOIN notification_preferences p ON p.employee_id = c.recipient_id
+         ORDER BY c.created_at",
+    )
+    .bind(now + Duration::seconds(CLAIM_LEASE_SECONDS))
+    .bind(channel)
+    .bind(now)
+    .bind(DELIVERY_LIMIT)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(internal)?
+    .into_iter()
+    .map(map_pending)
+    .collect()
+}
+
+struct PendingNotification {
+    id: Uuid,
+    kind: String,
+    payload: serde_json::Value,
+    retry_count: i32,
+    email: Option<String>,
+    locale: Option<String>,
+    chat_webhook_url: Option<String>,
+}
+
+impl DeliverySummary {
+    fn count(&mut self, outcome: &Outcome) {
+        match outcome {
+            Outcome::Sent => self.sent += 1,
+            Outcome::Skipped(_) => self.skipped += 1,
+            Outcome::Retry(..) | Outcome::Failed(..) => self.failed += 1,
+        }
+    }
+}
+
+enum Outcome {
+    Sent,
+    Skipped(&'static str),
+    /// Failed attempts so far and the last error; tried again later.
+    Retry(i32, String),
+    /// Given up, with the attempts made and the last error.
+    Failed(i32, String),
+}
+
+impl Outcome {
+    /// Retries a failed send until `max_attempts` have been made.
+    fn after_failure(attempts: i32, max_attempts: u32, err: anyhow::Error) -> Self {
+        if attempts.max(0) as u32 >= max_attempts {
+            Outcome::Failed(attempts, format!("{err:#}"))
+        } else {
+            Outcome::Retry(attempts, format!("{err:#}"))
+        }
+    }
+}
+
+async fn record_outcome(
+    state: &AppState,
+    notification_id: Uuid,
+    outcome: Outcome,
+) -> Result<(), ServiceError> {
+    let query = match outcome {
+        Outcome::Sent => sqlx::query(
+            "UPDATE notifications SET status = 'sent', sent_at = $2, last_error = NULL
+             WHERE id = $1",
+        )
+        .bind(notification_id)
+        .bind(Utc::now()),
+        Outcome::Skipped(reason) => sqlx::query(
+            "UPDATE notifications SET status = 'skipped', last_error = $2 WHERE id = $1",
+        )
+        .bind(notification_id)
+        .bind(reason),
+        Outcome::Retry(attempts, error) => sqlx::query(
+            "UPDATE notifications SET retry_count = $2, next_attempt_at = $3, last_error = $4
+             WHERE id = $1",
+        )
+        .bind(notification_id)
+        .bind(attempts)
+        .bind(Utc::now() + outbox::retry_delay(attempts))
+        .bind(error),
+        Outcome::Failed(attempts, error) => sqlx::query(
+            "UPDATE notifications SET status = 'failed', retry_count = $2, last_error = $3
+             WHERE id = $1",
+        )
+        .bind(notification_id)
+        .bind(attempts)
+        .bind(error),
+    };
+    query.execute(&state.pool).await.map_err(internal)?;
+    Ok(())
+}
+
+fn map_pending(row: PgRow) -> Result<PendingNotification, ServiceError> {
+    Ok(PendingNotification {
+        id: row.try_get("id").map_err(internal)?,
+        kind: row.try_get("kind").map_err(internal)?,
+        payload: row.try_get("payload").map_err(internal)?,
+        retry_count: row.try_get("retry_count").map_err(internal)?,
+        email: row.try_get("email").map_err(internal)?,
+        locale: row.try_get("locale").map_err(internal)?,
+        chat_webhook_url: row.try_get("chat_webhook_url").map_err(internal)?,
+    })
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
+
+fn map_preferences(row: PgRow) -> NotificationPreferences {
+    NotificationPreferences {
+        employee_id: row.get("employee_id"),
+        approval_reminders_opt_out: row.get("approval_reminders_opt_out"),
+        digest_frequency: DigestFrequency::parse(row.get("digest_frequency")).unwrap_or_default(),
+        chat_webhook_url: row.get("chat_webhook_url"),
+        updated_at: row.get("updated_at"),
+    }
+}
@@ -0,0 +1,448 @@
+//! Organizations and their employee rosters.
+//!
+//! Each subsidiary on the deployment is an organization. Employees, reports,
+//! and batches belong to exactly one, and every read and write is scoped to
+//! the signed-in employee's organization. Administrators manage their own
+//! organization's name and roster; administrators of the operating
+//! organization also create new organizations, each with a first
+//! administrator who can take it from there.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use serde::Deserialize;
+use sqlx::{PgConnection, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{Employee, Organization, Role},
+    infrastructure::{auth::AuthenticatedUser, state::AppState},
+};
+
+use super::{audit, departments, errors::ServiceError, management_chain};
+
+pub(super) const EMPLOYEE_COLUMNS: &str = "id, org_id, hr_identifier, manager_id, department, \
+     department_id, cost_center_id, role, created_at, deactivated_at";
+
+/// Payload accepted by `PUT /admin/organization`.
+#[derive(Debug, Deserialize)]
+pub struct RenameOrganizationRequest {
+    pub name: String,
+}
+
+/// Payload accepted by `POST /admin/organizations`.
+#[derive(Debug, Deserialize)]
+pub struct CreateOrganizationRequest {
+    pub name: String,
+    /// HR identifier of the new organization's first administrator.
+    pub admin_hr_identifier: String,
+}
+
+/// Payload accepted by `POST /admin/employees` and `PUT /admin/employees/:id`.
+#[derive(Debug, Deserialize)]
+pub struct EmployeeRequest {
+    pub hr_identifier: String,
+    pub manager_id: Option<Uuid>,
+    /// Code of an active department of the organization.
+    pub department: Option<String>,
+    #[serde(default)]
+    pub cost_center_id: Option<Uuid>,
+    pub role: Role,
+}
+
+/// Service managing `organizations` and the employees in each.
+pub struct OrganizationService {
+    state: Arc<AppState>,
+}
+
+impl OrganizationService {
+    /// Constructs the service from shared application state.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// The signed-in employee's organization.
+    pub async fn current(&self, actor: &AuthenticatedUser) -> Result<Organization, ServiceError> {
+        sqlx::query_as::<_, Organization>("SELECT * FROM organizations WHERE id = $1")
+            .bind(actor.org_id)
+            .fetch_optional(&self.state.pool)
+            .await
+            .map_err(internal)?
+            .ok_or(ServiceError::NotFound)
+    }
+
+    /// Renames the administrator's own organization. Names are unique across
+    /// the deployment.
+    pub async fn rename(
+        &self,
+        actor: &AuthenticatedUser,
+        payload: RenameOrganizationRequest,
+    ) -> Result<Organization, ServiceError> {
+        require_admin(actor)?;
+        let name = validate_name(&payload.name)?;
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let before = audit::ORGANIZATION
+            .snapshot(tx.as_mut(), actor.org_id)
+            .await?;
+        let organization = sqlx::query_as::<_, Organization>(
+            "UPDATE organizations SET name = $2 WHERE id = $1 RETURNING *",
+        )
+        .bind(actor.org_id)
+        .bind(name)
+        .fetch_optional(tx.as_mut())
+        .await
+        .map_err(conflict_or_internal)?
+        .ok_or(ServiceError::NotFound)?;
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::ORGANIZATION,
+            organization.id,
+            audit::UPDATED,
+            before,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        Ok(organization)
+    }
+
+    /// Every organization on the deployment. Restricted to administrators of
+    /// the operating organization.
+    pub async fn list(&self, actor: &AuthenticatedUser) -> Result<Vec<Organization>, ServiceError> {
+        require_operator(actor)?;
+        sqlx::query_as::<_, Organization>("SELECT * FROM organizations ORDER BY name")
+            .fetch_all(&self.state.pool)
+            .await
+            .map_err(internal)
+    }
+
+    /// Adds an organization together with its first administrator.
+    /// Restricted to administrators of the operating organization; a taken
+    /// name or HR identifier is a conflict.
+    pub async fn create(
+        &self,
+        actor: &AuthenticatedUser,
+        payload: CreateOrganizationRequest,
+    ) -> Result<Organization, ServiceError> {
+        require_operator(actor)?;
+        let name = validate_name(&payload.name)?;
+        let hr_identifier = validate_hr_identifier(&payload.admin_hr_identifier)?;
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let organization = sqlx::query_as::<_, Organization>(
+            "INSERT INTO organizations (id, name, created_at)
+             VALUES ($1,$2,$3)
+             RETURNING *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(name)
+        .bind(Utc::now())
+        .fetch_one(tx.as_mut())
+        .await
+        .map_err(conflict_or_internal)?;
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::ORGANIZATION,
+            organization.id,
+            audit::CREATED,
+            None,
+        )
+        .await?;
+        let admin = EmployeeRequest {
+            hr_identifier: hr_identifier.to_string(),
+            manager_id: None,
+            department: None,
+            cost_center_id: None,
+            role: Role::Admin,
+        };
+        insert_employee(&mut tx, actor, organization.id, &admin).await?;
+        tx.commit().await.map_err(internal)?;
+        management_chain::invalidate(&self.state);
+        Ok(organization)
+    }
+
+    /// The administrator's organization's employees, by HR identifier.
+    pub async fn employees(
+        &self,
+        actor: &AuthenticatedUser,
+    ) -> Result<Vec<Employee>, ServiceError> {
+        require_admin(actor)?;
+        sqlx::query_as::<_, Employee>(&format!(
+            "SELECT {EMPLOYEE_COLUMNS} FROM employees WHERE org_id = $1 ORDER BY hr_identifier"
+        ))
+        .bind(actor.org_id)
+        .fetch_all(&self.state.pool)
+        .await
+        .map_err(internal)
+    }
+
+    /// Adds an employee to the administrator's organization. HR identifiers
+    /// are unique across the deployment, ignoring case, since sign-in does
+    /// not name an organization; the manager must be in the same
+    /// organization.
+    pub async fn create_employee(
+        &self,
+        actor: &AuthenticatedUser,
+        payload: EmployeeRequest,
+    ) -> Result<Employee, ServiceError> {
+        require_admin(actor)?;
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        ensure_manager(tx.as_mut(), actor.org_id, None, payload.manager_id).await?;
+        let employee = insert_employee(&mut tx, actor, actor.org_id, &payload).await?;
+        tx.commit().await.map_err(internal)?;
+        management_chain::invalidate(&self.state);
+        Ok(employee)
+    }
+
+    /// Replaces an employee of the administrator's organization. Employees of
+    /// other organizations are reported as not found.
+    pub async fn update_employee(
+        &self,
+        actor: &AuthenticatedUser,
+        employee_id: Uuid,
+        payload: EmployeeRequest,
+    ) -> Result<Employee, ServiceError> {
+        require_admin(actor)?;
+        let hr_identifier = validate_hr_identifier(&payload.hr_identifier)?;
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let before = audit::EMPLOYEE.snapshot(tx.as_mut(), employee_id).await?;
+        ensure_manager(
+            tx.as_mut(),
+            actor.org_id,
+            Some(employee_id),
+            payload.manager_id,
+        )
+        .await?;
+        ensure_hr_identifier_free(tx.as_mut(), hr_identifier, Some(employee_id)).await?;
+        let (department, department_id) =
+            assignment(tx.as_mut(), actor.org_id, Some(employee_id), &payload).await?;
+        let employee = sqlx::query_as::<_, Employee>(&format!(
+            "UPDATE employees
+             SET hr_identifier = $3, manager_id = $4, department = $5, department_id = $6,
+                 cost_center_id = $7, role = $8
+             WHERE id = $1 AND org_id = $2
+             RETURNING {EMPLOYEE_COLUMNS}"
+        ))
+        .bind(employee_id)
+        .bind(actor.org_id)
+        .bind(hr_identifier)
+        .bind(payload.manager_id)
+        .bind(department)
+        .bind(department_id)
+        .bind(payload.cost_center_id)
+        .bind(payload.role)
+        .fetch_optional(tx.as_mut())
+        .await
+        .map_err(conflict_or_internal)?
+        .ok_or(ServiceError::NotFound)?;
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::EMPLOYEE,
+            employee_id,
+            audit::UPDATED,
+            before,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        management_chain::invalidate(&self.state);
+        Ok(employee)
+    }
+}
+
+async fn insert_employee(
+    tx: &mut Transaction<'_, Postgres>,
+    actor: &AuthenticatedUser,
+    org_id: Uuid,
+    payload: &EmployeeRequest,
+) -> Result<Employee, ServiceError> {
+    let hr_identifier = validate_hr_identifier(&payload.hr_identifier)?;
+    ensure_hr_identifier_free(tx.as_mut(), hr_identifier, None).await?;
+    let (department, department_id) = assignment(tx.as_mut(), org_id, None, payload).await?;
+    let employee = sqlx::query_as::<_, Employee>(&format!(
+        "INSERT INTO employees
+             (id, org_id, hr_identifier, manager_id, department, department_id, cost_center_id,
+              role, created_at)
+         VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9)
+         RETURNING {EMPLOYEE_COLUMNS}"
+    ))
+    .bind(Uuid::new_v4())
+    .bind(org_id)
+    .bind(hr_identifier)
+    .bind(payload.manager_id)
+    .bind(department)
+    .bind(department_id)
+    .bind(payload.cost_center_id)
+    .bind(payload.role)
+    .bind(Utc::now())
+    .fetch_one(tx.as_mut())
+    .await
+    .map_err(conflict_or_internal)?;
+    audit::record_change(
+        tx.as_mut(),
+        Some(actor.employee_id),
+        audit::EMPLOYEE,
+        employee.id,
+        audit::CREATED,
+        None,
+    )
+    .await?;
+    Ok(employee)
+}
+
+/// The department code and ID `payload` assigns, after checking the
+/// department and cost center are active ones of `org_id`. An employee
+/// being updated may keep an inactive department or cost center.
+async fn assignment(
+    conn: &mut PgConnection,
+    org_id: Uuid,
+    employee_id: Option<Uuid>,
+    payload: &EmployeeRequest,
+) -> Result<(Option<String>, Option<Uuid>), ServiceError> {
+    let (current_department, current_cost_center): (Option<Uuid>, Option<Uuid>) = match employee_id
+    {
+        Some(employee_id) => sqlx::query_as(
+            "SELECT department_id, cost_center_id FROM employees WHERE id = $1 AND org_id = $2",
+        )
+        .bind(employee_id)
+        .bind(org_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(internal)?
+        .ok_or(ServiceError::NotFound)?,
+        None => (None, None),
+    };
+    if let Some(cost_center_id) = payload.cost_center_id {
+        if current_cost_center != Some(cost_center_id) {
+            departments::ensure_cost_centers(&mut *conn, org_id, &[cost_center_id]).await?;
+        }
+    }
+    match normalize_department(payload.department.as_deref()) {
+        Some(code) => {
+            let department =
+                departments::resolve_department(conn, org_id, code, current_department).await?;
+            Ok((Some(department.code), Some(department.id)))
+        }
+        None => Ok((None, None)),
+    }
+}
+
+/// Sign-in matches HR identifiers case-insensitively, so two that differ
+/// only in case would be the same login.
+async fn ensure_hr_identifier_free(
+    conn: &mut PgConnection,
+    hr_identifier: &str,
+    except: Option<Uuid>,
+) -> Result<(), ServiceError> {
+    let taken: bool = sqlx::query_scalar(
+        "SELECT EXISTS (
+             SELECT 1 FROM employees
+             WHERE UPPER(hr_identifier) = UPPER($1) AND ($2::uuid IS NULL OR id <> $2)
+         )",
+    )
+    .bind(hr_identifier)
+    .bind(except)
+    .fetch_one(conn)
+    .await
+    .map_err(internal)?;
+    if taken {
+        return Err(ServiceError::Conflict);
+    }
+    Ok(())
+}
+
+/// A manager must be another active employee of the same organization.
+async fn ensure_manager(
+    conn: &mut PgConnection,
+    org_id: Uuid,
+    employee_id: Option<Uuid>,
+    manager_id: Option<Uuid>,
+) -> Result<(), ServiceError> {
+    let Some(manager_id) = manager_id else {
+        return Ok(());
+    };
+    if Some(manager_id) == employee_id {
+        return Err(ServiceError::Validation(
+            "an employee cannot manage themselves".into(),
+        ));
+    }
+    let exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS (
+             SELECT 1 FROM employees WHERE id = $1 AND org_id = $2 AND deactivated_at IS NULL
+         )",
+    )
+    .bind(manager_id)
+    .bind(org_id)
+    .fetch_one(conn)
+    .await
+    .map_err(internal)?;
+    if !exists {
+        return Err(ServiceError::Validation("manager not found".into()));
+    }
+    Ok(())
+}
+
+/// Returns the trimmed name.
+fn validate_name(name: &str) -> Result<&str, ServiceError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ServiceError::Validation("name is required".into()));
+    }
+    Ok(name)
+}
+
+/// Returns the trimmed HR identifier.
+fn validate_hr_identifier(hr_identifier: &str) -> Result<&str, ServiceError> {
+    let hr_identifier = hr_identifier.trim();
+    if hr_identifier.is_empty() {
+        return Err(ServiceError::Validation("hr_identifier is required".into()));
+    }
+    Ok(hr_identifier)
+}
+
+pub(super) fn normalize_department(department: Option<&str>) -> Option<&str> {
+    department.map(str::trim).filter(|value| !value.is_empty())
+}
+
+fn require_admin(actor: &AuthenticatedUser) -> Result<(), ServiceError> {
+    if actor.role != Role::Admin {
+        return Err(ServiceError::Forbidden);
+    }
+    Ok(())
+}
+
+fn require_operator(actor: &AuthenticatedUser) -> Result<(), ServiceError> {
+    if actor.role != Role::Admin || !actor.is_operator() {
+        return Err(ServiceError::Forbidden);
+    }
+    Ok(())
+}
+
+fn conflict_or_internal(err: sqlx::Error) -> ServiceError {
+    match err {
+        sqlx::Error::Database(db) if db.code().as_deref() == Some("23505") => {
+            ServiceError::Conflict
+        }
+        other => internal(other),
+    }
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_and_departments_are_trimmed() {
+        assert_eq!(validate_name("  Freight West ").unwrap(), "Freight West");
+        assert!(matches!(
+            validate_name("   "),
+            Err(ServiceError::Validation(_))
+        ));
+        assert_eq!(normalize_department(Some(" Ops ")), Some("Ops"));
+        assert_eq!(normalize_department(Some("  ")), None);
+    }
+}
@@ -0,0 +1,211 @@
+//! Transactional outbox for side effects that leave the database.
+//!
+//! A state change that needs an external call, such as the NetSuite export of
+//! a finalized batch, writes an `outbox_events` row in the same transaction
+//! instead of making the call while the transaction is open. Once the
+//! transaction commits the caller hands the event to `deliver` straight away;
+//! if the process dies first, `jobs::outbox` finds the event on its next
+//! drain. Delivery is at least once, so every topic handler must tolerate
+//! running twice, and enqueuing an event whose `dedup_key` already exists is a
+//! no-op.
+//!
+//! Notifications need no outbox of their own: `notifications::enqueue` already
+//! writes to the `notifications` queue inside the caller's transaction.
+
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use sqlx::{PgExecutor, Row};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::infrastructure::state::AppState;
+
+use super::{errors::ServiceError, finance};
+
+/// Topic exporting a `pending` NetSuite batch; the payload carries `batch_id`.
+pub const NETSUITE_EXPORT_TOPIC: &str = "netsuite.export_batch";
+
+/// Events claimed by one drain.
+const DRAIN_LIMIT: i64 = 100;
+
+/// How long a claimed event is hidden from other deliveries. A delivery that
+/// crashes mid-way becomes due again once the lease runs out.
+const CLAIM_LEASE_SECONDS: i64 = 300;
+
+/// First retry delay; each later failure doubles it up to `MAX_RETRY_DELAY_SECONDS`.
+const BASE_RETRY_DELAY_SECONDS: i64 = 30;
+const MAX_RETRY_DELAY_SECONDS: i64 = 3_600;
+
+/// Outcome of one drain.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DrainSummary {
+    pub delivered: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchExport {
+    batch_id: Uuid,
+}
+
+/// Records an event to deliver once the surrounding transaction commits.
+///
+/// Returns the new event's id, or `None` when an event with `dedup_key` was
+/// already queued.
+pub async fn enqueue<'e, E>(
+    executor: E,
+    topic: &str,
+    dedup_key: &str,
+    payload: serde_json::Value,
+) -> Result<Option<Uuid>, ServiceError>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query_scalar(
+        "INSERT INTO outbox_events (id, topic, dedup_key, payload, status, next_attempt_at, created_at)
+         VALUES ($1,$2,$3,$4,'pending',$5,$5)
+         ON CONFLICT (dedup_key) DO NOTHING
+         RETURNING id",
+    )
+    .bind(Uuid::new_v4())
+    .bind(topic)
+    .bind(dedup_key)
+    .bind(payload)
+    .bind(Utc::now())
+    .fetch_optional(executor)
+    .await
+    .map_err(internal)
+}
+
+/// Delivers every due event, oldest first, up to `DRAIN_LIMIT` per call.
+/// Stops early once shutdown begins; the rest stay due for the next drain.
+pub async fn drain(state: &AppState) -> Result<DrainSummary, ServiceError> {
+    let due: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT id FROM outbox_events
+         WHERE status = 'pending' AND next_attempt_at <= $1
+         ORDER BY created_at
+         LIMIT $2",
+    )
+    .bind(Utc::now())
+    .bind(DRAIN_LIMIT)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(internal)?;
+
+    let mut summary = DrainSummary::default();
+    for event_id in due {
+        if state.shutdown.is_cancelled() {
+            break;
+        }
+        match deliver(state, event_id).await {
+            Ok(true) => summary.delivered += 1,
+            Ok(false) => {}
+            Err(_) => summary.failed += 1,
+        }
+    }
+    Ok(summary)
+}
+
+/// Claims a due event and runs its topic handler.
+///
+/// Returns `Ok(false)` when the event is not due, was already delivered, or is
+/// being delivered elsewhere. A failed handler schedules the next attempt with
+/// `retry_delay`, or marks the event `dead` after `outbox.max_attempts`, and
+/// returns the handler's error.
+pub async fn deliver(state: &AppState, event_id: Uuid) -> Result<bool, ServiceError> {
+    let now = Utc::now();
+    let claimed = sqlx::query(
+        "UPDATE outbox_events
+         SET attempts = attempts + 1, next_attempt_at = $2
+         WHERE id = $1 AND status = 'pending' AND next_attempt_at <= $3
+         RETURNING topic, payload, attempts",
+    )
+    .bind(event_id)
+    .bind(now + Duration::seconds(CLAIM_LEASE_SECONDS))
+    .bind(now)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(internal)?;
+    let Some(row) = claimed else {
+        return Ok(false);
+    };
+    let topic: String = row.try_get("topic").map_err(internal)?;
+    let payload: serde_json::Value = row.try_get("payload").map_err(internal)?;
+    let attempts: i32 = row.try_get("attempts").map_err(internal)?;
+
+    match dispatch(state, &topic, payload).await {
+        Ok(()) => {
+            sqlx::query(
+                "UPDATE outbox_events
+                 SET status = 'delivered', delivered_at = $2, last_error = NULL
+                 WHERE id = $1",
+            )
+            .bind(event_id)
+            .bind(Utc::now())
+            .execute(&state.pool)
+            .await
+            .map_err(internal)?;
+            Ok(true)
+        }
+        Err(err) => {
+            let exhausted = attempts.max(0) as u32 >= state.config.outbox.max_attempts;
+            warn!(%event_id, %topic, attempts, exhausted, error = %err, "outbox delivery failed");
+            sqlx::query(
+                "UPDATE outbox_events
+                 SET status = $2, next_attempt_at = $3, last_error = $4
+                 WHERE id = $1",
+            )
+            .bind(event_id)
+            .bind(if exhausted { "dead" } else { "pending" })
+            .bind(Utc::now() + retry_delay(attempts))
+            .bind(err.to_string())
+            .execute(&state.pool)
+            .await
+            .map_err(internal)?;
+            Err(err)
+        }
+    }
+}
+
+async fn dispatch(
+    state: &AppState,
+    topic: &str,
+    payload: serde_json::Value,
+) -> Result<(), ServiceError> {
+    match topic {
+        NETSUITE_EXPORT_TOPIC => {
+            let BatchExport { batch_id } = serde_json::from_value(payload)
+                .map_err(|err| ServiceError::Internal(format!("invalid {topic} payload: {err}")))?;
+            finance::export_pending_batch(state, batch_id).await
+        }
+        other => Err(ServiceError::Internal(format!(
+            "no handler for outbox topic {other}"
+        ))),
+    }
+}
+
+/// Delay before retrying an event that has failed `attempts` times. Also
+/// paces email notification retries.
+pub(crate) fn retry_delay(attempts: i32) -> Duration {
+    let doublings = attempts.clamp(1, 16) - 1;
+    Duration::seconds((BASE_RETRY_DELAY_SECONDS << doublings).min(MAX_RETRY_DELAY_SECONDS))
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_doubles_up_to_an_hour() {
+        assert_eq!(retry_delay(1), Duration::seconds(30));
+        assert_eq!(retry_delay(2), Duration::seconds(60));
+        assert_eq!(retry_delay(4), Duration::seconds(240));
+        assert_eq!(retry_delay(7), Duration::seconds(1_920));
+        assert_eq!(retry_delay(8), Duration::hours(1));
+        assert_eq!(retry_delay(40), Duration::hours(1));
+    }
+}
@@ -0,0 +1,189 @@
+//! Cursor pagination shared by the list endpoints.
+//!
+//! Lists take `limit` and `cursor` query parameters and answer with a
+//! [`Paginated`] envelope. Cursors are opaque to clients: they encode the sort
+//! key and ID of the last row of a page, so the next page starts right after
+//! it even when rows are inserted or removed between requests.
+
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Query},
+    http::request::Parts,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD as BASE64, Engine};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{api::error::ApiError, services::errors::ServiceError};
+
+/// Rows per page when the caller does not pass `limit`, and the most it may
+/// ask for.
+pub const DEFAULT_LIMIT: u32 = 25;
+pub const MAX_LIMIT: u32 = 100;
+
+/// One page of a list plus the cursor of the next one.
+#[derive(Debug, Clone, Serialize)]
+pub struct Paginated<T> {
+    pub items: Vec<T>,
+    /// Pass as `cursor` to fetch the next page; absent on the last page.
+    pub next_cursor: Option<String>,
+    /// Rows matching the request's filters across all pages.
+    pub total: i64,
+}
+
+impl<T> Paginated<T> {
+    /// Builds a page from the rows a query fetched with
+    /// `PageRequest::fetch_limit`, using the extra row, if any, only to tell
+    /// that another page follows.
+    pub fn new(
+        mut items: Vec<T>,
+        request: &PageRequest,
+        total: i64,
+        cursor_of: impl Fn(&T) -> Cursor,
+    ) -> Self {
+        let limit = request.limit as usize;
+        let next_cursor = if items.len() > limit {
+            items.truncate(limit);
+            items.last().map(|item| cursor_of(item).encode())
+        } else {
+            None
+        };
+        Self {
+            items,
+            next_cursor,
+            total,
+        }
+    }
+}
+
+/// Position after the last row of a page: that row's sort timestamp and ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    pub at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl Cursor {
+    pub fn new(at: DateTime<Utc>, id: Uuid) -> Self {
+        Self { at, id }
+    }
+
+    pub fn encode(&self) -> String {
+        BASE64.encode(format!(
+            "{}|{}",
+            self.at.to_rfc3339_opts(SecondsFormat::Micros, true),
+            self.id
+        ))
+    }
+
+    pub fn decode(value: &str) -> Option<Self> {
+        let decoded = String::from_utf8(BASE64.decode(value).ok()?).ok()?;
+        let (at, id) = decoded.split_once('|')?;
+        Some(Self {
+            at: DateTime::parse_from_rfc3339(at).ok()?.with_timezone(&Utc),
+            id: id.parse().ok()?,
+        })
+    }
+}
+
+/// Validated `limit` and `cursor` query parameters. Extracting it rejects
+/// out-of-range limits and malformed cursors with `422`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageRequest {
+    pub limit: u32,
+    pub after: Option<Cursor>,
+}
+
+impl Default for PageRequest {
+    fn default() -> Self {
+        Self {
+            limit: DEFAULT_LIMIT,
+            after: None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PageParams {
+    limit: Option<u32>,
+    cursor: Option<String>,
+}
+
+impl PageRequest {
+    /// Rows to fetch: one more than the page holds, to detect a next page.
+    pub fn fetch_limit(&self) -> i64 {
+        i64::from(self.limit) + 1
+    }
+
+    fn from_params(params: PageParams) -> Result<Self, ServiceError> {
+        let limit = params.limit.unwrap_or(DEFAULT_LIMIT);
+        if !(1..=MAX_LIMIT).contains(&limit) {
+            return Err(ServiceError::Validation(format!(
+                "limit must be between 1 and {MAX_LIMIT}"
+            )));
+        }
+        let after = params
+            .cursor
+            .as_deref()
+            .filter(|cursor| !cursor.is_empty())
+            .map(|cursor| {
+                Cursor::decode(cursor)
+                    .ok_or_else(|| ServiceError::Validation("cursor is invalid".into()))
+            })
+            .transpose()?;
+        Ok(Self { limit, after })
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for PageRequest
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(params) = Query::<PageParams>::from_request_parts(parts, state)
+            .await
+            .map_err(|_| ServiceError::Validation("limit must be a positive integer".into()))?;
+        Ok(PageRequest::from_params(params)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursors_round_trip_and_bad_parameters_are_rejected() {
+        let cursor = Cursor::new(
+            "2024-04-30T18:32:15.123456Z".parse().unwrap(),
+            Uuid::new_v4(),
+        );
+        assert_eq!(Cursor::decode(&cursor.encode()), Some(cursor));
+        assert_eq!(Cursor::decode("not-a-cursor"), None);
+
+        let request = PageRequest::from_params(PageParams {
+            limit: Some(2),
+            cursor: Some(cursor.encode()),
+        })
+        .unwrap();
+        assert_eq!(request.after, Some(cursor));
+        assert_eq!(request.fetch_limit(), 3);
+
+        let page = Paginated::new(vec![1, 2, 3], &request, 7, |_| cursor);
+        assert_eq!(page.items, vec![1, 2]);
+        assert_eq!(page.next_cursor, Some(cursor.encode()));
+        let last = Paginated::new(vec![1], &request, 7, |_| cursor);
+        assert_eq!(last.next_cursor, None);
+
+        for limit in [0, MAX_LIMIT + 1] {
+            let params = PageParams {
+                limit: Some(limit),
+                cursor: None,
+            };
+            assert!(PageRequest::from_params(params).is_err());
+        }
+    }
+}
@@ -0,0 +1,435 @@
+//! Per-diem rate tables and the GSA CONUS importer.
+//!
+//! Finance and managers can list the rates `domain::policy` resolves meal
+//! limits from; administrators load a fiscal year's rates by posting the GSA
+//! per-diem rates file (CSV) to `/policy/per-diem-rates/import`. Re-importing a
+//! year replaces its GSA rows, so the latest file is always authoritative.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use serde::Serialize;
+use sqlx::{postgres::PgRow, PgPool, Row};
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{PerDiemRate, Role},
+    infrastructure::{auth::AuthenticatedUser, state::AppState},
+};
+
+use super::{audit, errors::ServiceError};
+
+/// Source recorded on rows loaded from the GSA CONUS file.
+pub const GSA_CONUS_SOURCE: &str = "gsa_conus";
+
+/// Result of `PerDiemService::import_gsa`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PerDiemImport {
+    pub fiscal_year: i32,
+    pub imported: usize,
+    pub replaced: u64,
+}
+
+/// One data row of a GSA per-diem rates file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GsaRate {
+    pub state: Option<String>,
+    /// `None` for the state's (or CONUS) standard rate.
+    pub destination: Option<String>,
+    pub county: Option<String>,
+    pub season_begin: Option<i16>,
+    pub season_end: Option<i16>,
+    pub meals_cents: i64,
+    pub lodging_cents: Option<i64>,
+}
+
+/// Service managing the `per_diem_rates` table.
+pub struct PerDiemService {
+    state: Arc<AppState>,
+}
+
+impl PerDiemService {
+    /// Constructs the service from shared application state.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// Lists rates, optionally for one fiscal year, by location.
+    pub async fn list(
+        &self,
+        actor: &AuthenticatedUser,
+        fiscal_year: Option<i32>,
+    ) -> Result<Vec<PerDiemRate>, ServiceError> {
+        if actor.role == Role::Employee {
+            return Err(ServiceError::Forbidden);
+        }
+        sqlx::query(
+            "SELECT * FROM per_diem_rates
+             WHERE $1::INTEGER IS NULL OR fiscal_year = $1
+             ORDER BY fiscal_year DESC, country, state NULLS FIRST, city NULLS FIRST,
+                      season_begin NULLS FIRST",
+        )
+        .bind(fiscal_year)
+        .fetch_all(&self.state.pool)
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .map(map_rate)
+        .collect()
+    }
+
+    /// Replaces `fiscal_year`'s GSA rates with the rows of `csv`. Restricted
+    /// to administrators; nothing is changed if any row is invalid.
+    pub async fn import_gsa(
+        &self,
+        actor: &AuthenticatedUser,
+        fiscal_year: i32,
+        csv: &str,
+    ) -> Result<PerDiemImport, ServiceError> {
+        if actor.role != Role::Admin || !actor.is_operator() {
+            return Err(ServiceError::Forbidden);
+        }
+        if !(2000..=2100).contains(&fiscal_year) {
+            return Err(ServiceError::Validation(
+                "fiscal_year must be between 2000 and 2100".into(),
+            ));
+        }
+        let rates = parse_gsa_csv(csv)?;
+        if rates.is_empty() {
+            return Err(ServiceError::Validation(
+                "the file contains no rates".into(),
+            ));
+        }
+
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let replaced =
+            sqlx::query("DELETE FROM per_diem_rates WHERE fiscal_year = $1 AND source = $2")
+                .bind(fiscal_year)
+                .bind(GSA_CONUS_SOURCE)
+                .execute(&mut *tx)
+                .await
+                .map_err(internal)?
+                .rows_affected();
+        let imported_at = Utc::now();
+        for rate in &rates {
+            sqlx::query(
+                "INSERT INTO per_diem_rates
+                    (id, country, state, city, county, fiscal_year, season_begin, season_end,
+                     meals_cents, lodging_cents, source, imported_at)
+                 VALUES ($1, 'US', $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+            )
+            .bind(Uuid::new_v4())
+            .bind(&rate.state)
+            .bind(&rate.destination)
+            .bind(&rate.county)
+            .bind(fiscal_year)
+            .bind(rate.season_begin)
+            .bind(rate.season_end)
+            .bind(rate.meals_cents)
+            .bind(rate.lodging_cents)
+            .bind(GSA_CONUS_SOURCE)
+            .bind(imported_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(internal)?;
+        }
+        // Imports replace a whole fiscal year, so one entry summarizes them.
+        audit::record(
+            &mut tx,
+            Some(actor.employee_id),
+            "per_diem_import",
+            Uuid::nil(),
+            audit::CREATED,
+            None,
+            Some(serde_json::json!({
+                "fiscal_year": fiscal_year,
+                "source": GSA_CONUS_SOURCE,
+                "imported": rates.len(),
+                "replaced": replaced,
+            })),
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+
+        Ok(PerDiemImport {
+            fiscal_year,
+            imported: rates.len(),
+            replaced,
+        })
+    }
+}
+
+/// Loads every rate for `fiscal_years`, for `domain::policy::resolve_per_diem`.
+pub async fn applicable_rates(
+    pool: &PgPool,
+    fiscal_years: &[i32],
+) -> Result<Vec<PerDiemRate>, ServiceError> {
+    sqlx::query("SELECT * FROM per_diem_rates WHERE fiscal_year = ANY($1) ORDER BY id")
+        .bind(fiscal_years)
+        .fetch_all(pool)
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .map(map_rate)
+        .collect()
+}
+
+/// Parses the GSA per-diem rates file.
+///
+/// Title rows before the header (the row naming a `Destination` column) are
+/// skipped. Columns are found by name: `State`, `Destination`, `County`,
+/// `Season Begin`, `Season End`, and the ones containing `Lodging` and
+/// `M&IE`; only `Destination` and `M&IE` are required. Seasons may be month
+/// names (`October`) or `MM/DD` dates, and amounts whole or decimal dollars
+/// with an optional `$`. A `Standard Rate` destination is a standard rate
+/// for its state, or for CONUS when the state is blank.
+pub fn parse_gsa_csv(data: &str) -> Result<Vec<GsaRate>, ServiceError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(data.as_bytes());
+
+    let mut columns: Option<GsaColumns> = None;
+    let mut rates = Vec::new();
+    for record in reader.records() {
+        let record =
+            record.map_err(|err| ServiceError::Validation(format!("invalid CSV: {err}")))?;
+        let line = record.position().map_or(0, |position| position.line());
+        let Some(columns) = &columns else {
+            columns = GsaColumns::from_header(&record)?;
+            continue;
+        };
+        if record.iter().all(str::is_empty) {
+            continue;
+        }
+        rates.push(
+            columns
+                .parse(&record)
+                .map_err(|message| ServiceError::Validation(format!("line {line}: {message}")))?,
+        );
+    }
+    if columns.is_none() {
+        return Err(ServiceError::Validation(
+            "no header row with a Destination column was found".into(),
+        ));
+    }
+    Ok(rates)
+}
+
+struct GsaColumns {
+    state: Option<usize>,
+    destination: usize,
+    county: Option<usize>,
+    season_begin: Option<usize>,
+    season_end: Option<usize>,
+    lodging: Option<usize>,
+    meals: usize,
+}
+
+impl GsaColumns {
+    /// Returns `None` when `record` is not the header row.
+    fn from_header(record: &csv::StringRecord) -> Result<Option<Self>, ServiceError> {
+        let names: Vec<String> = record.iter().map(str::to_lowercase).collect();
+        let find = |matches: &dyn Fn(&str) -> bool| names.iter().position(|name| matches(name));
+        let Some(destination) = find(&|name| name == "destination") else {
+            return Ok(None);
+        };
+        let meals = find(&|name| name.contains("m&ie"))
+            .ok_or_else(|| ServiceError::Validation("the header row has no M&IE column".into()))?;
+        Ok(Some(Self {
+            state: find(&|name| name == "state"),
+            destination,
+            county: find(&|name| name.starts_with("county")),
+            season_begin: find(&|name| name == "season begin"),
+            season_end: find(&|name| name == "season end"),
+            lodging: find(&|name| name.contains("lodging")),
+            meals,
+        }))
+    }
+
+    fn parse(&self, record: &csv::StringRecord) -> Result<GsaRate, String> {
+        let field = |index: Option<usize>| {
+            index
+                .and_then(|index| record.get(index))
+                .filter(|value| !value.is_empty())
+        };
+        let destination = field(Some(self.destination)).ok_or("Destination is blank")?;
+        let destination =
+            (!destination.eq_ignore_ascii_case("standard rate")).then(|| destination.to_string());
+        let state = field(self.state).map(str::to_uppercase);
+        if destination.is_some() && state.is_none() {
+            return Err("State is blank".to_string());
+        }
+
+        let (season_begin, season_end) = match (field(self.season_begin), field(self.season_end)) {
+            (None, None) => (None, None),
+            (Some(begin), Some(end)) => (
+                Some(parse_season_day(begin, false)?),
+                Some(parse_season_day(end, true)?),
+            ),
+            _ => return Err("Season Begin and Season End must both be set".to_string()),
+        };
+        let meals_cents = parse_dollars(field(Some(self.meals)).ok_or("M&IE is blank")?)?;
+        let lodging_cents = field(self.lodging).map(parse_dollars).transpose()?;
+
+        Ok(GsaRate {
+            state,
+            destination,
+            county: field(self.county).map(str::to_string),
+            season_begin,
+            season_end,
+            meals_cents,
+            lodging_cents,
+        })
+    }
+}
+
+/// Parses a season boundary as `month * 100 + day`. A bare month starts on
+/// its first day, or ends on its last when `end` is set.
+fn parse_season_day(value: &str, end: bool) -> Result<i16, String> {
+    let invalid = || format!("invalid season date {value}");
+    let (month, day) = match value.split_once('/') {
+        Some((month, rest)) => {
+            let day = rest.split('/').next().unwrap_or_default();
+            (
+                month.parse::<i16>().map_err(|_| invalid())?,
+                day.parse::<i16>().map_err(|_| invalid())?,
+            )
+        }
+        None => {
+            let month = MONTHS
+                .iter()
+                .position(|name| {
+                    value.len() >= 3
+                        && name
+                            .get(..value.len())
+                            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(value))
+                })
+                .ok_or_else(invalid)? as i16
+                + 1;
+            (
+                month,
+                if end {
+                    MONTH_DAYS[month as usize - 1]
+                } else {
+                    1
+                },
+            )
+        }
+    };
+    if !(1..=12).contains(&month) || !(1..=MONTH_DAYS[month as usize - 1]).contains(&day) {
+        return Err(invalid());
+    }
+    Ok(month * 100 + day)
+}
+
+/// Parses a dollar amount such as `$157`, `157.5`, or `1,204.00` into cents.
+fn parse_dollars(value: &str) -> Result<i64, String> {
+    let invalid = || format!("invalid amount {value}");
+    let cleaned: String = value
+        .chars()
+        .filter(|c| !matches!(c, '$' | ',') && !c.is_whitespace())
+        .collect();
+    let (dollars, cents) = cleaned.split_once('.').unwrap_or((&cleaned, ""));
+    if dollars.is_empty() || cents.len() > 2 || !cents.chars().all(|c| c.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let dollars: i64 = dollars.parse().map_err(|_| invalid())?;
+    let cents: i64 = format!("{cents:0<2}").parse().map_err(|_| invalid())?;
+    if dollars < 0 {
+        return Err(invalid());
+    }
+    Ok(dollars * 100 + cents)
+}
+
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// Last day of each month; February allows the 29th so seasons ending in
+/// February cover leap days.
+const MONTH_DAYS: [i16; 12] = [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+
+fn map_rate(row: PgRow) -> Result<PerDiemRate, ServiceError> {
+    Ok(PerDiemRate {
+        id: row.try_get("id").map_err(internal)?,
+        country: row.try_get("country").map_err(internal)?,
+        state: row.try_get("state").map_err(internal)?,
+        city: row.try_get("city").map_err(internal)?,
+        county: row.try_get("county").map_err(internal)?,
+        fiscal_year: row.try_get("fiscal_year").map_err(internal)?,
+        season_begin: row.try_get("season_begin").map_err(internal)?,
+        season_end: row.try_get("season_end").map_err(internal)?,
+        meals_cents: row.try_get("meals_cents").map_err(internal)?,
+        lodging_cents: row.try_get("lodging_cents").map_err(internal)?,
+        source: row.try_get("source").map_err(internal)?,
+        imported_at: row.try_get("imported_at").map_err(internal)?,
+    })
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gsa_file_rows_become_rates() {
+        let csv = "\
+FY2024 Per Diem Rates for the Continental United States,,,,,,,
+ID,State,Destination,County,Season Begin,Season End,FY24 Lodging Rate,FY24 M&IE
+,,Standard Rate,,,,$107,$59
+1,IL,Chicago,Cook / Lake,October,December,$157,$79
+2,CO,Aspen,Pitkin,12/01,March,\"$1,204.00\",$92
+,,,,,,,
+";
+        let rates = parse_gsa_csv(csv).unwrap();
+
+        assert_eq!(rates.len(), 3);
+        assert_eq!(rates[0].destination, None);
+        assert_eq!(rates[0].state, None);
+        assert_eq!(rates[0].meals_cents, 5_900);
+        assert_eq!(
+            rates[1],
+            GsaRate {
+                state: Some("IL".to_string()),
+                destination: Some("Chicago".to_string()),
+                county: Some("Cook / Lake".to_string()),
+                season_begin: Some(1001),
+                season_end: Some(1231),
+                meals_cents: 7_900,
+                lodging_cents: Some(15_700),
+            }
+        );
+        assert_eq!(rates[2].season_begin, Some(1201));
+        assert_eq!(rates[2].season_end, Some(331));
+        assert_eq!(rates[2].lodging_cents, Some(120_400));
+    }
+
+    #[test]
+    fn invalid_gsa_rows_name_their_line() {
+        let csv = "State,Destination,M&IE\nIL,Chicago,seventy\n";
+        match parse_gsa_csv(csv) {
+            Err(ServiceError::Validation(message)) => {
+                assert_eq!(message, "line 2: invalid amount seventy")
+            }
+            other => panic!("expected a validation error, got {other:?}"),
+        }
+        assert!(parse_gsa_csv("Destination,Rate\nChicago,79\n").is_err());
+        assert!(parse_gsa_csv("no,header\n").is_err());
+    }
+}
@@ -0,0 +1,221 @@
+//! Accounting period maintenance and posting-date resolution.
+//!
+//! Finance opens and closes periods through `/finance/periods`. Finalization
+//! asks `resolve_posting_date` where a batch may post: dates outside every
+//! period, or inside an open one, post as-is; a closed period redirects the
+//! batch to the first open date after it. Dates no period covers count as
+//! open, so period control is opt-in.
+
+use std::sync::Arc;
+
+use chrono::{NaiveDate, Utc};
+use serde::Deserialize;
+use sqlx::{postgres::PgRow, PgConnection, Row};
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{AccountingPeriod, Role},
+    infrastructure::{auth::AuthenticatedUser, state::AppState},
+};
+
+use super::{audit, errors::ServiceError};
+
+pub const PERIOD_OPEN: &str = "open";
+pub const PERIOD_CLOSED: &str = "closed";
+
+/// Payload accepted by `POST /finance/periods`.
+#[derive(Debug, Deserialize)]
+pub struct CreatePeriodRequest {
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+}
+
+/// Service managing the `periods` table.
+pub struct PeriodService {
+    state: Arc<AppState>,
+}
+
+impl PeriodService {
+    /// Constructs the service from shared application state.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// Lists periods, most recent first.
+    pub async fn list(
+        &self,
+        actor: &AuthenticatedUser,
+    ) -> Result<Vec<AccountingPeriod>, ServiceError> {
+        require_finance(actor)?;
+        sqlx::query("SELECT * FROM periods ORDER BY period_start DESC")
+            .map(map_period)
+            .fetch_all(&self.state.pool)
+            .await
+            .map_err(|err| ServiceError::Internal(err.to_string()))
+    }
+
+    /// Adds an open period. Fails with `ServiceError::Conflict` when the range
+    /// overlaps an existing period.
+    pub async fn create(
+        &self,
+        actor: &AuthenticatedUser,
+        payload: CreatePeriodRequest,
+    ) -> Result<AccountingPeriod, ServiceError> {
+        require_finance(actor)?;
+        if payload.period_end < payload.period_start {
+            return Err(ServiceError::Validation(
+                "period_end must be on or after period_start".into(),
+            ));
+        }
+
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let period = sqlx::query(
+            "INSERT INTO periods (id, period_start, period_end, status, created_at)
+             VALUES ($1,$2,$3,$4,$5) RETURNING *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(payload.period_start)
+        .bind(payload.period_end)
+        .bind(PERIOD_OPEN)
+        .bind(Utc::now())
+        .map(map_period)
+        .fetch_one(tx.as_mut())
+        .await
+        .map_err(|err| match err {
+            sqlx::Error::Database(db) if db.code().as_deref() == Some("23P01") => {
+                ServiceError::Conflict
+            }
+            other => internal(other),
+        })?;
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::ACCOUNTING_PERIOD,
+            period.id,
+            audit::CREATED,
+            None,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        Ok(period)
+    }
+
+    /// Closes a period so later finalizations post into the next open one.
+    pub async fn close(
+        &self,
+        actor: &AuthenticatedUser,
+        period_id: Uuid,
+    ) -> Result<AccountingPeriod, ServiceError> {
+        self.set_status(actor, period_id, PERIOD_CLOSED).await
+    }
+
+    /// Reopens a closed period, e.g. to post a late correction.
+    pub async fn reopen(
+        &self,
+        actor: &AuthenticatedUser,
+        period_id: Uuid,
+    ) -> Result<AccountingPeriod, ServiceError> {
+        self.set_status(actor, period_id, PERIOD_OPEN).await
+    }
+
+    async fn set_status(
+        &self,
+        actor: &AuthenticatedUser,
+        period_id: Uuid,
+        status: &str,
+    ) -> Result<AccountingPeriod, ServiceError> {
+        require_finance(actor)?;
+        let closing = status == PERIOD_CLOSED;
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let before = audit::ACCOUNTING_PERIOD
+            .snapshot(tx.as_mut(), period_id)
+            .await?;
+        let period = sqlx::query(
+            "UPDATE periods
+             SET status = $2,
+                 closed_by = CASE WHEN $3 THEN $4 ELSE NULL END,
+                 closed_at = CASE WHEN $3 THEN $5 ELSE NULL END
+             WHERE id = $1
+             RETURNING *",
+        )
+        .bind(period_id)
+        .bind(status)
+        .bind(closing)
+        .bind(actor.employee_id)
+        .bind(Utc::now())
+        .map(map_period)
+        .fetch_optional(tx.as_mut())
+        .await
+        .map_err(internal)?
+        .ok_or(ServiceError::NotFound)?;
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::ACCOUNTING_PERIOD,
+            period_id,
+            audit::STATUS_CHANGED,
+            before,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        Ok(period)
+    }
+}
+
+/// Returns the date a batch finalized on `date` may post to: `date` itself
+/// unless a closed period covers it, otherwise the first day after the run of
+/// closed periods.
+///
+/// Takes share locks on the periods it inspects so none can be closed while
+/// the caller's transaction is still writing the batch.
+pub async fn resolve_posting_date(
+    conn: &mut PgConnection,
+    date: NaiveDate,
+) -> Result<NaiveDate, ServiceError> {
+    let mut candidate = date;
+    loop {
+        let covering: Option<(String, NaiveDate)> = sqlx::query(
+            "SELECT status, period_end FROM periods
+             WHERE $1 BETWEEN period_start AND period_end
+             FOR SHARE",
+        )
+        .bind(candidate)
+        .map(|row: PgRow| (row.get("status"), row.get("period_end")))
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|err| ServiceError::Internal(err.to_string()))?;
+        match covering {
+            Some((status, period_end)) if status == PERIOD_CLOSED => {
+                candidate = period_end.succ_opt().ok_or_else(|| {
+                    ServiceError::Validation("no open accounting period is available".into())
+                })?;
+            }
+            _ => return Ok(candidate),
+        }
+    }
+}
+
+/// Accounting periods are shared by every organization, so only finance
+/// users of the operating organization open and close them.
+fn require_finance(actor: &AuthenticatedUser) -> Result<(), ServiceError> {
+    if actor.role != Role::Finance || !actor.is_operator() {
+        return Err(ServiceError::Forbidden);
+    }
+    Ok(())
+}
+
+fn map_period(row: PgRow) -> AccountingPeriod {
+    AccountingPeriod {
+        id: row.get("id"),
+        period_start: row.get("period_start"),
+        period_end: row.get("period_end"),
+        status: row.get("status"),
+        closed_by: row.get("closed_by"),
+        closed_at: row.get("closed_at"),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
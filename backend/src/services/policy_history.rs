@@ -0,0 +1,108 @@
+//! Stored policy evaluation runs.
+//!
+//! Every draft evaluation that changes a report's findings, and every
+//! submission, is recorded in `policy_evaluation_runs`.
+//! `GET /expenses/reports/:id/policy/history` lists the runs with the findings
+//! each introduced or resolved, so reviewers can follow how edits changed the
+//! report's compliance.
+
+use chrono::{DateTime, Utc};
+use sqlx::{PgExecutor, PgPool, Row};
+use uuid::Uuid;
+
+use crate::domain::policy::{
+    diff_findings, EvaluationTrigger, PolicyEvaluation, PolicyEvaluationRun,
+};
+
+use super::errors::ServiceError;
+
+/// Backend version recorded with each run.
+pub const ENGINE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Records `evaluation` for `report_id`. Evaluations identical to the
+/// report's latest run are skipped unless they come from a submission.
+pub async fn record_run<'e>(
+    executor: impl PgExecutor<'e>,
+    report_id: Uuid,
+    trigger: EvaluationTrigger,
+    evaluation: &PolicyEvaluation,
+    evaluated_by: Uuid,
+) -> Result<(), ServiceError> {
+    let snapshot =
+        serde_json::to_value(evaluation).map_err(|err| ServiceError::Internal(err.to_string()))?;
+    sqlx::query(
+        "INSERT INTO policy_evaluation_runs
+            (report_id, trigger, policy_version, engine_version, is_valid, evaluation,
+             evaluated_by, evaluated_at)
+         SELECT $1,$2,$3,$4,$5,$6,$7,$8
+         WHERE $2 = 'submission' OR $6 IS DISTINCT FROM (
+             SELECT evaluation FROM policy_evaluation_runs
+             WHERE report_id = $1
+             ORDER BY id DESC
+             LIMIT 1
+         )",
+    )
+    .bind(report_id)
+    .bind(trigger.as_str())
+    .bind(evaluation.policy_version)
+    .bind(ENGINE_VERSION)
+    .bind(evaluation.is_valid)
+    .bind(snapshot)
+    .bind(evaluated_by)
+    .bind(Utc::now())
+    .execute(executor)
+    .await
+    .map_err(internal)?;
+    Ok(())
+}
+
+/// Lists `report_id`'s runs oldest first, each compared with the one before.
+pub async fn runs(
+    pool: &PgPool,
+    report_id: Uuid,
+) -> Result<Vec<PolicyEvaluationRun>, ServiceError> {
+    let rows = sqlx::query(
+        "SELECT id, trigger, engine_version, evaluation, evaluated_by, evaluated_at
+         FROM policy_evaluation_runs
+         WHERE report_id = $1
+         ORDER BY id",
+    )
+    .bind(report_id)
+    .fetch_all(pool)
+    .await
+    .map_err(internal)?;
+
+    let mut runs: Vec<PolicyEvaluationRun> = Vec::with_capacity(rows.len());
+    for row in rows {
+        let trigger: String = row.try_get("trigger").map_err(internal)?;
+        let evaluation: serde_json::Value = row.try_get("evaluation").map_err(internal)?;
+        let evaluation: PolicyEvaluation = serde_json::from_value(evaluation).map_err(|err| {
+            ServiceError::Internal(format!("policy_evaluation_runs.evaluation: {err}"))
+        })?;
+        let previous = runs
+            .last()
+            .map(|run| run.evaluation.findings.as_slice())
+            .unwrap_or_default();
+        let (introduced, resolved) = diff_findings(previous, &evaluation.findings);
+        let evaluated_at: DateTime<Utc> = row.try_get("evaluated_at").map_err(internal)?;
+        runs.push(PolicyEvaluationRun {
+            id: row.try_get("id").map_err(internal)?,
+            trigger: EvaluationTrigger::parse(&trigger).ok_or_else(|| {
+                ServiceError::Internal(format!(
+                    "policy_evaluation_runs.trigger has unknown value {trigger}"
+                ))
+            })?,
+            engine_version: row.try_get("engine_version").map_err(internal)?,
+            evaluated_by: row.try_get("evaluated_by").map_err(internal)?,
+            evaluated_at,
+            evaluation,
+            introduced,
+            resolved,
+        });
+    }
+    Ok(runs)
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
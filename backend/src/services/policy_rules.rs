@@ -0,0 +1,462 @@
+//! Policy rule maintenance and loading.
+//!
+//! Administrators manage the rules `domain::policy` evaluates through
+//! `/policy/rules`; finance and managers can read them to explain a flagged
+//! report. Rules belong to the administrator's organization, except shared
+//! rules, which apply to every organization and only administrators of the
+//! operating organization maintain. `current_rules` serves the enabled rules
+//! relevant to a report's organization and categories for evaluation from the
+//! cache in `AppState`, which every edit here invalidates.
+
+use std::sync::Arc;
+
+use chrono::{NaiveDate, Utc};
+use serde::Deserialize;
+use sqlx::{postgres::PgRow, PgPool, Postgres, Row, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{
+        ExpenseCategory, PolicyRule, Role, RuleComparison, RuleCondition, RuleScope, RuleSeverity,
+    },
+    infrastructure::{
+        auth::AuthenticatedUser, policy_cache::PolicySnapshot, reference_cache::ReferenceData,
+        state::AppState,
+    },
+};
+
+use super::{audit, errors::ServiceError, policy_versions};
+
+/// Payload accepted by `POST /policy/rules` and `PUT /policy/rules/:id`.
+#[derive(Debug, Deserialize)]
+pub struct PolicyRuleRequest {
+    pub name: String,
+    #[serde(default)]
+    pub category: Option<ExpenseCategory>,
+    pub comparison: RuleComparison,
+    pub threshold_cents: i64,
+    pub scope: RuleScope,
+    pub severity: RuleSeverity,
+    /// Defaults to `always`.
+    #[serde(default)]
+    pub condition: RuleCondition,
+    /// Compare each item's cost per attendee; item scope only.
+    #[serde(default)]
+    pub per_attendee: bool,
+    /// Required for `late_booking` rules and rejected otherwise.
+    #[serde(default)]
+    pub advance_days: Option<i32>,
+    pub message: String,
+    /// Defaults to today.
+    #[serde(default)]
+    pub active_from: Option<NaiveDate>,
+    #[serde(default)]
+    pub active_to: Option<NaiveDate>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Apply the rule to every organization instead of the administrator's
+    /// own.
+    #[serde(default)]
+    pub shared: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Service managing the `policy_rules` table.
+pub struct PolicyRuleService {
+    state: Arc<AppState>,
+}
+
+impl PolicyRuleService {
+    /// Constructs the service from shared application state.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// Lists the rules applying to the actor's organization, including
+    /// disabled ones, by category then name.
+    pub async fn list(&self, actor: &AuthenticatedUser) -> Result<Vec<PolicyRule>, ServiceError> {
+        if actor.role == Role::Employee {
+            return Err(ServiceError::Forbidden);
+        }
+        sqlx::query(
+            "SELECT * FROM policy_rules
+             WHERE org_id IS NULL OR org_id = $1
+             ORDER BY category NULLS FIRST, name, id",
+        )
+        .bind(actor.org_id)
+        .fetch_all(&self.state.pool)
+        .await
+        .map_err(|err| ServiceError::Internal(err.to_string()))?
+        .into_iter()
+        .map(map_rule)
+        .collect()
+    }
+
+    /// Adds a rule. Restricted to administrators.
+    pub async fn create(
+        &self,
+        actor: &AuthenticatedUser,
+        payload: PolicyRuleRequest,
+    ) -> Result<PolicyRule, ServiceError> {
+        require_admin(actor)?;
+        let org_id = rule_org(actor, &payload)?;
+        let (name, message, active_from) = validate(&payload)?;
+        let mut tx = self
+            .state
+            .pool
+            .begin()
+            .await
+            .map_err(|err| ServiceError::Internal(err.to_string()))?;
+        let row = sqlx::query(
+            "INSERT INTO policy_rules
+                (id, name, category, comparison, threshold_cents, scope, severity, message,
+                 active_from, active_to, enabled, updated_by, updated_at, condition, per_attendee,
+                 advance_days, org_id)
+             VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,$13,$14,$15,$16,$17)
+             RETURNING *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(name)
+        .bind(payload.category.map(|category| category.as_str()))
+        .bind(payload.comparison.as_str())
+        .bind(payload.threshold_cents)
+        .bind(payload.scope.as_str())
+        .bind(payload.severity.as_str())
+        .bind(message)
+        .bind(active_from)
+        .bind(payload.active_to)
+        .bind(payload.enabled)
+        .bind(actor.employee_id)
+        .bind(Utc::now())
+        .bind(payload.condition.as_str())
+        .bind(payload.per_attendee)
+        .bind(payload.advance_days)
+        .bind(org_id)
+        .fetch_one(tx.as_mut())
+        .await
+        .map_err(|err| ServiceError::Internal(err.to_string()))?;
+        let rule = map_rule(row)?;
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::POLICY_RULE,
+            rule.id,
+            audit::CREATED,
+            None,
+        )
+        .await?;
+        tx.commit()
+            .await
+            .map_err(|err| ServiceError::Internal(err.to_string()))?;
+        self.state.policy_cache.invalidate();
+        self.state
+            .reference_cache
+            .invalidate(ReferenceData::PolicyRules);
+        Ok(rule)
+    }
+
+    /// Replaces a rule. Restricted to administrators of the rule's
+    /// organization; shared rules to those of the operating organization.
+    pub async fn update(
+        &self,
+        actor: &AuthenticatedUser,
+        rule_id: Uuid,
+        payload: PolicyRuleRequest,
+    ) -> Result<PolicyRule, ServiceError> {
+        require_admin(actor)?;
+        let org_id = rule_org(actor, &payload)?;
+        let (name, message, active_from) = validate(&payload)?;
+        let mut tx = self
+            .state
+            .pool
+            .begin()
+            .await
+            .map_err(|err| ServiceError::Internal(err.to_string()))?;
+        ensure_editable(&mut tx, actor, rule_id).await?;
+        let before = audit::POLICY_RULE.snapshot(tx.as_mut(), rule_id).await?;
+        let row = sqlx::query(
+            "UPDATE policy_rules
+             SET name = $2, category = $3, comparison = $4, threshold_cents = $5, scope = $6,
+                 severity = $7, message = $8, active_from = $9, active_to = $10, enabled = $11,
+                 updated_by = $12, updated_at = $13, condition = $14,
+                 per_attendee = $15, advance_days = $16, org_id = $17
+             WHERE id = $1
+             RETURNING *",
+        )
+        .bind(rule_id)
+        .bind(name)
+        .bind(payload.category.map(|category| category.as_str()))
+        .bind(payload.comparison.as_str())
+        .bind(payload.threshold_cents)
+        .bind(payload.scope.as_str())
+        .bind(payload.severity.as_str())
+        .bind(message)
+        .bind(active_from)
+        .bind(payload.active_to)
+        .bind(payload.enabled)
+        .bind(actor.employee_id)
+        .bind(Utc::now())
+        .bind(payload.condition.as_str())
+        .bind(payload.per_attendee)
+        .bind(payload.advance_days)
+        .bind(org_id)
+        .fetch_optional(tx.as_mut())
+        .await
+        .map_err(|err| ServiceError::Internal(err.to_string()))?
+        .ok_or(ServiceError::NotFound)?;
+        let rule = map_rule(row)?;
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::POLICY_RULE,
+            rule_id,
+            audit::UPDATED,
+            before,
+        )
+        .await?;
+        tx.commit()
+            .await
+            .map_err(|err| ServiceError::Internal(err.to_string()))?;
+        self.state.policy_cache.invalidate();
+        self.state
+            .reference_cache
+            .invalidate(ReferenceData::PolicyRules);
+        Ok(rule)
+    }
+
+    /// Deletes a rule. Restricted to administrators, as for `update`;
+    /// disabling a rule keeps it for reference instead.
+    pub async fn delete(
+        &self,
+        actor: &AuthenticatedUser,
+        rule_id: Uuid,
+    ) -> Result<(), ServiceError> {
+        require_admin(actor)?;
+        let mut tx = self
+            .state
+            .pool
+            .begin()
+            .await
+            .map_err(|err| ServiceError::Internal(err.to_string()))?;
+        ensure_editable(&mut tx, actor, rule_id).await?;
+        let before = audit::POLICY_RULE.snapshot(tx.as_mut(), rule_id).await?;
+        if before.is_none() {
+            return Err(ServiceError::NotFound);
+        }
+        sqlx::query("DELETE FROM policy_rules WHERE id = $1")
+            .bind(rule_id)
+            .execute(tx.as_mut())
+            .await
+            .map_err(|err| ServiceError::Internal(err.to_string()))?;
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::POLICY_RULE,
+            rule_id,
+            audit::DELETED,
+            before,
+        )
+        .await?;
+        tx.commit()
+            .await
+            .map_err(|err| ServiceError::Internal(err.to_string()))?;
+        self.state.policy_cache.invalidate();
+        self.state
+            .reference_cache
+            .invalidate(ReferenceData::PolicyRules);
+        Ok(())
+    }
+}
+
+/// The current policy version and its enabled rules that can apply to items
+/// of `org_id` in `categories`: the organization's and shared rules, either
+/// category-specific for those categories or without one. Served from
+/// `AppState::policy_cache` while it is fresh.
+pub async fn current_rules(
+    state: &AppState,
+    org_id: Uuid,
+    categories: &[ExpenseCategory],
+) -> Result<(Option<i64>, Vec<PolicyRule>), ServiceError> {
+    let snapshot = match state.policy_cache.get() {
+        Some(snapshot) => snapshot,
+        None => {
+            let generation = state.policy_cache.generation();
+            let snapshot = load_snapshot(&state.pool).await?;
+            state.policy_cache.store(generation, snapshot.clone());
+            snapshot
+        }
+    };
+    let rules = snapshot
+        .rules
+        .iter()
+        .filter(|rule| {
+            rule.applies_to(org_id)
+                && rule
+                    .category
+                    .is_none_or(|category| categories.contains(&category))
+        })
+        .cloned()
+        .collect();
+    Ok((snapshot.version, rules))
+}
+
+/// Reads every enabled rule and the current version from one database
+/// snapshot, so the version always matches the rules.
+async fn load_snapshot(pool: &PgPool) -> Result<PolicySnapshot, ServiceError> {
+    let internal = |err: sqlx::Error| ServiceError::Internal(err.to_string());
+    let mut tx = pool.begin().await.map_err(internal)?;
+    sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+        .execute(&mut *tx)
+        .await
+        .map_err(internal)?;
+    let version = policy_versions::current_version(&mut *tx).await?;
+    let rules = sqlx::query(
+        "SELECT * FROM policy_rules WHERE enabled ORDER BY category NULLS FIRST, name, id",
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(internal)?
+    .into_iter()
+    .map(map_rule)
+    .collect::<Result<Vec<_>, _>>()?;
+    tx.commit().await.map_err(internal)?;
+    Ok(PolicySnapshot {
+        version,
+        rules: Arc::new(rules),
+    })
+}
+
+/// Returns the trimmed name and message and the effective `active_from`.
+fn validate(payload: &PolicyRuleRequest) -> Result<(&str, &str, NaiveDate), ServiceError> {
+    let name = payload.name.trim();
+    let message = payload.message.trim();
+    if name.is_empty() || message.is_empty() {
+        return Err(ServiceError::Validation(
+            "name and message are required".into(),
+        ));
+    }
+    if payload.threshold_cents < 0 {
+        return Err(ServiceError::Validation(
+            "threshold_cents must not be negative".into(),
+        ));
+    }
+    if payload.per_attendee && payload.scope != RuleScope::Item {
+        return Err(ServiceError::Validation(
+            "per_attendee rules must use item scope".into(),
+        ));
+    }
+    match (payload.condition, payload.advance_days) {
+        (RuleCondition::LateBooking, None) => {
+            return Err(ServiceError::Validation(
+                "late_booking rules require advance_days".into(),
+            ));
+        }
+        (RuleCondition::LateBooking, Some(days)) if days <= 0 => {
+            return Err(ServiceError::Validation(
+                "advance_days must be greater than 0".into(),
+            ));
+        }
+        (RuleCondition::LateBooking, Some(_)) | (_, None) => {}
+        (_, Some(_)) => {
+            return Err(ServiceError::Validation(
+                "advance_days only applies to late_booking rules".into(),
+            ));
+        }
+    }
+    let active_from = payload
+        .active_from
+        .unwrap_or_else(|| Utc::now().date_naive());
+    if payload.active_to.is_some_and(|end| end < active_from) {
+        return Err(ServiceError::Validation(
+            "active_to must be on or after active_from".into(),
+        ));
+    }
+    Ok((name, message, active_from))
+}
+
+fn require_admin(actor: &AuthenticatedUser) -> Result<(), ServiceError> {
+    if actor.role != Role::Admin {
+        return Err(ServiceError::Forbidden);
+    }
+    Ok(())
+}
+
+/// The organization a rule written by `actor` belongs to: none for shared
+/// rules, which only the operating organization may write.
+fn rule_org(
+    actor: &AuthenticatedUser,
+    payload: &PolicyRuleRequest,
+) -> Result<Option<Uuid>, ServiceError> {
+    if !payload.shared {
+        return Ok(Some(actor.org_id));
+    }
+    if !actor.is_operator() {
+        return Err(ServiceError::Forbidden);
+    }
+    Ok(None)
+}
+
+/// Locks `rule_id` for an edit by `actor`: `NotFound` for rules of other
+/// organizations, `Forbidden` for shared rules outside the operating
+/// organization.
+async fn ensure_editable(
+    tx: &mut Transaction<'_, Postgres>,
+    actor: &AuthenticatedUser,
+    rule_id: Uuid,
+) -> Result<(), ServiceError> {
+    let org_id: Option<Uuid> =
+        sqlx::query_scalar("SELECT org_id FROM policy_rules WHERE id = $1 FOR UPDATE")
+            .bind(rule_id)
+            .fetch_optional(tx.as_mut())
+            .await
+            .map_err(|err| ServiceError::Internal(err.to_string()))?
+            .ok_or(ServiceError::NotFound)?;
+    match org_id {
+        Some(org_id) if org_id != actor.org_id => Err(ServiceError::NotFound),
+        None if !actor.is_operator() => Err(ServiceError::Forbidden),
+        _ => Ok(()),
+    }
+}
+
+fn map_rule(row: PgRow) -> Result<PolicyRule, ServiceError> {
+    let text = |column: &str| -> Result<String, ServiceError> {
+        row.try_get(column)
+            .map_err(|err| ServiceError::Internal(err.to_string()))
+    };
+    let invalid = |column: &str, value: &str| {
+        ServiceError::Internal(format!("policy_rules.{column} has unknown value {value}"))
+    };
+    let category = row
+        .try_get::<Option<String>, _>("category")
+        .map_err(|err| ServiceError::Internal(err.to_string()))?
+        .map(|value| ExpenseCategory::parse(&value).ok_or_else(|| invalid("category", &value)))
+        .transpose()?;
+    let comparison = text("comparison")?;
+    let scope = text("scope")?;
+    let severity = text("severity")?;
+    let condition = text("condition")?;
+    Ok(PolicyRule {
+        id: row.get("id"),
+        org_id: row.get("org_id"),
+        name: row.get("name"),
+        category,
+        comparison: RuleComparison::parse(&comparison)
+            .ok_or_else(|| invalid("comparison", &comparison))?,
+        threshold_cents: row.get("threshold_cents"),
+        scope: RuleScope::parse(&scope).ok_or_else(|| invalid("scope", &scope))?,
+        severity: RuleSeverity::parse(&severity).ok_or_else(|| invalid("severity", &severity))?,
+        condition: RuleCondition::parse(&condition)
+            .ok_or_else(|| invalid("condition", &condition))?,
+        per_attendee: row.get("per_attendee"),
+        advance_days: row.get("advance_days"),
+        message: row.get("message"),
+        active_from: row.get("active_from"),
+        active_to: row.get("active_to"),
+        enabled: row.get("enabled"),
+        updated_by: row.get("updated_by"),
+        updated_at: row.get("updated_at"),
+    })
+}
@@ -0,0 +1,202 @@
+//! Policy version history and submitted evaluations.
+//!
+//! A trigger on `policy_rules` snapshots every rule into `policy_versions`
+//! whenever the rules change, and `/policy/versions` lists that history. A
+//! report's evaluation is stored with the version it used when the report is
+//! submitted, so later rule changes do not alter how it was judged.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use sqlx::{postgres::PgRow, PgConnection, PgExecutor, PgPool, Row};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        models::{PolicyRule, PolicyVersion, Role},
+        policy::PolicyEvaluation,
+    },
+    infrastructure::{auth::AuthenticatedUser, state::AppState},
+};
+
+use super::errors::ServiceError;
+
+/// Service reading the `policy_versions` history.
+pub struct PolicyVersionService {
+    state: Arc<AppState>,
+}
+
+impl PolicyVersionService {
+    /// Constructs the service from shared application state.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// Lists every policy version, newest first, with the rules applying to
+    /// the actor's organization and the period it was in force. Versions are
+    /// shared, so edits by other organizations also start a version.
+    pub async fn list(
+        &self,
+        actor: &AuthenticatedUser,
+    ) -> Result<Vec<PolicyVersion>, ServiceError> {
+        if actor.role == Role::Employee {
+            return Err(ServiceError::Forbidden);
+        }
+        sqlx::query(
+            "SELECT id, effective_from, rules,
+                    LEAD(effective_from) OVER (ORDER BY id) AS effective_to
+             FROM policy_versions
+             ORDER BY id DESC",
+        )
+        .fetch_all(&self.state.pool)
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .map(|row| {
+            let mut version = map_version(row)?;
+            version.rules.retain(|rule| rule.applies_to(actor.org_id));
+            Ok(version)
+        })
+        .collect()
+    }
+}
+
+/// The version currently in force, if any has been recorded.
+pub async fn current_version<'e>(
+    executor: impl PgExecutor<'e>,
+) -> Result<Option<i64>, ServiceError> {
+    sqlx::query_scalar("SELECT MAX(id) FROM policy_versions")
+        .fetch_one(executor)
+        .await
+        .map_err(internal)
+}
+
+/// The version in force at `at` and its rules that apply to `org_id`. Times
+/// before the first recorded version resolve to that version, the oldest
+/// known.
+pub async fn version_at(
+    pool: &PgPool,
+    org_id: Uuid,
+    at: DateTime<Utc>,
+) -> Result<Option<(i64, Vec<PolicyRule>)>, ServiceError> {
+    let row = sqlx::query(
+        "SELECT id, rules FROM policy_versions
+         WHERE effective_from <= $1 OR id = (SELECT MIN(id) FROM policy_versions)
+         ORDER BY id DESC
+         LIMIT 1",
+    )
+    .bind(at)
+    .fetch_optional(pool)
+    .await
+    .map_err(internal)?;
+    row.map(|row| {
+        let version: i64 = row.try_get("id").map_err(internal)?;
+        let mut rules = parse_rules(row.try_get("rules").map_err(internal)?)?;
+        rules.retain(|rule| rule.applies_to(org_id));
+        Ok((version, rules))
+    })
+    .transpose()
+}
+
+/// Records the evaluation `report_id` was submitted with, replacing any from
+/// an earlier submission.
+pub async fn store_evaluation(
+    conn: &mut PgConnection,
+    report_id: Uuid,
+    evaluation: &PolicyEvaluation,
+) -> Result<(), ServiceError> {
+    let Some(version) = evaluation.policy_version else {
+        return Ok(());
+    };
+    let snapshot =
+        serde_json::to_value(evaluation).map_err(|err| ServiceError::Internal(err.to_string()))?;
+    sqlx::query(
+        "INSERT INTO report_policy_evaluations (report_id, policy_version, evaluation, evaluated_at)
+         VALUES ($1,$2,$3,$4)
+         ON CONFLICT (report_id) DO UPDATE
+         SET policy_version = EXCLUDED.policy_version, evaluation = EXCLUDED.evaluation,
+             evaluated_at = EXCLUDED.evaluated_at",
+    )
+    .bind(report_id)
+    .bind(version)
+    .bind(snapshot)
+    .bind(Utc::now())
+    .execute(conn)
+    .await
+    .map_err(internal)?;
+    Ok(())
+}
+
+/// The evaluation stored when `report_id` was submitted, if any.
+pub async fn stored_evaluation(
+    pool: &PgPool,
+    report_id: Uuid,
+) -> Result<Option<PolicyEvaluation>, ServiceError> {
+    let snapshot: Option<serde_json::Value> =
+        sqlx::query_scalar("SELECT evaluation FROM report_policy_evaluations WHERE report_id = $1")
+            .bind(report_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(internal)?;
+    snapshot
+        .map(|snapshot| {
+            serde_json::from_value(snapshot).map_err(|err| {
+                ServiceError::Internal(format!("report_policy_evaluations.evaluation: {err}"))
+            })
+        })
+        .transpose()
+}
+
+fn parse_rules(rules: serde_json::Value) -> Result<Vec<PolicyRule>, ServiceError> {
+    serde_json::from_value(rules)
+        .map_err(|err| ServiceError::Internal(format!("policy_versions.rules: {err}")))
+}
+
+fn map_version(row: PgRow) -> Result<PolicyVersion, ServiceError> {
+    Ok(PolicyVersion {
+        version: row.try_get("id").map_err(internal)?,
+        effective_from: row.try_get("effective_from").map_err(internal)?,
+        effective_to: row.try_get("effective_to").map_err(internal)?,
+        rules: parse_rules(row.try_get("rules").map_err(internal)?)?,
+    })
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::{RuleCondition, RuleScope};
+
+    #[test]
+    fn parses_rules_as_snapshotted_by_the_trigger() {
+        // `to_jsonb` of a `policy_rules` row.
+        let snapshot = serde_json::json!([{
+            "id": "6f1c2a52-5d1e-4c8b-9a57-0c4e2b7d9f31",
+            "name": "receipt_required",
+            "scope": "item",
+            "enabled": true,
+            "message": "A receipt is required for the {amount} expense on {date}",
+            "category": null,
+            "severity": "blocking",
+            "active_to": null,
+            "condition": "missing_receipt",
+            "comparison": "gt",
+            "updated_at": "2024-08-18T09:30:00.123456+00:00",
+            "updated_by": null,
+            "active_from": "2024-01-01",
+            "advance_days": null,
+            "per_attendee": false,
+            "threshold_cents": 2500
+        }]);
+
+        let rules = parse_rules(snapshot).unwrap();
+
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].scope, RuleScope::Item);
+        assert_eq!(rules[0].condition, RuleCondition::MissingReceipt);
+        assert_eq!(rules[0].threshold_cents, 2500);
+    }
+}
@@ -0,0 +1,243 @@
+//! Employees' own profile preferences.
+//!
+//! `GET`/`PATCH /auth/me/preferences` read and change three settings kept on
+//! the employee:
+//!
+//! * `locale`, the language notifications are rendered in;
+//! * `timezone`, an IANA zone such as `America/Chicago` that decides when a
+//!   reporting period has ended for them (stale draft reminders);
+//! * `default_currency`, used for new reports created without a `currency`.
+//!
+//! Unset settings fall back to English, UTC, and no default currency.
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection};
+use unic_langid::LanguageIdentifier;
+use uuid::Uuid;
+
+use crate::{
+    domain::models::Currency,
+    infrastructure::{auth::AuthenticatedUser, state::AppState},
+};
+
+use super::{audit, errors::ServiceError};
+
+/// An employee's preferences.
+#[derive(Debug, Clone, Serialize, FromRow, PartialEq, Eq)]
+pub struct ProfilePreferences {
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+    pub default_currency: Option<String>,
+}
+
+/// Payload accepted by `PATCH /auth/me/preferences`. Fields left out are
+/// kept; `null` clears one.
+#[derive(Debug, Default, Deserialize)]
+pub struct UpdateProfilePreferences {
+    #[serde(default, with = "::serde_with::rust::double_option")]
+    pub locale: Option<Option<String>>,
+    #[serde(default, with = "::serde_with::rust::double_option")]
+    pub timezone: Option<Option<String>>,
+    #[serde(default, with = "::serde_with::rust::double_option")]
+    pub default_currency: Option<Option<String>>,
+}
+
+/// Service behind the profile preference routes.
+pub struct ProfileService {
+    state: Arc<AppState>,
+}
+
+impl ProfileService {
+    /// Constructs the service from shared application state.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// The actor's preferences.
+    pub async fn preferences(
+        &self,
+        actor: &AuthenticatedUser,
+    ) -> Result<ProfilePreferences, ServiceError> {
+        let mut conn = self.state.pool.acquire().await.map_err(internal)?;
+        load(&mut conn, actor.employee_id).await
+    }
+
+    /// Changes the preferences named in `payload`. Fails with
+    /// `ServiceError::Validation` on a malformed BCP 47 locale, a timezone
+    /// Postgres does not know, or a currency that is not a three-letter code.
+    pub async fn update_preferences(
+        &self,
+        actor: &AuthenticatedUser,
+        payload: UpdateProfilePreferences,
+    ) -> Result<ProfilePreferences, ServiceError> {
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let current = load(&mut tx, actor.employee_id).await?;
+
+        let locale = match payload.locale {
+            Some(locale) => normalize_locale(locale.as_deref())?,
+            None => current.locale.clone(),
+        };
+        let timezone = match payload.timezone {
+            Some(timezone) => normalize_timezone(&mut tx, timezone.as_deref()).await?,
+            None => current.timezone.clone(),
+        };
+        let default_currency = match payload.default_currency {
+            Some(currency) => normalize_currency(currency.as_deref())?,
+            None => current.default_currency.clone(),
+        };
+        let updated = ProfilePreferences {
+            locale,
+            timezone,
+            default_currency,
+        };
+        if updated == current {
+            return Ok(current);
+        }
+
+        let before = audit::EMPLOYEE.snapshot(&mut tx, actor.employee_id).await?;
+        sqlx::query(
+            "UPDATE employees SET locale = $2, timezone = $3, default_currency = $4 WHERE id = $1",
+        )
+        .bind(actor.employee_id)
+        .bind(&updated.locale)
+        .bind(&updated.timezone)
+        .bind(&updated.default_currency)
+        .execute(&mut *tx)
+        .await
+        .map_err(internal)?;
+        audit::record_change(
+            &mut tx,
+            Some(actor.employee_id),
+            audit::EMPLOYEE,
+            actor.employee_id,
+            audit::UPDATED,
+            before,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        Ok(updated)
+    }
+}
+
+/// The default currency of `employee_id`, for a report created without one.
+pub async fn default_currency(
+    conn: &mut PgConnection,
+    employee_id: Uuid,
+) -> Result<Option<Currency>, ServiceError> {
+    let code: Option<String> =
+        sqlx::query_scalar("SELECT default_currency FROM employees WHERE id = $1")
+            .bind(employee_id)
+            .fetch_optional(conn)
+            .await
+            .map_err(internal)?
+            .flatten();
+    code.map(|code| Currency::parse(&code))
+        .transpose()
+        .map_err(|err| ServiceError::Internal(err.to_string()))
+}
+
+async fn load(
+    conn: &mut PgConnection,
+    employee_id: Uuid,
+) -> Result<ProfilePreferences, ServiceError> {
+    sqlx::query_as::<_, ProfilePreferences>(
+        "SELECT locale, timezone, default_currency FROM employees WHERE id = $1",
+    )
+    .bind(employee_id)
+    .fetch_optional(conn)
+    .await
+    .map_err(internal)?
+    .ok_or(ServiceError::NotFound)
+}
+
+/// A blank value clears the setting.
+fn blank_to_none(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|value| !value.is_empty())
+}
+
+/// Canonicalizes a BCP 47 tag, accepting `_` between subtags.
+fn normalize_locale(locale: Option<&str>) -> Result<Option<String>, ServiceError> {
+    blank_to_none(locale)
+        .map(|tag| {
+            tag.replace('_', "-")
+                .parse::<LanguageIdentifier>()
+                .map(|langid| langid.to_string())
+                .map_err(|_| {
+                    ServiceError::Validation("locale must be a BCP 47 tag such as de-DE".into())
+                })
+        })
+        .transpose()
+}
+
+fn normalize_currency(currency: Option<&str>) -> Result<Option<String>, ServiceError> {
+    blank_to_none(currency)
+        .map(|code| {
+            Currency::parse(code)
+                .map(|currency| currency.code().to_string())
+                .map_err(|_| {
+                    ServiceError::Validation(
+                        "default_currency must be a three-letter ISO 4217 code".into(),
+                    )
+                })
+        })
+        .transpose()
+}
+
+/// Checks the zone against the ones Postgres knows, since Postgres applies
+/// it, and returns its canonical spelling.
+async fn normalize_timezone(
+    conn: &mut PgConnection,
+    timezone: Option<&str>,
+) -> Result<Option<String>, ServiceError> {
+    let Some(timezone) = blank_to_none(timezone) else {
+        return Ok(None);
+    };
+    let name: Option<String> = sqlx::query_scalar(
+        "SELECT name FROM pg_timezone_names WHERE LOWER(name) = LOWER($1) ORDER BY name LIMIT 1",
+    )
+    .bind(timezone)
+    .fetch_optional(conn)
+    .await
+    .map_err(internal)?;
+    name.map(Some).ok_or_else(|| {
+        ServiceError::Validation("timezone must be an IANA time zone such as Europe/Berlin".into())
+    })
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_locales_and_currencies() {
+        assert_eq!(
+            normalize_locale(Some(" de_de ")).unwrap().as_deref(),
+            Some("de-DE")
+        );
+        assert_eq!(normalize_locale(Some("  ")).unwrap(), None);
+        assert!(normalize_locale(Some("not a locale")).is_err());
+
+        assert_eq!(
+            normalize_currency(Some("eur")).unwrap().as_deref(),
+            Some("EUR")
+        );
+        assert_eq!(normalize_currency(None).unwrap(), None);
+        assert!(normalize_currency(Some("EURO")).is_err());
+    }
+
+    #[test]
+    fn distinguishes_missing_fields_from_null() {
+        let payload: UpdateProfilePreferences =
+            serde_json::from_value(serde_json::json!({ "locale": null, "timezone": "UTC" }))
+                .unwrap();
+        assert_eq!(payload.locale, Some(None));
+        assert_eq!(payload.timezone, Some(Some("UTC".to_string())));
+        assert_eq!(payload.default_currency, None);
+    }
+}
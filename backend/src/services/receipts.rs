@@ -0,0 +1,297 @@
+//! Processes receipt uploads before they reach storage.
+//!
+//! Phone photos routinely embed GPS coordinates and device details in EXIF
+//! metadata. Uploads accepted by `POST /receipts` are rewritten so
+//! only the orientation tag survives, while the original capture timestamp is
+//! recorded in `receipt_uploads` and later compared with the claimed
+//! `expense_date` as a soft policy signal (`domain::policy`).
+
+use std::{io::Cursor, sync::Arc};
+
+use bytes::Bytes;
+use chrono::{NaiveDate, NaiveDateTime};
+use exif::{experimental::Writer, Exif, Field, In, Reader, Tag, Value};
+use img_parts::{DynImage, ImageEXIF};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::infrastructure::{auth::AuthenticatedUser, state::AppState};
+
+use super::errors::ServiceError;
+
+/// Stored receipt metadata returned to the client, which references
+/// `file_key` when attaching the receipt to an expense item.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReceiptUpload {
+    pub file_key: String,
+    pub file_name: String,
+    pub mime_type: String,
+    pub size_bytes: i64,
+    pub captured_at: Option<NaiveDateTime>,
+}
+
+/// Receipt bytes after metadata stripping plus the capture time recovered
+/// from the original EXIF block.
+#[derive(Debug)]
+pub struct SanitizedReceipt {
+    pub data: Bytes,
+    pub captured_at: Option<NaiveDateTime>,
+}
+
+/// Service writing sanitized receipts to the configured storage backend.
+pub struct ReceiptService {
+    state: Arc<AppState>,
+}
+
+impl ReceiptService {
+    /// Constructs the service from shared application state.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// Strips EXIF metadata, stores the receipt under a per-employee key, and
+    /// records the upload so the capture time follows the receipt once it is
+    /// attached to an expense item.
+    ///
+    /// Fails with `ServiceError::Validation` for empty or oversized uploads
+    /// and for images that cannot be parsed.
+    pub async fn upload(
+        &self,
+        actor: &AuthenticatedUser,
+        file_name: &str,
+        mime_type: &str,
+        data: Bytes,
+    ) -> Result<ReceiptUpload, ServiceError> {
+        if file_name.trim().is_empty() {
+            return Err(ServiceError::Validation("file_name is required".into()));
+        }
+        if data.is_empty() {
+            return Err(ServiceError::Validation("receipt file is empty".into()));
+        }
+        let max_bytes = self.state.config.receipts.max_bytes;
+        if data.len() as u64 > max_bytes {
+            return Err(ServiceError::Validation(format!(
+                "exceeds maximum size of {max_bytes} bytes"
+            )));
+        }
+
+        let sanitized = sanitize_receipt(data)
+            .map_err(|err| ServiceError::Validation(format!("unreadable receipt image: {err}")))?;
+        let file_key = format!(
+            "receipts/{}/{}{}",
+            actor.employee_id,
+            Uuid::new_v4(),
+            extension(file_name)
+        );
+        let size_bytes = sanitized.data.len() as i64;
+        let content_sha256 = format!("{:x}", Sha256::digest(&sanitized.data));
+
+        self.state
+            .storage
+            .put(&file_key, sanitized.data, mime_type)
+            .await
+            .map_err(|err| ServiceError::Internal(err.to_string()))?;
+
+        sqlx::query(
+            "INSERT INTO receipt_uploads (file_key, uploaded_by, file_name, mime_type, size_bytes, captured_at, content_sha256)
+             VALUES ($1,$2,$3,$4,$5,$6,$7)",
+        )
+        .bind(&file_key)
+        .bind(actor.employee_id)
+        .bind(file_name)
+        .bind(mime_type)
+        .bind(size_bytes)
+        .bind(sanitized.captured_at)
+        .bind(&content_sha256)
+        .execute(&self.state.pool)
+        .await
+        .map_err(|err| ServiceError::Internal(err.to_string()))?;
+
+        Ok(ReceiptUpload {
+            file_key,
+            file_name: file_name.to_string(),
+            mime_type: mime_type.to_string(),
+            size_bytes,
+            captured_at: sanitized.captured_at,
+        })
+    }
+}
+
+/// Removes EXIF metadata from JPEG, PNG, and WebP receipts, keeping only the
+/// orientation tag so photos still render upright.
+///
+/// Non-image uploads such as PDFs are returned unchanged.
+pub fn sanitize_receipt(data: Bytes) -> anyhow::Result<SanitizedReceipt> {
+    let Some(mut image) = DynImage::from_bytes(data.clone())? else {
+        return Ok(SanitizedReceipt {
+            data,
+            captured_at: None,
+        });
+    };
+    let Some(raw_exif) = image.exif() else {
+        return Ok(SanitizedReceipt {
+            data,
+            captured_at: None,
+        });
+    };
+
+    // Unparseable EXIF is still dropped; we just cannot recover anything.
+    let parsed = Reader::new().read_raw(raw_exif.to_vec()).ok();
+    let captured_at = parsed.as_ref().and_then(capture_time);
+    let retained = match parsed.as_ref().and_then(orientation) {
+        Some(orientation) => Some(orientation_only_exif(orientation)?),
+        None => None,
+    };
+    image.set_exif(retained);
+
+    Ok(SanitizedReceipt {
+        data: image.encoder().bytes(),
+        captured_at,
+    })
+}
+
+fn capture_time(exif: &Exif) -> Option<NaiveDateTime> {
+    [Tag::DateTimeOriginal, Tag::DateTimeDigitized, Tag::DateTime]
+        .into_iter()
+        .find_map(|tag| {
+            let field = exif.get_field(tag, In::PRIMARY)?;
+            let Value::Ascii(ref parts) = field.value else {
+                return None;
+            };
+            let parsed = exif::DateTime::from_ascii(parts.first()?).ok()?;
+            NaiveDate::from_ymd_opt(
+                i32::from(parsed.year),
+                u32::from(parsed.month),
+                u32::from(parsed.day),
+            )?
+            .and_hms_opt(
+                u32::from(parsed.hour),
+                u32::from(parsed.minute),
+                u32::from(parsed.second),
+            )
+        })
+}
+
+fn orientation(exif: &Exif) -> Option<u16> {
+    let value = exif
+        .get_field(Tag::Orientation, In::PRIMARY)?
+        .value
+        .get_uint(0)?;
+    u16::try_from(value)
+        .ok()
+        .filter(|value| (1..=8).contains(value))
+}
+
+fn orientation_only_exif(orientation: u16) -> anyhow::Result<Bytes> {
+    let field = Field {
+        tag: Tag::Orientation,
+        ifd_num: In::PRIMARY,
+        value: Value::Short(vec![orientation]),
+    };
+    let mut writer = Writer::new();
+    writer.push_field(&field);
+    let mut buffer = Cursor::new(Vec::new());
+    writer.write(&mut buffer, false)?;
+    Ok(Bytes::from(buffer.into_inner()))
+}
+
+fn extension(file_name: &str) -> String {
+    std::path::Path::new(file_name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .filter(|ext| ext.chars().all(|c| c.is_ascii_alphanumeric()))
+        .map(|ext| format!(".{}", ext.to_ascii_lowercase()))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exif::Rational;
+    use img_parts::jpeg::Jpeg;
+
+    /// Skeleton JPEG with APP0, DQT, SOF0, and a one-byte SOS header followed
+    /// by EOI; enough structure for img-parts to rewrite the EXIF segment.
+    fn bare_jpeg() -> Bytes {
+        Bytes::from_static(&[
+            0xFF, 0xD8, // SOI
+            0xFF, 0xE0, 0x00, 0x02, // APP0
+            0xFF, 0xDB, 0x00, 0x02, // DQT
+            0xFF, 0xC0, 0x00, 0x02, // SOF0
+            0xFF, 0xDA, 0x00, 0x03, 0x00, // SOS
+            0xFF, 0xD9, // EOI
+        ])
+    }
+
+    fn phone_exif() -> Bytes {
+        let fields = [
+            Field {
+                tag: Tag::Orientation,
+                ifd_num: In::PRIMARY,
+                value: Value::Short(vec![6]),
+            },
+            Field {
+                tag: Tag::DateTimeOriginal,
+                ifd_num: In::PRIMARY,
+                value: Value::Ascii(vec![b"2024:05:03 12:34:56".to_vec()]),
+            },
+            Field {
+                tag: Tag::GPSLatitude,
+                ifd_num: In::PRIMARY,
+                value: Value::Rational(vec![
+                    Rational::from((41, 1)),
+                    Rational::from((52, 1)),
+                    Rational::from((0, 1)),
+                ]),
+            },
+        ];
+        let mut writer = Writer::new();
+        for field in &fields {
+            writer.push_field(field);
+        }
+        let mut buffer = Cursor::new(Vec::new());
+        writer.write(&mut buffer, false).unwrap();
+        Bytes::from(buffer.into_inner())
+    }
+
+    #[test]
+    fn sanitize_strips_gps_but_keeps_orientation_and_capture_time() {
+        let mut jpeg = Jpeg::from_bytes(bare_jpeg()).unwrap();
+        jpeg.set_exif(Some(phone_exif()));
+        let original = jpeg.encoder().bytes();
+
+        let sanitized = sanitize_receipt(original).unwrap();
+
+        assert_eq!(
+            sanitized.captured_at,
+            NaiveDate::from_ymd_opt(2024, 5, 3)
+                .unwrap()
+                .and_hms_opt(12, 34, 56)
+        );
+        let cleaned = Jpeg::from_bytes(sanitized.data).unwrap();
+        let exif = Reader::new()
+            .read_raw(cleaned.exif().expect("orientation retained").to_vec())
+            .unwrap();
+        assert_eq!(orientation(&exif), Some(6));
+        assert!(exif.get_field(Tag::GPSLatitude, In::PRIMARY).is_none());
+        assert!(exif.get_field(Tag::DateTimeOriginal, In::PRIMARY).is_none());
+    }
+
+    #[test]
+    fn sanitize_passes_through_non_images() {
+        let pdf = Bytes::from_static(b"%PDF-1.7 receipt");
+
+        let sanitized = sanitize_receipt(pdf.clone()).unwrap();
+
+        assert_eq!(sanitized.data, pdf);
+        assert!(sanitized.captured_at.is_none());
+    }
+
+    #[test]
+    fn extension_is_normalized_and_sanitized() {
+        assert_eq!(extension("Lunch.JPG"), ".jpg");
+        assert_eq!(extension("receipt"), "");
+        assert_eq!(extension("bad.j/pg"), "");
+    }
+}
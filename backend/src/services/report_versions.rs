@@ -0,0 +1,76 @@
+//! Version-checked status changes for expense reports.
+//!
+//! Every workflow write to a report bumps `expense_reports.version` and
+//! applies only while the report is still at the version its writer read.
+//! Two actions racing on one report, such as a finance export and an
+//! approval reversal, therefore cannot interleave: whichever writes second
+//! finds the version moved and fails with `ServiceError::Conflict`, rolling
+//! back its transaction.
+
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::domain::models::ReportStatus;
+
+use super::errors::ServiceError;
+
+/// A report and the version its writer read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReportVersion {
+    pub id: Uuid,
+    pub version: i32,
+}
+
+/// The current versions of the listed reports; missing reports are left out.
+pub async fn current(
+    conn: &mut PgConnection,
+    report_ids: &[Uuid],
+) -> Result<Vec<ReportVersion>, ServiceError> {
+    sqlx::query_as::<_, (Uuid, i32)>(
+        "SELECT id, version FROM expense_reports
+         WHERE id = ANY($1)
+         ORDER BY array_position($1, id)",
+    )
+    .bind(report_ids)
+    .fetch_all(conn)
+    .await
+    .map(|rows| {
+        rows.into_iter()
+            .map(|(id, version)| ReportVersion { id, version })
+            .collect()
+    })
+    .map_err(|err| ServiceError::Internal(err.to_string()))
+}
+
+/// Moves every report in `reports` to `status`, bumping its version, or
+/// none of them: `Conflict` when any is no longer at the version given.
+/// Callers run this in a transaction, which a conflict must roll back.
+pub async fn set_status(
+    conn: &mut PgConnection,
+    reports: &[ReportVersion],
+    status: ReportStatus,
+) -> Result<(), ServiceError> {
+    if reports.is_empty() {
+        return Ok(());
+    }
+    let (ids, versions): (Vec<Uuid>, Vec<i32>) = reports
+        .iter()
+        .map(|report| (report.id, report.version))
+        .unzip();
+    let result = sqlx::query(
+        "UPDATE expense_reports r
+         SET status = $1, version = r.version + 1, updated_at = NOW()
+         FROM UNNEST($2::uuid[], $3::int4[]) AS expected(id, version)
+         WHERE r.id = expected.id AND r.version = expected.version",
+    )
+    .bind(status)
+    .bind(&ids)
+    .bind(&versions)
+    .execute(conn)
+    .await
+    .map_err(|err| ServiceError::Internal(err.to_string()))?;
+    if result.rows_affected() != reports.len() as u64 {
+        return Err(ServiceError::Conflict);
+    }
+    Ok(())
+}
@@ -0,0 +1,269 @@
+//! Reports waiting on an approver: the manager queue and the finance
+//! escalation queue.
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use parking_lot::RwLock;
+use sqlx::types::Json;
+use uuid::Uuid;
+
+use crate::{
+    domain::models::ReportStatus,
+    infrastructure::db::PgPool,
+    services::{
+        escalations::{EscalatedReport, EscalationLevel},
+        manager::{ManagerQueueLineItem, ManagerQueueReport},
+        pagination::PageRequest,
+    },
+};
+
+use super::{internal, ServiceError};
+
+/// A report in a manager's queue with its line items.
+#[derive(Debug, Clone)]
+pub struct QueuedReport {
+    pub report: ManagerQueueReport,
+    pub line_items: Vec<ManagerQueueLineItem>,
+}
+
+#[async_trait]
+pub trait ApprovalRepo: Send + Sync {
+    /// How many reports of `org_id` wait on `manager_id`, and the page of
+    /// them after `page.after`, oldest submission first, holding up to
+    /// `page.fetch_limit()` rows. Every submitted report waits on every
+    /// manager of its organization; an exception review only on its assigned
+    /// exception approver.
+    async fn manager_queue(
+        &self,
+        org_id: Uuid,
+        manager_id: Uuid,
+        page: &PageRequest,
+    ) -> Result<(i64, Vec<QueuedReport>), ServiceError>;
+
+    /// Reports of `org_id` escalated to finance that still wait in the
+    /// escalated approval round, longest waiting first.
+    async fn finance_escalations(&self, org_id: Uuid)
+        -> Result<Vec<EscalatedReport>, ServiceError>;
+}
+
+/// `ApprovalRepo` over the `manager_queue_entries` read model and the
+/// `approval_escalations` table.
+pub struct PgApprovalRepo {
+    pool: PgPool,
+}
+
+impl PgApprovalRepo {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[derive(Debug)]
+struct QueueRow {
+    id: Uuid,
+    employee_id: Uuid,
+    hr_identifier: String,
+    reporting_period_start: NaiveDate,
+    reporting_period_end: NaiveDate,
+    total_amount_cents: i64,
+    total_reimbursable_cents: i64,
+    currency: String,
+    submitted_at: DateTime<Utc>,
+    line_items: Json<Vec<ManagerQueueLineItem>>,
+}
+
+impl From<QueueRow> for QueuedReport {
+    fn from(value: QueueRow) -> Self {
+        Self {
+            report: ManagerQueueReport {
+                id: value.id,
+                employee_id: value.employee_id,
+                employee_hr_identifier: value.hr_identifier,
+                reporting_period_start: value.reporting_period_start,
+                reporting_period_end: value.reporting_period_end,
+                submitted_at: value.submitted_at,
+                total_amount_cents: value.total_amount_cents,
+                total_reimbursable_cents: value.total_reimbursable_cents,
+                currency: value.currency,
+            },
+            line_items: value.line_items.0,
+        }
+    }
+}
+
+#[async_trait]
+impl ApprovalRepo for PgApprovalRepo {
+    async fn manager_queue(
+        &self,
+        org_id: Uuid,
+        manager_id: Uuid,
+        page: &PageRequest,
+    ) -> Result<(i64, Vec<QueuedReport>), ServiceError> {
+        let total = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!"
+               FROM manager_queue_entries
+               WHERE org_id = $1 AND (status = $3 OR manager_id = $2)"#,
+            org_id,
+            manager_id,
+            ReportStatus::Submitted.as_str(),
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(internal)?;
+
+        let rows = sqlx::query_as!(
+            QueueRow,
+            r#"SELECT
+                   report_id AS id,
+                   employee_id,
+                   employee_hr_identifier AS hr_identifier,
+                   reporting_period_start,
+                   reporting_period_end,
+                   total_amount_cents,
+                   total_reimbursable_cents,
+                   currency,
+                   submitted_at,
+                   line_items AS "line_items: Json<Vec<ManagerQueueLineItem>>"
+               FROM manager_queue_entries
+               WHERE org_id = $1 AND (status = $3 OR manager_id = $2)
+                 AND ($4::timestamptz IS NULL OR (submitted_at, report_id) > ($4, $5::uuid))
+               ORDER BY submitted_at ASC, report_id ASC
+               LIMIT $6"#,
+            org_id,
+            manager_id,
+            ReportStatus::Submitted.as_str(),
+            page.after.map(|cursor| cursor.at),
+            page.after.map(|cursor| cursor.id),
+            page.fetch_limit(),
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(internal)?;
+
+        Ok((total, rows.into_iter().map(QueuedReport::from).collect()))
+    }
+
+    async fn finance_escalations(
+        &self,
+        org_id: Uuid,
+    ) -> Result<Vec<EscalatedReport>, ServiceError> {
+        sqlx::query_as!(
+            EscalatedReport,
+            r#"SELECT r.id AS report_id, r.status AS "status: ReportStatus", r.employee_id,
+                      x.approver_id, s.escalated_to AS "skip_level_manager_id?",
+                      x.pending_since, x.created_at AS escalated_at, r.total_amount_cents,
+                      r.currency
+               FROM approval_escalations x
+               JOIN expense_reports r ON r.id = x.report_id
+               LEFT JOIN approval_escalations s
+                   ON s.report_id = x.report_id AND s.level = $2
+                  AND s.pending_since = x.pending_since
+               WHERE x.level = $1
+                 AND r.status::text IN ($3, $4)
+                 AND r.updated_at = x.pending_since
+                 AND r.org_id = $5
+               ORDER BY x.pending_since, r.id"#,
+            EscalationLevel::Finance.as_str(),
+            EscalationLevel::SkipLevel.as_str(),
+            ReportStatus::Submitted.as_str(),
+            ReportStatus::ExceptionReview.as_str(),
+            org_id,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(internal)
+    }
+}
+
+/// A report queued in `MemoryApprovalRepo`, for every manager of `org_id`
+/// or only for `approver_id`.
+struct QueueEntry {
+    org_id: Uuid,
+    approver_id: Option<Uuid>,
+    report: QueuedReport,
+}
+
+/// `ApprovalRepo` fake over queue entries held in memory.
+#[derive(Default)]
+pub struct MemoryApprovalRepo {
+    queued: RwLock<Vec<QueueEntry>>,
+    escalations: RwLock<Vec<(Uuid, EscalatedReport)>>,
+}
+
+impl MemoryApprovalRepo {
+    /// Queues submitted `report`, belonging to `org_id`, for every manager
+    /// of the organization.
+    pub fn queue(&self, org_id: Uuid, report: QueuedReport) {
+        self.queued.write().push(QueueEntry {
+            org_id,
+            approver_id: None,
+            report,
+        });
+    }
+
+    /// Queues `report`, belonging to `org_id`, for exception review by
+    /// `approver_id` only.
+    pub fn queue_exception_review(&self, org_id: Uuid, approver_id: Uuid, report: QueuedReport) {
+        self.queued.write().push(QueueEntry {
+            org_id,
+            approver_id: Some(approver_id),
+            report,
+        });
+    }
+
+    /// Adds `report`, belonging to `org_id`, to the finance escalation
+    /// queue.
+    pub fn escalate(&self, org_id: Uuid, report: EscalatedReport) {
+        self.escalations.write().push((org_id, report));
+    }
+}
+
+#[async_trait]
+impl ApprovalRepo for MemoryApprovalRepo {
+    async fn manager_queue(
+        &self,
+        org_id: Uuid,
+        manager_id: Uuid,
+        page: &PageRequest,
+    ) -> Result<(i64, Vec<QueuedReport>), ServiceError> {
+        let mut queued: Vec<QueuedReport> = self
+            .queued
+            .read()
+            .iter()
+            .filter(|entry| {
+                entry.org_id == org_id
+                    && entry
+                        .approver_id
+                        .is_none_or(|approver| approver == manager_id)
+            })
+            .map(|entry| entry.report.clone())
+            .collect();
+        queued.sort_by_key(|entry| (entry.report.submitted_at, entry.report.id));
+        let total = queued.len() as i64;
+        let rows = queued
+            .into_iter()
+            .filter(|entry| {
+                page.after.is_none_or(|cursor| {
+                    (entry.report.submitted_at, entry.report.id) > (cursor.at, cursor.id)
+                })
+            })
+            .take(page.fetch_limit() as usize)
+            .collect();
+        Ok((total, rows))
+    }
+
+    async fn finance_escalations(
+        &self,
+        org_id: Uuid,
+    ) -> Result<Vec<EscalatedReport>, ServiceError> {
+        let mut escalations: Vec<EscalatedReport> = self
+            .escalations
+            .read()
+            .iter()
+            .filter(|(org, _)| *org == org_id)
+            .map(|(_, report)| report.clone())
+            .collect();
+        escalations.sort_by_key(|report| (report.pending_since, report.report_id));
+        Ok(escalations)
+    }
+}
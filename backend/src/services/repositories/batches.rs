@@ -0,0 +1,170 @@
+//! NetSuite batch listings.
+
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use uuid::Uuid;
+
+use crate::{
+    infrastructure::db::PgPool,
+    services::{
+        finance::{escape_like, BatchFilter, BatchSummary},
+        pagination::PageRequest,
+    },
+};
+
+use super::{internal, ServiceError};
+
+#[async_trait]
+pub trait BatchRepo: Send + Sync {
+    /// How many of `org_id`'s batches match `filter`, and the page of them
+    /// after `page.after`, newest finalization first, holding up to
+    /// `page.fetch_limit()` rows. The filter is expected to be validated.
+    async fn list(
+        &self,
+        org_id: Uuid,
+        filter: &BatchFilter,
+        page: &PageRequest,
+    ) -> Result<(i64, Vec<BatchSummary>), ServiceError>;
+}
+
+/// `BatchRepo` over `netsuite_batches` and their journal lines.
+pub struct PgBatchRepo {
+    pool: PgPool,
+}
+
+impl PgBatchRepo {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl BatchRepo for PgBatchRepo {
+    async fn list(
+        &self,
+        org_id: Uuid,
+        filter: &BatchFilter,
+        page: &PageRequest,
+    ) -> Result<(i64, Vec<BatchSummary>), ServiceError> {
+        let reference_pattern =
+            reference(filter).map(|reference| format!("%{}%", escape_like(reference)));
+
+        let total = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!"
+               FROM netsuite_batches b
+               WHERE ($1::text IS NULL OR b.status = $1)
+                 AND ($2::date IS NULL OR (b.finalized_at AT TIME ZONE 'UTC')::date >= $2)
+                 AND ($3::date IS NULL OR (b.finalized_at AT TIME ZONE 'UTC')::date <= $3)
+                 AND ($4::text IS NULL OR b.batch_reference ILIKE $4)
+                 AND b.org_id = $5"#,
+            filter.status.as_deref(),
+            filter.finalized_from,
+            filter.finalized_to,
+            reference_pattern.as_deref(),
+            org_id,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(internal)?;
+
+        let batches = sqlx::query_as!(
+            BatchSummary,
+            r#"SELECT b.id, b.batch_reference, b.finalized_at, b.posting_date, b.status,
+                      b.exported_at, b.export_attempts,
+                      COUNT(DISTINCT j.report_id) AS "report_count!",
+                      COALESCE(SUM(j.amount_cents) FILTER (WHERE j.line_kind = 'expense'), 0)::BIGINT
+                          AS "total_amount_cents!"
+               FROM netsuite_batches b
+               LEFT JOIN journal_lines j ON j.batch_id = b.id
+               WHERE ($1::text IS NULL OR b.status = $1)
+                 AND ($2::date IS NULL OR (b.finalized_at AT TIME ZONE 'UTC')::date >= $2)
+                 AND ($3::date IS NULL OR (b.finalized_at AT TIME ZONE 'UTC')::date <= $3)
+                 AND ($4::text IS NULL OR b.batch_reference ILIKE $4)
+                 AND b.org_id = $5
+                 AND ($6::timestamptz IS NULL OR (b.finalized_at, b.id) < ($6, $7::uuid))
+               GROUP BY b.id
+               ORDER BY b.finalized_at DESC, b.id DESC
+               LIMIT $8"#,
+            filter.status.as_deref(),
+            filter.finalized_from,
+            filter.finalized_to,
+            reference_pattern.as_deref(),
+            org_id,
+            page.after.map(|cursor| cursor.at),
+            page.after.map(|cursor| cursor.id),
+            page.fetch_limit(),
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(internal)?;
+
+        Ok((total, batches))
+    }
+}
+
+/// `BatchRepo` fake over batch summaries held in memory, each with the
+/// organization it belongs to.
+#[derive(Default)]
+pub struct MemoryBatchRepo {
+    batches: RwLock<Vec<(Uuid, BatchSummary)>>,
+}
+
+impl MemoryBatchRepo {
+    pub fn new(batches: Vec<(Uuid, BatchSummary)>) -> Self {
+        Self {
+            batches: RwLock::new(batches),
+        }
+    }
+}
+
+#[async_trait]
+impl BatchRepo for MemoryBatchRepo {
+    async fn list(
+        &self,
+        org_id: Uuid,
+        filter: &BatchFilter,
+        page: &PageRequest,
+    ) -> Result<(i64, Vec<BatchSummary>), ServiceError> {
+        let reference = reference(filter).map(str::to_lowercase);
+        let mut matching: Vec<BatchSummary> = self
+            .batches
+            .read()
+            .iter()
+            .filter(|(org, _)| *org == org_id)
+            .map(|(_, batch)| batch)
+            .filter(|batch| {
+                let day = batch.finalized_at.date_naive();
+                filter
+                    .status
+                    .as_deref()
+                    .is_none_or(|status| batch.status == status)
+                    && filter.finalized_from.is_none_or(|from| day >= from)
+                    && filter.finalized_to.is_none_or(|to| day <= to)
+                    && reference.as_deref().is_none_or(|reference| {
+                        batch.batch_reference.to_lowercase().contains(reference)
+                    })
+            })
+            .cloned()
+            .collect();
+        matching.sort_by_key(|batch| std::cmp::Reverse((batch.finalized_at, batch.id)));
+        let total = matching.len() as i64;
+        let batches = matching
+            .into_iter()
+            .filter(|batch| {
+                page.after
+                    .is_none_or(|cursor| (batch.finalized_at, batch.id) < (cursor.at, cursor.id))
+            })
+            .take(page.fetch_limit() as usize)
+            .collect();
+        Ok((total, batches))
+    }
+}
+
+/// The reference filter, unless blank.
+fn reference(filter: &BatchFilter) -> Option<&str> {
+    filter
+        .reference
+        .as_deref()
+        .map(str::trim)
+        .filter(|reference| !reference.is_empty())
+}
@@ -0,0 +1,22 @@
+//! Repository traits between the services and PostgreSQL.
+//!
+//! Each repository owns the SQL and row mapping for one aggregate and comes
+//! in two implementations: a `Pg*` one over a sqlx pool, which the services
+//! use by default, and a `Memory*` fake holding rows in a `Vec`, so service
+//! rules such as access checks, validation, and paging can be unit tested
+//! without a database. Services take a repository through their
+//! `with_*_repo` constructors.
+
+pub mod approvals;
+pub mod batches;
+pub mod reports;
+
+pub use approvals::{ApprovalRepo, MemoryApprovalRepo, PgApprovalRepo};
+pub use batches::{BatchRepo, MemoryBatchRepo, PgBatchRepo};
+pub use reports::{MemoryReportRepo, PgReportRepo, ReportRepo};
+
+use super::errors::ServiceError;
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
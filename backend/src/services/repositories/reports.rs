@@ -0,0 +1,74 @@
+//! Expense report lookups.
+
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{ExpenseReport, ReportStatus},
+    infrastructure::db::PgPool,
+};
+
+use super::{internal, ServiceError};
+
+#[async_trait]
+pub trait ReportRepo: Send + Sync {
+    /// The report with `id`, or `None` when it is missing or soft-deleted.
+    async fn find(&self, id: Uuid) -> Result<Option<ExpenseReport>, ServiceError>;
+}
+
+/// `ReportRepo` over the `expense_reports` table.
+pub struct PgReportRepo {
+    pool: PgPool,
+}
+
+impl PgReportRepo {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ReportRepo for PgReportRepo {
+    async fn find(&self, id: Uuid) -> Result<Option<ExpenseReport>, ServiceError> {
+        sqlx::query_as!(
+            ExpenseReport,
+            r#"SELECT id, org_id, employee_id, reporting_period_start, reporting_period_end,
+                      status AS "status: ReportStatus", total_amount_cents,
+                      total_reimbursable_cents, currency, version, created_at, updated_at,
+                      submitted_at, trip_id, owner_deactivated_at, created_by, submitted_by
+               FROM expense_reports
+               WHERE id = $1 AND deleted_at IS NULL"#,
+            id,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(internal)
+    }
+}
+
+/// `ReportRepo` fake over reports held in memory.
+#[derive(Default)]
+pub struct MemoryReportRepo {
+    reports: RwLock<Vec<ExpenseReport>>,
+}
+
+impl MemoryReportRepo {
+    pub fn new(reports: Vec<ExpenseReport>) -> Self {
+        Self {
+            reports: RwLock::new(reports),
+        }
+    }
+}
+
+#[async_trait]
+impl ReportRepo for MemoryReportRepo {
+    async fn find(&self, id: Uuid) -> Result<Option<ExpenseReport>, ServiceError> {
+        Ok(self
+            .reports
+            .read()
+            .iter()
+            .find(|report| report.id == id)
+            .cloned())
+    }
+}
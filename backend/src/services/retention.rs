@@ -0,0 +1,361 @@
+//! Data retention for expense reports past `retention.retention_days`.
+//!
+//! Only reports no longer in flight (`draft`, `needs_changes`, `denied`,
+//! `finance_finalized`) and untouched since the cutoff are affected.
+//! Anonymizing clears the free text on their items and approvals and removes
+//! their receipts, keeping amounts, categories, and dates; `delete` mode
+//! removes reports outright unless journal lines reference them, in which case
+//! they are anonymized so finalized journal summaries stay intact. Audit log
+//! entries older than the cutoff are trimmed either way.
+//!
+//! Nothing is removed without an administrator: the scheduled job and
+//! `POST /retention/runs` only record a dry-run plan with the counts it would
+//! touch, and `POST /retention/runs/:id/execute` with `{"confirm": true}`
+//! applies it.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgRow, PgConnection, Row};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{ReportStatus, Role},
+    infrastructure::{auth::AuthenticatedUser, config::RetentionMode, state::AppState},
+};
+
+use super::{audit, errors::ServiceError};
+
+pub const RUN_PLANNED: &str = "planned";
+pub const RUN_EXECUTED: &str = "executed";
+
+/// Statuses a report can rest in indefinitely.
+const SETTLED_STATUSES: [ReportStatus; 4] = [
+    ReportStatus::Draft,
+    ReportStatus::NeedsChanges,
+    ReportStatus::Denied,
+    ReportStatus::FinanceFinalized,
+];
+
+/// A planned or executed retention purge. Counts are what the plan would
+/// touch until the run is executed, then what it did.
+#[derive(Debug, Clone, Serialize)]
+pub struct RetentionRun {
+    pub id: Uuid,
+    pub mode: String,
+    pub cutoff: DateTime<Utc>,
+    pub status: String,
+    pub reports_anonymized: i64,
+    pub reports_deleted: i64,
+    pub receipts_removed: i64,
+    pub audit_logs_removed: i64,
+    pub requested_by: Option<Uuid>,
+    pub confirmed_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub executed_at: Option<DateTime<Utc>>,
+}
+
+/// Payload accepted by `POST /retention/runs/:id/execute`.
+#[derive(Debug, Deserialize)]
+pub struct ExecuteRetentionRequest {
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// Service planning and executing retention runs.
+pub struct RetentionService {
+    state: Arc<AppState>,
+}
+
+impl RetentionService {
+    /// Constructs the service from shared application state.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// Lists retention runs, newest first. Restricted to administrators.
+    pub async fn list(&self, actor: &AuthenticatedUser) -> Result<Vec<RetentionRun>, ServiceError> {
+        require_admin(actor)?;
+        sqlx::query("SELECT * FROM retention_runs ORDER BY created_at DESC")
+            .fetch_all(&self.state.pool)
+            .await
+            .map_err(internal)?
+            .into_iter()
+            .map(map_run)
+            .collect()
+    }
+
+    /// Records a dry-run plan with the current settings. Restricted to
+    /// administrators.
+    pub async fn plan(&self, actor: &AuthenticatedUser) -> Result<RetentionRun, ServiceError> {
+        require_admin(actor)?;
+        plan(&self.state, Some(actor.employee_id)).await
+    }
+
+    /// Applies a planned run. Restricted to administrators, who must pass
+    /// `confirm: true`; runs that were already executed are a conflict.
+    pub async fn execute(
+        &self,
+        actor: &AuthenticatedUser,
+        run_id: Uuid,
+        payload: ExecuteRetentionRequest,
+    ) -> Result<RetentionRun, ServiceError> {
+        require_admin(actor)?;
+        if !payload.confirm {
+            return Err(ServiceError::Validation(
+                "confirm must be true to execute a retention run".into(),
+            ));
+        }
+
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let run = sqlx::query("SELECT * FROM retention_runs WHERE id = $1 FOR UPDATE")
+            .bind(run_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(internal)?
+            .map(map_run)
+            .transpose()?
+            .ok_or(ServiceError::NotFound)?;
+        if run.status != RUN_PLANNED {
+            return Err(ServiceError::Conflict);
+        }
+        let before = audit::RETENTION_RUN.snapshot(&mut tx, run_id).await?;
+        let mode = RetentionMode::parse(&run.mode).ok_or_else(|| {
+            ServiceError::Internal(format!(
+                "retention_runs.mode has unknown value {}",
+                run.mode
+            ))
+        })?;
+
+        let targets: Vec<(Uuid, bool)> =
+            sqlx::query(&format!("WITH {TARGETS} SELECT id, remove FROM targets"))
+                .bind(settled_statuses())
+                .bind(run.cutoff)
+                .bind(mode.as_str())
+                .map(|row: PgRow| (row.get("id"), row.get("remove")))
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(internal)?;
+        let all: Vec<Uuid> = targets.iter().map(|(id, _)| *id).collect();
+        let mut removed = Vec::new();
+        let mut anonymized = Vec::new();
+        for (id, remove) in targets {
+            if remove {
+                removed.push(id);
+            } else {
+                anonymized.push(id);
+            }
+        }
+
+        let file_keys = remove_receipts(&mut tx, &all).await?;
+        anonymize_reports(&mut tx, &anonymized).await?;
+        sqlx::query("DELETE FROM expense_reports WHERE id = ANY($1)")
+            .bind(&removed)
+            .execute(&mut *tx)
+            .await
+            .map_err(internal)?;
+        let audit_logs_removed = sqlx::query("DELETE FROM audit_logs WHERE performed_at < $1")
+            .bind(run.cutoff)
+            .execute(&mut *tx)
+            .await
+            .map_err(internal)?
+            .rows_affected();
+
+        let row = sqlx::query(
+            "UPDATE retention_runs
+             SET status = $2, reports_anonymized = $3, reports_deleted = $4,
+                 receipts_removed = $5, audit_logs_removed = $6, confirmed_by = $7,
+                 executed_at = $8
+             WHERE id = $1
+             RETURNING *",
+        )
+        .bind(run_id)
+        .bind(RUN_EXECUTED)
+        .bind(anonymized.len() as i64)
+        .bind(removed.len() as i64)
+        .bind(file_keys.len() as i64)
+        .bind(audit_logs_removed as i64)
+        .bind(actor.employee_id)
+        .bind(Utc::now())
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(internal)?;
+        audit::record_change(
+            &mut tx,
+            Some(actor.employee_id),
+            audit::RETENTION_RUN,
+            run_id,
+            audit::STATUS_CHANGED,
+            before,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+
+        // Files go only once the rows referencing them are gone for good.
+        let mut failed = 0;
+        for key in &file_keys {
+            if self.state.storage.delete(key).await.is_err() {
+                failed += 1;
+            }
+        }
+        if failed > 0 {
+            warn!(
+                run_id = %run_id,
+                failed,
+                "retention run could not delete some receipt files"
+            );
+        }
+
+        map_run(row)
+    }
+}
+
+/// Reports a run with `$3` mode would touch, given the settled statuses `$1`
+/// and cutoff `$2`; `remove` marks those it deletes rather than anonymizes.
+/// Reports already anonymized are skipped unless `delete` mode can remove
+/// them, and archived reports are skipped until they are rehydrated.
+const TARGETS: &str = "eligible AS (
+         SELECT r.id, r.anonymized_at,
+                EXISTS (SELECT 1 FROM journal_lines j WHERE j.report_id = r.id) AS journaled
+         FROM expense_reports r
+         WHERE r.status::text = ANY($1) AND r.updated_at < $2 AND r.archived_at IS NULL
+     ),
+     targets AS (
+         SELECT id, ($3 = 'delete' AND NOT journaled) AS remove
+         FROM eligible
+         WHERE anonymized_at IS NULL OR ($3 = 'delete' AND NOT journaled)
+     )";
+
+/// Computes and records a dry-run plan with the configured mode and window.
+/// `requested_by` is `None` for the scheduled job.
+pub async fn plan(
+    state: &AppState,
+    requested_by: Option<Uuid>,
+) -> Result<RetentionRun, ServiceError> {
+    let config = &state.config.retention;
+    let now = Utc::now();
+    let cutoff = now - Duration::days(i64::from(config.retention_days));
+    let row = sqlx::query(&format!(
+        "WITH {TARGETS},
+         counts AS (
+             SELECT COUNT(*) FILTER (WHERE NOT remove) AS reports_anonymized,
+                    COUNT(*) FILTER (WHERE remove) AS reports_deleted,
+                    (SELECT COUNT(*)
+                     FROM receipts rc
+                     JOIN expense_items i ON i.id = rc.expense_item_id
+                     WHERE i.report_id IN (SELECT id FROM targets)) AS receipts_removed,
+                    (SELECT COUNT(*) FROM audit_logs WHERE performed_at < $2)
+                        AS audit_logs_removed
+             FROM targets
+         )
+         INSERT INTO retention_runs
+             (id, mode, cutoff, status, reports_anonymized, reports_deleted,
+              receipts_removed, audit_logs_removed, requested_by, created_at)
+         SELECT $4, $3, $2, $5, reports_anonymized, reports_deleted,
+                receipts_removed, audit_logs_removed, $6, $7
+         FROM counts
+         RETURNING *"
+    ))
+    .bind(settled_statuses())
+    .bind(cutoff)
+    .bind(config.mode.as_str())
+    .bind(Uuid::new_v4())
+    .bind(RUN_PLANNED)
+    .bind(requested_by)
+    .bind(now)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(internal)?;
+    map_run(row)
+}
+
+/// Deletes the receipts on `report_ids`' items and returns their file keys.
+async fn remove_receipts(
+    conn: &mut PgConnection,
+    report_ids: &[Uuid],
+) -> Result<Vec<String>, ServiceError> {
+    let file_keys: Vec<String> = sqlx::query_scalar(
+        "DELETE FROM receipts rc
+         USING expense_items i
+         WHERE i.id = rc.expense_item_id AND i.report_id = ANY($1)
+         RETURNING rc.file_key",
+    )
+    .bind(report_ids)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(internal)?;
+    sqlx::query("DELETE FROM receipt_uploads WHERE file_key = ANY($1)")
+        .bind(&file_keys)
+        .execute(&mut *conn)
+        .await
+        .map_err(internal)?;
+    Ok(file_keys)
+}
+
+/// Clears the free text on `report_ids`' items and approvals.
+async fn anonymize_reports(
+    conn: &mut PgConnection,
+    report_ids: &[Uuid],
+) -> Result<(), ServiceError> {
+    sqlx::query(
+        "UPDATE expense_items
+         SET description = NULL, attendees = NULL, location = NULL, merchant = NULL,
+             exception_justification = NULL
+         WHERE report_id = ANY($1)",
+    )
+    .bind(report_ids)
+    .execute(&mut *conn)
+    .await
+    .map_err(internal)?;
+    sqlx::query(
+        "UPDATE approvals SET comments = NULL, policy_exception_notes = NULL
+         WHERE report_id = ANY($1)",
+    )
+    .bind(report_ids)
+    .execute(&mut *conn)
+    .await
+    .map_err(internal)?;
+    sqlx::query("UPDATE expense_reports SET anonymized_at = $2 WHERE id = ANY($1)")
+        .bind(report_ids)
+        .bind(Utc::now())
+        .execute(&mut *conn)
+        .await
+        .map_err(internal)?;
+    Ok(())
+}
+
+fn settled_statuses() -> Vec<&'static str> {
+    SETTLED_STATUSES.iter().map(ReportStatus::as_str).collect()
+}
+
+/// Deployment-wide settings are maintained by administrators of the
+/// operating organization.
+fn require_admin(actor: &AuthenticatedUser) -> Result<(), ServiceError> {
+    if actor.role != Role::Admin || !actor.is_operator() {
+        return Err(ServiceError::Forbidden);
+    }
+    Ok(())
+}
+
+fn map_run(row: PgRow) -> Result<RetentionRun, ServiceError> {
+    Ok(RetentionRun {
+        id: row.try_get("id").map_err(internal)?,
+        mode: row.try_get("mode").map_err(internal)?,
+        cutoff: row.try_get("cutoff").map_err(internal)?,
+        status: row.try_get("status").map_err(internal)?,
+        reports_anonymized: row.try_get("reports_anonymized").map_err(internal)?,
+        reports_deleted: row.try_get("reports_deleted").map_err(internal)?,
+        receipts_removed: row.try_get("receipts_removed").map_err(internal)?,
+        audit_logs_removed: row.try_get("audit_logs_removed").map_err(internal)?,
+        requested_by: row.try_get("requested_by").map_err(internal)?,
+        confirmed_by: row.try_get("confirmed_by").map_err(internal)?,
+        created_at: row.try_get("created_at").map_err(internal)?,
+        executed_at: row.try_get("executed_at").map_err(internal)?,
+    })
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
@@ -0,0 +1,614 @@
+//! HR roster imports.
+//!
+//! HR exports the roster as CSV with `hr_identifier`, `manager`,
+//! `department`, and `role` columns, where `manager` is the manager's HR
+//! identifier, and administrators post it to `/admin/employees/import`. Each
+//! row creates the employee in the administrator's organization or replaces
+//! the manager, department, and role of the one with that HR identifier;
+//! employees the file leaves out are not touched. Managers may be employees
+//! already on file or rows of the same import. The import is all or nothing:
+//! when any row is invalid, nothing is saved and the report says why.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use chrono::Utc;
+use serde::Serialize;
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{Department, Employee, Role},
+    infrastructure::{auth::AuthenticatedUser, state::AppState},
+};
+
+use super::{
+    audit, departments,
+    errors::ServiceError,
+    management_chain,
+    organizations::{normalize_department, EMPLOYEE_COLUMNS},
+};
+
+/// Columns the header row must name, in any order and case.
+const COLUMNS: [&str; 4] = ["hr_identifier", "manager", "department", "role"];
+
+/// One data row of a roster file, trimmed but otherwise as written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RosterRow {
+    pub line: u64,
+    pub hr_identifier: String,
+    pub manager: String,
+    pub department: String,
+    pub role: String,
+}
+
+/// Result of `RosterService::import`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RosterImport {
+    /// Whether the changes were saved. Nothing is saved when any row failed,
+    /// and the other rows then report what they would have done.
+    pub applied: bool,
+    pub created: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub failed: usize,
+    pub rows: Vec<RosterRowResult>,
+}
+
+/// What happened to one row of the file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RosterRowResult {
+    pub line: u64,
+    pub hr_identifier: String,
+    pub outcome: RosterOutcome,
+    /// The employee the row created or matched; unset for failed rows and
+    /// for rows that would have created an employee.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub employee_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RosterOutcome {
+    Created,
+    Updated,
+    Unchanged,
+    Failed,
+}
+
+/// Service importing HR rosters into `employees`.
+pub struct RosterService {
+    state: Arc<AppState>,
+}
+
+impl RosterService {
+    /// Constructs the service from shared application state.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// Creates or updates the administrator's organization's employees from
+    /// the roster in `csv`, in one transaction.
+    pub async fn import(
+        &self,
+        actor: &AuthenticatedUser,
+        csv: &str,
+    ) -> Result<RosterImport, ServiceError> {
+        if actor.role != Role::Admin {
+            return Err(ServiceError::Forbidden);
+        }
+        let rows = parse_roster_csv(csv)?;
+        if rows.is_empty() {
+            return Err(ServiceError::Validation(
+                "the file contains no employees".into(),
+            ));
+        }
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let report = apply_roster(&mut tx, Some(actor.employee_id), actor.org_id, &rows).await?;
+        if report.applied {
+            tx.commit().await.map_err(internal)?;
+            management_chain::invalidate(&self.state);
+        }
+        Ok(report)
+    }
+}
+
+/// Plans `rows` against `org_id`'s employees and, when every row is valid,
+/// writes them in `tx` with an audit entry per changed employee. The caller
+/// commits when the report says the import was applied.
+pub async fn apply_roster(
+    tx: &mut Transaction<'_, Postgres>,
+    performed_by: Option<Uuid>,
+    org_id: Uuid,
+    rows: &[RosterRow],
+) -> Result<RosterImport, ServiceError> {
+    let employees = lock_roster(tx, org_id).await?;
+    let keys: Vec<String> = rows.iter().map(|row| key(&row.hr_identifier)).collect();
+    let elsewhere: HashSet<String> = sqlx::query_scalar(
+        "SELECT UPPER(hr_identifier) FROM employees
+         WHERE org_id <> $1 AND UPPER(hr_identifier) = ANY($2)",
+    )
+    .bind(org_id)
+    .bind(&keys)
+    .fetch_all(tx.as_mut())
+    .await
+    .map_err(internal)?
+    .into_iter()
+    .collect();
+
+    let departments = departments::departments_by_code(tx.as_mut(), org_id).await?;
+    let plans = plan_roster(rows, &employees, &elsewhere, &departments);
+    let mut report = RosterImport::default();
+    for plan in &plans {
+        match &plan.action {
+            Ok(Action::Create) => report.created += 1,
+            Ok(Action::Update(_)) => report.updated += 1,
+            Ok(Action::Unchanged(_)) => report.unchanged += 1,
+            Err(_) => report.failed += 1,
+        }
+    }
+    report.applied = report.failed == 0;
+    if !report.applied {
+        report.rows = plans.iter().map(|plan| plan.result(None)).collect();
+        return Ok(report);
+    }
+
+    let mut ids: HashMap<String, Uuid> = employees
+        .iter()
+        .map(|employee| (key(&employee.hr_identifier), employee.id))
+        .collect();
+    let mut before = HashMap::new();
+    for plan in &plans {
+        match plan.action {
+            Ok(Action::Create) => {
+                let id = Uuid::new_v4();
+                sqlx::query(
+                    "INSERT INTO employees
+                         (id, org_id, hr_identifier, department, department_id, role, created_at)
+                     VALUES ($1,$2,$3,$4,$5,$6,$7)",
+                )
+                .bind(id)
+                .bind(org_id)
+                .bind(&plan.hr_identifier)
+                .bind(&plan.department)
+                .bind(plan.department_id)
+                .bind(plan.role)
+                .bind(Utc::now())
+                .execute(tx.as_mut())
+                .await
+                .map_err(conflict_or_internal)?;
+                ids.insert(key(&plan.hr_identifier), id);
+            }
+            Ok(Action::Update(id)) => {
+                before.insert(id, audit::EMPLOYEE.snapshot(tx.as_mut(), id).await?);
+            }
+            _ => {}
+        }
+    }
+    // Managers are set once every row exists, since a row may name a manager
+    // created further down the file.
+    for plan in &plans {
+        let Ok(Action::Create | Action::Update(_)) = plan.action else {
+            continue;
+        };
+        let id = ids[&key(&plan.hr_identifier)];
+        let manager_id = plan.manager.as_ref().map(|manager| ids[manager]);
+        sqlx::query(
+            "UPDATE employees
+             SET hr_identifier = $2, manager_id = $3, department = $4, department_id = $5,
+                 role = $6
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(&plan.hr_identifier)
+        .bind(manager_id)
+        .bind(&plan.department)
+        .bind(plan.department_id)
+        .bind(plan.role)
+        .execute(tx.as_mut())
+        .await
+        .map_err(conflict_or_internal)?;
+        let (event, old_value) = match before.remove(&id) {
+            Some(old_value) => (audit::UPDATED, old_value),
+            None => (audit::CREATED, None),
+        };
+        audit::record_change(
+            tx.as_mut(),
+            performed_by,
+            audit::EMPLOYEE,
+            id,
+            event,
+            old_value,
+        )
+        .await?;
+    }
+    report.rows = plans
+        .iter()
+        .map(|plan| plan.result(ids.get(&key(&plan.hr_identifier)).copied()))
+        .collect();
+    Ok(report)
+}
+
+/// Locks `org_id`'s roster against other imports until `tx` ends and loads
+/// its employees. Other organizations' imports touch other rows, so only
+/// imports for the same organization wait.
+pub async fn lock_roster(
+    tx: &mut Transaction<'_, Postgres>,
+    org_id: Uuid,
+) -> Result<Vec<Employee>, ServiceError> {
+    sqlx::query("SELECT id FROM organizations WHERE id = $1 FOR UPDATE")
+        .bind(org_id)
+        .execute(tx.as_mut())
+        .await
+        .map_err(internal)?;
+    sqlx::query_as::<_, Employee>(&format!(
+        "SELECT {EMPLOYEE_COLUMNS} FROM employees WHERE org_id = $1"
+    ))
+    .bind(org_id)
+    .fetch_all(tx.as_mut())
+    .await
+    .map_err(internal)
+}
+
+/// Parses a roster file. The first row is the header; columns are found by
+/// name, and blank rows are skipped.
+pub fn parse_roster_csv(data: &str) -> Result<Vec<RosterRow>, ServiceError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(data.as_bytes());
+    let headers = reader
+        .headers()
+        .map_err(|err| ServiceError::Validation(format!("invalid CSV: {err}")))?
+        .clone();
+    let names: Vec<String> = headers.iter().map(str::to_lowercase).collect();
+    let mut columns = [0; COLUMNS.len()];
+    for (column, name) in columns.iter_mut().zip(COLUMNS) {
+        *column = names
+            .iter()
+            .position(|found| found == name)
+            .ok_or_else(|| {
+                ServiceError::Validation(format!("the header row has no {name} column"))
+            })?;
+    }
+    let [hr_identifier, manager, department, role] = columns;
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record =
+            record.map_err(|err| ServiceError::Validation(format!("invalid CSV: {err}")))?;
+        if record.iter().all(str::is_empty) {
+            continue;
+        }
+        let field = |index: usize| record.get(index).unwrap_or_default().to_string();
+        rows.push(RosterRow {
+            line: record.position().map_or(0, |position| position.line()),
+            hr_identifier: field(hr_identifier),
+            manager: field(manager),
+            department: field(department),
+            role: field(role),
+        });
+    }
+    Ok(rows)
+}
+
+/// What a valid row does to its employee.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Create,
+    Update(Uuid),
+    Unchanged(Uuid),
+}
+
+/// A row checked against the roster on file.
+#[derive(Debug, Clone)]
+struct Plan {
+    line: u64,
+    hr_identifier: String,
+    /// Key of the manager's HR identifier.
+    manager: Option<String>,
+    /// Code of the department `department_id` names.
+    department: Option<String>,
+    department_id: Option<Uuid>,
+    role: Role,
+    action: Result<Action, String>,
+}
+
+impl Plan {
+    fn result(&self, employee_id: Option<Uuid>) -> RosterRowResult {
+        let (outcome, error) = match &self.action {
+            Ok(Action::Create) => (RosterOutcome::Created, None),
+            Ok(Action::Update(_)) => (RosterOutcome::Updated, None),
+            Ok(Action::Unchanged(_)) => (RosterOutcome::Unchanged, None),
+            Err(error) => (RosterOutcome::Failed, Some(error.clone())),
+        };
+        let employee_id = match self.action {
+            Ok(Action::Update(id) | Action::Unchanged(id)) => Some(id),
+            Ok(Action::Create) => employee_id,
+            Err(_) => None,
+        };
+        RosterRowResult {
+            line: self.line,
+            hr_identifier: self.hr_identifier.clone(),
+            outcome,
+            employee_id,
+            error,
+        }
+    }
+}
+
+/// Decides what each row does, given the organization's `employees`, the
+/// HR identifiers (as keys) taken in other organizations, and the
+/// organization's `departments` by upper-cased code.
+fn plan_roster(
+    rows: &[RosterRow],
+    employees: &[Employee],
+    elsewhere: &HashSet<String>,
+    departments: &HashMap<String, Department>,
+) -> Vec<Plan> {
+    let by_key: HashMap<String, &Employee> = employees
+        .iter()
+        .map(|employee| (key(&employee.hr_identifier), employee))
+        .collect();
+    let keys_by_id: HashMap<Uuid, String> = employees
+        .iter()
+        .map(|employee| (employee.id, key(&employee.hr_identifier)))
+        .collect();
+    let mut first_line: HashMap<String, u64> = HashMap::new();
+    for row in rows {
+        first_line
+            .entry(key(&row.hr_identifier))
+            .or_insert(row.line);
+    }
+
+    rows.iter()
+        .map(|row| {
+            let row_key = key(&row.hr_identifier);
+            let manager = (!row.manager.is_empty()).then(|| key(&row.manager));
+            let current = by_key.get(&row_key);
+            let department = normalize_department(Some(&row.department))
+                .map(|code| match departments.get(&code.to_uppercase()) {
+                    Some(department)
+                        if department.active
+                            || current.and_then(|employee| employee.department_id)
+                                == Some(department.id) =>
+                    {
+                        Ok(department)
+                    }
+                    Some(_) => Err(format!("department {code} is inactive")),
+                    None => Err(format!("department {code} not found")),
+                })
+                .transpose();
+            let role = Role::try_from(row.role.as_str());
+            let action = if row.hr_identifier.is_empty() {
+                Err("hr_identifier is required".to_string())
+            } else if first_line[&row_key] != row.line {
+                Err(format!(
+                    "hr_identifier is already on line {}",
+                    first_line[&row_key]
+                ))
+            } else if elsewhere.contains(&row_key) {
+                Err("hr_identifier belongs to another organization".to_string())
+            } else if let Err(err) = &role {
+                Err(err.to_string())
+            } else if manager.as_ref() == Some(&row_key) {
+                Err("an employee cannot manage themselves".to_string())
+            } else if manager.as_ref().is_some_and(|manager| {
+                !by_key.contains_key(manager) && !first_line.contains_key(manager)
+            }) {
+                Err(format!("manager {} not found", row.manager))
+            } else if let Err(err) = &department {
+                Err(err.clone())
+            } else {
+                let department = department.as_ref().ok().copied().flatten();
+                Ok(match current {
+                    None => Action::Create,
+                    Some(employee)
+                        if employee.hr_identifier == row.hr_identifier
+                            && employee.department_id
+                                == department.map(|department| department.id)
+                            && employee.department.as_deref()
+                                == department.map(|department| department.code.as_str())
+                            && role.as_ref().is_ok_and(|role| *role == employee.role)
+                            && employee.manager_id.and_then(|id| keys_by_id.get(&id))
+                                == manager.as_ref() =>
+                    {
+                        Action::Unchanged(employee.id)
+                    }
+                    Some(employee) => Action::Update(employee.id),
+                })
+            };
+            let department = department.ok().flatten();
+            Plan {
+                line: row.line,
+                hr_identifier: row.hr_identifier.clone(),
+                manager,
+                department: department.map(|department| department.code.clone()),
+                department_id: department.map(|department| department.id),
+                role: role.unwrap_or(Role::Employee),
+                action,
+            }
+        })
+        .collect()
+}
+
+/// HR identifiers match case-insensitively, as at sign-in.
+fn key(hr_identifier: &str) -> String {
+    hr_identifier.to_uppercase()
+}
+
+fn conflict_or_internal(err: sqlx::Error) -> ServiceError {
+    match err {
+        sqlx::Error::Database(db) if db.code().as_deref() == Some("23505") => {
+            ServiceError::Conflict
+        }
+        other => internal(other),
+    }
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORG: Uuid = Uuid::from_u128(1);
+    const OPS: Uuid = Uuid::from_u128(50);
+
+    fn employee(id: u128, hr_identifier: &str, manager_id: Option<u128>) -> Employee {
+        Employee {
+            id: Uuid::from_u128(id),
+            org_id: ORG,
+            hr_identifier: hr_identifier.into(),
+            manager_id: manager_id.map(Uuid::from_u128),
+            department: Some("Ops".into()),
+            department_id: Some(OPS),
+            cost_center_id: None,
+            role: Role::Employee,
+            created_at: Utc::now(),
+            deactivated_at: None,
+        }
+    }
+
+    fn departments() -> HashMap<String, Department> {
+        [
+            (OPS, "Ops", true),
+            (Uuid::from_u128(51), "FIN", true),
+            (Uuid::from_u128(52), "Legacy", false),
+        ]
+        .into_iter()
+        .map(|(id, code, active)| {
+            (
+                code.to_uppercase(),
+                Department {
+                    id,
+                    org_id: ORG,
+                    code: code.into(),
+                    name: code.into(),
+                    active,
+                    created_at: Utc::now(),
+                    updated_at: Utc::now(),
+                },
+            )
+        })
+        .collect()
+    }
+
+    fn actions(plans: &[Plan]) -> Vec<Result<Action, String>> {
+        plans.iter().map(|plan| plan.action.clone()).collect()
+    }
+
+    #[test]
+    fn parses_columns_by_name_and_skips_blank_rows() {
+        let rows = parse_roster_csv(
+            "Role,HR_Identifier,Department,Manager\n manager , E100 ,Ops,\n,,,\nemployee,E101,,e100\n",
+        )
+        .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                RosterRow {
+                    line: 2,
+                    hr_identifier: "E100".into(),
+                    manager: String::new(),
+                    department: "Ops".into(),
+                    role: "manager".into(),
+                },
+                RosterRow {
+                    line: 4,
+                    hr_identifier: "E101".into(),
+                    manager: "e100".into(),
+                    department: String::new(),
+                    role: "employee".into(),
+                },
+            ]
+        );
+
+        assert!(matches!(
+            parse_roster_csv("hr_identifier,manager,role\nE100,,employee\n"),
+            Err(ServiceError::Validation(message)) if message.contains("department")
+        ));
+    }
+
+    #[test]
+    fn plans_creates_updates_and_unchanged_rows() {
+        let employees = [employee(1, "E100", None), employee(2, "E101", Some(1))];
+        let rows = parse_roster_csv(
+            "hr_identifier,manager,department,role\n\
+             e101,E100,Ops,employee\n\
+             E100,E102,Ops,employee\n\
+             E102,,fin,manager\n",
+        )
+        .unwrap();
+        let plans = plan_roster(&rows, &employees, &HashSet::new(), &departments());
+        assert_eq!(
+            actions(&plans),
+            vec![
+                // The case of the HR identifier changed.
+                Ok(Action::Update(Uuid::from_u128(2))),
+                // Reports to an employee created further down the file.
+                Ok(Action::Update(Uuid::from_u128(1))),
+                Ok(Action::Create),
+            ]
+        );
+        assert_eq!(plans[1].manager.as_deref(), Some("E102"));
+        assert_eq!(plans[2].role, Role::Manager);
+        // Departments are matched by code and stored as it is written.
+        assert_eq!(plans[2].department.as_deref(), Some("FIN"));
+        assert_eq!(plans[2].department_id, Some(Uuid::from_u128(51)));
+
+        let rows =
+            parse_roster_csv("hr_identifier,manager,department,role\nE101,e100, Ops ,EMPLOYEE\n")
+                .unwrap();
+        assert_eq!(
+            actions(&plan_roster(
+                &rows,
+                &employees,
+                &HashSet::new(),
+                &departments()
+            )),
+            vec![Ok(Action::Unchanged(Uuid::from_u128(2)))]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_rows_with_a_reason() {
+        let rows = parse_roster_csv(
+            "hr_identifier,manager,department,role\n\
+             ,,,employee\n\
+             E200,,,employee\n\
+             e200,,,employee\n\
+             E201,,,intern\n\
+             E202,E202,,employee\n\
+             E203,E999,,employee\n\
+             E204,,,employee\n\
+             E205,,Sales,employee\n\
+             E206,,legacy,employee\n",
+        )
+        .unwrap();
+        let elsewhere = HashSet::from(["E204".to_string()]);
+        let errors: Vec<String> = plan_roster(&rows, &[], &elsewhere, &departments())
+            .into_iter()
+            .filter_map(|plan| plan.action.err())
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                "hr_identifier is required",
+                "hr_identifier is already on line 3",
+                "unsupported role value: intern",
+                "an employee cannot manage themselves",
+                "manager E999 not found",
+                "hr_identifier belongs to another organization",
+                "department Sales not found",
+                "department legacy is inactive",
+            ]
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! Ranked search over expense item merchants and descriptions.
+//!
+//! Items match a query when their `search_vector` (merchant weighted above
+//! description, see the `search_indexes` migration) matches it as a web-style
+//! full-text query, or when the text appears anywhere in the merchant or
+//! description. Both kinds of match are served by GIN indexes, full-text and
+//! trigram, so neither scans `expense_items`. Hits are ranked by full-text
+//! relevance plus trigram similarity, so a close merchant name ranks well
+//! even when no whole word matches.
+
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgRow, Row};
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{ExpenseCategory, ReportStatus, Role},
+    infrastructure::{auth::AuthenticatedUser, state::AppState},
+};
+
+use super::{errors::ServiceError, finance::escape_like};
+
+/// Hits returned when the caller does not pass `limit`, and the most it may
+/// ask for.
+const DEFAULT_LIMIT: i64 = 25;
+const MAX_LIMIT: i64 = 100;
+
+/// Shortest and longest query accepted, in characters after trimming.
+const MIN_QUERY_CHARS: usize = 2;
+const MAX_QUERY_CHARS: usize = 200;
+
+/// Parameters accepted by `GET /expenses/search`.
+#[derive(Debug, Default, Deserialize)]
+pub struct SearchParams {
+    pub q: String,
+    pub limit: Option<i64>,
+}
+
+/// A validated search: the text as typed, for full-text and similarity
+/// matching, and as an escaped `ILIKE` substring pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchQuery {
+    text: String,
+    pattern: String,
+}
+
+impl SearchQuery {
+    pub fn parse(raw: &str) -> Result<Self, ServiceError> {
+        let text = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+        let chars = text.chars().count();
+        if !(MIN_QUERY_CHARS..=MAX_QUERY_CHARS).contains(&chars) {
+            return Err(ServiceError::Validation(format!(
+                "q must be between {MIN_QUERY_CHARS} and {MAX_QUERY_CHARS} characters"
+            )));
+        }
+        let pattern = format!("%{}%", escape_like(&text));
+        Ok(Self { text, pattern })
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+}
+
+/// An item matching a search, with the report it belongs to.
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemSearchHit {
+    pub item_id: Uuid,
+    pub report_id: Uuid,
+    pub employee_id: Uuid,
+    pub report_status: ReportStatus,
+    pub expense_date: NaiveDate,
+    pub category: ExpenseCategory,
+    pub merchant: Option<String>,
+    pub description: Option<String>,
+    pub amount_cents: i64,
+    /// Relevance to the query; higher is better. Only comparable between
+    /// hits of the same search.
+    pub rank: f64,
+}
+
+/// Service searching expense items.
+pub struct SearchService {
+    state: Arc<AppState>,
+}
+
+impl SearchService {
+    /// Constructs the service from shared application state.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// Items whose merchant or description match `params.q`, best match
+    /// first, then newest expense. Employees search their own reports;
+    /// managers, finance, and admins search everyone's in their organization,
+    /// as they can open any of its reports. Deleted items and reports are left
+    /// out.
+    pub async fn search_items(
+        &self,
+        actor: &AuthenticatedUser,
+        params: &SearchParams,
+    ) -> Result<Vec<ItemSearchHit>, ServiceError> {
+        let query = SearchQuery::parse(&params.q)?;
+        let owner = (actor.role == Role::Employee).then_some(actor.employee_id);
+
+        sqlx::query(
+            "WITH q AS (SELECT websearch_to_tsquery('english', $1) AS ts)
+             SELECT i.id, i.report_id, r.employee_id, r.status, i.expense_date, i.category,
+                    i.merchant, i.description, i.amount_cents,
+                    (ts_rank(i.search_vector, q.ts)
+                     + GREATEST(similarity(COALESCE(i.merchant, ''), $1),
+                                similarity(COALESCE(i.description, ''), $1)))::float8 AS rank
+             FROM expense_items i
+             JOIN expense_reports r ON r.id = i.report_id
+             CROSS JOIN q
+             WHERE (i.search_vector @@ q.ts OR i.merchant ILIKE $2 OR i.description ILIKE $2)
+               AND i.deleted_at IS NULL AND r.deleted_at IS NULL
+               AND ($3::uuid IS NULL OR r.employee_id = $3)
+               AND r.org_id = $5
+             ORDER BY rank DESC, i.expense_date DESC, i.id
+             LIMIT $4",
+        )
+        .bind(query.text())
+        .bind(query.pattern())
+        .bind(owner)
+        .bind(params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT))
+        .bind(actor.org_id)
+        .fetch_all(&self.state.read_pool)
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .map(map_hit)
+        .collect()
+    }
+}
+
+fn map_hit(row: PgRow) -> Result<ItemSearchHit, ServiceError> {
+    Ok(ItemSearchHit {
+        item_id: row.try_get("id").map_err(internal)?,
+        report_id: row.try_get("report_id").map_err(internal)?,
+        employee_id: row.try_get("employee_id").map_err(internal)?,
+        report_status: row.try_get("status").map_err(internal)?,
+        expense_date: row.try_get("expense_date").map_err(internal)?,
+        category: row.try_get("category").map_err(internal)?,
+        merchant: row.try_get("merchant").map_err(internal)?,
+        description: row.try_get("description").map_err(internal)?,
+        amount_cents: row.try_get("amount_cents").map_err(internal)?,
+        rank: row.try_get("rank").map_err(internal)?,
+    })
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queries_collapse_whitespace_escape_wildcards_and_enforce_length() {
+        let query = SearchQuery::parse("  Blue   Bottle_50% ").unwrap();
+        assert_eq!(query.text(), "Blue Bottle_50%");
+        assert_eq!(query.pattern(), "%Blue Bottle\\_50\\%%");
+
+        assert!(matches!(
+            SearchQuery::parse(" a "),
+            Err(ServiceError::Validation(_))
+        ));
+        assert!(matches!(
+            SearchQuery::parse(&"x".repeat(201)),
+            Err(ServiceError::Validation(_))
+        ));
+    }
+}
@@ -0,0 +1,367 @@
+//! Deactivation of employees who have left.
+//!
+//! Administrators deactivate an employee through
+//! `POST /admin/employees/:id/deactivate`, and the HR sync deactivates the
+//! employees the HR system no longer lists as employed. One transaction sets
+//! `employees.deactivated_at` and hands off everything still waiting on the
+//! employee:
+//!
+//! * their open drafts (`draft` and `needs_changes`) are flagged with
+//!   `owner_deactivated_at`;
+//! * their direct reports move to their own manager, which reroutes the
+//!   reports awaiting their approval, and the policy exception reviews
+//!   assigned to them go to that manager too (or to administrators when they
+//!   have none);
+//! * their department head assignments are removed and their unused
+//!   approval links expired.
+//!
+//! Deactivated employees can no longer sign in or record decisions.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{PgConnection, Row};
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{Employee, ReportStatus, Role},
+    infrastructure::{auth::AuthenticatedUser, state::AppState},
+};
+
+use super::{
+    audit, errors::ServiceError, management_chain, notifications, organizations::EMPLOYEE_COLUMNS,
+};
+
+/// Notification kind queued for the manager who takes over a deactivated
+/// employee's pending approvals.
+pub const APPROVALS_REASSIGNED_KIND: &str = "approvals_reassigned";
+
+/// What deactivating an employee changed.
+#[derive(Debug, Clone, Serialize)]
+pub struct Termination {
+    pub employee: Employee,
+    /// Their open drafts, now flagged with `owner_deactivated_at`.
+    pub flagged_drafts: Vec<Uuid>,
+    /// Employees who reported to them and now report to their manager.
+    pub reassigned_employees: Vec<Uuid>,
+    /// Reports that were awaiting their approval or exception review.
+    pub rerouted_reports: Vec<Uuid>,
+    /// Departments they no longer head.
+    pub revoked_department_heads: Vec<String>,
+}
+
+/// Service behind the deactivation route.
+pub struct TerminationService {
+    state: Arc<AppState>,
+}
+
+impl TerminationService {
+    /// Constructs the service from shared application state.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// Deactivates an employee of the administrator's organization. Employees
+    /// of other organizations are reported as not found, and one already
+    /// deactivated is a conflict. Administrators cannot deactivate
+    /// themselves.
+    pub async fn deactivate(
+        &self,
+        actor: &AuthenticatedUser,
+        employee_id: Uuid,
+    ) -> Result<Termination, ServiceError> {
+        if actor.role != Role::Admin {
+            return Err(ServiceError::Forbidden);
+        }
+        if actor.employee_id == employee_id {
+            return Err(ServiceError::Validation(
+                "administrators cannot deactivate themselves".into(),
+            ));
+        }
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let termination = deactivate(
+            &mut tx,
+            Some(actor.employee_id),
+            actor.org_id,
+            employee_id,
+            Utc::now(),
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        management_chain::invalidate(&self.state);
+        Ok(termination)
+    }
+}
+
+/// Deactivates `org_id`'s employee `employee_id` as of `now` on the caller's
+/// transaction; the caller invalidates the management-chain cache once it
+/// commits.
+pub async fn deactivate(
+    conn: &mut PgConnection,
+    performed_by: Option<Uuid>,
+    org_id: Uuid,
+    employee_id: Uuid,
+    now: DateTime<Utc>,
+) -> Result<Termination, ServiceError> {
+    let employee = sqlx::query_as::<_, Employee>(&format!(
+        "SELECT {EMPLOYEE_COLUMNS} FROM employees WHERE id = $1 AND org_id = $2 FOR UPDATE"
+    ))
+    .bind(employee_id)
+    .bind(org_id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(internal)?
+    .ok_or(ServiceError::NotFound)?;
+    if employee.deactivated_at.is_some() {
+        return Err(ServiceError::Conflict);
+    }
+    let successor = employee.manager_id;
+
+    let before = audit::EMPLOYEE.snapshot(&mut *conn, employee_id).await?;
+    let employee = sqlx::query_as::<_, Employee>(&format!(
+        "UPDATE employees SET deactivated_at = $2 WHERE id = $1 RETURNING {EMPLOYEE_COLUMNS}"
+    ))
+    .bind(employee_id)
+    .bind(now)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(internal)?;
+    audit::record_change(
+        &mut *conn,
+        performed_by,
+        audit::EMPLOYEE,
+        employee_id,
+        audit::UPDATED,
+        before,
+    )
+    .await?;
+
+    let flagged_drafts = flag_drafts(conn, performed_by, employee_id, now).await?;
+    let reassigned_employees = reassign_reports(conn, performed_by, employee_id, successor).await?;
+    let mut rerouted_reports: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT r.id FROM expense_reports r
+         JOIN employees e ON e.id = r.employee_id
+         WHERE e.id = ANY($1) AND r.status = $2 AND r.deleted_at IS NULL
+         ORDER BY r.submitted_at, r.id",
+    )
+    .bind(&reassigned_employees)
+    .bind(ReportStatus::Submitted)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(internal)?;
+    let exception_reviews =
+        reroute_exception_reviews(conn, performed_by, employee_id, successor, now).await?;
+    rerouted_reports.extend(exception_reviews);
+    let revoked_department_heads = revoke_department_heads(conn, performed_by, employee_id).await?;
+    sqlx::query(
+        "UPDATE approval_action_tokens SET expires_at = LEAST(expires_at, $2)
+         WHERE approver_id = $1 AND used_at IS NULL",
+    )
+    .bind(employee_id)
+    .bind(now)
+    .execute(&mut *conn)
+    .await
+    .map_err(internal)?;
+
+    if let (Some(successor), false) = (successor, rerouted_reports.is_empty()) {
+        let payload = serde_json::json!({
+            "deactivated_employee_id": employee_id,
+            "hr_identifier": employee.hr_identifier,
+            "reports": rerouted_reports,
+        });
+        notifications::enqueue(&mut *conn, successor, APPROVALS_REASSIGNED_KIND, payload).await?;
+    }
+
+    Ok(Termination {
+        employee,
+        flagged_drafts,
+        reassigned_employees,
+        rerouted_reports,
+        revoked_department_heads,
+    })
+}
+
+/// Fails with `ServiceError::Forbidden` when `employee_id` was deactivated;
+/// their tokens stay valid until they expire, so approval checks the actor.
+pub async fn ensure_active(conn: &mut PgConnection, employee_id: Uuid) -> Result<(), ServiceError> {
+    let deactivated: bool = sqlx::query_scalar(
+        "SELECT EXISTS (
+             SELECT 1 FROM employees WHERE id = $1 AND deactivated_at IS NOT NULL
+         )",
+    )
+    .bind(employee_id)
+    .fetch_one(conn)
+    .await
+    .map_err(internal)?;
+    if deactivated {
+        return Err(ServiceError::Forbidden);
+    }
+    Ok(())
+}
+
+/// Flags the employee's drafts and reports sent back to them for changes.
+async fn flag_drafts(
+    conn: &mut PgConnection,
+    performed_by: Option<Uuid>,
+    employee_id: Uuid,
+    now: DateTime<Utc>,
+) -> Result<Vec<Uuid>, ServiceError> {
+    let drafts: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT id FROM expense_reports
+         WHERE employee_id = $1 AND status = ANY($2) AND deleted_at IS NULL
+         ORDER BY reporting_period_start, id",
+    )
+    .bind(employee_id)
+    .bind([ReportStatus::Draft, ReportStatus::NeedsChanges])
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(internal)?;
+    for &report_id in &drafts {
+        let before = audit::EXPENSE_REPORT
+            .snapshot(&mut *conn, report_id)
+            .await?;
+        sqlx::query("UPDATE expense_reports SET owner_deactivated_at = $2 WHERE id = $1")
+            .bind(report_id)
+            .bind(now)
+            .execute(&mut *conn)
+            .await
+            .map_err(internal)?;
+        audit::record_change(
+            &mut *conn,
+            performed_by,
+            audit::EXPENSE_REPORT,
+            report_id,
+            audit::UPDATED,
+            before,
+        )
+        .await?;
+    }
+    Ok(drafts)
+}
+
+/// Moves the employee's direct reports to `successor`, their own manager.
+async fn reassign_reports(
+    conn: &mut PgConnection,
+    performed_by: Option<Uuid>,
+    employee_id: Uuid,
+    successor: Option<Uuid>,
+) -> Result<Vec<Uuid>, ServiceError> {
+    let reports: Vec<Uuid> =
+        sqlx::query_scalar("SELECT id FROM employees WHERE manager_id = $1 ORDER BY hr_identifier")
+            .bind(employee_id)
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(internal)?;
+    for &report_id in &reports {
+        let before = audit::EMPLOYEE.snapshot(&mut *conn, report_id).await?;
+        // A manager who reported to the deactivated employee does not
+        // become their own manager.
+        sqlx::query("UPDATE employees SET manager_id = NULLIF($2, id) WHERE id = $1")
+            .bind(report_id)
+            .bind(successor)
+            .execute(&mut *conn)
+            .await
+            .map_err(internal)?;
+        audit::record_change(
+            &mut *conn,
+            performed_by,
+            audit::EMPLOYEE,
+            report_id,
+            audit::UPDATED,
+            before,
+        )
+        .await?;
+    }
+    Ok(reports)
+}
+
+/// Hands the policy exception reviews assigned to the employee to
+/// `successor`, or to administrators when there is none or it submitted the
+/// report.
+async fn reroute_exception_reviews(
+    conn: &mut PgConnection,
+    performed_by: Option<Uuid>,
+    employee_id: Uuid,
+    successor: Option<Uuid>,
+    now: DateTime<Utc>,
+) -> Result<Vec<Uuid>, ServiceError> {
+    let reviews: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT id FROM expense_reports
+         WHERE exception_approver_id = $1 AND status = $2 AND deleted_at IS NULL
+         ORDER BY submitted_at, id",
+    )
+    .bind(employee_id)
+    .bind(ReportStatus::ExceptionReview)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(internal)?;
+    for &report_id in &reviews {
+        let before = audit::EXPENSE_REPORT
+            .snapshot(&mut *conn, report_id)
+            .await?;
+        sqlx::query(
+            "UPDATE expense_reports
+             SET exception_approver_id = NULLIF($2, employee_id), version = version + 1,
+                 updated_at = $3
+             WHERE id = $1",
+        )
+        .bind(report_id)
+        .bind(successor)
+        .bind(now)
+        .execute(&mut *conn)
+        .await
+        .map_err(internal)?;
+        audit::record_change(
+            &mut *conn,
+            performed_by,
+            audit::EXPENSE_REPORT,
+            report_id,
+            audit::UPDATED,
+            before,
+        )
+        .await?;
+    }
+    Ok(reviews)
+}
+
+/// Removes the employee's department head assignments and returns their
+/// departments.
+async fn revoke_department_heads(
+    conn: &mut PgConnection,
+    performed_by: Option<Uuid>,
+    employee_id: Uuid,
+) -> Result<Vec<String>, ServiceError> {
+    let heads = sqlx::query(
+        "SELECT id, department FROM department_heads WHERE employee_id = $1 ORDER BY department",
+    )
+    .bind(employee_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(internal)?;
+    let mut departments = Vec::with_capacity(heads.len());
+    for head in heads {
+        let head_id: Uuid = head.get("id");
+        let before = audit::DEPARTMENT_HEAD.snapshot(&mut *conn, head_id).await?;
+        sqlx::query("DELETE FROM department_heads WHERE id = $1")
+            .bind(head_id)
+            .execute(&mut *conn)
+            .await
+            .map_err(internal)?;
+        audit::record_change(
+            &mut *conn,
+            performed_by,
+            audit::DEPARTMENT_HEAD,
+            head_id,
+            audit::DELETED,
+            before,
+        )
+        .await?;
+        departments.push(head.get("department"));
+    }
+    Ok(departments)
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
@@ -0,0 +1,197 @@
+//! Trip pre-approval.
+//!
+//! Employees record a trip through `POST /trips` before travelling and their
+//! manager approves or rejects it. Reports created with the trip's
+//! `trip_id` are evaluated with its dates as working days, so weekend and
+//! holiday expenses incurred on an approved trip are not flagged.
+
+use std::sync::Arc;
+
+use chrono::{NaiveDate, Utc};
+use serde::Deserialize;
+use sqlx::{postgres::PgRow, Row};
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{Role, Trip, TripStatus},
+    infrastructure::{auth::AuthenticatedUser, state::AppState},
+};
+
+use super::{audit, errors::ServiceError};
+
+/// Payload accepted by `POST /trips`.
+#[derive(Debug, Deserialize)]
+pub struct TripRequest {
+    pub purpose: String,
+    #[serde(default)]
+    pub destination: Option<String>,
+    pub departure_date: NaiveDate,
+    pub return_date: NaiveDate,
+}
+
+/// Payload accepted by `POST /trips/:id/review`.
+#[derive(Debug, Deserialize)]
+pub struct TripReviewRequest {
+    pub approve: bool,
+}
+
+/// Service managing the `trips` table.
+pub struct TripService {
+    state: Arc<AppState>,
+}
+
+impl TripService {
+    /// Constructs the service from shared application state.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// Lists trips newest departure first: the actor's own and, for managers,
+    /// their direct reports'. Finance and administrators see every trip in
+    /// their organization.
+    pub async fn list(&self, actor: &AuthenticatedUser) -> Result<Vec<Trip>, ServiceError> {
+        let everyone = matches!(actor.role, Role::Finance | Role::Admin);
+        sqlx::query(
+            "SELECT t.* FROM trips t
+             JOIN employees e ON e.id = t.employee_id
+             WHERE ($2 AND e.org_id = $3) OR t.employee_id = $1 OR e.manager_id = $1
+             ORDER BY t.departure_date DESC, t.id",
+        )
+        .bind(actor.employee_id)
+        .bind(everyone)
+        .bind(actor.org_id)
+        .fetch_all(&self.state.pool)
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .map(map_trip)
+        .collect()
+    }
+
+    /// Records a pending trip for the actor.
+    pub async fn create(
+        &self,
+        actor: &AuthenticatedUser,
+        payload: TripRequest,
+    ) -> Result<Trip, ServiceError> {
+        let purpose = payload.purpose.trim();
+        if purpose.is_empty() {
+            return Err(ServiceError::Validation("purpose is required".into()));
+        }
+        if payload.return_date < payload.departure_date {
+            return Err(ServiceError::Validation(
+                "return_date must be on or after departure_date".into(),
+            ));
+        }
+        let row = sqlx::query(
+            "INSERT INTO trips
+                (id, employee_id, purpose, destination, departure_date, return_date, status,
+                 created_at)
+             VALUES ($1,$2,$3,$4,$5,$6,$7,$8)
+             RETURNING *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(actor.employee_id)
+        .bind(purpose)
+        .bind(
+            payload
+                .destination
+                .as_deref()
+                .map(str::trim)
+                .filter(|destination| !destination.is_empty()),
+        )
+        .bind(payload.departure_date)
+        .bind(payload.return_date)
+        .bind(TripStatus::Pending.as_str())
+        .bind(Utc::now())
+        .fetch_one(&self.state.pool)
+        .await
+        .map_err(internal)?;
+        map_trip(row)
+    }
+
+    /// Approves or rejects a pending trip. Only the traveller's manager or an
+    /// administrator of their organization may review it, and never their own
+    /// trip.
+    pub async fn review(
+        &self,
+        actor: &AuthenticatedUser,
+        trip_id: Uuid,
+        payload: TripReviewRequest,
+    ) -> Result<Trip, ServiceError> {
+        let trip = sqlx::query(
+            "SELECT t.employee_id, t.status, e.manager_id
+             FROM trips t
+             JOIN employees e ON e.id = t.employee_id
+             WHERE t.id = $1 AND e.org_id = $2",
+        )
+        .bind(trip_id)
+        .bind(actor.org_id)
+        .fetch_optional(&self.state.pool)
+        .await
+        .map_err(internal)?
+        .ok_or(ServiceError::NotFound)?;
+        let employee_id: Uuid = trip.get("employee_id");
+        let manager_id: Option<Uuid> = trip.get("manager_id");
+        let may_review = manager_id == Some(actor.employee_id) || actor.role == Role::Admin;
+        if !may_review || employee_id == actor.employee_id {
+            return Err(ServiceError::Forbidden);
+        }
+
+        let status = if payload.approve {
+            TripStatus::Approved
+        } else {
+            TripStatus::Rejected
+        };
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let before = audit::TRIP.snapshot(tx.as_mut(), trip_id).await?;
+        let row = sqlx::query(
+            "UPDATE trips SET status = $2, reviewed_by = $3, reviewed_at = $4
+             WHERE id = $1 AND status = $5
+             RETURNING *",
+        )
+        .bind(trip_id)
+        .bind(status.as_str())
+        .bind(actor.employee_id)
+        .bind(Utc::now())
+        .bind(TripStatus::Pending.as_str())
+        .fetch_optional(tx.as_mut())
+        .await
+        .map_err(internal)?
+        .ok_or(ServiceError::Conflict)?;
+        let trip = map_trip(row)?;
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::TRIP,
+            trip_id,
+            audit::STATUS_CHANGED,
+            before,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        Ok(trip)
+    }
+}
+
+fn map_trip(row: PgRow) -> Result<Trip, ServiceError> {
+    let status: String = row.try_get("status").map_err(internal)?;
+    Ok(Trip {
+        id: row.get("id"),
+        employee_id: row.get("employee_id"),
+        purpose: row.get("purpose"),
+        destination: row.get("destination"),
+        departure_date: row.get("departure_date"),
+        return_date: row.get("return_date"),
+        status: TripStatus::parse(&status).ok_or_else(|| {
+            ServiceError::Internal(format!("trips.status has unknown value {status}"))
+        })?,
+        reviewed_by: row.get("reviewed_by"),
+        reviewed_at: row.get("reviewed_at"),
+        created_at: row.get("created_at"),
+    })
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
@@ -0,0 +1,607 @@
+//! Outbound webhook subscriptions and event delivery.
+//!
+//! Administrators register endpoints for workflow events through
+//! `/admin/webhooks`. While `webhooks.enabled` is set, `emit` records one
+//! `webhook_deliveries` row per active subscription to the event, inside the
+//! transaction that produced it, and the `webhooks` job posts them through
+//! `infrastructure::webhooks`. Delivery is at least once, so receivers should
+//! ignore an event `id` they have already handled.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgRow, PgExecutor, Row};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    domain::models::Role,
+    infrastructure::{
+        auth::AuthenticatedUser, notifications::chat::validate_webhook_url, state::AppState,
+        webhooks,
+    },
+};
+
+use super::{audit, errors::ServiceError, outbox};
+
+/// A report was submitted for approval.
+pub const REPORT_SUBMITTED_EVENT: &str = "report.submitted";
+/// A report finished manager (and any exception) review.
+pub const REPORT_APPROVED_EVENT: &str = "report.approved";
+/// NetSuite accepted a finalized batch.
+pub const BATCH_EXPORTED_EVENT: &str = "batch.exported";
+
+/// Every event a subscription can ask for.
+pub const EVENT_TYPES: [&str; 3] = [
+    REPORT_SUBMITTED_EVENT,
+    REPORT_APPROVED_EVENT,
+    BATCH_EXPORTED_EVENT,
+];
+
+/// Deliveries claimed by one run.
+const DELIVERY_LIMIT: i64 = 100;
+
+/// How long a claimed delivery is hidden from other runs. A run that crashes
+/// mid-request leaves it due again once the lease runs out.
+const CLAIM_LEASE_SECONDS: i64 = 300;
+
+/// Deliveries listed when the caller does not pass `limit`, and the most it
+/// may ask for.
+const DEFAULT_DELIVERY_PAGE: i64 = 50;
+const MAX_DELIVERY_PAGE: i64 = 500;
+
+/// Longest error message stored for a failed attempt.
+const MAX_ERROR_CHARS: usize = 2_000;
+
+/// Outcome of one delivery run.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DeliverySummary {
+    pub delivered: usize,
+    pub failed: usize,
+}
+
+/// A registered endpoint. The signing secret is only returned on creation.
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookSubscription {
+    pub id: Uuid,
+    pub url: String,
+    pub event_types: Vec<String>,
+    pub description: Option<String>,
+    pub active: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Response to `POST /admin/webhooks`, carrying the secret receivers verify
+/// signatures with.
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatedWebhookSubscription {
+    #[serde(flatten)]
+    pub subscription: WebhookSubscription,
+    pub secret: String,
+}
+
+/// Payload accepted by `POST /admin/webhooks`.
+#[derive(Debug, Deserialize)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    pub event_types: Vec<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// Payload accepted by `PUT /admin/webhooks/:id`.
+#[derive(Debug, Deserialize)]
+pub struct UpdateWebhookRequest {
+    pub url: String,
+    pub event_types: Vec<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub active: bool,
+}
+
+/// One event queued for one subscription, with its latest attempt.
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub subscription_id: Uuid,
+    pub event_id: Uuid,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub status: String,
+    pub attempts: i32,
+    /// HTTP status of the last response, if the receiver answered.
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+/// Filters accepted by `GET /admin/webhooks/:id/deliveries`.
+#[derive(Debug, Default, Deserialize)]
+pub struct DeliveryQuery {
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// Service managing webhook subscriptions and their delivery log.
+pub struct WebhookService {
+    state: Arc<AppState>,
+}
+
+impl WebhookService {
+    /// Constructs the service from shared application state.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// Lists subscriptions, oldest first. Restricted to administrators.
+    pub async fn list(
+        &self,
+        actor: &AuthenticatedUser,
+    ) -> Result<Vec<WebhookSubscription>, ServiceError> {
+        require_admin(actor)?;
+        sqlx::query("SELECT * FROM webhook_subscriptions ORDER BY created_at")
+            .fetch_all(&self.state.pool)
+            .await
+            .map_err(internal)?
+            .into_iter()
+            .map(map_subscription)
+            .collect()
+    }
+
+    /// Registers an endpoint with a freshly generated signing secret.
+    /// Restricted to administrators.
+    pub async fn create(
+        &self,
+        actor: &AuthenticatedUser,
+        payload: CreateWebhookRequest,
+    ) -> Result<CreatedWebhookSubscription, ServiceError> {
+        require_admin(actor)?;
+        let (url, event_types) = validate(&payload.url, &payload.event_types)?;
+        let secret = webhooks::generate_secret();
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let row = sqlx::query(
+            "INSERT INTO webhook_subscriptions
+                (id, url, secret, event_types, description, active, created_by, created_at, updated_at)
+             VALUES ($1,$2,$3,$4,$5,TRUE,$6,$7,$7)
+             RETURNING *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(url)
+        .bind(&secret)
+        .bind(&event_types)
+        .bind(description(payload.description.as_deref()))
+        .bind(actor.employee_id)
+        .bind(Utc::now())
+        .fetch_one(tx.as_mut())
+        .await
+        .map_err(internal)?;
+        let subscription = map_subscription(row)?;
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::WEBHOOK_SUBSCRIPTION,
+            subscription.id,
+            audit::CREATED,
+            None,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        Ok(CreatedWebhookSubscription {
+            subscription,
+            secret,
+        })
+    }
+
+    /// Replaces a subscription's endpoint, events, and status, keeping its
+    /// secret. Deliveries already queued still go out. Restricted to
+    /// administrators.
+    pub async fn update(
+        &self,
+        actor: &AuthenticatedUser,
+        subscription_id: Uuid,
+        payload: UpdateWebhookRequest,
+    ) -> Result<WebhookSubscription, ServiceError> {
+        require_admin(actor)?;
+        let (url, event_types) = validate(&payload.url, &payload.event_types)?;
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let before = audit::WEBHOOK_SUBSCRIPTION
+            .snapshot(tx.as_mut(), subscription_id)
+            .await?;
+        let row = sqlx::query(
+            "UPDATE webhook_subscriptions
+             SET url = $2, event_types = $3, description = $4, active = $5, updated_at = $6
+             WHERE id = $1
+             RETURNING *",
+        )
+        .bind(subscription_id)
+        .bind(url)
+        .bind(&event_types)
+        .bind(description(payload.description.as_deref()))
+        .bind(payload.active)
+        .bind(Utc::now())
+        .fetch_optional(tx.as_mut())
+        .await
+        .map_err(internal)?
+        .ok_or(ServiceError::NotFound)?;
+        let subscription = map_subscription(row)?;
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::WEBHOOK_SUBSCRIPTION,
+            subscription_id,
+            audit::UPDATED,
+            before,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        Ok(subscription)
+    }
+
+    /// Deletes a subscription along with its delivery log. Restricted to
+    /// administrators.
+    pub async fn delete(
+        &self,
+        actor: &AuthenticatedUser,
+        subscription_id: Uuid,
+    ) -> Result<(), ServiceError> {
+        require_admin(actor)?;
+        let mut tx = self.state.pool.begin().await.map_err(internal)?;
+        let before = audit::WEBHOOK_SUBSCRIPTION
+            .snapshot(tx.as_mut(), subscription_id)
+            .await?;
+        if before.is_none() {
+            return Err(ServiceError::NotFound);
+        }
+        sqlx::query("DELETE FROM webhook_subscriptions WHERE id = $1")
+            .bind(subscription_id)
+            .execute(tx.as_mut())
+            .await
+            .map_err(internal)?;
+        audit::record_change(
+            tx.as_mut(),
+            Some(actor.employee_id),
+            audit::WEBHOOK_SUBSCRIPTION,
+            subscription_id,
+            audit::DELETED,
+            before,
+        )
+        .await?;
+        tx.commit().await.map_err(internal)?;
+        Ok(())
+    }
+
+    /// A subscription's deliveries, newest first, optionally only those with
+    /// `status`. Restricted to administrators.
+    pub async fn deliveries(
+        &self,
+        actor: &AuthenticatedUser,
+        subscription_id: Uuid,
+        query: DeliveryQuery,
+    ) -> Result<Vec<WebhookDelivery>, ServiceError> {
+        require_admin(actor)?;
+        if let Some(status) = query.status.as_deref() {
+            if !["pending", "delivered", "failed"].contains(&status) {
+                return Err(ServiceError::Validation(
+                    "status must be pending, delivered, or failed".into(),
+                ));
+            }
+        }
+        let exists: bool =
+            sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM webhook_subscriptions WHERE id = $1)")
+                .bind(subscription_id)
+                .fetch_one(&self.state.pool)
+                .await
+                .map_err(internal)?;
+        if !exists {
+            return Err(ServiceError::NotFound);
+        }
+
+        sqlx::query(
+            "SELECT * FROM webhook_deliveries
+             WHERE subscription_id = $1 AND ($2::text IS NULL OR status = $2)
+             ORDER BY created_at DESC
+             LIMIT $3",
+        )
+        .bind(subscription_id)
+        .bind(query.status)
+        .bind(
+            query
+                .limit
+                .unwrap_or(DEFAULT_DELIVERY_PAGE)
+                .clamp(1, MAX_DELIVERY_PAGE),
+        )
+        .fetch_all(&self.state.pool)
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .map(map_delivery)
+        .collect()
+    }
+}
+
+/// Queues `event_type` with `data` for every active subscription to it,
+/// delivered under `event_id`, the id of the `events::record` row the
+/// webhook stems from.
+///
+/// Accepts any executor so callers can emit inside the transaction that
+/// produced the event; nothing is queued if it rolls back. Callers check
+/// `webhooks.enabled` first.
+pub async fn emit<'e, E>(
+    executor: E,
+    event_id: Uuid,
+    event_type: &str,
+    data: serde_json::Value,
+) -> Result<(), ServiceError>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query(
+        "INSERT INTO webhook_deliveries
+            (id, subscription_id, event_id, event_type, payload, status, next_attempt_at, created_at)
+         SELECT gen_random_uuid(), id, $1, $2, $3, 'pending', $4, $4
+         FROM webhook_subscriptions
+         WHERE active AND $2 = ANY(event_types)",
+    )
+    .bind(event_id)
+    .bind(event_type)
+    .bind(data)
+    .bind(Utc::now())
+    .execute(executor)
+    .await
+    .map_err(internal)?;
+    Ok(())
+}
+
+/// Posts every due `pending` delivery, oldest first, up to `DELIVERY_LIMIT`
+/// per call.
+///
+/// A delivery is `delivered` once the receiver answers with a 2xx status.
+/// Any other answer, or none, is retried with `outbox::retry_delay` and marked
+/// `failed` after `webhooks.max_attempts`. Deliveries to a subscription that
+/// has since been deactivated fail at once. Stops early once shutdown begins;
+/// the rest become due again when their claim lapses.
+pub async fn deliver_pending(state: &AppState) -> Result<DeliverySummary, ServiceError> {
+    let now = Utc::now();
+    let claimed = sqlx::query(
+        "WITH claimed AS (
+             UPDATE webhook_deliveries
+             SET attempts = attempts + 1, next_attempt_at = $1
+             WHERE id IN (
+                 SELECT id FROM webhook_deliveries
+                 WHERE status = 'pending' AND next_attempt_at <= $2
+                 ORDER BY created_at
+                 LIMIT $3
+                 FOR UPDATE SKIP LOCKED
+             )
+             RETURNING id, subscription_id, event_id, event_type, payload, attempts, created_at
+         )
+         SELECT c.id, c.event_id, c.event_type, c.payload, c.attempts, c.created_at,
+                s.url, s.secret, s.active
+         FROM claimed c
+         JOIN webhook_subscriptions s ON s.id = c.subscription_id
+         ORDER BY c.created_at",
+    )
+    .bind(now + Duration::seconds(CLAIM_LEASE_SECONDS))
+    .bind(now)
+    .bind(DELIVERY_LIMIT)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(internal)?
+    .into_iter()
+    .map(map_pending)
+    .collect::<Result<Vec<_>, _>>()?;
+
+    let mut summary = DeliverySummary::default();
+    for delivery in claimed {
+        if state.shutdown.is_cancelled() {
+            break;
+        }
+        let outcome = if delivery.active {
+            let body = serde_json::to_vec(&serde_json::json!({
+                "id": delivery.event_id,
+                "type": delivery.event_type,
+                "created_at": delivery.created_at,
+                "data": delivery.payload,
+            }))
+            .map_err(|err| ServiceError::Internal(err.to_string()))?;
+            match webhooks::post(
+                &delivery.url,
+                &delivery.secret,
+                &delivery.event_type,
+                delivery.id,
+                body,
+            )
+            .await
+            {
+                Ok(status) if (200..300).contains(&status) => Ok(status),
+                Ok(status) => Err((
+                    Some(status),
+                    format!("receiver answered HTTP {status}"),
+                    false,
+                )),
+                Err(err) => Err((None, format!("{err:#}"), false)),
+            }
+        } else {
+            Err((None, "subscription is inactive".to_string(), true))
+        };
+
+        match outcome {
+            Ok(status) => {
+                summary.delivered += 1;
+                sqlx::query(
+                    "UPDATE webhook_deliveries
+                     SET status = 'delivered', response_status = $2, delivered_at = $3,
+                         last_error = NULL
+                     WHERE id = $1",
+                )
+                .bind(delivery.id)
+                .bind(i32::from(status))
+                .bind(Utc::now())
+                .execute(&state.pool)
+                .await
+                .map_err(internal)?;
+            }
+            Err((status, error, give_up)) => {
+                summary.failed += 1;
+                let exhausted = give_up
+                    || delivery.attempts.max(0) as u32 >= state.config.webhooks.max_attempts;
+                // The error can quote the receiver's URL, so it is only
+                // stored for the delivery log.
+                warn!(
+                    delivery_id = %delivery.id,
+                    event_type = %delivery.event_type,
+                    attempts = delivery.attempts,
+                    exhausted,
+                    "webhook delivery failed"
+                );
+                sqlx::query(
+                    "UPDATE webhook_deliveries
+                     SET status = $2, response_status = $3, next_attempt_at = $4, last_error = $5
+                     WHERE id = $1",
+                )
+                .bind(delivery.id)
+                .bind(if exhausted { "failed" } else { "pending" })
+                .bind(status.map(i32::from))
+                .bind(Utc::now() + outbox::retry_delay(delivery.attempts))
+                .bind(error.chars().take(MAX_ERROR_CHARS).collect::<String>())
+                .execute(&state.pool)
+                .await
+                .map_err(internal)?;
+            }
+        }
+    }
+    Ok(summary)
+}
+
+struct PendingDelivery {
+    id: Uuid,
+    event_id: Uuid,
+    event_type: String,
+    payload: serde_json::Value,
+    attempts: i32,
+    created_at: DateTime<Utc>,
+    url: String,
+    secret: String,
+    active: bool,
+}
+
+/// Returns the trimmed URL and the sorted, de-duplicated event types.
+fn validate<'a>(
+    url: &'a str,
+    event_types: &[String],
+) -> Result<(&'a str, Vec<String>), ServiceError> {
+    let url = url.trim();
+    validate_webhook_url(url)
+        .map_err(|_| ServiceError::Validation("url must be an HTTPS URL".into()))?;
+    let mut events: Vec<String> = event_types
+        .iter()
+        .map(|event| event.trim().to_string())
+        .collect();
+    events.sort();
+    events.dedup();
+    if events.is_empty() {
+        return Err(ServiceError::Validation(
+            "event_types must name at least one event".into(),
+        ));
+    }
+    if let Some(unknown) = events
+        .iter()
+        .find(|event| !EVENT_TYPES.contains(&event.as_str()))
+    {
+        return Err(ServiceError::Validation(format!(
+            "unknown event type {unknown}; expected one of {}",
+            EVENT_TYPES.join(", ")
+        )));
+    }
+    Ok((url, events))
+}
+
+fn description(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|value| !value.is_empty())
+}
+
+/// Deployment-wide settings are maintained by administrators of the
+/// operating organization.
+fn require_admin(actor: &AuthenticatedUser) -> Result<(), ServiceError> {
+    if actor.role != Role::Admin || !actor.is_operator() {
+        return Err(ServiceError::Forbidden);
+    }
+    Ok(())
+}
+
+fn map_subscription(row: PgRow) -> Result<WebhookSubscription, ServiceError> {
+    Ok(WebhookSubscription {
+        id: row.try_get("id").map_err(internal)?,
+        url: row.try_get("url").map_err(internal)?,
+        event_types: row.try_get("event_types").map_err(internal)?,
+        description: row.try_get("description").map_err(internal)?,
+        active: row.try_get("active").map_err(internal)?,
+        created_by: row.try_get("created_by").map_err(internal)?,
+        created_at: row.try_get("created_at").map_err(internal)?,
+        updated_at: row.try_get("updated_at").map_err(internal)?,
+    })
+}
+
+fn map_delivery(row: PgRow) -> Result<WebhookDelivery, ServiceError> {
+    Ok(WebhookDelivery {
+        id: row.try_get("id").map_err(internal)?,
+        subscription_id: row.try_get("subscription_id").map_err(internal)?,
+        event_id: row.try_get("event_id").map_err(internal)?,
+        event_type: row.try_get("event_type").map_err(internal)?,
+        payload: row.try_get("payload").map_err(internal)?,
+        status: row.try_get("status").map_err(internal)?,
+        attempts: row.try_get("attempts").map_err(internal)?,
+        response_status: row.try_get("response_status").map_err(internal)?,
+        last_error: row.try_get("last_error").map_err(internal)?,
+        next_attempt_at: row.try_get("next_attempt_at").map_err(internal)?,
+        created_at: row.try_get("created_at").map_err(internal)?,
+        delivered_at: row.try_get("delivered_at").map_err(internal)?,
+    })
+}
+
+fn map_pending(row: PgRow) -> Result<PendingDelivery, ServiceError> {
+    Ok(PendingDelivery {
+        id: row.try_get("id").map_err(internal)?,
+        event_id: row.try_get("event_id").map_err(internal)?,
+        event_type: row.try_get("event_type").map_err(internal)?,
+        payload: row.try_get("payload").map_err(internal)?,
+        attempts: row.try_get("attempts").map_err(internal)?,
+        created_at: row.try_get("created_at").map_err(internal)?,
+        url: row.try_get("url").map_err(internal)?,
+        secret: row.try_get("secret").map_err(internal)?,
+        active: row.try_get("active").map_err(internal)?,
+    })
+}
+
+fn internal(err: sqlx::Error) -> ServiceError {
+    ServiceError::Internal(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_urls_and_normalizes_event_types() {
+        let (url, events) = validate(
+            " https://hooks.example.com/expenses ",
+            &[
+                "report.submitted".into(),
+                " batch.exported".into(),
+                "report.submitted".into(),
+            ],
+        )
+        .unwrap();
+        assert_eq!(url, "https://hooks.example.com/expenses");
+        assert_eq!(events, ["batch.exported", "report.submitted"]);
+
+        assert!(validate("http://hooks.example.com", &["report.submitted".into()]).is_err());
+        assert!(validate("https://hooks.example.com", &[]).is_err());
+        assert!(validate("https://hooks.example.com", &["report.deleted".into()]).is_err());
+    }
+}
@@ -0,0 +1,167 @@
+//! Prometheus metrics.
+//!
+//! `GET /metrics` renders everything recorded through the `metrics` facade in
+//! the Prometheus text format. Database statements are timed from the events
+//! sqlx logs for each of them, labelled with the statement's operation and
+//! the route of the API request that ran it.
+
+use std::{fmt, sync::OnceLock, time::Duration};
+
+use axum::http::header;
+use axum::response::IntoResponse;
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use tracing::{
+    field::{Field, Visit},
+    span::{Attributes, Id},
+    Event, Subscriber,
+};
+use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
+
+/// Target sqlx logs each statement under.
+pub(super) const SQLX_QUERY_TARGET: &str = "sqlx::query";
+
+const DB_QUERY_DURATION: &str = "db_query_duration_seconds";
+/// Histogram buckets, in seconds, for every `_seconds` metric.
+const DURATION_BUCKETS: &[f64] = &[
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+/// How often recorded samples are folded into the histograms between scrapes,
+/// and the SLO gauges refreshed.
+const UPKEEP_INTERVAL: Duration = Duration::from_secs(5);
+/// Route label for statements run outside an API request, such as by jobs.
+const NO_ROUTE: &str = "<background>";
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+static HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+
+/// Installs the global Prometheus recorder. Later calls do nothing.
+pub(super) fn install() -> anyhow::Result<()> {
+    if HANDLE.get().is_some() {
+        return Ok(());
+    }
+    let handle = PrometheusBuilder::new()
+        .set_buckets_for_metric(Matcher::Suffix("_seconds".into()), DURATION_BUCKETS)?
+        .install_recorder()?;
+    metrics::describe_histogram!(
+        DB_QUERY_DURATION,
+        metrics::Unit::Seconds,
+        "Database statement execution time by operation and API route."
+    );
+    if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+        let upkeep = handle.clone();
+        runtime.spawn(async move {
+            let mut interval = tokio::time::interval(UPKEEP_INTERVAL);
+            loop {
+                interval.tick().await;
+                super::slo::evaluate();
+                upkeep.run_upkeep();
+            }
+        });
+    }
+    HANDLE.get_or_init(|| handle);
+    Ok(())
+}
+
+/// `GET /metrics`. Empty until `telemetry::init` has run.
+pub async fn render() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, CONTENT_TYPE)],
+        HANDLE
+            .get()
+            .map(PrometheusHandle::render)
+            .unwrap_or_default(),
+    )
+}
+
+/// Records `db_query_duration_seconds` from sqlx's statement events. Needs
+/// `sqlx::query` enabled at `debug` and the request span's `route` field.
+pub(super) struct QueryMetrics;
+
+/// Route a request span was created with, kept for the statements under it.
+struct SpanRoute(String);
+
+impl<S> Layer<S> for QueryMetrics
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut fields = QueryFields::default();
+        attrs.record(&mut fields);
+        if let (Some(route), Some(span)) = (fields.route, ctx.span(id)) {
+            span.extensions_mut().insert(SpanRoute(route));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        if event.metadata().target() != SQLX_QUERY_TARGET {
+            return;
+        }
+        let mut fields = QueryFields::default();
+        event.record(&mut fields);
+        let Some(elapsed_secs) = fields.elapsed_secs else {
+            return;
+        };
+        let route = ctx
+            .event_scope(event)
+            .and_then(|scope| {
+                scope
+                    .into_iter()
+                    .find_map(|span| span.extensions().get::<SpanRoute>().map(|r| r.0.clone()))
+            })
+            .unwrap_or_else(|| NO_ROUTE.to_string());
+        metrics::histogram!(
+            DB_QUERY_DURATION,
+            "operation" => operation(fields.summary.as_deref().unwrap_or_default()),
+            "route" => route,
+        )
+        .record(elapsed_secs);
+    }
+}
+
+/// The statement's leading keyword, limited to a fixed set to keep the label
+/// bounded.
+fn operation(summary: &str) -> &'static str {
+    let keyword = summary.split_whitespace().next().unwrap_or_default();
+    ["SELECT", "INSERT", "UPDATE", "DELETE", "WITH"]
+        .into_iter()
+        .find(|known| keyword.eq_ignore_ascii_case(known))
+        .unwrap_or("OTHER")
+}
+
+#[derive(Default)]
+struct QueryFields {
+    route: Option<String>,
+    summary: Option<String>,
+    elapsed_secs: Option<f64>,
+}
+
+impl Visit for QueryFields {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        if field.name() == "elapsed_secs" {
+            self.elapsed_secs = Some(value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "route" => self.route = Some(value.to_string()),
+            "summary" => self.summary = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operation_keeps_known_keywords_only() {
+        assert_eq!(operation("select e.id, e.hr_identifier FROM …"), "SELECT");
+        assert_eq!(operation("WITH queue AS ( …"), "WITH");
+        assert_eq!(operation("LOCK TABLE expense_reports"), "OTHER");
+        assert_eq!(operation(""), "OTHER");
+    }
+}
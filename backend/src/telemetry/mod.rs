@@ -1,15 +1,212 @@
-use std::sync::OnceLock;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+//! Logging and trace export.
+//!
+//! Logs are JSON lines filtered by `RUST_LOG` (default `info`). With
+//! `telemetry.otlp_enabled`, spans are also exported over OTLP/HTTP: each API
+//! request gets a span that continues the caller's W3C `traceparent`, the
+//! sqlx statements it runs are recorded as span events, and outbound NetSuite
+//! calls carry the trace context on in their own `traceparent`.
+//!
+//! With `telemetry.error_reporting_dsn`, `error`-level events, such as the
+//! internal errors `ServiceError::report` logs, and panics are also sent to a
+//! Sentry-protocol backend with a stack trace and the request span's fields;
+//! lower-level events from the same request ride along as breadcrumbs.
+//!
+//! Request spans carry the matched route, so the slow-statement warnings sqlx
+//! logs name the endpoint that ran the statement; see [`metrics`] for the
+//! Prometheus endpoint and [`slo`] for the per-route objectives it reports.
 
-static TELEMETRY: OnceLock<()> = OnceLock::new();
+pub mod metrics;
+pub mod slo;
 
-pub fn init() {
+use std::{sync::OnceLock, time::Duration};
+
+use anyhow::Context as _;
+use axum::{
+    extract::MatchedPath,
+    http::{HeaderMap, Request},
+};
+use opentelemetry::{global, trace::TracerProvider as _, KeyValue};
+use opentelemetry_http::{HeaderExtractor, HeaderInjector};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{propagation::TraceContextPropagator, trace::SdkTracerProvider, Resource};
+use tracing::{warn, Level, Span};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::{
+    filter::Targets, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer,
+};
+
+use crate::{
+    api::{access_log::UNMATCHED_ROUTE, request_id::RequestId},
+    infrastructure::config::TelemetryConfig,
+};
+
+const TRACER_NAME: &str = "expense_portal";
+/// sqlx logs each statement at `debug`; traces keep them even when the log
+/// level is higher.
+const SQLX_STATEMENTS: &str = "sqlx::query=debug";
+
+/// How long shutdown waits for queued error reports to be sent.
+const ERROR_REPORT_FLUSH_TIMEOUT: Duration = Duration::from_secs(2);
+
+static TELEMETRY: OnceLock<Telemetry> = OnceLock::new();
+
+struct Telemetry {
+    traces: Option<SdkTracerProvider>,
+    errors: Option<sentry::ClientInitGuard>,
+}
+
+/// Installs the log subscriber and, when configured, the OTLP exporter.
+/// Later calls do nothing.
+pub fn init(config: &TelemetryConfig) -> anyhow::Result<()> {
+    if TELEMETRY.get().is_some() {
+        return Ok(());
+    }
+    let provider = if config.otlp_enabled {
+        Some(tracer_provider(config)?)
+    } else {
+        None
+    };
+    let errors = config
+        .error_reporting_dsn
+        .as_deref()
+        .map(str::trim)
+        .filter(|dsn| !dsn.is_empty())
+        .map(|dsn| error_reporter(dsn, config))
+        .transpose()?;
+    metrics::install()?;
+    slo::install(config)?;
     TELEMETRY.get_or_init(|| {
-        let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
-            .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info"));
+        let traces = provider.as_ref().map(|provider| {
+            global::set_text_map_propagator(TraceContextPropagator::new());
+            global::set_tracer_provider(provider.clone());
+            tracing_opentelemetry::layer()
+                .with_tracer(provider.tracer(TRACER_NAME))
+                .with_filter(
+                    env_filter().add_directive(
+                        SQLX_STATEMENTS
+                            .parse()
+                            .expect("static filter directive is valid"),
+                    ),
+                )
+        });
         tracing_subscriber::registry()
-            .with(env_filter)
-            .with(tracing_subscriber::fmt::layer().json())
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .json()
+                    .with_filter(env_filter()),
+            )
+            .with(traces)
+            .with(
+                metrics::QueryMetrics.with_filter(
+                    Targets::new()
+                        .with_target(metrics::SQLX_QUERY_TARGET, Level::DEBUG)
+                        .with_target(module_path!(), Level::INFO),
+                ),
+            )
+            .with(
+                errors
+                    .as_ref()
+                    .map(|_| sentry::integrations::tracing::layer().with_filter(env_filter())),
+            )
             .init();
+        Telemetry {
+            traces: provider,
+            errors,
+        }
+    });
+    Ok(())
+}
+
+/// Exports the spans and error reports still buffered. Call once, before the
+/// process exits.
+pub fn shutdown() {
+    let Some(telemetry) = TELEMETRY.get() else {
+        return;
+    };
+    if let Some(provider) = &telemetry.traces {
+        if let Err(err) = provider.shutdown() {
+            warn!(error = %err, "flushing traces failed");
+        }
+    }
+    if let Some(errors) = &telemetry.errors {
+        if !errors.flush(Some(ERROR_REPORT_FLUSH_TIMEOUT)) {
+            warn!("flushing error reports timed out");
+        }
+    }
+}
+
+/// Span for an API request, continuing the trace in its `traceparent`
+/// header. Only the path and matched route are recorded; query strings can
+/// carry tokens.
+pub fn request_span<B>(request: &Request<B>) -> Span {
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(RequestId::as_str)
+        .unwrap_or_default();
+    let span = tracing::info_span!(
+        "http_request",
+        method = %request.method(),
+        path = %request.uri().path(),
+        route = request
+            .extensions()
+            .get::<MatchedPath>()
+            .map_or(UNMATCHED_ROUTE, MatchedPath::as_str),
+        request_id,
+    );
+    let parent = global::get_text_map_propagator(|propagator| {
+        propagator.extract(&HeaderExtractor(request.headers()))
+    });
+    span.set_parent(parent);
+    span
+}
+
+/// Headers carrying the current span's trace context to an outbound request.
+/// Empty when trace export is off.
+pub fn trace_headers() -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    let context = Span::current().context();
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut HeaderInjector(&mut headers))
     });
+    headers
+}
+
+fn tracer_provider(config: &TelemetryConfig) -> anyhow::Result<SdkTracerProvider> {
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_endpoint(&config.otlp_endpoint)
+        .build()
+        .context("failed to build the OTLP span exporter")?;
+    let resource = Resource::builder()
+        .with_service_name(config.service_name.clone())
+        .with_attribute(KeyValue::new(
+            "deployment.environment.name",
+            config.environment.clone(),
+        ))
+        .build();
+    Ok(SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(resource)
+        .build())
+}
+
+fn error_reporter(dsn: &str, config: &TelemetryConfig) -> anyhow::Result<sentry::ClientInitGuard> {
+    let dsn = dsn
+        .parse()
+        .context("telemetry.error_reporting_dsn is not a valid DSN")?;
+    Ok(sentry::init(sentry::ClientOptions {
+        dsn: Some(dsn),
+        release: sentry::release_name!(),
+        environment: Some(config.environment.clone().into()),
+        server_name: Some(config.service_name.clone().into()),
+        attach_stacktrace: true,
+        // Keeps client IPs and user details out of reports.
+        send_default_pii: false,
+        ..Default::default()
+    }))
+}
+
+fn env_filter() -> EnvFilter {
+    EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"))
 }
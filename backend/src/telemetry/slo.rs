@@ -0,0 +1,255 @@
+//! Per-route service level objectives.
+//!
+//! Every matched API request is recorded against its route template. On each
+//! metrics upkeep, a route's requests from the last `slo_window_seconds` give
+//! its success ratio (any response but a `5xx`), its p95 latency, and how much
+//! of its error budget is left, exported as gauges. A route that spends its
+//! whole budget or misses the latency target is logged as a warning and
+//! counted in `slo_breaches_total` once, and logged again when it recovers.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+use axum::http::StatusCode;
+use parking_lot::Mutex;
+use tracing::{info, warn};
+
+use crate::infrastructure::config::TelemetryConfig;
+
+/// Requests a window needs before a route can be judged, so one failure on
+/// an idle route does not page anyone.
+const MIN_REQUESTS: usize = 20;
+/// Newest requests kept per route; older ones fall out of busy windows early.
+const MAX_SAMPLES: usize = 10_000;
+
+static SLO: OnceLock<Slo> = OnceLock::new();
+
+struct Slo {
+    objectives: Objectives,
+    routes: Mutex<HashMap<String, RouteWindow>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Objectives {
+    success_target: f64,
+    latency_p95: Duration,
+    window: Duration,
+}
+
+#[derive(Default)]
+struct RouteWindow {
+    samples: VecDeque<Sample>,
+    budget_exhausted: bool,
+    latency_breached: bool,
+}
+
+struct Sample {
+    at: Instant,
+    latency: Duration,
+    success: bool,
+}
+
+#[derive(Debug, PartialEq)]
+struct Summary {
+    requests: usize,
+    success_ratio: f64,
+    latency_p95: Duration,
+    /// Share of the allowed failures still unspent; negative once overspent.
+    budget_remaining: f64,
+}
+
+/// Starts tracking requests against the configured targets. Later calls do
+/// nothing.
+pub(super) fn install(config: &TelemetryConfig) -> anyhow::Result<()> {
+    if !(config.slo_success_target > 0.0 && config.slo_success_target < 1.0) {
+        anyhow::bail!("telemetry.slo_success_target must be between 0 and 1");
+    }
+    SLO.get_or_init(|| Slo {
+        objectives: Objectives {
+            success_target: config.slo_success_target,
+            latency_p95: Duration::from_millis(config.slo_latency_p95_ms),
+            window: Duration::from_secs(config.slo_window_seconds.max(1)),
+        },
+        routes: Mutex::new(HashMap::new()),
+    });
+    metrics::describe_gauge!(
+        "http_route_success_ratio",
+        "Share of the route's requests in the SLO window that did not fail with a 5xx."
+    );
+    metrics::describe_gauge!(
+        "http_route_latency_p95_seconds",
+        metrics::Unit::Seconds,
+        "95th percentile latency of the route's requests in the SLO window."
+    );
+    metrics::describe_gauge!(
+        "http_route_error_budget_remaining",
+        "Share of the route's error budget left in the SLO window; negative once overspent."
+    );
+    metrics::describe_counter!(
+        "slo_breaches_total",
+        "Times a route exhausted its error budget or missed its p95 latency target."
+    );
+    Ok(())
+}
+
+/// Records one finished request. Does nothing before `telemetry::init`.
+pub fn record(route: &str, status: StatusCode, latency: Duration) {
+    let Some(slo) = SLO.get() else {
+        return;
+    };
+    let sample = Sample {
+        at: Instant::now(),
+        latency,
+        success: !status.is_server_error(),
+    };
+    let mut routes = slo.routes.lock();
+    let window = match routes.get_mut(route) {
+        Some(window) => window,
+        None => routes.entry(route.to_string()).or_default(),
+    };
+    if window.samples.len() == MAX_SAMPLES {
+        window.samples.pop_front();
+    }
+    window.samples.push_back(sample);
+}
+
+/// Refreshes the SLO gauges and logs routes crossing their objectives.
+pub(super) fn evaluate() {
+    let Some(slo) = SLO.get() else {
+        return;
+    };
+    let objectives = slo.objectives;
+    let now = Instant::now();
+    let mut routes = slo.routes.lock();
+    for (route, window) in routes.iter_mut() {
+        window.prune(now, objectives.window);
+        let Some(summary) = window.summary(&objectives) else {
+            continue;
+        };
+        let labels = [("route", route.clone())];
+        metrics::gauge!("http_route_success_ratio", &labels).set(summary.success_ratio);
+        metrics::gauge!("http_route_latency_p95_seconds", &labels)
+            .set(summary.latency_p95.as_secs_f64());
+        metrics::gauge!("http_route_error_budget_remaining", &labels).set(summary.budget_remaining);
+
+        let judged = summary.requests >= MIN_REQUESTS;
+        let exhausted = judged && summary.budget_remaining <= 0.0;
+        if exhausted != window.budget_exhausted {
+            window.budget_exhausted = exhausted;
+            if exhausted {
+                breaches(route, "availability").increment(1);
+                warn!(
+                    route = %route,
+                    success_ratio = summary.success_ratio,
+                    target = objectives.success_target,
+                    requests = summary.requests,
+                    "route exhausted its error budget"
+                );
+            } else {
+                info!(
+                    route = %route,
+                    success_ratio = summary.success_ratio,
+                    "route is back within its error budget"
+                );
+            }
+        }
+        let slow = judged && summary.latency_p95 > objectives.latency_p95;
+        if slow != window.latency_breached {
+            window.latency_breached = slow;
+            let latency_p95_ms = summary.latency_p95.as_millis() as u64;
+            if slow {
+                breaches(route, "latency").increment(1);
+                warn!(
+                    route = %route,
+                    latency_p95_ms,
+                    target_ms = objectives.latency_p95.as_millis() as u64,
+                    requests = summary.requests,
+                    "route missed its p95 latency target"
+                );
+            } else {
+                info!(
+                    route = %route,
+                    latency_p95_ms,
+                    "route is back within its p95 latency target"
+                );
+            }
+        }
+    }
+}
+
+fn breaches(route: &str, objective: &'static str) -> metrics::Counter {
+    metrics::counter!(
+        "slo_breaches_total",
+        "route" => route.to_string(),
+        "objective" => objective,
+    )
+}
+
+impl RouteWindow {
+    fn prune(&mut self, now: Instant, window: Duration) {
+        while self
+            .samples
+            .front()
+            .is_some_and(|sample| now.duration_since(sample.at) > window)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    /// `None` while the window holds no requests; the gauges then keep the
+    /// last window that had some.
+    fn summary(&self, objectives: &Objectives) -> Option<Summary> {
+        let requests = self.samples.len();
+        if requests == 0 {
+            return None;
+        }
+        let successes = self.samples.iter().filter(|sample| sample.success).count();
+        let success_ratio = successes as f64 / requests as f64;
+        let mut latencies: Vec<Duration> =
+            self.samples.iter().map(|sample| sample.latency).collect();
+        latencies.sort_unstable();
+        // Nearest rank: the smallest latency at least 95% of requests met.
+        let rank = (requests * 95).div_ceil(100).max(1);
+        Some(Summary {
+            requests,
+            success_ratio,
+            latency_p95: latencies[rank - 1],
+            budget_remaining: 1.0 - (1.0 - success_ratio) / (1.0 - objectives.success_target),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_spends_error_budget_and_prunes_old_requests() {
+        let objectives = Objectives {
+            success_target: 0.9,
+            latency_p95: Duration::from_millis(100),
+            window: Duration::from_secs(60),
+        };
+        let start = Instant::now();
+        let mut window = RouteWindow::default();
+        for millis in 1..=20u64 {
+            window.samples.push_back(Sample {
+                at: start,
+                latency: Duration::from_millis(millis * 10),
+                success: millis > 1,
+            });
+        }
+
+        let summary = window.summary(&objectives).expect("window has requests");
+        assert_eq!(summary.requests, 20);
+        assert!((summary.success_ratio - 0.95).abs() < 1e-9);
+        assert_eq!(summary.latency_p95, Duration::from_millis(190));
+        assert!((summary.budget_remaining - 0.5).abs() < 1e-9);
+
+        window.prune(start + Duration::from_secs(61), objectives.window);
+        assert_eq!(window.summary(&objectives), None);
+    }
+}
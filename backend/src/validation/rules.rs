@@ -1,11 +1,13 @@
 use crate::domain::{
-    models::ExpenseItem,
+    models::{Currency, ExpenseItem},
     policy::{evaluate_item, PolicyEvaluation},
 };
 
 pub fn validate_item(
     item: &ExpenseItem,
-    caps: &[crate::domain::models::PolicyCap],
+    currency: Currency,
+    rules: &[crate::domain::models::PolicyRule],
+    has_receipt: bool,
 ) -> PolicyEvaluation {
-    evaluate_item(item, caps)
+    evaluate_item(item, currency, rules, has_receipt)
 }
@@ -12,8 +12,12 @@ use expense_portal::{
     domain::models::Role,
     infrastructure::{
         config::{
-            AppConfig, AuthConfig, Config, DatabaseConfig, NetSuiteConfig, ReceiptRules,
-            StorageConfig,
+            AppConfig, ApprovalLinkConfig, ArchiveConfig, AuditVerifyConfig, AuthConfig,
+            AutoFinalizeConfig, BankingConfig, ChatConfig, Config, DatabaseConfig, DigestConfig,
+            EmailConfig, EscalationConfig, FinalizationConfig, FxConfig, GrpcConfig, HrSyncConfig,
+            JobsConfig, JournalExportConfig, NetSuiteConfig, OutboxConfig, PolicyConfig,
+            PurgeConfig, ReceiptRules, ReconciliationConfig, ReminderConfig, RetentionConfig,
+            StaleDraftConfig, StorageConfig, TelemetryConfig, WebhooksConfig,
         },
         state::AppState,
         storage,
@@ -35,14 +39,22 @@ async fn expenses_reports_requires_valid_token() -> Result<()> {
 }
 
 async fn run_scenario(pool: PgPool) -> Result<()> {
-    let mut storage_config = StorageConfig::default();
-    storage_config.provider = "memory".to_string();
+    let storage_config = StorageConfig {
+        provider: "memory".to_string(),
+        ..StorageConfig::default()
+    };
 
     let config = Arc::new(Config {
         app: AppConfig::default(),
         database: DatabaseConfig {
             url: "postgres://integration".to_string(),
             max_connections: 5,
+            slow_query_threshold_ms: 500,
+            read_url: None,
+            acquire_timeout_secs: 30,
+            idle_timeout_secs: 600,
+            max_lifetime_secs: 1800,
+            statement_timeout_ms: 60_000,
         },
         auth: AuthConfig {
             jwt_secret: "integration-secret".to_string(),
@@ -54,6 +66,30 @@ async fn run_scenario(pool: PgPool) -> Result<()> {
         storage: storage_config,
         netsuite: NetSuiteConfig::default(),
         receipts: ReceiptRules::default(),
+        reminders: ReminderConfig::default(),
+        digest: DigestConfig::default(),
+        purge: PurgeConfig::default(),
+        stale_drafts: StaleDraftConfig::default(),
+        escalations: EscalationConfig::default(),
+        retention: RetentionConfig::default(),
+        archive: ArchiveConfig::default(),
+        audit_verify: AuditVerifyConfig::default(),
+        outbox: OutboxConfig::default(),
+        fx: FxConfig::default(),
+        hr_sync: HrSyncConfig::default(),
+        email: EmailConfig::default(),
+        chat: ChatConfig::default(),
+        webhooks: WebhooksConfig::default(),
+        telemetry: TelemetryConfig::default(),
+        jobs: JobsConfig::default(),
+        approval_links: ApprovalLinkConfig::default(),
+        journal_export: JournalExportConfig::default(),
+        auto_finalize: AutoFinalizeConfig::default(),
+        reconciliation: ReconciliationConfig::default(),
+        finalization: FinalizationConfig::default(),
+        policy: PolicyConfig::default(),
+        grpc: GrpcConfig::default(),
+        banking: BankingConfig::default(),
     });
 
     let storage = storage::build_storage(&config.storage)?;
@@ -153,7 +189,13 @@ async fn run_scenario(pool: PgPool) -> Result<()> {
                     serde_json::json!({
                         "reporting_period_start": "2024-01-01",
                         "reporting_period_end": "2024-01-31",
-                        "currency": "USD"
+                        "currency": "USD",
+                        "items": [{
+                            "expense_date": "2024-01-15",
+                            "category": "supplies",
+                            "amount_cents": 1_250,
+                            "reimbursable": true
+                        }]
                     })
                     .to_string(),
                 ))
@@ -164,6 +206,17 @@ async fn run_scenario(pool: PgPool) -> Result<()> {
 
     assert_eq!(authorized_response.status(), StatusCode::OK);
 
+    sqlx::query("DELETE FROM audit_logs WHERE performed_by IN (SELECT id FROM employees WHERE hr_identifier = $1)")
+        .bind(&hr_identifier)
+        .execute(&pool)
+        .await?;
+    sqlx::query(
+        "DELETE FROM expense_reports
+         WHERE employee_id IN (SELECT id FROM employees WHERE hr_identifier = $1)",
+    )
+    .bind(&hr_identifier)
+    .execute(&pool)
+    .await?;
     sqlx::query("DELETE FROM employees WHERE hr_identifier = $1")
         .bind(hr_identifier)
         .execute(&pool)
@@ -6,15 +6,19 @@ use axum::{
     http::{header, Request, StatusCode},
     Extension,
 };
-use chrono::{Duration, NaiveDate, Utc};
+use chrono::{Duration, NaiveDate, SubsecRound, Utc};
 use expense_portal::{
     api,
     domain::models::{Employee, Role},
     infrastructure::{
         auth::issue_token,
         config::{
-            AppConfig, AuthConfig, Config, DatabaseConfig, NetSuiteConfig, ReceiptRules,
-            StorageConfig,
+            AppConfig, ApprovalLinkConfig, ArchiveConfig, AuditVerifyConfig, AuthConfig,
+            AutoFinalizeConfig, BankingConfig, ChatConfig, Config, DatabaseConfig, DigestConfig,
+            EmailConfig, EscalationConfig, FinalizationConfig, FxConfig, GrpcConfig, HrSyncConfig,
+            JobsConfig, JournalExportConfig, NetSuiteConfig, OutboxConfig, PolicyConfig,
+            PurgeConfig, ReceiptRules, ReconciliationConfig, ReminderConfig, RetentionConfig,
+            StaleDraftConfig, StorageConfig, TelemetryConfig, WebhooksConfig,
         },
         state::AppState,
         storage,
@@ -40,6 +44,11 @@ async fn manager_queue_returns_pending_reports() -> Result<()> {
     run_test(run_happy_path).await
 }
 
+#[tokio::test]
+async fn manager_queue_lists_every_report_of_the_organization() -> Result<()> {
+    run_test(run_lists_organization_reports).await
+}
+
 async fn run_requires_manager(pool: PgPool) -> Result<()> {
     let (config, state) = build_state(pool.clone()).await?;
     let app = api::build_router(Arc::clone(&config)).layer(Extension(Arc::clone(&state)));
@@ -97,34 +106,39 @@ async fn run_happy_path(pool: PgPool) -> Result<()> {
 
     let manager_hr = format!("MGMT-{}", manager_id.simple());
     let employee_hr = format!("EMP-{}", employee_id.simple());
+    // The queue lists the whole organization, so start an empty one.
+    let org_id = insert_organization(&pool).await?;
 
     sqlx::query(
-        "INSERT INTO employees (id, hr_identifier, manager_id, department, role, created_at)
-         VALUES ($1,$2,$3,$4,$5,$6)",
+        "INSERT INTO employees (id, hr_identifier, manager_id, department, role, org_id, created_at)
+         VALUES ($1,$2,$3,$4,$5,$6,$7)",
     )
     .bind(manager_id)
     .bind(&manager_hr)
     .bind::<Option<Uuid>>(None)
     .bind::<Option<String>>(Some("Operations".to_string()))
     .bind(Role::Manager)
+    .bind(org_id)
     .bind(Utc::now())
     .execute(&pool)
     .await?;
 
     sqlx::query(
-        "INSERT INTO employees (id, hr_identifier, manager_id, department, role, created_at)
-         VALUES ($1,$2,$3,$4,$5,$6)",
+        "INSERT INTO employees (id, hr_identifier, manager_id, department, role, org_id, created_at)
+         VALUES ($1,$2,$3,$4,$5,$6,$7)",
     )
     .bind(employee_id)
     .bind(&employee_hr)
     .bind::<Option<Uuid>>(Some(manager_id))
     .bind::<Option<String>>(Some("Logistics".to_string()))
     .bind(Role::Employee)
+    .bind(org_id)
     .bind(Utc::now())
     .execute(&pool)
     .await?;
 
-    let submitted_at = Utc::now() - Duration::days(2);
+    // Postgres keeps microseconds.
+    let submitted_at = (Utc::now() - Duration::days(2)).trunc_subsecs(6);
     let period_start = NaiveDate::from_ymd_opt(2024, 5, 1).expect("valid date");
     let period_end = NaiveDate::from_ymd_opt(2024, 5, 31).expect("valid date");
 
@@ -208,9 +222,9 @@ async fn run_happy_path(pool: PgPool) -> Result<()> {
     let body = to_bytes(response.into_body(), 1024 * 1024).await?;
     let payload: Value = serde_json::from_slice(&body)?;
     let queue = payload
-        .get("queue")
+        .get("items")
         .and_then(Value::as_array)
-        .expect("queue array");
+        .expect("items array");
 
     assert_eq!(queue.len(), 1);
     let entry = &queue[0];
@@ -284,6 +298,19 @@ async fn run_happy_path(pool: PgPool) -> Result<()> {
         Some("lodging")
     );
 
+    // Later edits to the waiting report leave its place in the queue alone.
+    sqlx::query("UPDATE expense_reports SET updated_at = $2 WHERE id = $1")
+        .bind(report_id)
+        .bind(Utc::now())
+        .execute(&pool)
+        .await?;
+    let queued_at: chrono::DateTime<Utc> =
+        sqlx::query_scalar("SELECT submitted_at FROM manager_queue_entries WHERE report_id = $1")
+            .bind(report_id)
+            .fetch_one(&pool)
+            .await?;
+    assert_eq!(queued_at, submitted_at);
+
     sqlx::query("DELETE FROM expense_items WHERE report_id = $1")
         .bind(report_id)
         .execute(&pool)
@@ -297,19 +324,151 @@ async fn run_happy_path(pool: PgPool) -> Result<()> {
         .bind(&employee_ids)
         .execute(&pool)
         .await?;
+    sqlx::query("DELETE FROM organizations WHERE id = $1")
+        .bind(org_id)
+        .execute(&pool)
+        .await?;
 
     Ok(())
 }
 
+async fn run_lists_organization_reports(pool: PgPool) -> Result<()> {
+    let (config, state) = build_state(pool.clone()).await?;
+    let app = api::build_router(Arc::clone(&config)).layer(Extension(Arc::clone(&state)));
+
+    let org_id = insert_organization(&pool).await?;
+    let other_org_id = insert_organization(&pool).await?;
+    let viewing_manager_id = Uuid::new_v4();
+    let other_manager_id = Uuid::new_v4();
+    let employee_id = Uuid::new_v4();
+    let other_org_employee_id = Uuid::new_v4();
+
+    for (id, manager_id, role, org) in [
+        (viewing_manager_id, None, Role::Manager, org_id),
+        (other_manager_id, None, Role::Manager, org_id),
+        (employee_id, Some(other_manager_id), Role::Employee, org_id),
+        (other_org_employee_id, None, Role::Employee, other_org_id),
+    ] {
+        sqlx::query(
+            "INSERT INTO employees (id, hr_identifier, manager_id, department, role, org_id, created_at)
+             VALUES ($1,$2,$3,$4,$5,$6,$7)",
+        )
+        .bind(id)
+        .bind(format!("QUEUE-{}", id.simple()))
+        .bind::<Option<Uuid>>(manager_id)
+        .bind::<Option<String>>(None)
+        .bind(role)
+        .bind(org)
+        .bind(Utc::now())
+        .execute(&pool)
+        .await?;
+    }
+
+    let period_start = NaiveDate::from_ymd_opt(2024, 6, 1).expect("valid date");
+    let period_end = NaiveDate::from_ymd_opt(2024, 6, 30).expect("valid date");
+    let report_id = Uuid::new_v4();
+    let other_org_report_id = Uuid::new_v4();
+    for (id, owner) in [
+        (report_id, employee_id),
+        (other_org_report_id, other_org_employee_id),
+    ] {
+        sqlx::query(
+            "INSERT INTO expense_reports
+                 (id, employee_id, reporting_period_start, reporting_period_end, status,
+                  total_amount_cents, total_reimbursable_cents, currency, version, created_at, updated_at)
+             VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11)",
+        )
+        .bind(id)
+        .bind(owner)
+        .bind(period_start)
+        .bind(period_end)
+        .bind("submitted")
+        .bind(10_000_i64)
+        .bind(10_000_i64)
+        .bind("USD")
+        .bind(1_i32)
+        .bind(Utc::now())
+        .bind(Utc::now())
+        .execute(&pool)
+        .await?;
+    }
+
+    let viewing_manager = fetch_employee(&pool, viewing_manager_id).await?;
+    let token = issue_token(&state, &viewing_manager)?;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/api/manager/queue")
+                .header(header::AUTHORIZATION, format!("Bearer {token}"))
+                .body(Body::empty())
+                .expect("failed to build request"),
+        )
+        .await
+        .expect("service error");
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), 1024 * 1024).await?;
+    let payload: Value = serde_json::from_slice(&body)?;
+    let queued: Vec<&str> = payload
+        .get("items")
+        .and_then(Value::as_array)
+        .expect("items array")
+        .iter()
+        .filter_map(|entry| entry.pointer("/report/id").and_then(Value::as_str))
+        .collect();
+    // Another team's report is listed; another organization's is not.
+    assert_eq!(queued, vec![report_id.to_string()]);
+
+    sqlx::query("DELETE FROM expense_reports WHERE id = ANY($1)")
+        .bind(vec![report_id, other_org_report_id])
+        .execute(&pool)
+        .await?;
+    sqlx::query("DELETE FROM employees WHERE id = ANY($1)")
+        .bind(vec![
+            employee_id,
+            other_org_employee_id,
+            viewing_manager_id,
+            other_manager_id,
+        ])
+        .execute(&pool)
+        .await?;
+    sqlx::query("DELETE FROM organizations WHERE id = ANY($1)")
+        .bind(vec![org_id, other_org_id])
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
+
+async fn insert_organization(pool: &PgPool) -> Result<Uuid> {
+    let org_id = Uuid::new_v4();
+    sqlx::query("INSERT INTO organizations (id, name) VALUES ($1, $2)")
+        .bind(org_id)
+        .bind(format!("Queue org {}", org_id.simple()))
+        .execute(pool)
+        .await?;
+    Ok(org_id)
+}
+
 async fn build_state(pool: PgPool) -> Result<(Arc<Config>, Arc<AppState>)> {
-    let mut storage_config = StorageConfig::default();
-    storage_config.provider = "memory".to_string();
+    let storage_config = StorageConfig {
+        provider: "memory".to_string(),
+        ..StorageConfig::default()
+    };
 
     let config = Arc::new(Config {
         app: AppConfig::default(),
         database: DatabaseConfig {
             url: "postgres://integration".to_string(),
             max_connections: 5,
+            slow_query_threshold_ms: 500,
+            read_url: None,
+            acquire_timeout_secs: 30,
+            idle_timeout_secs: 600,
+            max_lifetime_secs: 1800,
+            statement_timeout_ms: 60_000,
         },
         auth: AuthConfig {
             jwt_secret: "integration-secret".to_string(),
@@ -321,6 +480,30 @@ async fn build_state(pool: PgPool) -> Result<(Arc<Config>, Arc<AppState>)> {
         storage: storage_config,
         netsuite: NetSuiteConfig::default(),
         receipts: ReceiptRules::default(),
+        reminders: ReminderConfig::default(),
+        digest: DigestConfig::default(),
+        purge: PurgeConfig::default(),
+        stale_drafts: StaleDraftConfig::default(),
+        escalations: EscalationConfig::default(),
+        retention: RetentionConfig::default(),
+        archive: ArchiveConfig::default(),
+        audit_verify: AuditVerifyConfig::default(),
+        outbox: OutboxConfig::default(),
+        fx: FxConfig::default(),
+        hr_sync: HrSyncConfig::default(),
+        email: EmailConfig::default(),
+        chat: ChatConfig::default(),
+        webhooks: WebhooksConfig::default(),
+        telemetry: TelemetryConfig::default(),
+        jobs: JobsConfig::default(),
+        approval_links: ApprovalLinkConfig::default(),
+        journal_export: JournalExportConfig::default(),
+        auto_finalize: AutoFinalizeConfig::default(),
+        reconciliation: ReconciliationConfig::default(),
+        finalization: FinalizationConfig::default(),
+        policy: PolicyConfig::default(),
+        grpc: GrpcConfig::default(),
+        banking: BankingConfig::default(),
     });
 
     let storage = storage::build_storage(&config.storage)?;
@@ -331,7 +514,7 @@ async fn build_state(pool: PgPool) -> Result<(Arc<Config>, Arc<AppState>)> {
 
 async fn fetch_employee(pool: &PgPool, id: Uuid) -> Result<Employee> {
     let employee = sqlx::query_as::<_, Employee>(
-        "SELECT id, hr_identifier, manager_id, department, role, created_at FROM employees WHERE id = $1",
+        "SELECT id, org_id, hr_identifier, manager_id, department, department_id, cost_center_id, role, created_at, deactivated_at FROM employees WHERE id = $1",
     )
     .bind(id)
     .fetch_one(pool)
@@ -0,0 +1,200 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Request, StatusCode},
+    Extension,
+};
+use chrono::Utc;
+use expense_portal::{
+    api,
+    domain::models::Role,
+    infrastructure::{
+        config::{
+            AppConfig, ApprovalLinkConfig, ArchiveConfig, AuditVerifyConfig, AuthConfig,
+            AutoFinalizeConfig, BankingConfig, ChatConfig, Config, DatabaseConfig, DigestConfig,
+            EmailConfig, EscalationConfig, FinalizationConfig, FxConfig, GrpcConfig, HrSyncConfig,
+            JobsConfig, JournalExportConfig, NetSuiteConfig, OutboxConfig, PolicyConfig,
+            PurgeConfig, ReceiptRules, ReconciliationConfig, ReminderConfig, RetentionConfig,
+            StaleDraftConfig, StorageConfig, TelemetryConfig, WebhooksConfig,
+        },
+        state::AppState,
+        storage,
+    },
+};
+use serde_json::Value;
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+#[path = "test_harness.rs"]
+mod test_harness;
+
+use test_harness::run_test;
+
+const BOUNDARY: &str = "receipt-boundary";
+
+#[tokio::test]
+async fn keyed_multi_file_upload_is_stored_and_replayed() -> Result<()> {
+    run_test(run_scenario).await
+}
+
+async fn run_scenario(pool: PgPool) -> Result<()> {
+    let config = Arc::new(Config {
+        // Two files together exceed both the general limit and one file's.
+        app: AppConfig {
+            max_body_bytes: 1_024,
+            ..AppConfig::default()
+        },
+        database: DatabaseConfig {
+            url: "postgres://integration".to_string(),
+            max_connections: 5,
+            slow_query_threshold_ms: 500,
+            read_url: None,
+            acquire_timeout_secs: 30,
+            idle_timeout_secs: 600,
+            max_lifetime_secs: 1800,
+            statement_timeout_ms: 60_000,
+        },
+        auth: AuthConfig {
+            jwt_secret: "integration-secret".to_string(),
+            jwt_ttl_seconds: 3_600,
+            developer_credential: "dev-pass".to_string(),
+            bypass_auth: false,
+            bypass_hr_identifier: None,
+        },
+        storage: StorageConfig {
+            provider: "memory".to_string(),
+            ..StorageConfig::default()
+        },
+        netsuite: NetSuiteConfig::default(),
+        receipts: ReceiptRules {
+            max_bytes: 1_000,
+            max_files_per_item: 3,
+            ..ReceiptRules::default()
+        },
+        reminders: ReminderConfig::default(),
+        digest: DigestConfig::default(),
+        purge: PurgeConfig::default(),
+        stale_drafts: StaleDraftConfig::default(),
+        escalations: EscalationConfig::default(),
+        retention: RetentionConfig::default(),
+        archive: ArchiveConfig::default(),
+        audit_verify: AuditVerifyConfig::default(),
+        outbox: OutboxConfig::default(),
+        fx: FxConfig::default(),
+        hr_sync: HrSyncConfig::default(),
+        email: EmailConfig::default(),
+        chat: ChatConfig::default(),
+        webhooks: WebhooksConfig::default(),
+        telemetry: TelemetryConfig::default(),
+        jobs: JobsConfig::default(),
+        approval_links: ApprovalLinkConfig::default(),
+        journal_export: JournalExportConfig::default(),
+        auto_finalize: AutoFinalizeConfig::default(),
+        reconciliation: ReconciliationConfig::default(),
+        finalization: FinalizationConfig::default(),
+        policy: PolicyConfig::default(),
+        grpc: GrpcConfig::default(),
+        banking: BankingConfig::default(),
+    });
+
+    let storage = storage::build_storage(&config.storage)?;
+    let state = Arc::new(AppState::new(Arc::clone(&config), pool.clone(), storage)?);
+
+    let hr_identifier = format!("DEV{}", Uuid::new_v4().simple());
+    sqlx::query(
+        "INSERT INTO employees (id, hr_identifier, manager_id, department, role, created_at)
+         VALUES ($1,$2,$3,$4,$5,$6)",
+    )
+    .bind(Uuid::new_v4())
+    .bind(&hr_identifier)
+    .bind::<Option<Uuid>>(None)
+    .bind::<Option<String>>(None)
+    .bind(Role::Employee)
+    .bind(Utc::now())
+    .execute(&pool)
+    .await?;
+
+    let app = api::build_router(Arc::clone(&config)).layer(Extension(Arc::clone(&state)));
+
+    let login = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/auth/login")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    serde_json::json!({
+                        "hr_identifier": hr_identifier,
+                        "credential": "dev-pass"
+                    })
+                    .to_string(),
+                ))
+                .expect("failed to build login request"),
+        )
+        .await
+        .expect("service error");
+    assert_eq!(login.status(), StatusCode::OK);
+    let login_body = to_bytes(login.into_body(), 1024 * 1024).await?;
+    let token = serde_json::from_slice::<Value>(&login_body)?
+        .get("token")
+        .and_then(Value::as_str)
+        .unwrap()
+        .to_string();
+
+    let mut body = Vec::new();
+    for (name, byte) in [("first.pdf", b'a'), ("second.pdf", b'b')] {
+        body.extend_from_slice(
+            format!(
+                "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{name}\"\r\nContent-Type: application/pdf\r\n\r\n"
+            )
+            .as_bytes(),
+        );
+        body.extend_from_slice(&[byte; 900]);
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{BOUNDARY}--\r\n").as_bytes());
+    let idempotency_key = Uuid::new_v4().to_string();
+
+    let upload = || {
+        app.clone().oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/receipts")
+                .header(header::AUTHORIZATION, format!("Bearer {token}"))
+                .header(
+                    header::CONTENT_TYPE,
+                    format!("multipart/form-data; boundary={BOUNDARY}"),
+                )
+                .header("idempotency-key", &idempotency_key)
+                .body(Body::from(body.clone()))
+                .expect("failed to build upload request"),
+        )
+    };
+
+    let first = upload().await.expect("service error");
+    assert_eq!(first.status(), StatusCode::OK);
+    assert!(first.headers().get("idempotent-replayed").is_none());
+    let first_body: Value =
+        serde_json::from_slice(&to_bytes(first.into_body(), 1024 * 1024).await?)?;
+    let receipts = first_body["receipts"].as_array().expect("receipts list");
+    assert_eq!(receipts.len(), 2);
+
+    let retry = upload().await.expect("service error");
+    assert_eq!(retry.status(), StatusCode::OK);
+    assert_eq!(
+        retry
+            .headers()
+            .get("idempotent-replayed")
+            .and_then(|value| value.to_str().ok()),
+        Some("true")
+    );
+    let retry_body: Value =
+        serde_json::from_slice(&to_bytes(retry.into_body(), 1024 * 1024).await?)?;
+    assert_eq!(retry_body, first_body);
+
+    Ok(())
+}
@@ -13,8 +13,12 @@ use expense_portal::{
     infrastructure::{
         auth::issue_token,
         config::{
-            AppConfig, AuthConfig, Config, DatabaseConfig, NetSuiteConfig, ReceiptRules,
-            StorageConfig,
+            AppConfig, ApprovalLinkConfig, ArchiveConfig, AuditVerifyConfig, AuthConfig,
+            AutoFinalizeConfig, BankingConfig, ChatConfig, Config, DatabaseConfig, DigestConfig,
+            EmailConfig, EscalationConfig, FinalizationConfig, FxConfig, GrpcConfig, HrSyncConfig,
+            JobsConfig, JournalExportConfig, NetSuiteConfig, OutboxConfig, PolicyConfig,
+            PurgeConfig, ReceiptRules, ReconciliationConfig, ReminderConfig, RetentionConfig,
+            StaleDraftConfig, StorageConfig, TelemetryConfig, WebhooksConfig,
         },
         state::AppState,
         storage,
@@ -141,14 +145,22 @@ async fn run_reviewer_access(pool: PgPool, role: Role) -> Result<()> {
 }
 
 async fn build_state(pool: PgPool) -> Result<(Arc<Config>, Arc<AppState>)> {
-    let mut storage_config = StorageConfig::default();
-    storage_config.provider = "memory".to_string();
+    let storage_config = StorageConfig {
+        provider: "memory".to_string(),
+        ..StorageConfig::default()
+    };
 
     let config = Arc::new(Config {
         app: AppConfig::default(),
         database: DatabaseConfig {
             url: "postgres://integration".to_string(),
             max_connections: 5,
+            slow_query_threshold_ms: 500,
+            read_url: None,
+            acquire_timeout_secs: 30,
+            idle_timeout_secs: 600,
+            max_lifetime_secs: 1800,
+            statement_timeout_ms: 60_000,
         },
         auth: AuthConfig {
             jwt_secret: "integration-secret".to_string(),
@@ -160,6 +172,30 @@ async fn build_state(pool: PgPool) -> Result<(Arc<Config>, Arc<AppState>)> {
         storage: storage_config,
         netsuite: NetSuiteConfig::default(),
         receipts: ReceiptRules::default(),
+        reminders: ReminderConfig::default(),
+        digest: DigestConfig::default(),
+        purge: PurgeConfig::default(),
+        stale_drafts: StaleDraftConfig::default(),
+        escalations: EscalationConfig::default(),
+        retention: RetentionConfig::default(),
+        archive: ArchiveConfig::default(),
+        audit_verify: AuditVerifyConfig::default(),
+        outbox: OutboxConfig::default(),
+        fx: FxConfig::default(),
+        hr_sync: HrSyncConfig::default(),
+        email: EmailConfig::default(),
+        chat: ChatConfig::default(),
+        webhooks: WebhooksConfig::default(),
+        telemetry: TelemetryConfig::default(),
+        jobs: JobsConfig::default(),
+        approval_links: ApprovalLinkConfig::default(),
+        journal_export: JournalExportConfig::default(),
+        auto_finalize: AutoFinalizeConfig::default(),
+        reconciliation: ReconciliationConfig::default(),
+        finalization: FinalizationConfig::default(),
+        policy: PolicyConfig::default(),
+        grpc: GrpcConfig::default(),
+        banking: BankingConfig::default(),
     });
 
     let storage = storage::build_storage(&config.storage)?;
@@ -192,7 +228,7 @@ async fn create_employee(pool: &PgPool, role: Role) -> Result<Employee> {
     .await?;
 
     let employee = sqlx::query_as::<_, Employee>(
-        "SELECT id, hr_identifier, manager_id, department, role, created_at FROM employees WHERE id = $1",
+        "SELECT id, org_id, hr_identifier, manager_id, department, department_id, cost_center_id, role, created_at, deactivated_at FROM employees WHERE id = $1",
     )
     .bind(id)
     .fetch_one(pool)
@@ -132,8 +132,8 @@ const EmployeePortal = () => {
       setShowClientErrors(false);
     } catch (error) {
       if (axios.isAxiosError(error) && error.response?.status === 422) {
-        const data = error.response.data as { errors?: Record<string, string[]> } | undefined;
-        setBackendErrors(data?.errors ?? {});
+        const data = error.response.data as { fields?: Record<string, string[]> } | undefined;
+        setBackendErrors(data?.fields ?? {});
         return;
       }
       setSubmissionError('Unable to submit report. Please try again.');
@@ -21,6 +21,7 @@ import './FinanceConsole.css';
 
 type ApiError = {
   error?: string;
+  message?: string;
 };
 
 const financeBatchSchema = z
@@ -44,14 +45,16 @@ const financeBatchSchema = z
   }));
 
 const financeBatchResponseSchema = z.object({
-  batches: z.array(financeBatchSchema)
+  items: z.array(financeBatchSchema),
+  next_cursor: z.string().nullish(),
+  total: z.number()
 });
 
 type FinanceBatch = z.infer<typeof financeBatchSchema>;
 
 const fetchBatches = async () => {
   const payload = await request<unknown>('get', '/finance/batches');
-  return financeBatchResponseSchema.parse(payload).batches;
+  return financeBatchResponseSchema.parse(payload).items;
 };
 
 const formatCurrency = (amountCents: number) =>
@@ -93,10 +96,13 @@ const FinanceConsole = () => {
     if (!error) {
       return 'Unable to load finance batches.';
     }
-    return error.response?.data?.error ?? error.message ?? 'Unable to load finance batches.';
+    return error.response?.data?.message ?? error.message ?? 'Unable to load finance batches.';
   }, [error]);
 
-  const pendingBatches = useMemo(() => data.filter((batch) => batch.status !== 'exported'), [data]);
+  const pendingBatches = useMemo(
+    () => data.filter((batch) => batch.status !== 'exported' && batch.status !== 'posted'),
+    [data]
+  );
 
   return (
     <section className="finance-console">
@@ -53,14 +53,16 @@ const managerQueueEntrySchema = z.object({
 });
 
 const managerQueueResponseSchema = z.object({
-  queue: z.array(managerQueueEntrySchema)
+  items: z.array(managerQueueEntrySchema),
+  next_cursor: z.string().nullish(),
+  total: z.number()
 });
 
 type ManagerQueueEntry = z.infer<typeof managerQueueEntrySchema>;
 
 const fetchQueue = async () => {
   const payload = await request<unknown>('get', '/manager/queue');
-  return managerQueueResponseSchema.parse(payload).queue;
+  return managerQueueResponseSchema.parse(payload).items;
 };
 
 const formatCurrency = (amountCents: number, currency: string) =>
@@ -153,7 +153,9 @@ EOF
   echo "Running database migrations..."
   (
     cd backend
-    cargo_with_toolchain run --bin migrator
+    # Check queries against the committed .sqlx data; the database is not
+    # migrated until the migrator has run.
+    SQLX_OFFLINE=true cargo_with_toolchain run --bin migrator
   )
   exit 0
 fi
@@ -197,6 +199,7 @@ docker run --rm \
   -w /app \
   -e "EXPENSES__DATABASE__URL=${CONTAINER_DATABASE_URL}" \
   -e "DATABASE_URL=${CONTAINER_DATABASE_URL}" \
+  -e "SQLX_OFFLINE=true" \
   -e "RUST_LOG=${RUST_LOG:-debug}" \
   ${TOOLCHAIN_CHANNEL:+-e "RUSTUP_TOOLCHAIN=${TOOLCHAIN_CHANNEL}"} \
   "${RUST_DOCKER_IMAGE}" \